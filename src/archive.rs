@@ -0,0 +1,149 @@
+//! `--export-all`: every payload a scan sends, with its outcome, in a single
+//! `.tar.gz` file instead of thousands of loose files.
+//!
+//! Each payload is stored byte for byte as `payloads/NNNNNN_check_index.raw`
+//! next to a `.json` file of the same name holding its [`ExportedPayload`]
+//! metadata. Entries are streamed into the archive as payloads are sent, so a
+//! long scan does not hold them in memory.
+
+use std::fs::File;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use chrono::Utc;
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use tar::{Builder, Header};
+
+use crate::error::{Result, SmugglexError};
+use crate::model::{ExportedPayload, ResponseSummary};
+use crate::payloads::Payload;
+use crate::redact::redact;
+
+/// A `.tar.gz` archive payloads are appended to as they are sent.
+pub struct PayloadArchive {
+    builder: Mutex<Option<Builder<GzEncoder<File>>>>,
+    entries: AtomicUsize,
+}
+
+impl PayloadArchive {
+    /// Create (or truncate) the archive at `path`.
+    pub fn create(path: &Path) -> Result<Self> {
+        let file = File::create(path)?;
+        Ok(Self {
+            builder: Mutex::new(Some(Builder::new(GzEncoder::new(
+                file,
+                Compression::default(),
+            )))),
+            entries: AtomicUsize::new(0),
+        })
+    }
+
+    /// Append `payload` (redacted like every other export) and `metadata`,
+    /// filling in its `raw_file` and `exported_at`.
+    pub fn append(&self, payload: &str, mut metadata: ExportedPayload) -> Result<()> {
+        let mut guard = self.builder.lock().unwrap_or_else(|e| e.into_inner());
+        let builder = guard
+            .as_mut()
+            .ok_or_else(|| SmugglexError::Config("payload archive is closed".to_string()))?;
+        let number = self.entries.fetch_add(1, Ordering::Relaxed) + 1;
+        let stem = format!(
+            "{:06}_{}_{}",
+            number, metadata.check_type, metadata.payload_index
+        );
+        metadata.raw_file = format!("{}.raw", stem);
+        metadata.exported_at = Utc::now().to_rfc3339();
+        let json = serde_json::to_string_pretty(&metadata)?;
+        append_file(
+            builder,
+            &format!("payloads/{}", metadata.raw_file),
+            redact(payload).as_bytes(),
+        )?;
+        append_file(builder, &format!("payloads/{}.json", stem), json.as_bytes())?;
+        Ok(())
+    }
+
+    /// Number of payloads appended so far.
+    pub fn len(&self) -> usize {
+        self.entries.load(Ordering::Relaxed)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Write the tar trailer and flush the compressed stream. Later
+    /// [`append`](Self::append) calls fail.
+    pub fn finish(&self) -> Result<usize> {
+        let builder = self
+            .builder
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        if let Some(builder) = builder {
+            builder.into_inner()?.finish()?;
+        }
+        Ok(self.len())
+    }
+}
+
+fn append_file(builder: &mut Builder<GzEncoder<File>>, path: &str, data: &[u8]) -> Result<()> {
+    let mut header = Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(Utc::now().timestamp().max(0) as u64);
+    builder.append_data(&mut header, path, data)?;
+    Ok(())
+}
+
+static ARCHIVE: OnceLock<PayloadArchive> = OnceLock::new();
+
+/// Open the process-wide `--export-all` archive at `path`.
+pub fn open_archive(path: &Path) -> Result<()> {
+    let archive = PayloadArchive::create(path)?;
+    let _ = ARCHIVE.set(archive);
+    Ok(())
+}
+
+/// The `--export-all` archive, when one was opened.
+pub fn active_archive() -> Option<&'static PayloadArchive> {
+    ARCHIVE.get()
+}
+
+/// Where a check records the payloads it sends: the archive, and the target
+/// they are sent to.
+#[derive(Clone, Copy)]
+pub struct ArchiveRecorder<'a> {
+    pub archive: &'a PayloadArchive,
+    /// Target URL, recorded in each payload's metadata
+    pub target: &'a str,
+}
+
+impl ArchiveRecorder<'_> {
+    /// Record `payload`, sent by `check_type`, with its `outcome` (`clean`,
+    /// `error`, `refuted`, `detected`, ...) and what came back.
+    pub fn record(
+        &self,
+        check_type: &str,
+        payload: &Payload,
+        outcome: &str,
+        response: ResponseSummary,
+    ) -> Result<()> {
+        self.archive.append(
+            &payload.request,
+            ExportedPayload {
+                target: self.target.to_string(),
+                check_type: check_type.to_string(),
+                payload_index: payload.index,
+                payload_name: payload.name.clone(),
+                technique: payload.technique.clone(),
+                raw_file: String::new(),
+                timestamp: Utc::now().to_rfc3339(),
+                exported_at: String::new(),
+                outcome: Some(outcome.to_string()),
+                response,
+            },
+        )
+    }
+}
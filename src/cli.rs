@@ -1,5 +1,11 @@
-use clap::{Parser, ValueEnum};
+use crate::exploit::{
+    DEFAULT_AUTO_EXPLOIT_BUDGET, DEFAULT_SWEEP_BUDGET, FuzzCategory, ResponseMatcher, SizeRange,
+};
+use crate::mutator::FuzzMode;
+use crate::scanner::detection::DetectionMethod;
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use colored::control;
+use regex::Regex;
 use std::fmt;
 
 /// Default method for the attack request. Kept as a named constant so the
@@ -8,18 +14,43 @@ use std::fmt;
 pub const DEFAULT_METHOD: &str = "POST";
 
 /// Every check name smugglex understands: the payload-string checks plus the
-/// real-HTTP/2 downgrade check. Used to validate `--checks` so a typo does not
-/// silently run zero checks and report a clean target.
-pub const KNOWN_CHECK_NAMES: [&str; 7] = [
+/// real-HTTP/2 downgrade and TLS early-data checks. Used to validate `--checks`
+/// so a typo does not silently run zero checks and report a clean target.
+pub const KNOWN_CHECK_NAMES: [&str; 12] = [
     "cl-te",
     "te-cl",
     "te-te",
     "h2c",
     "h2",
     "cl-edge",
+    "cl-cl",
+    "asterisk",
+    "connection",
+    "head",
     "h2-downgrade",
+    "early-data",
 ];
 
+/// Every exploit name `--exploit` understands, in the order they are documented.
+pub const KNOWN_EXPLOIT_NAMES: [&str; 7] = [
+    "localhost-access",
+    "internal-sweep",
+    "path-fuzz",
+    "cloud-metadata",
+    "smuggle",
+    "capture",
+    "reveal",
+];
+
+/// Placeholder in a `--vhost` value for the scanned target's host name, so
+/// `admin.{host}` becomes `admin.example.com` on `https://example.com`.
+pub const VHOST_TARGET_HOST: &str = "{host}";
+
+/// `vhost` with [`VHOST_TARGET_HOST`] replaced by `target_host`.
+pub fn vhost_for_target(vhost: &str, target_host: &str) -> String {
+    vhost.replace(VHOST_TARGET_HOST, target_host)
+}
+
 /// Return the names in a comma-separated `--checks` value that match no known
 /// check (trimmed; empty segments ignored). An empty result means every
 /// requested name was recognized.
@@ -50,6 +81,10 @@ pub enum OutputFormat {
     Plain,
     /// JSON output (structured)
     Json,
+    /// Plain text, then a GitHub Actions `::error`/`::warning` annotation per finding
+    Github,
+    /// JUnit XML, one test case per check (for CI test reports)
+    Junit,
 }
 
 impl fmt::Display for OutputFormat {
@@ -57,6 +92,8 @@ impl fmt::Display for OutputFormat {
         match self {
             OutputFormat::Plain => write!(f, "plain"),
             OutputFormat::Json => write!(f, "json"),
+            OutputFormat::Github => write!(f, "github"),
+            OutputFormat::Junit => write!(f, "junit"),
         }
     }
 }
@@ -66,6 +103,319 @@ impl OutputFormat {
     pub fn is_json(&self) -> bool {
         matches!(self, OutputFormat::Json)
     }
+
+    /// Check if format adds GitHub Actions annotations
+    pub fn is_github(&self) -> bool {
+        matches!(self, OutputFormat::Github)
+    }
+
+    /// Check if format is JUnit XML
+    pub fn is_junit(&self) -> bool {
+        matches!(self, OutputFormat::Junit)
+    }
+}
+
+/// Layout of target lists read from stdin
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StdinFormat {
+    /// One URL per line
+    Urls,
+    /// One JSON scan job per line
+    Jsonl,
+}
+
+impl fmt::Display for StdinFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StdinFormat::Urls => write!(f, "urls"),
+            StdinFormat::Jsonl => write!(f, "jsonl"),
+        }
+    }
+}
+
+/// Formats `smugglex report` renders saved results to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    /// Stand-alone HTML page
+    Html,
+    /// Markdown document
+    Md,
+    /// SARIF 2.1.0 log for code-scanning dashboards
+    Sarif,
+    /// One row per check
+    Csv,
+    /// DefectDojo "Generic Findings Import" JSON, one finding per vulnerable
+    /// check
+    Defectdojo,
+    /// ZAP traditional JSON report, one alert per vulnerable check
+    ZapJson,
+    /// ZAP traditional XML report, one alert per vulnerable check
+    ZapXml,
+}
+
+impl ReportFormat {
+    /// Report format a `-o` file's extension asks for (`.defectdojo.json`
+    /// for a DefectDojo import, `.zap.json` and `.zap.xml` for ZAP reports);
+    /// `None` for JSON results (`.json` or any other extension).
+    pub fn for_path(path: &str) -> Option<Self> {
+        let lower = path.to_ascii_lowercase();
+        if lower.ends_with(".defectdojo.json") {
+            return Some(ReportFormat::Defectdojo);
+        }
+        if lower.ends_with(".zap.json") {
+            return Some(ReportFormat::ZapJson);
+        }
+        let extension = std::path::Path::new(path)
+            .extension()?
+            .to_str()?
+            .to_ascii_lowercase();
+        match extension.as_str() {
+            "html" | "htm" => Some(ReportFormat::Html),
+            "md" | "markdown" => Some(ReportFormat::Md),
+            "sarif" => Some(ReportFormat::Sarif),
+            "csv" => Some(ReportFormat::Csv),
+            "xml" if lower.ends_with(".zap.xml") => Some(ReportFormat::ZapXml),
+            _ => None,
+        }
+    }
+}
+
+/// File layout of `--export-payloads`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// The request as a `.txt` file (plus the technique as `.json`)
+    Txt,
+    /// The request as a byte-exact `.raw` file plus a `.json` sidecar with
+    /// the target, check, payload name, timestamps and response summary
+    Raw,
+}
+
+/// Client the `smugglex reproduce` one-liner pipes the payload into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReplayTool {
+    /// `ncat` (with `--ssl` for https)
+    Ncat,
+    /// `openssl s_client` for https targets; plain http still uses `ncat`
+    Openssl,
+}
+
+/// Role of a `serve` process in a distributed scan
+#[derive(Debug, Clone, PartialEq, Eq, ValueEnum)]
+pub enum ServeRole {
+    /// Hand out target shards to workers and aggregate their results
+    Coordinator,
+    /// Pull target shards from a coordinator and scan them
+    Worker,
+}
+
+/// What to do when the target's DNS answers change while it is being scanned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DnsChangePolicy {
+    /// Log a warning and keep scanning the pinned address
+    Warn,
+    /// Stop scanning the target and report it as failed
+    Abort,
+}
+
+/// Subcommands. Without one, smugglex scans the given targets directly.
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Distributed scanning: run as a coordinator or a worker
+    Serve(ServeArgs),
+    /// Run a REST API daemon that accepts scan jobs
+    Daemon(DaemonArgs),
+    /// Replay the winning payloads of saved results and report which findings
+    /// still reproduce
+    Retest(RetestArgs),
+    /// Convert saved results to another report format without scanning
+    Report(ReportArgs),
+    /// Summarize findings over time from a `--history-dir`: first and last
+    /// seen, when fixed, flapping, and counts per technique
+    Stats(StatsArgs),
+    /// Print a shell one-liner that sends a saved finding's winning payload
+    /// byte for byte
+    Reproduce(ReproduceArgs),
+    /// Print a shell completion script to stdout
+    Completions(CompletionsArgs),
+    /// Inspect the built-in payload families
+    Payloads(PayloadsArgs),
+    /// Download and list the curated wordlists `--exploit-wordlist` and
+    /// `--vhost` take by name
+    Wordlists(WordlistsArgs),
+    /// Lint `--raw-request` templates and show the request each expands to
+    Validate(ValidateArgs),
+}
+
+/// Options for `smugglex payloads`
+#[derive(Args, Debug, Clone)]
+pub struct PayloadsArgs {
+    #[command(subcommand)]
+    pub command: PayloadsCommand,
+}
+
+/// `smugglex payloads` actions
+#[derive(Subcommand, Debug, Clone)]
+pub enum PayloadsCommand {
+    /// List every payload family with its size, description and an example
+    /// request. Use `--format json` before `payloads` for JSON.
+    List(PayloadsListArgs),
+}
+
+/// Options for `smugglex payloads list`
+#[derive(Args, Debug, Clone)]
+pub struct PayloadsListArgs {
+    /// Only list this check's family (e.g. te-cl)
+    #[arg(long, value_name = "CHECK")]
+    pub check: Option<String>,
+}
+
+/// Options for `smugglex wordlists`
+#[derive(Args, Debug, Clone)]
+pub struct WordlistsArgs {
+    #[command(subcommand)]
+    pub command: WordlistsCommand,
+}
+
+/// `smugglex wordlists` actions
+#[derive(Subcommand, Debug, Clone)]
+pub enum WordlistsCommand {
+    /// Download the curated wordlists (all of them, or the ones named) into
+    /// the data directory, replacing older copies
+    Update(WordlistsUpdateArgs),
+    /// List the curated wordlists and which are downloaded. Use `--format
+    /// json` before `wordlists` for JSON.
+    List,
+}
+
+/// Options for `smugglex wordlists update`
+#[derive(Args, Debug, Clone)]
+pub struct WordlistsUpdateArgs {
+    /// Wordlists to download (default: all)
+    #[arg(value_name = "NAME")]
+    pub names: Vec<String>,
+}
+
+/// Options for `smugglex completions`
+#[derive(Args, Debug, Clone)]
+pub struct CompletionsArgs {
+    /// Shell to generate the completion script for
+    #[arg(value_enum, value_name = "SHELL")]
+    pub shell: clap_complete::Shell,
+}
+
+/// Options for `smugglex validate`
+#[derive(Args, Debug, Clone)]
+pub struct ValidateArgs {
+    /// Template file, or a directory of templates
+    #[arg(value_name = "PATH")]
+    pub path: std::path::PathBuf,
+
+    /// Host to expand the templates for (default: each template's own Host)
+    #[arg(long, value_name = "HOST")]
+    pub host: Option<String>,
+}
+
+/// Options for `smugglex report`
+#[derive(Args, Debug, Clone)]
+pub struct ReportArgs {
+    /// Results file written by a previous scan with `-o`
+    #[arg(value_name = "RESULTS")]
+    pub results: std::path::PathBuf,
+
+    /// Report format
+    #[arg(long, value_enum)]
+    pub format: ReportFormat,
+
+    /// Write the report to a file instead of stdout
+    #[arg(short, long, value_name = "FILE")]
+    pub output: Option<String>,
+}
+
+/// Options for `smugglex reproduce`. `-H` flags given before `reproduce` fill
+/// in header values `--redact` masked in the saved payload.
+#[derive(Args, Debug, Clone)]
+pub struct ReproduceArgs {
+    /// Results file written by a previous scan with `-o`
+    #[arg(value_name = "RESULTS")]
+    pub results: std::path::PathBuf,
+
+    /// Finding to reproduce: its number in the listing, or its
+    /// `check/payload-name` id. Without it, the findings are listed.
+    #[arg(long, value_name = "FINDING")]
+    pub id: Option<String>,
+
+    /// Client the payload is piped into
+    #[arg(long, value_enum, default_value = "ncat")]
+    pub tool: ReplayTool,
+}
+
+/// Options for `smugglex retest`. Scan flags (timeout, detection, ...) go
+/// before `retest` and apply to every replay.
+#[derive(Args, Debug, Clone)]
+pub struct RetestArgs {
+    /// Results file written by a previous scan with `-o`
+    #[arg(value_name = "RESULTS")]
+    pub results: std::path::PathBuf,
+}
+
+/// Options for `smugglex stats`
+#[derive(Args, Debug, Clone)]
+pub struct StatsArgs {
+    /// History directory written by scans with `--history-dir` (default:
+    /// the `--history-dir` given before `stats`)
+    #[arg(long = "history-dir", value_name = "DIR")]
+    pub history_dir: Option<std::path::PathBuf>,
+}
+
+/// Options for `smugglex daemon`. Process-wide settings (proxy, TLS) come from
+/// the flags given before `daemon`; each job supplies its own scan flags.
+#[derive(Args, Debug, Clone)]
+pub struct DaemonArgs {
+    /// Address the API listens on
+    #[arg(long, default_value = "127.0.0.1:8787")]
+    pub listen: String,
+
+    /// Require `Authorization: Bearer <TOKEN>` on every API request
+    #[arg(long, env = "SMUGGLEX_TOKEN", hide_env_values = true)]
+    pub token: Option<String>,
+
+    /// Directory where recurring schedules and their run history are kept
+    #[arg(long, value_name = "DIR")]
+    pub state_dir: Option<std::path::PathBuf>,
+}
+
+/// Options for `smugglex serve`. Scan flags (checks, timeout, ...) go before
+/// `serve` on the coordinator and are forwarded to every worker.
+#[derive(Args, Debug, Clone)]
+pub struct ServeArgs {
+    /// Role of this process
+    #[arg(long, value_enum)]
+    pub role: ServeRole,
+
+    /// Address the coordinator listens on
+    #[arg(long, default_value = "0.0.0.0:7878")]
+    pub listen: String,
+
+    /// Coordinator address a worker connects to (host:port)
+    #[arg(long, required_if_eq("role", "worker"))]
+    pub coordinator: Option<String>,
+
+    /// Shared secret both sides prove they hold (HMAC challenge; the token is
+    /// never sent). The channel is otherwise plaintext: use a trusted network
+    /// or a tunnel
+    #[arg(long, env = "SMUGGLEX_TOKEN", hide_env_values = true)]
+    pub token: String,
+
+    /// Number of targets per shard handed to a worker
+    #[arg(long = "shard-size", default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+    pub shard_size: u64,
+
+    /// Worker name reported to the coordinator (defaults to the process id)
+    #[arg(long = "worker-name")]
+    pub worker_name: Option<String>,
+
+    /// Targets to distribute (coordinator; stdin is read when omitted)
+    pub targets: Vec<String>,
 }
 
 /// A powerful HTTP Request Smuggling testing tool for detecting CL.TE, TE.CL, TE.TE, H2C, and H2 smuggling vulnerabilities
@@ -82,28 +432,127 @@ impl OutputFormat {
       █   █  ██
 "#)]
 pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// Target URLs (supports multiple URLs and stdin input)
     #[arg(help_heading = "TARGET")]
     pub urls: Vec<String>,
 
+    /// What stdin lines hold when no URL is given: `urls`, or `jsonl` scan
+    /// jobs (`{"url": ..., "method", "headers", "vhost", "cookies", "checks",
+    /// "profile"}`)
+    #[arg(
+        help_heading = "TARGET",
+        long = "stdin-format",
+        value_enum,
+        default_value_t = StdinFormat::Urls,
+        env = "SMUGGLEX_STDIN_FORMAT",
+    )]
+    pub stdin_format: StdinFormat,
+
+    /// Read targets from FILE, one per line: a URL, or a JSON job like
+    /// `--stdin-format jsonl` takes, overriding the method, headers, vhost,
+    /// cookie use and checks for that target
+    #[arg(
+        help_heading = "TARGET",
+        short = 'l',
+        long = "list",
+        value_name = "FILE",
+        conflicts_with = "raw_request",
+        env = "SMUGGLEX_LIST"
+    )]
+    pub list: Option<std::path::PathBuf>,
+
+    /// Scan every address the target host resolves to separately, keeping the
+    /// host name in Host and SNI, and report results per address
+    #[arg(help_heading = "TARGET", long = "scan-all-ips", action = clap::ArgAction::SetTrue, env = "SMUGGLEX_SCAN_ALL_IPS")]
+    pub scan_all_ips: bool,
+
+    /// Connect to this address instead of resolving the target host, keeping
+    /// the host name in Host and SNI (origin behind a CDN, pre-cutover DNS)
+    #[arg(
+        help_heading = "TARGET",
+        long = "target-ip",
+        value_name = "IP",
+        conflicts_with = "scan_all_ips",
+        env = "SMUGGLEX_TARGET_IP"
+    )]
+    pub target_ip: Option<std::net::IpAddr>,
+
+    /// Only scan targets whose URL matches one of these regexes (repeatable)
+    #[arg(
+        help_heading = "TARGET",
+        long = "scope-include",
+        value_name = "REGEX",
+        env = "SMUGGLEX_SCOPE_INCLUDE"
+    )]
+    pub scope_include: Vec<Regex>,
+
+    /// Never scan targets whose URL matches one of these regexes (repeatable;
+    /// wins over --scope-include)
+    #[arg(
+        help_heading = "TARGET",
+        long = "scope-exclude",
+        value_name = "REGEX",
+        env = "SMUGGLEX_SCOPE_EXCLUDE"
+    )]
+    pub scope_exclude: Vec<Regex>,
+
+    /// Skip the reachability preflight (one GET per target before the checks)
+    #[arg(
+        help_heading = "TARGET",
+        long = "no-preflight",
+        env = "SMUGGLEX_NO_PREFLIGHT"
+    )]
+    pub no_preflight: bool,
+
+    /// What to do when the target's DNS answers change mid-scan (connections
+    /// stay pinned to the address resolved at the start)
+    #[arg(
+        help_heading = "TARGET",
+        long = "on-dns-change",
+        value_enum,
+        default_value = "warn",
+        env = "SMUGGLEX_ON_DNS_CHANGE"
+    )]
+    pub on_dns_change: DnsChangePolicy,
+
     /// Custom method for the attack request
-    #[arg(help_heading = "REQUEST", short, long, default_value = DEFAULT_METHOD)]
+    #[arg(help_heading = "REQUEST", short, long, default_value = DEFAULT_METHOD, env = "SMUGGLEX_METHOD")]
     pub method: String,
 
     /// Socket timeout in seconds
-    #[arg(help_heading = "REQUEST", short, long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+    #[arg(help_heading = "REQUEST", short, long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..), env = "SMUGGLEX_TIMEOUT")]
     pub timeout: u64,
 
     /// Custom headers (format: "Header: Value")
-    #[arg(help_heading = "REQUEST", short = 'H', long = "header")]
+    #[arg(
+        help_heading = "REQUEST",
+        short = 'H',
+        long = "header",
+        env = "SMUGGLEX_HEADER",
+        hide_env_values = true
+    )]
     pub headers: Vec<String>,
 
-    /// Virtual host to use in Host header (overrides URL hostname)
-    #[arg(help_heading = "REQUEST", long = "vhost")]
+    /// Virtual host to use in Host header (overrides URL hostname); a
+    /// comma-separated list or a file of hosts scans each one separately
+    #[arg(
+        help_heading = "REQUEST",
+        long = "vhost",
+        value_name = "HOST[,HOST...]|FILE",
+        env = "SMUGGLEX_VHOST"
+    )]
     pub vhost: Option<String>,
 
     /// Read a raw HTTP request from a file and use it as the request template
-    #[arg(help_heading = "REQUEST", long = "raw-request", value_name = "FILE")]
+    #[arg(
+        help_heading = "REQUEST",
+        long = "raw-request",
+        value_name = "FILE",
+        env = "SMUGGLEX_RAW_REQUEST"
+    )]
     pub raw_request: Option<String>,
 
     /// Scheme for --raw-request when the request line is origin-form (http or https)
@@ -113,7 +562,8 @@ pub struct Cli {
         value_name = "SCHEME",
         default_value = "https",
         requires = "raw_request",
-        value_parser = ["http", "https"]
+        value_parser = ["http", "https"],
+        env = "SMUGGLEX_RAW_REQUEST_PROTO",
     )]
     pub raw_request_proto: String,
 
@@ -124,62 +574,265 @@ pub struct Cli {
     #[arg(skip)]
     pub raw_target: Option<String>,
 
+    /// Placement markers (`{{TE}}`, `{{CL}}`, `{{BODY}}`) found in the
+    /// `--raw-request` template. Not a user-facing flag; populated by the
+    /// raw-request pipeline and applied to every generated payload.
+    #[arg(skip)]
+    pub raw_markers: Option<crate::raw_request::PlacementMarkers>,
+
     /// Fetch and append cookies from initial request
-    #[arg(help_heading = "REQUEST", long = "cookies", action = clap::ArgAction::SetTrue)]
+    #[arg(help_heading = "REQUEST", long = "cookies", action = clap::ArgAction::SetTrue, env = "SMUGGLEX_COOKIES")]
     pub use_cookies: bool,
 
-    /// Output file for results (JSON format)
-    #[arg(help_heading = "OUTPUT", short, long)]
-    pub output: Option<String>,
+    /// Output file for results; repeatable. The format follows the
+    /// extension: `.html`, `.md`, `.sarif` and `.csv` files get that report,
+    /// anything else the JSON results
+    #[arg(
+        help_heading = "OUTPUT",
+        short,
+        long,
+        value_name = "FILE",
+        env = "SMUGGLEX_OUTPUT"
+    )]
+    pub output: Vec<String>,
 
-    /// Output format (plain or json)
-    #[arg(help_heading = "OUTPUT", short = 'f', long = "format", default_value_t = OutputFormat::Plain)]
+    /// Output format (plain, json, junit, or github for plain output plus GitHub Actions annotations)
+    #[arg(help_heading = "OUTPUT", short = 'f', long = "format", default_value_t = OutputFormat::Plain, env = "SMUGGLEX_FORMAT")]
     pub format: OutputFormat,
 
     /// Shorthand for --format json (machine-readable output for scripts and AI agents)
-    #[arg(help_heading = "OUTPUT", long = "json", action = clap::ArgAction::SetTrue)]
+    #[arg(help_heading = "OUTPUT", long = "json", action = clap::ArgAction::SetTrue, env = "SMUGGLEX_JSON")]
     pub json: bool,
 
     /// Export payloads to directory when vulnerabilities are found
-    #[arg(help_heading = "OUTPUT", long = "export-payloads")]
+    #[arg(
+        help_heading = "OUTPUT",
+        long = "export-payloads",
+        env = "SMUGGLEX_EXPORT_PAYLOADS"
+    )]
     pub export_dir: Option<String>,
 
+    /// Layout of exported payloads: txt, or raw bytes with a JSON metadata sidecar
+    #[arg(
+        help_heading = "OUTPUT",
+        long = "export-format",
+        value_enum,
+        default_value = "txt",
+        requires = "export_dir",
+        env = "SMUGGLEX_EXPORT_FORMAT"
+    )]
+    pub export_format: ExportFormat,
+
+    /// Archive every payload sent, with its outcome, into a .tar.gz file
+    #[arg(
+        help_heading = "OUTPUT",
+        long = "export-all",
+        value_name = "FILE",
+        env = "SMUGGLEX_EXPORT_ALL"
+    )]
+    pub export_all: Option<std::path::PathBuf>,
+
+    /// Headers whose values are masked in printed, saved and exported requests (comma-separated)
+    #[arg(help_heading = "OUTPUT", long = "redact", value_name = "HEADERS", value_delimiter = ',', default_value = crate::redact::DEFAULT_REDACTED_HEADERS, env = "SMUGGLEX_REDACT")]
+    pub redact: Vec<String>,
+
+    /// Print and save every header value unmasked (disables --redact)
+    #[arg(help_heading = "OUTPUT", long = "no-redact", action = clap::ArgAction::SetTrue, env = "SMUGGLEX_NO_REDACT")]
+    pub no_redact: bool,
+
+    /// Report where the time of each finding's attack request went (DNS,
+    /// TCP, TLS, write, first byte, transfer)
+    #[arg(help_heading = "OUTPUT", long = "phase-timing", action = clap::ArgAction::SetTrue, env = "SMUGGLEX_PHASE_TIMING")]
+    pub phase_timing: bool,
+
+    /// Email a summary of the findings, with the JSON results attached, to
+    /// these addresses when a scan finds something (comma-separated)
+    #[arg(
+        help_heading = "OUTPUT",
+        long = "email-to",
+        value_name = "ADDRS",
+        value_delimiter = ',',
+        requires = "smtp",
+        env = "SMUGGLEX_EMAIL_TO"
+    )]
+    pub email_to: Vec<String>,
+
+    /// SMTP server for --email-to: smtp://[user:pass@]host[:port] (STARTTLS
+    /// when offered) or smtps://... (TLS)
+    #[arg(
+        help_heading = "OUTPUT",
+        long = "smtp",
+        value_name = "URL",
+        env = "SMUGGLEX_SMTP"
+    )]
+    pub smtp: Option<String>,
+
+    /// Sender of --email-to mail (default: the SMTP user when it is an
+    /// address, else smugglex@<SMTP host>)
+    #[arg(
+        help_heading = "OUTPUT",
+        long = "email-from",
+        value_name = "ADDR",
+        env = "SMUGGLEX_EMAIL_FROM"
+    )]
+    pub email_from: Option<String>,
+
+    /// Send an RFC 5424 event per finding and per scanned target to a
+    /// syslog collector: udp://host[:port] or tcp://host[:port]
+    #[arg(
+        help_heading = "OUTPUT",
+        long = "syslog",
+        value_name = "URL",
+        env = "SMUGGLEX_SYSLOG"
+    )]
+    pub syslog: Option<String>,
+
+    /// Keep every run's results in this directory, as
+    /// <host>/<timestamp>.json per target plus an index.jsonl
+    #[arg(
+        help_heading = "OUTPUT",
+        long = "history-dir",
+        value_name = "DIR",
+        env = "SMUGGLEX_HISTORY_DIR"
+    )]
+    pub history_dir: Option<std::path::PathBuf>,
+
     /// Verbose mode
-    #[arg(help_heading = "OUTPUT", short = 'V', long, action = clap::ArgAction::SetTrue)]
+    #[arg(help_heading = "OUTPUT", short = 'V', long, action = clap::ArgAction::SetTrue, env = "SMUGGLEX_VERBOSE")]
     pub verbose: bool,
 
-    /// Specify which checks to run (comma-separated: cl-te,te-cl,te-te,h2c,h2,cl-edge,h2-downgrade).
+    /// Specify which checks to run (comma-separated: cl-te,te-cl,te-te,h2c,h2,cl-edge,cl-cl,asterisk,connection,head,h2-downgrade,early-data).
     /// h2-downgrade speaks real HTTP/2 (ALPN h2) to detect H2.CL/H2.TE and runs only on https targets.
-    #[arg(help_heading = "DETECT", short = 'c', long = "checks")]
+    /// early-data sends a request as TLS 1.3 0-RTT data; it runs only when named, on https targets.
+    #[arg(
+        help_heading = "DETECT",
+        short = 'c',
+        long = "checks",
+        env = "SMUGGLEX_CHECKS"
+    )]
     pub checks: Option<String>,
 
     /// Exit quickly after finding the first vulnerability
-    #[arg(help_heading = "DETECT", short = '1', long = "exit-first", action = clap::ArgAction::SetTrue)]
+    #[arg(help_heading = "DETECT", short = '1', long = "exit-first", action = clap::ArgAction::SetTrue, env = "SMUGGLEX_EXIT_FIRST")]
     pub exit_first: bool,
 
     /// Enable proxy fingerprinting before scan
-    #[arg(help_heading = "DETECT", long = "fingerprint", action = clap::ArgAction::SetTrue)]
+    #[arg(help_heading = "DETECT", long = "fingerprint", action = clap::ArgAction::SetTrue, env = "SMUGGLEX_FINGERPRINT")]
     pub fingerprint: bool,
 
-    /// Enable mutation-based fuzzing
-    #[arg(help_heading = "DETECT", long = "fuzz", action = clap::ArgAction::SetTrue)]
+    /// Instead of scanning, send a few cheap probes per target (keep-alive
+    /// reuse, one CL.TE and one TE.CL payload, fingerprint) and rank the
+    /// targets worth a full scan
+    #[arg(help_heading = "DETECT", long = "triage", action = clap::ArgAction::SetTrue, env = "SMUGGLEX_TRIAGE")]
+    pub triage: bool,
+
+    /// Probe with TRACE to see how the chain rewrites obfuscated TE/CL headers,
+    /// and send (and fuzz) payloads whose headers survive first
+    #[arg(help_heading = "DETECT", long = "trace-probe", action = clap::ArgAction::SetTrue, env = "SMUGGLEX_TRACE_PROBE")]
+    pub trace_probe: bool,
+
+    /// Enable mutation-based fuzzing (h2-downgrade mutates HTTP/2 frames)
+    #[arg(help_heading = "DETECT", long = "fuzz", action = clap::ArgAction::SetTrue, env = "SMUGGLEX_FUZZ")]
     pub fuzz: bool,
 
     /// Mutation seed for reproducibility (default: 42)
-    #[arg(help_heading = "DETECT", long = "fuzz-seed", default_value_t = 42)]
+    #[arg(
+        help_heading = "DETECT",
+        long = "fuzz-seed",
+        default_value_t = 42,
+        env = "SMUGGLEX_FUZZ_SEED"
+    )]
     pub fuzz_seed: u64,
 
+    /// Mutation strategies for --fuzz: byte (header/line-ending tweaks),
+    /// grammar (structure-aware), or mixed
+    #[arg(
+        help_heading = "DETECT",
+        long = "fuzz-mode",
+        value_enum,
+        default_value = "mixed",
+        env = "SMUGGLEX_FUZZ_MODE"
+    )]
+    pub fuzz_mode: FuzzMode,
+
+    /// Directory where --fuzz keeps anomalous mutants across runs; saved
+    /// entries are minimized and reused as seeds
+    #[arg(
+        help_heading = "DETECT",
+        long = "fuzz-corpus",
+        value_name = "DIR",
+        requires = "fuzz",
+        env = "SMUGGLEX_FUZZ_CORPUS"
+    )]
+    pub fuzz_corpus: Option<String>,
+
+    /// Checks whose payloads --fuzz mutates (comma-separated; default: every
+    /// check that runs). Other checks send only their built-in payloads
+    #[arg(
+        help_heading = "DETECT",
+        long = "fuzz-checks",
+        value_name = "CHECKS",
+        requires = "fuzz",
+        env = "SMUGGLEX_FUZZ_CHECKS"
+    )]
+    pub fuzz_checks: Option<String>,
+
+    /// Maximum mutants per fuzzed check (default: 5 per seed payload)
+    #[arg(
+        help_heading = "DETECT",
+        long = "fuzz-budget",
+        value_name = "N",
+        requires = "fuzz",
+        value_parser = clap::value_parser!(u32).range(1..),
+        env = "SMUGGLEX_FUZZ_BUDGET",
+    )]
+    pub fuzz_budget: Option<u32>,
+
     /// Exploit types to run after detection (comma-separated:
-    /// localhost-access,path-fuzz,smuggle,capture,reveal)
-    #[arg(help_heading = "EXPLOIT", short = 'e', long = "exploit")]
+    /// localhost-access,internal-sweep,path-fuzz,cloud-metadata,smuggle,capture,reveal)
+    #[arg(
+        help_heading = "EXPLOIT",
+        short = 'e',
+        long = "exploit",
+        env = "SMUGGLEX_EXPLOIT"
+    )]
     pub exploit: Option<String>,
 
+    /// Pick one exploit from the confirmed vulnerability and the proxy
+    /// fingerprint (reveal for CL.TE behind a cache, localhost-access
+    /// otherwise). Runs the fingerprint probe.
+    #[arg(
+        help_heading = "EXPLOIT",
+        long = "auto-exploit",
+        action = clap::ArgAction::SetTrue,
+        conflicts_with = "exploit",
+        env = "SMUGGLEX_AUTO_EXPLOIT",
+    )]
+    pub auto_exploit: bool,
+
+    /// Maximum requests the `--auto-exploit` exploit may send
+    #[arg(
+        help_heading = "EXPLOIT",
+        long = "auto-exploit-budget",
+        value_name = "N",
+        default_value_t = DEFAULT_AUTO_EXPLOIT_BUDGET,
+        value_parser = clap::value_parser!(u32).range(1..),
+        env = "SMUGGLEX_AUTO_EXPLOIT_BUDGET",
+    )]
+    pub auto_exploit_budget: u32,
+
+    /// Run exploits without asking: skip the `y/N` confirmation that follows
+    /// the exploit plan (required when stdin is not a terminal)
+    #[arg(help_heading = "EXPLOIT", short = 'y', long = "yes", action = clap::ArgAction::SetTrue, env = "SMUGGLEX_YES")]
+    pub yes: bool,
+
     /// Reflecting endpoint for the `reveal` exploit (a page that echoes a form
     /// field, e.g. a search box). Defaults to the scanned path.
     #[arg(
         help_heading = "EXPLOIT",
         long = "reveal-endpoint",
-        value_name = "PATH"
+        value_name = "PATH",
+        env = "SMUGGLEX_REVEAL_ENDPOINT"
     )]
     pub reveal_endpoint: Option<String>,
 
@@ -188,7 +841,8 @@ pub struct Cli {
         help_heading = "EXPLOIT",
         long = "reveal-param",
         value_name = "NAME",
-        default_value = "q"
+        default_value = "q",
+        env = "SMUGGLEX_REVEAL_PARAM"
     )]
     pub reveal_param: String,
 
@@ -196,21 +850,167 @@ pub struct Cli {
     /// headers; use \r\n for line breaks). For `smuggle` it defaults to a request
     /// that makes the back-end process method GPOST; for `capture` it defaults to
     /// `GET /admin` (whose smuggled response is then recovered).
-    #[arg(help_heading = "EXPLOIT", long = "smuggle-request")]
+    #[arg(
+        help_heading = "EXPLOIT",
+        long = "smuggle-request",
+        env = "SMUGGLEX_SMUGGLE_REQUEST"
+    )]
     pub smuggle_request: Option<String>,
 
-    /// Ports to test for localhost access exploit (comma-separated)
+    /// Ports to test for the localhost access and internal sweep exploits
+    /// (comma-separated)
     #[arg(
         help_heading = "EXPLOIT",
         long = "exploit-ports",
-        default_value = "22,80,443,8080,3306"
+        visible_alias = "ports",
+        default_value = "22,80,443,8080,3306",
+        env = "SMUGGLEX_EXPLOIT_PORTS"
     )]
     pub exploit_ports: String,
 
-    /// Wordlist file for path-fuzz exploit (one path per line)
-    #[arg(help_heading = "EXPLOIT", long = "exploit-wordlist")]
+    /// Internal host names for localhost access exploit (comma-separated),
+    /// each smuggled to on every `--exploit-ports` port
+    #[arg(
+        help_heading = "EXPLOIT",
+        long = "exploit-hosts",
+        env = "SMUGGLEX_EXPLOIT_HOSTS"
+    )]
+    pub exploit_hosts: Option<String>,
+
+    /// Internal IPv4 addresses for the internal-sweep exploit: a CIDR block
+    /// (at most a /16) or a single address
+    #[arg(
+        help_heading = "EXPLOIT",
+        long = "range",
+        value_name = "CIDR",
+        env = "SMUGGLEX_RANGE"
+    )]
+    pub range: Option<String>,
+
+    /// Maximum requests the internal-sweep exploit may send
+    #[arg(
+        help_heading = "EXPLOIT",
+        long = "sweep-budget",
+        value_name = "N",
+        default_value_t = DEFAULT_SWEEP_BUDGET,
+        value_parser = clap::value_parser!(u32).range(1..),
+        env = "SMUGGLEX_SWEEP_BUDGET",
+    )]
+    pub sweep_budget: u32,
+
+    /// Most requests the exploits of a target may send together; exploits
+    /// stop there and report what they found so far
+    #[arg(
+        help_heading = "EXPLOIT",
+        long = "exploit-max-requests",
+        value_name = "N",
+        value_parser = clap::value_parser!(u32).range(1..),
+        env = "SMUGGLEX_EXPLOIT_MAX_REQUESTS",
+    )]
+    pub exploit_max_requests: Option<u32>,
+
+    /// Most seconds the exploits of a target may run together, checked
+    /// between requests; exploits stop there and report what they found
+    #[arg(
+        help_heading = "EXPLOIT",
+        long = "exploit-max-time",
+        value_name = "SECS",
+        value_parser = clap::value_parser!(u64).range(1..),
+        env = "SMUGGLEX_EXPLOIT_MAX_TIME",
+    )]
+    pub exploit_max_time: Option<u64>,
+
+    /// Wordlist file for path-fuzz exploit (one path per line), or the name
+    /// of a wordlist downloaded by `smugglex wordlists update`
+    #[arg(
+        help_heading = "EXPLOIT",
+        long = "exploit-wordlist",
+        env = "SMUGGLEX_EXPLOIT_WORDLIST"
+    )]
     pub exploit_wordlist: Option<String>,
 
+    /// Directory downloaded wordlists are kept in (default:
+    /// $XDG_DATA_HOME/smugglex, or ~/.local/share/smugglex)
+    #[arg(
+        help_heading = "EXPLOIT",
+        long = "data-dir",
+        value_name = "DIR",
+        env = "SMUGGLEX_DATA_DIR"
+    )]
+    pub data_dir: Option<std::path::PathBuf>,
+
+    /// Built-in path-fuzz wordlists to use (comma-separated: common, admin,
+    /// api, debug, cloud-metadata, actuator). Combined with --exploit-wordlist
+    /// when both are given; defaults to `common` when neither is.
+    #[arg(
+        help_heading = "EXPLOIT",
+        long = "fuzz-categories",
+        value_enum,
+        value_delimiter = ',',
+        env = "SMUGGLEX_FUZZ_CATEGORIES"
+    )]
+    pub fuzz_categories: Vec<FuzzCategory>,
+
+    /// Report path-fuzz responses with these status codes (comma-separated)
+    #[arg(
+        help_heading = "EXPLOIT",
+        long = "match-status",
+        value_name = "CODES",
+        value_delimiter = ',',
+        env = "SMUGGLEX_MATCH_STATUS"
+    )]
+    pub match_status: Vec<u16>,
+
+    /// Hide path-fuzz responses with these status codes (comma-separated)
+    #[arg(
+        help_heading = "EXPLOIT",
+        long = "filter-status",
+        value_name = "CODES",
+        value_delimiter = ',',
+        env = "SMUGGLEX_FILTER_STATUS"
+    )]
+    pub filter_status: Vec<u16>,
+
+    /// Report path-fuzz responses whose body size is in these ranges
+    /// (comma-separated, `N` or `N-M` bytes)
+    #[arg(
+        help_heading = "EXPLOIT",
+        long = "match-size",
+        value_name = "SIZES",
+        value_delimiter = ',',
+        env = "SMUGGLEX_MATCH_SIZE"
+    )]
+    pub match_size: Vec<SizeRange>,
+
+    /// Hide path-fuzz responses whose body size is in these ranges
+    /// (comma-separated, `N` or `N-M` bytes)
+    #[arg(
+        help_heading = "EXPLOIT",
+        long = "filter-size",
+        value_name = "SIZES",
+        value_delimiter = ',',
+        env = "SMUGGLEX_FILTER_SIZE"
+    )]
+    pub filter_size: Vec<SizeRange>,
+
+    /// Report path-fuzz responses whose body matches this regex
+    #[arg(
+        help_heading = "EXPLOIT",
+        long = "match-regex",
+        value_name = "REGEX",
+        env = "SMUGGLEX_MATCH_REGEX"
+    )]
+    pub match_regex: Option<Regex>,
+
+    /// Hide path-fuzz responses whose body matches this regex
+    #[arg(
+        help_heading = "EXPLOIT",
+        long = "filter-regex",
+        value_name = "REGEX",
+        env = "SMUGGLEX_FILTER_REGEX"
+    )]
+    pub filter_regex: Option<Regex>,
+
     /// Print version information
     #[arg(short = 'v', long = "version", action = clap::ArgAction::SetTrue)]
     pub version: bool,
@@ -220,16 +1020,27 @@ pub struct Cli {
         help_heading = "REQUEST",
         short = 'd',
         long = "delay",
-        default_value_t = 0
+        default_value_t = 0,
+        env = "SMUGGLEX_DELAY"
     )]
     pub delay: u64,
 
+    /// Global cap on requests per second across all concurrent targets
+    #[arg(
+        help_heading = "REQUEST",
+        long = "rps",
+        value_name = "N",
+        value_parser = clap::value_parser!(u32).range(1..),
+        env = "SMUGGLEX_RPS",
+    )]
+    pub rps: Option<u32>,
+
     /// Quiet mode (only show vulnerabilities)
-    #[arg(help_heading = "OUTPUT", short = 'q', long, action = clap::ArgAction::SetTrue)]
+    #[arg(help_heading = "OUTPUT", short = 'q', long, action = clap::ArgAction::SetTrue, env = "SMUGGLEX_QUIET")]
     pub quiet: bool,
 
     /// Disable colored output
-    #[arg(help_heading = "OUTPUT", long = "no-color", action = clap::ArgAction::SetTrue)]
+    #[arg(help_heading = "OUTPUT", long = "no-color", action = clap::ArgAction::SetTrue, env = "SMUGGLEX_NO_COLOR")]
     pub no_color: bool,
 
     /// Number of URLs to scan concurrently
@@ -237,32 +1048,414 @@ pub struct Cli {
         help_heading = "REQUEST",
         short = 'j',
         long = "concurrency",
-        default_value_t = 1
+        default_value_t = 1,
+        env = "SMUGGLEX_CONCURRENCY"
     )]
     pub concurrency: usize,
 
+    /// Tune -j, the number of URLs scanned at once: start at 1, step up while
+    /// targets stay healthy and halve on errors or unstable latency, up to
+    /// -j (16 when -j is 1). Payloads within a check are always sent one at a
+    /// time, since a desync shows on the requests that follow it
+    #[arg(
+        help_heading = "REQUEST",
+        long = "auto-concurrency",
+        action = clap::ArgAction::SetTrue,
+        env = "SMUGGLEX_AUTO_CONCURRENCY"
+    )]
+    pub auto_concurrency: bool,
+
+    /// Maximum URLs scanned at once per resolved host, within the -j limit
+    #[arg(
+        help_heading = "REQUEST",
+        long = "per-host-concurrency",
+        value_name = "N",
+        value_parser = clap::value_parser!(u32).range(1..),
+        env = "SMUGGLEX_PER_HOST_CONCURRENCY",
+    )]
+    pub per_host_concurrency: Option<u32>,
+
+    /// Longest Retry-After pause honored, in seconds (0 ignores Retry-After)
+    #[arg(
+        help_heading = "REQUEST",
+        long = "retry-after-max",
+        value_name = "SECS",
+        default_value_t = 30,
+        env = "SMUGGLEX_RETRY_AFTER_MAX"
+    )]
+    pub retry_after_max: u64,
+
+    /// Most response bytes read and kept per request, in KB; reading stops
+    /// there
+    #[arg(
+        help_heading = "REQUEST",
+        long = "max-response-size",
+        value_name = "KB",
+        default_value_t = 512,
+        value_parser = clap::value_parser!(u64).range(1..),
+        env = "SMUGGLEX_MAX_RESPONSE_SIZE",
+    )]
+    pub max_response_size: u64,
+
+    /// Keep compressed response bodies (Content-Encoding) as received instead
+    /// of decoding gzip, deflate and br
+    #[arg(
+        help_heading = "REQUEST",
+        long = "no-decompress",
+        env = "SMUGGLEX_NO_DECOMPRESS"
+    )]
+    pub no_decompress: bool,
+
     /// HTTP/SOCKS proxy URL (e.g., http://127.0.0.1:8080)
-    #[arg(help_heading = "REQUEST", short = 'x', long = "proxy")]
+    #[arg(
+        help_heading = "REQUEST",
+        short = 'x',
+        long = "proxy",
+        env = "SMUGGLEX_PROXY",
+        hide_env_values = true
+    )]
     pub proxy: Option<String>,
 
+    /// Credentials for the proxy (Basic, or Digest when challenged);
+    /// accepts @env:NAME and @file:PATH
+    #[arg(
+        help_heading = "REQUEST",
+        long = "proxy-auth",
+        value_name = "USER:PASS",
+        env = "SMUGGLEX_PROXY_AUTH",
+        hide_env_values = true
+    )]
+    pub proxy_auth: Option<String>,
+
+    /// Ignore HTTP_PROXY / HTTPS_PROXY / ALL_PROXY / NO_PROXY from the environment
+    #[arg(
+        help_heading = "REQUEST",
+        long = "no-env-proxy",
+        env = "SMUGGLEX_NO_ENV_PROXY"
+    )]
+    pub no_env_proxy: bool,
+
+    /// Send only the curated highest-yield payloads of each check (~10 per check)
+    #[arg(help_heading = "DETECT", long = "quick", env = "SMUGGLEX_QUICK")]
+    pub quick: bool,
+
+    /// Also send the long tail of rarely-useful variants (full control-character
+    /// matrix, per-byte prefix sweeps, exotic encodings) after the default payloads
+    #[arg(
+        help_heading = "DETECT",
+        long = "extended",
+        conflicts_with = "quick",
+        env = "SMUGGLEX_EXTENDED"
+    )]
+    pub extended: bool,
+
     /// Maximum number of payloads to test per check type
-    #[arg(help_heading = "DETECT", long = "max-payloads")]
+    #[arg(
+        help_heading = "DETECT",
+        long = "max-payloads",
+        env = "SMUGGLEX_MAX_PAYLOADS"
+    )]
     pub max_payloads: Option<usize>,
 
     /// Number of baseline requests for timing measurement
-    #[arg(help_heading = "DETECT", long = "baseline-count", default_value_t = 3)]
+    #[arg(
+        help_heading = "DETECT",
+        long = "baseline-count",
+        default_value_t = 3,
+        env = "SMUGGLEX_BASELINE_COUNT"
+    )]
     pub baseline_count: usize,
 
+    /// Throwaway requests sent before each baseline measurement to warm up
+    /// caches and connection pools
+    #[arg(
+        help_heading = "DETECT",
+        long = "warmup",
+        value_name = "N",
+        default_value_t = 0,
+        env = "SMUGGLEX_WARMUP"
+    )]
+    pub warmup: usize,
+
+    /// Run each check (h2-downgrade and early-data included) N times and report
+    /// how many runs found it (found 3/3, 1/3, ...), flagging intermittent
+    /// findings as flaky
+    #[arg(
+        help_heading = "DETECT",
+        long = "repeat",
+        value_name = "N",
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u32).range(1..),
+        env = "SMUGGLEX_REPEAT"
+    )]
+    pub repeat: u32,
+
+    /// Seconds to pause a target after a storm of failed or 5xx responses
+    /// before re-baselining it; the target is aborted if it has not recovered
+    #[arg(
+        help_heading = "DETECT",
+        long = "storm-cooldown",
+        value_name = "SECS",
+        default_value_t = 10,
+        env = "SMUGGLEX_STORM_COOLDOWN"
+    )]
+    pub storm_cooldown: u64,
+
+    /// Detection strategies to combine (timing, differential, canary, oob)
+    #[arg(
+        help_heading = "DETECT",
+        long = "detect",
+        value_enum,
+        value_delimiter = ',',
+        default_value = "timing,differential,canary",
+        env = "SMUGGLEX_DETECT"
+    )]
+    pub detect: Vec<DetectionMethod>,
+
+    /// Out-of-band interaction host for `--detect oob` (e.g. an interactsh domain)
+    #[arg(
+        help_heading = "DETECT",
+        long = "oob-host",
+        value_name = "HOST",
+        env = "SMUGGLEX_OOB_HOST"
+    )]
+    pub oob_host: Option<String>,
+
     /// Skip TLS certificate verification (allow self-signed certificates)
-    #[arg(help_heading = "TLS", short = 'k', long = "insecure", action = clap::ArgAction::SetTrue)]
+    #[arg(help_heading = "TLS", short = 'k', long = "insecure", action = clap::ArgAction::SetTrue, env = "SMUGGLEX_INSECURE")]
     pub insecure: bool,
 
     /// Custom CA certificate file (PEM format) for self-signed/internal certificates
-    #[arg(help_heading = "TLS", long = "cacert", value_name = "FILE")]
+    #[arg(
+        help_heading = "TLS",
+        long = "cacert",
+        value_name = "FILE",
+        env = "SMUGGLEX_CACERT"
+    )]
     pub cacert: Option<String>,
+
+    /// Do a full TLS handshake on every connection instead of resuming cached sessions
+    #[arg(help_heading = "TLS", long = "no-tls-resume", action = clap::ArgAction::SetTrue, env = "SMUGGLEX_NO_TLS_RESUME")]
+    pub no_tls_resume: bool,
+}
+
+/// Why `target` matches one of `exclude`, or none of a non-empty `include`;
+/// `None` when it is in scope.
+pub fn out_of_scope(target: &str, include: &[Regex], exclude: &[Regex]) -> Option<String> {
+    if let Some(re) = exclude.iter().find(|re| re.is_match(target)) {
+        return Some(format!("matches --scope-exclude '{}'", re));
+    }
+    if !include.is_empty() && !include.iter().any(|re| re.is_match(target)) {
+        return Some("matches no --scope-include".to_string());
+    }
+    None
+}
+
+/// Flags a daemon job may not pass: they read or write files on the daemon
+/// host, run exploits, or send results anywhere but the API.
+pub const JOB_FORBIDDEN_ARGS: &[&str] = &[
+    "list",
+    "raw_request",
+    "output",
+    "export_dir",
+    "export_all",
+    "history_dir",
+    "email_to",
+    "smtp",
+    "email_from",
+    "syslog",
+    "fuzz_corpus",
+    "exploit",
+    "auto_exploit",
+    "yes",
+    "exploit_wordlist",
+    "data_dir",
+    "cacert",
+];
+
+/// Flags a daemon job may not pass because they are process-wide: the daemon
+/// applied them once at startup and every job shares them.
+pub const JOB_DAEMON_WIDE_ARGS: &[&str] = &[
+    "proxy",
+    "proxy_auth",
+    "no_env_proxy",
+    "rps",
+    "per_host_concurrency",
+    "max_response_size",
+    "retry_after_max",
+    "no_decompress",
+    "redact",
+    "no_redact",
+    "insecure",
+    "no_tls_resume",
+];
+
+/// The long flag of a [`Cli`] argument id (`rps` -> `rps`, `exit_first` ->
+/// `exit-first`).
+fn long_flag(id: &str) -> String {
+    use clap::CommandFactory;
+    Cli::command()
+        .get_arguments()
+        .find(|arg| arg.get_id() == id)
+        .and_then(|arg| arg.get_long())
+        .unwrap_or(id)
+        .to_string()
 }
 
 impl Cli {
+    /// Parse the scan args of a daemon job. Subcommands, the flags in
+    /// [`JOB_FORBIDDEN_ARGS`] and [`JOB_DAEMON_WIDE_ARGS`], `@env:`/`@file:`
+    /// values and `--vhost` lists, files or wordlists are rejected; the
+    /// forbidden settings are also cleared when they come from the daemon's
+    /// environment, so a job never touches the daemon host beyond the
+    /// requests it sends.
+    pub fn parse_job_args(args: &[String]) -> crate::error::Result<Cli> {
+        Cli::parse_restricted_args(args, "job args", true)
+    }
+
+    /// Parse the scan profile a coordinator sent to a worker, with the same
+    /// rules as [`Cli::parse_job_args`] except that process-wide settings
+    /// (proxy, rate limit, TLS) are allowed: the worker applies them. A
+    /// coordinator can thus only make a worker send scan requests, never
+    /// exploit, read files or write anything on the worker host.
+    pub fn parse_worker_profile(args: &[String]) -> crate::error::Result<Cli> {
+        Cli::parse_restricted_args(args, "a worker profile", false)
+    }
+
+    /// The scan profile a coordinator forwards to its workers, from its own
+    /// parsed args: every top-level setting given on the command line or
+    /// through a `SMUGGLEX_*` variable, as `--flag` / `--flag=value` args.
+    /// The [`JOB_FORBIDDEN_ARGS`] (output files, exploits, ...) stay with the
+    /// coordinator, which writes the aggregated results itself.
+    pub fn worker_profile(matches: &clap::ArgMatches) -> Vec<String> {
+        use clap::{ArgAction, CommandFactory, parser::ValueSource};
+        let mut profile = Vec::new();
+        for arg in Cli::command().get_arguments() {
+            let id = arg.get_id().as_str();
+            let Some(long) = arg.get_long() else {
+                continue;
+            };
+            if JOB_FORBIDDEN_ARGS.contains(&id)
+                || !matches!(
+                    matches.value_source(id),
+                    Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+                )
+            {
+                continue;
+            }
+            match arg.get_action() {
+                ArgAction::SetTrue => {
+                    if matches.get_flag(id) {
+                        profile.push(format!("--{}", long));
+                    }
+                }
+                ArgAction::Help | ArgAction::HelpShort | ArgAction::HelpLong => {}
+                _ => {
+                    for value in matches.get_raw(id).into_iter().flatten() {
+                        profile.push(format!("--{}={}", long, value.to_string_lossy()));
+                    }
+                }
+            }
+        }
+        profile
+    }
+
+    /// [`Cli::parse_job_args`] and [`Cli::parse_worker_profile`]; `context`
+    /// names the args in errors.
+    fn parse_restricted_args(
+        args: &[String],
+        context: &str,
+        reject_daemon_wide: bool,
+    ) -> crate::error::Result<Cli> {
+        use clap::{CommandFactory, FromArgMatches, parser::ValueSource};
+        let invalid = |e: clap::Error| {
+            crate::error::SmugglexError::Config(format!("invalid scan args: {}", e))
+        };
+        let matches = Cli::command()
+            .try_get_matches_from(
+                std::iter::once("smugglex").chain(args.iter().map(|a| a.as_str())),
+            )
+            .map_err(invalid)?;
+        let mut cli = Cli::from_arg_matches(&matches).map_err(invalid)?;
+        if cli.command.is_some() {
+            return Err(crate::error::SmugglexError::Config(format!(
+                "subcommands are not allowed in {}",
+                context
+            )));
+        }
+        if let Some(id) = JOB_FORBIDDEN_ARGS
+            .iter()
+            .find(|id| matches.value_source(id) == Some(ValueSource::CommandLine))
+        {
+            return Err(crate::error::SmugglexError::Config(format!(
+                "--{} is not allowed in {}",
+                long_flag(id),
+                context
+            )));
+        }
+        if reject_daemon_wide
+            && let Some(id) = JOB_DAEMON_WIDE_ARGS
+                .iter()
+                .find(|id| matches.value_source(id) == Some(ValueSource::CommandLine))
+        {
+            let daemon_wide: Vec<String> = JOB_DAEMON_WIDE_ARGS
+                .iter()
+                .map(|id| format!("--{}", long_flag(id)))
+                .collect();
+            return Err(crate::error::SmugglexError::Config(format!(
+                "--{} is daemon-wide and must be set when starting the daemon, not per job \
+                 (daemon-wide: {})",
+                long_flag(id),
+                daemon_wide.join(", ")
+            )));
+        }
+        // A job must not read the daemon's environment or files into the
+        // requests it sends.
+        if cli
+            .headers
+            .iter()
+            .any(|h| crate::secrets::has_secret_reference(h))
+            || cli
+                .proxy_auth
+                .as_deref()
+                .is_some_and(crate::secrets::has_secret_reference)
+        {
+            return Err(crate::error::SmugglexError::Config(format!(
+                "@env:/@file: values are not allowed in {}",
+                context
+            )));
+        }
+        // A job scans one Host per target, taken literally.
+        if let Some(ref vhost) = cli.vhost
+            && (vhost.contains(',')
+                || std::path::Path::new(vhost).exists()
+                || crate::wordlists::find_wordlist(vhost).is_some())
+        {
+            return Err(crate::error::SmugglexError::Config(format!(
+                "--vhost lists, files and wordlists are not allowed in {}",
+                context
+            )));
+        }
+        cli.list = None;
+        cli.raw_request = None;
+        cli.output.clear();
+        cli.export_dir = None;
+        cli.export_all = None;
+        cli.history_dir = None;
+        cli.email_to.clear();
+        cli.smtp = None;
+        cli.email_from = None;
+        cli.syslog = None;
+        cli.fuzz_corpus = None;
+        cli.exploit = None;
+        cli.auto_exploit = false;
+        cli.yes = false;
+        cli.exploit_wordlist = None;
+        cli.data_dir = None;
+        cli.cacert = None;
+        Ok(cli)
+    }
+
     /// Apply global settings like no-color mode
     pub fn apply_global_settings(&self) {
         if self.no_color {
@@ -273,7 +1466,104 @@ impl Cli {
         }
         if let Some(ref proxy) = self.proxy {
             crate::http::set_proxy(proxy.clone());
+        } else if !self.no_env_proxy {
+            crate::http::set_env_proxy(crate::env_proxy::EnvProxy::from_env());
+        }
+        if let Some(rps) = self.rps {
+            crate::http::set_rate_limit(rps);
+        }
+        crate::http::set_retry_after_max(std::time::Duration::from_secs(self.retry_after_max));
+        crate::decompress::set_decompression(!self.no_decompress);
+        crate::http::set_max_response_size(self.max_response_size as usize * 1024);
+        if let Some(ref dir) = self.data_dir {
+            crate::wordlists::set_data_dir(dir.clone());
+        }
+        if let Some(limit) = self.per_host_concurrency {
+            crate::http::set_per_host_concurrency(limit as usize);
+        }
+        // Values read from `@env:`/`@file:` are secrets whatever the header.
+        let mut redacted = self.redact.clone();
+        redacted.extend(crate::secrets::secret_header_names(&self.headers));
+        crate::redact::set_redacted_headers(if self.no_redact { &[] } else { &redacted });
+    }
+
+    /// Why `target` is outside `--scope-include` / `--scope-exclude`, or
+    /// `None` when it may be scanned.
+    pub fn out_of_scope(&self, target: &str) -> Option<String> {
+        out_of_scope(target, &self.scope_include, &self.scope_exclude)
+    }
+
+    /// Host header values from `--vhost`: each label of the downloaded
+    /// subdomain wordlist it names under the target's host, the lines of the
+    /// file it names (blank lines and `#` comments skipped), else its
+    /// comma-separated entries; a value without a comma is used as is. Values
+    /// may hold [`VHOST_TARGET_HOST`]. Empty without `--vhost`.
+    pub fn vhosts(&self) -> crate::error::Result<Vec<String>> {
+        let Some(ref vhost) = self.vhost else {
+            return Ok(Vec::new());
+        };
+        let list = if let Some((wordlist, file)) = crate::wordlists::resolve_wordlist(vhost)? {
+            if wordlist.kind != crate::wordlists::WordlistKind::Subdomains {
+                return Err(crate::error::SmugglexError::Config(format!(
+                    "--vhost '{}' is a {} wordlist, not a subdomains one",
+                    vhost, wordlist.kind
+                )));
+            }
+            crate::wordlists::wordlist_entries(&std::fs::read_to_string(file)?)
+                .into_iter()
+                .map(|label| format!("{}.{}", label, VHOST_TARGET_HOST))
+                .collect()
+        } else if std::path::Path::new(vhost).is_file() {
+            std::fs::read_to_string(vhost)?
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty() && !l.starts_with('#'))
+                .map(String::from)
+                .collect::<Vec<_>>()
+        } else if !vhost.contains(',') {
+            return Ok(vec![vhost.clone()]);
+        } else {
+            vhost
+                .split(',')
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(String::from)
+                .collect()
+        };
+        if list.is_empty() {
+            return Err(crate::error::SmugglexError::Config(format!(
+                "--vhost '{}' names no hosts",
+                vhost
+            )));
         }
+        Ok(list)
+    }
+
+    /// Path-fuzz response rules from the `--match-*` / `--filter-*` options.
+    pub fn response_matcher(&self) -> ResponseMatcher {
+        ResponseMatcher {
+            match_status: self.match_status.clone(),
+            filter_status: self.filter_status.clone(),
+            match_size: self.match_size.clone(),
+            filter_size: self.filter_size.clone(),
+            match_regex: self.match_regex.clone(),
+            filter_regex: self.filter_regex.clone(),
+        }
+    }
+
+    /// `-o` files that get the JSON results.
+    pub fn json_outputs(&self) -> impl Iterator<Item = &str> {
+        self.output
+            .iter()
+            .map(String::as_str)
+            .filter(|path| ReportFormat::for_path(path).is_none())
+    }
+
+    /// `-o` files that get a report, with the format their extension names.
+    pub fn report_outputs(&self) -> impl Iterator<Item = (&str, ReportFormat)> {
+        self.output
+            .iter()
+            .filter_map(|path| ReportFormat::for_path(path).map(|format| (path.as_str(), format)))
     }
 
     /// Returns the effective output format, honoring both --format and the --json shorthand.
@@ -287,6 +1577,30 @@ impl Cli {
     }
 }
 
+/// Completion script for `shell`.
+///
+/// `--checks`, `--fuzz-checks` and `--exploit` take free-form comma-separated
+/// lists, so their names are attached as possible values only on the command
+/// the script is generated from; parsing still goes through the list checks.
+pub fn completion_script(shell: clap_complete::Shell) -> String {
+    use clap::CommandFactory;
+    use clap::builder::PossibleValuesParser;
+
+    let mut cmd = Cli::command()
+        .mut_arg("checks", |a| {
+            a.value_parser(PossibleValuesParser::new(KNOWN_CHECK_NAMES))
+        })
+        .mut_arg("fuzz_checks", |a| {
+            a.value_parser(PossibleValuesParser::new(KNOWN_CHECK_NAMES))
+        })
+        .mut_arg("exploit", |a| {
+            a.value_parser(PossibleValuesParser::new(KNOWN_EXPLOIT_NAMES))
+        });
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut cmd, "smugglex", &mut script);
+    String::from_utf8_lossy(&script).into_owned()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -313,6 +1627,7 @@ mod tests {
     fn has_any_known_check_detects_all_typos() {
         assert!(has_any_known_check("clte,cl-te", &KNOWN_CHECK_NAMES));
         assert!(has_any_known_check("h2-downgrade", &KNOWN_CHECK_NAMES));
+        assert!(has_any_known_check("early-data", &KNOWN_CHECK_NAMES));
         // A wholly typo'd selection must report no known check (the dangerous
         // case: scanning nothing while reporting a clean target).
         assert!(!has_any_known_check("clte", &KNOWN_CHECK_NAMES));
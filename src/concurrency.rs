@@ -0,0 +1,161 @@
+//! `--auto-concurrency`: pick how many targets to scan at once from how the
+//! targets hold up, instead of guessing a safe `-j` up front.
+//!
+//! Only target-level parallelism is tuned. A check sends its payloads one at
+//! a time on purpose: a desync shows on the request that follows the attack,
+//! so concurrent payloads would poison each other's responses.
+//!
+//! Targets are scanned in rounds. The first round scans one target; every
+//! healthy round allows one more target in the next, up to the ceiling, and a
+//! round that degraded halves it:
+//!
+//! - more than [`MAX_ERROR_RATE`] of its requests failed at the transport
+//!   level (refused, reset, TLS, DNS), or
+//! - a target's baseline requests got unstable: the slowest took more than
+//!   [`MAX_LATENCY_SPREAD`] times the median and over [`MIN_LATENCY_JUMP_MS`]
+//!   longer.
+//!
+//! Timeouts are left out of the error rate: an attack request timing out is
+//! how a TE.CL or CL.TE desync shows, not a sign of overload.
+
+use crate::model::{RequestErrorKind, ScanResults};
+
+/// Ceiling when `-j` is left at 1.
+pub const DEFAULT_AUTO_CONCURRENCY_MAX: usize = 16;
+
+/// Share of a round's requests that may fail before it backs off.
+pub const MAX_ERROR_RATE: f64 = 0.05;
+
+/// How many times the median baseline latency the slowest baseline request
+/// of a target may take.
+pub const MAX_LATENCY_SPREAD: u64 = 3;
+
+/// Least absolute gap between the slowest and the median baseline request
+/// for a spread to count, so millisecond jitter on a fast target does not.
+pub const MIN_LATENCY_JUMP_MS: u64 = 1000;
+
+/// What one scanned target tells about its load.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScanSample {
+    /// Requests sent
+    pub requests: u64,
+    /// Requests that failed at the transport level, timeouts excepted
+    pub errors: u64,
+    /// Durations of the checks' baseline (unmodified) requests
+    pub latencies_ms: Vec<u64>,
+}
+
+impl ScanSample {
+    /// The sample of a target's results; a scan that failed outright counts
+    /// as one failed request.
+    pub fn from_results(scan: &ScanResults) -> Self {
+        if scan.error.is_some() {
+            return Self::failed();
+        }
+        let errors = scan
+            .checks
+            .iter()
+            .flat_map(|check| &check.request_errors)
+            .filter(|(kind, _)| **kind != RequestErrorKind::Timeout)
+            .map(|(_, count)| *count as u64)
+            .sum();
+        let requests = scan.requests_sent.unwrap_or_else(|| {
+            scan.checks
+                .iter()
+                .map(|check| check.payloads_sent as u64 + 1)
+                .sum()
+        });
+        ScanSample {
+            requests: requests.max(errors),
+            errors,
+            latencies_ms: scan
+                .checks
+                .iter()
+                .map(|check| check.normal_duration_ms)
+                .filter(|ms| *ms > 0)
+                .collect(),
+        }
+    }
+
+    /// A target whose scan failed before producing results.
+    pub fn failed() -> Self {
+        ScanSample {
+            requests: 1,
+            errors: 1,
+            latencies_ms: Vec::new(),
+        }
+    }
+
+    /// Whether the baseline latency spread past [`MAX_LATENCY_SPREAD`]; needs
+    /// at least three requests to tell.
+    pub fn latency_unstable(&self) -> bool {
+        if self.latencies_ms.len() < 3 {
+            return false;
+        }
+        let mut sorted = self.latencies_ms.clone();
+        sorted.sort_unstable();
+        let median = sorted[sorted.len() / 2];
+        let slowest = sorted[sorted.len() - 1];
+        slowest > median * MAX_LATENCY_SPREAD && slowest - median >= MIN_LATENCY_JUMP_MS
+    }
+}
+
+/// A change of the number of targets scanned at once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Adjustment {
+    pub from: usize,
+    pub to: usize,
+    /// Why it backed off; `None` for a step up
+    pub reason: Option<String>,
+}
+
+/// Additive-increase, multiplicative-decrease control of the targets scanned
+/// at once.
+#[derive(Debug, Clone)]
+pub struct AutoConcurrency {
+    ceiling: usize,
+    current: usize,
+}
+
+impl AutoConcurrency {
+    /// Start at one target, never going above `ceiling` (at least 1).
+    pub fn new(ceiling: usize) -> Self {
+        AutoConcurrency {
+            ceiling: ceiling.max(1),
+            current: 1,
+        }
+    }
+
+    /// Targets to scan in the next round.
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// Adjust to the samples of the round just scanned; `None` when the
+    /// number stays.
+    pub fn observe(&mut self, round: &[ScanSample]) -> Option<Adjustment> {
+        let from = self.current;
+        let requests: u64 = round.iter().map(|s| s.requests).sum();
+        let errors: u64 = round.iter().map(|s| s.errors).sum();
+        let unstable = round.iter().filter(|s| s.latency_unstable()).count();
+        let reason = if requests > 0 && errors as f64 / requests as f64 > MAX_ERROR_RATE {
+            Some(format!("{} of {} request(s) failed", errors, requests))
+        } else if unstable > 0 {
+            Some(format!(
+                "baseline latency unstable on {} target(s)",
+                unstable
+            ))
+        } else {
+            None
+        };
+        self.current = match reason {
+            Some(_) => (from / 2).max(1),
+            None => (from + 1).min(self.ceiling),
+        };
+        (self.current != from).then_some(Adjustment {
+            from,
+            to: self.current,
+            reason,
+        })
+    }
+}
@@ -0,0 +1,136 @@
+//! On-disk fuzzing corpus (`--fuzz-corpus`).
+//!
+//! Mutants that produced a timing/status anomaly are saved as raw requests,
+//! one file per distinct request, so they outlive the run that found them and
+//! are fed back in as extra seeds on the next `--fuzz` run.
+
+use crate::error::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Corpus file extension.
+const CORPUS_EXT: &str = "txt";
+
+/// A directory of saved anomalous payloads, named `<check>-<hash>.txt`.
+#[derive(Debug, Clone)]
+pub struct FuzzCorpus {
+    dir: PathBuf,
+}
+
+impl FuzzCorpus {
+    /// Open (creating if needed) the corpus directory.
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    /// Save `request` under `check_name`. Requests that only differ in their
+    /// `Host` value share an entry, so the same mutant found on several
+    /// targets is kept once. Returns the new file's path, or `None` when the
+    /// entry already existed.
+    pub fn save(&self, check_name: &str, request: &str) -> Result<Option<PathBuf>> {
+        let path = self.dir.join(format!(
+            "{}-{:016x}.{}",
+            check_name,
+            corpus_hash(&normalize_host(request, "")),
+            CORPUS_EXT
+        ));
+        if path.exists() {
+            return Ok(None);
+        }
+        fs::write(&path, request)?;
+        Ok(Some(path))
+    }
+
+    /// Load the entries saved for `check_name`, sorted by file name, with the
+    /// `Host` header rewritten to `host_header` so they address this target.
+    pub fn load(&self, check_name: &str, host_header: &str) -> Result<Vec<String>> {
+        let mut paths: Vec<PathBuf> = fs::read_dir(&self.dir)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| is_entry_for(name, check_name))
+            })
+            .collect();
+        paths.sort();
+
+        let mut entries = Vec::with_capacity(paths.len());
+        for path in paths {
+            let request = fs::read_to_string(&path)?;
+            if !request.is_empty() {
+                entries.push(normalize_host(&request, host_header));
+            }
+        }
+        Ok(entries)
+    }
+}
+
+/// Whether `file_name` is a `<check_name>-<16 hex digits>.txt` corpus entry
+/// (a bare prefix match would let `cl-te` pick up a `cl-te-edge` entry).
+fn is_entry_for(file_name: &str, check_name: &str) -> bool {
+    file_name
+        .strip_prefix(check_name)
+        .and_then(|rest| rest.strip_prefix('-'))
+        .and_then(|rest| rest.strip_suffix(CORPUS_EXT))
+        .and_then(|rest| rest.strip_suffix('.'))
+        .is_some_and(|hash| hash.len() == 16 && hash.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+/// Replace the value of the first `Host` header line with `host`.
+fn normalize_host(request: &str, host: &str) -> String {
+    let head_end = request.find("\r\n\r\n").unwrap_or(request.len());
+    let mut line_start = 0;
+    while line_start < head_end {
+        let line_end = request[line_start..head_end]
+            .find("\r\n")
+            .map_or(head_end, |p| line_start + p);
+        let line = &request[line_start..line_end];
+        if let Some((name, _)) = line.split_once(':')
+            && name.eq_ignore_ascii_case("host")
+        {
+            return format!(
+                "{}{}: {}{}",
+                &request[..line_start],
+                name,
+                host,
+                &request[line_end..]
+            );
+        }
+        line_start = line_end + 2;
+    }
+    request.to_string()
+}
+
+/// FNV-1a, used for corpus file names because it is stable across builds and
+/// Rust versions (unlike `DefaultHasher`).
+fn corpus_hash(data: &str) -> u64 {
+    data.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_host_rewrites_only_the_host_line() {
+        let request = "POST / HTTP/1.1\r\nHost: a.example\r\nX-Host: keep\r\n\r\nHost: body";
+        assert_eq!(
+            normalize_host(request, "b.example"),
+            "POST / HTTP/1.1\r\nHost: b.example\r\nX-Host: keep\r\n\r\nHost: body"
+        );
+        assert_eq!(
+            normalize_host("GET / HTTP/1.1\r\n\r\n", "x"),
+            "GET / HTTP/1.1\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn corpus_hash_is_stable() {
+        assert_eq!(corpus_hash(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(corpus_hash("a"), 0xaf63_dc4c_8601_ec8c);
+    }
+}
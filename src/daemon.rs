@@ -0,0 +1,916 @@
+//! REST API daemon (`smugglex daemon --listen 127.0.0.1:8787`).
+//!
+//! A deliberately small HTTP/1.1 JSON API so scans can be driven by other
+//! systems without wrapping the CLI and parsing stdout:
+//!
+//! | Method | Path                          | Purpose                                   |
+//! |--------|-------------------------------|-------------------------------------------|
+//! | GET    | `/health`                     | Liveness and version                      |
+//! | POST   | `/scans`                      | Submit `{"targets": [...], "args": [...]}` |
+//! | GET    | `/scans`                      | List jobs and their state                 |
+//! | GET    | `/scans/{id}`                 | Job status and progress                   |
+//! | GET    | `/scans/{id}/results`         | Results envelope (`?format=sarif` for SARIF) |
+//! | POST   | `/schedules`                  | Recurring job: `{"targets", "args", "every": "24h"}` |
+//! | GET    | `/schedules`                  | List schedules                            |
+//! | GET    | `/schedules/{id}`             | Schedule with its run history             |
+//! | DELETE | `/schedules/{id}`             | Stop and remove a schedule                |
+//!
+//! `args` are ordinary scan flags (`["-c", "cl-te", "-t", "5"]`) and are
+//! validated on submit. Jobs run one at a time in submission order. When the
+//! daemon has a token, every request must carry `Authorization: Bearer <token>`.
+//!
+//! A schedule enqueues a job right away and then every `every` interval. Each
+//! finished run is recorded in the schedule's history together with the
+//! findings it reported that the previous run did not; new findings are logged
+//! and, when the schedule has a `notify_url`, POSTed there as JSON. With a
+//! state directory the schedules and their history survive restarts.
+//!
+//! A client gets [`DEFAULT_READ_TIMEOUT`] to send its request before it is
+//! answered with `408`, and only the latest [`DEFAULT_MAX_FINISHED_JOBS`]
+//! finished jobs are kept.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use futures::StreamExt;
+use futures::channel::mpsc;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::distributed::token_matches;
+use crate::error::{Result, SmugglexError};
+use crate::model::ScanResults;
+use crate::output::{build_batch_results, build_sarif};
+use crate::utils::{LogLevel, log};
+
+/// Upper bound on a request's header block, to keep a misbehaving client from
+/// growing the buffer without limit.
+const MAX_HEADER_BYTES: usize = 64 * 1024;
+
+/// Upper bound on a request body (a job submission).
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// How long a client may take to send its whole request before it is
+/// answered with `408`.
+pub const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Finished jobs kept in memory; the oldest are dropped beyond this.
+pub const DEFAULT_MAX_FINISHED_JOBS: usize = 1000;
+
+/// How often the scheduler looks for due schedules.
+const SCHEDULER_TICK: Duration = Duration::from_secs(1);
+
+/// Runs kept in a schedule's history; older entries are dropped.
+const MAX_SCHEDULE_HISTORY: usize = 100;
+
+/// File inside the state directory holding schedules and their history.
+const SCHEDULES_FILE: &str = "schedules.json";
+
+/// Boxed future returned by [`ScanRunner::scan`].
+pub type ScanFuture = Pin<Box<dyn Future<Output = ScanResults> + Send>>;
+
+/// How the daemon turns a job into scans. Implemented by the binary, which owns
+/// the CLI parsing and the per-target scan routine.
+pub trait ScanRunner: Send + Sync + 'static {
+    /// Reject invalid scan flags at submit time (answered with `400`).
+    fn validate(&self, args: &[String]) -> Result<()>;
+    /// Scan one target with the job's flags.
+    fn scan(&self, args: Vec<String>, target: String) -> ScanFuture;
+}
+
+/// Lifecycle of a submitted job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobState {
+    /// Waiting for earlier jobs to finish
+    Queued,
+    /// Currently scanning
+    Running,
+    /// All targets scanned
+    Done,
+}
+
+/// Body of `POST /scans`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct JobRequest {
+    /// Target URLs to scan.
+    pub targets: Vec<String>,
+    /// Scan flags applied to every target (same syntax as the CLI).
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+/// Status of a job as reported by `GET /scans/{id}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobStatus {
+    /// Job identifier
+    pub id: String,
+    /// Current state
+    pub state: JobState,
+    /// Scan flags the job runs with
+    pub args: Vec<String>,
+    /// Targets submitted with the job
+    pub targets: Vec<String>,
+    /// Number of targets scanned so far
+    pub completed: usize,
+    /// Number of scanned targets with at least one vulnerable check
+    pub vulnerable_targets: usize,
+    /// ISO 8601 submission time
+    pub submitted_at: String,
+    /// ISO 8601 time the job started running
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+    /// ISO 8601 time the job finished
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<String>,
+    /// Schedule that enqueued this job, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule_id: Option<String>,
+}
+
+/// Body of `POST /schedules`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScheduleRequest {
+    /// Target URLs to scan on every run.
+    pub targets: Vec<String>,
+    /// Scan flags applied to every run (same syntax as the CLI).
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Interval between runs (e.g. `30m`, `24h`, `7d`).
+    pub every: String,
+    /// URL that receives a JSON POST when a run reports new findings.
+    #[serde(default)]
+    pub notify_url: Option<String>,
+}
+
+/// One finished run of a schedule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleRun {
+    /// Job that executed the run
+    pub job_id: String,
+    /// ISO 8601 time the run finished
+    pub finished_at: String,
+    /// Number of targets scanned
+    pub scanned: usize,
+    /// Number of targets with at least one vulnerable check
+    pub vulnerable_targets: usize,
+    /// Findings (`<target> <check>`) not reported by the previous run
+    pub new_findings: Vec<String>,
+}
+
+/// A recurring job, persisted in the state directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Schedule {
+    /// Schedule identifier
+    pub id: String,
+    /// Target URLs scanned on every run
+    pub targets: Vec<String>,
+    /// Scan flags applied to every run
+    pub args: Vec<String>,
+    /// Interval as submitted (e.g. `24h`)
+    pub every: String,
+    /// Parsed interval in seconds
+    pub interval_secs: u64,
+    /// Webhook for new findings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify_url: Option<String>,
+    /// ISO 8601 creation time
+    pub created_at: String,
+    /// ISO 8601 time the next run is due
+    pub next_run_at: String,
+    /// Findings reported by the latest run, used to spot new ones
+    #[serde(default)]
+    pub last_findings: Vec<String>,
+    /// Finished runs, oldest first
+    #[serde(default)]
+    pub history: Vec<ScheduleRun>,
+}
+
+/// Longest schedule interval accepted: one year.
+pub const MAX_INTERVAL: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+
+/// Parse an interval such as `90s`, `30m`, `24h` or `7d` (a bare number is
+/// seconds), up to [`MAX_INTERVAL`].
+pub fn parse_interval(value: &str) -> Result<Duration> {
+    let value = value.trim();
+    let (digits, unit) = match value.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => value.split_at(i),
+        None => (value, "s"),
+    };
+    let amount: u64 = digits
+        .parse()
+        .map_err(|_| SmugglexError::Config(format!("invalid interval '{}'", value)))?;
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => {
+            return Err(SmugglexError::Config(format!(
+                "invalid interval unit in '{}' (use s, m, h or d)",
+                value
+            )));
+        }
+    };
+    if amount == 0 {
+        return Err(SmugglexError::Config(
+            "interval must be greater than zero".to_string(),
+        ));
+    }
+    match amount.checked_mul(multiplier) {
+        Some(secs) if secs <= MAX_INTERVAL.as_secs() => Ok(Duration::from_secs(secs)),
+        _ => Err(SmugglexError::Config(format!(
+            "interval '{}' is longer than {} days",
+            value,
+            MAX_INTERVAL.as_secs() / (24 * 60 * 60)
+        ))),
+    }
+}
+
+/// Findings in a set of results as `<target> <check>` strings, for run-to-run diffs.
+fn findings_of(results: &[ScanResults]) -> Vec<String> {
+    results
+        .iter()
+        .flat_map(|r| {
+            r.checks
+                .iter()
+                .filter(|c| c.vulnerable)
+                .map(move |c| format!("{} {}", r.target, c.check_type))
+        })
+        .collect()
+}
+
+struct Job {
+    status: JobStatus,
+    results: Vec<ScanResults>,
+}
+
+/// Shared daemon state: the job table plus the queue feeding the runner task.
+struct Daemon {
+    jobs: Mutex<HashMap<String, Job>>,
+    order: Mutex<Vec<String>>,
+    next_id: Mutex<u64>,
+    queue: mpsc::UnboundedSender<String>,
+    runner: Arc<dyn ScanRunner>,
+    token: Option<String>,
+    schedules: Mutex<Vec<Schedule>>,
+    next_schedule_id: Mutex<u64>,
+    state_dir: Option<PathBuf>,
+    read_timeout: Duration,
+    max_finished_jobs: usize,
+}
+
+impl Daemon {
+    /// Drop the oldest finished jobs beyond `max_finished_jobs`.
+    fn prune_finished_jobs(&self) {
+        let mut jobs = self.jobs.lock().unwrap_or_else(|e| e.into_inner());
+        let mut order = self.order.lock().unwrap_or_else(|e| e.into_inner());
+        let finished = |id: &String| {
+            jobs.get(id)
+                .is_some_and(|j| j.status.state == JobState::Done)
+        };
+        let mut excess = order
+            .iter()
+            .filter(|id| finished(id))
+            .count()
+            .saturating_sub(self.max_finished_jobs);
+        if excess == 0 {
+            return;
+        }
+        order.retain(|id| {
+            if excess > 0 && finished(id) {
+                excess -= 1;
+                false
+            } else {
+                true
+            }
+        });
+        jobs.retain(|id, _| order.contains(id));
+    }
+
+    /// Write schedules to the state directory (no-op without one).
+    fn persist_schedules(&self) {
+        let Some(ref dir) = self.state_dir else {
+            return;
+        };
+        let schedules = self.schedules.lock().unwrap_or_else(|e| e.into_inner());
+        let written = std::fs::create_dir_all(dir)
+            .map_err(SmugglexError::from)
+            .and_then(|()| Ok(serde_json::to_string_pretty(&*schedules)?))
+            .and_then(|json| Ok(std::fs::write(dir.join(SCHEDULES_FILE), json)?));
+        if let Err(e) = written {
+            log(
+                LogLevel::Error,
+                &format!("failed to persist schedules: {}", e),
+            );
+        }
+    }
+}
+
+/// Load persisted schedules from a state directory (empty when none exist yet).
+pub fn load_schedules(state_dir: &std::path::Path) -> Result<Vec<Schedule>> {
+    let path = state_dir.join(SCHEDULES_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(&path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+/// Settings for [`run_daemon`].
+#[derive(Debug, Clone)]
+pub struct DaemonConfig {
+    /// Address to listen on.
+    pub listen: String,
+    /// Bearer token required on every request, if set.
+    pub token: Option<String>,
+    /// Directory where schedules and their history are persisted.
+    pub state_dir: Option<PathBuf>,
+    /// Time allowed to read a request ([`DEFAULT_READ_TIMEOUT`]).
+    pub read_timeout: Duration,
+    /// Finished jobs kept before the oldest are dropped
+    /// ([`DEFAULT_MAX_FINISHED_JOBS`]).
+    pub max_finished_jobs: usize,
+}
+
+/// Bind `config.listen` and serve the API until the process is stopped.
+pub async fn run_daemon(config: DaemonConfig, runner: Arc<dyn ScanRunner>) -> Result<()> {
+    let listener = TcpListener::bind(&config.listen)
+        .await
+        .map_err(|e| SmugglexError::Io(format!("failed to listen on {}: {}", config.listen, e)))?;
+    serve_daemon(listener, config, runner).await
+}
+
+/// [`run_daemon`] over an already-bound listener (`config.listen` is ignored).
+pub async fn serve_daemon(
+    listener: TcpListener,
+    config: DaemonConfig,
+    runner: Arc<dyn ScanRunner>,
+) -> Result<()> {
+    let schedules = match config.state_dir {
+        Some(ref dir) => load_schedules(dir)?,
+        None => Vec::new(),
+    };
+    let next_schedule_id = schedules
+        .iter()
+        .filter_map(|s| s.id.strip_prefix("schedule-")?.parse::<u64>().ok())
+        .max()
+        .unwrap_or(0)
+        + 1;
+
+    let (tx, rx) = mpsc::unbounded();
+    let daemon = Arc::new(Daemon {
+        jobs: Mutex::new(HashMap::new()),
+        order: Mutex::new(Vec::new()),
+        next_id: Mutex::new(1),
+        queue: tx,
+        runner,
+        token: config.token,
+        schedules: Mutex::new(schedules),
+        next_schedule_id: Mutex::new(next_schedule_id),
+        state_dir: config.state_dir,
+        read_timeout: config.read_timeout,
+        max_finished_jobs: config.max_finished_jobs,
+    });
+    log(
+        LogLevel::Info,
+        &format!("daemon listening on http://{}", listener.local_addr()?),
+    );
+
+    tokio::spawn(run_jobs(Arc::clone(&daemon), rx));
+    tokio::spawn(run_scheduler(Arc::clone(&daemon)));
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let daemon = Arc::clone(&daemon);
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, &daemon).await {
+                log(LogLevel::Warning, &format!("api request failed: {}", e));
+            }
+        });
+    }
+}
+
+/// Process queued jobs one at a time, in submission order.
+async fn run_jobs(daemon: Arc<Daemon>, mut rx: mpsc::UnboundedReceiver<String>) {
+    while let Some(id) = rx.next().await {
+        let (args, targets) = {
+            let mut jobs = daemon.jobs.lock().unwrap_or_else(|e| e.into_inner());
+            let Some(job) = jobs.get_mut(&id) else {
+                continue;
+            };
+            job.status.state = JobState::Running;
+            job.status.started_at = Some(Utc::now().to_rfc3339());
+            (job.status.args.clone(), job.status.targets.clone())
+        };
+        log(LogLevel::Info, &format!("job {} started", id));
+
+        for target in targets {
+            let result = daemon.runner.scan(args.clone(), target).await;
+            let mut jobs = daemon.jobs.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(job) = jobs.get_mut(&id) {
+                job.status.completed += 1;
+                if result.checks.iter().any(|c| c.vulnerable) {
+                    job.status.vulnerable_targets += 1;
+                }
+                job.results.push(result);
+            }
+        }
+
+        let finished = {
+            let mut jobs = daemon.jobs.lock().unwrap_or_else(|e| e.into_inner());
+            jobs.get_mut(&id).map(|job| {
+                job.status.state = JobState::Done;
+                job.status.finished_at = Some(Utc::now().to_rfc3339());
+                (job.status.clone(), findings_of(&job.results))
+            })
+        };
+        log(LogLevel::Info, &format!("job {} finished", id));
+        daemon.prune_finished_jobs();
+
+        if let Some((status, findings)) = finished
+            && let Some(ref schedule_id) = status.schedule_id
+        {
+            record_schedule_run(&daemon, schedule_id, &status, findings).await;
+        }
+    }
+}
+
+/// Append a finished run to its schedule's history and report new findings.
+async fn record_schedule_run(
+    daemon: &Daemon,
+    schedule_id: &str,
+    status: &JobStatus,
+    findings: Vec<String>,
+) {
+    let notify = {
+        let mut schedules = daemon.schedules.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(schedule) = schedules.iter_mut().find(|s| s.id == schedule_id) else {
+            return;
+        };
+        let new_findings: Vec<String> = findings
+            .iter()
+            .filter(|f| !schedule.last_findings.contains(f))
+            .cloned()
+            .collect();
+        schedule.history.push(ScheduleRun {
+            job_id: status.id.clone(),
+            finished_at: status.finished_at.clone().unwrap_or_default(),
+            scanned: status.completed,
+            vulnerable_targets: status.vulnerable_targets,
+            new_findings: new_findings.clone(),
+        });
+        if schedule.history.len() > MAX_SCHEDULE_HISTORY {
+            let excess = schedule.history.len() - MAX_SCHEDULE_HISTORY;
+            schedule.history.drain(..excess);
+        }
+        schedule.last_findings = findings;
+        (!new_findings.is_empty()).then(|| (schedule.notify_url.clone(), new_findings))
+    };
+    daemon.persist_schedules();
+
+    let Some((notify_url, new_findings)) = notify else {
+        return;
+    };
+    log(
+        LogLevel::Warning,
+        &format!(
+            "schedule {} reported {} new finding(s): {}",
+            schedule_id,
+            new_findings.len(),
+            new_findings.join(", ")
+        ),
+    );
+    if let Some(url) = notify_url {
+        let payload = serde_json::json!({
+            "schedule": schedule_id,
+            "job": status.id,
+            "new_findings": new_findings,
+        });
+        if let Err(e) = post_notification(&url, &payload).await {
+            log(LogLevel::Error, &format!("failed to notify {}: {}", url, e));
+        }
+    }
+}
+
+/// Parse a schedule's `notify_url`: an `http` or `https` URL with a host.
+pub fn parse_notify_url(value: &str) -> Result<url::Url> {
+    let url = url::Url::parse(value)
+        .map_err(|e| SmugglexError::Config(format!("invalid notify_url '{}': {}", value, e)))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(SmugglexError::Config(format!(
+            "notify_url '{}' must be an http or https URL",
+            value
+        )));
+    }
+    if url.host_str().is_none_or(str::is_empty) {
+        return Err(SmugglexError::Config(format!(
+            "notify_url '{}' has no host",
+            value
+        )));
+    }
+    Ok(url)
+}
+
+/// POST a JSON notification to a webhook URL.
+async fn post_notification(url: &str, payload: &serde_json::Value) -> Result<()> {
+    let url = parse_notify_url(url)?;
+    let host = url.host_str().unwrap_or_default();
+    let port = url
+        .port_or_known_default()
+        .ok_or_else(|| SmugglexError::Config("notify URL has no port".to_string()))?;
+    let path = match url.query() {
+        Some(q) => format!("{}?{}", url.path(), q),
+        None => url.path().to_string(),
+    };
+    let body = serde_json::to_string(payload)?;
+    let request = format!(
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        path,
+        host,
+        body.len(),
+        body
+    );
+    crate::http::send_request(host, port, &request, 10, false, url.scheme() == "https").await?;
+    Ok(())
+}
+
+/// Enqueue a job for every schedule whose next run is due, forever.
+async fn run_scheduler(daemon: Arc<Daemon>) {
+    loop {
+        let now = Utc::now();
+        let due: Vec<(String, Vec<String>, Vec<String>)> = {
+            let mut schedules = daemon.schedules.lock().unwrap_or_else(|e| e.into_inner());
+            schedules
+                .iter_mut()
+                .filter(|s| {
+                    DateTime::parse_from_rfc3339(&s.next_run_at)
+                        .map(|t| t.with_timezone(&Utc) <= now)
+                        .unwrap_or(true)
+                })
+                .map(|s| {
+                    // A schedule persisted before intervals were capped
+                    // may not fit; it runs again after the longest one.
+                    let next = i64::try_from(s.interval_secs)
+                        .ok()
+                        .and_then(chrono::Duration::try_seconds)
+                        .and_then(|interval| now.checked_add_signed(interval))
+                        .unwrap_or_else(|| now + MAX_INTERVAL);
+                    s.next_run_at = next.to_rfc3339();
+                    (s.id.clone(), s.targets.clone(), s.args.clone())
+                })
+                .collect()
+        };
+        if !due.is_empty() {
+            daemon.persist_schedules();
+        }
+        for (schedule_id, targets, args) in due {
+            let job = enqueue_job(&daemon, targets, args, Some(schedule_id.clone()));
+            log(
+                LogLevel::Info,
+                &format!("schedule {} enqueued {}", schedule_id, job.id),
+            );
+        }
+        tokio::time::sleep(SCHEDULER_TICK).await;
+    }
+}
+
+/// A parsed API request.
+struct ApiRequest {
+    method: String,
+    path: String,
+    query: Option<String>,
+    authorization: Option<String>,
+    body: Vec<u8>,
+}
+
+/// Read one HTTP/1.1 request (headers plus a `Content-Length` body).
+async fn read_request(stream: &mut TcpStream) -> Result<ApiRequest> {
+    let mut buf = Vec::new();
+    let mut tmp = [0u8; 4096];
+    let header_end = loop {
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+        if buf.len() > MAX_HEADER_BYTES {
+            return Err(SmugglexError::Config(
+                "request headers too large".to_string(),
+            ));
+        }
+        let n = stream.read(&mut tmp).await?;
+        if n == 0 {
+            return Err(SmugglexError::Config(
+                "connection closed mid-request".to_string(),
+            ));
+        }
+        buf.extend_from_slice(&tmp[..n]);
+    };
+
+    let head = String::from_utf8_lossy(&buf[..header_end]).into_owned();
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or("").split_whitespace();
+    let method = request_line.next().unwrap_or("").to_string();
+    let target = request_line.next().unwrap_or("/");
+    let (path, query) = match target.split_once('?') {
+        Some((p, q)) => (p.to_string(), Some(q.to_string())),
+        None => (target.to_string(), None),
+    };
+
+    let mut content_length = 0usize;
+    let mut authorization = None;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        if name.trim().eq_ignore_ascii_case("content-length") {
+            content_length = value
+                .trim()
+                .parse()
+                .map_err(|_| SmugglexError::Config("invalid Content-Length".to_string()))?;
+        } else if name.trim().eq_ignore_ascii_case("authorization") {
+            authorization = Some(value.trim().to_string());
+        }
+    }
+    if content_length > MAX_BODY_BYTES {
+        return Err(SmugglexError::Config("request body too large".to_string()));
+    }
+
+    let mut body = buf[header_end + 4..].to_vec();
+    while body.len() < content_length {
+        let n = stream.read(&mut tmp).await?;
+        if n == 0 {
+            break;
+        }
+        body.extend_from_slice(&tmp[..n]);
+    }
+    body.truncate(content_length);
+
+    Ok(ApiRequest {
+        method,
+        path,
+        query,
+        authorization,
+        body,
+    })
+}
+
+/// Write a JSON response and close the connection.
+async fn write_json(stream: &mut TcpStream, status: u16, body: &serde_json::Value) -> Result<()> {
+    let reason = match status {
+        200 => "OK",
+        201 => "Created",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        _ => "Internal Server Error",
+    };
+    let payload = serde_json::to_string_pretty(body)?;
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        payload.len(),
+        payload
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+fn error_body(message: &str) -> serde_json::Value {
+    serde_json::json!({ "error": message })
+}
+
+async fn handle_connection(mut stream: TcpStream, daemon: &Daemon) -> Result<()> {
+    let request = match tokio::time::timeout(daemon.read_timeout, read_request(&mut stream)).await {
+        Ok(Ok(r)) => r,
+        Ok(Err(e)) => return write_json(&mut stream, 400, &error_body(&e.to_string())).await,
+        Err(_) => {
+            let message = format!(
+                "request not received within {}s",
+                daemon.read_timeout.as_secs_f64()
+            );
+            return write_json(&mut stream, 408, &error_body(&message)).await;
+        }
+    };
+    let (status, body) = route(&request, daemon);
+    write_json(&mut stream, status, &body).await
+}
+
+/// Dispatch a request to its handler, returning status code and JSON body.
+fn route(request: &ApiRequest, daemon: &Daemon) -> (u16, serde_json::Value) {
+    if let Some(ref token) = daemon.token {
+        let expected = format!("Bearer {}", token);
+        let presented = request.authorization.as_deref().unwrap_or_default();
+        if !token_matches(&expected, presented) {
+            return (401, error_body("missing or invalid bearer token"));
+        }
+    }
+
+    let segments: Vec<&str> = request
+        .path
+        .trim_matches('/')
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect();
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["health"]) => (
+            200,
+            serde_json::json!({ "status": "ok", "version": env!("CARGO_PKG_VERSION") }),
+        ),
+        ("POST", ["scans"]) => submit_job(request, daemon),
+        ("GET", ["scans"]) => {
+            let jobs = daemon.jobs.lock().unwrap_or_else(|e| e.into_inner());
+            let order = daemon.order.lock().unwrap_or_else(|e| e.into_inner());
+            let list: Vec<&JobStatus> = order
+                .iter()
+                .filter_map(|id| jobs.get(id).map(|j| &j.status))
+                .collect();
+            (200, serde_json::json!({ "jobs": list }))
+        }
+        ("GET", ["scans", id]) => {
+            let jobs = daemon.jobs.lock().unwrap_or_else(|e| e.into_inner());
+            match jobs.get(*id) {
+                Some(job) => (200, serde_json::to_value(&job.status).unwrap_or_default()),
+                None => (404, error_body("unknown job")),
+            }
+        }
+        ("GET", ["scans", id, "results"]) => {
+            let jobs = daemon.jobs.lock().unwrap_or_else(|e| e.into_inner());
+            let Some(job) = jobs.get(*id) else {
+                return (404, error_body("unknown job"));
+            };
+            let sarif = request
+                .query
+                .as_deref()
+                .is_some_and(|q| q.split('&').any(|kv| kv == "format=sarif"));
+            if sarif {
+                (200, build_sarif(&job.results, env!("CARGO_PKG_VERSION")))
+            } else {
+                let batch =
+                    build_batch_results(job.results.clone(), Some(env!("CARGO_PKG_VERSION")));
+                let mut value = serde_json::to_value(&batch).unwrap_or_default();
+                value["state"] = serde_json::to_value(job.status.state).unwrap_or_default();
+                (200, value)
+            }
+        }
+        ("POST", ["schedules"]) => create_schedule(request, daemon),
+        ("GET", ["schedules"]) => {
+            let schedules = daemon.schedules.lock().unwrap_or_else(|e| e.into_inner());
+            (200, serde_json::json!({ "schedules": *schedules }))
+        }
+        ("GET", ["schedules", id]) => {
+            let schedules = daemon.schedules.lock().unwrap_or_else(|e| e.into_inner());
+            match schedules.iter().find(|s| s.id == *id) {
+                Some(schedule) => (200, serde_json::to_value(schedule).unwrap_or_default()),
+                None => (404, error_body("unknown schedule")),
+            }
+        }
+        ("DELETE", ["schedules", id]) => {
+            let removed = {
+                let mut schedules = daemon.schedules.lock().unwrap_or_else(|e| e.into_inner());
+                let before = schedules.len();
+                schedules.retain(|s| s.id != *id);
+                schedules.len() != before
+            };
+            if removed {
+                daemon.persist_schedules();
+                (200, serde_json::json!({ "deleted": id }))
+            } else {
+                (404, error_body("unknown schedule"))
+            }
+        }
+        (_, ["health"])
+        | (_, ["scans"])
+        | (_, ["scans", _])
+        | (_, ["scans", _, "results"])
+        | (_, ["schedules"])
+        | (_, ["schedules", _]) => (405, error_body("method not allowed")),
+        _ => (404, error_body("not found")),
+    }
+}
+
+fn submit_job(request: &ApiRequest, daemon: &Daemon) -> (u16, serde_json::Value) {
+    let job: JobRequest = match serde_json::from_slice(&request.body) {
+        Ok(j) => j,
+        Err(e) => return (400, error_body(&format!("invalid job body: {}", e))),
+    };
+    if job.targets.is_empty() {
+        return (400, error_body("targets must not be empty"));
+    }
+    if let Err(e) = daemon.runner.validate(&job.args) {
+        return (400, error_body(&e.to_string()));
+    }
+
+    let status = enqueue_job(daemon, job.targets, job.args, None);
+    (202, serde_json::to_value(&status).unwrap_or_default())
+}
+
+/// Register a queued job and hand it to the runner task.
+fn enqueue_job(
+    daemon: &Daemon,
+    targets: Vec<String>,
+    args: Vec<String>,
+    schedule_id: Option<String>,
+) -> JobStatus {
+    let id = {
+        let mut next = daemon.next_id.lock().unwrap_or_else(|e| e.into_inner());
+        let id = format!("job-{}", *next);
+        *next += 1;
+        id
+    };
+    let status = JobStatus {
+        id: id.clone(),
+        state: JobState::Queued,
+        args,
+        targets,
+        completed: 0,
+        vulnerable_targets: 0,
+        submitted_at: Utc::now().to_rfc3339(),
+        started_at: None,
+        finished_at: None,
+        schedule_id,
+    };
+    daemon
+        .jobs
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(
+            id.clone(),
+            Job {
+                status: status.clone(),
+                results: Vec::new(),
+            },
+        );
+    daemon
+        .order
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(id.clone());
+    let _ = daemon.queue.unbounded_send(id);
+    status
+}
+
+fn create_schedule(request: &ApiRequest, daemon: &Daemon) -> (u16, serde_json::Value) {
+    let req: ScheduleRequest = match serde_json::from_slice(&request.body) {
+        Ok(r) => r,
+        Err(e) => return (400, error_body(&format!("invalid schedule body: {}", e))),
+    };
+    if req.targets.is_empty() {
+        return (400, error_body("targets must not be empty"));
+    }
+    let interval = match parse_interval(&req.every) {
+        Ok(i) => i,
+        Err(e) => return (400, error_body(&e.to_string())),
+    };
+    if let Err(e) = daemon.runner.validate(&req.args) {
+        return (400, error_body(&e.to_string()));
+    }
+    if let Some(ref url) = req.notify_url
+        && let Err(e) = parse_notify_url(url)
+    {
+        return (400, error_body(&e.to_string()));
+    }
+
+    let id = {
+        let mut next = daemon
+            .next_schedule_id
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let id = format!("schedule-{}", *next);
+        *next += 1;
+        id
+    };
+    let now = Utc::now().to_rfc3339();
+    let schedule = Schedule {
+        id,
+        targets: req.targets,
+        args: req.args,
+        every: req.every,
+        interval_secs: interval.as_secs(),
+        notify_url: req.notify_url,
+        created_at: now.clone(),
+        // The first run is due immediately.
+        next_run_at: now,
+        last_findings: Vec::new(),
+        history: Vec::new(),
+    };
+    let body = serde_json::to_value(&schedule).unwrap_or_default();
+    daemon
+        .schedules
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(schedule);
+    daemon.persist_schedules();
+    (201, body)
+}
@@ -0,0 +1,97 @@
+//! Transparent decoding of compressed responses (`Content-Encoding`).
+//!
+//! A target that compresses every response defeats body-length comparisons
+//! and regex matchers: two different pages compress to similar sizes, and
+//! nothing readable is left to match. Responses are therefore decoded as they
+//! are read and handed on as if they had been sent uncompressed. Bodies that
+//! fail to decode — cut short, or in an unknown coding — are kept as they
+//! arrived. `--no-decompress` turns decoding off.
+
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use flate2::read::{DeflateDecoder, MultiGzDecoder, ZlibDecoder};
+
+use crate::http::HttpResponse;
+
+/// Largest decoded body kept; a body inflating past it stays compressed.
+pub const MAX_DECODED_BYTES: usize = 16 * 1024 * 1024;
+
+static DECOMPRESS: AtomicBool = AtomicBool::new(true);
+
+/// Turn response decoding on or off (`--no-decompress`); on by default.
+pub fn set_decompression(enabled: bool) {
+    DECOMPRESS.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether responses are decoded.
+pub fn decompression_enabled() -> bool {
+    DECOMPRESS.load(Ordering::Relaxed)
+}
+
+/// Decode `body` compressed with `codings` (a `Content-Encoding` value,
+/// codings listed in the order they were applied). `None` for an unknown
+/// coding, a body that does not decode, or one larger than
+/// [`MAX_DECODED_BYTES`] decoded.
+pub fn decode_body(codings: &str, body: &[u8]) -> Option<Vec<u8>> {
+    let mut data = body.to_vec();
+    for coding in codings.rsplit(',').map(str::trim) {
+        data = match coding.to_ascii_lowercase().as_str() {
+            "" | "identity" => continue,
+            "gzip" | "x-gzip" => read_limited(MultiGzDecoder::new(&data[..]))?,
+            // `deflate` is meant to be zlib-wrapped, but servers sending a
+            // bare deflate stream are common.
+            "deflate" => read_limited(ZlibDecoder::new(&data[..]))
+                .or_else(|| read_limited(DeflateDecoder::new(&data[..])))?,
+            "br" => read_limited(brotli_decompressor::Decompressor::new(&data[..], 4096))?,
+            _ => return None,
+        };
+    }
+    Some(data)
+}
+
+fn read_limited(reader: impl Read) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    reader
+        .take(MAX_DECODED_BYTES as u64 + 1)
+        .read_to_end(&mut out)
+        .ok()?;
+    (out.len() <= MAX_DECODED_BYTES).then_some(out)
+}
+
+/// `raw` with a compressed body decoded, when decoding is enabled. The
+/// response is rewritten as if it had been sent uncompressed: its
+/// `Content-Encoding`, `Transfer-Encoding` and `Content-Length` headers give
+/// way to the decoded body's `Content-Length`, the trailers of a chunked body
+/// join the headers, and any bytes past the body are kept after it. A
+/// response to HEAD has no body to decode and is returned unchanged.
+pub fn decompress_response(raw: Vec<u8>) -> Vec<u8> {
+    if !decompression_enabled() {
+        return raw;
+    }
+    let response = HttpResponse::parse(&raw);
+    let Some(codings) = response.header("content-encoding") else {
+        return raw;
+    };
+    if response.body.is_empty() {
+        return raw;
+    }
+    let Some(body) = decode_body(codings, &response.body) else {
+        return raw;
+    };
+
+    let mut out = Vec::with_capacity(raw.len() + body.len());
+    out.extend_from_slice(response.status_line.as_bytes());
+    out.extend_from_slice(b"\r\n");
+    let dropped = ["content-encoding", "transfer-encoding", "content-length"];
+    for (name, value) in response.headers.iter().chain(&response.trailers) {
+        if dropped.iter().any(|d| name.eq_ignore_ascii_case(d)) {
+            continue;
+        }
+        out.extend_from_slice(format!("{}: {}\r\n", name, value).as_bytes());
+    }
+    out.extend_from_slice(format!("Content-Length: {}\r\n\r\n", body.len()).as_bytes());
+    out.extend_from_slice(&body);
+    out.extend_from_slice(&response.trailing);
+    out
+}
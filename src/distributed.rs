@@ -0,0 +1,502 @@
+//! Distributed scanning: one coordinator hands out target shards to any number
+//! of workers and aggregates their results (`smugglex serve --role ...`).
+//!
+//! The wire protocol is deliberately small — newline-delimited JSON messages
+//! over plain TCP, one [`Message`] per line:
+//!
+//! ```text
+//! worker -> coordinator   {"type":"hello","worker":"box-1","nonce":"..."}
+//! coordinator -> worker   {"type":"challenge","nonce":"...","proof":"..."}
+//! worker -> coordinator   {"type":"auth","proof":"..."}
+//! coordinator -> worker   {"type":"welcome","profile":["--checks=cl-te","--timeout=5"]}
+//! worker -> coordinator   {"type":"next"}
+//! coordinator -> worker   {"type":"shard","id":0,"targets":["https://a", ...]}
+//! worker -> coordinator   {"type":"results","id":0,"results":[ScanResults, ...]}
+//! ...
+//! coordinator -> worker   {"type":"done"}
+//! ```
+//!
+//! Both sides prove they hold the shared `--token` before anything else is
+//! sent: each answers the other's random nonce with an HMAC-SHA256 of it keyed
+//! by the token, so the token itself never crosses the wire. The channel is
+//! otherwise plaintext and unauthenticated per message; run it on a trusted
+//! network or through a tunnel.
+//! The coordinator also ships its own scan flags (the *profile*) in the welcome
+//! message, so every worker scans with exactly the same settings. A shard whose
+//! worker disconnects before returning results is put back on the queue.
+
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use futures::channel::oneshot;
+use ring::hmac;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::error::{Result, SmugglexError};
+use crate::model::ScanResults;
+use crate::utils::{LogLevel, is_machine, log};
+
+/// Longest line read from a worker before it has authenticated, so an
+/// unauthenticated peer cannot make the coordinator buffer without bound.
+pub const MAX_HELLO_LINE: u64 = 64 * 1024;
+
+/// One protocol message. Serialized as a single JSON object per line, tagged by `type`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Message {
+    /// First message from a worker: its name and a nonce for the
+    /// coordinator to prove it holds the token.
+    Hello { worker: String, nonce: String },
+    /// The coordinator's proof for the worker's nonce, and its own nonce.
+    Challenge { nonce: String, proof: String },
+    /// The worker's proof for the coordinator's nonce.
+    Auth { proof: String },
+    /// Coordinator accepted the worker; carries the scan profile to apply.
+    Welcome { profile: Vec<String> },
+    /// Worker is ready for another shard.
+    Next,
+    /// A batch of targets for the worker to scan.
+    Shard { id: usize, targets: Vec<String> },
+    /// Worker finished a shard.
+    Results {
+        id: usize,
+        results: Vec<ScanResults>,
+    },
+    /// No shards left; the worker should disconnect.
+    Done,
+    /// Protocol or authentication failure; the connection is closed afterwards.
+    Error { message: String },
+}
+
+/// Settings for [`run_coordinator`].
+#[derive(Debug, Clone)]
+pub struct CoordinatorConfig {
+    /// Address to listen on (e.g. `0.0.0.0:7878`).
+    pub listen: String,
+    /// Shared secret every worker must present.
+    pub token: String,
+    /// Number of targets per shard.
+    pub shard_size: usize,
+    /// Scan flags forwarded to every worker.
+    pub profile: Vec<String>,
+}
+
+/// Split targets into shards of at most `shard_size` entries (a size of 0 is
+/// treated as 1), preserving order.
+pub fn shard_targets(targets: &[String], shard_size: usize) -> Vec<Vec<String>> {
+    targets
+        .chunks(shard_size.max(1))
+        .map(|chunk| chunk.to_vec())
+        .collect()
+}
+
+/// Compare two secrets without short-circuiting on the first differing byte.
+pub(crate) fn token_matches(expected: &str, presented: &str) -> bool {
+    let (a, b) = (expected.as_bytes(), presented.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// A fresh random nonce, base64-encoded.
+fn new_nonce() -> Result<String> {
+    let mut nonce = [0u8; 16];
+    SystemRandom::new()
+        .fill(&mut nonce)
+        .map_err(|_| SmugglexError::Io("failed to generate a nonce".to_string()))?;
+    Ok(STANDARD.encode(nonce))
+}
+
+/// HMAC-SHA256 of `role:nonce` keyed by the shared token, base64-encoded:
+/// proof that `role` holds the token, bound to the peer's nonce.
+fn token_proof(token: &str, role: &str, nonce: &str) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, token.as_bytes());
+    STANDARD.encode(hmac::sign(&key, format!("{}:{}", role, nonce).as_bytes()))
+}
+
+/// Check a [`token_proof`] in constant time.
+fn proof_matches(token: &str, role: &str, nonce: &str, proof: &str) -> bool {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, token.as_bytes());
+    STANDARD
+        .decode(proof)
+        .is_ok_and(|tag| hmac::verify(&key, format!("{}:{}", role, nonce).as_bytes(), &tag).is_ok())
+}
+
+/// Write one message as a JSON line.
+async fn send_message<W: AsyncWrite + Unpin>(writer: &mut W, message: &Message) -> Result<()> {
+    let mut line = serde_json::to_string(message)?;
+    line.push('\n');
+    writer.write_all(line.as_bytes()).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+/// Read the next message; `None` when the peer closed the connection.
+async fn recv_message<R: AsyncRead + Unpin>(reader: &mut BufReader<R>) -> Result<Option<Message>> {
+    recv_message_limited(reader, u64::MAX).await
+}
+
+/// [`recv_message`], failing on a line longer than `limit` bytes instead of
+/// reading the rest of it.
+async fn recv_message_limited<R: AsyncRead + Unpin>(
+    reader: &mut BufReader<R>,
+    limit: u64,
+) -> Result<Option<Message>> {
+    let mut line = Vec::new();
+    let read = reader
+        .take(limit.saturating_add(1))
+        .read_until(b'\n', &mut line)
+        .await?;
+    if read == 0 {
+        return Ok(None);
+    }
+    if line.last() != Some(&b'\n') && read as u64 > limit {
+        return Err(SmugglexError::Config(format!(
+            "message longer than {} bytes",
+            limit
+        )));
+    }
+    Ok(Some(serde_json::from_slice(&line)?))
+}
+
+/// Shard bookkeeping shared by every worker connection.
+struct CoordinatorState {
+    queue: VecDeque<(usize, Vec<String>)>,
+    in_flight: HashMap<usize, Vec<String>>,
+    results: Vec<(usize, Vec<ScanResults>)>,
+    remaining: usize,
+    finished: Option<oneshot::Sender<()>>,
+}
+
+impl CoordinatorState {
+    /// Put a shard back on the queue (worker vanished before returning results).
+    fn requeue(&mut self, id: usize) {
+        if let Some(targets) = self.in_flight.remove(&id) {
+            self.queue.push_front((id, targets));
+        }
+    }
+}
+
+/// Run the coordinator until every shard has been scanned, then return the
+/// aggregated results in the original target order.
+pub async fn run_coordinator(
+    config: CoordinatorConfig,
+    targets: Vec<String>,
+) -> Result<Vec<ScanResults>> {
+    let listener = TcpListener::bind(&config.listen)
+        .await
+        .map_err(|e| SmugglexError::Io(format!("failed to listen on {}: {}", config.listen, e)))?;
+    serve_coordinator(listener, config, targets).await
+}
+
+/// [`run_coordinator`] over an already-bound listener.
+pub async fn serve_coordinator(
+    listener: TcpListener,
+    config: CoordinatorConfig,
+    targets: Vec<String>,
+) -> Result<Vec<ScanResults>> {
+    let shards = shard_targets(&targets, config.shard_size);
+    if shards.is_empty() {
+        return Ok(Vec::new());
+    }
+    if !is_machine() {
+        log(
+            LogLevel::Info,
+            &format!(
+                "coordinator listening on {} ({} targets in {} shards)",
+                listener.local_addr()?,
+                targets.len(),
+                shards.len()
+            ),
+        );
+    }
+
+    let (finished_tx, mut finished_rx) = oneshot::channel();
+    let state = Arc::new(Mutex::new(CoordinatorState {
+        remaining: shards.len(),
+        queue: shards.into_iter().enumerate().collect(),
+        in_flight: HashMap::new(),
+        results: Vec::new(),
+        finished: Some(finished_tx),
+    }));
+    let config = Arc::new(config);
+
+    loop {
+        tokio::select! {
+            _ = &mut finished_rx => break,
+            accepted = listener.accept() => {
+                let (stream, peer) = accepted?;
+                let state = Arc::clone(&state);
+                let config = Arc::clone(&config);
+                tokio::spawn(async move {
+                    if let Err(e) = handle_worker(stream, &state, &config).await
+                        && !is_machine()
+                    {
+                        log(LogLevel::Warning, &format!("worker {} dropped: {}", peer, e));
+                    }
+                });
+            }
+        }
+    }
+
+    let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+    let mut results = std::mem::take(&mut state.results);
+    results.sort_by_key(|(id, _)| *id);
+    Ok(results.into_iter().flat_map(|(_, r)| r).collect())
+}
+
+/// Serve one worker connection: authenticate, then hand out shards until the
+/// queue is empty. Any shard still assigned when the connection ends is requeued.
+async fn handle_worker(
+    stream: TcpStream,
+    state: &Mutex<CoordinatorState>,
+    config: &CoordinatorConfig,
+) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader);
+
+    let (worker, worker_nonce) = match recv_message_limited(&mut lines, MAX_HELLO_LINE).await? {
+        Some(Message::Hello { worker, nonce }) => (worker, nonce),
+        _ => {
+            return Err(SmugglexError::Config(
+                "expected hello from worker".to_string(),
+            ));
+        }
+    };
+    let nonce = new_nonce()?;
+    send_message(
+        &mut writer,
+        &Message::Challenge {
+            nonce: nonce.clone(),
+            proof: token_proof(&config.token, "coordinator", &worker_nonce),
+        },
+    )
+    .await?;
+    match recv_message_limited(&mut lines, MAX_HELLO_LINE).await? {
+        Some(Message::Auth { proof }) if proof_matches(&config.token, "worker", &nonce, &proof) => {
+        }
+        Some(Message::Auth { .. }) => {
+            send_message(
+                &mut writer,
+                &Message::Error {
+                    message: "invalid token".to_string(),
+                },
+            )
+            .await?;
+            return Err(SmugglexError::Config(
+                "worker presented an invalid token".to_string(),
+            ));
+        }
+        _ => {
+            return Err(SmugglexError::Config(format!(
+                "worker '{}' did not authenticate",
+                worker
+            )));
+        }
+    }
+    if !is_machine() {
+        log(LogLevel::Info, &format!("worker '{}' connected", worker));
+    }
+    send_message(
+        &mut writer,
+        &Message::Welcome {
+            profile: config.profile.clone(),
+        },
+    )
+    .await?;
+
+    let mut assigned: Option<usize> = None;
+    let outcome = async {
+        loop {
+            match recv_message(&mut lines).await? {
+                Some(Message::Next) => {
+                    let next = {
+                        let mut st = state.lock().unwrap_or_else(|e| e.into_inner());
+                        // A worker asking again without returning results gave
+                        // up its shard; requeue it rather than losing track.
+                        if let Some(id) = assigned.take() {
+                            st.requeue(id);
+                        }
+                        st.queue.pop_front().inspect(|(id, targets)| {
+                            st.in_flight.insert(*id, targets.clone());
+                        })
+                    };
+                    match next {
+                        Some((id, targets)) => {
+                            assigned = Some(id);
+                            send_message(&mut writer, &Message::Shard { id, targets }).await?;
+                        }
+                        None => {
+                            send_message(&mut writer, &Message::Done).await?;
+                            return Ok(());
+                        }
+                    }
+                }
+                Some(Message::Results { id, results }) => {
+                    let mut st = state.lock().unwrap_or_else(|e| e.into_inner());
+                    // Ignore results for shards this worker does not hold (e.g. a
+                    // duplicate after a requeue), so nothing is counted twice.
+                    if assigned == Some(id) && st.in_flight.remove(&id).is_some() {
+                        assigned = None;
+                        st.results.push((id, results));
+                        st.remaining -= 1;
+                        if !is_machine() {
+                            log(
+                                LogLevel::Info,
+                                &format!(
+                                    "worker '{}' finished shard {} ({} shards remaining)",
+                                    worker, id, st.remaining
+                                ),
+                            );
+                        }
+                        if st.remaining == 0
+                            && let Some(tx) = st.finished.take()
+                        {
+                            let _ = tx.send(());
+                        }
+                    }
+                }
+                None => return Ok(()),
+                Some(other) => {
+                    return Err(SmugglexError::Config(format!(
+                        "unexpected message from worker: {:?}",
+                        other
+                    )));
+                }
+            }
+        }
+    }
+    .await;
+
+    if let Some(id) = assigned {
+        state.lock().unwrap_or_else(|e| e.into_inner()).requeue(id);
+    }
+    outcome
+}
+
+/// Connect to a coordinator, authenticate, and return the scan profile it sent
+/// together with the open connection for [`run_worker_shards`].
+pub async fn connect_worker(
+    coordinator: &str,
+    token: &str,
+    worker: &str,
+) -> Result<(Vec<String>, WorkerConnection)> {
+    let stream = TcpStream::connect(coordinator).await.map_err(|e| {
+        SmugglexError::Connect(format!(
+            "failed to connect to coordinator {}: {}",
+            coordinator, e
+        ))
+    })?;
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader);
+    let nonce = new_nonce()?;
+    send_message(
+        &mut writer,
+        &Message::Hello {
+            worker: worker.to_string(),
+            nonce: nonce.clone(),
+        },
+    )
+    .await?;
+    // The coordinator proves it holds the token before the worker answers
+    // its challenge or accepts a profile from it.
+    match recv_message(&mut lines).await? {
+        Some(Message::Challenge {
+            nonce: challenge,
+            proof,
+        }) if proof_matches(token, "coordinator", &nonce, &proof) => {
+            send_message(
+                &mut writer,
+                &Message::Auth {
+                    proof: token_proof(token, "worker", &challenge),
+                },
+            )
+            .await?;
+        }
+        Some(Message::Challenge { .. }) => {
+            return Err(SmugglexError::Config(format!(
+                "coordinator {} does not hold the token",
+                coordinator
+            )));
+        }
+        other => {
+            return Err(SmugglexError::Config(format!(
+                "unexpected coordinator reply: {:?}",
+                other
+            )));
+        }
+    }
+    match recv_message(&mut lines).await? {
+        Some(Message::Welcome { profile }) => Ok((profile, WorkerConnection { lines, writer })),
+        Some(Message::Error { message }) => Err(SmugglexError::Config(format!(
+            "coordinator rejected worker: {}",
+            message
+        ))),
+        other => Err(SmugglexError::Config(format!(
+            "unexpected coordinator reply: {:?}",
+            other
+        ))),
+    }
+}
+
+/// An authenticated worker connection, returned by [`connect_worker`].
+pub struct WorkerConnection {
+    lines: BufReader<tokio::net::tcp::OwnedReadHalf>,
+    writer: tokio::net::tcp::OwnedWriteHalf,
+}
+
+impl WorkerConnection {
+    /// The authenticated connection's reader and writer, to speak the
+    /// protocol directly.
+    pub fn into_split(
+        self,
+    ) -> (
+        BufReader<tokio::net::tcp::OwnedReadHalf>,
+        tokio::net::tcp::OwnedWriteHalf,
+    ) {
+        (self.lines, self.writer)
+    }
+}
+
+/// Pull shards from the coordinator and scan each target with `scan` until the
+/// coordinator reports there is nothing left. Returns the number of targets scanned.
+pub async fn run_worker_shards<F, Fut>(mut conn: WorkerConnection, scan: F) -> Result<usize>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = ScanResults>,
+{
+    let mut scanned = 0;
+    loop {
+        send_message(&mut conn.writer, &Message::Next).await?;
+        match recv_message(&mut conn.lines).await? {
+            Some(Message::Shard { id, targets }) => {
+                let mut results = Vec::with_capacity(targets.len());
+                for target in targets {
+                    results.push(scan(target).await);
+                    scanned += 1;
+                }
+                send_message(&mut conn.writer, &Message::Results { id, results }).await?;
+            }
+            Some(Message::Done) | None => return Ok(scanned),
+            Some(Message::Error { message }) => {
+                return Err(SmugglexError::Config(format!(
+                    "coordinator error: {}",
+                    message
+                )));
+            }
+            Some(other) => {
+                return Err(SmugglexError::Config(format!(
+                    "unexpected coordinator message: {:?}",
+                    other
+                )));
+            }
+        }
+    }
+}
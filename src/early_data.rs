@@ -0,0 +1,376 @@
+//! TLS 1.3 0-RTT (early data) smuggling check.
+//!
+//! A front-end that accepts early data must decide what a replayable request
+//! may do before the handshake proves it fresh. RFC 8470 has it forward such
+//! requests with `Early-Data: 1` so the back-end can answer `425 Too Early`.
+//! When the two hops disagree about early requests — the front-end parses or
+//! routes them on a different path, or the back-end never learns they were
+//! early — a smuggling-shaped request sent as early data is answered
+//! differently from the same bytes sent after the handshake.
+//!
+//! The check primes a session ticket with a plain GET, sends a request
+//! carrying both `Content-Length` and `Transfer-Encoding` as early data on a
+//! resumed connection, and compares the answer with the same request sent
+//! over a full handshake. A mismatch that reproduces on a fresh ticket is
+//! reported. Connections go directly to the target; `--proxy` does not apply.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use rustls::pki_types::ServerName;
+use tokio::io::AsyncWriteExt;
+use tokio_rustls::TlsConnector;
+
+use crate::error::{Result, SmugglexError, TimeoutPhase};
+use crate::model::{CheckResult, Confidence};
+
+/// Name of the check, as given to `--checks`.
+pub const CHECK_NAME: &str = "early-data";
+
+/// Extra early-data attempts, each on a freshly primed ticket, that must
+/// reproduce a mismatch before it is reported.
+const EARLY_DATA_CONFIRMATION_RETRIES: usize = 1;
+
+/// One request/response over its own TLS connection.
+#[derive(Debug)]
+struct Exchange {
+    /// First line of the response
+    status: String,
+    duration: Duration,
+    tls13: bool,
+    /// The session ticket allowed early data, so the request went out as 0-RTT
+    offered: bool,
+    /// The server accepted the early data
+    accepted: bool,
+}
+
+/// How the early-data probe compared with the same request sent after a full
+/// handshake.
+#[derive(Debug, PartialEq, Eq)]
+enum Verdict {
+    /// The server did not issue a resumable ticket allowing early data
+    NotOffered,
+    /// Early data was offered and the server declined it
+    Rejected,
+    /// Early data reached a hop that deferred it with `425 Too Early`
+    TooEarly,
+    /// Early data was accepted and answered like the full-handshake request
+    Consistent,
+    /// Early data was accepted and answered differently
+    Mismatch,
+}
+
+impl Verdict {
+    /// Diagnostic recorded when the verdict is not a finding.
+    fn diagnostic(&self) -> &'static str {
+        match self {
+            Verdict::NotOffered => "early_data_not_offered",
+            Verdict::Rejected => "early_data_rejected",
+            Verdict::TooEarly => "early_data_too_early",
+            Verdict::Consistent => "early_data_accepted",
+            Verdict::Mismatch => "early_data_status_mismatch",
+        }
+    }
+}
+
+fn classify(early: &Exchange, control_status: &str) -> Verdict {
+    if !early.offered {
+        Verdict::NotOffered
+    } else if !early.accepted {
+        Verdict::Rejected
+    } else if crate::utils::parse_status_code(&early.status) == Some(425) {
+        Verdict::TooEarly
+    } else if early.status != control_status {
+        Verdict::Mismatch
+    } else {
+        Verdict::Consistent
+    }
+}
+
+/// The smuggling-shaped request sent as early data: `Content-Length` and
+/// chunked `Transfer-Encoding` that agree on an empty body, so hops that
+/// pick different framings still see a complete request.
+fn early_data_request(authority: &str, path: &str) -> String {
+    format!(
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/x-www-form-urlencoded\r\nContent-Length: 5\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\n",
+        path, authority
+    )
+}
+
+/// Send `request` on a new TLS connection built from `config` and read one
+/// response. With `early`, the request is written as 0-RTT data when the
+/// cached ticket allows it (and resent after the handshake if rejected).
+async fn exchange(
+    config: &Arc<rustls::ClientConfig>,
+    host: &str,
+    port: u16,
+    request: &[u8],
+    early: bool,
+    timeout: Duration,
+) -> Result<Exchange> {
+    crate::http::throttle().await;
+    let connector = TlsConnector::from(Arc::clone(config)).early_data(early);
+    let domain = ServerName::try_from(host.to_string())?;
+    let start = Instant::now();
+    let run = async {
+        let tcp = crate::http::connect_tcp(host, port).await?;
+        let mut tls = crate::http::tls_handshake(&connector, domain, tcp).await?;
+        let offered = early && tls.get_mut().1.early_data().is_some();
+        tls.write_all(request).await?;
+        // Completes the handshake, resending the data if it was rejected.
+        tls.flush().await?;
+        let response = crate::http::read_one_http_response(&mut tls).await?;
+        let (_, conn) = tls.get_ref();
+        Ok(Exchange {
+            status: String::from_utf8_lossy(&response)
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+            duration: start.elapsed(),
+            tls13: conn.protocol_version() == Some(rustls::ProtocolVersion::TLSv1_3),
+            offered,
+            accepted: conn.is_early_data_accepted(),
+        })
+    };
+    tokio::time::timeout(timeout, run).await.map_err(|_| {
+        SmugglexError::timeout(
+            TimeoutPhase::Request,
+            format!("no response within {:?}", timeout),
+        )
+    })?
+}
+
+/// Run the TLS 1.3 early-data check and return a [`CheckResult`]. Requires
+/// TLS; targets without TLS 1.3 or without 0-RTT tickets come back not
+/// vulnerable with a diagnostic saying why.
+pub async fn run_early_data_check(
+    host: &str,
+    port: u16,
+    authority: &str,
+    path: &str,
+    timeout: u64,
+    verbose: bool,
+) -> CheckResult {
+    let dur = Duration::from_secs(timeout);
+    let early_config = crate::http::get_early_data_tls_config();
+    let not_vulnerable =
+        |normal_status: String, normal_ms: u64, diagnostics: Vec<String>| CheckResult {
+            check_type: CHECK_NAME.to_string(),
+            vulnerable: false,
+            payload_index: None,
+            payload_name: None,
+            technique: None,
+            normal_status,
+            attack_status: None,
+            normal_duration_ms: normal_ms,
+            attack_duration_ms: None,
+            normal_connect_ms: None,
+            attack_connect_ms: None,
+            timestamp: Utc::now().to_rfc3339(),
+            payload: None,
+            confidence: None,
+            detection_signals: Vec::new(),
+            diagnostics,
+            payloads_sent: 0,
+            request_errors: Default::default(),
+            h2_observations: Vec::new(),
+            fuzz_anomalies: Vec::new(),
+            unsolicited_bytes: None,
+            attack_phases: None,
+            cross_check: None,
+            evidence: None,
+            consistency: None,
+        };
+
+    // A full handshake with a plain GET; reading its response takes in the
+    // session tickets the early-data attempt resumes from.
+    let prime = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+        path, authority
+    );
+    let primed = match exchange(early_config, host, port, prime.as_bytes(), false, dur).await {
+        Ok(primed) => primed,
+        Err(e) => {
+            return not_vulnerable(
+                "no TLS response".to_string(),
+                0,
+                vec![format!("early_data_prime_failed: {}", e)],
+            );
+        }
+    };
+    if !primed.tls13 {
+        return not_vulnerable(
+            primed.status,
+            primed.duration.as_millis() as u64,
+            vec!["early_data_requires_tls13".to_string()],
+        );
+    }
+
+    let request = early_data_request(authority, path);
+    let control = match exchange(
+        crate::http::get_tls_config(),
+        host,
+        port,
+        request.as_bytes(),
+        false,
+        dur,
+    )
+    .await
+    {
+        Ok(control) => control,
+        Err(e) => {
+            return not_vulnerable(
+                primed.status,
+                primed.duration.as_millis() as u64,
+                vec![format!("early_data_control_failed: {}", e)],
+            );
+        }
+    };
+    let normal_ms = control.duration.as_millis() as u64;
+
+    let early = match exchange(early_config, host, port, request.as_bytes(), true, dur).await {
+        Ok(early) => early,
+        Err(e) => {
+            return not_vulnerable(
+                control.status,
+                normal_ms,
+                vec![format!("early_data_failed: {}", e)],
+            );
+        }
+    };
+    let verdict = classify(&early, &control.status);
+    if verbose {
+        println!(
+            "  [*] {}: 0-RTT {} ({}), full handshake {}",
+            CHECK_NAME,
+            early.status,
+            verdict.diagnostic(),
+            control.status
+        );
+    }
+    if verdict != Verdict::Mismatch {
+        return not_vulnerable(
+            control.status,
+            normal_ms,
+            vec![verdict.diagnostic().to_string()],
+        );
+    }
+
+    // Each attempt spends a ticket, so prime a fresh one before retrying.
+    for _ in 0..EARLY_DATA_CONFIRMATION_RETRIES {
+        let retry = match exchange(early_config, host, port, prime.as_bytes(), false, dur).await {
+            Ok(_) => exchange(early_config, host, port, request.as_bytes(), true, dur).await,
+            Err(e) => Err(e),
+        };
+        let reproduced = retry
+            .as_ref()
+            .is_ok_and(|retry| classify(retry, &control.status) == Verdict::Mismatch);
+        if !reproduced {
+            if verbose {
+                println!(
+                    "  [*] {}: mismatch not reproduced on a fresh ticket, rejecting",
+                    CHECK_NAME
+                );
+            }
+            return not_vulnerable(
+                control.status,
+                normal_ms,
+                vec!["early_data_mismatch_not_reproduced".to_string()],
+            );
+        }
+    }
+
+    CheckResult {
+        check_type: CHECK_NAME.to_string(),
+        vulnerable: true,
+        payload_index: Some(0),
+        payload_name: Some("early-data.cl-te".to_string()),
+        technique: None,
+        normal_status: control.status,
+        attack_status: Some(early.status),
+        normal_duration_ms: normal_ms,
+        attack_duration_ms: Some(early.duration.as_millis() as u64),
+        normal_connect_ms: None,
+        attack_connect_ms: None,
+        timestamp: Utc::now().to_rfc3339(),
+        payload: Some(request),
+        confidence: Some(Confidence::Medium),
+        detection_signals: vec![
+            "early_data_accepted".to_string(),
+            "early_data_status_mismatch".to_string(),
+        ],
+        diagnostics: Vec::new(),
+        payloads_sent: 0,
+        request_errors: Default::default(),
+        h2_observations: Vec::new(),
+        fuzz_anomalies: Vec::new(),
+        unsolicited_bytes: None,
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn early(status: &str, offered: bool, accepted: bool) -> Exchange {
+        Exchange {
+            status: status.to_string(),
+            duration: Duration::from_millis(100),
+            tls13: true,
+            offered,
+            accepted,
+        }
+    }
+
+    #[test]
+    fn classify_requires_accepted_early_data() {
+        let ok = "HTTP/1.1 200 OK";
+        assert_eq!(classify(&early(ok, false, false), ok), Verdict::NotOffered);
+        assert_eq!(
+            classify(&early("HTTP/1.1 400 Bad Request", true, false), ok),
+            Verdict::Rejected
+        );
+    }
+
+    #[test]
+    fn classify_compares_with_full_handshake() {
+        let ok = "HTTP/1.1 200 OK";
+        assert_eq!(
+            classify(&early("HTTP/1.1 425 Too Early", true, true), ok),
+            Verdict::TooEarly
+        );
+        assert_eq!(classify(&early(ok, true, true), ok), Verdict::Consistent);
+        assert_eq!(
+            classify(&early(ok, true, true), "HTTP/1.1 400 Bad Request"),
+            Verdict::Mismatch
+        );
+    }
+
+    #[test]
+    fn early_data_request_frames_agree() {
+        let request = early_data_request("example.com", "/api");
+        assert!(request.starts_with("POST /api HTTP/1.1\r\nHost: example.com\r\n"));
+        let body = request.split_once("\r\n\r\n").unwrap().1;
+        assert_eq!(body, "0\r\n\r\n");
+        assert!(request.contains(&format!("Content-Length: {}\r\n", body.len())));
+    }
+
+    #[tokio::test]
+    async fn plain_tcp_target_fails_priming() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let _ = socket.write_all(b"HTTP/1.1 200 OK\r\n\r\n").await;
+        });
+        let result = run_early_data_check("127.0.0.1", port, "127.0.0.1", "/", 2, false).await;
+        assert!(!result.vulnerable);
+        assert!(result.diagnostics[0].starts_with("early_data_prime_failed"));
+    }
+}
@@ -0,0 +1,103 @@
+//! `HTTP_PROXY` / `HTTPS_PROXY` / `NO_PROXY` from the environment.
+//!
+//! Used when no `--proxy` is given, unless `--no-env-proxy` opts out. As in
+//! curl, the lowercase variable wins over the uppercase one and `ALL_PROXY`
+//! covers either scheme when the specific variable is unset.
+
+/// Proxy settings read from the environment.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnvProxy {
+    /// Proxy for `http://` targets
+    pub http: Option<String>,
+    /// Proxy for `https://` targets
+    pub https: Option<String>,
+    /// `NO_PROXY` entries, lowercased
+    pub no_proxy: Vec<String>,
+}
+
+impl EnvProxy {
+    /// Read the process environment.
+    pub fn from_env() -> Self {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// Build from `lookup` (variable name → value); empty values count as
+    /// unset.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let var = |name: &str| {
+            [name.to_ascii_lowercase(), name.to_string()]
+                .iter()
+                .filter_map(|n| lookup(n))
+                .map(|v| v.trim().to_string())
+                .find(|v| !v.is_empty())
+        };
+        let all = var("ALL_PROXY");
+        Self {
+            http: var("HTTP_PROXY").or_else(|| all.clone()).map(normalize),
+            https: var("HTTPS_PROXY").or(all).map(normalize),
+            no_proxy: var("NO_PROXY")
+                .map(|v| {
+                    v.split(',')
+                        .map(|e| e.trim().to_ascii_lowercase())
+                        .filter(|e| !e.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Whether no proxy variable is set.
+    pub fn is_empty(&self) -> bool {
+        self.http.is_none() && self.https.is_none()
+    }
+
+    /// Proxy URL for a connection to `host`, or `None` when the scheme has no
+    /// proxy or `NO_PROXY` excludes the host.
+    pub fn proxy_for(&self, host: &str, use_tls: bool) -> Option<&str> {
+        let proxy = if use_tls { &self.https } else { &self.http };
+        let proxy = proxy.as_deref()?;
+        (!self.bypasses(host)).then_some(proxy)
+    }
+
+    /// Whether `NO_PROXY` excludes `host`: `*` matches everything, other
+    /// entries match the host and its subdomains (a leading `.` or `*.` is
+    /// ignored, as is a `:port` suffix).
+    pub fn bypasses(&self, host: &str) -> bool {
+        let host = host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_ascii_lowercase();
+        self.no_proxy.iter().any(|entry| {
+            if entry == "*" {
+                return true;
+            }
+            let entry = strip_port(entry.trim_start_matches("*.").trim_start_matches('.'));
+            host == entry
+                || host
+                    .strip_suffix(entry)
+                    .is_some_and(|rest| rest.ends_with('.'))
+        })
+    }
+}
+
+/// `host:port` → `host`, `[::1]:8080` → `::1`; a bare IPv6 address is kept.
+fn strip_port(entry: &str) -> &str {
+    if let Some(inner) = entry.strip_prefix('[') {
+        return inner.split(']').next().unwrap_or(inner);
+    }
+    match entry.rsplit_once(':') {
+        Some((name, port)) if !name.contains(':') && port.bytes().all(|b| b.is_ascii_digit()) => {
+            name
+        }
+        _ => entry,
+    }
+}
+
+/// `proxy:3128` → `http://proxy:3128`.
+fn normalize(proxy: String) -> String {
+    if proxy.contains("://") {
+        proxy
+    } else {
+        format!("http://{}", proxy)
+    }
+}
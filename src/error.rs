@@ -1,32 +1,79 @@
+use crate::model::{ErrorInfo, RequestErrorKind, Unreachable};
+use serde::{Deserialize, Serialize};
 use std::error::Error;
 use std::fmt;
 
-/// Custom error type for the smugglex application
+/// Custom error type for the smugglex application. Errors are typed by where
+/// they arose, so callers can decide what to retry without reading messages.
 #[derive(Debug)]
 pub enum SmugglexError {
-    /// HTTP request related errors
-    HttpRequest(String),
+    /// The target host name did not resolve
+    Dns(String),
+    /// The TCP connection, or the CONNECT through a proxy, failed
+    Connect(String),
     /// TLS connection errors
     Tls(String),
-    /// URL parsing errors
-    UrlParse(String),
-    /// I/O errors (file operations)
+    /// No answer within the timeout; `phase` is where the exchange stalled
+    Timeout {
+        phase: TimeoutPhase,
+        message: String,
+    },
+    /// I/O errors: a connection reset or closed mid-exchange, file operations
     Io(String),
-    /// JSON serialization/deserialization errors
-    Json(String),
-    /// Timeout errors
-    Timeout(String),
-    /// Invalid input parameters
-    InvalidInput(String),
+    /// Input that does not parse: URLs, JSON, messages from a peer
+    Parse(String),
+    /// Invalid options, arguments or files given by the user
+    Config(String),
+    /// The target kept failing after an error storm cool-down
+    TargetDestabilized(String),
+    /// The connection failed once request bytes had gone out (reset or
+    /// closed mid-exchange). The target may have acted on them, so the
+    /// request is never re-sent; kind and message are the wrapped error's
+    AfterWrite(Box<SmugglexError>),
+}
+
+/// Part of an exchange a [`SmugglexError::Timeout`] stalled in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TimeoutPhase {
+    /// Opening the connection (TCP, proxy CONNECT, TLS handshake)
+    Connect,
+    /// Sending the request and waiting for the first response byte
+    FirstByte,
+    /// Receiving the rest of the response
+    Transfer,
+    /// Anywhere in the exchange; the phase was not tracked
+    Request,
+}
+
+impl TimeoutPhase {
+    /// Name used in JSON and plain output.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TimeoutPhase::Connect => "connect",
+            TimeoutPhase::FirstByte => "first-byte",
+            TimeoutPhase::Transfer => "transfer",
+            TimeoutPhase::Request => "request",
+        }
+    }
+}
+
+impl fmt::Display for TimeoutPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 impl fmt::Display for SmugglexError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            SmugglexError::HttpRequest(msg) => {
+            SmugglexError::Dns(msg) => {
+                write!(f, "DNS error: {} (check that the host name resolves)", msg)
+            }
+            SmugglexError::Connect(msg) => {
                 write!(
                     f,
-                    "HTTP request error: {} (check target connectivity and verify the URL is reachable)",
+                    "Connection error: {} (check target connectivity and verify the URL is reachable)",
                     msg
                 )
             }
@@ -37,48 +84,175 @@ impl fmt::Display for SmugglexError {
                     msg
                 )
             }
-            SmugglexError::UrlParse(msg) => {
+            SmugglexError::Timeout { phase, message } => {
                 write!(
                     f,
-                    "URL parsing error: {} (ensure the URL includes scheme, e.g. http:// or https://)",
-                    msg
+                    "Timeout ({}): {} (try increasing timeout with -t option)",
+                    phase, message
                 )
             }
             SmugglexError::Io(msg) => write!(f, "I/O error: {}", msg),
-            SmugglexError::Json(msg) => write!(f, "JSON error: {}", msg),
-            SmugglexError::Timeout(msg) => {
-                write!(
-                    f,
-                    "Timeout: {} (try increasing timeout with -t option)",
-                    msg
-                )
-            }
-            SmugglexError::InvalidInput(msg) => write!(f, "Invalid input: {}", msg),
+            SmugglexError::Parse(msg) => write!(f, "Parse error: {}", msg),
+            SmugglexError::Config(msg) => write!(f, "Invalid input: {}", msg),
+            SmugglexError::TargetDestabilized(msg) => write!(f, "target destabilized: {}", msg),
+            SmugglexError::AfterWrite(err) => err.fmt(f),
         }
     }
 }
 
 impl Error for SmugglexError {}
 
+impl SmugglexError {
+    /// A timeout in `phase`.
+    pub fn timeout(phase: TimeoutPhase, message: impl Into<String>) -> Self {
+        SmugglexError::Timeout {
+            phase,
+            message: message.into(),
+        }
+    }
+
+    /// Mark a failure as having happened after request bytes were written.
+    /// Timeouts are left as they are: their phase already tells.
+    pub fn after_write(self) -> Self {
+        match self {
+            SmugglexError::Timeout { .. } | SmugglexError::AfterWrite(_) => self,
+            other => SmugglexError::AfterWrite(Box::new(other)),
+        }
+    }
+
+    /// Whether the request had been (partly) written when this failed.
+    pub fn request_written(&self) -> bool {
+        matches!(self, SmugglexError::AfterWrite(_))
+    }
+
+    /// Name of the error's kind, used in JSON output.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SmugglexError::Dns(_) => "dns",
+            SmugglexError::Connect(_) => "connect",
+            SmugglexError::Tls(_) => "tls",
+            SmugglexError::Timeout { .. } => "timeout",
+            SmugglexError::Io(_) => "io",
+            SmugglexError::Parse(_) => "parse",
+            SmugglexError::Config(_) => "config",
+            SmugglexError::TargetDestabilized(_) => "target-destabilized",
+            SmugglexError::AfterWrite(err) => err.kind(),
+        }
+    }
+
+    /// Whether sending the same request again may succeed: the connection
+    /// could not be opened (other than refused), or was reset before any of
+    /// the request was written. Once request bytes went out nothing is
+    /// retried — a smuggling payload sent twice may poison another user's
+    /// request, and a reset after it is a signal to record. A response that
+    /// stalls is not retried either — a delay is what timing detection
+    /// measures — and neither are DNS, TLS, parse and configuration errors,
+    /// which repeat.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            // Nothing listening on the port will not change on a retry.
+            SmugglexError::Connect(_) => {
+                self.request_error_kind() != Some(RequestErrorKind::ConnectRefused)
+            }
+            SmugglexError::Timeout { phase, .. } => *phase == TimeoutPhase::Connect,
+            SmugglexError::Io(_) => self.request_error_kind() == Some(RequestErrorKind::Reset),
+            SmugglexError::Dns(_)
+            | SmugglexError::Tls(_)
+            | SmugglexError::Parse(_)
+            | SmugglexError::Config(_)
+            | SmugglexError::TargetDestabilized(_)
+            | SmugglexError::AfterWrite(_) => false,
+        }
+    }
+
+    /// Kind, timeout phase and retryability, for JSON output.
+    pub fn info(&self) -> ErrorInfo {
+        ErrorInfo {
+            kind: self.kind().to_string(),
+            phase: match self {
+                SmugglexError::Timeout { phase, .. } => Some(*phase),
+                _ => None,
+            },
+            retryable: self.is_retryable(),
+        }
+    }
+
+    /// Classify a failed request for reporting. `None` for errors that are not
+    /// about the network exchange (bad input, parse errors).
+    pub fn request_error_kind(&self) -> Option<RequestErrorKind> {
+        match self {
+            SmugglexError::Dns(_) => Some(RequestErrorKind::Dns),
+            SmugglexError::Timeout { .. } => Some(RequestErrorKind::Timeout),
+            SmugglexError::Tls(_) => Some(RequestErrorKind::TlsHandshake),
+            SmugglexError::Connect(msg) | SmugglexError::Io(msg) => {
+                let msg = msg.to_ascii_lowercase();
+                let kind = if msg.contains("failed to lookup address")
+                    || msg.contains("name or service not known")
+                    || msg.contains("nodename nor servname")
+                    || msg.contains("no such host")
+                {
+                    RequestErrorKind::Dns
+                } else if msg.contains("connection refused") {
+                    RequestErrorKind::ConnectRefused
+                } else if msg.contains("connection reset")
+                    || msg.contains("broken pipe")
+                    || msg.contains("connection aborted")
+                    || msg.contains("early eof")
+                    || msg.contains("close_notify")
+                {
+                    RequestErrorKind::Reset
+                } else if msg.contains("timed out") {
+                    RequestErrorKind::Timeout
+                } else {
+                    RequestErrorKind::Other
+                };
+                Some(kind)
+            }
+            SmugglexError::Parse(_)
+            | SmugglexError::Config(_)
+            | SmugglexError::TargetDestabilized(_) => None,
+            SmugglexError::AfterWrite(err) => err.request_error_kind(),
+        }
+    }
+}
+
+impl SmugglexError {
+    /// Category of a failed reachability preflight.
+    pub fn unreachable_kind(&self) -> Unreachable {
+        match self.request_error_kind() {
+            Some(RequestErrorKind::Dns) => Unreachable::DnsFailure,
+            Some(RequestErrorKind::ConnectRefused) => Unreachable::Refused,
+            Some(RequestErrorKind::TlsHandshake) => Unreachable::TlsError,
+            Some(RequestErrorKind::Timeout) => Unreachable::Timeout,
+            _ => Unreachable::HttpError,
+        }
+    }
+}
+
 impl From<std::io::Error> for SmugglexError {
     fn from(err: std::io::Error) -> Self {
-        if err.kind() == std::io::ErrorKind::TimedOut {
-            SmugglexError::Timeout(err.to_string())
-        } else {
-            SmugglexError::Io(err.to_string())
+        match err.kind() {
+            std::io::ErrorKind::TimedOut => {
+                SmugglexError::timeout(TimeoutPhase::Request, err.to_string())
+            }
+            std::io::ErrorKind::ConnectionRefused => SmugglexError::Connect(err.to_string()),
+            _ => SmugglexError::Io(err.to_string()),
         }
     }
 }
 
 impl From<serde_json::Error> for SmugglexError {
     fn from(err: serde_json::Error) -> Self {
-        SmugglexError::Json(err.to_string())
+        SmugglexError::Parse(format!("invalid JSON: {}", err))
     }
 }
 
 impl From<url::ParseError> for SmugglexError {
     fn from(err: url::ParseError) -> Self {
-        SmugglexError::UrlParse(err.to_string())
+        SmugglexError::Parse(format!(
+            "invalid URL: {} (ensure the URL includes scheme, e.g. http:// or https://)",
+            err
+        ))
     }
 }
 
@@ -96,13 +270,13 @@ impl From<rustls::pki_types::InvalidDnsNameError> for SmugglexError {
 
 impl From<tokio::time::error::Elapsed> for SmugglexError {
     fn from(_err: tokio::time::error::Elapsed) -> Self {
-        SmugglexError::Timeout("Request timed out".to_string())
+        SmugglexError::timeout(TimeoutPhase::Request, "Request timed out")
     }
 }
 
 impl From<&str> for SmugglexError {
     fn from(err: &str) -> Self {
-        SmugglexError::InvalidInput(err.to_string())
+        SmugglexError::Config(err.to_string())
     }
 }
 
@@ -0,0 +1,108 @@
+//! `--auto-exploit`: choose one demonstrative exploit for a confirmed finding.
+//!
+//! Triage at scale needs proof of impact without an operator picking an
+//! exploit per target, and without harming other users of the target. The
+//! choice favours exploits whose effects stay on our own connections: behind a
+//! cache, a smuggled request's response could be stored and served to others,
+//! so a CL.TE finding there is demonstrated by reflecting our own rewritten
+//! follow-up (`reveal`). Everywhere else `localhost-access` shows what the
+//! back-end can reach.
+
+use crate::fingerprint::{FingerprintResult, behind_cache};
+
+/// Request budget applied to the chosen exploit when none is given.
+pub const DEFAULT_AUTO_EXPLOIT_BUDGET: u32 = 40;
+
+/// Exploit chosen by [`plan_auto_exploit`] and why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutoExploitPlan {
+    /// Exploit name as accepted by `--exploit`
+    pub exploit: &'static str,
+    pub reason: String,
+}
+
+/// Pick the exploit for a finding of `vuln_type` (a check name such as
+/// `cl-te`), using the fingerprint when one is available.
+pub fn plan_auto_exploit(
+    vuln_type: &str,
+    fingerprint: Option<&FingerprintResult>,
+) -> AutoExploitPlan {
+    let vuln_type_lower = vuln_type.to_lowercase();
+    let clte = vuln_type_lower.contains("cl-te") || vuln_type_lower.contains("clte");
+    let cached = fingerprint.is_some_and(behind_cache);
+
+    if clte && cached {
+        let proxy = fingerprint.map_or(String::new(), |fp| fp.detected_proxy.to_string());
+        AutoExploitPlan {
+            exploit: "reveal",
+            reason: format!(
+                "CL.TE behind a cache ({}): reflection keeps smuggled responses on our own connection",
+                proxy
+            ),
+        }
+    } else {
+        AutoExploitPlan {
+            exploit: "localhost-access",
+            reason: if cached {
+                format!(
+                    "{} behind a cache: localhost-access only needs the smuggle to reach the back-end",
+                    vuln_type
+                )
+            } else {
+                format!("{} with no cache detected", vuln_type)
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fingerprint::ProxyType;
+    use std::collections::HashMap;
+
+    fn fingerprint(proxy: ProxyType, headers: &[&str]) -> FingerprintResult {
+        FingerprintResult {
+            detected_proxy: proxy,
+            server_header: None,
+            via_header: None,
+            powered_by: None,
+            raw_headers: headers
+                .iter()
+                .map(|h| (h.to_string(), "1".to_string()))
+                .collect::<HashMap<_, _>>(),
+            asterisk_rewrite: false,
+            hop_count: None,
+            error_hop: None,
+            alpn: None,
+        }
+    }
+
+    #[test]
+    fn clte_behind_cache_uses_reflection() {
+        let varnish = fingerprint(ProxyType::Varnish, &[]);
+        assert_eq!(plan_auto_exploit("cl-te", Some(&varnish)).exploit, "reveal");
+
+        // A cache header on an otherwise plain proxy counts too.
+        let nginx_cache = fingerprint(ProxyType::Nginx, &["x-cache"]);
+        assert_eq!(
+            plan_auto_exploit("cl-te", Some(&nginx_cache)).exploit,
+            "reveal"
+        );
+    }
+
+    #[test]
+    fn other_findings_use_localhost_access() {
+        let varnish = fingerprint(ProxyType::Varnish, &[]);
+        let nginx = fingerprint(ProxyType::Nginx, &[]);
+        assert_eq!(
+            plan_auto_exploit("te-cl", Some(&varnish)).exploit,
+            "localhost-access"
+        );
+        assert_eq!(
+            plan_auto_exploit("cl-te", Some(&nginx)).exploit,
+            "localhost-access"
+        );
+        assert_eq!(plan_auto_exploit("cl-te", None).exploit, "localhost-access");
+    }
+}
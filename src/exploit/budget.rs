@@ -0,0 +1,100 @@
+//! `--exploit-max-requests` / `--exploit-max-time`: caps shared by every
+//! exploit run against a target. An exploit takes its requests from the
+//! budget before sending them and, once the budget refuses, stops and returns
+//! what it found so far.
+
+use std::fmt;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// The cap that stopped exploitation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExploitCap {
+    /// `--exploit-max-requests` requests were sent
+    Requests(usize),
+    /// `--exploit-max-time` elapsed
+    Time(Duration),
+}
+
+impl fmt::Display for ExploitCap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExploitCap::Requests(max) => write!(f, "exploit request cap of {} reached", max),
+            ExploitCap::Time(max) => {
+                write!(f, "exploit time cap of {}s reached", max.as_secs())
+            }
+        }
+    }
+}
+
+/// Requests and time the exploits of one target may still spend.
+#[derive(Debug)]
+pub struct ExploitBudget {
+    max_requests: Option<usize>,
+    max_time: Option<Duration>,
+    started: Instant,
+    sent: AtomicUsize,
+    hit: OnceLock<ExploitCap>,
+}
+
+impl ExploitBudget {
+    /// A budget starting now.
+    pub fn new(max_requests: Option<usize>, max_time: Option<Duration>) -> Self {
+        ExploitBudget {
+            max_requests,
+            max_time,
+            started: Instant::now(),
+            sent: AtomicUsize::new(0),
+            hit: OnceLock::new(),
+        }
+    }
+
+    /// A budget without caps.
+    pub fn unlimited() -> Self {
+        Self::new(None, None)
+    }
+
+    /// Take `requests` from the budget. Returns false, taking nothing, once
+    /// the time is up or they would pass the request cap.
+    pub fn take(&self, requests: usize) -> bool {
+        if let Some(max_time) = self.max_time
+            && self.started.elapsed() >= max_time
+        {
+            self.hit.get_or_init(|| ExploitCap::Time(max_time));
+            return false;
+        }
+        let Some(max) = self.max_requests else {
+            self.sent.fetch_add(requests, Ordering::Relaxed);
+            return true;
+        };
+        let taken = self
+            .sent
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |sent| {
+                (sent + requests <= max).then_some(sent + requests)
+            });
+        if taken.is_err() {
+            self.hit.get_or_init(|| ExploitCap::Requests(max));
+        }
+        taken.is_ok()
+    }
+
+    /// Requests taken so far.
+    pub fn sent(&self) -> usize {
+        self.sent.load(Ordering::Relaxed)
+    }
+
+    /// The cap that refused a request, if one did.
+    pub fn hit(&self) -> Option<ExploitCap> {
+        self.hit.get().copied()
+    }
+
+    /// Why work was not done: the cap that was hit, else the exploit's own
+    /// request budget.
+    pub fn exhausted_reason(&self) -> String {
+        match self.hit() {
+            Some(cap) => cap.to_string(),
+            None => "request budget exhausted".to_string(),
+        }
+    }
+}
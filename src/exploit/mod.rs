@@ -1,19 +1,47 @@
+mod auto;
+mod budget;
 mod capture;
+mod cloud_metadata;
+mod internal_sweep;
 mod localhost_access;
 mod path_fuzz;
+mod plan;
 mod reveal;
 mod smuggle;
 
-pub use capture::{CaptureParams, print_capture_results, test_capture};
+pub use auto::{AutoExploitPlan, DEFAULT_AUTO_EXPLOIT_BUDGET, plan_auto_exploit};
+pub use budget::{ExploitBudget, ExploitCap};
+pub use capture::{
+    CAPTURE_FOLLOW_UPS, CaptureParams, default_capture_request, print_capture_results, test_capture,
+};
+pub use cloud_metadata::{
+    CloudMetadataParams, CloudMetadataResult, CloudProvider, METADATA_ENDPOINTS,
+    METADATA_FOLLOW_UPS, MetadataEndpoint, print_cloud_metadata_results, test_cloud_metadata,
+};
+pub use internal_sweep::{
+    DEFAULT_SWEEP_BUDGET, InternalSweepParams, InternalSweepResult, MIN_SWEEP_PREFIX,
+    SWEEP_CONTROL_ADDRESS, SWEEP_MIN_DELAY_MS, SweepProbeResult, SweepRange, SweepVerdict,
+    print_sweep_results, sweep_requests, test_internal_sweep,
+};
 pub use localhost_access::{
-    LocalhostAccessParams, LocalhostAccessResult, print_localhost_results, test_localhost_access,
+    LOOPBACK_HOSTS, LocalhostAccessParams, LocalhostAccessResult, PortVerdict,
+    order_ports_by_likelihood, parse_hosts, parse_ports, print_localhost_results,
+    test_localhost_access,
 };
 pub use path_fuzz::{
-    PathFuzzParams, PathFuzzResult, get_fuzz_paths, print_path_fuzz_results, test_path_fuzz,
+    FuzzCategory, PathFuzzParams, PathFuzzResult, ResponseMatcher, SizeRange, get_fuzz_paths,
+    print_path_fuzz_results, test_path_fuzz,
+};
+pub use plan::{PlanInputs, plan_exploits, print_exploit_plan};
+pub use reveal::{
+    REVEAL_FOLLOW_UPS, RevealParams, RevealResult, print_reveal_results, test_reveal,
+};
+pub use smuggle::{
+    DEFAULT_SMUGGLE_REQUEST, SMUGGLE_ROUNDS, SmuggleParams, print_smuggle_results, test_smuggle,
+    unescape_request,
 };
-pub use reveal::{RevealParams, RevealResult, print_reveal_results, test_reveal};
-pub use smuggle::{DEFAULT_SMUGGLE_REQUEST, SmuggleParams, print_smuggle_results, test_smuggle};
 
+use crate::http::HttpResponse;
 use crate::model::CheckResult;
 
 /// Detected vulnerability information for exploitation
@@ -36,11 +64,7 @@ pub fn extract_vulnerability_context(results: &[CheckResult]) -> Option<Vulnerab
     })
 }
 
-/// Extract body from HTTP response
+/// Extract body from HTTP response (with any bytes trailing it)
 pub(crate) fn extract_body(response: &str) -> String {
-    if let Some(body_start) = response.find("\r\n\r\n") {
-        response[body_start + 4..].to_string()
-    } else {
-        String::new()
-    }
+    HttpResponse::parse(response.as_bytes()).text_after_head()
 }
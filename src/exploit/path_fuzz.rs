@@ -1,10 +1,18 @@
 use crate::error::{Result, SmugglexError};
-use crate::http::send_request;
-use crate::utils::parse_status_code;
+use crate::http::{HttpResponse, send_request};
+use crate::model::DiscoveredPath;
+use crate::wordlists::{WordlistKind, resolve_wordlist, wordlist_entries};
+use clap::ValueEnum;
 use colored::*;
+use regex::Regex;
+use std::fmt;
+use std::str::FromStr;
 use std::time::Duration;
 
-use super::{VulnerabilityContext, extract_body};
+use super::{ExploitBudget, VulnerabilityContext};
+
+/// Requests sent per path: the smuggle and the probe behind it.
+pub(super) const REQUESTS_PER_PATH: usize = 2;
 
 /// Default paths to fuzz when no wordlist is provided
 const DEFAULT_PATHS: &[&str] = &[
@@ -67,6 +75,277 @@ const DEFAULT_PATHS: &[&str] = &[
     "wordpress",
 ];
 
+/// Admin panels and management consoles across common stacks
+const ADMIN_PATHS: &[&str] = &[
+    "admin",
+    "admin/",
+    "administrator",
+    "admin.php",
+    "admin/login",
+    "admin/dashboard",
+    "admin/users",
+    "admin/config",
+    "admincp",
+    "adminpanel",
+    "backoffice",
+    "console",
+    "cpanel",
+    "dashboard",
+    "manage",
+    "manager/html",
+    "management",
+    "panel",
+    "phpmyadmin",
+    "pma",
+    "portal",
+    "superadmin",
+    "sysadmin",
+    "wp-admin",
+    "jenkins",
+    "grafana",
+    "kibana",
+];
+
+/// API roots, versioned prefixes, and schema/documentation endpoints
+const API_PATHS: &[&str] = &[
+    "api",
+    "api/v1",
+    "api/v2",
+    "api/v3",
+    "api/internal",
+    "api/admin",
+    "api/users",
+    "api/private",
+    "api/health",
+    "api/swagger.json",
+    "api-docs",
+    "v1",
+    "v2",
+    "graphql",
+    "graphiql",
+    "openapi.json",
+    "swagger",
+    "swagger-ui.html",
+    "swagger.json",
+    "rest",
+    "rpc",
+    "internal/api",
+];
+
+/// Debug, diagnostics, and leaked development artefacts
+const DEBUG_PATHS: &[&str] = &[
+    "debug",
+    "debug/pprof",
+    "debug/vars",
+    "_debug",
+    "_profiler",
+    "__debug__",
+    "trace",
+    "phpinfo.php",
+    "info.php",
+    "server-status",
+    "server-info",
+    "status",
+    "metrics",
+    "elmah.axd",
+    "trace.axd",
+    ".env",
+    ".git/HEAD",
+    ".git/config",
+    ".svn/entries",
+    ".DS_Store",
+    "config.json",
+    "web.config",
+    "console",
+];
+
+/// Cloud instance metadata services, reachable when the smuggled request is
+/// routed to a link-local host
+const CLOUD_METADATA_PATHS: &[&str] = &[
+    "latest/meta-data/",
+    "latest/meta-data/iam/security-credentials/",
+    "latest/user-data",
+    "latest/dynamic/instance-identity/document",
+    "latest/api/token",
+    "computeMetadata/v1/",
+    "computeMetadata/v1/instance/service-accounts/default/token",
+    "metadata/instance?api-version=2021-02-01",
+    "metadata/identity/oauth2/token",
+    "metadata/v1/",
+    "openstack/latest/meta_data.json",
+    "opc/v1/instance/",
+];
+
+/// Spring Boot actuator endpoints, at both the default and legacy base paths
+const ACTUATOR_PATHS: &[&str] = &[
+    "actuator",
+    "actuator/health",
+    "actuator/info",
+    "actuator/env",
+    "actuator/beans",
+    "actuator/configprops",
+    "actuator/mappings",
+    "actuator/metrics",
+    "actuator/loggers",
+    "actuator/heapdump",
+    "actuator/threaddump",
+    "actuator/httptrace",
+    "actuator/gateway/routes",
+    "actuator/jolokia",
+    "actuator/prometheus",
+    "env",
+    "heapdump",
+    "mappings",
+    "jolokia",
+    "manage/health",
+];
+
+/// Built-in wordlists selectable with `--fuzz-categories`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FuzzCategory {
+    /// General-purpose list used when no category or wordlist is given
+    Common,
+    /// Admin panels and management consoles
+    Admin,
+    /// API roots, versioned prefixes, and API documentation
+    Api,
+    /// Debug endpoints and leaked development files
+    Debug,
+    /// AWS, GCP, Azure, and other cloud instance metadata services
+    CloudMetadata,
+    /// Spring Boot actuator endpoints
+    Actuator,
+}
+
+impl FuzzCategory {
+    /// Name as accepted by `--fuzz-categories`.
+    pub fn name(self) -> &'static str {
+        match self {
+            FuzzCategory::Common => "common",
+            FuzzCategory::Admin => "admin",
+            FuzzCategory::Api => "api",
+            FuzzCategory::Debug => "debug",
+            FuzzCategory::CloudMetadata => "cloud-metadata",
+            FuzzCategory::Actuator => "actuator",
+        }
+    }
+
+    /// Embedded paths for this category, without the leading `/`.
+    pub fn paths(self) -> &'static [&'static str] {
+        match self {
+            FuzzCategory::Common => DEFAULT_PATHS,
+            FuzzCategory::Admin => ADMIN_PATHS,
+            FuzzCategory::Api => API_PATHS,
+            FuzzCategory::Debug => DEBUG_PATHS,
+            FuzzCategory::CloudMetadata => CLOUD_METADATA_PATHS,
+            FuzzCategory::Actuator => ACTUATOR_PATHS,
+        }
+    }
+}
+
+/// Inclusive range of response body sizes in bytes, written `N` or `N-M`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SizeRange {
+    pub min: usize,
+    pub max: usize,
+}
+
+impl SizeRange {
+    pub fn contains(&self, size: usize) -> bool {
+        (self.min..=self.max).contains(&size)
+    }
+}
+
+impl FromStr for SizeRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let parse = |v: &str| {
+            v.trim()
+                .parse::<usize>()
+                .map_err(|_| format!("invalid size '{}' (expected N or N-M)", s))
+        };
+        let (min, max) = match s.split_once('-') {
+            Some((min, max)) => (parse(min)?, parse(max)?),
+            None => {
+                let size = parse(s)?;
+                (size, size)
+            }
+        };
+        if min > max {
+            return Err(format!("invalid size range '{}' (start exceeds end)", s));
+        }
+        Ok(SizeRange { min, max })
+    }
+}
+
+impl fmt::Display for SizeRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.min == self.max {
+            write!(f, "{}", self.min)
+        } else {
+            write!(f, "{}-{}", self.min, self.max)
+        }
+    }
+}
+
+/// ffuf-style rules deciding which path-fuzz responses are reported. A
+/// response is a hit when it satisfies every `match_*` rule given and no
+/// `filter_*` rule; with no `match_*` rules, the built-in indicators decide.
+#[derive(Debug, Clone, Default)]
+pub struct ResponseMatcher {
+    pub match_status: Vec<u16>,
+    pub filter_status: Vec<u16>,
+    pub match_size: Vec<SizeRange>,
+    pub filter_size: Vec<SizeRange>,
+    pub match_regex: Option<Regex>,
+    pub filter_regex: Option<Regex>,
+}
+
+impl ResponseMatcher {
+    /// Whether any `match_*` rule is set.
+    pub fn has_match_rules(&self) -> bool {
+        !self.match_status.is_empty() || !self.match_size.is_empty() || self.match_regex.is_some()
+    }
+
+    /// Whether any rule at all is set.
+    pub fn is_empty(&self) -> bool {
+        !self.has_match_rules()
+            && self.filter_status.is_empty()
+            && self.filter_size.is_empty()
+            && self.filter_regex.is_none()
+    }
+
+    /// Describe how the response satisfies every `match_*` rule, or `None`
+    /// when one of them fails.
+    pub fn matches(&self, status: Option<u16>, body: &str) -> Option<String> {
+        let mut matched = Vec::new();
+        if !self.match_status.is_empty() {
+            let code = status.filter(|code| self.match_status.contains(code))?;
+            matched.push(format!("status {}", code));
+        }
+        if !self.match_size.is_empty() {
+            let range = self.match_size.iter().find(|r| r.contains(body.len()))?;
+            matched.push(format!("size {} in {}", body.len(), range));
+        }
+        if let Some(ref regex) = self.match_regex {
+            let m = regex.find(body)?;
+            matched.push(format!("regex '{}' ('{}')", regex.as_str(), m.as_str()));
+        }
+        Some(matched.join(", "))
+    }
+
+    /// Whether the response hits any `filter_*` rule.
+    pub fn filters(&self, status: Option<u16>, body: &str) -> bool {
+        status.is_some_and(|code| self.filter_status.contains(&code))
+            || self.filter_size.iter().any(|r| r.contains(body.len()))
+            || self
+                .filter_regex
+                .as_ref()
+                .is_some_and(|regex| regex.is_match(body))
+    }
+}
+
 /// Result of a path fuzz attempt
 #[derive(Debug)]
 pub struct PathFuzzResult {
@@ -74,9 +353,22 @@ pub struct PathFuzzResult {
     pub found: bool,
     pub reason: String,
     pub response_status: Option<String>,
+    /// Probe response body size in bytes
+    pub response_size: Option<usize>,
     pub response_body_preview: Option<String>,
 }
 
+impl From<&PathFuzzResult> for DiscoveredPath {
+    fn from(result: &PathFuzzResult) -> Self {
+        DiscoveredPath {
+            path: result.path.clone(),
+            reason: result.reason.clone(),
+            response_status: result.response_status.clone(),
+            response_size: result.response_size,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct PathFuzzParams<'a> {
     pub host: &'a str,
@@ -87,7 +379,10 @@ pub struct PathFuzzParams<'a> {
     pub verbose: bool,
     pub vuln_ctx: &'a VulnerabilityContext,
     pub fuzz_paths: &'a [String],
+    pub matcher: &'a ResponseMatcher,
     pub delay: u64,
+    /// Caps shared with the other exploits; untested paths are left out
+    pub budget: &'a ExploitBudget,
 }
 
 #[derive(Debug)]
@@ -99,6 +394,7 @@ struct SinglePathTestParams<'a> {
     pub verbose: bool,
     pub vuln_ctx: &'a VulnerabilityContext,
     pub fuzz_path: &'a str,
+    pub matcher: &'a ResponseMatcher,
     pub baseline_status: Option<u16>,
     pub baseline_body: &'a str,
     pub baseline_duration: Duration,
@@ -113,13 +409,41 @@ struct AnalyzePathFuzzParams<'a> {
     pub baseline_status: Option<u16>,
     pub baseline_body: &'a str,
     pub baseline_duration: Duration,
+    pub matcher: &'a ResponseMatcher,
     pub verbose: bool,
 }
 
-/// Get paths to fuzz from wordlist file or use defaults
-pub fn get_fuzz_paths(wordlist_path: Option<&str>) -> Result<Vec<String>> {
+/// Get paths to fuzz from the selected built-in categories and/or a wordlist
+/// file, in that order and without duplicates. With neither, the `common`
+/// list is used.
+pub fn get_fuzz_paths(
+    wordlist_path: Option<&str>,
+    categories: &[FuzzCategory],
+) -> Result<Vec<String>> {
+    let categories = if categories.is_empty() && wordlist_path.is_none() {
+        &[FuzzCategory::Common][..]
+    } else {
+        categories
+    };
+    let mut paths: Vec<String> = Vec::new();
+    for category in categories {
+        for path in category.paths() {
+            push_fuzz_path(&mut paths, path);
+        }
+    }
+
     if let Some(path) = wordlist_path {
-        let content = std::fs::read_to_string(path).map_err(|e| {
+        let file = match resolve_wordlist(path)? {
+            Some((wordlist, file)) if wordlist.kind == WordlistKind::Paths => file,
+            Some((wordlist, _)) => {
+                return Err(SmugglexError::Config(format!(
+                    "--exploit-wordlist '{}' is a {} wordlist, not a paths one",
+                    path, wordlist.kind
+                )));
+            }
+            None => std::path::PathBuf::from(path),
+        };
+        let content = std::fs::read_to_string(&file).map_err(|e| {
             SmugglexError::Io(format!(
                 "Failed to read wordlist file '{}': {} ({})",
                 path,
@@ -127,33 +451,38 @@ pub fn get_fuzz_paths(wordlist_path: Option<&str>) -> Result<Vec<String>> {
                 e.kind()
             ))
         })?;
-        let paths: Vec<String> = content
-            .lines()
-            .map(|line| line.trim())
-            .filter(|line| !line.is_empty() && !line.starts_with('#'))
-            .map(|line| {
-                if line.starts_with('/') {
-                    line.to_string()
-                } else {
-                    format!("/{}", line)
-                }
-            })
-            .collect();
+        let entries = wordlist_entries(&content);
 
-        if paths.is_empty() {
-            return Err(SmugglexError::InvalidInput(
+        if entries.is_empty() {
+            return Err(SmugglexError::Config(
                 "Wordlist file is empty or contains no valid paths".to_string(),
             ));
         }
-        Ok(paths)
+        for entry in entries {
+            push_fuzz_path(&mut paths, entry);
+        }
+    }
+    Ok(paths)
+}
+
+/// Append `path` with a leading `/` unless it is already listed.
+fn push_fuzz_path(paths: &mut Vec<String>, path: &str) {
+    let path = if path.starts_with('/') {
+        path.to_string()
     } else {
-        Ok(DEFAULT_PATHS.iter().map(|p| format!("/{}", p)).collect())
+        format!("/{}", path)
+    };
+    if !paths.contains(&path) {
+        paths.push(path);
     }
 }
 
 /// Test hidden paths using detected smuggling vulnerability
 pub async fn test_path_fuzz(params: &PathFuzzParams<'_>) -> Result<Vec<PathFuzzResult>> {
     let mut results = Vec::new();
+    if !params.budget.take(1) {
+        return Ok(results);
+    }
 
     // Get baseline response for comparison
     let baseline_request = format!(
@@ -169,8 +498,9 @@ pub async fn test_path_fuzz(params: &PathFuzzParams<'_>) -> Result<Vec<PathFuzzR
         params.use_tls,
     )
     .await?;
-    let baseline_status = parse_status_code(baseline_response.lines().next().unwrap_or(""));
-    let baseline_body = extract_body(&baseline_response);
+    let baseline = HttpResponse::parse(baseline_response.as_bytes());
+    let baseline_status = baseline.status;
+    let baseline_body = baseline.text_after_head();
 
     if params.verbose {
         println!(
@@ -185,6 +515,18 @@ pub async fn test_path_fuzz(params: &PathFuzzParams<'_>) -> Result<Vec<PathFuzzR
 
     // Test each path
     for (i, fuzz_path) in params.fuzz_paths.iter().enumerate() {
+        if !params.budget.take(REQUESTS_PER_PATH) {
+            if params.verbose {
+                println!(
+                    "\n{} {}; {} of {} paths untested",
+                    "[*]".cyan(),
+                    params.budget.exhausted_reason(),
+                    params.fuzz_paths.len() - i,
+                    params.fuzz_paths.len()
+                );
+            }
+            break;
+        }
         if params.delay > 0 && i > 0 {
             tokio::time::sleep(Duration::from_millis(params.delay)).await;
         }
@@ -200,6 +542,7 @@ pub async fn test_path_fuzz(params: &PathFuzzParams<'_>) -> Result<Vec<PathFuzzR
             verbose: params.verbose,
             vuln_ctx: params.vuln_ctx,
             fuzz_path,
+            matcher: params.matcher,
             baseline_status,
             baseline_body: &baseline_body,
             baseline_duration,
@@ -257,9 +600,9 @@ async fn test_single_path(params: &SinglePathTestParams<'_>) -> Result<PathFuzzR
             .await
             {
                 Ok((probe_response, probe_duration)) => {
-                    let probe_status_line = probe_response.lines().next().unwrap_or("");
-                    let probe_status = parse_status_code(probe_status_line);
-                    let probe_body = extract_body(&probe_response);
+                    let probe = HttpResponse::parse(probe_response.as_bytes());
+                    let probe_status = probe.status;
+                    let probe_body = probe.text_after_head();
 
                     // Analyze response to detect hidden path discovery
                     let analyze_params = AnalyzePathFuzzParams {
@@ -270,19 +613,23 @@ async fn test_single_path(params: &SinglePathTestParams<'_>) -> Result<PathFuzzR
                         baseline_status: params.baseline_status,
                         baseline_body: params.baseline_body,
                         baseline_duration: params.baseline_duration,
+                        matcher: params.matcher,
                         verbose: params.verbose,
                     };
                     analyze_path_fuzz_response(&analyze_params)
                 }
                 Err(e) => {
                     // Connection errors might indicate something interesting
-                    if matches!(e, SmugglexError::Timeout(_)) {
+                    if matches!(e, SmugglexError::Timeout { .. }) {
+                        // Without a response there is nothing for match rules to
+                        // accept, so only report the timeout when none are set.
                         Ok(PathFuzzResult {
                             path: params.fuzz_path.to_string(),
-                            found: true,
+                            found: params.matcher.is_empty(),
                             reason: "Connection timeout (possible path processing delay)"
                                 .to_string(),
                             response_status: None,
+                            response_size: None,
                             response_body_preview: None,
                         })
                     } else {
@@ -291,6 +638,7 @@ async fn test_single_path(params: &SinglePathTestParams<'_>) -> Result<PathFuzzR
                             found: false,
                             reason: format!("Probe request failed: {}", e),
                             response_status: None,
+                            response_size: None,
                             response_body_preview: None,
                         })
                     }
@@ -302,6 +650,7 @@ async fn test_single_path(params: &SinglePathTestParams<'_>) -> Result<PathFuzzR
             found: false,
             reason: format!("Smuggling request failed: {}", e),
             response_status: None,
+            response_size: None,
             response_body_preview: None,
         }),
     }
@@ -483,9 +832,31 @@ fn analyze_path_fuzz_response(params: &AnalyzePathFuzzParams<'_>) -> Result<Path
         success_indicators.push(format!("Significant timing difference: {}ms", timing_diff));
     }
 
-    let found = !success_indicators.is_empty();
+    let mut found = !success_indicators.is_empty();
+    if params.matcher.has_match_rules() {
+        match params
+            .matcher
+            .matches(params.probe_status, params.probe_body)
+        {
+            Some(matched) => {
+                success_indicators.insert(0, format!("Matched {}", matched));
+                found = true;
+            }
+            None => found = false,
+        }
+    }
+    let filtered = params
+        .matcher
+        .filters(params.probe_status, params.probe_body);
+    if filtered {
+        found = false;
+    }
     let reason = if found {
         success_indicators.join("; ")
+    } else if filtered {
+        "Filtered by response rules".to_string()
+    } else if params.matcher.has_match_rules() {
+        "Did not match response rules".to_string()
     } else {
         "No indicators of hidden path".to_string()
     };
@@ -521,6 +892,7 @@ fn analyze_path_fuzz_response(params: &AnalyzePathFuzzParams<'_>) -> Result<Path
         found,
         reason,
         response_status: params.probe_status.map(|s| format!("HTTP {}", s)),
+        response_size: Some(params.probe_body.len()),
         response_body_preview: body_preview,
     })
 }
@@ -553,6 +925,9 @@ pub fn print_path_fuzz_results(results: &[PathFuzzResult], target_url: &str) {
             if let Some(ref status) = result.response_status {
                 println!("  {} {}", "Response Status:".bold(), status);
             }
+            if let Some(size) = result.response_size {
+                println!("  {} {} bytes", "Response Size:".bold(), size);
+            }
             if let Some(ref preview) = result.response_body_preview {
                 println!("  {} {}", "Body Preview:".bold(), preview.dimmed());
             }
@@ -605,4 +980,46 @@ mod tests {
         assert_eq!(&body.as_bytes()[..cl], b"1\r\nX\r\n");
         assert!(body[cl..].starts_with("0\r\n\r\nGET /admin"));
     }
+
+    #[test]
+    fn analyze_path_fuzz_response_applies_response_rules() {
+        let analyze = |matcher: &ResponseMatcher, status: u16, body: &str| {
+            analyze_path_fuzz_response(&AnalyzePathFuzzParams {
+                fuzz_path: "/admin",
+                probe_status: Some(status),
+                probe_body: body,
+                probe_duration: Duration::from_millis(10),
+                baseline_status: Some(404),
+                baseline_body: "not found",
+                baseline_duration: Duration::from_millis(10),
+                matcher,
+                verbose: false,
+            })
+            .unwrap()
+        };
+
+        // Indicators alone decide when no rules are set.
+        let none = ResponseMatcher::default();
+        assert!(analyze(&none, 200, "").found);
+
+        // A filter hides an otherwise interesting response.
+        let filter_empty = ResponseMatcher {
+            filter_size: vec![SizeRange { min: 0, max: 0 }],
+            ..Default::default()
+        };
+        let result = analyze(&filter_empty, 200, "");
+        assert!(!result.found);
+        assert_eq!(result.reason, "Filtered by response rules");
+
+        // Match rules replace the indicators.
+        let match_302 = ResponseMatcher {
+            match_status: vec![302],
+            ..Default::default()
+        };
+        assert!(!analyze(&match_302, 200, "welcome").found);
+        let result = analyze(&match_302, 302, "");
+        assert!(result.found);
+        assert!(result.reason.starts_with("Matched status 302"));
+        assert_eq!(result.response_size, Some(0));
+    }
 }
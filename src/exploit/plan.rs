@@ -0,0 +1,199 @@
+//! The exploit plan shown before exploitation: which exploits are about to
+//! run, the most requests each may send, and the requests they smuggle, so
+//! the operator can decline before anything is fired at the target.
+
+use colored::*;
+
+use super::capture::{CAPTURE_FOLLOW_UPS, default_capture_request};
+use super::cloud_metadata::{METADATA_ENDPOINTS, METADATA_FOLLOW_UPS};
+use super::internal_sweep::{SweepRange, sweep_requests};
+use super::localhost_access::{
+    LOOPBACK_HOSTS, REQUESTS_PER_ATTEMPT, order_ports_by_likelihood, parse_hosts, parse_ports,
+};
+use super::path_fuzz::REQUESTS_PER_PATH;
+use super::reveal::{
+    REVEAL_FOLLOW_UPS, build_probe, build_reveal_inner, probes_needed, reveal_marker,
+};
+use super::smuggle::{DEFAULT_SMUGGLE_REQUEST, SMUGGLE_ROUNDS, TE_VARIANTS, unescape_request};
+use crate::model::PlannedExploit;
+
+/// Smuggled requests listed per exploit before the rest are summarized.
+const SHOWN_SMUGGLED: usize = 5;
+
+/// What the exploits would be run with.
+#[derive(Debug)]
+pub struct PlanInputs<'a> {
+    /// Exploits to run, in order
+    pub exploits: &'a [&'a str],
+    pub host: &'a str,
+    pub port: u16,
+    pub path: &'a str,
+    /// `--exploit-ports`
+    pub ports: &'a str,
+    /// `--exploit-hosts`, empty without it
+    pub hosts: &'a str,
+    /// `--range`
+    pub range: Option<&'a str>,
+    /// `--sweep-budget`
+    pub sweep_budget: usize,
+    /// Paths `path-fuzz` would try
+    pub fuzz_paths: &'a [String],
+    /// `--smuggle-request`, escapes not yet interpreted
+    pub smuggle_request: Option<&'a str>,
+    pub reveal_endpoint: Option<&'a str>,
+    pub reveal_param: &'a str,
+    /// Request cap for exploits that support one (set by `--auto-exploit`)
+    pub request_budget: Option<usize>,
+    /// `--exploit-max-requests`, shared by every exploit
+    pub max_requests: Option<usize>,
+}
+
+/// Plan each known exploit of `inputs`. Request counts are upper bounds: an
+/// exploit that lands early, or skips ports that answer alike, sends fewer,
+/// and `--exploit-max-requests` caps them all together.
+pub fn plan_exploits(inputs: &PlanInputs<'_>) -> Vec<PlannedExploit> {
+    let wrappers = TE_VARIANTS.len() * 2;
+    let capped = |requests: usize| {
+        inputs
+            .request_budget
+            .map_or(requests, |max| requests.min(max))
+    };
+    inputs
+        .exploits
+        .iter()
+        .filter_map(|&exploit| {
+            let (max_requests, smuggled) = match exploit {
+                "localhost-access" => {
+                    let ports = order_ports_by_likelihood(&parse_ports(inputs.ports));
+                    let hosts = parse_hosts(inputs.hosts);
+                    // Baseline, closed control port, then every loopback form per port;
+                    // internal hosts add an unknown-name control and one try per port.
+                    let mut attempts = 1 + ports.len() * LOOPBACK_HOSTS.len();
+                    if !hosts.is_empty() {
+                        attempts += 1 + hosts.len() * ports.len();
+                    }
+                    let targets =
+                        std::iter::once(LOOPBACK_HOSTS[0]).chain(hosts.iter().map(String::as_str));
+                    (
+                        capped(1 + attempts * REQUESTS_PER_ATTEMPT),
+                        targets
+                            .flat_map(|host| {
+                                ports.iter().map(move |port| {
+                                    format!("GET / HTTP/1.1 (Host: {}:{})", host, port)
+                                })
+                            })
+                            .collect(),
+                    )
+                }
+                "internal-sweep" => {
+                    let range = SweepRange::parse(inputs.range?).ok()?;
+                    let ports = order_ports_by_likelihood(&parse_ports(inputs.ports));
+                    let max_requests = capped(sweep_requests(
+                        range.address_count() * ports.len(),
+                        inputs.sweep_budget,
+                    ));
+                    // Only the pairs the budget leaves room for are listed.
+                    let attempts = max_requests.saturating_sub(1) / REQUESTS_PER_ATTEMPT;
+                    (
+                        max_requests,
+                        range
+                            .addresses()
+                            .flat_map(|address| {
+                                ports.iter().map(move |port| {
+                                    format!("GET / HTTP/1.1 (Host: {}:{})", address, port)
+                                })
+                            })
+                            .take(attempts.saturating_sub(1))
+                            .collect(),
+                    )
+                }
+                "path-fuzz" => (
+                    1 + inputs.fuzz_paths.len() * REQUESTS_PER_PATH,
+                    inputs
+                        .fuzz_paths
+                        .iter()
+                        .map(|path| format!("GET {} HTTP/1.1", path))
+                        .collect(),
+                ),
+                "cloud-metadata" => (
+                    1 + METADATA_ENDPOINTS.len() * (1 + METADATA_FOLLOW_UPS),
+                    METADATA_ENDPOINTS
+                        .iter()
+                        .map(|e| format!("GET {} HTTP/1.1 (Host: {})", e.path, e.host))
+                        .collect(),
+                ),
+                "smuggle" => {
+                    let inner = inputs
+                        .smuggle_request
+                        .map(unescape_request)
+                        .unwrap_or_else(|| DEFAULT_SMUGGLE_REQUEST.to_string());
+                    (wrappers * SMUGGLE_ROUNDS, vec![request_line(&inner)])
+                }
+                "capture" => {
+                    let inner = inputs
+                        .smuggle_request
+                        .map(unescape_request)
+                        .unwrap_or_else(|| default_capture_request(inputs.host));
+                    (
+                        1 + wrappers * (1 + CAPTURE_FOLLOW_UPS),
+                        vec![request_line(&inner)],
+                    )
+                }
+                "reveal" => {
+                    let endpoint = inputs.reveal_endpoint.unwrap_or(inputs.path);
+                    let inner = build_reveal_inner(inputs.host, endpoint, inputs.reveal_param);
+                    let probe = build_probe(inputs.host, &reveal_marker(inputs.host, inputs.port));
+                    let probes = REVEAL_FOLLOW_UPS.max(probes_needed(probe.len()));
+                    (capped(wrappers * (1 + probes)), vec![request_line(&inner)])
+                }
+                _ => return None,
+            };
+            Some(PlannedExploit {
+                exploit: exploit.to_string(),
+                max_requests: inputs
+                    .max_requests
+                    .map_or(max_requests, |max| max_requests.min(max)),
+                smuggled,
+            })
+        })
+        .collect()
+}
+
+fn request_line(request: &str) -> String {
+    request
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
+
+/// Print `plan` for `target_url` to stderr, ahead of the confirmation prompt.
+pub fn print_exploit_plan(plan: &[PlannedExploit], target_url: &str) {
+    eprintln!(
+        "\n{}",
+        format!("=== Exploit plan for {} ===", target_url).bold()
+    );
+    for exploit in plan {
+        eprintln!(
+            "  {} {} (up to {} request(s))",
+            "[*]".cyan(),
+            exploit.exploit.bold(),
+            exploit.max_requests
+        );
+        for smuggled in exploit.smuggled.iter().take(SHOWN_SMUGGLED) {
+            eprintln!("      smuggles {}", smuggled.yellow());
+        }
+        if exploit.smuggled.len() > SHOWN_SMUGGLED {
+            eprintln!(
+                "      ... and {} more",
+                exploit.smuggled.len() - SHOWN_SMUGGLED
+            );
+        }
+    }
+    eprintln!(
+        "  {} up to {} request(s) in total",
+        "[*]".cyan(),
+        plan.iter().map(|e| e.max_requests).sum::<usize>()
+    );
+}
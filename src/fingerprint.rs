@@ -1,10 +1,17 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
 
+use rustls::pki_types::ServerName;
 use serde::{Deserialize, Serialize};
+use tokio_rustls::TlsConnector;
 
 use crate::error::Result;
-use crate::http::send_request;
+use crate::http::{HttpResponse, connect_tcp, get_tls_config, proxy_for, send_request};
+use crate::model::ProxyHop;
+use crate::trace::{TraceFate, TraceReport};
 
 /// Known proxy/server types that can be identified via response headers.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -56,6 +63,21 @@ pub struct FingerprintResult {
     pub via_header: Option<String>,
     pub powered_by: Option<String>,
     pub raw_headers: HashMap<String, String>,
+    /// A hop rewrites the asterisk-form `OPTIONS * HTTP/1.1` target to `/`
+    #[serde(default)]
+    pub asterisk_rewrite: bool,
+    /// Proxy hops in front of the final responder, from the Max-Forwards
+    /// probe (`None` when no hop answered a decremented Max-Forwards)
+    #[serde(default)]
+    pub hop_count: Option<usize>,
+    /// Depth of the first hop that answered the Max-Forwards probe with an
+    /// error status (`0` is the hop the client connects to)
+    #[serde(default)]
+    pub error_hop: Option<usize>,
+    /// Protocol the front door negotiated over ALPN when offered `h2` and
+    /// `http/1.1` (`None` for plain HTTP, without ALPN, or through a proxy)
+    #[serde(default)]
+    pub alpn: Option<String>,
 }
 
 impl fmt::Display for FingerprintResult {
@@ -70,28 +92,30 @@ impl fmt::Display for FingerprintResult {
         if let Some(ref p) = self.powered_by {
             writeln!(f, "X-Powered-By: {}", p)?;
         }
+        if self.asterisk_rewrite {
+            writeln!(f, "Asterisk-form: OPTIONS * rewritten to /")?;
+        }
+        if let Some(hops) = self.hop_count {
+            writeln!(f, "Proxy hops: {}", hops)?;
+        }
+        if let Some(hop) = self.error_hop {
+            writeln!(f, "Error hop: {}", hop)?;
+        }
+        if let Some(ref alpn) = self.alpn {
+            writeln!(f, "ALPN: {}", alpn)?;
+        }
         Ok(())
     }
 }
 
-/// Parse HTTP response headers into a map (lowercase keys).
+/// Parse HTTP response headers into a map (lowercase keys; of repeated
+/// fields, the last one).
 fn parse_response_headers(response: &str) -> HashMap<String, String> {
-    let mut headers = HashMap::new();
-    for line in response.lines() {
-        if line.starts_with("HTTP/") {
-            continue;
-        }
-        // The first blank line terminates the header section; stop here so the
-        // response body is never parsed as headers. (Previously this used
-        // `continue`, letting body lines that contain ':' leak in as headers.)
-        if line.trim().is_empty() {
-            break;
-        }
-        if let Some((key, value)) = line.split_once(':') {
-            headers.insert(key.trim().to_ascii_lowercase(), value.trim().to_string());
-        }
-    }
-    headers
+    HttpResponse::parse(response.as_bytes())
+        .headers
+        .into_iter()
+        .map(|(key, value)| (key.to_ascii_lowercase(), value))
+        .collect()
 }
 
 /// Identify the proxy type from parsed response headers.
@@ -176,7 +200,174 @@ fn identify_proxy(headers: &HashMap<String, String>) -> ProxyType {
     ProxyType::Unknown("unidentified".to_string())
 }
 
-/// Send a GET probe to the target and fingerprint the proxy/server from response headers.
+/// Path no server should route, compared against `*` and `/` by
+/// [`probe_asterisk_rewrite`].
+const ASTERISK_PROBE_MISSING_PATH: &str = "/smugglex-asterisk-probe";
+
+/// The parts of an `OPTIONS` response compared by [`asterisk_rewritten`].
+#[derive(Debug, PartialEq)]
+struct OptionsShape {
+    status: Option<u16>,
+    allow: Option<String>,
+    body_length: usize,
+}
+
+impl OptionsShape {
+    fn from_response(response: &str) -> Self {
+        let response = HttpResponse::parse(response.as_bytes());
+        Self {
+            status: response.status,
+            allow: response.header_values("allow").last().map(str::to_string),
+            body_length: response.len_after_head(),
+        }
+    }
+}
+
+/// Whether the `OPTIONS *` response looks like the `OPTIONS /` one while `/`
+/// is distinguishable from a path that does not exist — i.e. the request
+/// reached the back-end as `OPTIONS /`. A server that answers every path the
+/// same way gives no signal.
+fn asterisk_rewritten(star: &str, root: &str, missing: &str) -> bool {
+    let root = OptionsShape::from_response(root);
+    OptionsShape::from_response(star) == root && OptionsShape::from_response(missing) != root
+}
+
+/// Probe whether a hop rewrites the asterisk-form `OPTIONS * HTTP/1.1`
+/// target to `/`, by comparing it with `OPTIONS /` and `OPTIONS` on a missing
+/// path. Any request failing makes the probe inconclusive (`false`).
+pub async fn probe_asterisk_rewrite(
+    host: &str,
+    port: u16,
+    timeout: u64,
+    verbose: bool,
+    use_tls: bool,
+) -> bool {
+    let mut responses = Vec::with_capacity(3);
+    for target in ["*", "/", ASTERISK_PROBE_MISSING_PATH] {
+        let request = format!(
+            "OPTIONS {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+            target, host
+        );
+        match send_request(host, port, &request, timeout, verbose, use_tls).await {
+            Ok((response, _)) => responses.push(response),
+            Err(_) => return false,
+        }
+    }
+    asterisk_rewritten(&responses[0], &responses[1], &responses[2])
+}
+
+/// Deepest `Max-Forwards` value sent by [`probe_max_forwards`]; longer chains
+/// are reported at this depth.
+const MAX_FORWARDS_PROBE_DEPTH: usize = 5;
+
+/// Which hop answered a Max-Forwards probe, as far as the response shows.
+#[derive(Debug, PartialEq)]
+struct HopSignature {
+    status: Option<u16>,
+    server: Option<String>,
+    via: Option<String>,
+}
+
+impl HopSignature {
+    fn from_response(response: &str) -> Self {
+        let response = HttpResponse::parse(response.as_bytes());
+        let last = |name| response.header_values(name).last().map(str::to_string);
+        Self {
+            status: response.status,
+            server: last("server"),
+            via: last("via"),
+        }
+    }
+}
+
+/// Hop count and error hop from the responses to `Max-Forwards: 0..=N`.
+///
+/// A hop that honors Max-Forwards answers a request that reaches it at `0`
+/// itself, so the responses change until the value lets the request reach the
+/// final responder. The hop count is the first value whose response matches
+/// the deepest one; `None` when even `0` does (no hop honors the header, or
+/// there is no proxy — indistinguishable). The error hop is the first depth
+/// up to the final responder that answered with a 4xx/5xx.
+fn estimate_hops(signatures: &[HopSignature]) -> (Option<usize>, Option<usize>) {
+    let Some(deepest) = signatures.last() else {
+        return (None, None);
+    };
+    let hops = signatures.iter().position(|s| s == deepest).unwrap_or(0);
+    if hops == 0 {
+        return (None, None);
+    }
+    let error_hop = signatures[..=hops]
+        .iter()
+        .position(|s| s.status.is_some_and(|c| c >= 400));
+    (Some(hops), error_hop)
+}
+
+/// Estimate the proxy chain depth by sending TRACE, then OPTIONS if TRACE is
+/// inconclusive, with `Max-Forwards` from `0` to
+/// [`MAX_FORWARDS_PROBE_DEPTH`]. Returns the hop count and error hop (see
+/// [`estimate_hops`]); any request failing makes that method inconclusive.
+pub async fn probe_max_forwards(
+    host: &str,
+    port: u16,
+    path: &str,
+    timeout: u64,
+    verbose: bool,
+    use_tls: bool,
+) -> (Option<usize>, Option<usize>) {
+    'methods: for method in ["TRACE", "OPTIONS"] {
+        let mut signatures = Vec::with_capacity(MAX_FORWARDS_PROBE_DEPTH + 1);
+        for max_forwards in 0..=MAX_FORWARDS_PROBE_DEPTH {
+            let request = format!(
+                "{} {} HTTP/1.1\r\nHost: {}\r\nMax-Forwards: {}\r\nConnection: close\r\n\r\n",
+                method, path, host, max_forwards
+            );
+            match send_request(host, port, &request, timeout, verbose, use_tls).await {
+                Ok((response, _)) => signatures.push(HopSignature::from_response(&response)),
+                Err(_) => continue 'methods,
+            }
+        }
+        let estimate = estimate_hops(&signatures);
+        if estimate.0.is_some() {
+            return estimate;
+        }
+    }
+    (None, None)
+}
+
+/// Offer `h2` and `http/1.1` over ALPN and return the protocol the front door
+/// picks; `None` when the handshake fails or the server ignores ALPN. Connects
+/// directly, so callers skip it when the target is reached through a proxy.
+pub async fn probe_alpn(host: &str, port: u16, timeout: u64) -> Option<String> {
+    let connector = TlsConnector::from(Arc::clone(get_tls_config()));
+    let connector = connector.with_alpn(vec![b"h2".to_vec(), b"http/1.1".to_vec()]);
+    let domain = ServerName::try_from(host.to_string()).ok()?;
+    let handshake = async {
+        let tcp = connect_tcp(host, port).await.ok()?;
+        connector.connect(domain, tcp).await.ok()
+    };
+    let tls = tokio::time::timeout(Duration::from_secs(timeout), handshake)
+        .await
+        .ok()??;
+    tls.get_ref()
+        .1
+        .alpn_protocol()
+        .map(|p| String::from_utf8_lossy(p).into_owned())
+}
+
+/// Whether the front door likely downgrades HTTP/2 to HTTP/1.1 for its
+/// back-end: it negotiates `h2` and the response shows a hop behind it (a
+/// recognized proxy or CDN, a `Via` header, or a Max-Forwards hop count).
+/// Proxies such as nginx only speak HTTP/1.x upstream.
+pub fn h2_downgrade_likely(fingerprint: &FingerprintResult) -> bool {
+    fingerprint.alpn.as_deref() == Some("h2")
+        && (!matches!(fingerprint.detected_proxy, ProxyType::Unknown(_))
+            || fingerprint.via_header.is_some()
+            || fingerprint.hop_count.is_some_and(|hops| hops > 0))
+}
+
+/// Send a GET probe to the target and fingerprint the proxy/server from
+/// response headers, then probe for asterisk-form rewriting, the proxy chain
+/// depth and, on https targets reached directly, the ALPN protocol.
 pub async fn fingerprint_target(
     host: &str,
     port: u16,
@@ -194,6 +385,14 @@ pub async fn fingerprint_target(
         send_request(host, port, &request, timeout, verbose, use_tls).await?;
     let headers = parse_response_headers(&response);
     let detected_proxy = identify_proxy(&headers);
+    let asterisk_rewrite = probe_asterisk_rewrite(host, port, timeout, verbose, use_tls).await;
+    let (hop_count, error_hop) =
+        probe_max_forwards(host, port, path, timeout, verbose, use_tls).await;
+    let alpn = if use_tls && proxy_for(host, use_tls).is_none() {
+        probe_alpn(host, port, timeout).await
+    } else {
+        None
+    };
 
     Ok(FingerprintResult {
         detected_proxy,
@@ -201,6 +400,10 @@ pub async fn fingerprint_target(
         via_header: headers.get("via").cloned(),
         powered_by: headers.get("x-powered-by").cloned(),
         raw_headers: headers,
+        asterisk_rewrite,
+        hop_count,
+        error_hop,
+        alpn,
     })
 }
 
@@ -211,24 +414,533 @@ pub async fn fingerprint_target(
 /// - Varnish: known issues with both CL.TE and TE.CL
 /// - CloudFront: CL.TE has been historically effective
 /// - HAProxy: TE.CL issues have been documented
+///
+/// When an HTTP/2 downgrade is likely (see [`h2_downgrade_likely`]), `h2`
+/// comes first.
 pub fn suggest_checks(fingerprint: &FingerprintResult) -> Vec<&'static str> {
-    match &fingerprint.detected_proxy {
-        ProxyType::Nginx => vec!["cl-te", "te-te", "te-cl", "h2c", "h2", "cl-edge"],
-        ProxyType::Apache => vec!["te-cl", "cl-te", "te-te", "h2c", "h2", "cl-edge"],
-        ProxyType::Varnish => vec!["cl-te", "te-cl", "te-te", "h2c", "h2", "cl-edge"],
-        ProxyType::CloudFront => vec!["cl-te", "te-te", "te-cl", "h2", "h2c", "cl-edge"],
-        ProxyType::Cloudflare => vec!["te-te", "cl-te", "te-cl", "h2", "h2c", "cl-edge"],
-        ProxyType::HAProxy => vec!["te-cl", "cl-te", "te-te", "h2c", "h2", "cl-edge"],
-        ProxyType::Envoy => vec!["cl-te", "te-cl", "te-te", "h2", "h2c", "cl-edge"],
-        ProxyType::ATS => vec!["cl-te", "te-cl", "te-te", "h2c", "h2", "cl-edge"],
-        ProxyType::Squid => vec!["te-cl", "cl-te", "te-te", "h2c", "h2", "cl-edge"],
-        ProxyType::Caddy => vec!["cl-te", "te-cl", "te-te", "h2", "h2c", "cl-edge"],
-        ProxyType::IIS => vec!["te-cl", "cl-te", "te-te", "h2c", "h2", "cl-edge"],
-        ProxyType::Traefik => vec!["cl-te", "te-cl", "te-te", "h2", "h2c", "cl-edge"],
-        ProxyType::Akamai => vec!["cl-te", "te-te", "te-cl", "h2", "h2c", "cl-edge"],
-        ProxyType::Fastly => vec!["cl-te", "te-te", "te-cl", "h2", "h2c", "cl-edge"],
-        ProxyType::Unknown(_) => vec!["cl-te", "te-cl", "te-te", "h2c", "h2", "cl-edge"],
+    let mut checks = match &fingerprint.detected_proxy {
+        ProxyType::Nginx => vec![
+            "cl-te",
+            "te-te",
+            "te-cl",
+            "h2c",
+            "h2",
+            "cl-edge",
+            "cl-cl",
+            "asterisk",
+            "connection",
+            "head",
+        ],
+        ProxyType::Apache => vec![
+            "te-cl",
+            "cl-te",
+            "te-te",
+            "h2c",
+            "h2",
+            "cl-edge",
+            "cl-cl",
+            "asterisk",
+            "connection",
+            "head",
+        ],
+        ProxyType::Varnish => vec![
+            "cl-te",
+            "te-cl",
+            "te-te",
+            "h2c",
+            "h2",
+            "cl-edge",
+            "cl-cl",
+            "asterisk",
+            "connection",
+            "head",
+        ],
+        ProxyType::CloudFront => vec![
+            "cl-te",
+            "te-te",
+            "te-cl",
+            "h2",
+            "h2c",
+            "cl-edge",
+            "cl-cl",
+            "asterisk",
+            "connection",
+            "head",
+        ],
+        ProxyType::Cloudflare => vec![
+            "te-te",
+            "cl-te",
+            "te-cl",
+            "h2",
+            "h2c",
+            "cl-edge",
+            "cl-cl",
+            "asterisk",
+            "connection",
+            "head",
+        ],
+        ProxyType::HAProxy => vec![
+            "te-cl",
+            "cl-te",
+            "te-te",
+            "h2c",
+            "h2",
+            "cl-edge",
+            "cl-cl",
+            "asterisk",
+            "connection",
+            "head",
+        ],
+        ProxyType::Envoy => vec![
+            "cl-te",
+            "te-cl",
+            "te-te",
+            "h2",
+            "h2c",
+            "cl-edge",
+            "cl-cl",
+            "asterisk",
+            "connection",
+            "head",
+        ],
+        ProxyType::ATS => vec![
+            "cl-te",
+            "te-cl",
+            "te-te",
+            "h2c",
+            "h2",
+            "cl-edge",
+            "cl-cl",
+            "asterisk",
+            "connection",
+            "head",
+        ],
+        ProxyType::Squid => vec![
+            "te-cl",
+            "cl-te",
+            "te-te",
+            "h2c",
+            "h2",
+            "cl-edge",
+            "cl-cl",
+            "asterisk",
+            "connection",
+            "head",
+        ],
+        ProxyType::Caddy => vec![
+            "cl-te",
+            "te-cl",
+            "te-te",
+            "h2",
+            "h2c",
+            "cl-edge",
+            "cl-cl",
+            "asterisk",
+            "connection",
+            "head",
+        ],
+        ProxyType::IIS => vec![
+            "te-cl",
+            "cl-te",
+            "te-te",
+            "h2c",
+            "h2",
+            "cl-edge",
+            "cl-cl",
+            "asterisk",
+            "connection",
+            "head",
+        ],
+        ProxyType::Traefik => vec![
+            "cl-te",
+            "te-cl",
+            "te-te",
+            "h2",
+            "h2c",
+            "cl-edge",
+            "cl-cl",
+            "asterisk",
+            "connection",
+            "head",
+        ],
+        ProxyType::Akamai => vec![
+            "cl-te",
+            "te-te",
+            "te-cl",
+            "h2",
+            "h2c",
+            "cl-edge",
+            "cl-cl",
+            "asterisk",
+            "connection",
+            "head",
+        ],
+        ProxyType::Fastly => vec![
+            "cl-te",
+            "te-te",
+            "te-cl",
+            "h2",
+            "h2c",
+            "cl-edge",
+            "cl-cl",
+            "asterisk",
+            "connection",
+            "head",
+        ],
+        ProxyType::Unknown(_) => vec![
+            "cl-te",
+            "te-cl",
+            "te-te",
+            "h2c",
+            "h2",
+            "cl-edge",
+            "cl-cl",
+            "asterisk",
+            "connection",
+            "head",
+        ],
+    };
+    if h2_downgrade_likely(fingerprint) {
+        checks.retain(|check| *check != "h2");
+        checks.insert(0, "h2");
+    }
+    checks
+}
+
+/// Check order adapted to what the pre-scan probes found, with the reasons.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckOrder {
+    /// Every payload check, most likely first
+    pub checks: Vec<&'static str>,
+    /// Why checks were moved, e.g. `te-te first: obfuscated Transfer-Encoding
+    /// reaches the echoing hop verbatim`
+    pub reasons: Vec<String>,
+}
+
+/// Whether an obfuscated spelling of the framing header `name` (other than
+/// `plain`) reached the TRACE-echoing hop verbatim; `None` when none was
+/// probed.
+fn obfuscation_survives(trace: &TraceReport, name: &str, plain: &str) -> Option<bool> {
+    let mut fates = trace
+        .variants
+        .iter()
+        .filter(|v| v.header != plain && v.header.to_ascii_lowercase().contains(name))
+        .map(|v| &v.fate)
+        .peekable();
+    fates.peek()?;
+    Some(fates.any(|fate| *fate == TraceFate::Verbatim))
+}
+
+/// Order the payload checks by what the fingerprint and TRACE probes found,
+/// starting from [`suggest_checks`] (or the default order without a
+/// fingerprint). Checks whose precondition was observed move to the front
+/// and checks the front-end is seen to defeat move to the back; the rest keep
+/// their relative order:
+/// - `h2` first when an h2 downgrade is likely, last when ALPN shows the
+///   front door does not speak h2
+/// - `asterisk` first when a hop rewrites `OPTIONS *`
+/// - `te-te` / `cl-edge` first when an obfuscated `Transfer-Encoding` /
+///   `Content-Length` spelling reaches the echoing hop verbatim, last when
+///   every spelling is normalized, stripped or rejected on the way
+pub fn adaptive_check_order(
+    fingerprint: Option<&FingerprintResult>,
+    trace: Option<&TraceReport>,
+) -> CheckOrder {
+    let base: Vec<&'static str> = match fingerprint {
+        Some(fp) => suggest_checks(fp),
+        None => crate::payloads::PAYLOAD_FAMILIES
+            .iter()
+            .map(|f| f.check)
+            .collect(),
+    };
+    let mut first: Vec<(&'static str, &'static str)> = Vec::new();
+    let mut last: Vec<(&'static str, &'static str)> = Vec::new();
+
+    if let Some(fp) = fingerprint {
+        if h2_downgrade_likely(fp) {
+            first.push((
+                "h2",
+                "the front door negotiates h2 in front of an HTTP/1.1 hop",
+            ));
+        } else if fp.alpn.as_deref().is_some_and(|alpn| alpn != "h2") {
+            last.push(("h2", "the front door does not negotiate h2"));
+        }
+        if fp.asterisk_rewrite {
+            first.push(("asterisk", "a hop rewrites OPTIONS * to /"));
+        }
+    }
+    if let Some(trace) = trace {
+        for (check, name, plain, survives, defeated) in [
+            (
+                "te-te",
+                "transfer-encoding",
+                "Transfer-Encoding: chunked",
+                "obfuscated Transfer-Encoding reaches the echoing hop verbatim",
+                "every obfuscated Transfer-Encoding is normalized, stripped or rejected",
+            ),
+            (
+                "cl-edge",
+                "content-length",
+                "Content-Length: 0",
+                "malformed Content-Length reaches the echoing hop verbatim",
+                "every malformed Content-Length is normalized, stripped or rejected",
+            ),
+        ] {
+            match obfuscation_survives(trace, name, plain) {
+                Some(true) => first.push((check, survives)),
+                Some(false) => last.push((check, defeated)),
+                None => {}
+            }
+        }
+    }
+
+    let tier = |check: &str| {
+        if first.iter().any(|(c, _)| *c == check) {
+            0
+        } else if last.iter().any(|(c, _)| *c == check) {
+            2
+        } else {
+            1
+        }
+    };
+    let mut checks = base;
+    checks.sort_by_key(|check| tier(check));
+    let reasons = first
+        .iter()
+        .map(|(check, why)| format!("{} first: {}", check, why))
+        .chain(
+            last.iter()
+                .map(|(check, why)| format!("{} last: {}", check, why)),
+        )
+        .collect();
+    CheckOrder { checks, reasons }
+}
+
+/// Response headers only a caching layer adds.
+const CACHE_HEADERS: &[&str] = &[
+    "age",
+    "x-cache",
+    "x-cache-hits",
+    "x-cache-status",
+    "cf-cache-status",
+    "x-varnish",
+    "x-proxy-cache",
+];
+
+/// Whether the response came through a caching layer: a caching proxy or CDN
+/// was identified, or a cache-status header is present.
+pub fn behind_cache(fingerprint: &FingerprintResult) -> bool {
+    matches!(
+        fingerprint.detected_proxy,
+        ProxyType::Varnish
+            | ProxyType::CloudFront
+            | ProxyType::Cloudflare
+            | ProxyType::ATS
+            | ProxyType::Squid
+            | ProxyType::Akamai
+            | ProxyType::Fastly
+    ) || CACHE_HEADERS
+        .iter()
+        .any(|h| fingerprint.raw_headers.contains_key(*h))
+}
+
+/// Product names recognized in chain headers, checked in order against the
+/// lowercased text: the first substring found names the product.
+const CHAIN_PRODUCTS: &[(&str, &str)] = &[
+    ("cloudfront", "cloudfront"),
+    ("cloudflare", "cloudflare"),
+    ("akamai", "akamai"),
+    ("fastly", "fastly"),
+    ("varnish", "varnish"),
+    ("squid", "squid"),
+    ("trafficserver", "ats"),
+    ("ats/", "ats"),
+    ("haproxy", "haproxy"),
+    ("envoy", "envoy"),
+    ("traefik", "traefik"),
+    ("caddy", "caddy"),
+    ("nginx", "nginx"),
+    ("openresty", "nginx"),
+    ("microsoft-iis", "iis"),
+    ("apache", "apache"),
+    ("httpd", "apache"),
+];
+
+/// Known product named anywhere in `text`.
+fn chain_product(text: &str) -> Option<&'static str> {
+    let lower = text.to_ascii_lowercase();
+    CHAIN_PRODUCTS
+        .iter()
+        .find(|(needle, _)| lower.contains(needle))
+        .map(|&(_, product)| product)
+}
+
+/// Split a `Via` value into its entries, ignoring commas inside comments.
+fn via_entries(via: &str) -> Vec<&str> {
+    let mut entries = Vec::new();
+    let (mut depth, mut start) = (0usize, 0);
+    for (i, c) in via.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                entries.push(via[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    entries.push(via[start..].trim());
+    entries.retain(|e| !e.is_empty());
+    entries
+}
+
+/// Product of one `Via` entry (`1.1 host (comment)`): a known product in the
+/// comment or received-by token, else the received-by token without its port.
+fn via_product(entry: &str) -> Option<String> {
+    let received_by = entry.split_whitespace().nth(1)?;
+    if let Some(product) = chain_product(entry) {
+        return Some(product.to_string());
+    }
+    let name = received_by.split(':').next().unwrap_or(received_by);
+    Some(name.to_ascii_lowercase())
+}
+
+/// Product of a `Server` value: a known product, else the first token without
+/// its version.
+fn server_product(server: &str) -> Option<String> {
+    if let Some(product) = chain_product(server) {
+        return Some(product.to_string());
     }
+    let token = server.split_whitespace().next()?;
+    let name = token.split('/').next().unwrap_or(token);
+    (!name.is_empty()).then(|| name.to_ascii_lowercase())
+}
+
+/// Reconstruct the proxy chain a response passed through, client side first:
+/// CDN edge headers (`CF-Ray`, `X-Amz-Cf-Id`, `X-Served-By`, Akamai
+/// headers), then the `Via` entries reversed (each hop appends its own on the
+/// way back), then cache headers (`X-Varnish`, `X-Cache`), then `Server` as
+/// the final responder. Each product appears once, at its first position.
+pub fn parse_proxy_chain(response: &str) -> Vec<ProxyHop> {
+    // Repeated headers are joined as the comma-separated list they stand for.
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for (key, value) in HttpResponse::parse(response.as_bytes()).headers {
+        let key = key.to_ascii_lowercase();
+        match headers.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, joined)) => {
+                joined.push_str(", ");
+                joined.push_str(&value);
+            }
+            None => headers.push((key, value)),
+        }
+    }
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    };
+
+    let mut candidates: Vec<(String, &str, &str)> = Vec::new();
+    for (name, product) in [
+        ("cf-ray", "cloudflare"),
+        ("x-amz-cf-pop", "cloudfront"),
+        ("x-amz-cf-id", "cloudfront"),
+        ("akamai-grn", "akamai"),
+        ("x-akamai-transformed", "akamai"),
+    ] {
+        if let Some(value) = header(name) {
+            candidates.push((product.to_string(), name, value));
+        }
+    }
+    if let Some(value) = header("x-served-by")
+        && value.contains("cache-")
+    {
+        candidates.push(("fastly".to_string(), "x-served-by", value));
+    }
+    if let Some(via) = header("via") {
+        for entry in via_entries(via).into_iter().rev() {
+            if let Some(product) = via_product(entry) {
+                candidates.push((product, "via", entry));
+            }
+        }
+    }
+    if let Some(value) = header("x-varnish") {
+        candidates.push(("varnish".to_string(), "x-varnish", value));
+    }
+    if let Some(value) = header("x-cache")
+        && let Some(product) = chain_product(value)
+    {
+        candidates.push((product.to_string(), "x-cache", value));
+    }
+    if let Some(value) = header("server")
+        && let Some(product) = server_product(value)
+    {
+        candidates.push((product, "server", value));
+    }
+
+    let mut chain: Vec<ProxyHop> = Vec::new();
+    for (product, source, detail) in candidates {
+        if chain.iter().all(|hop| hop.product != product) {
+            chain.push(ProxyHop {
+                product,
+                source: source.to_string(),
+                detail: detail.to_string(),
+            });
+        }
+    }
+    chain
+}
+
+/// Merge a response's chain into `chain`. Known hops keep their position; a
+/// new hop is inserted right after the hop preceding it in `hops`, so partial
+/// chains (an edge error page naming only the CDN) never reorder the rest.
+pub fn merge_proxy_chain(chain: &mut Vec<ProxyHop>, hops: Vec<ProxyHop>) {
+    let mut cursor = 0;
+    for hop in hops {
+        match chain.iter().position(|known| known.product == hop.product) {
+            Some(i) => cursor = cursor.max(i + 1),
+            None => {
+                chain.insert(cursor, hop);
+                cursor += 1;
+            }
+        }
+    }
+}
+
+tokio::task_local! {
+    static OBSERVED_CHAIN: RefCell<Vec<ProxyHop>>;
+}
+
+/// Fold `response` into the chain of the enclosing [`observe_proxy_chain`]
+/// scope; a no-op outside one.
+pub fn record_proxy_chain(response: &str) {
+    let _ = OBSERVED_CHAIN.try_with(|chain| {
+        merge_proxy_chain(&mut chain.borrow_mut(), parse_proxy_chain(response));
+    });
+}
+
+/// Chain observed so far in the enclosing [`observe_proxy_chain`] scope.
+pub fn observed_proxy_chain() -> Vec<ProxyHop> {
+    OBSERVED_CHAIN
+        .try_with(|chain| chain.borrow().clone())
+        .unwrap_or_default()
+}
+
+/// Run `scan` with every response it receives folded into one proxy chain,
+/// read back with [`observed_proxy_chain`].
+pub fn observe_proxy_chain<F: Future>(scan: F) -> impl Future<Output = F::Output> {
+    OBSERVED_CHAIN.scope(RefCell::new(Vec::new()), scan)
+}
+
+/// Render a chain as `cloudflare -> varnish -> nginx`.
+pub fn format_proxy_chain(chain: &[ProxyHop]) -> String {
+    chain
+        .iter()
+        .map(|hop| hop.product.as_str())
+        .collect::<Vec<_>>()
+        .join(" -> ")
 }
 
 #[cfg(test)]
@@ -257,6 +969,39 @@ mod tests {
         assert_eq!(headers.len(), 1);
     }
 
+    #[test]
+    fn asterisk_rewritten_needs_root_match_and_distinct_missing_path() {
+        let root = "HTTP/1.1 200 OK\r\nAllow: GET, HEAD\r\nContent-Length: 4\r\n\r\nhome";
+        let missing = "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n";
+        let native =
+            "HTTP/1.1 200 OK\r\nAllow: GET, HEAD, POST, OPTIONS\r\nContent-Length: 0\r\n\r\n";
+        assert!(asterisk_rewritten(root, root, missing));
+        assert!(!asterisk_rewritten(native, root, missing));
+        // Every path answered identically: no signal.
+        assert!(!asterisk_rewritten(root, root, root));
+    }
+
+    #[test]
+    fn estimate_hops_counts_until_the_final_responder() {
+        let hop = |status: u16, server: &str| HopSignature {
+            status: Some(status),
+            server: Some(server.to_string()),
+            via: None,
+        };
+        // CDN answers at 0, a 405 from the load balancer at 1, origin from 2.
+        let chain = [
+            hop(200, "cdn"),
+            hop(405, "lb"),
+            hop(200, "origin"),
+            hop(200, "origin"),
+        ];
+        assert_eq!(estimate_hops(&chain), (Some(2), Some(1)));
+        // Nobody honors Max-Forwards.
+        let flat = [hop(200, "origin"), hop(200, "origin")];
+        assert_eq!(estimate_hops(&flat), (None, None));
+        assert_eq!(estimate_hops(&[]), (None, None));
+    }
+
     #[test]
     fn test_identify_nginx() {
         let mut headers = HashMap::new();
@@ -313,6 +1058,10 @@ mod tests {
             via_header: None,
             powered_by: None,
             raw_headers: HashMap::new(),
+            asterisk_rewrite: false,
+            hop_count: None,
+            error_hop: None,
+            alpn: None,
         };
         let checks = suggest_checks(&fp);
         assert_eq!(checks[0], "cl-te");
@@ -326,6 +1075,10 @@ mod tests {
             via_header: None,
             powered_by: None,
             raw_headers: HashMap::new(),
+            asterisk_rewrite: false,
+            hop_count: None,
+            error_hop: None,
+            alpn: None,
         };
         let checks = suggest_checks(&fp);
         assert_eq!(checks[0], "te-cl");
@@ -339,9 +1092,13 @@ mod tests {
             via_header: None,
             powered_by: None,
             raw_headers: HashMap::new(),
+            asterisk_rewrite: false,
+            hop_count: None,
+            error_hop: None,
+            alpn: None,
         };
         let checks = suggest_checks(&fp);
-        assert_eq!(checks.len(), 6);
+        assert_eq!(checks.len(), 10);
     }
 
     #[test]
@@ -352,6 +1109,10 @@ mod tests {
             via_header: Some("1.1 varnish".to_string()),
             powered_by: Some("Express".to_string()),
             raw_headers: HashMap::new(),
+            asterisk_rewrite: false,
+            hop_count: None,
+            error_hop: None,
+            alpn: None,
         };
         let display = format!("{}", fp);
         assert!(display.contains("Nginx"));
@@ -439,4 +1200,46 @@ mod tests {
         headers.insert("server".to_string(), "ATS/9.2.0".to_string());
         assert_eq!(identify_proxy(&headers), ProxyType::ATS);
     }
+
+    #[test]
+    fn parse_proxy_chain_orders_edge_via_and_server() {
+        let response = "HTTP/1.1 200 OK\r\n\
+                        Server: nginx/1.24.0\r\n\
+                        Via: 1.1 varnish (Varnish/6.0)\r\n\
+                        Via: 1.1 abc.cloudfront.net (CloudFront)\r\n\
+                        X-Amz-Cf-Pop: LAX50-C1\r\n\
+                        X-Cache: Miss from cloudfront\r\n\
+                        \r\n\
+                        Via: 1.1 body-only\r\n";
+        let chain = parse_proxy_chain(response);
+        let products: Vec<&str> = chain.iter().map(|h| h.product.as_str()).collect();
+        assert_eq!(products, ["cloudfront", "varnish", "nginx"]);
+        assert_eq!(chain[0].source, "x-amz-cf-pop");
+        assert_eq!(chain[1].detail, "1.1 varnish (Varnish/6.0)");
+        assert_eq!(format_proxy_chain(&chain), "cloudfront -> varnish -> nginx");
+
+        let chain = parse_proxy_chain(
+            "HTTP/1.1 200 OK\r\nVia: 1.1 edge-7:8080\r\nServer: gunicorn/20.1\r\n\r\n",
+        );
+        let products: Vec<&str> = chain.iter().map(|h| h.product.as_str()).collect();
+        assert_eq!(products, ["edge-7", "gunicorn"]);
+    }
+
+    #[test]
+    fn merge_proxy_chain_inserts_new_hops_in_place() {
+        let hop = |product: &str| ProxyHop {
+            product: product.to_string(),
+            source: "via".to_string(),
+            detail: String::new(),
+        };
+        let mut chain = vec![hop("cloudflare"), hop("nginx")];
+        merge_proxy_chain(&mut chain, vec![hop("cloudflare")]);
+        merge_proxy_chain(
+            &mut chain,
+            vec![hop("cloudflare"), hop("varnish"), hop("nginx")],
+        );
+        merge_proxy_chain(&mut chain, vec![hop("nginx"), hop("gunicorn")]);
+        let products: Vec<&str> = chain.iter().map(|h| h.product.as_str()).collect();
+        assert_eq!(products, ["cloudflare", "varnish", "nginx", "gunicorn"]);
+    }
 }
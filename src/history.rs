@@ -0,0 +1,336 @@
+//! `--history-dir DIR`: keep the results of every run, one file per scanned
+//! target, with an index to answer "when did this first appear?" without
+//! opening every file.
+//!
+//! ```text
+//! DIR/
+//!   index.jsonl                          one line per stored result
+//!   target.com/20240501T091244Z.json     a target's results, as `-o` saves them
+//!   target.com_8443/20240501T091301Z.json
+//! ```
+//!
+//! Files are never overwritten or pruned; the index is only appended to, so
+//! concurrent runs sharing a directory do not lose each other's entries.
+
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::error::{Result, SmugglexError};
+use crate::model::ScanResults;
+
+/// Name of the index file in the history directory.
+pub const INDEX_FILE: &str = "index.jsonl";
+
+/// One stored result, as a line of [`INDEX_FILE`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Target URL
+    pub target: String,
+    /// ISO 8601 timestamp of the scan
+    pub timestamp: String,
+    /// Results file, relative to the history directory
+    pub file: String,
+    /// Whether any check was vulnerable
+    pub vulnerable: bool,
+    /// Findings as `check/payload-name` (`check/#index` without a name)
+    #[serde(default)]
+    pub findings: Vec<String>,
+    /// Why the scan failed, if it did
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// `path` with a leading `~/` replaced by the home directory, for values
+/// that did not pass through a shell (`SMUGGLEX_HISTORY_DIR`).
+pub fn expand_home(path: &Path) -> PathBuf {
+    match path.strip_prefix("~") {
+        Ok(rest) => std::env::var_os("HOME")
+            .or_else(|| std::env::var_os("USERPROFILE"))
+            .map(|home| PathBuf::from(home).join(rest))
+            .unwrap_or_else(|| path.to_path_buf()),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+/// Directory a target's results are kept in: its host, followed by
+/// `_<port>` when the port is not the scheme's default. Characters unsafe in
+/// a file name become `_`.
+pub fn host_dir_name(target: &str) -> String {
+    let name = match Url::parse(target) {
+        Ok(url) if url.host_str().is_some() => {
+            let host = url
+                .host_str()
+                .unwrap_or_default()
+                .trim_start_matches('[')
+                .trim_end_matches(']');
+            match url.port() {
+                Some(port) => format!("{}_{}", host, port),
+                None => host.to_string(),
+            }
+        }
+        _ => target.to_string(),
+    };
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>()
+        .trim_start_matches('.')
+        .to_string()
+}
+
+/// `scan`'s timestamp in a file-name-safe form (`20240501T091244Z`), or now
+/// when it does not parse.
+fn file_stamp(timestamp: &str) -> String {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or_else(|_| Utc::now())
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+/// Store `scan` under `dir` and append its entry to the index. A result
+/// whose timestamp matches one already stored for the host gets a `-2`,
+/// `-3`, ... suffix instead of replacing it.
+pub fn record_results(dir: &Path, scan: &ScanResults) -> Result<HistoryEntry> {
+    let host = host_dir_name(&scan.target);
+    let host_dir = dir.join(&host);
+    fs::create_dir_all(&host_dir)?;
+    let stamp = file_stamp(&scan.timestamp);
+    let json = serde_json::to_string_pretty(scan)?;
+    let mut attempt = 1;
+    let name = loop {
+        let name = if attempt == 1 {
+            format!("{}.json", stamp)
+        } else {
+            format!("{}-{}.json", stamp, attempt)
+        };
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(host_dir.join(&name))
+        {
+            Ok(mut file) => {
+                file.write_all(json.as_bytes())?;
+                break name;
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => attempt += 1,
+            Err(e) => return Err(e.into()),
+        }
+    };
+
+    let findings = scan
+        .checks
+        .iter()
+        .filter(|c| c.vulnerable)
+        .map(|c| match (&c.payload_name, c.payload_index) {
+            (Some(name), _) => format!("{}/{}", c.check_type, name),
+            (None, Some(index)) => format!("{}/#{}", c.check_type, index),
+            (None, None) => c.check_type.clone(),
+        })
+        .collect::<Vec<_>>();
+    let entry = HistoryEntry {
+        target: scan.target.clone(),
+        timestamp: scan.timestamp.clone(),
+        file: format!("{}/{}", host, name),
+        vulnerable: !findings.is_empty(),
+        findings,
+        error: scan.error.clone(),
+    };
+    // One write per line, so appends from concurrent runs do not interleave.
+    let mut line = serde_json::to_string(&entry)?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(INDEX_FILE))?
+        .write_all(line.as_bytes())?;
+    Ok(entry)
+}
+
+/// The entries of the index in `dir`, oldest first; empty when there is no
+/// index yet.
+pub fn read_index(dir: &Path) -> Result<Vec<HistoryEntry>> {
+    let content = match fs::read_to_string(dir.join(INDEX_FILE)) {
+        Ok(content) => content,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .map_err(|e| SmugglexError::Parse(format!("{} line {}: {}", INDEX_FILE, i + 1, e)))
+        })
+        .collect()
+}
+
+/// How one finding of a target fared across its stored scans.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FindingTrend {
+    /// Finding as `check/payload-name`
+    pub finding: String,
+    /// Timestamp of the first scan that found it
+    pub first_seen: String,
+    /// Timestamp of the last scan that found it
+    pub last_seen: String,
+    /// Timestamp of the first scan after `last_seen` that no longer found
+    /// it; `None` while the latest scan still does
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fixed: Option<String>,
+    /// Whether it went missing and came back at least once
+    pub flapping: bool,
+    /// Scans that found it
+    pub detections: usize,
+}
+
+/// A target's stored scans and the findings they reported.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TargetStats {
+    pub target: String,
+    /// Scans that completed
+    pub scans: usize,
+    /// Scans that failed, which count neither for nor against a finding
+    pub failed_scans: usize,
+    pub first_scan: String,
+    pub last_scan: String,
+    /// Findings in the order they first appeared
+    pub findings: Vec<FindingTrend>,
+}
+
+/// Findings of one technique (the check that found them) across targets.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TechniqueStats {
+    pub technique: String,
+    /// Distinct findings, counted once per target
+    pub findings: usize,
+    /// Findings the latest scan of their target still reports
+    pub open: usize,
+    pub fixed: usize,
+    /// Scans, over all targets, that reported a finding of the technique
+    pub detections: usize,
+}
+
+/// `smugglex stats`: findings over time per target and per technique.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HistoryStats {
+    /// Stored results, failed scans included
+    pub scans: usize,
+    pub targets: Vec<TargetStats>,
+    /// Techniques with the most findings first
+    pub techniques: Vec<TechniqueStats>,
+}
+
+/// Summarize the `entries` of a history index.
+pub fn history_stats(entries: &[HistoryEntry]) -> HistoryStats {
+    let time = |entry: &HistoryEntry| DateTime::parse_from_rfc3339(&entry.timestamp).ok();
+    let mut targets: Vec<(&str, Vec<&HistoryEntry>)> = Vec::new();
+    for entry in entries {
+        match targets
+            .iter_mut()
+            .find(|(target, _)| *target == entry.target)
+        {
+            Some((_, scans)) => scans.push(entry),
+            None => targets.push((&entry.target, vec![entry])),
+        }
+    }
+
+    let mut target_stats = Vec::new();
+    for (target, mut scans) in targets {
+        scans.sort_by_key(|entry| time(entry));
+        let completed: Vec<&HistoryEntry> = scans
+            .iter()
+            .copied()
+            .filter(|entry| entry.error.is_none())
+            .collect();
+        let mut findings: Vec<FindingTrend> = Vec::new();
+        for scan in &completed {
+            for finding in &scan.findings {
+                if findings.iter().any(|f| &f.finding == finding) {
+                    continue;
+                }
+                let present: Vec<bool> = completed
+                    .iter()
+                    .map(|s| s.findings.contains(finding))
+                    .collect();
+                let first = present.iter().position(|&p| p).unwrap_or_default();
+                let last = present.iter().rposition(|&p| p).unwrap_or_default();
+                findings.push(FindingTrend {
+                    finding: finding.clone(),
+                    first_seen: completed[first].timestamp.clone(),
+                    last_seen: completed[last].timestamp.clone(),
+                    fixed: completed.get(last + 1).map(|s| s.timestamp.clone()),
+                    flapping: present[first..=last].contains(&false),
+                    detections: present.iter().filter(|&&p| p).count(),
+                });
+            }
+        }
+        target_stats.push(TargetStats {
+            target: target.to_string(),
+            scans: completed.len(),
+            failed_scans: scans.len() - completed.len(),
+            first_scan: scans
+                .first()
+                .map(|s| s.timestamp.clone())
+                .unwrap_or_default(),
+            last_scan: scans
+                .last()
+                .map(|s| s.timestamp.clone())
+                .unwrap_or_default(),
+            findings,
+        });
+    }
+
+    let mut techniques: Vec<TechniqueStats> = Vec::new();
+    for finding in target_stats.iter().flat_map(|t| &t.findings) {
+        let technique = finding
+            .finding
+            .split('/')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        let index = match techniques.iter().position(|t| t.technique == technique) {
+            Some(index) => index,
+            None => {
+                techniques.push(TechniqueStats {
+                    technique,
+                    findings: 0,
+                    open: 0,
+                    fixed: 0,
+                    detections: 0,
+                });
+                techniques.len() - 1
+            }
+        };
+        let stats = &mut techniques[index];
+        stats.findings += 1;
+        stats.detections += finding.detections;
+        if finding.fixed.is_some() {
+            stats.fixed += 1;
+        } else {
+            stats.open += 1;
+        }
+    }
+    techniques.sort_by(|a, b| {
+        b.findings
+            .cmp(&a.findings)
+            .then_with(|| a.technique.cmp(&b.technique))
+    });
+
+    HistoryStats {
+        scans: entries.len(),
+        targets: target_stats,
+        techniques,
+    }
+}
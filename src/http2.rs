@@ -14,16 +14,23 @@
 //! the downgraded back-end wait for body bytes that never arrive, so the stream
 //! stalls until the timeout. A well-formed control request rules out a backend
 //! that is simply slow for this shape.
+//!
+//! With `--fuzz`, the check then mutates the request at the frame level (HPACK
+//! indexes, header block size, padding, CONTINUATION splits, stream IDs) —
+//! inputs string payloads cannot express — and looks for a stalled stream or
+//! a follow-up request that gets another request's response.
 
+use std::collections::HashSet;
 use std::time::{Duration, Instant};
 
 use chrono::Utc;
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
-use tokio::net::TcpStream;
-use tokio_rustls::TlsConnector;
 
 use crate::error::Result;
-use crate::model::{CheckResult, Confidence};
+use crate::model::{CheckResult, Confidence, FuzzAnomaly, H2Observation, H2Termination};
+use crate::mutator::{Mutator, MutatorConfig, payload_diff};
+use crate::scanner::MAX_FUZZ_ANOMALIES;
+use crate::transport::TransportKind;
 
 /// HTTP/2 client connection preface (RFC 9113 §3.4).
 const PREFACE: &[u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
@@ -43,6 +50,12 @@ const FLAG_END_HEADERS: u8 = 0x4;
 /// Number of attack confirmation retries that must also stall.
 const H2_CONFIRMATION_RETRIES: usize = 2;
 
+/// SETTINGS_INITIAL_WINDOW_SIZE (RFC 9113 §6.5.2).
+const SETTINGS_INITIAL_WINDOW_SIZE: u16 = 0x4;
+
+/// Most distinct [`H2Observation`]s kept per check.
+const MAX_H2_OBSERVATIONS: usize = 20;
+
 // ----------------------------- HPACK encoding ------------------------------
 
 /// Encode an HPACK integer (RFC 7541 §5.1) into `out`. `prefix` carries the
@@ -114,30 +127,45 @@ struct H2Request<'a> {
 
 impl H2Request<'_> {
     fn header_block(&self) -> Vec<u8> {
-        let mut b = Vec::new();
+        self.fields().concat()
+    }
+
+    /// The header block as separately encoded fields, so the frame fuzzer can
+    /// splice and split at field boundaries. The first [`PSEUDO_FIELDS`] are
+    /// the pseudo-headers.
+    fn fields(&self) -> Vec<Vec<u8>> {
+        let field = |f: HField| {
+            let mut b = Vec::new();
+            encode_field(&mut b, &f);
+            b
+        };
+        let mut fields = Vec::new();
         // Pseudo-headers first.
-        match self.method {
-            "GET" => encode_field(&mut b, &HField::Indexed(2)),
-            "POST" => encode_field(&mut b, &HField::Indexed(3)),
-            m => encode_field(&mut b, &HField::NamedValue(2, m.as_bytes())),
-        }
-        encode_field(&mut b, &HField::Indexed(7)); // :scheme https
+        fields.push(match self.method {
+            "GET" => field(HField::Indexed(2)),
+            "POST" => field(HField::Indexed(3)),
+            m => field(HField::NamedValue(2, m.as_bytes())),
+        });
+        fields.push(field(HField::Indexed(7))); // :scheme https
         if self.path == "/" {
-            encode_field(&mut b, &HField::Indexed(4));
+            fields.push(field(HField::Indexed(4)));
         } else {
-            encode_field(&mut b, &HField::NamedValue(4, self.path.as_bytes()));
+            fields.push(field(HField::NamedValue(4, self.path.as_bytes())));
         }
-        encode_field(&mut b, &HField::NamedValue(1, self.authority.as_bytes())); // :authority
+        fields.push(field(HField::NamedValue(1, self.authority.as_bytes()))); // :authority
         if let Some(cl) = self.content_length {
-            encode_field(&mut b, &HField::NamedValue(28, cl.as_bytes())); // content-length
+            fields.push(field(HField::NamedValue(28, cl.as_bytes()))); // content-length
         }
         for (name, value) in &self.extra {
-            encode_field(&mut b, &HField::NewName(name.as_bytes(), value.as_bytes()));
+            fields.push(field(HField::NewName(name.as_bytes(), value.as_bytes())));
         }
-        b
+        fields
     }
 }
 
+/// Number of pseudo-header fields at the start of [`H2Request::fields`].
+const PSEUDO_FIELDS: usize = 4;
+
 // ----------------------------- frame I/O -----------------------------------
 
 fn put_frame(out: &mut Vec<u8>, ftype: u8, flags: u8, stream: u32, payload: &[u8]) {
@@ -165,12 +193,84 @@ struct H2Outcome {
     responded: bool,
     /// Decoded `:status`, if it was a simple static-table reference.
     status: Option<u16>,
-    /// The peer reset our stream or closed the connection (a fast rejection,
-    /// distinct from a stall).
-    reset: bool,
+    /// How the peer ended the exchange without a response: a fast rejection
+    /// (GOAWAY, RST_STREAM, a closed connection) or a stall flow control
+    /// explains. `None` for a response or a genuine stall.
+    termination: Option<H2Termination>,
+    /// Error code of the GOAWAY or RST_STREAM.
+    error_code: Option<u32>,
     duration: Duration,
 }
 
+impl H2Outcome {
+    /// The peer ended the exchange without answering.
+    fn ended(termination: H2Termination, error_code: Option<u32>) -> Self {
+        Self {
+            responded: false,
+            status: None,
+            termination: Some(termination),
+            error_code,
+            duration: Duration::ZERO,
+        }
+    }
+}
+
+/// RFC 9113 §7 name of an HTTP/2 error code.
+fn error_code_name(code: u32) -> String {
+    let name = match code {
+        0x0 => "NO_ERROR",
+        0x1 => "PROTOCOL_ERROR",
+        0x2 => "INTERNAL_ERROR",
+        0x3 => "FLOW_CONTROL_ERROR",
+        0x4 => "SETTINGS_TIMEOUT",
+        0x5 => "STREAM_CLOSED",
+        0x6 => "FRAME_SIZE_ERROR",
+        0x7 => "REFUSED_STREAM",
+        0x8 => "CANCEL",
+        0x9 => "COMPRESSION_ERROR",
+        0xa => "CONNECT_ERROR",
+        0xb => "ENHANCE_YOUR_CALM",
+        0xc => "INADEQUATE_SECURITY",
+        0xd => "HTTP_1_1_REQUIRED",
+        _ => return format!("0x{:x}", code),
+    };
+    name.to_string()
+}
+
+/// Record how the peer ended `payload`'s probe, once per distinct
+/// observation; responses and genuine stalls are not recorded.
+fn record_observation(
+    observations: &mut Vec<H2Observation>,
+    payload: &str,
+    outcome: &H2Outcome,
+    verbose: bool,
+) {
+    let Some(termination) = outcome.termination else {
+        return;
+    };
+    let observation = H2Observation {
+        payload: payload.to_string(),
+        termination,
+        error_code: outcome.error_code.map(error_code_name),
+    };
+    if observations.len() >= MAX_H2_OBSERVATIONS || observations.contains(&observation) {
+        return;
+    }
+    if verbose {
+        println!(
+            "  [*] h2-downgrade {}: {}{}",
+            payload,
+            termination,
+            observation
+                .error_code
+                .as_deref()
+                .map(|code| format!(" ({})", code))
+                .unwrap_or_default()
+        );
+    }
+    observations.push(observation);
+}
+
 /// Map a fully-indexed `:status` HPACK byte to its code (static table 8..14).
 fn status_from_indexed(byte: u8) -> Option<u16> {
     match byte {
@@ -185,14 +285,6 @@ fn status_from_indexed(byte: u8) -> Option<u16> {
     }
 }
 
-async fn h2_connect(host: &str, port: u16) -> Result<tokio_rustls::client::TlsStream<TcpStream>> {
-    let connector = TlsConnector::from(std::sync::Arc::clone(crate::http::get_h2_tls_config()));
-    let tcp = TcpStream::connect((host, port)).await?;
-    let dnsname = rustls::pki_types::ServerName::try_from(host.to_string())?;
-    let tls = connector.connect(dnsname, tcp).await?;
-    Ok(tls)
-}
-
 /// Send one HTTP/2 request on a fresh connection and observe the response (or
 /// stall). `end_stream_on_headers` controls whether END_STREAM rides the
 /// HEADERS frame (the H2.CL/H2.TE attack: a declared body that is never sent)
@@ -204,48 +296,75 @@ async fn h2_probe(
     end_stream_on_headers: bool,
     timeout: Duration,
 ) -> H2Outcome {
+    let mut frames = Vec::new();
+    let hb = req.header_block();
+    let mut hflags = FLAG_END_HEADERS;
+    if end_stream_on_headers {
+        hflags |= FLAG_END_STREAM;
+    }
+    put_frame(&mut frames, FRAME_HEADERS, hflags, 1, &hb);
+    if !end_stream_on_headers {
+        put_frame(&mut frames, FRAME_DATA, FLAG_END_STREAM, 1, req.body);
+    }
+    h2_exchange(host, port, &frames, timeout).await
+}
+
+/// Send the connection preface, an empty SETTINGS frame and `frames` on a
+/// fresh connection, then wait for the response to stream 1 (or a stall).
+async fn h2_exchange(host: &str, port: u16, frames: &[u8], timeout: Duration) -> H2Outcome {
+    crate::http::throttle().await;
     let start = Instant::now();
+    let mut peer_window = None;
     let result = tokio::time::timeout(timeout, async {
-        let mut stream = h2_connect(host, port).await?;
+        let mut stream = TransportKind::Http2.transport().connect(host, port).await?;
 
         let mut out = Vec::new();
         out.extend_from_slice(PREFACE);
         put_frame(&mut out, FRAME_SETTINGS, 0, 0, &[]);
-        let hb = req.header_block();
-        let mut hflags = FLAG_END_HEADERS;
-        if end_stream_on_headers {
-            hflags |= FLAG_END_STREAM;
-        }
-        put_frame(&mut out, FRAME_HEADERS, hflags, 1, &hb);
-        if !end_stream_on_headers {
-            put_frame(&mut out, FRAME_DATA, FLAG_END_STREAM, 1, req.body);
-        }
+        out.extend_from_slice(frames);
         stream.write_all(&out).await?;
 
-        read_response(&mut stream).await
+        read_response(&mut stream, &mut peer_window).await
     })
     .await;
 
-    match result {
-        Ok(Ok(outcome)) => H2Outcome {
-            duration: start.elapsed(),
-            ..outcome
-        },
-        // Connection/IO error: treat as a fast reset, not a stall.
-        Ok(Err(_)) => H2Outcome {
-            responded: false,
-            status: None,
-            reset: true,
-            duration: start.elapsed(),
-        },
+    let outcome = match result {
+        Ok(Ok(outcome)) => outcome,
+        // Connection/IO error: a fast rejection, not a stall.
+        Ok(Err(_)) => H2Outcome::ended(H2Termination::ConnectionClosed, None),
+        // Timed out with DATA the peer's window could not take: flow control,
+        // not the back-end, held the stream.
+        Err(_) if peer_window.is_some_and(|window| (window as usize) < data_bytes(frames)) => {
+            H2Outcome::ended(H2Termination::FlowControlStall, None)
+        }
         // Timed out: the stream stalled — the smuggling signal.
         Err(_) => H2Outcome {
             responded: false,
             status: None,
-            reset: false,
-            duration: start.elapsed(),
+            termination: None,
+            error_code: None,
+            duration: Duration::ZERO,
         },
+    };
+    H2Outcome {
+        duration: start.elapsed(),
+        ..outcome
+    }
+}
+
+/// Bytes of DATA frame payload in `frames`.
+fn data_bytes(frames: &[u8]) -> usize {
+    let mut total = 0;
+    let mut i = 0;
+    while frames.len() >= i + 9 {
+        let flen =
+            ((frames[i] as usize) << 16) | ((frames[i + 1] as usize) << 8) | frames[i + 2] as usize;
+        if frames[i + 3] == FRAME_DATA {
+            total += flen;
+        }
+        i += 9 + flen;
     }
+    total
 }
 
 /// A single declared frame larger than this is treated as a hostile/garbled
@@ -264,10 +383,13 @@ enum FrameScan {
         send_settings_ack: bool,
     },
     /// No terminal frame yet; `consumed` leading bytes were fully parsed and can
-    /// be drained. `send_settings_ack` flags an owed SETTINGS ACK.
+    /// be drained. `send_settings_ack` flags an owed SETTINGS ACK;
+    /// `initial_window` is the last SETTINGS_INITIAL_WINDOW_SIZE the peer sent
+    /// in them.
     NeedMore {
         consumed: usize,
         send_settings_ack: bool,
+        initial_window: Option<u32>,
     },
 }
 
@@ -278,14 +400,9 @@ enum FrameScan {
 /// so a hostile length field can never index out of bounds; an oversized
 /// declared length is rejected via [`MAX_FRAME_BYTES`].
 fn scan_frames(acc: &[u8]) -> FrameScan {
-    let terminal = |responded: bool, status: Option<u16>, reset: bool| H2Outcome {
-        responded,
-        status,
-        reset,
-        duration: Duration::ZERO,
-    };
     let mut i = 0;
     let mut send_settings_ack = false;
+    let mut initial_window = None;
     while acc.len() >= i + 9 {
         let flen = ((acc[i] as usize) << 16) | ((acc[i + 1] as usize) << 8) | acc[i + 2] as usize;
         let ftype = acc[i + 3];
@@ -293,7 +410,7 @@ fn scan_frames(acc: &[u8]) -> FrameScan {
         let stream_id = u32::from_be_bytes([acc[i + 5] & 0x7f, acc[i + 6], acc[i + 7], acc[i + 8]]);
         if flen > MAX_FRAME_BYTES {
             return FrameScan::Outcome {
-                outcome: terminal(false, None, true),
+                outcome: H2Outcome::ended(H2Termination::ConnectionClosed, None),
                 send_settings_ack,
             };
         }
@@ -304,15 +421,40 @@ fn scan_frames(acc: &[u8]) -> FrameScan {
 
         if ftype == FRAME_SETTINGS && flags & FLAG_ACK == 0 {
             send_settings_ack = true;
+            for setting in payload.chunks_exact(6) {
+                if u16::from_be_bytes([setting[0], setting[1]]) == SETTINGS_INITIAL_WINDOW_SIZE {
+                    initial_window = Some(u32::from_be_bytes([
+                        setting[2], setting[3], setting[4], setting[5],
+                    ]));
+                }
+            }
         } else if ftype == FRAME_HEADERS && stream_id == 1 {
             let status = payload.first().copied().and_then(status_from_indexed);
             return FrameScan::Outcome {
-                outcome: terminal(true, status, false),
+                outcome: H2Outcome {
+                    responded: true,
+                    status,
+                    termination: None,
+                    error_code: None,
+                    duration: Duration::ZERO,
+                },
+                send_settings_ack,
+            };
+        } else if ftype == FRAME_RST_STREAM && stream_id == 1 {
+            let code = payload
+                .get(..4)
+                .map(|c| u32::from_be_bytes([c[0], c[1], c[2], c[3]]));
+            return FrameScan::Outcome {
+                outcome: H2Outcome::ended(H2Termination::RstStream, code),
                 send_settings_ack,
             };
-        } else if (ftype == FRAME_RST_STREAM && stream_id == 1) || ftype == FRAME_GOAWAY {
+        } else if ftype == FRAME_GOAWAY {
+            // Last-stream-id, then the error code.
+            let code = payload
+                .get(4..8)
+                .map(|c| u32::from_be_bytes([c[0], c[1], c[2], c[3]]));
             return FrameScan::Outcome {
-                outcome: terminal(false, None, true),
+                outcome: H2Outcome::ended(H2Termination::Goaway, code),
                 send_settings_ack,
             };
         }
@@ -321,23 +463,23 @@ fn scan_frames(acc: &[u8]) -> FrameScan {
     FrameScan::NeedMore {
         consumed: i,
         send_settings_ack,
+        initial_window,
     }
 }
 
 /// Read frames until a response HEADERS for our stream arrives, or the peer
-/// resets/closes. ACKs the server SETTINGS so the connection stays live.
-async fn read_response<S: AsyncRead + AsyncWrite + Unpin>(stream: &mut S) -> Result<H2Outcome> {
+/// resets/closes. ACKs the server SETTINGS so the connection stays live, and
+/// keeps the stream window the peer announced in `peer_window`.
+async fn read_response<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut S,
+    peer_window: &mut Option<u32>,
+) -> Result<H2Outcome> {
     let mut buf = vec![0u8; 16384];
     let mut acc: Vec<u8> = Vec::new();
     loop {
         let n = stream.read(&mut buf).await?;
         if n == 0 {
-            return Ok(H2Outcome {
-                responded: false,
-                status: None,
-                reset: true,
-                duration: Duration::ZERO,
-            });
+            return Ok(H2Outcome::ended(H2Termination::ConnectionClosed, None));
         }
         acc.extend_from_slice(&buf[..n]);
 
@@ -356,7 +498,11 @@ async fn read_response<S: AsyncRead + AsyncWrite + Unpin>(stream: &mut S) -> Res
             FrameScan::NeedMore {
                 consumed,
                 send_settings_ack,
+                initial_window,
             } => {
+                if initial_window.is_some() {
+                    *peer_window = initial_window;
+                }
                 if send_settings_ack {
                     let mut ack = Vec::new();
                     put_frame(&mut ack, FRAME_SETTINGS, FLAG_ACK, 0, &[]);
@@ -368,16 +514,549 @@ async fn read_response<S: AsyncRead + AsyncWrite + Unpin>(stream: &mut S) -> Res
     }
 }
 
+/// Like [`read_response`], but returns every byte received up to and including
+/// the decisive frame instead of the interpreted outcome, stopping at
+/// [`crate::http::max_response_size`] bytes. Backs [`crate::transport::Http2`].
+pub(crate) async fn read_raw_response<S: AsyncRead + AsyncWrite + Unpin + ?Sized>(
+    stream: &mut S,
+) -> Result<Vec<u8>> {
+    let mut buf = vec![0u8; 16384];
+    let mut raw: Vec<u8> = Vec::new();
+    // Offset of the first frame not yet fully parsed.
+    let mut parsed = 0;
+    loop {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            return Ok(raw);
+        }
+        raw.extend_from_slice(&buf[..n]);
+        let (done, send_settings_ack) = match scan_frames(&raw[parsed..]) {
+            FrameScan::Outcome {
+                send_settings_ack, ..
+            } => (true, send_settings_ack),
+            FrameScan::NeedMore {
+                consumed,
+                send_settings_ack,
+                ..
+            } => {
+                parsed += consumed;
+                (false, send_settings_ack)
+            }
+        };
+        if send_settings_ack {
+            let mut ack = Vec::new();
+            put_frame(&mut ack, FRAME_SETTINGS, FLAG_ACK, 0, &[]);
+            stream.write_all(&ack).await?;
+        }
+        let max = crate::http::max_response_size();
+        if done || raw.len() >= max {
+            raw.truncate(max);
+            return Ok(raw);
+        }
+    }
+}
+
+// ----------------------------- frame fuzzing -------------------------------
+
+// Frame type and flags only the frame fuzzer emits.
+const FRAME_CONTINUATION: u8 = 0x9;
+const FLAG_PADDED: u8 = 0x8;
+const FLAG_PRIORITY: u8 = 0x20;
+
+/// Default SETTINGS_MAX_FRAME_SIZE (RFC 9113 §6.5.2).
+const DEFAULT_MAX_FRAME_SIZE: usize = 16_384;
+
+/// Body of the frame fuzzer's request: the start of another request, with a
+/// `content-length` that covers it exactly. Honoured, it is inert body data; a
+/// front-end that loses track of the length while downgrading forwards it as
+/// the start of the next back-end request, so the follow-up baseline request
+/// is answered for `/smugglex-h2-fuzz` instead.
+const FUZZ_BODY: &[u8] = b"GET /smugglex-h2-fuzz HTTP/1.1\r\nX-Ignore: x";
+
+/// Frame-level mutation strategies, applied in turn.
+const FRAME_STRATEGIES: [&str; 5] = [
+    "hpack-illegal-index",
+    "oversized-header-block",
+    "padding-abuse",
+    "continuation-split",
+    "stream-id-game",
+];
+
+/// A frame-level mutant of the fuzz request.
+struct FrameMutant {
+    /// One of [`FRAME_STRATEGIES`].
+    strategy: &'static str,
+    /// What the mutation changed.
+    detail: String,
+    /// Frames sent after the connection preface and SETTINGS.
+    frames: Vec<u8>,
+}
+
+/// Well-formed frames for a request on `stream`: HEADERS, then DATA with
+/// END_STREAM.
+fn request_frames(block: &[u8], body: &[u8], stream: u32) -> Vec<u8> {
+    let mut out = Vec::new();
+    put_frame(&mut out, FRAME_HEADERS, FLAG_END_HEADERS, stream, block);
+    put_frame(&mut out, FRAME_DATA, FLAG_END_STREAM, stream, body);
+    out
+}
+
+/// Append `block` for stream 1 as a HEADERS frame followed by one
+/// CONTINUATION frame per cut (ascending offsets; equal cuts give empty
+/// frames). END_HEADERS rides the last frame.
+fn put_header_block(out: &mut Vec<u8>, block: &[u8], cuts: &[usize]) {
+    let mut start = 0;
+    for (i, end) in cuts.iter().copied().chain([block.len()]).enumerate() {
+        let ftype = if i == 0 {
+            FRAME_HEADERS
+        } else {
+            FRAME_CONTINUATION
+        };
+        let flags = if i == cuts.len() { FLAG_END_HEADERS } else { 0 };
+        put_frame(out, ftype, flags, 1, &block[start..end]);
+        start = end;
+    }
+}
+
+/// Insert a field the peer cannot resolve: an index past the static table
+/// (the dynamic table is empty), index 0, an overflowing integer, or a
+/// dynamic table size update after the first field.
+fn mutate_illegal_index(fields: &[Vec<u8>], body: &[u8], rng: &mut Mutator) -> (String, Vec<u8>) {
+    let mut field = Vec::new();
+    let detail = match rng.rand_index(5) {
+        0 => {
+            let index = 62 + rng.rand_index(64);
+            hpack_int(&mut field, 0x80, 7, index);
+            format!("indexed field {} past the static table", index)
+        }
+        1 => {
+            let index = 62 + rng.rand_index(64);
+            hpack_int(&mut field, 0x40, 6, index);
+            hpack_string(&mut field, b"x");
+            format!("literal with name index {} past the static table", index)
+        }
+        2 => {
+            hpack_int(&mut field, 0x80, 7, 0);
+            "indexed field 0".to_string()
+        }
+        3 => {
+            hpack_int(&mut field, 0x80, 7, u32::MAX as usize);
+            format!("indexed field {} (integer overflow)", u32::MAX)
+        }
+        _ => {
+            hpack_int(&mut field, 0x20, 5, 1 << 20);
+            "dynamic table size update to 1048576".to_string()
+        }
+    };
+    let at = 1 + rng.rand_index(fields.len());
+    let mut block = fields.to_vec();
+    block.insert(at, field);
+    (
+        format!("{} at field {}", detail, at),
+        request_frames(&block.concat(), body, 1),
+    )
+}
+
+/// Add a header whose value reaches or exceeds the default maximum frame size,
+/// sent either in one (oversized) HEADERS frame or split across CONTINUATION
+/// frames of the maximum size.
+fn mutate_oversized_block(fields: &[Vec<u8>], body: &[u8], rng: &mut Mutator) -> (String, Vec<u8>) {
+    let size = DEFAULT_MAX_FRAME_SIZE << (2 * rng.rand_index(3));
+    let mut pad = Vec::new();
+    encode_field(
+        &mut pad,
+        &HField::NewName(b"x-smugglex-pad", &vec![b'a'; size]),
+    );
+    let at = PSEUDO_FIELDS + rng.rand_index(fields.len() - PSEUDO_FIELDS + 1);
+    let mut block = fields.to_vec();
+    block.insert(at, pad);
+    let block = block.concat();
+
+    let mut out = Vec::new();
+    let detail = if rng.rand_index(2) == 0 {
+        put_frame(&mut out, FRAME_HEADERS, FLAG_END_HEADERS, 1, &block);
+        format!("{}-byte header in a single HEADERS frame", size)
+    } else {
+        let cuts: Vec<usize> = (1..block.len().div_ceil(DEFAULT_MAX_FRAME_SIZE))
+            .map(|i| i * DEFAULT_MAX_FRAME_SIZE)
+            .collect();
+        put_header_block(&mut out, &block, &cuts);
+        format!(
+            "{}-byte header across {} CONTINUATION frames",
+            size,
+            cuts.len()
+        )
+    };
+    put_frame(&mut out, FRAME_DATA, FLAG_END_STREAM, 1, body);
+    (detail, out)
+}
+
+/// Misuse the PADDED flag: oversized or missing padding, or padding that
+/// carries the body (or swallows it).
+fn mutate_padding(fields: &[Vec<u8>], body: &[u8], rng: &mut Mutator) -> (String, Vec<u8>) {
+    let block = fields.concat();
+    let mut out = Vec::new();
+    let mut padded = Vec::new();
+    let detail = match rng.rand_index(4) {
+        0 => {
+            padded.push(255);
+            padded.extend_from_slice(&block);
+            padded.extend_from_slice(&[0; 255]);
+            put_frame(
+                &mut out,
+                FRAME_HEADERS,
+                FLAG_END_HEADERS | FLAG_PADDED,
+                1,
+                &padded,
+            );
+            put_frame(&mut out, FRAME_DATA, FLAG_END_STREAM, 1, body);
+            "HEADERS with 255 bytes of padding".to_string()
+        }
+        1 => {
+            let pad = 1 + rng.rand_index(255);
+            padded.push(pad as u8);
+            padded.extend_from_slice(&block);
+            put_frame(
+                &mut out,
+                FRAME_HEADERS,
+                FLAG_END_HEADERS | FLAG_PADDED,
+                1,
+                &padded,
+            );
+            put_frame(&mut out, FRAME_DATA, FLAG_END_STREAM, 1, body);
+            format!("HEADERS pad length {} with no padding bytes", pad)
+        }
+        2 => {
+            padded.push(body.len() as u8);
+            padded.extend_from_slice(body);
+            padded.extend_from_slice(body);
+            put_frame(&mut out, FRAME_HEADERS, FLAG_END_HEADERS, 1, &block);
+            put_frame(
+                &mut out,
+                FRAME_DATA,
+                FLAG_END_STREAM | FLAG_PADDED,
+                1,
+                &padded,
+            );
+            "DATA padding repeats the body".to_string()
+        }
+        _ => {
+            padded.push(body.len() as u8);
+            padded.extend_from_slice(body);
+            put_frame(&mut out, FRAME_HEADERS, FLAG_END_HEADERS, 1, &block);
+            put_frame(
+                &mut out,
+                FRAME_DATA,
+                FLAG_END_STREAM | FLAG_PADDED,
+                1,
+                &padded,
+            );
+            "DATA pad length covers the whole body".to_string()
+        }
+    };
+    (detail, out)
+}
+
+/// Split the header block across CONTINUATION frames at arbitrary byte
+/// offsets (mid-field included), one byte per frame, or around empty frames.
+fn mutate_continuation(fields: &[Vec<u8>], body: &[u8], rng: &mut Mutator) -> (String, Vec<u8>) {
+    let block = fields.concat();
+    let (detail, cuts) = match rng.rand_index(4) {
+        0 => {
+            let cut = 1 + rng.rand_index(block.len() - 1);
+            (format!("split at byte {}", cut), vec![cut])
+        }
+        1 => {
+            let mut cuts = vec![
+                1 + rng.rand_index(block.len() - 1),
+                1 + rng.rand_index(block.len() - 1),
+            ];
+            cuts.sort_unstable();
+            (format!("split at bytes {} and {}", cuts[0], cuts[1]), cuts)
+        }
+        2 => ("one byte per frame".to_string(), (1..block.len()).collect()),
+        _ => {
+            let cut = 1 + rng.rand_index(block.len() - 1);
+            (
+                format!("split at byte {} around an empty CONTINUATION", cut),
+                vec![cut, cut],
+            )
+        }
+    };
+    let mut out = Vec::new();
+    put_header_block(&mut out, &block, &cuts);
+    put_frame(&mut out, FRAME_DATA, FLAG_END_STREAM, 1, body);
+    (detail, out)
+}
+
+/// Play stream-state games around a complete request on stream 1: DATA after
+/// END_STREAM, trailers that redeclare the length, a self-dependency, or
+/// stream 1 opened after stream 3.
+fn mutate_stream_ids(fields: &[Vec<u8>], body: &[u8], rng: &mut Mutator) -> (String, Vec<u8>) {
+    let block = fields.concat();
+    let mut out = Vec::new();
+    let detail = match rng.rand_index(4) {
+        0 => {
+            out = request_frames(&block, body, 1);
+            put_frame(&mut out, FRAME_DATA, FLAG_END_STREAM, 1, body);
+            "DATA on stream 1 after END_STREAM"
+        }
+        1 => {
+            let mut trailer = Vec::new();
+            encode_field(&mut trailer, &HField::NamedValue(28, b"0"));
+            put_frame(&mut out, FRAME_HEADERS, FLAG_END_HEADERS, 1, &block);
+            put_frame(&mut out, FRAME_DATA, 0, 1, body);
+            put_frame(
+                &mut out,
+                FRAME_HEADERS,
+                FLAG_END_HEADERS | FLAG_END_STREAM,
+                1,
+                &trailer,
+            );
+            "trailers redeclare content-length: 0"
+        }
+        2 => {
+            let mut payload = vec![0, 0, 0, 1, 15]; // depends on stream 1, weight 16
+            payload.extend_from_slice(&block);
+            put_frame(
+                &mut out,
+                FRAME_HEADERS,
+                FLAG_END_HEADERS | FLAG_PRIORITY,
+                1,
+                &payload,
+            );
+            put_frame(&mut out, FRAME_DATA, FLAG_END_STREAM, 1, body);
+            "HEADERS priority depends on its own stream"
+        }
+        _ => {
+            out = request_frames(&block, body, 3);
+            out.extend_from_slice(&request_frames(&block, body, 1));
+            "stream 1 opened after stream 3"
+        }
+    };
+    (detail.to_string(), out)
+}
+
+/// Generate frame-level mutants of `req`: `mutations_per_payload` per
+/// strategy, capped at `max_mutants`, without repeats.
+fn frame_mutants(req: &H2Request<'_>, config: &MutatorConfig) -> Vec<FrameMutant> {
+    let total = FRAME_STRATEGIES.len() * config.mutations_per_payload;
+    let total = config.max_mutants.map_or(total, |cap| total.min(cap));
+    let fields = req.fields();
+    let mut rng = Mutator::new(config.clone());
+    let mut seen = HashSet::new();
+    let mut mutants = Vec::with_capacity(total);
+    // Strategies with few variants run dry first; bound the redraws.
+    for attempt in 0..total * 4 {
+        if mutants.len() >= total {
+            break;
+        }
+        let (detail, frames) = match attempt % FRAME_STRATEGIES.len() {
+            0 => mutate_illegal_index(&fields, req.body, &mut rng),
+            1 => mutate_oversized_block(&fields, req.body, &mut rng),
+            2 => mutate_padding(&fields, req.body, &mut rng),
+            3 => mutate_continuation(&fields, req.body, &mut rng),
+            _ => mutate_stream_ids(&fields, req.body, &mut rng),
+        };
+        if seen.insert(frames.clone()) {
+            mutants.push(FrameMutant {
+                strategy: FRAME_STRATEGIES[attempt % FRAME_STRATEGIES.len()],
+                detail,
+                frames,
+            });
+        }
+    }
+    mutants
+}
+
+/// One line per frame (`TYPE stream=N flags=0xNN len=N`), used to report
+/// mutants and diff them against the well-formed request.
+fn describe_frames(frames: &[u8]) -> String {
+    let mut out = String::new();
+    let mut i = 0;
+    while frames.len() >= i + 9 {
+        let flen =
+            ((frames[i] as usize) << 16) | ((frames[i + 1] as usize) << 8) | frames[i + 2] as usize;
+        let stream_id = u32::from_be_bytes([
+            frames[i + 5] & 0x7f,
+            frames[i + 6],
+            frames[i + 7],
+            frames[i + 8],
+        ]);
+        let name = match frames[i + 3] {
+            FRAME_DATA => "DATA",
+            FRAME_HEADERS => "HEADERS",
+            FRAME_CONTINUATION => "CONTINUATION",
+            _ => "UNKNOWN",
+        };
+        out.push_str(&format!(
+            "{} stream={} flags=0x{:02x} len={}\n",
+            name,
+            stream_id,
+            frames[i + 4],
+            flen
+        ));
+        i += 9 + flen;
+    }
+    out
+}
+
+/// What made a frame mutant stand out.
+#[derive(Clone, Copy, PartialEq)]
+enum FrameSignal {
+    /// The mutant's own stream stalled.
+    Stall,
+    /// The next well-formed request got this status instead of the baseline's.
+    FollowUp(Option<u16>),
+}
+
+/// `HTTP/2 <status>` for report fields.
+fn status_label(status: Option<u16>) -> String {
+    status
+        .map(|s| format!("HTTP/2 {}", s))
+        .unwrap_or_else(|| "HTTP/2 (ok)".to_string())
+}
+
+/// Target and reference values shared by the frame fuzzer's probes.
+struct FrameFuzzer<'a> {
+    host: &'a str,
+    port: u16,
+    timeout: Duration,
+    /// Well-formed GET used as the follow-up request.
+    follow_up: &'a H2Request<'a>,
+    baseline_status: Option<u16>,
+}
+
+/// A confirmed frame-level desync.
+struct FrameFinding {
+    index: usize,
+    mutant: FrameMutant,
+    signal: FrameSignal,
+    attack_ms: u64,
+}
+
+/// Result of a frame fuzzing run.
+struct FrameFuzzReport {
+    finding: Option<FrameFinding>,
+    anomalies: Vec<FuzzAnomaly>,
+    /// Mutants the peer ended with GOAWAY / RST_STREAM / a closed connection
+    observations: Vec<H2Observation>,
+    sent: usize,
+}
+
+impl FrameFuzzer<'_> {
+    /// Send `frames`, plus a follow-up request when the stream did not stall,
+    /// and classify the result; `None` when nothing stood out. The attack's
+    /// own outcome is returned alongside.
+    async fn probe(&self, frames: &[u8]) -> (Option<FrameSignal>, H2Outcome) {
+        let attack = h2_exchange(self.host, self.port, frames, self.timeout).await;
+        if stalled(&attack, self.timeout) {
+            return (Some(FrameSignal::Stall), attack);
+        }
+        let follow = h2_probe(self.host, self.port, self.follow_up, true, self.timeout).await;
+        let signal = (follow.responded && follow.status != self.baseline_status)
+            .then_some(FrameSignal::FollowUp(follow.status));
+        (signal, attack)
+    }
+
+    /// Send every mutant; the first whose signal survives the control request
+    /// and all confirmation retries is the finding. The others that stood out
+    /// are kept for triage.
+    async fn run(
+        &self,
+        mutants: Vec<FrameMutant>,
+        control: &[u8],
+        verbose: bool,
+    ) -> FrameFuzzReport {
+        let control_listing = describe_frames(control);
+        let mut report = FrameFuzzReport {
+            finding: None,
+            anomalies: Vec::new(),
+            observations: Vec::new(),
+            sent: 0,
+        };
+        for (index, mutant) in mutants.into_iter().enumerate() {
+            report.sent += 1;
+            let (signal, attack) = self.probe(&mutant.frames).await;
+            record_observation(
+                &mut report.observations,
+                &format!("frame-mutant #{} ({})", index, mutant.strategy),
+                &attack,
+                verbose,
+            );
+            let duration = attack.duration;
+            let Some(signal) = signal else {
+                continue;
+            };
+
+            // The well-formed request must not show the same signal: a stall
+            // or a changed follow-up status there is the back-end, not a desync.
+            let outcome = if self.probe(control).await.0 == Some(signal) {
+                Some("rejected by the control request")
+            } else {
+                let mut reproduced = true;
+                for _ in 0..H2_CONFIRMATION_RETRIES {
+                    if self.probe(&mutant.frames).await.0 != Some(signal) {
+                        reproduced = false;
+                        break;
+                    }
+                }
+                (!reproduced).then_some("not reproduced on retry")
+            };
+
+            let Some(outcome) = outcome else {
+                report.finding = Some(FrameFinding {
+                    index,
+                    mutant,
+                    signal,
+                    attack_ms: duration.as_millis() as u64,
+                });
+                break;
+            };
+            if verbose {
+                println!(
+                    "  [*] h2-downgrade frame mutant #{} ({}) {}",
+                    index, mutant.strategy, outcome
+                );
+            }
+            if report.anomalies.len() < MAX_FUZZ_ANOMALIES {
+                let observed = match signal {
+                    FrameSignal::Stall => format!("stream stalled {}ms", duration.as_millis()),
+                    FrameSignal::FollowUp(status) => format!(
+                        "follow-up answered {} (baseline {})",
+                        status_label(status),
+                        status_label(self.baseline_status)
+                    ),
+                };
+                let listing = describe_frames(&mutant.frames);
+                report.anomalies.push(FuzzAnomaly {
+                    payload_index: index,
+                    payload_name: Some(format!("frame-mutant/{}", mutant.strategy)),
+                    anomaly: format!("{}; {}", observed, outcome),
+                    payload: format!("{}: {}\n{}", mutant.strategy, mutant.detail, listing),
+                    seed_diff: payload_diff(&control_listing, &listing),
+                    reproduction_file: None,
+                });
+            }
+        }
+        report
+    }
+}
+
 // ----------------------------- detection -----------------------------------
 
-/// True when a probe stalled (no response and not a fast reset) for at least
-/// 80% of the timeout — i.e. the stream hung rather than being rejected.
+/// True when a probe stalled (no response, not ended by the peer and not held
+/// by flow control) for at least 80% of the timeout — i.e. the stream hung
+/// rather than being rejected.
 fn stalled(o: &H2Outcome, timeout: Duration) -> bool {
-    !o.responded && !o.reset && o.duration.as_millis() * 100 >= timeout.as_millis() * 80
+    !o.responded
+        && o.termination.is_none()
+        && o.duration.as_millis() * 100 >= timeout.as_millis() * 80
 }
 
 /// Run the real-HTTP/2 downgrade smuggling check (H2.CL / H2.TE) and return a
-/// [`CheckResult`]. Requires TLS (ALPN `h2`).
+/// [`CheckResult`]. Requires TLS (ALPN `h2`). With `fuzz`, frame-level mutants
+/// are tried when the fixed shapes find nothing.
 pub async fn run_h2_downgrade_check(
     host: &str,
     port: u16,
@@ -385,6 +1064,7 @@ pub async fn run_h2_downgrade_check(
     path: &str,
     timeout: u64,
     verbose: bool,
+    fuzz: Option<&MutatorConfig>,
 ) -> CheckResult {
     let check_name = "h2-downgrade";
     let dur = Duration::from_secs(timeout);
@@ -393,15 +1073,28 @@ pub async fn run_h2_downgrade_check(
             check_type: check_name.to_string(),
             vulnerable: false,
             payload_index: None,
+            payload_name: None,
+            technique: None,
             normal_status,
             attack_status: None,
             normal_duration_ms: normal_ms,
             attack_duration_ms: None,
+            normal_connect_ms: None,
+            attack_connect_ms: None,
             timestamp: Utc::now().to_rfc3339(),
             payload: None,
             confidence: None,
             detection_signals: Vec::new(),
             diagnostics,
+            payloads_sent: 0,
+            request_errors: Default::default(),
+            h2_observations: Vec::new(),
+            fuzz_anomalies: Vec::new(),
+            unsolicited_bytes: None,
+            attack_phases: None,
+            cross_check: None,
+            evidence: None,
+            consistency: None,
         };
 
     // Baseline: a well-formed GET must answer promptly, establishing both that
@@ -414,19 +1107,22 @@ pub async fn run_h2_downgrade_check(
         content_length: None,
         body: b"",
     };
+    // Probes the peer ended without a response, by payload.
+    let mut observations: Vec<H2Observation> = Vec::new();
     let base = h2_probe(host, port, &baseline, true, dur).await;
+    record_observation(&mut observations, "baseline", &base, verbose);
     if !base.responded {
-        return not_vulnerable(
-            "no h2 response".to_string(),
-            base.duration.as_millis() as u64,
-            vec!["h2_baseline_no_response".to_string()],
-        );
+        return CheckResult {
+            h2_observations: observations,
+            ..not_vulnerable(
+                "no h2 response".to_string(),
+                base.duration.as_millis() as u64,
+                vec!["h2_baseline_no_response".to_string()],
+            )
+        };
     }
     let base_ms = base.duration.as_millis();
-    let normal_status = base
-        .status
-        .map(|s| format!("HTTP/2 {}", s))
-        .unwrap_or_else(|| "HTTP/2 (ok)".to_string());
+    let normal_status = status_label(base.status);
 
     // A well-formed POST whose content-length matches its DATA. If even this
     // stalls, the backend is just slow for POSTs and any finding is rejected.
@@ -470,11 +1166,14 @@ pub async fn run_h2_downgrade_check(
 
     for (name, desc, attack_req) in &shapes {
         let attack = h2_probe(host, port, attack_req, true, dur).await;
+        // A stream killed on this shape alone is a fingerprint worth keeping.
+        record_observation(&mut observations, name, &attack, verbose);
         if !stalled(&attack, dur) {
             continue; // responded or fast-rejected -> not this vector
         }
 
         let control_out = h2_probe(host, port, &control, false, dur).await;
+        record_observation(&mut observations, "control", &control_out, verbose);
         if stalled(&control_out, dur) {
             if verbose {
                 println!(
@@ -489,7 +1188,9 @@ pub async fn run_h2_downgrade_check(
         // transient hangs should not confirm a finding).
         let mut all_stalled = true;
         for _ in 0..H2_CONFIRMATION_RETRIES {
-            if !stalled(&h2_probe(host, port, attack_req, true, dur).await, dur) {
+            let retry = h2_probe(host, port, attack_req, true, dur).await;
+            record_observation(&mut observations, name, &retry, verbose);
+            if !stalled(&retry, dur) {
                 all_stalled = false;
                 break;
             }
@@ -515,19 +1216,127 @@ pub async fn run_h2_downgrade_check(
             check_type: check_name.to_string(),
             vulnerable: true,
             payload_index: Some(0),
+            payload_name: Some(name.to_string()),
+            technique: None,
             normal_status,
             attack_status: Some("stream stalled (no response)".to_string()),
             normal_duration_ms: base_ms as u64,
             attack_duration_ms: Some(attack.duration.as_millis() as u64),
+            normal_connect_ms: None,
+            attack_connect_ms: None,
             timestamp: Utc::now().to_rfc3339(),
             payload: Some(payload),
             confidence: Some(Confidence::High),
             detection_signals: signals,
             diagnostics: Vec::new(),
+            payloads_sent: 0,
+            request_errors: Default::default(),
+            h2_observations: observations,
+            fuzz_anomalies: Vec::new(),
+            unsolicited_bytes: None,
+            attack_phases: None,
+            cross_check: None,
+            evidence: None,
+            consistency: None,
         };
     }
 
-    not_vulnerable(normal_status, base_ms as u64, Vec::new())
+    let Some(config) = fuzz else {
+        return CheckResult {
+            h2_observations: observations,
+            ..not_vulnerable(normal_status, base_ms as u64, Vec::new())
+        };
+    };
+    let fuzz_cl = FUZZ_BODY.len().to_string();
+    let fuzz_req = H2Request {
+        method: "POST",
+        authority,
+        path,
+        extra: Vec::new(),
+        content_length: Some(&fuzz_cl),
+        body: FUZZ_BODY,
+    };
+    let fuzzer = FrameFuzzer {
+        host,
+        port,
+        timeout: dur,
+        follow_up: &baseline,
+        baseline_status: base.status,
+    };
+    let control_frames = request_frames(&fuzz_req.header_block(), FUZZ_BODY, 1);
+    let report = fuzzer
+        .run(frame_mutants(&fuzz_req, config), &control_frames, verbose)
+        .await;
+    if verbose {
+        println!(
+            "  [*] {} frame fuzzing: {} mutants sent, {} anomalies",
+            check_name,
+            report.sent,
+            report.anomalies.len()
+        );
+    }
+
+    observations.extend(report.observations);
+    observations.truncate(MAX_H2_OBSERVATIONS);
+    let Some(finding) = report.finding else {
+        return CheckResult {
+            payloads_sent: report.sent,
+            fuzz_anomalies: report.anomalies,
+            unsolicited_bytes: None,
+            attack_phases: None,
+            cross_check: None,
+            evidence: None,
+            consistency: None,
+            h2_observations: observations,
+            ..not_vulnerable(normal_status, base_ms as u64, Vec::new())
+        };
+    };
+    let (signal, attack_status) = match finding.signal {
+        FrameSignal::Stall => ("h2_stall", "stream stalled (no response)".to_string()),
+        FrameSignal::FollowUp(status) => (
+            "followup_status_changed",
+            format!("follow-up answered {}", status_label(status)),
+        ),
+    };
+    CheckResult {
+        check_type: check_name.to_string(),
+        vulnerable: true,
+        payload_index: Some(finding.index),
+        payload_name: Some(format!("frame-mutant/{}", finding.mutant.strategy)),
+        technique: None,
+        normal_status,
+        attack_status: Some(attack_status),
+        normal_duration_ms: base_ms as u64,
+        attack_duration_ms: Some(finding.attack_ms),
+        normal_connect_ms: None,
+        attack_connect_ms: None,
+        timestamp: Utc::now().to_rfc3339(),
+        payload: Some(format!(
+            "HTTP/2 frame mutant ({}: {}) :method POST :authority {} :path {}\n{}",
+            finding.mutant.strategy,
+            finding.mutant.detail,
+            authority,
+            path,
+            describe_frames(&finding.mutant.frames)
+        )),
+        confidence: Some(Confidence::High),
+        detection_signals: vec![
+            format!("h2_frame_fuzz:{}", finding.mutant.strategy),
+            "h2_downgrade_desync".to_string(),
+            signal.to_string(),
+            "control_unaffected".to_string(),
+        ],
+        diagnostics: Vec::new(),
+        payloads_sent: report.sent,
+        request_errors: Default::default(),
+        h2_observations: observations,
+        fuzz_anomalies: report.anomalies,
+        unsolicited_bytes: None,
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
+    }
 }
 
 #[cfg(test)]
@@ -643,21 +1452,24 @@ mod tests {
         let hang = H2Outcome {
             responded: false,
             status: None,
-            reset: false,
+            termination: None,
+            error_code: None,
             duration: Duration::from_secs(9),
         };
         assert!(stalled(&hang, to));
         let fast_reset = H2Outcome {
             responded: false,
             status: None,
-            reset: true,
+            termination: Some(H2Termination::RstStream),
+            error_code: Some(0x1),
             duration: Duration::from_millis(50),
         };
         assert!(!stalled(&fast_reset, to));
         let ok = H2Outcome {
             responded: true,
             status: Some(200),
-            reset: false,
+            termination: None,
+            error_code: None,
             duration: Duration::from_millis(200),
         };
         assert!(!stalled(&ok, to));
@@ -694,21 +1506,56 @@ mod tests {
     }
 
     #[test]
-    fn scan_frames_maps_rst_and_goaway_to_reset() {
+    fn scan_frames_distinguishes_rst_and_goaway() {
         let mut rst = Vec::new();
-        put_frame(&mut rst, FRAME_RST_STREAM, 0, 1, &[0, 0, 0, 0]);
+        put_frame(&mut rst, FRAME_RST_STREAM, 0, 1, &[0, 0, 0, 0x1]);
         match scan_frames(&rst) {
-            FrameScan::Outcome { outcome, .. } => assert!(outcome.reset && !outcome.responded),
+            FrameScan::Outcome { outcome, .. } => {
+                assert_eq!(outcome.termination, Some(H2Termination::RstStream));
+                assert_eq!(outcome.error_code, Some(0x1));
+                assert!(!outcome.responded);
+            }
             _ => panic!("RST_STREAM(1) should be terminal"),
         }
         let mut goaway = Vec::new();
-        put_frame(&mut goaway, FRAME_GOAWAY, 0, 0, &[0, 0, 0, 0, 0, 0, 0, 0]);
+        put_frame(&mut goaway, FRAME_GOAWAY, 0, 0, &[0, 0, 0, 1, 0, 0, 0, 0xb]);
         match scan_frames(&goaway) {
-            FrameScan::Outcome { outcome, .. } => assert!(outcome.reset),
+            FrameScan::Outcome { outcome, .. } => {
+                assert_eq!(outcome.termination, Some(H2Termination::Goaway));
+                assert_eq!(outcome.error_code, Some(0xb));
+            }
             _ => panic!("GOAWAY should be terminal"),
         }
     }
 
+    #[test]
+    fn scan_frames_reads_initial_window_from_settings() {
+        let mut acc = Vec::new();
+        let mut setting = SETTINGS_INITIAL_WINDOW_SIZE.to_be_bytes().to_vec();
+        setting.extend_from_slice(&16u32.to_be_bytes());
+        put_frame(&mut acc, FRAME_SETTINGS, 0, 0, &setting);
+        match scan_frames(&acc) {
+            FrameScan::NeedMore { initial_window, .. } => assert_eq!(initial_window, Some(16)),
+            _ => panic!("SETTINGS alone is not terminal"),
+        }
+    }
+
+    #[test]
+    fn data_bytes_counts_only_data_payloads() {
+        let mut frames = Vec::new();
+        put_frame(&mut frames, FRAME_HEADERS, FLAG_END_HEADERS, 1, &[0x83]);
+        put_frame(&mut frames, FRAME_DATA, 0, 1, b"abc");
+        put_frame(&mut frames, FRAME_DATA, 0, 1, b"de");
+        assert_eq!(data_bytes(&frames), 5);
+    }
+
+    #[test]
+    fn error_code_names_follow_rfc9113() {
+        assert_eq!(error_code_name(0x1), "PROTOCOL_ERROR");
+        assert_eq!(error_code_name(0xb), "ENHANCE_YOUR_CALM");
+        assert_eq!(error_code_name(0x42), "0x42");
+    }
+
     #[test]
     fn scan_frames_needs_more_on_partial_frame() {
         // A 9-byte header declaring a 10-byte payload with no body yet.
@@ -724,7 +1571,9 @@ mod tests {
         // 0xFFFFFF (16 MiB) exceeds MAX_FRAME_BYTES: reject fast, never slice.
         let acc = vec![0xFF, 0xFF, 0xFF, FRAME_HEADERS, 0, 0, 0, 0, 1];
         match scan_frames(&acc) {
-            FrameScan::Outcome { outcome, .. } => assert!(outcome.reset),
+            FrameScan::Outcome { outcome, .. } => {
+                assert_eq!(outcome.termination, Some(H2Termination::ConnectionClosed))
+            }
             _ => panic!("an oversized declared frame must be rejected"),
         }
     }
@@ -738,6 +1587,7 @@ mod tests {
             FrameScan::NeedMore {
                 consumed,
                 send_settings_ack,
+                ..
             } => {
                 assert_eq!(consumed, 9, "the complete SETTINGS frame should be drained");
                 assert!(send_settings_ack);
@@ -746,6 +1596,52 @@ mod tests {
         }
     }
 
+    #[test]
+    fn put_header_block_splits_into_continuations() {
+        let mut out = Vec::new();
+        put_header_block(&mut out, b"abcdef", &[2, 2, 4]);
+        assert_eq!(
+            describe_frames(&out),
+            "HEADERS stream=1 flags=0x00 len=2\n\
+             CONTINUATION stream=1 flags=0x00 len=0\n\
+             CONTINUATION stream=1 flags=0x00 len=2\n\
+             CONTINUATION stream=1 flags=0x04 len=2\n"
+        );
+    }
+
+    #[test]
+    fn frame_mutants_are_deterministic_and_capped() {
+        let cl = FUZZ_BODY.len().to_string();
+        let fuzz_req = H2Request {
+            method: "POST",
+            authority: "h.test",
+            path: "/",
+            extra: Vec::new(),
+            content_length: Some(&cl),
+            body: FUZZ_BODY,
+        };
+        let config = MutatorConfig::default();
+        let mutants = frame_mutants(&fuzz_req, &config);
+        assert!(mutants.len() > FRAME_STRATEGIES.len());
+        assert!(mutants.len() <= FRAME_STRATEGIES.len() * config.mutations_per_payload);
+        for strategy in FRAME_STRATEGIES {
+            assert!(mutants.iter().any(|m| m.strategy == strategy));
+        }
+        let again = frame_mutants(&fuzz_req, &config);
+        assert!(
+            mutants
+                .iter()
+                .zip(&again)
+                .all(|(a, b)| a.frames == b.frames && a.detail == b.detail)
+        );
+
+        let capped = MutatorConfig {
+            max_mutants: Some(3),
+            ..MutatorConfig::default()
+        };
+        assert_eq!(frame_mutants(&fuzz_req, &capped).len(), 3);
+    }
+
     #[tokio::test]
     async fn read_response_decodes_headers_over_duplex() {
         let (mut server, mut client) = tokio::io::duplex(4096);
@@ -753,7 +1649,7 @@ mod tests {
         put_frame(&mut frames, FRAME_SETTINGS, 0, 0, &[]);
         put_frame(&mut frames, FRAME_HEADERS, FLAG_END_HEADERS, 1, &[0x88]);
         server.write_all(&frames).await.unwrap();
-        let outcome = read_response(&mut client).await.unwrap();
+        let outcome = read_response(&mut client, &mut None).await.unwrap();
         assert!(outcome.responded);
         assert_eq!(outcome.status, Some(200));
     }
@@ -762,8 +1658,8 @@ mod tests {
     async fn read_response_reset_on_eof() {
         let (server, mut client) = tokio::io::duplex(64);
         drop(server); // closing the peer makes the next read return 0 (EOF)
-        let outcome = read_response(&mut client).await.unwrap();
+        let outcome = read_response(&mut client, &mut None).await.unwrap();
         assert!(!outcome.responded);
-        assert!(outcome.reset);
+        assert_eq!(outcome.termination, Some(H2Termination::ConnectionClosed));
     }
 }
@@ -0,0 +1,154 @@
+//! `--stdin-format jsonl`: one scan job per stdin line instead of a bare URL,
+//! so a tool driving smugglex can scan each target with its own method,
+//! headers, Host, checks and flags in a single run.
+//!
+//! ```text
+//! {"url":"https://a.example.com","checks":"cl-te,te-cl"}
+//! {"url":"https://b.example.com/api","method":"GET","headers":["X-Team: blue"],"profile":["-t","5","--quick"]}
+//! ```
+//!
+//! A job's `profile` holds scan flags in command-line syntax, applied on top
+//! of the flags smugglex was started with; the named fields are applied last.
+//!
+//! A `-l` targets file mixes both: a line is either a bare URL, scanned with
+//! the command line's flags, or a job object.
+
+use clap::{CommandFactory, FromArgMatches};
+use serde::Deserialize;
+
+use crate::cli::{Cli, KNOWN_CHECK_NAMES, has_any_known_check};
+use crate::error::{Result, SmugglexError};
+use crate::secrets::expand_headers;
+
+/// One line of `--stdin-format jsonl` input
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ScanJob {
+    /// Target URL
+    pub url: String,
+    /// HTTP method for the attack requests (`--method`)
+    #[serde(default)]
+    pub method: Option<String>,
+    /// Headers added to the `-H` ones (`"Name: value"`)
+    #[serde(default)]
+    pub headers: Vec<String>,
+    /// Host header to send (`--vhost`)
+    #[serde(default)]
+    pub vhost: Option<String>,
+    /// Whether to fetch and send the target's cookies (`--cookies`)
+    #[serde(default)]
+    pub cookies: Option<bool>,
+    /// Checks to run, comma-separated (`--checks`)
+    #[serde(default)]
+    pub checks: Option<String>,
+    /// Further scan flags, as command-line arguments
+    #[serde(default)]
+    pub profile: Vec<String>,
+}
+
+/// Parse `input` as one job per line, skipping blank lines. A line that is
+/// not a job object fails the whole input, naming its line number.
+pub fn parse_jobs(input: &str) -> Result<Vec<ScanJob>> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| parse_job(line, "stdin", i + 1))
+        .collect()
+}
+
+/// Parse a `-l` targets file read from `source`: each line is a URL or, when
+/// it starts with `{`, a job object. Blank lines and `#` comments are skipped.
+pub fn parse_target_list(input: &str, source: &str) -> Result<Vec<ScanJob>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(i, line)| {
+            if line.starts_with('{') {
+                parse_job(line, source, i + 1)
+            } else {
+                Ok(ScanJob::for_url(line))
+            }
+        })
+        .collect()
+}
+
+fn parse_job(line: &str, source: &str, number: usize) -> Result<ScanJob> {
+    serde_json::from_str(line)
+        .map_err(|e| SmugglexError::Parse(format!("{} line {}: {}", source, number, e)))
+}
+
+impl ScanJob {
+    /// A job scanning `url` with the command line's settings alone.
+    pub fn for_url(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            method: None,
+            headers: Vec::new(),
+            vhost: None,
+            cookies: None,
+            checks: None,
+            profile: Vec::new(),
+        }
+    }
+}
+
+/// The settings `job` is scanned with: `base_args` (the command line, without
+/// the program name) followed by the job's profile, a later flag overriding
+/// an earlier one, then the job's method, headers, Host, cookie use and checks.
+/// Process-wide settings (rate limit, TLS, proxy, response size cap) are set
+/// once from the command line and not changed by a job.
+pub fn job_cli(base_args: &[String], job: &ScanJob) -> Result<Cli> {
+    let args = std::iter::once("smugglex")
+        .chain(base_args.iter().map(String::as_str))
+        .chain(job.profile.iter().map(String::as_str));
+    let invalid = |e: clap::Error| {
+        let message = e.to_string();
+        SmugglexError::Config(format!(
+            "{}: invalid profile: {}",
+            job.url,
+            message.lines().next().unwrap_or_default()
+        ))
+    };
+    let matches = Cli::command()
+        .args_override_self(true)
+        .try_get_matches_from(args)
+        .map_err(invalid)?;
+    let mut cli = Cli::from_arg_matches(&matches).map_err(invalid)?;
+    if cli.command.is_some() {
+        return Err(SmugglexError::Config(format!(
+            "{}: subcommands are not allowed in a job profile",
+            job.url
+        )));
+    }
+    cli.urls = vec![job.url.clone()];
+    if let Some(ref method) = job.method {
+        cli.method = method.clone();
+    }
+    cli.headers.extend(job.headers.iter().cloned());
+    if let Some(ref vhost) = job.vhost {
+        cli.vhost = Some(vhost.clone());
+    }
+    if let Some(cookies) = job.cookies {
+        cli.use_cookies = cookies;
+    }
+    if let Some(ref checks) = job.checks {
+        cli.checks = Some(checks.clone());
+    }
+
+    if let Some(ref checks) = cli.checks
+        && !has_any_known_check(checks, &KNOWN_CHECK_NAMES)
+    {
+        return Err(SmugglexError::Config(format!(
+            "{}: no valid checks selected from '{}'",
+            job.url, checks
+        )));
+    }
+    expand_headers(&cli.headers)
+        .map_err(|e| SmugglexError::Config(format!("{}: {}", job.url, e)))?;
+    cli.vhosts()
+        .map_err(|e| SmugglexError::Config(format!("{}: {}", job.url, e)))?;
+    Ok(cli)
+}
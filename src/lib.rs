@@ -1,13 +1,37 @@
+pub mod archive;
 pub mod cli;
+pub mod concurrency;
+pub mod corpus;
+pub mod daemon;
+pub mod decompress;
+pub mod distributed;
+pub mod early_data;
+pub mod email;
+pub mod env_proxy;
 pub mod error;
 pub mod exploit;
 pub mod fingerprint;
+pub mod history;
 pub mod http;
 pub mod http2;
+pub mod jobs;
 pub mod model;
 pub mod mutator;
 pub mod output;
 pub mod payloads;
+pub mod proxy_auth;
 pub mod raw_request;
+pub mod redact;
+pub mod report;
+pub mod reproduce;
+pub mod retest;
 pub mod scanner;
+pub mod secrets;
+pub mod syslog;
+pub mod target_info;
+pub mod trace;
+pub mod transport;
+pub mod triage;
 pub mod utils;
+pub mod validate;
+pub mod wordlists;
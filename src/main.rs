@@ -1,32 +1,73 @@
-use clap::Parser;
+use clap::{CommandFactory, Parser};
 use colored::*;
 use indicatif::{ProgressBar, ProgressStyle};
-use std::io::{self, BufRead, IsTerminal};
+use std::collections::HashMap;
+use std::io::{self, BufRead, IsTerminal, Read};
 use std::time::Duration;
 use url::Url;
 
-use smugglex::cli::Cli;
+use smugglex::archive::{ArchiveRecorder, active_archive, open_archive};
+use smugglex::cli::{
+    Cli, Command, DaemonArgs, DnsChangePolicy, PayloadsArgs, PayloadsCommand, ReportArgs,
+    ReproduceArgs, RetestArgs, ServeArgs, ServeRole, StatsArgs, StdinFormat, ValidateArgs,
+    WordlistsArgs, WordlistsCommand, vhost_for_target,
+};
+use smugglex::concurrency::{AutoConcurrency, DEFAULT_AUTO_CONCURRENCY_MAX, ScanSample};
+use smugglex::corpus::FuzzCorpus;
+use smugglex::email::{SmtpServer, build_message, findings_summary, send_mail, validate_address};
 use smugglex::error::{Result, SmugglexError};
 use smugglex::exploit::{
-    LocalhostAccessParams, PathFuzzParams, VulnerabilityContext, extract_vulnerability_context,
-    get_fuzz_paths, print_localhost_results, print_path_fuzz_results, test_localhost_access,
-    test_path_fuzz,
+    AutoExploitPlan, CAPTURE_FOLLOW_UPS, CloudMetadataParams, ExploitBudget, FuzzCategory,
+    InternalSweepParams, LocalhostAccessParams, METADATA_FOLLOW_UPS, PathFuzzParams, PlanInputs,
+    REVEAL_FOLLOW_UPS, ResponseMatcher, SMUGGLE_ROUNDS, SweepRange, VulnerabilityContext,
+    default_capture_request, extract_vulnerability_context, get_fuzz_paths, parse_hosts,
+    parse_ports, plan_auto_exploit, plan_exploits, print_cloud_metadata_results,
+    print_exploit_plan, print_localhost_results, print_path_fuzz_results, print_sweep_results,
+    test_cloud_metadata, test_internal_sweep, test_localhost_access, test_path_fuzz,
+    unescape_request,
+};
+use smugglex::fingerprint::{
+    FingerprintResult, adaptive_check_order, fingerprint_target, format_proxy_chain,
+    h2_downgrade_likely, observe_proxy_chain, observed_proxy_chain,
 };
-use smugglex::fingerprint::{fingerprint_target, suggest_checks};
+use smugglex::history::{expand_home, history_stats, read_index, record_results};
 use smugglex::http;
-use smugglex::model::{CheckResult, FingerprintInfo, ScanResults};
-use smugglex::mutator::{Mutator, MutatorConfig};
-use smugglex::output::{
-    build_batch_results, log_scan_results, print_batch_json, save_batch_to_file,
-    save_results_to_file,
+use smugglex::jobs::{ScanJob, job_cli, parse_jobs, parse_target_list};
+use smugglex::model::{
+    CheckResult, DiscoveredPath, ErrorInfo, EvidenceScore, ExploitDecision, ExploitPlan,
+    ExploitResults, FingerprintInfo, InternalSweep, LocalhostPortResult, PlannedExploit,
+    ScanResults, ScanSummary, TriageResult, merge_repeated_runs,
 };
-use smugglex::payloads::{
-    get_cl_edge_case_payloads, get_cl_te_payloads, get_h2_payloads, get_h2c_payloads,
-    get_te_cl_payloads, get_te_te_payloads,
+use smugglex::mutator::{FuzzFeedback, Mutator, MutatorConfig};
+use smugglex::output::{
+    build_batch_results, github_annotations, load_scan_results, log_history_stats,
+    log_payload_catalog, log_reproduction, log_retest_report, log_scan_results, log_scan_summary,
+    log_template_validations, log_triage_report, log_wordlists, print_batch_json,
+    save_batch_to_file, save_report, save_retest_report, save_scan_results, save_triage_report,
 };
+use smugglex::payloads::{PAYLOAD_FAMILIES, PayloadFn, PayloadIter, payload_family};
+use smugglex::proxy_auth::ProxyCredentials;
 use smugglex::raw_request::{merge_headers, parse_raw_request};
+use smugglex::redact::{redact_check, redact_scan_results};
+use smugglex::report::{render_junit, render_report};
+use smugglex::reproduce::{build_reproduction, finding_id, select_finding};
+use smugglex::retest::{RetestSettings, build_retest_report, load_saved_findings, retest_finding};
+use smugglex::scanner::detection::DetectionMethod;
 use smugglex::scanner::{CheckParams, run_checks_for_type};
-use smugglex::utils::{LogLevel, fetch_cookies, is_machine, log, set_machine};
+use smugglex::secrets::{expand_headers, expand_value};
+use smugglex::syslog::{SyslogSink, local_hostname, send_syslog, syslog_events};
+use smugglex::target_info::{observe_target_info, observed_target_info};
+use smugglex::trace::{TraceFate, TraceReport, trace_probe};
+use smugglex::transport::check_transport;
+use smugglex::triage::{TriageSettings, build_triage_report, triage_target};
+use smugglex::utils::{
+    LogLevel, PayloadExport, fetch_cookies, is_machine, is_quiet, log, preflight, set_machine,
+};
+use smugglex::validate::validate_path;
+use smugglex::wordlists::{
+    WORDLISTS, download_wordlist, find_wordlist, save_wordlist, wordlist_dir, wordlist_entries,
+    wordlist_status,
+};
 
 #[derive(Debug)]
 struct ExploitParams<'a> {
@@ -40,11 +81,21 @@ struct ExploitParams<'a> {
     verbose: bool,
     target_url: &'a str,
     ports_str: &'a str,
+    hosts_str: Option<&'a str>,
+    /// `--range` for `internal-sweep`
+    range: Option<&'a str>,
+    sweep_budget: usize,
     wordlist_path: Option<&'a str>,
+    fuzz_categories: &'a [FuzzCategory],
+    matcher: &'a ResponseMatcher,
     delay: u64,
     smuggle_request: Option<&'a str>,
     reveal_endpoint: Option<&'a str>,
     reveal_param: &'a str,
+    /// Request cap for exploits that support one (set by `--auto-exploit`)
+    request_budget: Option<usize>,
+    /// `--exploit-max-requests` / `--exploit-max-time`, shared by every exploit
+    budget: &'a ExploitBudget,
 }
 
 /// Outcome of scanning a single target. Used to collect results for batch JSON output
@@ -54,12 +105,14 @@ struct ExploitParams<'a> {
 enum ScanOutcome {
     Success {
         target: String,
-        scan_results: ScanResults,
+        scan_results: Box<ScanResults>,
         found_vulnerability: bool,
     },
     Failure {
         target: String,
         error: String,
+        /// Kind and retryability, when the failure came from a typed error
+        error_info: Option<ErrorInfo>,
     },
 }
 
@@ -81,17 +134,122 @@ impl ScanOutcome {
             }
         )
     }
+
+    /// What the scan tells `--auto-concurrency` about the target's load.
+    fn sample(&self) -> ScanSample {
+        match self {
+            ScanOutcome::Success { scan_results, .. } => ScanSample::from_results(scan_results),
+            ScanOutcome::Failure { .. } => ScanSample::failed(),
+        }
+    }
+
+    /// Convert into the structured result, synthesizing a minimal entry for a
+    /// failure so every requested target appears in machine-readable output.
+    fn into_scan_results(self, method: &str) -> ScanResults {
+        match self {
+            ScanOutcome::Success { scan_results, .. } => *scan_results,
+            ScanOutcome::Failure {
+                target,
+                error,
+                error_info,
+            } => ScanResults {
+                target,
+                address: None,
+                vhost: None,
+                ip: None,
+                port: None,
+                scheme: None,
+                method: method.to_string(),
+                timestamp: chrono::Utc::now().to_rfc3339(),
+                fingerprint: None,
+                checks: Vec::new(),
+                exploits: None,
+                proxy_chain: Vec::new(),
+                unreachable: None,
+                throttle: None,
+                target_info: None,
+                error: Some(error),
+                error_info,
+                duration_ms: None,
+                requests_sent: None,
+                summary: None,
+            },
+        }
+    }
+}
+
+/// Resolve `--proxy-auth` (`@env:`/`@file:` allowed) and install it for the
+/// proxy CONNECT.
+fn configure_proxy_auth(cli: &Cli) -> Result<()> {
+    if let Some(ref value) = cli.proxy_auth {
+        let value = expand_value(value, "--proxy-auth")?;
+        http::set_proxy_auth(ProxyCredentials::parse(&value)?);
+    }
+    Ok(())
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let mut cli = Cli::parse();
     cli.apply_global_settings();
+    if let Err(e) = configure_proxy_auth(&cli) {
+        emit_input_error(&cli, &e.to_string());
+        std::process::exit(2);
+    }
+
+    match cli.command.take() {
+        Some(Command::Serve(serve)) => {
+            // Workers initialize TLS from the coordinator's profile, so dispatch
+            // before the global TLS init below.
+            let code = match run_serve(&cli, serve).await {
+                Ok(code) => code,
+                Err(e) => {
+                    emit_input_error(&cli, &e.to_string());
+                    2
+                }
+            };
+            std::process::exit(code);
+        }
+        Some(Command::Report(report)) => {
+            std::process::exit(run_report(&cli, report));
+        }
+        Some(Command::Stats(stats)) => {
+            std::process::exit(run_stats(&cli, stats));
+        }
+        Some(Command::Completions(completions)) => {
+            print!("{}", smugglex::cli::completion_script(completions.shell));
+            return Ok(());
+        }
+        Some(Command::Payloads(payloads)) => {
+            std::process::exit(run_payloads(&cli, payloads));
+        }
+        Some(Command::Wordlists(wordlists)) => {
+            std::process::exit(run_wordlists(&cli, wordlists).await);
+        }
+        Some(Command::Validate(validate)) => {
+            std::process::exit(run_validate(&cli, validate));
+        }
+        Some(Command::Reproduce(reproduce)) => {
+            std::process::exit(run_reproduce(&cli, reproduce));
+        }
+        Some(Command::Retest(retest)) => {
+            std::process::exit(run_retest(&cli, retest).await);
+        }
+        Some(Command::Daemon(daemon)) => {
+            if let Err(e) = run_daemon(&cli, daemon).await {
+                emit_input_error(&cli, &e.to_string());
+                std::process::exit(2);
+            }
+            return Ok(());
+        }
+        None => {}
+    }
 
     // Initialize TLS config (must happen before any network requests).
     http::init_tls_config(
         cli.insecure,
         cli.cacert.as_deref().map(std::path::Path::new),
+        !cli.no_tls_resume,
     )
     .unwrap_or_else(|e| {
         eprintln!("{} TLS init error: {}", "[!]".yellow().bold(), e);
@@ -99,8 +257,8 @@ async fn main() -> Result<()> {
     });
 
     // Activate machine mode for clean structured output (used by AI agents, scripts, CI).
-    // When active, stdout will contain *only* JSON; all chatter goes to stderr or is suppressed.
-    if cli.effective_format().is_json() {
+    // When active, stdout will contain *only* JSON (or JUnit XML); all chatter goes to stderr or is suppressed.
+    if cli.effective_format().is_json() || cli.effective_format().is_junit() {
         set_machine(true);
         // In pure machine mode we also want to suppress most progress noise.
         // (progress bar creation below already respects verbose, we additionally hide it for json)
@@ -111,19 +269,24 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
-    let urls = match resolve_urls(&mut cli) {
-        Ok(urls) => urls,
+    let targets = match resolve_targets(&mut cli) {
+        Ok(targets) => targets,
         Err(e) => {
             emit_input_error(&cli, &e.to_string());
             // Usage/input error → exit 2 (common convention for CLI tools)
             std::process::exit(2);
         }
     };
-    if urls.is_empty() {
+    if targets.is_empty() {
         emit_input_error(&cli, "No valid URLs provided");
         // Usage/input error → exit 2 (common convention for CLI tools)
         std::process::exit(2);
     }
+    let targets = in_scope_targets(&cli, targets, |(url, _)| url);
+    if targets.is_empty() {
+        emit_input_error(&cli, "every target is out of scope");
+        std::process::exit(2);
+    }
 
     // Validate `--checks` up front (target-independent): a typo must not
     // silently scan nothing and report a clean target with exit 0.
@@ -154,26 +317,114 @@ async fn main() -> Result<()> {
         }
     }
 
+    // `@env:`/`@file:` header values must resolve before anything is sent.
+    if let Err(e) = expand_headers(&cli.headers) {
+        emit_input_error(&cli, &e.to_string());
+        std::process::exit(2);
+    }
+    if let Err(e) = cli.vhosts() {
+        emit_input_error(&cli, &e.to_string());
+        std::process::exit(2);
+    }
+
+    if let Some(ref fuzz_checks) = cli.fuzz_checks {
+        let unknown =
+            smugglex::cli::unknown_check_names(fuzz_checks, &smugglex::cli::KNOWN_CHECK_NAMES);
+        if !unknown.is_empty() && !is_machine() {
+            log(
+                LogLevel::Warning,
+                &format!(
+                    "ignoring unrecognized --fuzz-checks name(s): {}",
+                    unknown.join(", "),
+                ),
+            );
+        }
+    }
+
+    if let Some(ref exploit) = cli.exploit
+        && exploit.split(',').any(|x| x.trim() == "internal-sweep")
+    {
+        let range = match cli.range {
+            Some(ref range) => SweepRange::parse(range).map(|_| ()),
+            None => Err(SmugglexError::Config(
+                "--exploit internal-sweep requires --range".to_string(),
+            )),
+        };
+        if let Err(e) = range {
+            emit_input_error(&cli, &e.to_string());
+            std::process::exit(2);
+        }
+    }
+
+    if cli.detect.contains(&DetectionMethod::Oob) && cli.oob_host.is_none() {
+        emit_input_error(&cli, "--detect oob requires --oob-host");
+        std::process::exit(2);
+    }
+
+    // A bad SMTP URL or address must not surface only once there is
+    // something to report.
+    let mailer = match email_settings(&cli) {
+        Ok(mailer) => mailer,
+        Err(e) => {
+            emit_input_error(&cli, &e.to_string());
+            std::process::exit(2);
+        }
+    };
+
+    let syslog = match cli.syslog.as_deref().map(SyslogSink::parse).transpose() {
+        Ok(syslog) => syslog,
+        Err(e) => {
+            emit_input_error(&cli, &e.to_string());
+            std::process::exit(2);
+        }
+    };
+
+    if cli.triage {
+        let urls = targets.into_iter().map(|(url, _)| url).collect();
+        std::process::exit(run_triage(&cli, urls).await);
+    }
+
+    if let Some(ref path) = cli.export_all
+        && let Err(e) = open_archive(path)
+    {
+        emit_input_error(&cli, &format!("cannot create {}: {}", path.display(), e));
+        std::process::exit(2);
+    }
+
     // Collect outcomes from all targets. This enables:
     // - Clean single JSON document for batch scans (critical for AI / jq / scripts)
     // - Correct exit code (0 = clean, 1 = vulnerable found)
-    let mut outcomes: Vec<ScanOutcome> = Vec::with_capacity(urls.len());
-
-    if cli.concurrency > 1 {
+    let mut outcomes: Vec<ScanOutcome> = Vec::with_capacity(targets.len());
+
+    let mut tuner = cli.auto_concurrency.then(|| {
+        AutoConcurrency::new(if cli.concurrency > 1 {
+            cli.concurrency
+        } else {
+            DEFAULT_AUTO_CONCURRENCY_MAX
+        })
+    });
+    if cli.concurrency > 1 || tuner.is_some() {
         // Concurrent processing in chunks (preserves previous backpressure behavior)
-        for chunk in urls.chunks(cli.concurrency) {
+        let mut rest = &targets[..];
+        while !rest.is_empty() {
+            let size = tuner
+                .as_ref()
+                .map_or(cli.concurrency, AutoConcurrency::current);
+            let (chunk, tail) = rest.split_at(size.min(rest.len()));
+            rest = tail;
+            let round_start = outcomes.len();
             let mut handles = Vec::new();
-            for target_url in chunk {
+            for (target_url, target_cli) in chunk {
                 let url = target_url.clone();
-                let cli_ref = cli.clone();
+                let cli_ref = target_cli.clone();
                 handles.push((
                     url.clone(),
-                    tokio::spawn(async move { scan_one_target(url, cli_ref).await }),
+                    tokio::spawn(async move { scan_target_vhosts(url, cli_ref).await }),
                 ));
             }
             for (target, handle) in handles {
                 match handle.await {
-                    Ok(outcome) => outcomes.push(outcome),
+                    Ok(target_outcomes) => outcomes.extend(target_outcomes),
                     Err(join_err) => {
                         if !is_machine() {
                             log(
@@ -184,15 +435,52 @@ async fn main() -> Result<()> {
                         outcomes.push(ScanOutcome::Failure {
                             target,
                             error: format!("worker task failed: {}", join_err),
+                            error_info: None,
                         });
                     }
                 }
             }
+            if let Some(ref mut tuner) = tuner {
+                let round: Vec<ScanSample> = outcomes[round_start..]
+                    .iter()
+                    .map(ScanOutcome::sample)
+                    .collect();
+                if let Some(adjustment) = tuner.observe(&round)
+                    && !is_machine()
+                {
+                    match adjustment.reason {
+                        Some(reason) => log(
+                            LogLevel::Warning,
+                            &format!(
+                                "auto-concurrency: {} -> {}, {}",
+                                adjustment.from, adjustment.to, reason
+                            ),
+                        ),
+                        None if cli.verbose => log(
+                            LogLevel::Info,
+                            &format!("auto-concurrency: {} -> {}", adjustment.from, adjustment.to),
+                        ),
+                        None => {}
+                    }
+                }
+            }
         }
     } else {
-        for target_url in urls {
-            let outcome = scan_one_target(target_url, cli.clone()).await;
-            outcomes.push(outcome);
+        for (target_url, target_cli) in targets {
+            outcomes.extend(scan_target_vhosts(target_url, target_cli).await);
+        }
+    }
+
+    if let (Some(archive), Some(path)) = (active_archive(), cli.export_all.as_ref()) {
+        match archive.finish() {
+            Ok(count) => log(
+                LogLevel::Info,
+                &format!("archived {} payload(s) to {}", count, path.display()),
+            ),
+            Err(e) => log(
+                LogLevel::Error,
+                &format!("failed to write {}: {}", path.display(), e),
+            ),
         }
     }
 
@@ -202,36 +490,51 @@ async fn main() -> Result<()> {
         .iter()
         .any(|o| matches!(o, ScanOutcome::Failure { .. }));
 
+    // Convert outcomes to ScanResults (synthesize minimal entry for failures so every
+    // requested target appears in the output).
+    let scan_results: Vec<ScanResults> = outcomes
+        .into_iter()
+        .map(|o| o.into_scan_results(&cli.method))
+        .collect();
+    write_report_outputs(&cli, &scan_results);
+    record_history(&cli, &scan_results);
+    if any_vulnerable && let Some((ref server, ref from)) = mailer {
+        email_findings(&cli, server, from, &scan_results).await;
+    }
+    if let Some(ref sink) = syslog {
+        let events = syslog_events(&scan_results, &local_hostname());
+        if let Err(e) = send_syslog(sink, &events, cli.timeout).await {
+            log(
+                LogLevel::Error,
+                &format!("failed to send syslog events to {}: {}", sink.host, e),
+            );
+        }
+    }
+    if cli.effective_format().is_github() {
+        for line in github_annotations(&scan_results) {
+            println!("{}", line);
+        }
+    }
+
     // Emit results
     let json_mode = cli.effective_format().is_json();
-    if json_mode {
-        // Convert outcomes to ScanResults (synthesize minimal entry for failures so every
-        // requested target appears in the output).
-        let scan_results: Vec<ScanResults> = outcomes
-            .into_iter()
-            .map(|o| match o {
-                ScanOutcome::Success { scan_results, .. } => scan_results,
-                ScanOutcome::Failure { target, error } => ScanResults {
-                    target,
-                    method: cli.method.clone(),
-                    timestamp: chrono::Utc::now().to_rfc3339(),
-                    fingerprint: None,
-                    checks: Vec::new(),
-                    error: Some(error),
-                },
-            })
-            .collect();
-
+    let junit_mode = cli.effective_format().is_junit();
+    if json_mode || junit_mode {
+        if junit_mode {
+            print!("{}", render_junit(&scan_results, env!("CARGO_PKG_VERSION")));
+        }
         let batch = build_batch_results(scan_results, Some(env!("CARGO_PKG_VERSION")));
-        print_batch_json(&batch);
+        if json_mode {
+            print_batch_json(&batch);
+        }
 
-        if let Some(ref output_file) = cli.output
-            && let Err(e) = save_batch_to_file(&batch, output_file)
-        {
-            log(
-                LogLevel::Error,
-                &format!("failed to write batch output file: {}", e),
-            );
+        for output_file in cli.json_outputs() {
+            if let Err(e) = save_batch_to_file(&batch, output_file) {
+                log(
+                    LogLevel::Error,
+                    &format!("failed to write batch output file: {}", e),
+                );
+            }
         }
     } else {
         // Plain text mode: preserve previous per-target human output behavior.
@@ -254,76 +557,884 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-/// Emit an input/usage error consistently with the selected output format:
-/// a pure (empty) JSON envelope in machine mode so AI agents can parse uniformly,
-/// or a human-readable message otherwise. Callers exit with code 2 afterward.
-fn emit_input_error(cli: &Cli, message: &str) {
+/// Run `smugglex serve`: either coordinate a distributed scan or work for one.
+/// Returns the process exit code (same contract as a direct scan).
+async fn run_serve(cli: &Cli, serve: ServeArgs) -> Result<i32> {
+    match serve.role {
+        ServeRole::Coordinator => {
+            if cli.effective_format().is_json() || cli.effective_format().is_junit() {
+                set_machine(true);
+            }
+            let targets = if !serve.targets.is_empty() {
+                serve.targets.clone()
+            } else if cli.stdin_format == StdinFormat::Jsonl || cli.list.is_some() {
+                return Err(SmugglexError::Config(
+                    "--stdin-format jsonl jobs and -l targets files are not distributed; pass plain URLs"
+                        .to_string(),
+                ));
+            } else {
+                let mut stdin_cli = cli.clone();
+                resolve_urls(&mut stdin_cli)?
+            };
+            let targets = in_scope_targets(cli, targets, |url| url);
+            if targets.is_empty() {
+                return Err(SmugglexError::Config(
+                    "no targets to distribute".to_string(),
+                ));
+            }
+            // Workers scan one Host per target and take --vhost literally.
+            if let Some(ref vhost) = cli.vhost
+                && cli.vhosts()? != [vhost.clone()]
+            {
+                return Err(SmugglexError::Config(
+                    "--vhost lists and files are not distributed; pass a single host".to_string(),
+                ));
+            }
+            // The scan settings every worker applies, wherever they were set;
+            // refused here rather than by every worker.
+            let matches = Cli::command()
+                .try_get_matches()
+                .map_err(|e| SmugglexError::Config(e.to_string()))?;
+            let profile = Cli::worker_profile(&matches);
+            Cli::parse_worker_profile(&profile)?;
+            let config = smugglex::distributed::CoordinatorConfig {
+                listen: serve.listen.clone(),
+                token: serve.token.clone(),
+                shard_size: serve.shard_size as usize,
+                profile,
+            };
+            let results = smugglex::distributed::run_coordinator(config, targets).await?;
+            Ok(report_collected_results(cli, results))
+        }
+        ServeRole::Worker => {
+            let coordinator = serve
+                .coordinator
+                .as_deref()
+                .ok_or("--coordinator is required for --role worker")?;
+            let name = serve
+                .worker_name
+                .clone()
+                .unwrap_or_else(|| format!("worker-{}", std::process::id()));
+            let (profile, conn) =
+                smugglex::distributed::connect_worker(coordinator, &serve.token, &name).await?;
+
+            // A profile that would exploit, read files or write anything here
+            // is refused; results travel back to the coordinator.
+            let mut scan_cli = Cli::parse_worker_profile(&profile)
+                .map_err(|e| SmugglexError::Config(format!("invalid scan profile: {}", e)))?;
+            // Local paths are the worker's own to choose.
+            scan_cli.cacert = cli.cacert.clone();
+            scan_cli.data_dir = cli.data_dir.clone();
+            scan_cli.json = false;
+            scan_cli.format = smugglex::cli::OutputFormat::Plain;
+            scan_cli.apply_global_settings();
+            configure_proxy_auth(&scan_cli)?;
+            http::init_tls_config(
+                scan_cli.insecure,
+                scan_cli.cacert.as_deref().map(std::path::Path::new),
+                !scan_cli.no_tls_resume,
+            )?;
+            log(
+                LogLevel::Info,
+                &format!("connected to coordinator {} as '{}'", coordinator, name),
+            );
+
+            let scanned = smugglex::distributed::run_worker_shards(conn, |target| {
+                let scan_cli = scan_cli.clone();
+                async move {
+                    let method = scan_cli.method.clone();
+                    scan_one_target(target, scan_cli)
+                        .await
+                        .into_scan_results(&method)
+                }
+            })
+            .await?;
+            log(
+                LogLevel::Info,
+                &format!("coordinator finished; scanned {} target(s)", scanned),
+            );
+            Ok(0)
+        }
+    }
+}
+
+/// Runs daemon jobs through the same per-target routine as a direct scan,
+/// parsing each job's flags into its own `Cli`.
+struct CliScanRunner {
+    /// The daemon's own `--scope-include` / `--scope-exclude`, which bound
+    /// every job on top of the job's flags.
+    scope_include: Vec<regex::Regex>,
+    scope_exclude: Vec<regex::Regex>,
+}
+
+impl smugglex::daemon::ScanRunner for CliScanRunner {
+    fn validate(&self, args: &[String]) -> Result<()> {
+        Cli::parse_job_args(args).map(|_| ())
+    }
+
+    fn scan(&self, args: Vec<String>, target: String) -> smugglex::daemon::ScanFuture {
+        let scope = smugglex::cli::out_of_scope(&target, &self.scope_include, &self.scope_exclude);
+        Box::pin(async move {
+            if let Some(reason) = scope {
+                return ScanOutcome::Failure {
+                    target,
+                    error: format!("out of scope: {}", reason),
+                    error_info: None,
+                }
+                .into_scan_results(smugglex::cli::DEFAULT_METHOD);
+            }
+            match Cli::parse_job_args(&args) {
+                Ok(cli) => {
+                    let method = cli.method.clone();
+                    scan_one_target(target, cli)
+                        .await
+                        .into_scan_results(&method)
+                }
+                Err(e) => ScanOutcome::Failure {
+                    target,
+                    error: e.to_string(),
+                    error_info: Some(e.info()),
+                }
+                .into_scan_results(smugglex::cli::DEFAULT_METHOD),
+            }
+        })
+    }
+}
+
+/// Run `smugglex daemon` until the process is stopped.
+async fn run_daemon(cli: &Cli, daemon: DaemonArgs) -> Result<()> {
+    http::init_tls_config(
+        cli.insecure,
+        cli.cacert.as_deref().map(std::path::Path::new),
+        !cli.no_tls_resume,
+    )?;
+    let config = smugglex::daemon::DaemonConfig {
+        listen: daemon.listen,
+        token: daemon.token,
+        state_dir: daemon.state_dir,
+        read_timeout: smugglex::daemon::DEFAULT_READ_TIMEOUT,
+        max_finished_jobs: smugglex::daemon::DEFAULT_MAX_FINISHED_JOBS,
+    };
+    let runner = CliScanRunner {
+        scope_include: cli.scope_include.clone(),
+        scope_exclude: cli.scope_exclude.clone(),
+    };
+    smugglex::daemon::run_daemon(config, std::sync::Arc::new(runner)).await
+}
+
+/// Run `smugglex report`: render saved results offline and return the exit
+/// code (`2` when the results cannot be read or the report not written).
+fn run_report(cli: &Cli, report: ReportArgs) -> i32 {
+    let mut results = match load_scan_results(&report.results) {
+        Ok(results) => results,
+        Err(e) => {
+            emit_input_error(
+                cli,
+                &format!("cannot read {}: {}", report.results.display(), e),
+            );
+            return 2;
+        }
+    };
+    // Results saved with --no-redact (or by an older version) are masked now.
+    results.iter_mut().for_each(redact_scan_results);
+    let rendered = render_report(&results, report.format, env!("CARGO_PKG_VERSION"));
+    match report.output {
+        Some(ref output_file) => match save_report(&rendered, output_file) {
+            Ok(()) => 0,
+            Err(e) => {
+                log(
+                    LogLevel::Error,
+                    &format!("failed to write output file: {}", e),
+                );
+                2
+            }
+        },
+        None => {
+            print!("{}", rendered);
+            0
+        }
+    }
+}
+
+/// Run `smugglex payloads`. Returns `2` for an unknown `--check`.
+fn run_payloads(cli: &Cli, payloads: PayloadsArgs) -> i32 {
+    let PayloadsCommand::List(list) = payloads.command;
+    let families: Vec<_> = match list.check {
+        Some(ref check) => match payload_family(check.trim()) {
+            Some(family) => vec![family],
+            None => {
+                let names: Vec<&str> = PAYLOAD_FAMILIES.iter().map(|f| f.check).collect();
+                emit_input_error(
+                    cli,
+                    &format!(
+                        "unknown payload family '{}' (valid: {})",
+                        check,
+                        names.join(", ")
+                    ),
+                );
+                return 2;
+            }
+        },
+        None => PAYLOAD_FAMILIES.iter().collect(),
+    };
+    let entries: Vec<_> = families.iter().map(|f| f.catalog_entry()).collect();
+
     if cli.effective_format().is_json() {
-        let empty_batch = build_batch_results(Vec::new(), Some(env!("CARGO_PKG_VERSION")));
-        match serde_json::to_string_pretty(&empty_batch) {
+        match serde_json::to_string_pretty(&entries) {
             Ok(json) => println!("{}", json),
             Err(e) => {
                 log(
                     LogLevel::Error,
-                    &format!("failed to serialize empty batch results: {}", e),
-                );
-                println!(
-                    "{}",
-                    serde_json::json!({
-                        "results": [],
-                        "summary": {
-                            "total_targets": 0,
-                            "vulnerable_targets": 0,
-                            "total_checks": 0,
-                            "vulnerable_checks": 0
-                        }
-                    })
+                    &format!("failed to serialize payload catalog: {}", e),
                 );
+                return 2;
             }
         }
     } else {
-        eprintln!("{} {}", "[!]".yellow().bold(), message);
+        log_payload_catalog(&entries);
     }
+    0
 }
 
-/// Load a `--raw-request` file and apply it as the request template: parse it,
-/// override the request fields the payload generators read (method, headers,
-/// Host), and return the synthetic target URL the scan pipeline consumes.
-fn apply_raw_request(cli: &mut Cli) -> Result<String> {
-    let path = cli
-        .raw_request
-        .clone()
-        .expect("apply_raw_request called without --raw-request");
-    let content = std::fs::read_to_string(&path).map_err(|e| {
-        SmugglexError::Io(format!("failed to read raw request file '{}': {}", path, e))
-    })?;
-    let raw = parse_raw_request(&content)?;
-
-    // Connection target only (scheme/host/port). The request-target is applied
-    // separately and verbatim via cli.raw_target so its exact bytes survive.
-    let connect_url = raw.connect_url(&cli.raw_request_proto);
-
-    // The captured body is intentionally discarded (the payloads craft their own);
-    // note it in verbose mode so the user isn't surprised it had no effect.
-    if raw.had_body && cli.verbose && !is_machine() {
+/// Run `smugglex stats`: summarize the history directory's index. Returns the
+/// exit code.
+fn run_stats(cli: &Cli, stats: StatsArgs) -> i32 {
+    let Some(dir) = stats.history_dir.or_else(|| cli.history_dir.clone()) else {
+        emit_input_error(cli, "stats requires --history-dir");
+        return 2;
+    };
+    let dir = expand_home(&dir);
+    let entries = match read_index(&dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            emit_input_error(cli, &format!("{}: {}", dir.display(), e));
+            return 2;
+        }
+    };
+    let summary = history_stats(&entries);
+    if cli.effective_format().is_json() {
+        match serde_json::to_string_pretty(&summary) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                log(
+                    LogLevel::Error,
+                    &format!("failed to serialize history stats: {}", e),
+                );
+                return 2;
+            }
+        }
+    } else if entries.is_empty() {
         log(
             LogLevel::Info,
-            "captured request body ignored; smuggling payloads generate their own body",
+            &format!("no scans recorded in {}", dir.display()),
         );
+    } else {
+        log_history_stats(&summary);
     }
+    0
+}
 
-    // The captured request's method wins, so warn if the user also passed an
-    // explicit --method (anything other than the default) that we're discarding.
-    let user_set_method = cli.method != smugglex::cli::DEFAULT_METHOD;
-    if user_set_method && cli.method != raw.method && !is_machine() {
-        log(
-            LogLevel::Warning,
-            &format!(
-                "--method {} is ignored; the captured request method {} (from --raw-request) is used instead",
-                cli.method, raw.method
-            ),
-        );
-    }
+/// Run `smugglex wordlists`: download the curated wordlists, or list them.
+/// Returns the exit code (`1` when a download failed).
+async fn run_wordlists(cli: &Cli, wordlists: WordlistsArgs) -> i32 {
+    let dir = match wordlist_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            emit_input_error(cli, &e.to_string());
+            return 2;
+        }
+    };
+    let update = match wordlists.command {
+        WordlistsCommand::Update(update) => update,
+        WordlistsCommand::List => {
+            let statuses = wordlist_status(&dir);
+            if cli.effective_format().is_json() {
+                match serde_json::to_string_pretty(&statuses) {
+                    Ok(json) => println!("{}", json),
+                    Err(e) => {
+                        log(
+                            LogLevel::Error,
+                            &format!("failed to serialize wordlists: {}", e),
+                        );
+                        return 2;
+                    }
+                }
+            } else {
+                log_wordlists(&statuses);
+            }
+            return 0;
+        }
+    };
+
+    let mut selected = Vec::new();
+    for name in &update.names {
+        match find_wordlist(name) {
+            Some(wordlist) => selected.push(wordlist),
+            None => {
+                let names: Vec<&str> = WORDLISTS.iter().map(|w| w.name).collect();
+                emit_input_error(
+                    cli,
+                    &format!("unknown wordlist '{}' (valid: {})", name, names.join(", ")),
+                );
+                return 2;
+            }
+        }
+    }
+    if selected.is_empty() {
+        selected = WORDLISTS.iter().collect();
+    }
+    if let Err(e) = http::init_tls_config(
+        cli.insecure,
+        cli.cacert.as_deref().map(std::path::Path::new),
+        !cli.no_tls_resume,
+    ) {
+        emit_input_error(cli, &format!("TLS init error: {}", e));
+        return 2;
+    }
+
+    let mut failed = 0;
+    for wordlist in selected {
+        let saved = match download_wordlist(wordlist, cli.timeout).await {
+            Ok(content) => save_wordlist(&dir, wordlist, &content).map(|path| (content, path)),
+            Err(e) => Err(e),
+        };
+        match saved {
+            Ok((content, path)) => log(
+                LogLevel::Info,
+                &format!(
+                    "{}: {} entries saved to {}",
+                    wordlist.name,
+                    wordlist_entries(&content).len(),
+                    path.display()
+                ),
+            ),
+            Err(e) => {
+                failed += 1;
+                log(
+                    LogLevel::Error,
+                    &format!("{}: download failed: {}", wordlist.name, e),
+                );
+            }
+        }
+    }
+    if failed > 0 { 1 } else { 0 }
+}
+
+/// Run `smugglex validate`: lint every template and return the exit code
+/// (`1` when a template has errors, `2` when there was nothing to validate).
+fn run_validate(cli: &Cli, validate: ValidateArgs) -> i32 {
+    let validations = match validate_path(&validate.path, validate.host.as_deref()) {
+        Ok(validations) => validations,
+        Err(e) => {
+            emit_input_error(
+                cli,
+                &format!("cannot read {}: {}", validate.path.display(), e),
+            );
+            return 2;
+        }
+    };
+    if validations.is_empty() {
+        emit_input_error(
+            cli,
+            &format!("no templates found in {}", validate.path.display()),
+        );
+        return 2;
+    }
+
+    if cli.effective_format().is_json() {
+        match serde_json::to_string_pretty(&validations) {
+            Ok(json) => println!("{}", json),
+            Err(e) => log(
+                LogLevel::Error,
+                &format!("failed to serialize validation results: {}", e),
+            ),
+        }
+    } else {
+        log_template_validations(&validations);
+    }
+
+    if validations.iter().any(|v| !v.errors.is_empty()) {
+        1
+    } else {
+        0
+    }
+}
+
+/// Run `smugglex reproduce`: print the one-liner of the `--id` finding, or of
+/// every finding without it. Returns `2` when the results cannot be read or
+/// the finding cannot be replayed.
+fn run_reproduce(cli: &Cli, reproduce: ReproduceArgs) -> i32 {
+    let json = cli.effective_format().is_json();
+    if json {
+        set_machine(true);
+    }
+    let findings = match load_saved_findings(&reproduce.results) {
+        Ok(findings) => findings,
+        Err(e) => {
+            emit_input_error(
+                cli,
+                &format!("cannot read {}: {}", reproduce.results.display(), e),
+            );
+            return 2;
+        }
+    };
+    let headers = match expand_headers(&cli.headers) {
+        Ok(headers) => headers,
+        Err(e) => {
+            emit_input_error(cli, &e.to_string());
+            return 2;
+        }
+    };
+
+    let selected: Vec<(usize, _)> = match reproduce.id {
+        Some(ref id) => match select_finding(&findings, id) {
+            Ok(i) => vec![(i + 1, &findings[i])],
+            Err(e) => {
+                emit_input_error(cli, &e.to_string());
+                return 2;
+            }
+        },
+        None => findings
+            .iter()
+            .enumerate()
+            .map(|(i, f)| (i + 1, f))
+            .collect(),
+    };
+    if selected.is_empty() && !json {
+        log(
+            LogLevel::Info,
+            &format!("no findings in {}", reproduce.results.display()),
+        );
+    }
+
+    let mut reproductions = Vec::with_capacity(selected.len());
+    let mut code = 0;
+    for (number, finding) in selected {
+        match build_reproduction(finding, reproduce.tool, &headers) {
+            Ok(reproduction) => {
+                if !json {
+                    log_reproduction(number, &reproduction);
+                }
+                reproductions.push(reproduction);
+            }
+            Err(e) => {
+                log(
+                    LogLevel::Warning,
+                    &format!("finding {} ({}): {}", number, finding_id(finding), e),
+                );
+                if reproduce.id.is_some() {
+                    code = 2;
+                }
+            }
+        }
+    }
+    if json {
+        match serde_json::to_string_pretty(&reproductions) {
+            Ok(json) => println!("{}", json),
+            Err(e) => log(
+                LogLevel::Error,
+                &format!("failed to serialize reproductions: {}", e),
+            ),
+        }
+    }
+    code
+}
+
+/// Run `smugglex retest`: replay every saved finding and return the exit code
+/// (`1` when one still reproduces, `2` when one could not be replayed).
+async fn run_retest(cli: &Cli, retest: RetestArgs) -> i32 {
+    if let Err(e) = http::init_tls_config(
+        cli.insecure,
+        cli.cacert.as_deref().map(std::path::Path::new),
+        !cli.no_tls_resume,
+    ) {
+        emit_input_error(cli, &format!("TLS init error: {}", e));
+        return 2;
+    }
+    if cli.effective_format().is_json() {
+        set_machine(true);
+    }
+    let findings = match load_saved_findings(&retest.results) {
+        Ok(findings) => findings,
+        Err(e) => {
+            emit_input_error(
+                cli,
+                &format!("cannot read {}: {}", retest.results.display(), e),
+            );
+            return 2;
+        }
+    };
+    if findings.is_empty() && !is_machine() {
+        log(
+            LogLevel::Info,
+            &format!("no findings to retest in {}", retest.results.display()),
+        );
+    }
+
+    let headers = match expand_headers(&cli.headers) {
+        Ok(headers) => headers,
+        Err(e) => {
+            emit_input_error(cli, &e.to_string());
+            return 2;
+        }
+    };
+    let settings = RetestSettings {
+        timeout: cli.timeout,
+        verbose: cli.verbose && !is_machine(),
+        delay: cli.delay,
+        baseline_count: cli.baseline_count,
+        warmup: cli.warmup,
+        storm_cooldown: cli.storm_cooldown,
+        detect: &cli.detect,
+        oob_host: cli.oob_host.as_deref(),
+        headers: &headers,
+    };
+    let mut results = Vec::with_capacity(findings.len());
+    for finding in &findings {
+        if let Some(reason) = cli.out_of_scope(&finding.target) {
+            if !is_machine() {
+                log(
+                    LogLevel::Warning,
+                    &format!(
+                        "skipping out-of-scope finding on {}: {}",
+                        finding.target, reason
+                    ),
+                );
+            }
+            continue;
+        }
+        if !is_machine() {
+            log(
+                LogLevel::Info,
+                &format!(
+                    "retesting {} on {}",
+                    finding.check.check_type, finding.target
+                ),
+            );
+        }
+        results.push(retest_finding(finding, &settings).await);
+    }
+
+    let report = build_retest_report(results, Some(env!("CARGO_PKG_VERSION")));
+    if is_machine() {
+        match serde_json::to_string_pretty(&report) {
+            Ok(json) => println!("{}", json),
+            Err(e) => log(
+                LogLevel::Error,
+                &format!("failed to serialize retest results: {}", e),
+            ),
+        }
+    } else {
+        log_retest_report(&report);
+    }
+    for output_file in &cli.output {
+        if let Err(e) = save_retest_report(&report, output_file) {
+            log(
+                LogLevel::Error,
+                &format!("failed to write output file: {}", e),
+            );
+        }
+    }
+
+    if report.reproduced > 0 {
+        1
+    } else if report.errors > 0 {
+        2
+    } else {
+        0
+    }
+}
+
+/// Run `--triage` over `urls`, `-j` at a time, and emit the ranked list.
+/// Exits 0 unless every target failed (2): a triage signal is not a finding.
+async fn run_triage(cli: &Cli, urls: Vec<String>) -> i32 {
+    let settings = TriageSettings {
+        timeout: cli.timeout,
+        verbose: cli.verbose && !is_machine(),
+        method: cli.method.clone(),
+        headers: cli.headers.clone(),
+    };
+    if !is_machine() {
+        log(
+            LogLevel::Info,
+            &format!("triaging {} target(s)", urls.len()),
+        );
+    }
+    let mut results = Vec::with_capacity(urls.len());
+    for chunk in urls.chunks(cli.concurrency.max(1)) {
+        let handles: Vec<_> = chunk
+            .iter()
+            .map(|target| {
+                let (target, settings) = (target.clone(), settings.clone());
+                tokio::spawn(async move { triage_target(&target, &settings).await })
+            })
+            .collect();
+        for (target, handle) in chunk.iter().zip(handles) {
+            results.push(handle.await.unwrap_or_else(|join_err| TriageResult {
+                target: target.clone(),
+                score: 0,
+                signals: Vec::new(),
+                status: None,
+                error: Some(format!("worker task failed: {}", join_err)),
+            }));
+        }
+    }
+
+    let report = build_triage_report(results, Some(env!("CARGO_PKG_VERSION")));
+    if is_machine() {
+        match serde_json::to_string_pretty(&report) {
+            Ok(json) => println!("{}", json),
+            Err(e) => log(
+                LogLevel::Error,
+                &format!("failed to serialize triage results: {}", e),
+            ),
+        }
+    } else {
+        log_triage_report(&report);
+    }
+    for output_file in &cli.output {
+        if let Err(e) = save_triage_report(&report, output_file) {
+            log(
+                LogLevel::Error,
+                &format!("failed to write output file: {}", e),
+            );
+        }
+    }
+
+    if report.errors == report.results.len() {
+        2
+    } else {
+        0
+    }
+}
+
+/// Render every target's results into the `-o` files whose extension names
+/// a report format (`.html`, `.md`, `.sarif`, `.csv`).
+fn write_report_outputs(cli: &Cli, results: &[ScanResults]) {
+    for (output_file, format) in cli.report_outputs() {
+        let rendered = render_report(results, format, env!("CARGO_PKG_VERSION"));
+        if let Err(e) = save_report(&rendered, output_file) {
+            log(
+                LogLevel::Error,
+                &format!("failed to write output file: {}", e),
+            );
+        }
+    }
+}
+
+/// Store every target's results in `--history-dir`.
+fn record_history(cli: &Cli, results: &[ScanResults]) {
+    let Some(ref dir) = cli.history_dir else {
+        return;
+    };
+    let dir = expand_home(dir);
+    let recorded = results
+        .iter()
+        .filter(|scan| match record_results(&dir, scan) {
+            Ok(_) => true,
+            Err(e) => {
+                log(
+                    LogLevel::Error,
+                    &format!(
+                        "failed to record {} in {}: {}",
+                        scan.target,
+                        dir.display(),
+                        e
+                    ),
+                );
+                false
+            }
+        })
+        .count();
+    if !is_machine() {
+        log(
+            LogLevel::Info,
+            &format!("recorded {} result(s) in {}", recorded, dir.display()),
+        );
+    }
+}
+
+/// The `--smtp` server and sender for `--email-to`, validated; `None`
+/// without `--email-to`.
+fn email_settings(cli: &Cli) -> Result<Option<(SmtpServer, String)>> {
+    if cli.email_to.is_empty() {
+        return Ok(None);
+    }
+    let smtp = cli
+        .smtp
+        .as_deref()
+        .ok_or_else(|| SmugglexError::Config("--email-to requires --smtp".to_string()))?;
+    let server = SmtpServer::parse(smtp)?;
+    let from = cli
+        .email_from
+        .clone()
+        .unwrap_or_else(|| server.default_sender());
+    for address in cli.email_to.iter().chain(std::iter::once(&from)) {
+        validate_address(address)?;
+    }
+    Ok(Some((server, from)))
+}
+
+/// Mail the summary of `results`' findings to `--email-to`, with the batch
+/// results attached. A failure is logged; the scan's outcome stands.
+async fn email_findings(cli: &Cli, server: &SmtpServer, from: &str, results: &[ScanResults]) {
+    let Some((subject, body)) = findings_summary(results) else {
+        return;
+    };
+    let batch = build_batch_results(results.to_vec(), Some(env!("CARGO_PKG_VERSION")));
+    let attachment = serde_json::to_string_pretty(&batch).unwrap_or_default();
+    let message = build_message(from, &cli.email_to, &subject, &body, &attachment);
+    match send_mail(server, from, &cli.email_to, &message, cli.timeout).await {
+        Ok(()) => {
+            if !is_machine() {
+                log(
+                    LogLevel::Info,
+                    &format!("emailed findings to {}", cli.email_to.join(", ")),
+                );
+            }
+        }
+        Err(e) => log(
+            LogLevel::Error,
+            &format!("failed to email findings via {}: {}", server.host, e),
+        ),
+    }
+}
+
+/// Emit results gathered outside the direct scan loop (e.g. from distributed
+/// workers) in the selected format and return the exit code.
+fn report_collected_results(cli: &Cli, results: Vec<ScanResults>) -> i32 {
+    let any_vulnerable = results
+        .iter()
+        .any(|r| r.checks.iter().any(|c| c.vulnerable));
+    let any_failures = results.iter().any(|r| r.error.is_some());
+    write_report_outputs(cli, &results);
+    record_history(cli, &results);
+
+    if cli.effective_format().is_json() || cli.effective_format().is_junit() {
+        if cli.effective_format().is_junit() {
+            print!("{}", render_junit(&results, env!("CARGO_PKG_VERSION")));
+        }
+        let batch = build_batch_results(results, Some(env!("CARGO_PKG_VERSION")));
+        if cli.effective_format().is_json() {
+            print_batch_json(&batch);
+        }
+        for output_file in cli.json_outputs() {
+            if let Err(e) = save_batch_to_file(&batch, output_file) {
+                log(
+                    LogLevel::Error,
+                    &format!("failed to write batch output file: {}", e),
+                );
+            }
+        }
+    } else {
+        for result in &results {
+            if let Some(ref error) = result.error {
+                log(
+                    LogLevel::Error,
+                    &format!("failed to scan {}: {}", result.target, error),
+                );
+                continue;
+            }
+            log_scan_results(
+                &result.checks,
+                &cli.effective_format(),
+                &result.target,
+                &result.method,
+                &result.fingerprint,
+                &result.proxy_chain,
+            );
+            for output_file in cli.json_outputs() {
+                if let Err(e) = save_scan_results(output_file, result) {
+                    log(
+                        LogLevel::Error,
+                        &format!("failed to write output file: {}", e),
+                    );
+                }
+            }
+        }
+    }
+
+    if any_vulnerable {
+        1
+    } else if any_failures {
+        2
+    } else {
+        0
+    }
+}
+
+/// Emit an input/usage error consistently with the selected output format:
+/// a pure (empty) JSON envelope in machine mode so AI agents can parse uniformly,
+/// or a human-readable message otherwise. Callers exit with code 2 afterward.
+fn emit_input_error(cli: &Cli, message: &str) {
+    if cli.effective_format().is_json() {
+        let empty_batch = build_batch_results(Vec::new(), Some(env!("CARGO_PKG_VERSION")));
+        match serde_json::to_string_pretty(&empty_batch) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                log(
+                    LogLevel::Error,
+                    &format!("failed to serialize empty batch results: {}", e),
+                );
+                println!(
+                    "{}",
+                    serde_json::json!({
+                        "results": [],
+                        "summary": {
+                            "total_targets": 0,
+                            "vulnerable_targets": 0,
+                            "total_checks": 0,
+                            "vulnerable_checks": 0
+                        }
+                    })
+                );
+            }
+        }
+    } else {
+        eprintln!("{} {}", "[!]".yellow().bold(), message);
+    }
+}
+
+/// Load a `--raw-request` file and apply it as the request template: parse it,
+/// override the request fields the payload generators read (method, headers,
+/// Host), and return the synthetic target URL the scan pipeline consumes.
+fn apply_raw_request(cli: &mut Cli) -> Result<String> {
+    let path = cli
+        .raw_request
+        .clone()
+        .expect("apply_raw_request called without --raw-request");
+    let content = std::fs::read_to_string(&path).map_err(|e| {
+        SmugglexError::Io(format!("failed to read raw request file '{}': {}", path, e))
+    })?;
+    let raw = parse_raw_request(&content)?;
+
+    // Connection target only (scheme/host/port). The request-target is applied
+    // separately and verbatim via cli.raw_target so its exact bytes survive.
+    let connect_url = raw.connect_url(&cli.raw_request_proto);
+
+    // The captured body is intentionally discarded (the payloads craft their own)
+    // unless it carries a `{{BODY}}` marker; note the discard in verbose mode so
+    // the user isn't surprised it had no effect.
+    let body_marked = raw.markers.as_ref().is_some_and(|m| m.body.is_some());
+    if raw.had_body && !body_marked && cli.verbose && !is_machine() {
+        log(
+            LogLevel::Info,
+            "captured request body ignored; smuggling payloads generate their own body",
+        );
+    }
+
+    // The captured request's method wins, so warn if the user also passed an
+    // explicit --method (anything other than the default) that we're discarding.
+    let user_set_method = cli.method != smugglex::cli::DEFAULT_METHOD;
+    if user_set_method && cli.method != raw.method && !is_machine() {
+        log(
+            LogLevel::Warning,
+            &format!(
+                "--method {} is ignored; the captured request method {} (from --raw-request) is used instead",
+                cli.method, raw.method
+            ),
+        );
+    }
 
     // Feed the captured request into the same fields the payload builders read.
     cli.method = raw.method;
@@ -333,6 +1444,9 @@ fn apply_raw_request(cli: &mut Cli) -> Result<String> {
     cli.headers = merge_headers(raw.headers, &user_headers);
     // Apply the request-target verbatim downstream (no URL normalization).
     cli.raw_target = Some(raw.target);
+    // Markers are applied to each generated payload; the marker lines themselves
+    // ride along in cli.headers so they keep their slot in the header order.
+    cli.raw_markers = raw.markers;
     // Preserve the exact Host header (including any :port) unless --vhost was set explicitly.
     if cli.vhost.is_none() {
         cli.vhost = Some(raw.host_header);
@@ -344,7 +1458,7 @@ fn apply_raw_request(cli: &mut Cli) -> Result<String> {
 fn resolve_urls(cli: &mut Cli) -> Result<Vec<String>> {
     if cli.raw_request.is_some() {
         if !cli.urls.is_empty() {
-            return Err(SmugglexError::InvalidInput(
+            return Err(SmugglexError::Config(
                 "--raw-request cannot be combined with target URLs; the target is taken from the request file".to_string(),
             ));
         }
@@ -373,6 +1487,224 @@ fn resolve_urls(cli: &mut Cli) -> Result<Vec<String>> {
     }
 }
 
+/// The targets to scan, each with the settings it is scanned with: the
+/// command line for URLs, or each job's own for `--stdin-format jsonl` and
+/// `-l` targets files.
+fn resolve_targets(cli: &mut Cli) -> Result<Vec<(String, Cli)>> {
+    if let Some(ref list) = cli.list {
+        let input = std::fs::read_to_string(list).map_err(|e| {
+            SmugglexError::Config(format!(
+                "cannot read targets file {}: {}",
+                list.display(),
+                e
+            ))
+        })?;
+        let jobs = parse_target_list(&input, &list.display().to_string())?;
+        let mut targets: Vec<(String, Cli)> = cli
+            .urls
+            .iter()
+            .map(|url| (url.clone(), cli.clone()))
+            .collect();
+        targets.extend(job_targets(cli, &jobs)?);
+        return Ok(targets);
+    }
+    if cli.stdin_format != StdinFormat::Jsonl || !cli.urls.is_empty() || io::stdin().is_terminal() {
+        let urls = resolve_urls(cli)?;
+        return Ok(urls.into_iter().map(|url| (url, cli.clone())).collect());
+    }
+    if cli.raw_request.is_some() {
+        return Err(SmugglexError::Config(
+            "--raw-request cannot be combined with --stdin-format jsonl".to_string(),
+        ));
+    }
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+    job_targets(cli, &parse_jobs(&input)?)
+}
+
+/// Each of `jobs` with the settings it is scanned with.
+fn job_targets(cli: &Cli, jobs: &[ScanJob]) -> Result<Vec<(String, Cli)>> {
+    let base_args: Vec<String> = std::env::args().skip(1).collect();
+    jobs.iter()
+        .map(|job| {
+            let mut job_cli = job_cli(&base_args, job)?;
+            // Results are written and printed once, as the command line says.
+            job_cli.output = cli.output.clone();
+            job_cli.json = cli.json;
+            job_cli.format = cli.format.clone();
+            Ok((job.url.clone(), job_cli))
+        })
+        .collect()
+}
+
+/// `targets` without the ones whose `url` is outside `--scope-include` /
+/// `--scope-exclude`, logging each one skipped.
+fn in_scope_targets<T>(cli: &Cli, targets: Vec<T>, url: impl Fn(&T) -> &String) -> Vec<T> {
+    targets
+        .into_iter()
+        .filter(|target| match cli.out_of_scope(url(target)) {
+            Some(reason) => {
+                if !is_machine() {
+                    log(
+                        LogLevel::Warning,
+                        &format!("skipping out-of-scope target {}: {}", url(target), reason),
+                    );
+                }
+                false
+            }
+            None => true,
+        })
+        .collect()
+}
+
+/// Scan `target` once per `--vhost` entry, each with that Host header (see
+/// [`scan_target_addresses`]).
+async fn scan_target_vhosts(target: String, mut cli: Cli) -> Vec<ScanOutcome> {
+    // Validated at startup.
+    let target_host = Url::parse(&target)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_default();
+    let vhosts: Vec<String> = cli
+        .vhosts()
+        .unwrap_or_default()
+        .iter()
+        .map(|vhost| vhost_for_target(vhost, &target_host))
+        .collect();
+    if vhosts.len() <= 1 {
+        if let Some(vhost) = vhosts.into_iter().next() {
+            cli.vhost = Some(vhost);
+        }
+        return scan_target_addresses(target, cli).await;
+    }
+    if !is_machine() {
+        log(
+            LogLevel::Info,
+            &format!(
+                "{}: scanning {} virtual hosts: {}",
+                target,
+                vhosts.len(),
+                vhosts.join(", ")
+            ),
+        );
+    }
+    let mut outcomes = Vec::new();
+    for vhost in vhosts {
+        let mut vhost_cli = cli.clone();
+        vhost_cli.vhost = Some(vhost);
+        outcomes.extend(scan_target_addresses(target.clone(), vhost_cli).await);
+    }
+    outcomes
+}
+
+/// Run a check `repeat` times and merge the runs (`--repeat`).
+async fn repeat_check<F, Fut>(repeat: u32, mut run: F) -> CheckResult
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = CheckResult>,
+{
+    let mut runs = Vec::with_capacity(repeat as usize);
+    for _ in 0..repeat.max(1) {
+        runs.push(run().await);
+    }
+    merge_repeated_runs(runs).expect("a check runs at least once")
+}
+
+/// Run the real-HTTP/2 downgrade check against the target. Frame-level
+/// fuzzing follows the same --fuzz scoping as the payload checks; the corpus
+/// does not apply (mutants are frames, not requests).
+async fn run_h2_downgrade(
+    cli: &Cli,
+    host: &str,
+    port: u16,
+    host_header: &str,
+    path: &str,
+    verbose: bool,
+) -> CheckResult {
+    let h2_fuzz = (cli.fuzz
+        && cli
+            .fuzz_checks
+            .as_deref()
+            .is_none_or(|names| names.split(',').any(|n| n.trim() == "h2-downgrade")))
+    .then(|| MutatorConfig {
+        seed: cli.fuzz_seed,
+        mutations_per_payload: 5,
+        mode: cli.fuzz_mode,
+        max_mutants: cli.fuzz_budget.map(|n| n as usize),
+    });
+    smugglex::http2::run_h2_downgrade_check(
+        host,
+        port,
+        host_header,
+        path,
+        cli.timeout,
+        verbose,
+        h2_fuzz.as_ref(),
+    )
+    .await
+}
+
+/// Scan `target` once, or with `--scan-all-ips` once per address its host
+/// resolves to, each scan pinned to its address.
+async fn scan_target_addresses(target: String, cli: Cli) -> Vec<ScanOutcome> {
+    if !cli.scan_all_ips {
+        return vec![scan_one_target(target, cli).await];
+    }
+    let resolved = match Url::parse(&target) {
+        Ok(url) => match (url.host_str(), url.port_or_known_default()) {
+            (Some(host), Some(port)) => {
+                let host = host.trim_start_matches('[').trim_end_matches(']');
+                http::resolve_addresses(host, port).await
+            }
+            // Let the regular scan report the malformed URL.
+            _ => return vec![scan_one_target(target, cli).await],
+        },
+        Err(_) => return vec![scan_one_target(target, cli).await],
+    };
+    let addrs = match resolved {
+        Ok(addrs) if !addrs.is_empty() => addrs,
+        other => {
+            let (error, error_info) = match other {
+                Err(e) => (format!("failed to resolve host: {}", e), Some(e.info())),
+                Ok(_) => ("host resolved to no addresses".to_string(), None),
+            };
+            if !is_machine() {
+                log(
+                    LogLevel::Error,
+                    &format!("failed to scan {}: {}", target, error),
+                );
+            }
+            return vec![ScanOutcome::Failure {
+                target,
+                error,
+                error_info,
+            }];
+        }
+    };
+    if addrs.len() > 1 && !is_machine() {
+        log(
+            LogLevel::Info,
+            &format!(
+                "{} resolves to {} addresses: {}",
+                target,
+                addrs.len(),
+                addrs
+                    .iter()
+                    .map(|a| a.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        );
+    }
+    let mut outcomes = Vec::with_capacity(addrs.len());
+    for addr in addrs {
+        outcomes.push(
+            http::with_pinned_address(addr, scan_one_target(target.clone(), cli.clone())).await,
+        );
+    }
+    outcomes
+}
+
 /// Core scan routine for one target. Returns a ScanOutcome (Success with full ScanResults
 /// or Failure with error string).
 ///
@@ -380,6 +1712,104 @@ fn resolve_urls(cli: &mut Cli) -> Result<Vec<String>> {
 /// In machine/JSON mode it suppresses all human chatter and progress output so that the
 /// only thing on stdout is the final structured JSON (emitted by the caller).
 async fn scan_one_target(target: String, cli: Cli) -> ScanOutcome {
+    // Daemon jobs and distributed shards arrive here without the up-front
+    // filter of a direct scan.
+    if let Some(reason) = cli.out_of_scope(&target) {
+        return ScanOutcome::Failure {
+            target,
+            error: format!("out of scope: {}", reason),
+            error_info: None,
+        };
+    }
+    // Resolve once and pin every connection of the scan to that address, so
+    // round-robin DNS cannot send the baseline and the attack to different
+    // servers. Through a proxy the proxy resolves the host instead. With
+    // --target-ip the host's DNS is not consulted at all.
+    if let Some(addr) = cli.target_ip
+        && http::pinned_address().is_none()
+    {
+        return http::with_pinned_address(addr, observe_scan(scan_target(target, cli, None))).await;
+    }
+    let dns = if target_proxy(&target).is_none() {
+        watch_target_dns(&target).await
+    } else {
+        None
+    };
+    match dns {
+        Some(dns) if http::pinned_address().is_none() => {
+            let addr = dns.pinned();
+            http::with_pinned_address(addr, observe_scan(scan_target(target, cli, Some(dns)))).await
+        }
+        dns => observe_scan(scan_target(target, cli, dns)).await,
+    }
+}
+
+/// Run `scan` with the proxy chain, Retry-After throttling, target info and
+/// requests it observes collected for its [`ScanResults`].
+fn observe_scan<F: Future>(scan: F) -> impl Future<Output = F::Output> {
+    observe_proxy_chain(observe_target_info(http::observe_throttle(
+        http::observe_requests(scan),
+    )))
+}
+
+/// IP address a scan's connections went to: the address it was pinned to,
+/// else the one its first direct connection reached.
+fn scanned_ip() -> Option<String> {
+    http::pinned_address()
+        .or_else(http::observed_peer)
+        .map(|addr| addr.to_string())
+}
+
+/// Resolve the host of `target` for pinning; `None` for an IP literal or when
+/// it does not resolve (the scan then reports the connection errors).
+async fn watch_target_dns(target: &str) -> Option<http::DnsWatch> {
+    let url = Url::parse(target).ok()?;
+    let host = url
+        .host_str()?
+        .trim_start_matches('[')
+        .trim_end_matches(']');
+    if host.parse::<std::net::IpAddr>().is_ok() {
+        return None;
+    }
+    http::DnsWatch::resolve(host, url.port_or_known_default()?)
+        .await
+        .ok()
+}
+
+/// Proxy the connections to `target` go through (`--proxy` or the
+/// environment).
+fn target_proxy(target: &str) -> Option<&'static str> {
+    let url = Url::parse(target).ok()?;
+    http::proxy_for(url.host_str()?, url.scheme() == "https")
+}
+
+/// Where and how one target is scanned: the parts of its URL and the settings
+/// the steps of [`scan_target`] share.
+struct TargetScan<'a> {
+    cli: &'a Cli,
+    /// URL connected to
+    target: &'a str,
+    /// URL reported (the full request-target of a --raw-request capture)
+    display_target: &'a str,
+    host: &'a str,
+    port: u16,
+    path: &'a str,
+    host_header: &'a str,
+    use_tls: bool,
+    /// Print requests and responses (`--verbose` outside machine mode)
+    verbose: bool,
+}
+
+/// What a check's payloads are built from besides its generator.
+struct PayloadSources<'a> {
+    cookies: &'a [String],
+    trace_report: Option<&'a TraceReport>,
+    corpus: Option<&'a FuzzCorpus>,
+}
+
+/// Body of [`scan_one_target`], run inside a proxy chain observation scope
+/// and, when `dns` is set, pinned to a resolved address.
+async fn scan_target(target: String, cli: Cli, mut dns: Option<http::DnsWatch>) -> ScanOutcome {
     let start_time = std::time::Instant::now();
     let target_url = target.as_str();
     let network_verbose = cli.verbose && !is_machine();
@@ -394,6 +1824,7 @@ async fn scan_one_target(target: String, cli: Cli) -> ScanOutcome {
         ScanOutcome::Failure {
             target: target_url.to_string(),
             error: message,
+            error_info: None,
         }
     };
 
@@ -440,9 +1871,96 @@ async fn scan_one_target(target: String, cli: Cli) -> ScanOutcome {
         target_url
     };
 
+    let scan = TargetScan {
+        cli: &cli,
+        target: target_url,
+        display_target,
+        host,
+        port,
+        path,
+        host_header,
+        use_tls,
+        verbose: network_verbose,
+    };
+
+    let address = http::pinned_address().map(|addr| addr.to_string());
+    let vhost = (host_header != host).then(|| host_header.to_string());
+    // Held for the whole scan; URLs on a busy host wait here.
+    let _host_slot = http::host_slot(address.as_deref().unwrap_or(host)).await;
+
     // Human logs only in plain mode
     if !is_machine() {
-        log(LogLevel::Info, &format!("start scan to {}", display_target));
+        let mut start = format!("start scan to {}", display_target);
+        if let Some(ref address) = address {
+            start.push_str(&format!(" at {}", address));
+        }
+        if let Some(ref vhost) = vhost {
+            start.push_str(&format!(" with Host {}", vhost));
+        }
+        log(LogLevel::Info, &start);
+    }
+
+    // One cheap request first, so a dead host is skipped with a category
+    // instead of failing every payload of every check.
+    if !cli.no_preflight
+        && let Err(e) = preflight(
+            host,
+            port,
+            path,
+            host_header,
+            use_tls,
+            cli.timeout,
+            network_verbose,
+        )
+        .await
+    {
+        let reason = e.unreachable_kind();
+        if !is_machine() {
+            log(
+                LogLevel::Warning,
+                &format!(
+                    "skipping unreachable target {}: {} ({})",
+                    display_target, reason, e
+                ),
+            );
+        }
+        let scan_results = ScanResults {
+            target: display_target.to_string(),
+            address,
+            vhost,
+            ip: scanned_ip(),
+            port: Some(port),
+            scheme: Some(url.scheme().to_string()),
+            method: cli.method.clone(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            fingerprint: None,
+            checks: Vec::new(),
+            exploits: None,
+            proxy_chain: Vec::new(),
+            unreachable: Some(reason),
+            throttle: None,
+            target_info: None,
+            error: Some(e.to_string()),
+            error_info: Some(e.info()),
+            duration_ms: Some(start_time.elapsed().as_millis() as u64),
+            requests_sent: Some(http::observed_requests()),
+            summary: None,
+        };
+        if !is_machine() {
+            for output_file in cli.json_outputs() {
+                if let Err(e) = save_scan_results(output_file, &scan_results) {
+                    log(
+                        LogLevel::Error,
+                        &format!("failed to write output file: {}", e),
+                    );
+                }
+            }
+        }
+        return ScanOutcome::Success {
+            target: display_target.to_string(),
+            scan_results: Box::new(scan_results),
+            found_vulnerability: false,
+        };
     }
 
     let cookies = if cli.use_cookies {
@@ -469,144 +1987,776 @@ async fn scan_one_target(target: String, cli: Cli) -> ScanOutcome {
     let pb = setup_progress_bar(cli.verbose || is_machine());
 
     // Fingerprinting pre-step
-    let mut fingerprint_info: Option<FingerprintInfo> = None;
-    let mut suggested_order: Option<Vec<&str>> = None;
+    let (fingerprint, fingerprint_info) = if cli.fingerprint || cli.auto_exploit {
+        run_fingerprint(&scan).await
+    } else {
+        (None, None)
+    };
 
-    if cli.fingerprint {
-        if !is_machine() {
-            log(LogLevel::Info, "running proxy fingerprint probe");
-        }
-        match fingerprint_target(host, port, path, cli.timeout, network_verbose, use_tls).await {
-            Ok(fp) => {
-                if !is_machine() {
-                    log(
-                        LogLevel::Info,
-                        &format!("detected proxy: {}", fp.detected_proxy),
-                    );
-                    if let Some(ref server) = fp.server_header {
-                        log(LogLevel::Info, &format!("server header: {}", server));
-                    }
-                }
-                if cli.effective_format().is_json() {
-                    fingerprint_info = Some(FingerprintInfo {
-                        detected_proxy: fp.detected_proxy.to_string(),
-                        server_header: fp.server_header.clone(),
-                        via_header: fp.via_header.clone(),
-                        powered_by: fp.powered_by.clone(),
-                    });
-                }
-                suggested_order = Some(suggest_checks(&fp));
-            }
-            Err(e) => {
-                if !is_machine() {
-                    log(
-                        LogLevel::Warning,
-                        &format!("fingerprint probe failed: {}", e),
-                    );
-                }
-            }
-        }
-    }
+    // TRACE echo pre-step: surviving TE/CL variants are sent and fuzzed first
+    let trace_report = if cli.trace_probe {
+        run_trace_probe(&scan).await
+    } else {
+        None
+    };
 
-    let all_checks = [
-        (
-            "cl-te",
-            get_cl_te_payloads as fn(&str, &str, &str, &[String], &[String]) -> Vec<String>,
-        ),
-        ("te-cl", get_te_cl_payloads),
-        ("te-te", get_te_te_payloads),
-        ("h2c", get_h2c_payloads),
-        ("h2", get_h2_payloads),
-        ("cl-edge", get_cl_edge_case_payloads),
-    ];
-
-    let checks_to_run: Vec<_> = if let Some(ref checks_str) = cli.checks {
+    let all_checks: Vec<(&str, PayloadFn)> = PAYLOAD_FAMILIES
+        .iter()
+        .map(|family| (family.check, family.generate))
+        .collect();
+
+    let mut checks_to_run: Vec<_> = if let Some(ref checks_str) = cli.checks {
         let selected_checks: Vec<&str> = checks_str.split(',').map(|s| s.trim()).collect();
         all_checks
             .into_iter()
             .filter(|(name, _)| selected_checks.contains(name))
             .collect()
-    } else if let Some(ref order) = suggested_order {
-        let mut ordered = Vec::new();
-        for name in order {
-            if let Some(entry) = all_checks.iter().find(|(n, _)| n == name) {
-                ordered.push(*entry);
+    } else {
+        all_checks
+    };
+    // Run the likeliest technique first, from what the probes above found.
+    if fingerprint.is_some() || trace_report.is_some() {
+        order_checks(
+            &mut checks_to_run,
+            fingerprint.as_ref(),
+            trace_report.as_ref(),
+            cli.verbose,
+        );
+    }
+
+    let mut results = Vec::new();
+    let mut found_vulnerability = false;
+    // Set when the circuit breaker gave up on the target; the remaining
+    // checks and exploits are skipped.
+    let mut destabilized: Option<SmugglexError> = None;
+
+    let h2_downgrade_selected = h2_downgrade_selected(&scan);
+    let early_data_selected = early_data_selected(&scan);
+    let total_checks =
+        checks_to_run.len() + h2_downgrade_selected as usize + early_data_selected as usize;
+    let h2_downgrade_first =
+        h2_downgrade_first(fingerprint.as_ref(), &checks_to_run, h2_downgrade_selected);
+
+    let corpus = match cli.fuzz_corpus {
+        Some(ref dir) if cli.fuzz => match FuzzCorpus::open(dir) {
+            Ok(corpus) => Some(corpus),
+            Err(e) => {
+                log(
+                    LogLevel::Warning,
+                    &format!("fuzz corpus disabled: cannot open {}: {}", dir, e),
+                );
+                None
+            }
+        },
+        _ => None,
+    };
+    let sources = PayloadSources {
+        cookies: &cookies,
+        trace_report: trace_report.as_ref(),
+        corpus: corpus.as_ref(),
+    };
+
+    if h2_downgrade_first {
+        if !cli.verbose && !is_machine() {
+            pb.set_message(format!("[1/{}] checking h2-downgrade", total_checks));
+        }
+        let result = repeat_check(cli.repeat, || {
+            run_h2_downgrade(&cli, host, port, host_header, path, network_verbose)
+        })
+        .await;
+        found_vulnerability |= result.vulnerable;
+        results.push(result);
+        pb.inc(1);
+    }
+
+    for (i, (check_name, payload_fn)) in checks_to_run.iter().enumerate() {
+        if cli.exit_first && found_vulnerability {
+            break;
+        }
+
+        if let Err(message) = recheck_dns(&mut dns, &cli, host).await {
+            if !cli.verbose && !is_machine() {
+                pb.finish_and_clear();
+            }
+            return scan_failure(message);
+        }
+
+        let (result, aborted) = run_payload_check(
+            &scan,
+            &sources,
+            &pb,
+            check_name,
+            *payload_fn,
+            i + 1 + h2_downgrade_first as usize,
+            total_checks,
+        )
+        .await;
+        if let Some(result) = result {
+            found_vulnerability |= result.vulnerable;
+            results.push(result);
+        }
+        if aborted.is_some() {
+            destabilized = aborted;
+            break;
+        }
+        pb.inc(1);
+    }
+
+    // Real HTTP/2 downgrade smuggling (H2.CL / H2.TE) over ALPN h2. Runs after
+    // the HTTP/1.1 checks because it uses a genuine HTTP/2 client rather than a
+    // payload string, unless the fingerprint makes a downgrade likely.
+    if h2_downgrade_selected
+        && !h2_downgrade_first
+        && destabilized.is_none()
+        && !(cli.exit_first && found_vulnerability)
+    {
+        if !cli.verbose && !is_machine() {
+            pb.set_message(format!(
+                "[{}/{}] checking h2-downgrade",
+                total_checks, total_checks
+            ));
+        }
+        let result = repeat_check(cli.repeat, || {
+            run_h2_downgrade(&cli, host, port, host_header, path, network_verbose)
+        })
+        .await;
+        found_vulnerability |= result.vulnerable;
+        results.push(result);
+        pb.inc(1);
+    }
+
+    if early_data_selected && destabilized.is_none() && !(cli.exit_first && found_vulnerability) {
+        if !cli.verbose && !is_machine() {
+            pb.set_message(format!(
+                "[{}/{}] checking early-data",
+                total_checks, total_checks
+            ));
+        }
+        let result = repeat_check(cli.repeat, || {
+            smugglex::early_data::run_early_data_check(
+                host,
+                port,
+                host_header,
+                path,
+                cli.timeout,
+                network_verbose,
+            )
+        })
+        .await;
+        found_vulnerability |= result.vulnerable;
+        results.push(result);
+        pb.inc(1);
+    }
+
+    if !cli.verbose && !is_machine() {
+        pb.finish_and_clear();
+    }
+
+    let proxy_chain = observed_proxy_chain();
+    if !proxy_chain.is_empty() && !is_machine() {
+        log(
+            LogLevel::Info,
+            &format!("proxy chain: {}", format_proxy_chain(&proxy_chain)),
+        );
+    }
+    let throttle = http::observed_throttle();
+    if let Some(ref throttle) = throttle
+        && !is_machine()
+    {
+        log(LogLevel::Warning, &format!("throttled: {}", throttle));
+    }
+    let target_info = observed_target_info();
+    if let Some(ref info) = target_info
+        && !is_machine()
+    {
+        log(LogLevel::Info, &format!("target info: {}", info));
+    }
+
+    // Phases are measured for every finding but only reported on request.
+    if !cli.phase_timing {
+        results
+            .iter_mut()
+            .for_each(|check| check.attack_phases = None);
+    }
+    for check in results.iter_mut() {
+        check.evidence = EvidenceScore::of(check);
+    }
+
+    // In machine mode we never call log_scan_results here — the caller will emit one clean JSON document.
+    if !is_machine() {
+        // Exploits below still need the unmasked payloads.
+        let mut shown = results.clone();
+        shown.iter_mut().for_each(redact_check);
+        log_scan_results(
+            &shown,
+            &cli.effective_format(),
+            display_target,
+            &cli.method,
+            &fingerprint_info,
+            &proxy_chain,
+        );
+    }
+
+    // A destabilized target is left alone.
+    let exploits = if destabilized.is_none() {
+        exploit_phase(&scan, &results, fingerprint.as_ref(), found_vulnerability).await
+    } else {
+        if cli.auto_exploit && !is_machine() {
+            log(
+                LogLevel::Info,
+                "auto-exploit skipped: no confirmed vulnerability to exploit",
+            );
+        }
+        None
+    };
+
+    let duration_ms = start_time.elapsed().as_millis() as u64;
+    let summary = ScanSummary::from_checks(&results, duration_ms);
+
+    // Build the structured result for the outcome (always produced, used for JSON batch or exit code)
+    let mut scan_results = ScanResults {
+        target: display_target.to_string(),
+        address,
+        vhost,
+        ip: scanned_ip(),
+        port: Some(port),
+        scheme: Some(url.scheme().to_string()),
+        method: cli.method.clone(),
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        fingerprint: fingerprint_info,
+        checks: results,
+        exploits,
+        proxy_chain,
+        unreachable: None,
+        throttle,
+        target_info,
+        error: destabilized.as_ref().map(|e| e.to_string()),
+        error_info: destabilized.as_ref().map(|e| e.info()),
+        duration_ms: Some(duration_ms),
+        requests_sent: Some(http::observed_requests()),
+        summary: Some(summary),
+    };
+
+    redact_scan_results(&mut scan_results);
+
+    // Per-target file output (-o) is only done for plain mode here.
+    // For JSON batch the caller writes the full envelope once at the end.
+    if !is_machine() {
+        for output_file in cli.json_outputs() {
+            if let Err(e) = save_scan_results(output_file, &scan_results) {
+                log(
+                    LogLevel::Error,
+                    &format!("failed to write output file: {}", e),
+                );
+            }
+        }
+    }
+
+    let duration = start_time.elapsed();
+    if !is_machine() {
+        log(
+            LogLevel::Info,
+            &format!("scan completed in {:.3} seconds", duration.as_secs_f64()),
+        );
+        if let Some(ref summary) = scan_results.summary {
+            log_scan_summary(&scan_results.checks, summary);
+        }
+    }
+
+    ScanOutcome::Success {
+        target: display_target.to_string(),
+        scan_results: Box::new(scan_results),
+        found_vulnerability,
+    }
+}
+
+/// Fingerprint the proxy in front of the target. The result is kept for
+/// check ordering and `--auto-exploit`; its JSON form only in JSON output.
+async fn run_fingerprint(
+    scan: &TargetScan<'_>,
+) -> (Option<FingerprintResult>, Option<FingerprintInfo>) {
+    if !is_machine() {
+        log(LogLevel::Info, "running proxy fingerprint probe");
+    }
+    let fp = match fingerprint_target(
+        scan.host,
+        scan.port,
+        scan.path,
+        scan.cli.timeout,
+        scan.verbose,
+        scan.use_tls,
+    )
+    .await
+    {
+        Ok(fp) => fp,
+        Err(e) => {
+            if !is_machine() {
+                log(
+                    LogLevel::Warning,
+                    &format!("fingerprint probe failed: {}", e),
+                );
+            }
+            return (None, None);
+        }
+    };
+    if !is_machine() {
+        log(
+            LogLevel::Info,
+            &format!("detected proxy: {}", fp.detected_proxy),
+        );
+        if let Some(ref server) = fp.server_header {
+            log(LogLevel::Info, &format!("server header: {}", server));
+        }
+        if fp.asterisk_rewrite {
+            log(
+                LogLevel::Info,
+                "OPTIONS * is rewritten to / before the back-end",
+            );
+        }
+        if let Some(hops) = fp.hop_count {
+            let error = fp
+                .error_hop
+                .map(|hop| format!(", first error from hop {}", hop))
+                .unwrap_or_default();
+            log(
+                LogLevel::Info,
+                &format!("Max-Forwards: {} proxy hop(s){}", hops, error),
+            );
+        }
+        if let Some(ref alpn) = fp.alpn {
+            log(LogLevel::Info, &format!("ALPN: {}", alpn));
+        }
+    }
+    let info = scan
+        .cli
+        .effective_format()
+        .is_json()
+        .then(|| FingerprintInfo {
+            detected_proxy: fp.detected_proxy.to_string(),
+            server_header: fp.server_header.clone(),
+            via_header: fp.via_header.clone(),
+            powered_by: fp.powered_by.clone(),
+            asterisk_rewrite: fp.asterisk_rewrite,
+            hop_count: fp.hop_count,
+            error_hop: fp.error_hop,
+            alpn: fp.alpn.clone(),
+            h2_downgrade_likely: h2_downgrade_likely(&fp),
+        });
+    (Some(fp), info)
+}
+
+/// Probe which TE/CL header variants a TRACE echo shows unchanged; `None`
+/// when TRACE is not echoed.
+async fn run_trace_probe(scan: &TargetScan<'_>) -> Option<TraceReport> {
+    if !is_machine() {
+        log(LogLevel::Info, "running TRACE echo probe");
+    }
+    let report = trace_probe(
+        scan.host,
+        scan.port,
+        scan.path,
+        scan.cli.timeout,
+        scan.verbose,
+        scan.use_tls,
+    )
+    .await;
+    if is_machine() {
+        return report;
+    }
+    match report {
+        Some(ref report) => {
+            log(
+                LogLevel::Info,
+                &format!(
+                    "TRACE echo: {} of {} header variants survive verbatim, {} normalized, {} stripped or rejected",
+                    report.count(|f| *f == TraceFate::Verbatim),
+                    report.variants.len(),
+                    report.count(|f| matches!(f, TraceFate::Normalized(_))),
+                    report.count(|f| { matches!(f, TraceFate::Stripped | TraceFate::Rejected(_)) }),
+                ),
+            );
+            if scan.cli.verbose {
+                for variant in &report.variants {
+                    println!("  {} -> {:?}", variant.header.escape_debug(), variant.fate);
+                }
             }
         }
-        ordered
-    } else {
-        all_checks.to_vec()
-    };
-
-    let mut results = Vec::new();
-    let mut found_vulnerability = false;
+        None => log(
+            LogLevel::Info,
+            "TRACE is not echoed; payloads keep their default order",
+        ),
+    }
+    report
+}
+
+/// Sort `checks` so the technique the fingerprint and TRACE probe point at
+/// runs first, logging why.
+fn order_checks(
+    checks: &mut [(&str, PayloadFn)],
+    fingerprint: Option<&FingerprintResult>,
+    trace_report: Option<&TraceReport>,
+    verbose: bool,
+) {
+    let order = adaptive_check_order(fingerprint, trace_report);
+    checks.sort_by_key(|(name, _)| {
+        order
+            .checks
+            .iter()
+            .position(|c| c == name)
+            .unwrap_or(usize::MAX)
+    });
+    if !is_machine() {
+        for reason in &order.reasons {
+            log(LogLevel::Info, &format!("check order: {}", reason));
+        }
+        if verbose {
+            let names: Vec<&str> = checks.iter().map(|(name, _)| *name).collect();
+            log(
+                LogLevel::Info,
+                &format!("check order: {}", names.join(" -> ")),
+            );
+        }
+    }
+}
+
+/// Whether `name` is one of the `-c` checks.
+fn check_named(cli: &Cli, name: &str) -> bool {
+    matches!(
+        cli.checks,
+        Some(ref s) if s.split(',').any(|x| x.trim() == name)
+    )
+}
+
+/// Whether the real-HTTP/2 downgrade check (H2.CL / H2.TE) runs. It speaks
+/// ALPN h2, so it only applies to https targets. It is not a payload-string
+/// check, so it lives outside `PAYLOAD_FAMILIES`; it runs when checks are
+/// unspecified or it is named.
+fn h2_downgrade_selected(scan: &TargetScan<'_>) -> bool {
+    let explicitly_requested = check_named(scan.cli, "h2-downgrade");
+    let transport = check_transport("h2-downgrade", scan.use_tls);
+    let usable = transport.available() && (scan.use_tls || !transport.requires_tls());
+    let selected = usable && (scan.cli.checks.is_none() || explicitly_requested);
+    if !is_machine() {
+        if explicitly_requested && !usable {
+            log(
+                LogLevel::Warning,
+                "h2-downgrade requires an https target (ALPN h2); skipping it for this non-TLS URL",
+            );
+        }
+        if selected
+            && http::proxy_for(scan.host, scan.use_tls).is_some()
+            && !transport.supports_proxy()
+        {
+            log(
+                LogLevel::Warning,
+                "h2-downgrade connects directly and does not route through the proxy",
+            );
+        }
+    }
+    selected
+}
 
-    // The real-HTTP/2 downgrade check (H2.CL / H2.TE) speaks ALPN h2, so it only
-    // applies to https targets. It is not a payload-string check, so it lives
-    // outside `all_checks`; honour it when checks are unspecified or it is named.
-    let h2_explicitly_requested = matches!(
-        cli.checks,
-        Some(ref s) if s.split(',').any(|x| x.trim() == "h2-downgrade")
-    );
-    let h2_downgrade_selected = use_tls && (cli.checks.is_none() || h2_explicitly_requested);
+/// Whether the TLS 1.3 early-data check runs. It resumes sessions and sends
+/// 0-RTT data directly, so it only runs on https targets and only when named.
+fn early_data_selected(scan: &TargetScan<'_>) -> bool {
+    let requested = check_named(scan.cli, smugglex::early_data::CHECK_NAME);
+    let selected = requested && scan.use_tls;
     if !is_machine() {
-        if h2_explicitly_requested && !use_tls {
+        if requested && !scan.use_tls {
             log(
                 LogLevel::Warning,
-                "h2-downgrade requires an https target (ALPN h2); skipping it for this non-TLS URL",
+                "early-data requires an https target (TLS 1.3); skipping it for this non-TLS URL",
             );
         }
-        if h2_downgrade_selected && cli.proxy.is_some() {
+        if selected && http::proxy_for(scan.host, scan.use_tls).is_some() {
             log(
                 LogLevel::Warning,
-                "h2-downgrade connects directly and does not route through --proxy",
+                "early-data connects directly and does not route through the proxy",
             );
         }
     }
-    let total_checks = checks_to_run.len() + h2_downgrade_selected as usize;
+    selected
+}
 
-    for (i, (check_name, payload_fn)) in checks_to_run.iter().enumerate() {
-        if cli.exit_first && found_vulnerability {
-            break;
+/// Whether h2-downgrade runs before the payload checks. An h2 front door in
+/// front of an HTTP/1.1 hop makes downgrade smuggling the likeliest vector;
+/// when the selection leaves the h2 checks out, point at them instead.
+fn h2_downgrade_first(
+    fingerprint: Option<&FingerprintResult>,
+    checks: &[(&str, PayloadFn)],
+    h2_downgrade_selected: bool,
+) -> bool {
+    let likely = fingerprint.is_some_and(h2_downgrade_likely);
+    if likely && !is_machine() {
+        let h2_selected = checks.iter().any(|(name, _)| *name == "h2");
+        if h2_downgrade_selected || h2_selected {
+            log(
+                LogLevel::Info,
+                "h2-downgrade likely: the front door negotiates h2 in front of an HTTP/1.1 hop; running h2 checks first",
+            );
+        } else {
+            log(
+                LogLevel::Warning,
+                "h2-downgrade likely: the front door negotiates h2 in front of an HTTP/1.1 hop; run h2 checks (-c h2,h2-downgrade)",
+            );
+        }
+    }
+    likely && h2_downgrade_selected
+}
+
+/// Re-resolve the pinned host between checks and apply `--on-dns-change`.
+/// Returns the failure message when the scan must stop.
+async fn recheck_dns(
+    dns: &mut Option<http::DnsWatch>,
+    cli: &Cli,
+    host: &str,
+) -> std::result::Result<(), String> {
+    let Some(dns) = dns else {
+        return Ok(());
+    };
+    let Some(answers) = dns.recheck().await else {
+        return Ok(());
+    };
+    let answers = answers
+        .iter()
+        .map(|a| a.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let pinned = http::pinned_address().map_or(String::new(), |a| a.to_string());
+    match cli.on_dns_change {
+        DnsChangePolicy::Warn => {
+            if !is_machine() {
+                log(
+                    LogLevel::Warning,
+                    &format!(
+                        "DNS answers for {} changed mid-scan to {}; connections stay pinned to {}",
+                        host, answers, pinned
+                    ),
+                );
+            }
+            Ok(())
         }
+        DnsChangePolicy::Abort => Err(format!(
+            "DNS answers changed mid-scan to {} (pinned to {})",
+            answers, pinned
+        )),
+    }
+}
 
-        let mut payloads = payload_fn(path, host_header, &cli.method, &cli.headers, &cookies);
+/// Whether `--fuzz` applies to `check_name` (all checks unless
+/// `--fuzz-checks` names them).
+fn fuzz_check(cli: &Cli, check_name: &str) -> bool {
+    cli.fuzz
+        && cli
+            .fuzz_checks
+            .as_deref()
+            .is_none_or(|names| names.split(',').any(|n| n.trim() == check_name))
+}
 
-        if cli.fuzz {
-            let config = MutatorConfig {
-                seed: cli.fuzz_seed,
-                mutations_per_payload: 5,
-            };
-            let mut mutator = Mutator::new(config);
-            payloads = mutator.mutate_payloads(&payloads);
+/// Build one run's payloads for `check_name`: the family's quick or extended
+/// set, ranked by the TRACE report, with `--raw-request` markers applied,
+/// fuzzed when `feedback` is set and capped at `--max-payloads`.
+fn check_payloads(
+    scan: &TargetScan<'_>,
+    sources: &PayloadSources<'_>,
+    check_name: &str,
+    payload_fn: PayloadFn,
+    feedback: Option<&FuzzFeedback>,
+) -> PayloadIter {
+    let cli = scan.cli;
+    let (path, host_header, cookies) = (scan.path, scan.host_header, sources.cookies);
+    let mut payloads = payload_fn(path, host_header, &cli.method, &cli.headers, cookies);
+    if let Some(family) = payload_family(check_name) {
+        payloads = payloads.in_family(family);
+        if cli.quick {
+            payloads = payloads.select_named(family.quick);
+        } else if cli.extended
+            && let Some(extended_fn) = family.extended
+        {
+            payloads = payloads.append(extended_fn(
+                path,
+                host_header,
+                &cli.method,
+                &cli.headers,
+                cookies,
+            ));
         }
+    }
+    if let Some(report) = sources.trace_report {
+        payloads = payloads.prioritize(|request| report.rank(request));
+    }
+
+    // Markers are placed before fuzzing: they rely on the generators'
+    // header layout, and feedback tracks mutants by their exact bytes.
+    if let Some(ref markers) = cli.raw_markers {
+        let markers = markers.clone();
+        let headers = cli.headers.clone();
+        let cookies = cookies.to_vec();
+        payloads = payloads.map_requests(move |p| markers.apply(&p, &headers, &cookies));
+    }
 
-        if let Some(max) = cli.max_payloads {
-            payloads.truncate(max);
+    if let Some(fuzz_feedback) = feedback {
+        // Seeds are materialized once; mutants are generated lazily so
+        // each one can follow the responses to the payloads before it.
+        let config = MutatorConfig {
+            seed: cli.fuzz_seed,
+            mutations_per_payload: 5,
+            mode: cli.fuzz_mode,
+            max_mutants: cli.fuzz_budget.map(|n| n as usize),
+        };
+        let mutator = Mutator::new(config);
+        let mut seed_names: HashMap<String, String> = HashMap::new();
+        let mut seeds: Vec<String> = payloads
+            .map(|p| {
+                if let Some(name) = p.name {
+                    seed_names.entry(p.request.clone()).or_insert(name);
+                }
+                p.request
+            })
+            .collect();
+        if let Some(corpus) = sources.corpus {
+            match corpus.load(check_name, host_header) {
+                Ok(entries) => {
+                    for entry in &entries {
+                        seed_names
+                            .entry(entry.clone())
+                            .or_insert_with(|| "corpus".to_string());
+                    }
+                    seeds.extend(entries);
+                }
+                Err(e) => log(
+                    LogLevel::Warning,
+                    &format!("failed to load {} fuzz corpus: {}", check_name, e),
+                ),
+            }
+        }
+        if let Some(report) = sources.trace_report {
+            for seed in seeds.iter().filter(|s| report.rank(s) == 0) {
+                fuzz_feedback.favor(seed);
+            }
         }
+        // Mutants are named after the seed they were derived from.
+        let feedback = fuzz_feedback.clone();
+        payloads = mutator
+            .feedback_payloads(&seeds, fuzz_feedback.clone())
+            .named(move |_, request| {
+                if let Some(name) = seed_names.get(request) {
+                    return name.clone();
+                }
+                match feedback
+                    .parent_of(request)
+                    .and_then(|parent| seed_names.get(&parent))
+                {
+                    Some(parent) => format!("mutant of {}", parent),
+                    None => "mutant".to_string(),
+                }
+            });
+    }
+
+    if let Some(max) = cli.max_payloads {
+        payloads = payloads.truncate(max);
+    }
+    payloads
+}
+
+/// Placeholder result of a check that did not run to completion.
+fn unfinished_check(check_name: &str, status: &str, diagnostic: String) -> CheckResult {
+    CheckResult {
+        check_type: check_name.to_string(),
+        vulnerable: false,
+        payload_index: None,
+        payload_name: None,
+        technique: None,
+        normal_status: status.to_string(),
+        attack_status: None,
+        normal_duration_ms: 0,
+        attack_duration_ms: None,
+        normal_connect_ms: None,
+        attack_connect_ms: None,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        payload: None,
+        confidence: None,
+        detection_signals: Vec::new(),
+        diagnostics: vec![diagnostic],
+        payloads_sent: 0,
+        request_errors: Default::default(),
+        h2_observations: Vec::new(),
+        fuzz_anomalies: Vec::new(),
+        unsolicited_bytes: None,
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
+    }
+}
 
+/// Run the payload check `check_name` `--repeat` times and merge the runs.
+/// Also returns the error when the circuit breaker gave up on the target.
+async fn run_payload_check(
+    scan: &TargetScan<'_>,
+    sources: &PayloadSources<'_>,
+    pb: &ProgressBar,
+    check_name: &str,
+    payload_fn: PayloadFn,
+    current_check: usize,
+    total_checks: usize,
+) -> (Option<CheckResult>, Option<SmugglexError>) {
+    let cli = scan.cli;
+    let fuzz_check = fuzz_check(cli, check_name);
+    let mut destabilized = None;
+    // `--repeat`: each run builds its payloads afresh, so fuzzing and
+    // feedback start over like a separate scan would.
+    let mut runs: Vec<CheckResult> = Vec::with_capacity(cli.repeat as usize);
+    for _ in 0..cli.repeat {
+        let fuzz_feedback = FuzzFeedback::default();
+        let feedback = fuzz_check.then_some(&fuzz_feedback);
         let params = CheckParams {
-            pb: &pb,
+            pb,
             check_name,
-            host,
-            port,
-            path,
-            attack_requests: payloads,
+            host: scan.host,
+            port: scan.port,
+            path: scan.path,
+            attack_requests: check_payloads(scan, sources, check_name, payload_fn, feedback),
             timeout: cli.timeout,
-            verbose: network_verbose,
-            use_tls,
-            export_dir: cli.export_dir.as_deref(),
-            current_check: i + 1,
+            verbose: scan.verbose,
+            use_tls: scan.use_tls,
+            export: cli.export_dir.as_deref().map(|dir| PayloadExport {
+                dir,
+                format: cli.export_format,
+                target: scan.target,
+            }),
+            archive: active_archive().map(|archive| ArchiveRecorder {
+                archive,
+                target: scan.target,
+            }),
+            current_check,
             total_checks,
             delay: cli.delay,
             baseline_count: cli.baseline_count,
+            warmup: cli.warmup,
+            storm_cooldown: cli.storm_cooldown,
+            detect: &cli.detect,
+            oob_host: cli.oob_host.as_deref(),
+            corpus: sources.corpus.filter(|_| fuzz_check),
+            fuzz_feedback: feedback,
         };
 
         match run_checks_for_type(params).await {
-            Ok(result) => {
-                found_vulnerability |= result.vulnerable;
-                results.push(result);
-                pb.inc(1);
+            Ok(result) => runs.push(result),
+            Err(e @ SmugglexError::TargetDestabilized(_)) => {
+                if !is_machine() {
+                    log(
+                        LogLevel::Warning,
+                        &format!("aborting {}: {}", scan.display_target, e),
+                    );
+                }
+                runs.push(unfinished_check(
+                    check_name,
+                    "TARGET_DESTABILIZED",
+                    "aborted:target_destabilized".to_string(),
+                ));
+                destabilized = Some(e);
+                break;
             }
             Err(e) => {
                 // Record as diagnostic but continue with other checks
@@ -616,149 +2766,178 @@ async fn scan_one_target(target: String, cli: Cli) -> ScanOutcome {
                         &format!("{} check failed: {}", check_name, e),
                     );
                 }
-                results.push(CheckResult {
-                    check_type: check_name.to_string(),
-                    vulnerable: false,
-                    payload_index: None,
-                    normal_status: "CHECK_FAILED".to_string(),
-                    attack_status: None,
-                    normal_duration_ms: 0,
-                    attack_duration_ms: None,
-                    timestamp: chrono::Utc::now().to_rfc3339(),
-                    payload: None,
-                    confidence: None,
-                    detection_signals: Vec::new(),
-                    diagnostics: vec![format!("check_failed: {}", e)],
-                });
-                pb.inc(1);
+                runs.push(unfinished_check(
+                    check_name,
+                    "CHECK_FAILED",
+                    format!("check_failed: {}", e),
+                ));
             }
         }
     }
-
-    // Real HTTP/2 downgrade smuggling (H2.CL / H2.TE) over ALPN h2. Runs after
-    // the HTTP/1.1 checks because it uses a genuine HTTP/2 client rather than a
-    // payload string.
-    if h2_downgrade_selected && !(cli.exit_first && found_vulnerability) {
-        if !cli.verbose && !is_machine() {
-            pb.set_message(format!(
-                "[{}/{}] checking h2-downgrade",
-                total_checks, total_checks
-            ));
-        }
-        let result = smugglex::http2::run_h2_downgrade_check(
-            host,
-            port,
-            host_header,
-            path,
-            cli.timeout,
-            network_verbose,
-        )
-        .await;
-        found_vulnerability |= result.vulnerable;
-        results.push(result);
-        pb.inc(1);
+    // A destabilized target ends the scan: a finding of an earlier run
+    // is kept, otherwise the check reports the abort.
+    if destabilized.is_some()
+        && let Some(aborted) = runs.pop()
+        && !runs.iter().any(|run| run.vulnerable)
+    {
+        runs = vec![aborted];
     }
+    (merge_repeated_runs(runs), destabilized)
+}
 
-    if !cli.verbose && !is_machine() {
-        pb.finish_and_clear();
+/// `--auto-exploit`: pick one exploit from the confirmed finding and the
+/// fingerprint, bounded by its request budget.
+fn auto_exploit_plan(
+    cli: &Cli,
+    results: &[CheckResult],
+    fingerprint: Option<&FingerprintResult>,
+    found_vulnerability: bool,
+) -> Option<AutoExploitPlan> {
+    if !cli.auto_exploit || !found_vulnerability {
+        return None;
     }
-
-    // In machine mode we never call log_scan_results here — the caller will emit one clean JSON document.
+    let plan = extract_vulnerability_context(results)
+        .map(|ctx| plan_auto_exploit(&ctx.vuln_type, fingerprint))?;
     if !is_machine() {
-        log_scan_results(
-            &results,
-            &cli.effective_format(),
-            display_target,
-            &cli.method,
-            &fingerprint_info,
+        log(
+            LogLevel::Info,
+            &format!("auto-exploit selected {}: {}", plan.exploit, plan.reason),
         );
     }
+    Some(plan)
+}
 
-    // Run exploits only in plain mode (their output is human-oriented).
-    // In machine/JSON mode we still allow payload export via the check phase, but skip exploit execution
-    // to keep stdout clean and because exploit details are better consumed interactively.
-    if let Some(ref exploit_str) = cli.exploit {
-        // The `smuggle`/`capture`/`reveal` exploits fire their payload directly
-        // and do not depend on a prior detection, so allow them to run even when
-        // the scan was quiet.
-        let direct_exploit = exploit_str
-            .split(',')
-            .any(|x| matches!(x.trim(), "smuggle" | "capture" | "reveal"));
-        if (found_vulnerability || direct_exploit) && !is_machine() {
-            let exploit_params = ExploitParams {
-                exploit_str,
-                results: &results,
-                host,
-                port,
-                path,
-                use_tls,
-                timeout: cli.timeout,
-                verbose: cli.verbose,
-                target_url: display_target,
-                ports_str: &cli.exploit_ports,
-                wordlist_path: cli.exploit_wordlist.as_deref(),
-                delay: cli.delay,
-                smuggle_request: cli.smuggle_request.as_deref(),
-                reveal_endpoint: cli.reveal_endpoint.as_deref(),
-                reveal_param: &cli.reveal_param,
-            };
-            if let Err(e) = run_exploits(&exploit_params).await {
-                log(LogLevel::Error, &format!("exploit phase failed: {}", e));
-            }
-        } else if found_vulnerability && is_machine() {
+/// Plan, confirm and run the `--exploit` (or `--auto-exploit`) exploits
+/// after the checks. `None` when nothing was planned or found.
+async fn exploit_phase(
+    scan: &TargetScan<'_>,
+    results: &[CheckResult],
+    fingerprint: Option<&FingerprintResult>,
+    found_vulnerability: bool,
+) -> Option<ExploitResults> {
+    let cli = scan.cli;
+    let auto_plan = auto_exploit_plan(cli, results, fingerprint, found_vulnerability);
+    let Some(exploit_str) = cli
+        .exploit
+        .as_deref()
+        .or(auto_plan.as_ref().map(|plan| plan.exploit))
+    else {
+        if cli.auto_exploit && !is_machine() {
             log(
-                LogLevel::Warning,
-                "exploit requested in JSON mode; skipping (re-run without --json/-f json for exploit output)",
+                LogLevel::Info,
+                "auto-exploit skipped: no confirmed vulnerability to exploit",
             );
-        } else if !is_machine() {
+        }
+        return None;
+    };
+
+    // In machine/JSON mode exploits run silently and only the structured ones
+    // (`localhost-access`, `internal-sweep`, `path-fuzz`) are kept, landing in the `exploits`
+    // section of the result; the others are human-oriented and skipped.
+    // The `smuggle`/`capture`/`reveal` exploits fire their payload directly
+    // and do not depend on a prior detection, so allow them to run even when
+    // the scan was quiet.
+    let direct_exploit = exploit_str
+        .split(',')
+        .any(|x| matches!(x.trim(), "smuggle" | "capture" | "reveal"));
+    if !(found_vulnerability || (direct_exploit && !is_machine())) {
+        if !is_machine() {
             log(
                 LogLevel::Warning,
                 "exploit requested but no vulnerabilities found to exploit",
             );
         }
+        return None;
     }
 
-    // Per-target file output (-o) is only done for plain mode here.
-    // For JSON batch the caller writes the full envelope once at the end.
-    if !is_machine()
-        && let Some(ref output_file) = cli.output
-        && let Err(e) = save_results_to_file(
-            output_file,
-            display_target,
-            &cli.method,
-            results.clone(),
-            &fingerprint_info,
-        )
-    {
-        log(
-            LogLevel::Error,
-            &format!("failed to write output file: {}", e),
-        );
-    }
+    // Only the exploits that will actually run are planned: the direct
+    // ones without a finding, the structured ones in machine mode.
+    let planned: Vec<&str> = exploit_str
+        .split(',')
+        .map(str::trim)
+        .filter(|x| found_vulnerability || matches!(*x, "smuggle" | "capture" | "reveal"))
+        .filter(|x| {
+            !is_machine() || matches!(*x, "localhost-access" | "internal-sweep" | "path-fuzz")
+        })
+        .collect();
+    let fuzz_paths = if planned.contains(&"path-fuzz") {
+        get_fuzz_paths(cli.exploit_wordlist.as_deref(), &cli.fuzz_categories).unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    let request_budget = auto_plan.as_ref().map(|_| cli.auto_exploit_budget as usize);
+    let plan = plan_exploits(&PlanInputs {
+        exploits: &planned,
+        host: scan.host,
+        port: scan.port,
+        path: scan.path,
+        ports: &cli.exploit_ports,
+        hosts: cli.exploit_hosts.as_deref().unwrap_or_default(),
+        range: cli.range.as_deref(),
+        sweep_budget: cli.sweep_budget as usize,
+        fuzz_paths: &fuzz_paths,
+        smuggle_request: cli.smuggle_request.as_deref(),
+        reveal_endpoint: cli.reveal_endpoint.as_deref(),
+        reveal_param: &cli.reveal_param,
+        request_budget,
+        max_requests: cli.exploit_max_requests.map(|max| max as usize),
+    });
+    let decision = if plan.is_empty() {
+        ExploitDecision::PreApproved
+    } else {
+        confirm_exploits(&plan, scan.display_target, cli.yes).await
+    };
 
-    let duration = start_time.elapsed();
-    if !is_machine() {
+    let matcher = cli.response_matcher();
+    let budget = ExploitBudget::new(
+        cli.exploit_max_requests.map(|max| max as usize),
+        cli.exploit_max_time.map(Duration::from_secs),
+    );
+    let exploit_params = ExploitParams {
+        exploit_str,
+        results,
+        host: scan.host,
+        port: scan.port,
+        path: scan.path,
+        use_tls: scan.use_tls,
+        timeout: cli.timeout,
+        verbose: scan.verbose,
+        target_url: scan.display_target,
+        ports_str: &cli.exploit_ports,
+        hosts_str: cli.exploit_hosts.as_deref(),
+        range: cli.range.as_deref(),
+        sweep_budget: cli.sweep_budget as usize,
+        wordlist_path: cli.exploit_wordlist.as_deref(),
+        fuzz_categories: &cli.fuzz_categories,
+        matcher: &matcher,
+        delay: cli.delay,
+        smuggle_request: cli.smuggle_request.as_deref(),
+        reveal_endpoint: cli.reveal_endpoint.as_deref(),
+        reveal_param: &cli.reveal_param,
+        request_budget,
+        budget: &budget,
+    };
+    let mut outcome = ExploitResults::default();
+    if decision.approved() {
+        match run_exploits(&exploit_params).await {
+            Ok(results) => outcome = results,
+            Err(e) => log(LogLevel::Error, &format!("exploit phase failed: {}", e)),
+        }
+    } else if decision == ExploitDecision::NoTerminal {
         log(
-            LogLevel::Info,
-            &format!("scan completed in {:.3} seconds", duration.as_secs_f64()),
+            LogLevel::Warning,
+            "exploitation skipped: stdin is not a terminal to confirm on; pass --yes to run exploits unattended",
         );
+    } else {
+        log(LogLevel::Warning, "exploitation declined");
     }
-
-    // Build the structured result for the outcome (always produced, used for JSON batch or exit code)
-    let scan_results = ScanResults {
-        target: display_target.to_string(),
-        method: cli.method.clone(),
-        timestamp: chrono::Utc::now().to_rfc3339(),
-        fingerprint: fingerprint_info,
-        checks: results,
-        error: None,
-    };
-
-    ScanOutcome::Success {
-        target: display_target.to_string(),
-        scan_results,
-        found_vulnerability,
+    if !plan.is_empty() {
+        outcome.plan = Some(ExploitPlan {
+            exploits: plan,
+            decision,
+        });
     }
+    (outcome.plan.is_some() || !outcome.is_empty()).then_some(outcome)
 }
 
 fn setup_progress_bar(verbose: bool) -> ProgressBar {
@@ -776,6 +2955,36 @@ fn setup_progress_bar(verbose: bool) -> ProgressBar {
     }
 }
 
+/// Show `plan` and ask whether to run it, unless `--yes` approved it already.
+/// Targets scanned concurrently ask one at a time.
+async fn confirm_exploits(plan: &[PlannedExploit], target_url: &str, yes: bool) -> ExploitDecision {
+    static PROMPT: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+    if yes {
+        if !is_machine() && !is_quiet() {
+            print_exploit_plan(plan, target_url);
+        }
+        return ExploitDecision::PreApproved;
+    }
+    if !io::stdin().is_terminal() {
+        return ExploitDecision::NoTerminal;
+    }
+    let _turn = PROMPT.lock().await;
+    print_exploit_plan(plan, target_url);
+    eprint!("Proceed with exploitation? [y/N] ");
+    let answer = tokio::task::spawn_blocking(|| {
+        let mut line = String::new();
+        io::stdin().lock().read_line(&mut line).map(|_| line)
+    })
+    .await;
+    match answer {
+        Ok(Ok(line)) if matches!(line.trim().to_lowercase().as_str(), "y" | "yes") => {
+            ExploitDecision::Confirmed
+        }
+        _ => ExploitDecision::Declined,
+    }
+}
+
 /// Extract vulnerability context and log it, returning None (with log) if unavailable.
 fn prepare_exploit_context(results: &[CheckResult], verbose: bool) -> Option<VulnerabilityContext> {
     let vuln_ctx = extract_vulnerability_context(results);
@@ -798,10 +3007,33 @@ fn prepare_exploit_context(results: &[CheckResult], verbose: bool) -> Option<Vul
     vuln_ctx
 }
 
-async fn run_exploits(params: &ExploitParams<'_>) -> Result<()> {
+async fn run_exploits(params: &ExploitParams<'_>) -> Result<ExploitResults> {
     let exploits: Vec<&str> = params.exploit_str.split(',').map(|s| s.trim()).collect();
+    let mut outcome = ExploitResults::default();
 
     for exploit_type in exploits {
+        if let Some(cap) = params.budget.hit() {
+            log(
+                LogLevel::Warning,
+                &format!("{} exploit skipped: {}", exploit_type, cap),
+            );
+            continue;
+        }
+        if is_machine()
+            && !matches!(
+                exploit_type,
+                "localhost-access" | "internal-sweep" | "path-fuzz"
+            )
+        {
+            log(
+                LogLevel::Warning,
+                &format!(
+                    "{} exploit has no machine-readable output; skipping in JSON mode",
+                    exploit_type
+                ),
+            );
+            continue;
+        }
         match exploit_type {
             "localhost-access" => {
                 log(LogLevel::Info, "running localhost-access exploit");
@@ -812,11 +3044,7 @@ async fn run_exploits(params: &ExploitParams<'_>) -> Result<()> {
                 };
 
                 // Parse target ports
-                let localhost_ports: Vec<u16> = params
-                    .ports_str
-                    .split(',')
-                    .filter_map(|s| s.trim().parse::<u16>().ok())
-                    .collect();
+                let localhost_ports = parse_ports(params.ports_str);
 
                 if localhost_ports.is_empty() {
                     log(
@@ -826,6 +3054,15 @@ async fn run_exploits(params: &ExploitParams<'_>) -> Result<()> {
                     continue;
                 }
 
+                let internal_hosts = params.hosts_str.map(parse_hosts).unwrap_or_default();
+                if params.hosts_str.is_some() && internal_hosts.is_empty() {
+                    log(
+                        LogLevel::Error,
+                        "no valid hosts specified for localhost-access",
+                    );
+                    continue;
+                }
+
                 if params.verbose {
                     println!(
                         "  {} Testing ports: {}",
@@ -848,11 +3085,19 @@ async fn run_exploits(params: &ExploitParams<'_>) -> Result<()> {
                     verbose: params.verbose,
                     vuln_ctx: &vuln_ctx,
                     localhost_ports: &localhost_ports,
+                    internal_hosts: &internal_hosts,
                     delay: params.delay,
+                    max_requests: params.request_budget,
+                    budget: params.budget,
                 };
                 match test_localhost_access(&localhost_params).await {
                     Ok(localhost_results) => {
-                        print_localhost_results(&localhost_results, params.target_url);
+                        if !is_machine() {
+                            print_localhost_results(&localhost_results, params.target_url);
+                        }
+                        outcome
+                            .localhost_access
+                            .extend(localhost_results.iter().map(LocalhostPortResult::from));
                     }
                     Err(e) => {
                         log(
@@ -862,6 +3107,58 @@ async fn run_exploits(params: &ExploitParams<'_>) -> Result<()> {
                     }
                 }
             }
+            "internal-sweep" => {
+                log(LogLevel::Info, "running internal-sweep exploit");
+
+                let vuln_ctx = match prepare_exploit_context(params.results, params.verbose) {
+                    Some(ctx) => ctx,
+                    None => continue,
+                };
+                // Validated before scanning; a missing range cannot get here.
+                let Some(range) = params.range.and_then(|r| SweepRange::parse(r).ok()) else {
+                    log(LogLevel::Error, "internal-sweep needs a valid --range");
+                    continue;
+                };
+                let ports = parse_ports(params.ports_str);
+                if ports.is_empty() {
+                    log(
+                        LogLevel::Error,
+                        "no valid ports specified for internal-sweep",
+                    );
+                    continue;
+                }
+
+                let sweep_params = InternalSweepParams {
+                    host: params.host,
+                    port: params.port,
+                    path: params.path,
+                    use_tls: params.use_tls,
+                    timeout: params.timeout,
+                    verbose: params.verbose,
+                    vuln_ctx: &vuln_ctx,
+                    range,
+                    ports: &ports,
+                    delay: params.delay,
+                    max_requests: params
+                        .request_budget
+                        .map_or(params.sweep_budget, |max| max.min(params.sweep_budget)),
+                    budget: params.budget,
+                };
+                match test_internal_sweep(&sweep_params).await {
+                    Ok(sweep) => {
+                        if !is_machine() {
+                            print_sweep_results(&sweep, params.target_url);
+                        }
+                        outcome.internal_sweep = Some(InternalSweep::from(&sweep));
+                    }
+                    Err(e) => {
+                        log(
+                            LogLevel::Error,
+                            &format!("internal-sweep exploit failed: {}", e),
+                        );
+                    }
+                }
+            }
             "path-fuzz" => {
                 log(LogLevel::Info, "running path-fuzz exploit");
 
@@ -871,7 +3168,8 @@ async fn run_exploits(params: &ExploitParams<'_>) -> Result<()> {
                 };
 
                 // Get paths to fuzz
-                let fuzz_paths = match get_fuzz_paths(params.wordlist_path) {
+                let fuzz_paths = match get_fuzz_paths(params.wordlist_path, params.fuzz_categories)
+                {
                     Ok(paths) => paths,
                     Err(e) => {
                         log(LogLevel::Error, &format!("failed to get fuzz paths: {}", e));
@@ -880,13 +3178,21 @@ async fn run_exploits(params: &ExploitParams<'_>) -> Result<()> {
                 };
 
                 if params.verbose {
+                    let mut sources: Vec<String> = params
+                        .fuzz_categories
+                        .iter()
+                        .map(|c| c.name().to_string())
+                        .collect();
+                    sources.extend(params.wordlist_path.map(str::to_string));
                     println!(
                         "  {} Testing {} paths{}",
                         "[*]".cyan(),
                         fuzz_paths.len(),
-                        params
-                            .wordlist_path
-                            .map_or("".to_string(), |p| format!(" from {}", p))
+                        if sources.is_empty() {
+                            String::new()
+                        } else {
+                            format!(" from {}", sources.join(", "))
+                        }
                     );
                 }
 
@@ -900,17 +3206,59 @@ async fn run_exploits(params: &ExploitParams<'_>) -> Result<()> {
                     verbose: params.verbose,
                     vuln_ctx: &vuln_ctx,
                     fuzz_paths: &fuzz_paths,
+                    matcher: params.matcher,
                     delay: params.delay,
+                    budget: params.budget,
                 };
                 match test_path_fuzz(&path_fuzz_params).await {
                     Ok(path_fuzz_results) => {
-                        print_path_fuzz_results(&path_fuzz_results, params.target_url);
+                        if !is_machine() {
+                            print_path_fuzz_results(&path_fuzz_results, params.target_url);
+                        }
+                        outcome.path_fuzz.extend(
+                            path_fuzz_results
+                                .iter()
+                                .filter(|r| r.found)
+                                .map(DiscoveredPath::from),
+                        );
                     }
                     Err(e) => {
                         log(LogLevel::Error, &format!("path-fuzz exploit failed: {}", e));
                     }
                 }
             }
+            "cloud-metadata" => {
+                log(LogLevel::Info, "running cloud-metadata exploit");
+
+                let vuln_ctx = match prepare_exploit_context(params.results, params.verbose) {
+                    Some(ctx) => ctx,
+                    None => continue,
+                };
+
+                let cloud_params = CloudMetadataParams {
+                    host: params.host,
+                    port: params.port,
+                    path: params.path,
+                    use_tls: params.use_tls,
+                    timeout: params.timeout,
+                    verbose: params.verbose,
+                    vuln_ctx: &vuln_ctx,
+                    follow_ups: METADATA_FOLLOW_UPS,
+                    delay: params.delay,
+                    budget: params.budget,
+                };
+                match test_cloud_metadata(&cloud_params).await {
+                    Ok(cloud_results) => {
+                        print_cloud_metadata_results(&cloud_results, params.target_url);
+                    }
+                    Err(e) => {
+                        log(
+                            LogLevel::Error,
+                            &format!("cloud-metadata exploit failed: {}", e),
+                        );
+                    }
+                }
+            }
             "smuggle" => {
                 log(LogLevel::Info, "running smuggle exploit");
 
@@ -922,7 +3270,7 @@ async fn run_exploits(params: &ExploitParams<'_>) -> Result<()> {
                 // on one CLI line; fall back to the GPOST-solving default.
                 let inner_request = params
                     .smuggle_request
-                    .map(|s| s.replace("\\r\\n", "\r\n").replace("\\n", "\n"))
+                    .map(unescape_request)
                     .unwrap_or_else(|| smugglex::exploit::DEFAULT_SMUGGLE_REQUEST.to_string());
 
                 let smuggle_params = smugglex::exploit::SmuggleParams {
@@ -933,8 +3281,9 @@ async fn run_exploits(params: &ExploitParams<'_>) -> Result<()> {
                     timeout: params.timeout,
                     verbose: params.verbose,
                     inner_request: inner_request.clone(),
-                    rounds: 6,
+                    rounds: SMUGGLE_ROUNDS,
                     delay: params.delay,
+                    budget: params.budget,
                 };
                 match smugglex::exploit::test_smuggle(&smuggle_params).await {
                     Ok(result) => smugglex::exploit::print_smuggle_results(
@@ -952,10 +3301,8 @@ async fn run_exploits(params: &ExploitParams<'_>) -> Result<()> {
                 // recon step the access-control labs need); must be complete.
                 let smuggled = params
                     .smuggle_request
-                    .map(|s| s.replace("\\r\\n", "\r\n").replace("\\n", "\n"))
-                    .unwrap_or_else(|| {
-                        format!("GET /admin HTTP/1.1\r\nHost: {}\r\n\r\n", params.host)
-                    });
+                    .map(unescape_request)
+                    .unwrap_or_else(|| default_capture_request(params.host));
 
                 let capture_params = smugglex::exploit::CaptureParams {
                     host: params.host,
@@ -965,7 +3312,8 @@ async fn run_exploits(params: &ExploitParams<'_>) -> Result<()> {
                     timeout: params.timeout,
                     verbose: params.verbose,
                     smuggled_request: smuggled.clone(),
-                    follow_ups: 3,
+                    follow_ups: CAPTURE_FOLLOW_UPS,
+                    budget: params.budget,
                 };
                 match smugglex::exploit::test_capture(&capture_params).await {
                     Ok(result) => smugglex::exploit::print_capture_results(
@@ -993,7 +3341,9 @@ async fn run_exploits(params: &ExploitParams<'_>) -> Result<()> {
                     verbose: params.verbose,
                     reflect_endpoint: params.reveal_endpoint.unwrap_or(params.path).to_string(),
                     reflect_param: params.reveal_param.to_string(),
-                    follow_ups: 4,
+                    follow_ups: REVEAL_FOLLOW_UPS,
+                    max_requests: params.request_budget,
+                    budget: params.budget,
                 };
                 match smugglex::exploit::test_reveal(&reveal_params).await {
                     Ok(result) => smugglex::exploit::print_reveal_results(
@@ -1013,5 +3363,16 @@ async fn run_exploits(params: &ExploitParams<'_>) -> Result<()> {
         }
     }
 
-    Ok(())
+    if let Some(cap) = params.budget.hit() {
+        log(
+            LogLevel::Warning,
+            &format!(
+                "{} after {} exploit request(s); results are partial",
+                cap,
+                params.budget.sent()
+            ),
+        );
+        outcome.capped = Some(cap.to_string());
+    }
+    Ok(outcome)
 }
@@ -1,4 +1,7 @@
+use crate::error::TimeoutPhase;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
 
 /// Confidence level for a vulnerability detection
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -12,6 +15,99 @@ pub enum Confidence {
     Low,
 }
 
+impl Confidence {
+    /// Name used in JSON and plain output.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Confidence::High => "high",
+            Confidence::Medium => "medium",
+            Confidence::Low => "low",
+        }
+    }
+
+    /// Strength for ordering: higher is stronger.
+    fn rank(&self) -> u8 {
+        match self {
+            Confidence::High => 3,
+            Confidence::Medium => 2,
+            Confidence::Low => 1,
+        }
+    }
+}
+
+/// Why an attack request failed to produce a response.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum RequestErrorKind {
+    /// The target hostname did not resolve
+    Dns,
+    /// Nothing accepted the TCP connection
+    ConnectRefused,
+    /// The TLS handshake failed (certificate, protocol, or a non-TLS service)
+    TlsHandshake,
+    /// The peer reset or closed the connection before a response arrived
+    Reset,
+    /// No response within the socket timeout
+    Timeout,
+    /// Any other transport failure
+    Other,
+}
+
+impl RequestErrorKind {
+    /// Name used in JSON and plain output.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RequestErrorKind::Dns => "dns",
+            RequestErrorKind::ConnectRefused => "connect-refused",
+            RequestErrorKind::TlsHandshake => "tls-handshake",
+            RequestErrorKind::Reset => "reset",
+            RequestErrorKind::Timeout => "timeout",
+            RequestErrorKind::Other => "other",
+        }
+    }
+}
+
+impl fmt::Display for RequestErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Why the reachability preflight skipped a target.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Unreachable {
+    /// The target hostname did not resolve
+    DnsFailure,
+    /// Nothing accepted the TCP connection
+    Refused,
+    /// The TLS handshake failed
+    TlsError,
+    /// No response within the socket timeout
+    Timeout,
+    /// The connection closed or answered with something other than HTTP
+    HttpError,
+}
+
+impl Unreachable {
+    /// Name used in JSON and plain output.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Unreachable::DnsFailure => "dns-failure",
+            Unreachable::Refused => "refused",
+            Unreachable::TlsError => "tls-error",
+            Unreachable::Timeout => "timeout",
+            Unreachable::HttpError => "http-error",
+        }
+    }
+}
+
+impl fmt::Display for Unreachable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Result of a vulnerability check
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CheckResult {
@@ -21,14 +117,32 @@ pub struct CheckResult {
     pub vulnerable: bool,
     /// Index of the payload that triggered detection
     pub payload_index: Option<usize>,
+    /// Name of the payload variation that triggered detection (e.g.,
+    /// "linefold-tab")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload_name: Option<String>,
+    /// Technique of the payload that triggered detection, for payloads of a
+    /// built-in family
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub technique: Option<Technique>,
     /// HTTP status line from the baseline (normal) request
     pub normal_status: String,
     /// HTTP status line from the attack request, if available
     pub attack_status: Option<String>,
-    /// Baseline request duration in milliseconds
+    /// Baseline response time in milliseconds (median; connection setup
+    /// excluded)
     pub normal_duration_ms: u64,
-    /// Attack request duration in milliseconds, if available
+    /// Attack response time in milliseconds (connection setup excluded), if
+    /// available
     pub attack_duration_ms: Option<u64>,
+    /// Median time spent opening baseline connections (TCP, proxy CONNECT,
+    /// TLS), excluded from `normal_duration_ms`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub normal_connect_ms: Option<u64>,
+    /// Time spent opening the attack connection, excluded from
+    /// `attack_duration_ms`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attack_connect_ms: Option<u64>,
     /// ISO 8601 timestamp of when the check was performed
     pub timestamp: String,
     /// Raw HTTP payload that triggered detection
@@ -51,6 +165,468 @@ pub struct CheckResult {
     /// hit the control-based FP rule for this payload shape.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub diagnostics: Vec<String>,
+    /// Number of distinct payloads actually sent for this check. Duplicate
+    /// requests (e.g. fuzz mutants that regenerate an existing variant) are
+    /// skipped and not counted.
+    #[serde(default)]
+    pub payloads_sent: usize,
+    /// Failed attack requests by error kind (e.g. `{"tls-handshake": 3}`).
+    /// Tells a target that rejected the connection apart from a back-end
+    /// that hung, which an absent `attack_status` alone cannot.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub request_errors: BTreeMap<RequestErrorKind, usize>,
+    /// HTTP/2 probes the peer ended without a response (GOAWAY, RST_STREAM,
+    /// a flow-control stall), by payload. Servers killing streams on
+    /// specific malformed headers is itself a fingerprint.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub h2_observations: Vec<H2Observation>,
+    /// Fuzzed payloads that showed an anomaly without confirming a
+    /// vulnerability, kept for manual triage.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fuzz_anomalies: Vec<FuzzAnomaly>,
+    /// Bytes that followed the winning payload's response on its connection
+    /// unasked — a second response, or part of another client's request —
+    /// decoded lossily: a capture of the desync itself
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unsolicited_bytes: Option<String>,
+    /// Where the time of the winning attack request went (`--phase-timing`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attack_phases: Option<PhaseTimings>,
+    /// The complementary-technique probe run after a CL.TE or TE.CL finding
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cross_check: Option<CrossCheck>,
+    /// How solid a finding is: its signals weighed into one score
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub evidence: Option<EvidenceScore>,
+    /// How many `--repeat` runs of the check found it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub consistency: Option<Consistency>,
+}
+
+/// How many of the `--repeat` runs of a check found a vulnerability
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct Consistency {
+    /// Runs of the check (`--repeat N`)
+    pub runs: usize,
+    /// Runs that found a vulnerability
+    pub detections: usize,
+    /// Found by some runs but not all: an intermittent result
+    pub flaky: bool,
+}
+
+impl Consistency {
+    /// `detections` out of `runs`, flaky when some but not all found it.
+    pub fn new(runs: usize, detections: usize) -> Self {
+        Consistency {
+            runs,
+            detections,
+            flaky: detections > 0 && detections < runs,
+        }
+    }
+}
+
+/// `found 2/3 (flaky)`
+impl fmt::Display for Consistency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "found {}/{}", self.detections, self.runs)?;
+        if self.flaky {
+            write!(f, " (flaky)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Fold the results of repeated runs of one check into one: the first run
+/// that found a vulnerability (else the first run), with the payloads and
+/// request errors of every run and the [`Consistency`] across them. A single
+/// run is returned as is.
+pub fn merge_repeated_runs(runs: Vec<CheckResult>) -> Option<CheckResult> {
+    if runs.len() <= 1 {
+        return runs.into_iter().next();
+    }
+    let detections = runs.iter().filter(|run| run.vulnerable).count();
+    let consistency = Consistency::new(runs.len(), detections);
+    let payloads_sent = runs.iter().map(|run| run.payloads_sent).sum();
+    let mut request_errors: BTreeMap<RequestErrorKind, usize> = BTreeMap::new();
+    for (kind, count) in runs.iter().flat_map(|run| &run.request_errors) {
+        *request_errors.entry(*kind).or_default() += count;
+    }
+    let chosen = runs.iter().position(|run| run.vulnerable).unwrap_or(0);
+    let mut merged = runs.into_iter().nth(chosen)?;
+    merged.payloads_sent = payloads_sent;
+    merged.request_errors = request_errors;
+    merged.consistency = Some(consistency);
+    Some(merged)
+}
+
+/// Whether a complementary-technique probe agreed with the claimed technique
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CrossCheckVerdict {
+    /// The complementary framing caused no desync, as the claimed technique predicts
+    Consistent,
+    /// The complementary framing desynced too; the technique may be misclassified
+    Inconsistent,
+    /// The probe failed, so nothing could be concluded
+    Inconclusive,
+}
+
+impl fmt::Display for CrossCheckVerdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CrossCheckVerdict::Consistent => write!(f, "consistent"),
+            CrossCheckVerdict::Inconsistent => write!(f, "inconsistent"),
+            CrossCheckVerdict::Inconclusive => write!(f, "inconclusive"),
+        }
+    }
+}
+
+/// A timing probe for the technique complementary to a finding's: TE.CL after
+/// a CL.TE finding, CL.TE after a TE.CL one
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CrossCheck {
+    /// Check the probe tested for (`te-cl` or `cl-te`)
+    pub technique: String,
+    pub verdict: CrossCheckVerdict,
+    /// What the probe's response showed
+    pub observation: String,
+}
+
+/// How a check ended, in the results summary
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckOutcome {
+    /// The check reported a vulnerability
+    Vulnerable,
+    /// Every attack request was answered and nothing was found
+    Clean,
+    /// Nothing was found, but attack requests failed, so the check may have
+    /// missed it
+    Error,
+}
+
+impl CheckOutcome {
+    /// Name used in JSON and plain output.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CheckOutcome::Vulnerable => "vulnerable",
+            CheckOutcome::Clean => "clean",
+            CheckOutcome::Error => "error",
+        }
+    }
+}
+
+impl fmt::Display for CheckOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Weight of each detection signal in a finding's evidence score, keyed by
+/// the signal's name (the part before any `:`). Signals not listed, such as
+/// the CL.CL hop notes, describe a finding without adding to its evidence.
+const SIGNAL_WEIGHTS: &[(&str, i32)] = &[
+    // Direct evidence: a response nobody asked for, or a queued request
+    ("unsolicited_bytes", 35),
+    ("head_body_queued", 35),
+    ("second_request_desync", 30),
+    ("h2_downgrade_desync", 30),
+    ("followup_divergence", 25),
+    ("early_data_status_mismatch", 25),
+    // Attack and control responses told apart
+    ("body_divergence_vs_control", 20),
+    ("header_divergence_vs_control", 15),
+    ("chunked_control_clean", 10),
+    ("control_responds_fast", 10),
+    ("control_unaffected", 10),
+    // Anomalies of the attack response itself
+    ("body_truncated", 20),
+    ("body_overrun", 20),
+    ("timing_anomaly", 20),
+    ("extreme_timing", 15),
+    ("status_408", 15),
+    ("status_504", 15),
+    ("connection_timeout", 10),
+    ("h2_frame_fuzz", 10),
+    ("early_data_accepted", 10),
+    // Evidence against
+    ("baseline_noisy", -15),
+];
+
+/// Weight of an HTTP/2 `<shape>_stall` signal
+const STALL_WEIGHT: i32 = 20;
+/// Weight of a cross-check that agreed with the claimed technique
+const CROSS_CHECK_CONSISTENT_WEIGHT: i32 = 10;
+/// Weight of a cross-check that desynced under the opposite technique
+const CROSS_CHECK_INCONSISTENT_WEIGHT: i32 = -25;
+/// Weight of a finding every `--repeat` run reproduced
+const REPEAT_STABLE_WEIGHT: i32 = 10;
+/// Weight of a finding only some `--repeat` runs reproduced
+const REPEAT_FLAKY_WEIGHT: i32 = -15;
+
+/// One factor of an evidence score
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ScoreFactor {
+    /// The signal (`timing_anomaly:83.4x`) or `cross_check:<verdict>`
+    pub factor: String,
+    /// Points it added, or took away when negative
+    pub weight: i32,
+}
+
+/// A finding's signals weighed into a 0-100 score, with the factors behind it
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EvidenceScore {
+    pub score: u8,
+    pub factors: Vec<ScoreFactor>,
+}
+
+impl EvidenceScore {
+    /// Score the finding `check` reports; `None` when it reports none.
+    pub fn of(check: &CheckResult) -> Option<Self> {
+        if !check.vulnerable {
+            return None;
+        }
+        let mut factors: Vec<ScoreFactor> = check
+            .detection_signals
+            .iter()
+            .filter_map(|signal| {
+                let name = signal.split(':').next().unwrap_or(signal);
+                let weight = SIGNAL_WEIGHTS
+                    .iter()
+                    .find(|(known, _)| *known == name)
+                    .map(|(_, weight)| *weight)
+                    .or_else(|| name.ends_with("_stall").then_some(STALL_WEIGHT))?;
+                Some(ScoreFactor {
+                    factor: signal.clone(),
+                    weight,
+                })
+            })
+            .collect();
+        if let Some(ref cross_check) = check.cross_check {
+            let weight = match cross_check.verdict {
+                CrossCheckVerdict::Consistent => CROSS_CHECK_CONSISTENT_WEIGHT,
+                CrossCheckVerdict::Inconsistent => CROSS_CHECK_INCONSISTENT_WEIGHT,
+                CrossCheckVerdict::Inconclusive => 0,
+            };
+            if weight != 0 {
+                factors.push(ScoreFactor {
+                    factor: format!("cross_check:{}", cross_check.verdict),
+                    weight,
+                });
+            }
+        }
+        if let Some(consistency) = check.consistency {
+            factors.push(ScoreFactor {
+                factor: format!(
+                    "consistency:{}/{}",
+                    consistency.detections, consistency.runs
+                ),
+                weight: if consistency.flaky {
+                    REPEAT_FLAKY_WEIGHT
+                } else {
+                    REPEAT_STABLE_WEIGHT
+                },
+            });
+        }
+        let total: i32 = factors.iter().map(|f| f.weight).sum();
+        Some(EvidenceScore {
+            score: total.clamp(0, 100) as u8,
+            factors,
+        })
+    }
+}
+
+/// `60/100 (timing_anomaly:83.4x +20, status_504 +15, ...)`
+impl fmt::Display for EvidenceScore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/100", self.score)?;
+        if !self.factors.is_empty() {
+            let factors: Vec<String> = self
+                .factors
+                .iter()
+                .map(|factor| format!("{} {:+}", factor.factor, factor.weight))
+                .collect();
+            write!(f, " ({})", factors.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl CheckResult {
+    /// Vulnerable, clean, or error when nothing was found but attack
+    /// requests failed.
+    pub fn outcome(&self) -> CheckOutcome {
+        if self.vulnerable {
+            CheckOutcome::Vulnerable
+        } else if self.request_errors.is_empty() {
+            CheckOutcome::Clean
+        } else {
+            CheckOutcome::Error
+        }
+    }
+}
+
+/// One request's time split into phases, in milliseconds, so a slow response
+/// can be told apart from a slow network
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+pub struct PhaseTimings {
+    /// Resolving the target's name; 0 when the scan is pinned to an address
+    /// or a proxy resolves it
+    pub dns_ms: u64,
+    /// TCP connect, and the CONNECT exchange through a proxy
+    pub tcp_ms: u64,
+    /// TLS handshake; 0 over plain HTTP
+    pub tls_ms: u64,
+    /// Writing the request
+    pub write_ms: u64,
+    /// From the end of the request to the first response byte: the time the
+    /// server took to answer
+    pub first_byte_ms: u64,
+    /// From the first response byte to the end of the response
+    pub transfer_ms: u64,
+}
+
+/// `dns 2ms, tcp 11ms, tls 35ms, write 0ms, first byte 5012ms, transfer 1ms`
+impl fmt::Display for PhaseTimings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "dns {}ms, tcp {}ms, tls {}ms, write {}ms, first byte {}ms, transfer {}ms",
+            self.dns_ms,
+            self.tcp_ms,
+            self.tls_ms,
+            self.write_ms,
+            self.first_byte_ms,
+            self.transfer_ms
+        )
+    }
+}
+
+/// What a built-in payload tries, attached to the payload and to the
+/// finding it triggers.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Technique {
+    /// Payload family, as accepted by `--checks` (e.g. "cl-te")
+    pub family: String,
+    /// Family and variation, stable across runs (e.g. "cl-te/linefold-tab")
+    pub id: String,
+    /// What the family varies to provoke a desync
+    pub description: String,
+    /// Where the technique is documented
+    pub reference: String,
+}
+
+/// Sidecar of a payload exported with `--export-format raw` or archived
+/// with `--export-all`: what the `.raw` file next to it was sent to, and what
+/// came back.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ExportedPayload {
+    /// Target URL the payload was sent to
+    pub target: String,
+    /// Check that sent it (e.g. "cl-te", or "cl-te-anomaly" for a fuzz anomaly)
+    pub check_type: String,
+    pub payload_index: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub technique: Option<Technique>,
+    /// File name of the byte-exact request
+    pub raw_file: String,
+    /// When the payload was found (RFC 3339)
+    pub timestamp: String,
+    /// When the files were written (RFC 3339)
+    pub exported_at: String,
+    /// What became of the payload, for `--export-all` archives: `clean`,
+    /// `error`, `refuted`, `detected`, `sweep-clean` or `sweep-detected`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<String>,
+    pub response: ResponseSummary,
+}
+
+/// What the target answered to an exported payload, against its baseline.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct ResponseSummary {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub normal_status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attack_status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub normal_duration_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attack_duration_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<Confidence>,
+    /// Detection signals of a finding, or the anomaly a fuzz mutant raised
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub signals: Vec<String>,
+}
+
+/// How an HTTP/2 peer ended a probe without answering it.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum H2Termination {
+    /// GOAWAY: the peer closed the connection
+    Goaway,
+    /// RST_STREAM on the probe's stream
+    RstStream,
+    /// The stream stalled behind a flow-control window smaller than its body,
+    /// so the stall says nothing about the back-end
+    FlowControlStall,
+    /// The connection closed or failed without a frame saying why
+    ConnectionClosed,
+}
+
+impl H2Termination {
+    /// Name used in JSON and plain output.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            H2Termination::Goaway => "goaway",
+            H2Termination::RstStream => "rst-stream",
+            H2Termination::FlowControlStall => "flow-control-stall",
+            H2Termination::ConnectionClosed => "connection-closed",
+        }
+    }
+}
+
+impl fmt::Display for H2Termination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An HTTP/2 probe the peer ended without a response.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct H2Observation {
+    /// Probe that was ended: `baseline`, `control`, an attack shape (`h2.cl`)
+    /// or a frame mutant (`frame-mutant #3 (padding-abuse)`)
+    pub payload: String,
+    pub termination: H2Termination,
+    /// Error code carried by the GOAWAY or RST_STREAM (e.g. `PROTOCOL_ERROR`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
+}
+
+/// A fuzzed payload whose response was anomalous but fell short of a
+/// confirmed vulnerability.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct FuzzAnomaly {
+    /// Position of the payload within its check
+    pub payload_index: usize,
+    /// Name of the payload (e.g. "mutant of linefold-tab"), when it has one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload_name: Option<String>,
+    /// What was observed and why it was not reported, e.g.
+    /// "delayed 5012ms (threshold 1500ms); not reproduced on retry"
+    pub anomaly: String,
+    /// Raw request that produced the anomaly
+    pub payload: String,
+    /// Line changes from the payload it was mutated from (`- seed`, `+ mutant`,
+    /// control characters escaped); empty for unmutated seeds
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub seed_diff: Vec<String>,
+    /// Raw request written for reproduction (with `--export-payloads`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reproduction_file: Option<String>,
 }
 
 /// Fingerprint information for JSON output
@@ -64,13 +640,318 @@ pub struct FingerprintInfo {
     pub via_header: Option<String>,
     /// Value of the X-Powered-By response header
     pub powered_by: Option<String>,
+    /// Whether a hop rewrites the asterisk-form `OPTIONS *` target to `/`
+    #[serde(default)]
+    pub asterisk_rewrite: bool,
+    /// Proxy hops in front of the final responder, from the Max-Forwards probe
+    #[serde(default)]
+    pub hop_count: Option<usize>,
+    /// Depth of the first hop that answered the Max-Forwards probe with an error
+    #[serde(default)]
+    pub error_hop: Option<usize>,
+    /// Protocol the front door negotiated over ALPN (`h2`, `http/1.1`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alpn: Option<String>,
+    /// The front door speaks h2 in front of an HTTP/1.1 hop, so the h2
+    /// checks were run first
+    #[serde(default)]
+    pub h2_downgrade_likely: bool,
+}
+
+/// One hop of the proxy chain reconstructed from response headers
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ProxyHop {
+    /// Product name, lowercased and without version (e.g. `varnish`)
+    pub product: String,
+    /// Response header the hop was recognized from
+    pub source: String,
+    /// Header value (or `Via` entry) that identified it
+    pub detail: String,
+}
+
+/// A port tested by the `localhost-access` exploit
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LocalhostPortResult {
+    /// Localhost (or internal host) port the smuggled request addressed
+    pub port: u16,
+    /// Whether the probe response showed signs of internal access
+    pub success: bool,
+    /// Port verdict (`open-different-service`, `same-app`, `filtered`, `skipped`)
+    pub verdict: String,
+    /// Loopback representation used in the smuggled `Host`; empty when it
+    /// named an internal host
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub loopback_host: String,
+    /// Internal host name used in the smuggled `Host` (`--exploit-hosts`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub internal_host: Option<String>,
+    /// Indicators behind the result
+    pub reason: String,
+    /// Probe response status line, if one was received
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_status: Option<String>,
+}
+
+/// An address and port the `internal-sweep` exploit smuggled to
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SweepTarget {
+    /// Internal address the smuggled `Host` named
+    pub address: String,
+    pub port: u16,
+    /// `reachable`, `refused` or `different-app`
+    pub verdict: String,
+    /// Indicators behind the verdict
+    pub reason: String,
+    /// Probe response status line, if one was received
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_status: Option<String>,
+}
+
+/// What the `internal-sweep` exploit covered and found
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct InternalSweep {
+    /// Swept block, normalized (`10.0.0.0/24`)
+    pub range: String,
+    /// Requests sent, baseline and control included
+    pub requests_sent: usize,
+    /// Address and port pairs left untested
+    pub untested: usize,
+    /// Why the sweep ended before covering the range
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stopped: Option<String>,
+    /// Every pair tested, in sweep order
+    pub targets: Vec<SweepTarget>,
+}
+
+/// A path the `path-fuzz` exploit reported as found
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DiscoveredPath {
+    /// Smuggled request path
+    pub path: String,
+    /// Indicators or matched rules behind the hit
+    pub reason: String,
+    /// Probe response status (e.g. "HTTP 200"), if one was received
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_status: Option<String>,
+    /// Probe response body size in bytes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_size: Option<usize>,
+}
+
+/// What was decided about an exploit plan
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ExploitDecision {
+    /// Approved at the `y/N` prompt
+    Confirmed,
+    /// Approved ahead of time with `--yes`
+    PreApproved,
+    /// Declined at the prompt
+    Declined,
+    /// Not asked: stdin is not a terminal and `--yes` was not given
+    NoTerminal,
+}
+
+impl ExploitDecision {
+    /// Whether the exploits may run.
+    pub fn approved(self) -> bool {
+        matches!(self, Self::Confirmed | Self::PreApproved)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Confirmed => "confirmed",
+            Self::PreApproved => "pre-approved",
+            Self::Declined => "declined",
+            Self::NoTerminal => "no-terminal",
+        }
+    }
+}
+
+impl std::fmt::Display for ExploitDecision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An exploit about to run, as shown before exploitation
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PlannedExploit {
+    /// Exploit name as accepted by `--exploit`
+    pub exploit: String,
+    /// Most requests it may send
+    pub max_requests: usize,
+    /// Request line (and Host, when it is the point) of each request it smuggles
+    pub smuggled: Vec<String>,
+}
+
+/// The exploits proposed for a target and whether they were allowed to run
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ExploitPlan {
+    pub exploits: Vec<PlannedExploit>,
+    pub decision: ExploitDecision,
+}
+
+impl ExploitPlan {
+    /// Most requests the whole plan may send.
+    pub fn max_requests(&self) -> usize {
+        self.exploits.iter().map(|e| e.max_requests).sum()
+    }
+}
+
+/// Structured outcome of the exploits run against a target
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct ExploitResults {
+    /// The plan shown before exploitation and its decision
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plan: Option<ExploitPlan>,
+    /// The cap that cut exploitation short (`--exploit-max-requests`,
+    /// `--exploit-max-time`); results are partial when set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capped: Option<String>,
+    /// Every port tested by `localhost-access`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub localhost_access: Vec<LocalhostPortResult>,
+    /// Paths found by `path-fuzz`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub path_fuzz: Vec<DiscoveredPath>,
+    /// Addresses and ports swept by `internal-sweep`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub internal_sweep: Option<InternalSweep>,
+}
+
+impl ExploitResults {
+    /// Whether no structured exploit produced output.
+    pub fn is_empty(&self) -> bool {
+        self.localhost_access.is_empty()
+            && self.path_fuzz.is_empty()
+            && self.internal_sweep.is_none()
+    }
+}
+
+/// `Retry-After` responses seen while scanning a target
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct Throttle {
+    /// Responses that carried a `Retry-After` header
+    pub events: usize,
+    /// Time spent waiting before the next request, in milliseconds
+    pub paused_ms: u64,
+    /// Longest delay a response asked for, in seconds
+    pub longest_retry_after_secs: u64,
+    /// Events whose delay was cut to `--retry-after-max`
+    pub capped: usize,
+}
+
+/// `3 Retry-After response(s), paused 12.5s (longest asked 120s, 1 capped)`
+impl fmt::Display for Throttle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} Retry-After response(s), paused {:.1}s (longest asked {}s",
+            self.events,
+            self.paused_ms as f64 / 1000.0,
+            self.longest_retry_after_secs
+        )?;
+        if self.capped > 0 {
+            write!(f, ", {} capped", self.capped)?;
+        }
+        f.write_str(")")
+    }
+}
+
+/// Product and version strings and TLS certificate details observed while
+/// scanning a target
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct TargetInfo {
+    /// Distinct `Server` header values, in the order first seen
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub server: Vec<String>,
+    /// Distinct `X-Powered-By` header values
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub powered_by: Vec<String>,
+    /// Product/version strings found in 4xx/5xx response bodies, e.g.
+    /// `Apache/2.4.57`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub error_page_versions: Vec<String>,
+    /// Leaf certificate the target presented
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub certificate: Option<CertificateInfo>,
+}
+
+impl TargetInfo {
+    /// Whether nothing was observed.
+    pub fn is_empty(&self) -> bool {
+        self.server.is_empty()
+            && self.powered_by.is_empty()
+            && self.error_page_versions.is_empty()
+            && self.certificate.is_none()
+    }
+}
+
+impl fmt::Display for TargetInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if !self.server.is_empty() {
+            parts.push(format!("Server {}", self.server.join(", ")));
+        }
+        if !self.powered_by.is_empty() {
+            parts.push(format!("X-Powered-By {}", self.powered_by.join(", ")));
+        }
+        if !self.error_page_versions.is_empty() {
+            parts.push(format!(
+                "error page {}",
+                self.error_page_versions.join(", ")
+            ));
+        }
+        if let Some(ref cert) = self.certificate {
+            parts.push(format!(
+                "certificate {} (issuer {}, expires {})",
+                cert.subject, cert.issuer, cert.not_after
+            ));
+        }
+        f.write_str(&parts.join("; "))
+    }
+}
+
+/// Metadata of an X.509 certificate
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct CertificateInfo {
+    /// Subject distinguished name, e.g. `CN=example.com, O=Example`
+    pub subject: String,
+    /// Issuer distinguished name
+    pub issuer: String,
+    /// Serial number, hex
+    pub serial: String,
+    /// Start of validity (RFC 3339)
+    pub not_before: String,
+    /// End of validity (RFC 3339)
+    pub not_after: String,
+    /// DNS names and IP addresses from the subjectAltName extension
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub san: Vec<String>,
 }
 
 /// Overall scan results
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ScanResults {
     /// Target URL that was scanned
     pub target: String,
+    /// Address every connection of the scan was pinned to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    /// Host header sent, when it differs from the target URL's host
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vhost: Option<String>,
+    /// IP address the scan's connections reached: the pinned address, or the
+    /// one the host resolved to. Absent when connecting through a proxy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ip: Option<String>,
+    /// Port connected to (explicit or the scheme's default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    /// `http` or `https`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scheme: Option<String>,
     /// HTTP method used for attack requests
     pub method: String,
     /// ISO 8601 timestamp of the scan
@@ -80,10 +961,256 @@ pub struct ScanResults {
     pub fingerprint: Option<FingerprintInfo>,
     /// Results of each individual smuggling check
     pub checks: Vec<CheckResult>,
+    /// Outcomes of the `localhost-access` and `path-fuzz` exploits, if run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exploits: Option<ExploitResults>,
+    /// Proxy chain seen across all scan responses, client side first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub proxy_chain: Vec<ProxyHop>,
+    /// Set when the reachability preflight skipped the target; `error`
+    /// carries the detail
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unreachable: Option<Unreachable>,
+    /// `Retry-After` pauses honored during the scan
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub throttle: Option<Throttle>,
+    /// Server products, versions and certificate seen during the scan
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_info: Option<TargetInfo>,
     /// Error message if the target scan failed (e.g. connection or parsing error).
     /// When present, `checks` will usually be empty.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Kind and retryability of `error`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_info: Option<ErrorInfo>,
+    /// Wall-clock time the scan of the target took, in milliseconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    /// Requests sent to the target (preflight, probes, payloads and exploits)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requests_sent: Option<u64>,
+    /// Totals over `checks`; absent for a target that failed before scanning
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<ScanSummary>,
+}
+
+/// Totals over a target's checks, so "was anything found?" needs no fold
+/// over `checks`
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct ScanSummary {
+    /// Checks that reported a vulnerability
+    pub vulnerable: usize,
+    /// Checks that found nothing with every attack request answered
+    pub clean: usize,
+    /// Checks that found nothing while some attack requests failed
+    pub errored: usize,
+    /// Payloads sent across all checks
+    pub payloads_sent: usize,
+    /// Findings only some `--repeat` runs reproduced
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub flaky: usize,
+    /// Strongest confidence among the findings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub highest_confidence: Option<Confidence>,
+    /// Wall-clock time of the scan, in milliseconds
+    pub duration_ms: u64,
+}
+
+impl ScanSummary {
+    /// Summarize `checks` of a scan that took `duration_ms`.
+    pub fn from_checks(checks: &[CheckResult], duration_ms: u64) -> Self {
+        let mut summary = Self {
+            duration_ms,
+            ..Self::default()
+        };
+        for check in checks {
+            match check.outcome() {
+                CheckOutcome::Vulnerable => summary.vulnerable += 1,
+                CheckOutcome::Clean => summary.clean += 1,
+                CheckOutcome::Error => summary.errored += 1,
+            }
+            summary.payloads_sent += check.payloads_sent;
+            if check.vulnerable && check.consistency.is_some_and(|c| c.flaky) {
+                summary.flaky += 1;
+            }
+            if let Some(ref confidence) = check.confidence
+                && check.vulnerable
+                && summary
+                    .highest_confidence
+                    .as_ref()
+                    .is_none_or(|highest| confidence.rank() > highest.rank())
+            {
+                summary.highest_confidence = Some(confidence.clone());
+            }
+        }
+        summary
+    }
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+/// `3 check(s): 1 vulnerable, 1 clean, 1 error; 55 payload(s) sent, highest
+/// confidence high, 12.5s`
+impl fmt::Display for ScanSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} check(s): {} vulnerable, {} clean, {} error; {} payload(s) sent",
+            self.vulnerable + self.clean + self.errored,
+            self.vulnerable,
+            self.clean,
+            self.errored,
+            self.payloads_sent
+        )?;
+        if self.flaky > 0 {
+            write!(f, ", {} flaky", self.flaky)?;
+        }
+        if let Some(ref confidence) = self.highest_confidence {
+            write!(f, ", highest confidence {}", confidence.as_str())?;
+        }
+        write!(f, ", {:.1}s", self.duration_ms as f64 / 1000.0)
+    }
+}
+
+/// How a target's `error` is classified
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ErrorInfo {
+    /// `dns`, `connect`, `tls`, `timeout`, `io`, `parse`, `config` or
+    /// `target-destabilized`
+    pub kind: String,
+    /// Part of the exchange a `timeout` stalled in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phase: Option<TimeoutPhase>,
+    /// Whether trying again may succeed (a connection that could not be
+    /// opened or was reset)
+    pub retryable: bool,
+}
+
+/// Shell one-liner printed by `smugglex reproduce`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Reproduction {
+    /// Finding the command replays (e.g. "cl-te/linefold-tab")
+    pub id: String,
+    /// Target URL of the finding
+    pub target: String,
+    /// Check that reported the finding
+    pub check_type: String,
+    /// `printf ... | ncat ...` command sending the winning payload's bytes
+    pub command: String,
+    /// Headers whose values stay redacted in the command (pass them with -H)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redacted: Vec<String>,
+}
+
+/// A saved finding replayed by `smugglex retest`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RetestResult {
+    /// Target URL of the original finding
+    pub target: String,
+    /// Address the original scan was pinned to, reused for the replay
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    /// Check that reported the finding
+    pub check_type: String,
+    /// Index of the winning payload in the original scan
+    pub payload_index: Option<usize>,
+    /// Name of the winning payload in the original scan
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload_name: Option<String>,
+    /// Confidence of the original finding
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_confidence: Option<Confidence>,
+    /// Whether the finding reproduced
+    pub reproduced: bool,
+    /// Result of the replay, with its fresh baseline and confirmation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub check: Option<CheckResult>,
+    /// Why the finding could not be replayed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Output of `smugglex retest`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RetestReport {
+    /// smugglex version that produced this output
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smugglex_version: Option<String>,
+    /// ISO 8601 timestamp when the retest completed
+    pub timestamp: String,
+    /// One entry per replayed finding
+    pub results: Vec<RetestResult>,
+    /// Findings that still reproduce
+    pub reproduced: usize,
+    /// Findings that were replayed and no longer reproduce
+    pub fixed: usize,
+    /// Findings that could not be replayed
+    pub errors: usize,
+}
+
+/// One target ranked by `--triage`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TriageResult {
+    /// Target URL
+    pub target: String,
+    /// Sum of the weights of `signals`; higher is more worth a full scan
+    pub score: u32,
+    /// What the triage probes found, e.g. `cl-te:timeout`, `pipelining`,
+    /// `proxy:varnish`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub signals: Vec<String>,
+    /// Status line of the baseline GET
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    /// Why the target could not be triaged
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Output of `--triage`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TriageReport {
+    /// smugglex version that produced this output
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smugglex_version: Option<String>,
+    /// ISO 8601 timestamp when the triage completed
+    pub timestamp: String,
+    /// Targets, highest score first
+    pub results: Vec<TriageResult>,
+    /// Targets with a non-zero score
+    pub promising: usize,
+    /// Targets that could not be triaged
+    pub errors: usize,
+}
+
+/// Lint result for one `--raw-request` template (`smugglex validate`)
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TemplateValidation {
+    /// Template file that was checked
+    pub path: String,
+    /// Problems that make the template unusable or change what is sent
+    pub errors: Vec<String>,
+    /// Suspicious constructs that still produce a request
+    pub warnings: Vec<String>,
+    /// Sample request the template expands to, when it parses
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expansion: Option<String>,
+}
+
+/// One built-in payload family in `smugglex payloads list`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PayloadCatalogEntry {
+    /// Check name, as accepted by `--checks`
+    pub check: String,
+    /// Number of payloads the family generates
+    pub count: usize,
+    /// What the family varies
+    pub description: String,
+    /// First payload of the family rendered for a sample target
+    pub example: String,
 }
 
 /// Summary statistics for a batch of scan results.
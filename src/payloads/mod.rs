@@ -1,19 +1,520 @@
+mod asterisk;
 mod cl_te;
 mod h2;
 mod h2c;
+mod head;
 mod te_cl;
 mod te_te;
 mod te_variations;
 
+mod cl_cl;
 mod cl_edge;
+mod connection;
 
-pub use cl_edge::get_cl_edge_case_payloads;
-pub use cl_te::get_cl_te_payloads;
-pub use h2::get_h2_payloads;
-pub use h2c::get_h2c_payloads;
-pub use te_cl::get_te_cl_payloads;
-pub use te_te::get_te_te_payloads;
-pub use te_variations::get_te_header_variations;
+use crate::model::{PayloadCatalogEntry, Technique};
+use crate::secrets::expand_header;
+
+pub use asterisk::{asterisk_payloads, get_asterisk_payloads};
+pub use cl_cl::{cl_cl_hop_signals, cl_cl_payloads, get_cl_cl_payloads};
+pub use cl_edge::{cl_edge_case_payloads, get_cl_edge_case_payloads};
+pub use cl_te::{cl_te_extended_payloads, cl_te_payloads, get_cl_te_payloads};
+pub use connection::{connection_payloads, get_connection_payloads};
+pub use h2::{get_h2_payloads, h2_payloads};
+pub use h2c::{get_h2c_payloads, h2c_payloads};
+pub use head::{get_head_payloads, head_payloads};
+pub use te_cl::{get_te_cl_payloads, te_cl_extended_payloads, te_cl_payloads};
+pub use te_te::{get_te_te_payloads, te_te_extended_payloads, te_te_payloads};
+pub use te_variations::{
+    get_te_header_variations, te_header_extended_variations, te_header_variations,
+};
+
+/// Signature shared by every payload-string check's generator:
+/// `(path, host, method, custom_headers, cookies)`.
+pub type PayloadFn = fn(&str, &str, &str, &[String], &[String]) -> PayloadIter;
+
+/// A built-in payload family: the generator behind one payload-string check.
+#[derive(Debug, Clone, Copy)]
+pub struct PayloadFamily {
+    /// Check name, as accepted by `--checks`
+    pub check: &'static str,
+    /// What the family varies to provoke a desync
+    pub description: &'static str,
+    /// Where the technique is documented
+    pub reference: &'static str,
+    pub generate: PayloadFn,
+    /// Names of the payloads `--quick` sends, in the order sent: the
+    /// variations that have historically found the most desyncs
+    pub quick: &'static [&'static str],
+    /// Long-tail payloads `--extended` sends after the default ones, for
+    /// families that keep a long tail out of the default set
+    pub extended: Option<PayloadFn>,
+}
+
+/// `--quick` selection shared by `cl-te` and `te-cl`, which pair the same
+/// Transfer-Encoding variations with opposite framing.
+const QUICK_TE_VARIATIONS: &[&str] = &[
+    "vanilla",
+    "space-before-colon",
+    "tab-after-colon",
+    "linefold-space",
+    "chunked-identity",
+    "identity-chunked",
+    "nospace",
+    "trailing-space",
+    "cr-suffix",
+    "uppercase-value",
+];
+
+/// Every payload-string check in its default scan order. The real-HTTP/2
+/// `h2-downgrade` check sends frames rather than payload strings, so it is
+/// not listed.
+pub const PAYLOAD_FAMILIES: [PayloadFamily; 10] = [
+    PayloadFamily {
+        check: "cl-te",
+        description: "Body framed by Content-Length that ends early under chunked parsing, paired with every obfuscated Transfer-Encoding header",
+        reference: "https://portswigger.net/web-security/request-smuggling#cl-te-vulnerabilities",
+        generate: cl_te_payloads,
+        quick: QUICK_TE_VARIATIONS,
+        extended: Some(cl_te_extended_payloads),
+    },
+    PayloadFamily {
+        check: "te-cl",
+        description: "Chunked body with a short Content-Length, paired with every obfuscated Transfer-Encoding header",
+        reference: "https://portswigger.net/web-security/request-smuggling#te-cl-vulnerabilities",
+        generate: te_cl_payloads,
+        quick: QUICK_TE_VARIATIONS,
+        extended: Some(te_cl_extended_payloads),
+    },
+    PayloadFamily {
+        check: "te-te",
+        description: "A plain and an obfuscated Transfer-Encoding header, so only one hop honors chunked framing",
+        reference: "https://portswigger.net/web-security/request-smuggling#te-te-behavior-obfuscating-the-te-header",
+        generate: te_te_payloads,
+        quick: &[
+            "dual-identity",
+            "dual-x-custom",
+            "dual-cow",
+            "reversed-dual",
+            "space-before-colon",
+            "tab-after-colon",
+            "linefold-space",
+            "nospace",
+            "uppercase",
+            "cr-suffix",
+        ],
+        extended: Some(te_te_extended_payloads),
+    },
+    PayloadFamily {
+        check: "h2c",
+        description: "Upgrade: h2c requests with HTTP2-Settings and smuggled framing",
+        reference: "https://bishopfox.com/blog/h2c-smuggling-request",
+        generate: h2c_payloads,
+        quick: &[
+            "basic-upgrade",
+            "upgrade-cl-smuggle",
+            "upgrade-cl-te",
+            "upgrade-te-cl",
+            "connection-keep-alive",
+            "settings-minimal",
+            "upgrade-space-before-colon",
+            "double-upgrade",
+        ],
+        extended: None,
+    },
+    PayloadFamily {
+        check: "h2",
+        description: "HTTP/2 pseudo-header and translation vectors rendered as HTTP/1.1 requests",
+        reference: "https://portswigger.net/web-security/request-smuggling/advanced",
+        generate: h2_payloads,
+        quick: &[
+            "cl-mismatch-smuggle",
+            "dual-cl-smuggle",
+            "te-forbidden",
+            "crlf-in-value",
+            "lf-in-value",
+            "downgrade-smuggle",
+            "request-in-header",
+            "cl0-with-body",
+            "connection-forbidden",
+            "dual-cl-with-pseudo",
+        ],
+        extended: None,
+    },
+    PayloadFamily {
+        check: "cl-edge",
+        description: "Duplicated, malformed or ambiguous Content-Length values (leading zeros, signs, hex, ...)",
+        reference: "https://www.rfc-editor.org/rfc/rfc9112#section-6.3",
+        generate: cl_edge_case_payloads,
+        quick: &[
+            "dual-cl-0-6",
+            "dual-cl-6-0",
+            "cl0-smuggled-request",
+            "cl0-chunked-body",
+            "te-before-cl",
+            "plus-sign",
+            "leading-zero",
+            "comma-duplicate",
+            "space-before-colon",
+            "data-after-last-chunk",
+        ],
+        extended: None,
+    },
+    PayloadFamily {
+        check: "cl-cl",
+        description: "Two Content-Length headers with different values",
+        reference: "https://www.rfc-editor.org/rfc/rfc9110#section-8.6",
+        generate: cl_cl_payloads,
+        quick: &[
+            "6-50/canonical",
+            "50-6/canonical",
+            "0-6/canonical",
+            "6-0/canonical",
+            "0-6/lowercase",
+            "6-0/lowercase",
+            "0-6/tab-after-colon",
+            "6-0/trailing-space",
+        ],
+        extended: None,
+    },
+    PayloadFamily {
+        check: "asterisk",
+        description: "Asterisk-form request target (OPTIONS *) with CL/TE framing",
+        reference: "https://www.rfc-editor.org/rfc/rfc9112#section-3.2.4",
+        generate: asterisk_payloads,
+        quick: &[
+            "options/cl-te",
+            "options/te-cl",
+            "options/cl-cl-0-6",
+            "method/cl-te",
+            "method/te-cl",
+            "method/cl-cl-0-6",
+        ],
+        extended: None,
+    },
+    PayloadFamily {
+        check: "connection",
+        description: "Connection headers that list a framing header as hop-by-hop, with CL.TE and TE.CL framing",
+        reference: "https://www.rfc-editor.org/rfc/rfc9110#section-7.6.1",
+        generate: connection_payloads,
+        quick: &[
+            "close/cl-te",
+            "close/te-cl",
+            "keep-alive/cl-te",
+            "keep-alive/te-cl",
+            "te/cl-te",
+            "te/te-cl",
+            "cl/cl-te",
+            "cl/te-cl",
+            "keep-alive-te/cl-te",
+            "keep-alive-cl/te-cl",
+        ],
+        extended: None,
+    },
+    PayloadFamily {
+        check: "head",
+        description: "HEAD requests declaring a body that queues a request prefix on the connection",
+        reference: "https://portswigger.net/research/http2",
+        generate: head_payloads,
+        quick: &["cl-only", "cl-te", "te-cl"],
+        extended: None,
+    },
+];
+
+/// Host the catalog examples are rendered for.
+const CATALOG_SAMPLE_HOST: &str = "example.com";
+
+impl PayloadFamily {
+    /// Catalog entry for `smugglex payloads list`: the family's size and its
+    /// first payload rendered as a default-method request to `/` on
+    /// [`CATALOG_SAMPLE_HOST`].
+    pub fn catalog_entry(&self) -> PayloadCatalogEntry {
+        let mut payloads = (self.generate)(
+            "/",
+            CATALOG_SAMPLE_HOST,
+            crate::cli::DEFAULT_METHOD,
+            &[],
+            &[],
+        );
+        PayloadCatalogEntry {
+            check: self.check.to_string(),
+            count: payloads.len(),
+            description: self.description.to_string(),
+            example: payloads.next().map(|p| p.request).unwrap_or_default(),
+        }
+    }
+
+    /// Technique of the payload at `index`, identified by its name when it
+    /// has one.
+    pub fn technique(&self, index: usize, name: Option<&str>) -> Technique {
+        Technique {
+            family: self.check.to_string(),
+            id: match name {
+                Some(name) => format!("{}/{}", self.check, name),
+                None => format!("{}/{}", self.check, index),
+            },
+            description: self.description.to_string(),
+            reference: self.reference.to_string(),
+        }
+    }
+}
+
+/// Look up a payload family by its check name.
+pub fn payload_family(check: &str) -> Option<&'static PayloadFamily> {
+    PAYLOAD_FAMILIES.iter().find(|f| f.check == check)
+}
+
+/// One generated attack request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Payload {
+    /// Position of the payload within its check (stable across runs)
+    pub index: usize,
+    /// Raw HTTP request sent on the wire
+    pub request: String,
+    /// Human-readable name of the variation (e.g. `linefold-tab`), when the
+    /// generator names its payloads
+    pub name: Option<String>,
+    /// What the payload tries, when it belongs to a built-in family (see
+    /// [`PayloadIter::in_family`])
+    pub technique: Option<Technique>,
+}
+
+/// Names the payload at an index, given its final request.
+type PayloadNamer = Box<dyn FnMut(usize, &str) -> String + Send>;
+
+/// Lazily generated payloads for one check.
+///
+/// Requests are formatted on demand in `next()`, so a check never holds more
+/// than the payload currently under test; the total is known up front
+/// (`len()`) for progress reporting.
+pub struct PayloadIter {
+    next: usize,
+    len: usize,
+    /// Payload index yielded at each position; `None` yields them in order.
+    order: Option<Vec<usize>>,
+    build: Box<dyn FnMut(usize) -> String + Send>,
+    /// See [`PayloadIter::named`].
+    namer: Option<PayloadNamer>,
+    /// See [`PayloadIter::in_family`].
+    family: Option<&'static PayloadFamily>,
+}
+
+impl PayloadIter {
+    /// `len` payloads, the `i`-th produced by `build(i)`.
+    pub fn new(len: usize, build: impl FnMut(usize) -> String + Send + 'static) -> Self {
+        Self {
+            next: 0,
+            len,
+            order: None,
+            build: Box::new(build),
+            namer: None,
+            family: None,
+        }
+    }
+
+    /// Name every payload with `namer(index, request)` (see [`Payload::name`]).
+    pub fn named(mut self, namer: impl FnMut(usize, &str) -> String + Send + 'static) -> Self {
+        self.namer = Some(Box::new(namer));
+        self
+    }
+
+    /// Attach `family`'s [`Technique`] to every payload.
+    pub fn in_family(mut self, family: &'static PayloadFamily) -> Self {
+        self.family = Some(family);
+        self
+    }
+
+    /// Yield only the payloads named in `names`, in that order (`--quick`);
+    /// names no payload carries are skipped. Like [`PayloadIter::prioritize`],
+    /// every request is generated once up front to be named.
+    pub fn select_named(mut self, names: &[&str]) -> Self {
+        let named: Vec<(usize, Option<String>)> = (self.next..self.len)
+            .map(|p| {
+                let index = self.index_at(p);
+                let request = (self.build)(index);
+                (
+                    index,
+                    self.namer.as_mut().map(|namer| namer(index, &request)),
+                )
+            })
+            .collect();
+        let order: Vec<usize> = names
+            .iter()
+            .filter_map(|name| {
+                named
+                    .iter()
+                    .find(|(_, n)| n.as_deref() == Some(*name))
+                    .map(|(index, _)| *index)
+            })
+            .collect();
+        self.len = order.len();
+        self.next = 0;
+        self.order = Some(order);
+        self
+    }
+
+    /// Follow the remaining payloads with those of `other` (`--extended`),
+    /// numbering them after this iterator's `len` so the indices of its own
+    /// payloads do not move. The result is named only if both are.
+    pub fn append(self, other: PayloadIter) -> Self {
+        let offset = self.len;
+        let order =
+            (self.order.is_some() || other.order.is_some() || self.next > 0 || other.next > 0)
+                .then(|| {
+                    (self.next..self.len)
+                        .map(|p| self.index_at(p))
+                        .chain((other.next..other.len).map(|p| offset + other.index_at(p)))
+                        .collect::<Vec<_>>()
+                });
+        let (mut build, mut other_build) = (self.build, other.build);
+        let namer: Option<PayloadNamer> = match (self.namer, other.namer) {
+            (Some(mut namer), Some(mut other_namer)) => Some(Box::new(move |i, request: &str| {
+                if i < offset {
+                    namer(i, request)
+                } else {
+                    other_namer(i - offset, request)
+                }
+            })),
+            _ => None,
+        };
+        Self {
+            next: 0,
+            len: order.as_ref().map_or(offset + other.len, Vec::len),
+            order,
+            build: Box::new(move |i| {
+                if i < offset {
+                    build(i)
+                } else {
+                    other_build(i - offset)
+                }
+            }),
+            namer,
+            family: self.family,
+        }
+    }
+
+    /// Yield at most `max` payloads (`--max-payloads`).
+    pub fn truncate(mut self, max: usize) -> Self {
+        self.len = self.len.min(max);
+        self
+    }
+
+    /// Rewrite every request as it is generated (e.g. raw-request placement markers).
+    pub fn map_requests(self, mut f: impl FnMut(String) -> String + Send + 'static) -> Self {
+        let Self {
+            next,
+            len,
+            order,
+            mut build,
+            namer,
+            family,
+        } = self;
+        Self {
+            next,
+            len,
+            order,
+            build: Box::new(move |i| f(build(i))),
+            namer,
+            family,
+        }
+    }
+
+    /// Yield the remaining payloads lowest `rank` first, keeping their
+    /// indices; ties keep their original order. Every request is generated
+    /// once up front to be ranked, so the generator must depend only on the
+    /// index (true of the built-in checks).
+    pub fn prioritize(mut self, mut rank: impl FnMut(&str) -> u8) -> Self {
+        let mut order: Vec<usize> = (self.next..self.len).map(|p| self.index_at(p)).collect();
+        let ranks: Vec<u8> = order.iter().map(|&i| rank(&(self.build)(i))).collect();
+        let mut positions: Vec<usize> = (0..order.len()).collect();
+        positions.sort_by_key(|&p| ranks[p]);
+        order = positions.into_iter().map(|p| order[p]).collect();
+        self.len = order.len();
+        self.next = 0;
+        self.order = Some(order);
+        self
+    }
+
+    fn index_at(&self, position: usize) -> usize {
+        self.order
+            .as_ref()
+            .map_or(position, |order| order[position])
+    }
+}
+
+impl Iterator for PayloadIter {
+    type Item = Payload;
+
+    fn next(&mut self) -> Option<Payload> {
+        if self.next >= self.len {
+            return None;
+        }
+        let index = self.index_at(self.next);
+        self.next += 1;
+        let request = (self.build)(index);
+        let name = self.namer.as_mut().map(|namer| namer(index, &request));
+        let technique = self
+            .family
+            .map(|family| family.technique(index, name.as_deref()));
+        Some(Payload {
+            index,
+            request,
+            name,
+            technique,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.next;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for PayloadIter {}
+
+impl From<Vec<String>> for PayloadIter {
+    fn from(mut requests: Vec<String>) -> Self {
+        let len = requests.len();
+        Self::new(len, move |i| std::mem::take(&mut requests[i]))
+    }
+}
+
+/// Request-line and header pieces shared by every payload of a check,
+/// formatted once so each payload only pays for its own variation.
+#[derive(Debug, Clone)]
+struct RequestParts {
+    method: String,
+    path: String,
+    host: String,
+    headers: String,
+    cookies: String,
+}
+
+impl RequestParts {
+    fn new(
+        path: &str,
+        host: &str,
+        method: &str,
+        custom_headers: &[String],
+        cookies: &[String],
+    ) -> Self {
+        Self {
+            method: method.to_string(),
+            path: path.to_string(),
+            host: host.to_string(),
+            headers: format_custom_headers(custom_headers),
+            cookies: format_cookies(cookies),
+        }
+    }
+}
+
+/// Drop entries whose `key` repeats an earlier entry's, keeping the first.
+pub(crate) fn dedup_preserving_order<T, K: Eq + std::hash::Hash>(
+    items: &mut Vec<T>,
+    key: impl Fn(&T) -> K,
+) {
+    let mut seen = std::collections::HashSet::with_capacity(items.len());
+    items.retain(|item| seen.insert(key(item)));
+}
 
 /// Helper function to format custom headers into a string
 pub fn format_custom_headers(custom_headers: &[String]) -> String {
@@ -26,7 +527,12 @@ pub fn format_custom_headers(custom_headers: &[String]) -> String {
             if i > 0 {
                 result.push_str("\r\n");
             }
-            result.push_str(header);
+            // References were resolved once up front, so a failure here
+            // cannot happen for a scan; the header is then sent as typed.
+            match expand_header(header) {
+                Ok(expanded) => result.push_str(&expanded),
+                Err(_) => result.push_str(header),
+            }
         }
         result.push_str("\r\n");
         result
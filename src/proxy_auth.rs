@@ -0,0 +1,189 @@
+//! Upstream proxy authentication (`--proxy-auth`) for the CONNECT tunnel.
+//!
+//! Credentials are sent as Basic up front; a proxy that answers `407` with a
+//! Digest challenge gets a second CONNECT carrying the Digest response
+//! (RFC 7616, `MD5` and `MD5-sess`).
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use md5::{Digest, Md5};
+
+use crate::error::{Result, SmugglexError};
+
+/// Username and password for the upstream proxy.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ProxyCredentials {
+    pub username: String,
+    pub password: String,
+}
+
+impl std::fmt::Debug for ProxyCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProxyCredentials")
+            .field("username", &self.username)
+            .field("password", &"[REDACTED]")
+            .finish()
+    }
+}
+
+impl ProxyCredentials {
+    /// Parse `user:pass`; the password runs to the end and may contain `:`.
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.split_once(':') {
+            Some((username, password)) if !username.is_empty() => Ok(Self {
+                username: username.to_string(),
+                password: password.to_string(),
+            }),
+            _ => Err(SmugglexError::Config(
+                "--proxy-auth must be 'user:pass'".to_string(),
+            )),
+        }
+    }
+
+    /// `Proxy-Authorization` value for Basic authentication.
+    pub fn basic_authorization(&self) -> String {
+        format!(
+            "Basic {}",
+            STANDARD.encode(format!("{}:{}", self.username, self.password))
+        )
+    }
+
+    /// `Proxy-Authorization` value answering a Digest `challenge` for a
+    /// `method` request to `uri` (for CONNECT, the `host:port` authority).
+    /// `cnonce` is only used when the challenge offers `qop=auth`.
+    pub fn digest_authorization(
+        &self,
+        challenge: &DigestChallenge,
+        method: &str,
+        uri: &str,
+        cnonce: &str,
+    ) -> String {
+        let nc = "00000001";
+        let mut ha1 = md5_hex(&format!(
+            "{}:{}:{}",
+            self.username, challenge.realm, self.password
+        ));
+        if challenge.is_session() {
+            ha1 = md5_hex(&format!("{}:{}:{}", ha1, challenge.nonce, cnonce));
+        }
+        let ha2 = md5_hex(&format!("{}:{}", method, uri));
+        let response = if challenge.qop_auth {
+            md5_hex(&format!(
+                "{}:{}:{}:{}:auth:{}",
+                ha1, challenge.nonce, nc, cnonce, ha2
+            ))
+        } else {
+            md5_hex(&format!("{}:{}:{}", ha1, challenge.nonce, ha2))
+        };
+
+        let mut header = format!(
+            "Digest username=\"{}\", realm=\"{}\", nonce=\"{}\", uri=\"{}\", response=\"{}\"",
+            self.username, challenge.realm, challenge.nonce, uri, response
+        );
+        if let Some(ref algorithm) = challenge.algorithm {
+            header.push_str(&format!(", algorithm={}", algorithm));
+        }
+        if challenge.qop_auth {
+            header.push_str(&format!(", qop=auth, nc={}, cnonce=\"{}\"", nc, cnonce));
+        }
+        if let Some(ref opaque) = challenge.opaque {
+            header.push_str(&format!(", opaque=\"{}\"", opaque));
+        }
+        header
+    }
+}
+
+/// A Digest challenge from a `Proxy-Authenticate` header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigestChallenge {
+    pub realm: String,
+    pub nonce: String,
+    pub opaque: Option<String>,
+    pub algorithm: Option<String>,
+    /// The proxy offers `qop=auth`
+    pub qop_auth: bool,
+}
+
+impl DigestChallenge {
+    /// Parse a `Proxy-Authenticate` value. `None` unless it is a Digest
+    /// challenge with a nonce and an MD5-based algorithm.
+    pub fn parse(value: &str) -> Option<Self> {
+        let (scheme, params) = value.trim().split_once(char::is_whitespace)?;
+        if !scheme.eq_ignore_ascii_case("digest") {
+            return None;
+        }
+        let params = auth_params(params);
+        let get = |key: &str| {
+            params
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, v)| v.clone())
+        };
+        let algorithm = get("algorithm");
+        if algorithm
+            .as_deref()
+            .is_some_and(|a| !a.eq_ignore_ascii_case("md5") && !a.eq_ignore_ascii_case("md5-sess"))
+        {
+            return None;
+        }
+        Some(Self {
+            realm: get("realm").unwrap_or_default(),
+            nonce: get("nonce")?,
+            opaque: get("opaque"),
+            qop_auth: get("qop").is_some_and(|qop| {
+                qop.split(',')
+                    .any(|q| q.trim().eq_ignore_ascii_case("auth"))
+            }),
+            algorithm,
+        })
+    }
+
+    fn is_session(&self) -> bool {
+        self.algorithm
+            .as_deref()
+            .is_some_and(|a| a.eq_ignore_ascii_case("md5-sess"))
+    }
+}
+
+/// `key=value` / `key="quoted, value"` pairs of an auth header, in order.
+fn auth_params(params: &str) -> Vec<(String, String)> {
+    let mut pairs = Vec::new();
+    let mut rest = params.trim_start_matches([' ', ',']);
+    while let Some((key, after)) = rest.split_once('=') {
+        let key = key.trim().to_string();
+        let after = after.trim_start();
+        let (value, next) = if let Some(quoted) = after.strip_prefix('"') {
+            let mut value = String::new();
+            let mut chars = quoted.char_indices();
+            let mut end = quoted.len();
+            while let Some((i, c)) = chars.next() {
+                match c {
+                    '\\' => {
+                        if let Some((_, escaped)) = chars.next() {
+                            value.push(escaped);
+                        }
+                    }
+                    '"' => {
+                        end = i + 1;
+                        break;
+                    }
+                    _ => value.push(c),
+                }
+            }
+            (value, &quoted[end..])
+        } else {
+            let end = after.find(',').unwrap_or(after.len());
+            (after[..end].trim().to_string(), &after[end..])
+        };
+        pairs.push((key, value));
+        rest = next.trim_start_matches([' ', ',']);
+    }
+    pairs
+}
+
+fn md5_hex(input: &str) -> String {
+    Md5::digest(input.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
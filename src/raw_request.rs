@@ -5,16 +5,51 @@
 //! empty line, and an optional body. smugglex uses such a file as a *template*
 //! for the request it crafts — method, request-target, Host and the remaining
 //! headers (cookies, auth tokens, content-type, ...) are reused, while the body
-//! is discarded because the smuggling payloads generate their own body and
-//! manage `Content-Length` / `Transfer-Encoding` themselves.
+//! is discarded (unless it carries `{{BODY}}`, below) because the smuggling
+//! payloads generate their own body and manage `Content-Length` /
+//! `Transfer-Encoding` themselves.
+//!
+//! A template may pin where the smuggling pieces land with placement markers:
+//! a `{{TE}}` or `{{CL}}` line in the header block marks where the crafted
+//! `Transfer-Encoding` / `Content-Length` headers are injected, and `{{BODY}}`
+//! in the body marks where the smuggling body goes; the template text around
+//! it is kept and counted in the crafted `Content-Length`. Without markers the
+//! payload generators' fixed layout is used unchanged.
 
 use crate::error::{Result, SmugglexError};
+use crate::payloads::{format_cookies, format_custom_headers};
 
 /// Headers that the smuggling payload generators add or control on their own.
 /// They are stripped from a raw request so we never emit duplicates or fight
 /// the crafted `Content-Length` / `Transfer-Encoding` desync vectors.
 const MANAGED_HEADERS: [&str; 4] = ["host", "content-length", "transfer-encoding", "connection"];
 
+/// Header-block marker for where the crafted `Transfer-Encoding` header (and any
+/// obfuscation lines that come with it) is injected.
+pub const TE_MARKER: &str = "{{TE}}";
+
+/// Header-block marker for where the crafted `Content-Length` header(s) are injected.
+pub const CL_MARKER: &str = "{{CL}}";
+
+/// Body marker for where the generated smuggling body is injected.
+pub const BODY_MARKER: &str = "{{BODY}}";
+
+/// Placement markers found in a raw request template.
+///
+/// The `{{TE}}` / `{{CL}}` marker lines themselves travel with the captured
+/// headers (so they keep their position relative to auth, content-type, ...)
+/// and are swapped for the crafted headers by [`PlacementMarkers::apply`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlacementMarkers {
+    /// Whether the header block carries a `{{TE}}` line.
+    pub te: bool,
+    /// Whether the header block carries a `{{CL}}` line.
+    pub cl: bool,
+    /// Template body split around `{{BODY}}` (text before, text after), if the
+    /// body carries the marker. The surrounding text is emitted verbatim.
+    pub body: Option<(String, String)>,
+}
+
 /// A raw HTTP request parsed from a file, reduced to the pieces smugglex reuses.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RawRequest {
@@ -37,6 +72,9 @@ pub struct RawRequest {
     /// The body itself is discarded — the smuggling payloads generate their own —
     /// but the flag lets the CLI note the discard in verbose mode.
     pub had_body: bool,
+    /// Placement markers, when the template uses any of `{{TE}}`, `{{CL}}` or
+    /// `{{BODY}}`. `None` keeps the payload generators' fixed layout.
+    pub markers: Option<PlacementMarkers>,
 }
 
 impl RawRequest {
@@ -70,10 +108,149 @@ pub fn merge_headers(mut captured: Vec<String>, user: &[String]) -> Vec<String>
     captured
 }
 
+impl PlacementMarkers {
+    /// Rearrange a generated payload so its crafted headers and body land where
+    /// the template's markers say.
+    ///
+    /// The payload generators emit `<request line, Host, Connection><custom
+    /// headers><cookies><crafted headers>\r\n\r\n<body>`; `custom_headers` and
+    /// `cookies` must be the values the payload was generated with so that block
+    /// can be located. Crafted lines starting with `Content-Length` go to `{{CL}}`
+    /// and every other crafted line (the TE header plus any obfuscation lines) to
+    /// `{{TE}}`; a group without a marker stays in its original slot. Payloads
+    /// that do not follow that layout only get the marker lines stripped, so a
+    /// literal `{{TE}}` is never sent on the wire. Template text around
+    /// `{{BODY}}` grows the body, so every `Content-Length` value is raised by
+    /// its length to keep framing the body the payload was built for.
+    pub fn apply(&self, payload: &str, custom_headers: &[String], cookies: &[String]) -> String {
+        let te_line = format!("{}\r\n", TE_MARKER);
+        let cl_line = format!("{}\r\n", CL_MARKER);
+        let custom_block = format_custom_headers(custom_headers);
+        let fixed_block = format!("{}{}", custom_block, format_cookies(cookies));
+
+        let located = if self.te || self.cl {
+            payload.find(&fixed_block).and_then(|start| {
+                let crafted_start = start + fixed_block.len();
+                let rest = &payload[crafted_start..];
+                // The fixed block ends in CRLF, so an immediate CRLF is the header
+                // terminator (no crafted headers at all).
+                if rest.starts_with("\r\n") {
+                    return Some((start, crafted_start, crafted_start, crafted_start + 2));
+                }
+                let crafted_end = crafted_start + rest.find("\r\n\r\n")?;
+                Some((start, crafted_start, crafted_end, crafted_end + 4))
+            })
+        } else {
+            None
+        };
+
+        let (head, body) = match located {
+            Some((start, crafted_start, crafted_end, body_start)) => {
+                let crafted: Vec<&str> = payload[crafted_start..crafted_end]
+                    .split("\r\n")
+                    .filter(|l| !l.is_empty())
+                    .collect();
+                let is_cl = |line: &str| {
+                    line.trim_start()
+                        .to_ascii_lowercase()
+                        .starts_with("content-length")
+                };
+                let group = |want_cl: bool| -> String {
+                    crafted
+                        .iter()
+                        .filter(|l| is_cl(l) == want_cl)
+                        .map(|l| format!("{}\r\n", l))
+                        .collect()
+                };
+
+                let mut custom = payload[start..start + custom_block.len()].to_string();
+                if self.te {
+                    custom = custom.replacen(&te_line, &group(false), 1);
+                }
+                if self.cl {
+                    custom = custom.replacen(&cl_line, &group(true), 1);
+                }
+
+                let mut head = String::with_capacity(payload.len());
+                head.push_str(&payload[..start]);
+                head.push_str(&custom);
+                head.push_str(&fixed_block[custom_block.len()..]);
+                // Crafted lines without a marker keep their original slot and order.
+                for line in crafted.iter().filter(|l| {
+                    let cl = is_cl(l);
+                    (cl && !self.cl) || (!cl && !self.te)
+                }) {
+                    head.push_str(line);
+                    head.push_str("\r\n");
+                }
+                // Drop the trailing CRLF: the header terminator is re-added below.
+                head.truncate(head.len() - 2);
+                (head, payload[body_start..].to_string())
+            }
+            None => {
+                let stripped = payload.replace(&te_line, "").replace(&cl_line, "");
+                match stripped.split_once("\r\n\r\n") {
+                    Some((head, body)) => (head.to_string(), body.to_string()),
+                    None => return stripped,
+                }
+            }
+        };
+
+        match &self.body {
+            Some((before, after)) => {
+                let head = shift_content_length(&head, before.len() + after.len());
+                format!("{}\r\n\r\n{}{}{}", head, before, body, after)
+            }
+            None => format!("{}\r\n\r\n{}", head, body),
+        }
+    }
+}
+
+/// `head` with every number in the value of each `Content-Length` line
+/// raised by `extra`; obfuscated spellings (`Content-Length : 5`,
+/// `Content-Length: 5, 5`) keep their shape.
+fn shift_content_length(head: &str, extra: usize) -> String {
+    if extra == 0 {
+        return head.to_string();
+    }
+    head.split("\r\n")
+        .map(|line| {
+            let Some((name, value)) = line.split_once(':') else {
+                return line.to_string();
+            };
+            if !name.trim().eq_ignore_ascii_case("content-length") {
+                return line.to_string();
+            }
+            let mut shifted = format!("{}:", name);
+            let mut digits = String::new();
+            for c in value.chars().chain(std::iter::once('\0')) {
+                if c.is_ascii_digit() {
+                    digits.push(c);
+                    continue;
+                }
+                if !digits.is_empty() {
+                    match digits.parse::<usize>() {
+                        Ok(n) => shifted.push_str(&(n + extra).to_string()),
+                        Err(_) => shifted.push_str(&digits),
+                    }
+                    digits.clear();
+                }
+                if c != '\0' {
+                    shifted.push(c);
+                }
+            }
+            shifted
+        })
+        .collect::<Vec<_>>()
+        .join("\r\n")
+}
+
 /// Parse the textual contents of a raw HTTP request file.
 ///
 /// Accepts both `\r\n` and bare `\n` line endings. The request body (anything
-/// after the first blank line) is intentionally ignored. Origin-form requests
+/// after the first blank line) is ignored unless it carries `{{BODY}}`, in
+/// which case the text around the marker is kept in
+/// [`PlacementMarkers::body`] to wrap the smuggling body. Origin-form requests
 /// require a `Host` header to determine the target; absolute-form request lines
 /// (`GET https://host/path HTTP/1.1`) derive the host, port and scheme from the
 /// line itself.
@@ -83,40 +260,60 @@ pub fn parse_raw_request(content: &str) -> Result<RawRequest> {
     let request_line = lines
         .by_ref()
         .find(|l| !l.trim().is_empty())
-        .ok_or_else(|| SmugglexError::InvalidInput("raw request file is empty".to_string()))?;
+        .ok_or_else(|| SmugglexError::Config("raw request file is empty".to_string()))?;
 
     let mut parts = request_line.split_whitespace();
     let method = parts
         .next()
-        .ok_or_else(|| SmugglexError::InvalidInput("raw request missing method".to_string()))?
+        .ok_or_else(|| SmugglexError::Config("raw request missing method".to_string()))?
         .to_string();
     // Guard against pointing `--raw-request` at a non-HTTP file (e.g. an accidental
     // JSON or HTML document): a valid method is an RFC 7230 token, so anything with
     // quotes/braces/colons gives a clear error instead of a baffling downstream one.
     if method.is_empty() || !method.bytes().all(is_tchar) {
-        return Err(SmugglexError::InvalidInput(format!(
+        return Err(SmugglexError::Config(format!(
             "'{}' is not a valid HTTP method; the first line does not look like an HTTP request line (expected e.g. 'GET /path HTTP/1.1')",
             method
         )));
     }
     let target_raw = parts
         .next()
-        .ok_or_else(|| {
-            SmugglexError::InvalidInput("raw request missing request target".to_string())
-        })?
+        .ok_or_else(|| SmugglexError::Config("raw request missing request target".to_string()))?
         .to_string();
 
     // Headers run until the first blank line; everything after is the body.
     let mut headers: Vec<String> = Vec::new();
     let mut host_header: Option<String> = None;
     let mut had_body = false;
+    let mut markers = PlacementMarkers::default();
     while let Some(line) = lines.next() {
         if line.trim().is_empty() {
             // Header section ends at the blank line; any non-empty line after it is
-            // the message body (discarded, but recorded so the CLI can note it).
-            had_body = lines.any(|l| !l.trim().is_empty());
+            // the message body (discarded unless it carries `{{BODY}}`, but
+            // recorded so the CLI can note it).
+            let body = lines.by_ref().collect::<Vec<_>>().join("\r\n");
+            let body = body.trim_end_matches(['\r', '\n']);
+            had_body = !body.trim().is_empty();
+            markers.body = body
+                .split_once(BODY_MARKER)
+                .map(|(before, after)| (before.to_string(), after.to_string()));
             break;
         }
+        // Marker lines keep their slot in the header order; they are replaced by
+        // the crafted headers once a payload is generated.
+        match line.trim() {
+            TE_MARKER => {
+                markers.te = true;
+                headers.push(TE_MARKER.to_string());
+                continue;
+            }
+            CL_MARKER => {
+                markers.cl = true;
+                headers.push(CL_MARKER.to_string());
+                continue;
+            }
+            _ => {}
+        }
         let Some((name, value)) = line.split_once(':') else {
             // Not a well-formed header line (e.g. an obsolete folded continuation);
             // skip it rather than emitting something malformed.
@@ -140,6 +337,9 @@ pub fn parse_raw_request(content: &str) -> Result<RawRequest> {
         parse_origin_form(method, target_raw, host_header, headers)?
     };
     request.had_body = had_body;
+    if markers != PlacementMarkers::default() {
+        request.markers = Some(markers);
+    }
     Ok(request)
 }
 
@@ -156,13 +356,13 @@ fn parse_origin_form(
     // (`OPTIONS *`) request lines, which would otherwise be concatenated into an
     // invalid synthetic URL downstream.
     if !target.starts_with('/') {
-        return Err(SmugglexError::InvalidInput(format!(
+        return Err(SmugglexError::Config(format!(
             "unsupported request target '{}' (expected an absolute path like '/path' or an absolute URL)",
             target
         )));
     }
     let host_header = host_header.ok_or_else(|| {
-        SmugglexError::InvalidInput(
+        SmugglexError::Config(
             "raw request is missing a Host header (required to determine the target)".to_string(),
         )
     })?;
@@ -172,7 +372,7 @@ fn parse_origin_form(
     // confusing downstream "URL parse error: empty host" against the synthetic
     // connect URL.
     if host.is_empty() {
-        return Err(SmugglexError::InvalidInput(
+        return Err(SmugglexError::Config(
             "raw request Host header is empty (required to determine the target)".to_string(),
         ));
     }
@@ -185,6 +385,7 @@ fn parse_origin_form(
         host_header,
         headers,
         had_body: false,
+        markers: None,
     })
 }
 
@@ -197,12 +398,12 @@ fn parse_absolute_form(
     headers: Vec<String>,
 ) -> Result<RawRequest> {
     let url = url::Url::parse(target_raw)
-        .map_err(|e| SmugglexError::InvalidInput(format!("invalid request target: {}", e)))?;
+        .map_err(|e| SmugglexError::Config(format!("invalid request target: {}", e)))?;
     // Use `host()` (not `host_str()`) so IPv6 literals keep their brackets
     // (`[::1]`); the unbracketed form would build invalid URLs and Host headers.
     let host = url
         .host()
-        .ok_or_else(|| SmugglexError::InvalidInput("request target has no host".to_string()))?
+        .ok_or_else(|| SmugglexError::Config("request target has no host".to_string()))?
         .to_string();
     let port = url.port();
 
@@ -239,6 +440,7 @@ fn parse_absolute_form(
         host_header,
         headers,
         had_body: false,
+        markers: None,
     })
 }
 
@@ -287,7 +489,7 @@ fn split_host_port(host_value: &str) -> Result<(String, Option<u16>)> {
                 && (host.ends_with(']') || !host.contains(':')) =>
         {
             let parsed = port.parse::<u16>().map_err(|_| {
-                SmugglexError::InvalidInput(format!(
+                SmugglexError::Config(format!(
                     "invalid port '{}' in Host header '{}' (must be 0-65535)",
                     port, host_value
                 ))
@@ -477,14 +679,14 @@ mod tests {
     fn errors_on_authority_form_target() {
         let raw = "CONNECT example.com:443 HTTP/1.1\r\nHost: example.com\r\n\r\n";
         let err = parse_raw_request(raw).unwrap_err();
-        assert!(matches!(err, SmugglexError::InvalidInput(_)));
+        assert!(matches!(err, SmugglexError::Config(_)));
     }
 
     #[test]
     fn errors_on_asterisk_form_target() {
         let raw = "OPTIONS * HTTP/1.1\r\nHost: example.com\r\n\r\n";
         let err = parse_raw_request(raw).unwrap_err();
-        assert!(matches!(err, SmugglexError::InvalidInput(_)));
+        assert!(matches!(err, SmugglexError::Config(_)));
     }
 
     #[test]
@@ -501,14 +703,14 @@ mod tests {
     #[test]
     fn errors_on_empty_input() {
         let err = parse_raw_request("\n\n   \n").unwrap_err();
-        assert!(matches!(err, SmugglexError::InvalidInput(_)));
+        assert!(matches!(err, SmugglexError::Config(_)));
     }
 
     #[test]
     fn errors_when_origin_form_missing_host() {
         let raw = "GET / HTTP/1.1\r\nAccept: */*\r\n\r\n";
         let err = parse_raw_request(raw).unwrap_err();
-        assert!(matches!(err, SmugglexError::InvalidInput(_)));
+        assert!(matches!(err, SmugglexError::Config(_)));
     }
 
     #[test]
@@ -521,8 +723,8 @@ mod tests {
         ] {
             let err = parse_raw_request(raw).unwrap_err();
             assert!(
-                matches!(err, SmugglexError::InvalidInput(_)),
-                "empty Host should be InvalidInput, raw = {raw:?}"
+                matches!(err, SmugglexError::Config(_)),
+                "empty Host should be a Config error, raw = {raw:?}"
             );
         }
     }
@@ -636,12 +838,12 @@ mod tests {
     fn split_host_port_errors_on_out_of_range_port() {
         // All-digit but > 65535: a clear authoring mistake, not "no port".
         let err = split_host_port("example.com:99999").unwrap_err();
-        assert!(matches!(err, SmugglexError::InvalidInput(_)));
+        assert!(matches!(err, SmugglexError::Config(_)));
         // And it surfaces through the full parse path.
         let raw = "GET /x HTTP/1.1\r\nHost: example.com:99999\r\n\r\n";
         assert!(matches!(
             parse_raw_request(raw).unwrap_err(),
-            SmugglexError::InvalidInput(_)
+            SmugglexError::Config(_)
         ));
     }
 
@@ -654,11 +856,7 @@ mod tests {
             "<!DOCTYPE html>\r\n<html></html>\r\n",
         ] {
             let err = parse_raw_request(junk).unwrap_err();
-            assert!(
-                matches!(err, SmugglexError::InvalidInput(_)),
-                "junk: {}",
-                junk
-            );
+            assert!(matches!(err, SmugglexError::Config(_)), "junk: {}", junk);
         }
     }
 
@@ -682,4 +880,96 @@ mod tests {
         let trailing_blank = "GET /x HTTP/1.1\r\nHost: h\r\n\r\n\r\n";
         assert!(!parse_raw_request(trailing_blank).unwrap().had_body);
     }
+
+    #[test]
+    fn parses_placement_markers() {
+        let raw = "POST /api HTTP/1.1\r\n\
+                   Host: h\r\n\
+                   Authorization: Bearer t\r\n\
+                   {{TE}}\r\n\
+                   Content-Type: application/json\r\n\
+                   {{CL}}\r\n\
+                   \r\n\
+                   {\"a\":1}{{BODY}}";
+        let parsed = parse_raw_request(raw).unwrap();
+        // Marker lines keep their position among the captured headers.
+        assert_eq!(
+            parsed.headers,
+            vec![
+                "Authorization: Bearer t".to_string(),
+                "{{TE}}".to_string(),
+                "Content-Type: application/json".to_string(),
+                "{{CL}}".to_string(),
+            ]
+        );
+        let markers = parsed.markers.unwrap();
+        assert!(markers.te && markers.cl);
+        assert_eq!(markers.body, Some(("{\"a\":1}".to_string(), String::new())));
+    }
+
+    #[test]
+    fn no_markers_leaves_template_unmarked() {
+        let parsed = parse_raw_request("GET / HTTP/1.1\r\nHost: h\r\n\r\nabc").unwrap();
+        assert_eq!(parsed.markers, None);
+    }
+
+    #[test]
+    fn apply_moves_crafted_headers_to_markers() {
+        let headers = vec![
+            "Authorization: x".to_string(),
+            "{{TE}}".to_string(),
+            "Content-Type: text/plain".to_string(),
+            "{{CL}}".to_string(),
+        ];
+        let cookies = vec!["s=1".to_string()];
+        let payload = crate::payloads::get_cl_te_payloads("/", "h", "POST", &headers, &cookies)
+            .into_iter()
+            .next()
+            .unwrap();
+        let markers = PlacementMarkers {
+            te: true,
+            cl: true,
+            body: None,
+        };
+        assert_eq!(
+            markers.apply(&payload, &headers, &cookies),
+            "POST / HTTP/1.1\r\nHost: h\r\nConnection: keep-alive\r\n\
+             Authorization: x\r\nTransfer-Encoding: chunked\r\n\
+             Content-Type: text/plain\r\nContent-Length: 6\r\n\
+             Cookie: s=1\r\n\r\n0\r\n\r\nG"
+        );
+    }
+
+    #[test]
+    fn apply_keeps_unmarked_group_in_place() {
+        let headers = vec!["{{TE}}".to_string(), "Accept: */*".to_string()];
+        let payload = crate::payloads::get_te_cl_payloads("/", "h", "POST", &headers, &[])
+            .into_iter()
+            .next()
+            .unwrap();
+        let markers = PlacementMarkers {
+            te: true,
+            cl: false,
+            body: None,
+        };
+        let applied = markers.apply(&payload, &headers, &[]);
+        assert!(applied.contains("keep-alive\r\nTransfer-Encoding: chunked\r\nAccept: */*\r\nContent-Length: 4\r\n\r\n1\r\nA"));
+        assert!(!applied.contains(TE_MARKER));
+    }
+
+    #[test]
+    fn apply_wraps_body_and_strips_markers_from_unknown_layouts() {
+        let markers = PlacementMarkers {
+            te: true,
+            cl: false,
+            body: Some(("pre-".to_string(), "-post".to_string())),
+        };
+        // A payload that does not embed the custom block verbatim still gets the
+        // marker line removed and the body wrapped.
+        let payload = "GET / HTTP/1.1\r\nHost: h\r\n{{TE}}\r\nUpgrade: h2c\r\n\r\nBODY";
+        assert_eq!(
+            markers.apply(payload, &["X: y".to_string()], &[]),
+            "GET / HTTP/1.1\r\nHost: h\r\nUpgrade: h2c\r\n\r\npre-BODY-post"
+        );
+    }
 }
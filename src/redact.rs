@@ -0,0 +1,159 @@
+//! Masking of sensitive header values (`--redact`).
+//!
+//! Found payloads carry the scan's own `-H`/`--cookies` headers, so results
+//! and exported requests would otherwise hand live credentials to everyone a
+//! report is shared with. The configured headers are masked wherever a
+//! request or response is printed or written; the requests sent on the wire
+//! are never touched.
+
+use std::sync::RwLock;
+
+use crate::model::{CheckResult, ScanResults};
+
+/// Headers redacted unless `--redact` names others.
+pub const DEFAULT_REDACTED_HEADERS: &str =
+    "Authorization,Proxy-Authorization,Cookie,Set-Cookie,X-Api-Key,X-Auth-Token";
+
+/// Value written in place of a redacted header's value.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Lowercased names of the headers to redact; empty disables redaction.
+static REDACTED_HEADERS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
+/// Set the headers masked by [`redact`] (case-insensitive; empty names are
+/// ignored). An empty list disables redaction.
+pub fn set_redacted_headers(names: &[String]) {
+    let names = names
+        .iter()
+        .map(|n| n.trim().to_ascii_lowercase())
+        .filter(|n| !n.is_empty())
+        .collect();
+    *REDACTED_HEADERS.write().unwrap_or_else(|e| e.into_inner()) = names;
+}
+
+/// Headers currently masked by [`redact`], lowercased.
+pub fn redacted_headers() -> Vec<String> {
+    REDACTED_HEADERS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// `text` with the values of the configured headers masked (see
+/// [`redact_with`]).
+pub fn redact(text: &str) -> String {
+    let names = redacted_headers();
+    if names.is_empty() {
+        return text.to_string();
+    }
+    redact_with(text, &names)
+}
+
+/// `text` with the value of every `Name: value` line whose name is in `names`
+/// (lowercased) replaced by [`REDACTED`].
+///
+/// Works line by line over requests and responses — including requests
+/// smuggled in a body — and over diff lines (`+ Cookie: a=1\r\n`, with the
+/// line ending escaped), keeping the separator and line ending.
+pub fn redact_with(text: &str, names: &[String]) -> String {
+    let mut out = String::with_capacity(text.len());
+    for line in text.split_inclusive('\n') {
+        match redact_line(line, names) {
+            Some(redacted) => out.push_str(&redacted),
+            None => out.push_str(line),
+        }
+    }
+    out
+}
+
+fn redact_line(line: &str, names: &[String]) -> Option<String> {
+    let content = line.trim_end_matches(['\r', '\n']);
+    let ending = &line[content.len()..];
+    let prefix_len = if content.starts_with("+ ") || content.starts_with("- ") {
+        2
+    } else {
+        0
+    };
+    let (name, value) = content[prefix_len..].split_once(':')?;
+    let name = name.trim();
+    if name.is_empty()
+        || name.contains(char::is_whitespace)
+        || !names.iter().any(|n| name.eq_ignore_ascii_case(n))
+    {
+        return None;
+    }
+    let escaped_ending = ["\\r\\n", "\\n", "\\r"]
+        .into_iter()
+        .find(|e| value.ends_with(e))
+        .unwrap_or("");
+    let padding = value.len() - value.trim_start().len();
+    let value_start = content.len() - value.len();
+    Some(format!(
+        "{}{}{}{}{}",
+        &content[..value_start],
+        &value[..padding],
+        REDACTED,
+        escaped_ending,
+        ending
+    ))
+}
+
+/// Mask the configured headers in a check's payload and fuzz anomalies.
+pub fn redact_check(check: &mut CheckResult) {
+    if let Some(ref payload) = check.payload {
+        check.payload = Some(redact(payload));
+    }
+    if let Some(ref bytes) = check.unsolicited_bytes {
+        check.unsolicited_bytes = Some(redact(bytes));
+    }
+    for anomaly in &mut check.fuzz_anomalies {
+        anomaly.payload = redact(&anomaly.payload);
+        for line in &mut anomaly.seed_diff {
+            *line = redact(line);
+        }
+    }
+}
+
+/// Mask the configured headers in every check of `results`.
+pub fn redact_scan_results(results: &mut ScanResults) {
+    results.checks.iter_mut().for_each(redact_check);
+}
+
+/// Fill the [`REDACTED`] header values of a saved `payload` back in from
+/// `headers` (`Name: value`, e.g. the `-H` flags of a replay). Returns the
+/// payload and the names of the headers that stay redacted.
+pub fn restore_redacted(payload: &str, headers: &[String]) -> (String, Vec<String>) {
+    let mut out = String::with_capacity(payload.len());
+    let mut missing = Vec::new();
+    for line in payload.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\r', '\n']);
+        let ending = &line[content.len()..];
+        let Some((name, value)) = content.split_once(':') else {
+            out.push_str(line);
+            continue;
+        };
+        if value.trim() != REDACTED {
+            out.push_str(line);
+            continue;
+        }
+        let replacement = headers.iter().find_map(|header| {
+            let (n, v) = header.split_once(':')?;
+            n.trim().eq_ignore_ascii_case(name.trim()).then(|| v.trim())
+        });
+        match replacement {
+            Some(v) => {
+                out.push_str(&format!("{}: {}{}", name, v, ending));
+            }
+            None => {
+                if !missing
+                    .iter()
+                    .any(|m: &String| m.eq_ignore_ascii_case(name.trim()))
+                {
+                    missing.push(name.trim().to_string());
+                }
+                out.push_str(line);
+            }
+        }
+    }
+    (out, missing)
+}
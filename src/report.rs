@@ -0,0 +1,547 @@
+use std::fmt::Write;
+
+use crate::cli::ReportFormat;
+use crate::fingerprint::format_proxy_chain;
+use crate::model::{CheckResult, Confidence, ScanResults};
+use crate::output::{build_defectdojo, build_sarif, build_zap_report};
+
+/// Elements of a ZAP XML `alertitem`, in ZAP's order.
+const ZAP_ALERT_FIELDS: [&str; 15] = [
+    "pluginid",
+    "alertRef",
+    "alert",
+    "name",
+    "riskcode",
+    "confidence",
+    "riskdesc",
+    "desc",
+    "instances",
+    "count",
+    "solution",
+    "otherinfo",
+    "reference",
+    "cweid",
+    "wascid",
+];
+
+/// Elements of a ZAP XML alert `instance`, in ZAP's order.
+const ZAP_INSTANCE_FIELDS: [&str; 6] =
+    ["uri", "method", "param", "attack", "evidence", "otherinfo"];
+
+/// Columns of the CSV report, one row per check.
+const CSV_COLUMNS: [&str; 15] = [
+    "target",
+    "address",
+    "vhost",
+    "method",
+    "check_type",
+    "vulnerable",
+    "confidence",
+    "payload_index",
+    "payload_name",
+    "normal_status",
+    "attack_status",
+    "normal_duration_ms",
+    "attack_duration_ms",
+    "detection_signals",
+    "error",
+];
+
+/// Render saved scan results in `format`. Works on the results alone, so a
+/// report can be regenerated at any time without touching the network.
+pub fn render_report(results: &[ScanResults], format: ReportFormat, version: &str) -> String {
+    match format {
+        ReportFormat::Html => render_html(results, version),
+        ReportFormat::Md => render_markdown(results, version),
+        ReportFormat::Sarif => {
+            serde_json::to_string_pretty(&build_sarif(results, version)).unwrap_or_default()
+        }
+        ReportFormat::Csv => render_csv(results),
+        ReportFormat::Defectdojo => {
+            serde_json::to_string_pretty(&build_defectdojo(results)).unwrap_or_default()
+        }
+        ReportFormat::ZapJson => {
+            serde_json::to_string_pretty(&build_zap_report(results, version)).unwrap_or_default()
+        }
+        ReportFormat::ZapXml => render_zap_xml(results, version),
+    }
+}
+
+fn confidence_name(confidence: &Option<Confidence>) -> &'static str {
+    match confidence {
+        Some(Confidence::High) => "high",
+        Some(Confidence::Medium) => "medium",
+        Some(Confidence::Low) => "low",
+        None => "",
+    }
+}
+
+fn vulnerable_count(results: &[ScanResults]) -> usize {
+    results
+        .iter()
+        .flat_map(|r| r.checks.iter())
+        .filter(|c| c.vulnerable)
+        .count()
+}
+
+/// Target heading detail: the pinned address, virtual host, detected proxy,
+/// proxy chain, server software, certificate and why the target was skipped
+/// or failed.
+fn target_details(scan: &ScanResults) -> Vec<(&'static str, String)> {
+    let mut details = Vec::new();
+    if let Some(ref address) = scan.address {
+        details.push(("Address", address.clone()));
+    }
+    if let Some(ref vhost) = scan.vhost {
+        details.push(("Virtual host", vhost.clone()));
+    }
+    details.push(("Method", scan.method.clone()));
+    details.push(("Scanned", scan.timestamp.clone()));
+    if let Some(ref fingerprint) = scan.fingerprint {
+        details.push(("Detected proxy", fingerprint.detected_proxy.clone()));
+        if let Some(ref alpn) = fingerprint.alpn {
+            let hint = if fingerprint.h2_downgrade_likely {
+                " (h2-downgrade likely)"
+            } else {
+                ""
+            };
+            details.push(("ALPN", format!("{}{}", alpn, hint)));
+        }
+    }
+    if !scan.proxy_chain.is_empty() {
+        details.push(("Proxy chain", format_proxy_chain(&scan.proxy_chain)));
+    }
+    if let Some(ref info) = scan.target_info {
+        let mut software: Vec<&str> = Vec::new();
+        for value in info
+            .server
+            .iter()
+            .chain(&info.powered_by)
+            .chain(&info.error_page_versions)
+        {
+            if !software.contains(&value.as_str()) {
+                software.push(value);
+            }
+        }
+        if !software.is_empty() {
+            details.push(("Server software", software.join(", ")));
+        }
+        if let Some(ref cert) = info.certificate {
+            let mut detail = format!(
+                "{} (issuer {}, serial {}, valid {} to {})",
+                cert.subject, cert.issuer, cert.serial, cert.not_before, cert.not_after
+            );
+            if !cert.san.is_empty() {
+                detail.push_str(&format!(", SAN {}", cert.san.join(", ")));
+            }
+            details.push(("Certificate", detail));
+        }
+    }
+    if let Some(unreachable) = scan.unreachable {
+        details.push(("Unreachable", unreachable.to_string()));
+    }
+    if let Some(ref throttle) = scan.throttle {
+        details.push(("Throttled", throttle.to_string()));
+    }
+    if let Some(ref error) = scan.error {
+        details.push(("Error", error.clone()));
+    }
+    details
+}
+
+fn check_verdict(check: &CheckResult) -> &'static str {
+    if check.vulnerable { "VULNERABLE" } else { "ok" }
+}
+
+/// Name of a finding's payload followed by `(#<index>)`, or `#<index>`
+/// alone when the name is unknown.
+fn payload_label(check: &CheckResult) -> String {
+    let index = check.payload_index.unwrap_or_default();
+    match check.payload_name {
+        Some(ref name) => format!("{} (#{})", name, index),
+        None => format!("#{}", index),
+    }
+}
+
+/// Markdown report: a summary, then one section per target with a table of
+/// its checks and the raw request of every finding.
+pub fn render_markdown(results: &[ScanResults], version: &str) -> String {
+    // `|` would end a table cell early.
+    let cell = |s: &str| s.replace('|', "\\|");
+    let mut out = String::new();
+    let _ = writeln!(out, "# smugglex report\n");
+    let _ = writeln!(
+        out,
+        "Generated by smugglex {}: {} target(s), {} vulnerable check(s).\n",
+        version,
+        results.len(),
+        vulnerable_count(results)
+    );
+    for scan in results {
+        let _ = writeln!(out, "## {}\n", scan.target);
+        for (label, value) in target_details(scan) {
+            let _ = writeln!(out, "- **{}:** {}", label, value);
+        }
+        if scan.checks.is_empty() {
+            out.push('\n');
+            continue;
+        }
+        let _ = writeln!(
+            out,
+            "\n| Check | Result | Confidence | Normal | Attack | Signals |"
+        );
+        let _ = writeln!(out, "|---|---|---|---|---|---|");
+        for check in &scan.checks {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} | {} | {} |",
+                cell(&check.check_type),
+                check_verdict(check),
+                confidence_name(&check.confidence),
+                cell(&check.normal_status),
+                cell(check.attack_status.as_deref().unwrap_or("")),
+                cell(&check.detection_signals.join(", "))
+            );
+        }
+        out.push('\n');
+        for check in scan.checks.iter().filter(|c| c.vulnerable) {
+            let Some(ref payload) = check.payload else {
+                continue;
+            };
+            let _ = writeln!(
+                out,
+                "### {} payload {}\n",
+                check.check_type,
+                payload_label(check)
+            );
+            if let Some(ref technique) = check.technique {
+                let _ = writeln!(
+                    out,
+                    "Technique `{}`: {} ([reference]({}))\n",
+                    technique.id, technique.description, technique.reference
+                );
+            }
+            let _ = writeln!(out, "```http\n{}\n```\n", payload.replace("\r\n", "\n"));
+        }
+    }
+    out
+}
+
+pub(crate) fn html_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Stand-alone HTML report with the same content as [`render_markdown`].
+pub fn render_html(results: &[ScanResults], version: &str) -> String {
+    let mut out = String::new();
+    out.push_str(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>smugglex report</title>\n<style>\n\
+         body { font-family: sans-serif; margin: 2rem; }\n\
+         table { border-collapse: collapse; }\n\
+         th, td { border: 1px solid #ccc; padding: 0.3rem 0.6rem; text-align: left; }\n\
+         .vulnerable { color: #b00020; font-weight: bold; }\n\
+         pre { background: #f4f4f4; padding: 0.8rem; overflow-x: auto; }\n\
+         </style>\n</head>\n<body>\n<h1>smugglex report</h1>\n",
+    );
+    let _ = writeln!(
+        out,
+        "<p>Generated by smugglex {}: {} target(s), {} vulnerable check(s).</p>",
+        html_escape(version),
+        results.len(),
+        vulnerable_count(results)
+    );
+    for scan in results {
+        let _ = writeln!(out, "<h2>{}</h2>\n<ul>", html_escape(&scan.target));
+        for (label, value) in target_details(scan) {
+            let _ = writeln!(
+                out,
+                "<li><strong>{}:</strong> {}</li>",
+                label,
+                html_escape(&value)
+            );
+        }
+        out.push_str("</ul>\n");
+        if scan.checks.is_empty() {
+            continue;
+        }
+        out.push_str(
+            "<table>\n<tr><th>Check</th><th>Result</th><th>Confidence</th>\
+             <th>Normal</th><th>Attack</th><th>Signals</th></tr>\n",
+        );
+        for check in &scan.checks {
+            let class = if check.vulnerable {
+                " class=\"vulnerable\""
+            } else {
+                ""
+            };
+            let _ = writeln!(
+                out,
+                "<tr><td>{}</td><td{}>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                html_escape(&check.check_type),
+                class,
+                check_verdict(check),
+                confidence_name(&check.confidence),
+                html_escape(&check.normal_status),
+                html_escape(check.attack_status.as_deref().unwrap_or("")),
+                html_escape(&check.detection_signals.join(", "))
+            );
+        }
+        out.push_str("</table>\n");
+        for check in scan.checks.iter().filter(|c| c.vulnerable) {
+            let Some(ref payload) = check.payload else {
+                continue;
+            };
+            let _ = writeln!(
+                out,
+                "<h3>{} payload {}</h3>",
+                html_escape(&check.check_type),
+                html_escape(&payload_label(check)),
+            );
+            if let Some(ref technique) = check.technique {
+                let _ = writeln!(
+                    out,
+                    "<p>Technique <code>{}</code>: {} (<a href=\"{}\">reference</a>)</p>",
+                    html_escape(&technique.id),
+                    html_escape(&technique.description),
+                    html_escape(&technique.reference)
+                );
+            }
+            let _ = writeln!(
+                out,
+                "<pre>{}</pre>",
+                html_escape(&payload.replace("\r\n", "\n"))
+            );
+        }
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// Quote a CSV field when it holds a separator, quote or line break.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// CSV report with one row per check (see [`CSV_COLUMNS`]); a target that
+/// failed before any check ran gets a single row carrying its error.
+pub fn render_csv(results: &[ScanResults]) -> String {
+    let mut out = CSV_COLUMNS.join(",");
+    out.push('\n');
+    for scan in results {
+        let address = scan.address.as_deref().unwrap_or("");
+        let vhost = scan.vhost.as_deref().unwrap_or("");
+        let error = scan.error.as_deref().unwrap_or("");
+        if scan.checks.is_empty() {
+            let mut row = vec![String::new(); CSV_COLUMNS.len()];
+            row[0] = csv_field(&scan.target);
+            row[1] = csv_field(address);
+            row[2] = csv_field(vhost);
+            row[3] = csv_field(&scan.method);
+            row[14] = csv_field(error);
+            out.push_str(&row.join(","));
+            out.push('\n');
+            continue;
+        }
+        for check in &scan.checks {
+            let row = [
+                csv_field(&scan.target),
+                csv_field(address),
+                csv_field(vhost),
+                csv_field(&scan.method),
+                csv_field(&check.check_type),
+                check.vulnerable.to_string(),
+                confidence_name(&check.confidence).to_string(),
+                check.payload_index.map_or(String::new(), |i| i.to_string()),
+                csv_field(check.payload_name.as_deref().unwrap_or("")),
+                csv_field(&check.normal_status),
+                csv_field(check.attack_status.as_deref().unwrap_or("")),
+                check.normal_duration_ms.to_string(),
+                check
+                    .attack_duration_ms
+                    .map_or(String::new(), |ms| ms.to_string()),
+                csv_field(&check.detection_signals.join(";")),
+                csv_field(error),
+            ];
+            out.push_str(&row.join(","));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Escape `s` for XML text and attributes, dropping the control characters
+/// XML 1.0 does not allow at all.
+fn xml_escape(s: &str) -> String {
+    let allowed: String = s
+        .chars()
+        .filter(|&c| c >= ' ' || matches!(c, '\t' | '\n' | '\r'))
+        .collect();
+    html_escape(&allowed)
+}
+
+/// JUnit XML report for CI test views: one test suite per target and one
+/// test case per check. A vulnerable check is a failure; a target whose scan
+/// failed gets a `scan` test case holding the error.
+pub fn render_junit(results: &[ScanResults], version: &str) -> String {
+    let mut suites = String::new();
+    let (mut total_tests, mut total_failures, mut total_errors) = (0, 0, 0);
+    for scan in results {
+        let target = xml_escape(&scan.target);
+        let mut cases = String::new();
+        let mut failures = 0;
+        for check in &scan.checks {
+            let _ = write!(
+                cases,
+                "    <testcase classname=\"{}\" name=\"{}\"",
+                target,
+                xml_escape(&check.check_type)
+            );
+            if !check.vulnerable {
+                cases.push_str("/>\n");
+                continue;
+            }
+            failures += 1;
+            let mut message = format!("HTTP request smuggling via {}", payload_label(check));
+            if check.confidence.is_some() {
+                let _ = write!(
+                    message,
+                    ", confidence {}",
+                    confidence_name(&check.confidence)
+                );
+            }
+            let mut details = String::new();
+            if let Some(ref technique) = check.technique {
+                let _ = writeln!(
+                    details,
+                    "Technique: {} ({})",
+                    technique.id, technique.description
+                );
+            }
+            if let Some(ref status) = check.attack_status {
+                let _ = writeln!(
+                    details,
+                    "Status: {} (baseline {})",
+                    status, check.normal_status
+                );
+            }
+            if !check.detection_signals.is_empty() {
+                let _ = writeln!(details, "Signals: {}", check.detection_signals.join(", "));
+            }
+            if let Some(ref evidence) = check.evidence {
+                let _ = writeln!(details, "Evidence: {}", evidence);
+            }
+            if let Some(ref payload) = check.payload {
+                let _ = write!(details, "\n{}", payload);
+            }
+            let _ = write!(
+                cases,
+                ">\n      <failure message=\"{}\" type=\"{}\">{}</failure>\n    </testcase>\n",
+                xml_escape(&message),
+                xml_escape(&check.check_type),
+                xml_escape(&details)
+            );
+        }
+        let mut tests = scan.checks.len();
+        let mut errors = 0;
+        if let Some(ref error) = scan.error {
+            tests += 1;
+            errors += 1;
+            let _ = writeln!(
+                cases,
+                "    <testcase classname=\"{}\" name=\"scan\">\n      <error message=\"{}\"/>\n    </testcase>",
+                target,
+                xml_escape(error)
+            );
+        }
+        let time = scan
+            .duration_ms
+            .map(|ms| format!(" time=\"{:.3}\"", ms as f64 / 1000.0))
+            .unwrap_or_default();
+        let _ = write!(
+            suites,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" timestamp=\"{}\"{}>\n{}  </testsuite>\n",
+            target,
+            tests,
+            failures,
+            errors,
+            xml_escape(&scan.timestamp),
+            time,
+            cases
+        );
+        total_tests += tests;
+        total_failures += failures;
+        total_errors += errors;
+    }
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <testsuites name=\"smugglex {}\" tests=\"{}\" failures=\"{}\" errors=\"{}\">\n{}</testsuites>\n",
+        xml_escape(version),
+        total_tests,
+        total_failures,
+        total_errors,
+        suites
+    )
+}
+
+/// ZAP traditional XML report: the document [`build_zap_report`] builds,
+/// written as `OWASPZAPReport` with `site` attributes and `alertitem`
+/// elements.
+pub fn render_zap_xml(results: &[ScanResults], version: &str) -> String {
+    let report = build_zap_report(results, version);
+    let text = |value: &serde_json::Value| xml_escape(value.as_str().unwrap_or_default());
+    let mut out = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <OWASPZAPReport programName=\"{}\" version=\"{}\" generated=\"{}\">\n",
+        text(&report["@programName"]),
+        text(&report["@version"]),
+        text(&report["@generated"])
+    );
+    for site in report["site"].as_array().into_iter().flatten() {
+        let _ = writeln!(
+            out,
+            "<site name=\"{}\" host=\"{}\" port=\"{}\" ssl=\"{}\">\n<alerts>",
+            text(&site["@name"]),
+            text(&site["@host"]),
+            text(&site["@port"]),
+            text(&site["@ssl"])
+        );
+        for alert in site["alerts"].as_array().into_iter().flatten() {
+            out.push_str("<alertitem>\n");
+            for field in ZAP_ALERT_FIELDS {
+                if field != "instances" {
+                    let _ = writeln!(out, "<{0}>{1}</{0}>", field, text(&alert[field]));
+                    continue;
+                }
+                out.push_str("<instances>\n");
+                for instance in alert[field].as_array().into_iter().flatten() {
+                    out.push_str("<instance>\n");
+                    for field in ZAP_INSTANCE_FIELDS {
+                        let _ = writeln!(out, "<{0}>{1}</{0}>", field, text(&instance[field]));
+                    }
+                    out.push_str("</instance>\n");
+                }
+                out.push_str("</instances>\n");
+            }
+            out.push_str("</alertitem>\n");
+        }
+        out.push_str("</alerts>\n</site>\n");
+    }
+    out.push_str("</OWASPZAPReport>\n");
+    out
+}
@@ -0,0 +1,158 @@
+//! `smugglex reproduce`: turn a saved finding into a shell one-liner that
+//! sends its winning payload byte for byte, for handing to the people who
+//! have to fix it.
+
+use url::Url;
+
+use crate::cli::ReplayTool;
+use crate::error::{Result, SmugglexError};
+use crate::model::Reproduction;
+use crate::redact::restore_redacted;
+use crate::retest::SavedFinding;
+
+/// Identifier of `finding` for `--id`: `check/payload-name`, or
+/// `check/#index` when the payload has no name.
+pub fn finding_id(finding: &SavedFinding) -> String {
+    match (&finding.check.payload_name, finding.check.payload_index) {
+        (Some(name), _) => format!("{}/{}", finding.check.check_type, name),
+        (None, Some(index)) => format!("{}/#{}", finding.check.check_type, index),
+        (None, None) => finding.check.check_type.clone(),
+    }
+}
+
+/// Index in `findings` of the finding `id` names: its 1-based number among
+/// the findings of the results file, or its [`finding_id`]. An id several
+/// findings share (the same payload won on several targets) is rejected,
+/// listing their numbers.
+pub fn select_finding(findings: &[SavedFinding], id: &str) -> Result<usize> {
+    let id = id.trim();
+    if let Ok(number) = id.parse::<usize>() {
+        return number
+            .checked_sub(1)
+            .filter(|&i| i < findings.len())
+            .ok_or_else(|| {
+                SmugglexError::Config(format!(
+                    "no finding #{} ({} finding(s) saved)",
+                    id,
+                    findings.len()
+                ))
+            });
+    }
+    let matches: Vec<usize> = findings
+        .iter()
+        .enumerate()
+        .filter(|(_, f)| finding_id(f) == id)
+        .map(|(i, _)| i)
+        .collect();
+    match matches[..] {
+        [i] => Ok(i),
+        [] => Err(SmugglexError::Config(format!(
+            "no finding '{}' (run without --id to list them)",
+            id
+        ))),
+        _ => Err(SmugglexError::Config(format!(
+            "'{}' matches findings {}; pass the number instead",
+            id,
+            matches
+                .iter()
+                .map(|i| format!("#{}", i + 1))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))),
+    }
+}
+
+/// `bytes` as a single-quoted `printf` format that prints exactly those
+/// bytes: `%` and `\` are doubled, CR, LF and tab use their escapes, and any
+/// other control or non-ASCII byte is written in octal.
+pub fn printf_literal(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() + 2);
+    out.push('\'');
+    for &b in bytes {
+        match b {
+            b'\'' => out.push_str("'\\''"),
+            b'\\' => out.push_str("\\\\"),
+            b'%' => out.push_str("%%"),
+            b'\r' => out.push_str("\\r"),
+            b'\n' => out.push_str("\\n"),
+            b'\t' => out.push_str("\\t"),
+            0x20..=0x7E => out.push(b as char),
+            _ => out.push_str(&format!("\\{:03o}", b)),
+        }
+    }
+    out.push('\'');
+    out
+}
+
+/// Quote `s` for a POSIX shell when it holds anything but safe characters.
+fn shell_word(s: &str) -> String {
+    if !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_.:/[]".contains(c))
+    {
+        s.to_string()
+    } else {
+        format!("'{}'", s.replace('\'', "'\\''"))
+    }
+}
+
+/// Build the one-liner replaying `finding`. The saved payload's redacted
+/// header values are filled back in from `headers` (the `-H` flags); the
+/// ones still missing are listed in [`Reproduction::redacted`]. The command
+/// connects to the address the scan was pinned to, when there was one,
+/// while still sending the target's name as SNI.
+pub fn build_reproduction(
+    finding: &SavedFinding,
+    tool: ReplayTool,
+    headers: &[String],
+) -> Result<Reproduction> {
+    let saved = finding.check.payload.as_deref().ok_or_else(|| {
+        SmugglexError::Config(format!(
+            "{} findings keep no raw payload; use `smugglex retest` instead",
+            finding.check.check_type
+        ))
+    })?;
+    let url = Url::parse(&finding.target)?;
+    let host = url
+        .host_str()
+        .ok_or_else(|| SmugglexError::Config("Invalid host in URL".to_string()))?;
+    let port = url
+        .port_or_known_default()
+        .ok_or_else(|| SmugglexError::Config("Invalid port in URL".to_string()))?;
+    let use_tls = url.scheme() == "https";
+    let connect_to = finding.address.as_deref().unwrap_or(host);
+
+    let (payload, redacted) = restore_redacted(saved, headers);
+    let client = match (tool, use_tls) {
+        (ReplayTool::Openssl, true) => format!(
+            "openssl s_client -quiet -connect {}:{} -servername {}",
+            shell_word(&bracket_ipv6(connect_to)),
+            port,
+            shell_word(host)
+        ),
+        (_, true) if connect_to != host => format!(
+            "ncat --ssl --ssl-servername {} {} {}",
+            shell_word(host),
+            shell_word(connect_to),
+            port
+        ),
+        (_, true) => format!("ncat --ssl {} {}", shell_word(host), port),
+        (_, false) => format!("ncat {} {}", shell_word(connect_to), port),
+    };
+    Ok(Reproduction {
+        id: finding_id(finding),
+        target: finding.target.clone(),
+        check_type: finding.check.check_type.clone(),
+        command: format!("printf {} | {}", printf_literal(payload.as_bytes()), client),
+        redacted,
+    })
+}
+
+/// `openssl -connect` wants IPv6 addresses in brackets.
+fn bracket_ipv6(host: &str) -> String {
+    if host.contains(':') && !host.starts_with('[') {
+        format!("[{}]", host)
+    } else {
+        host.to_string()
+    }
+}
@@ -0,0 +1,201 @@
+use std::net::IpAddr;
+use std::path::Path;
+
+use indicatif::ProgressBar;
+use url::Url;
+
+use crate::early_data::{self, run_early_data_check};
+use crate::error::{Result, SmugglexError};
+use crate::http::with_pinned_address;
+use crate::http2::run_h2_downgrade_check;
+use crate::model::{CheckResult, RetestReport, RetestResult, ScanResults};
+use crate::output::load_scan_results;
+use crate::payloads::PayloadIter;
+use crate::redact::{redact_check, restore_redacted};
+use crate::scanner::detection::DetectionMethod;
+use crate::scanner::{CheckParams, run_checks_for_type};
+use crate::utils::{LogLevel, log};
+
+/// A vulnerable check from saved results, with the target it was found on.
+#[derive(Debug, Clone)]
+pub struct SavedFinding {
+    pub target: String,
+    pub address: Option<String>,
+    pub check: CheckResult,
+}
+
+/// Scan settings the replay runs with (taken from the flags given before
+/// `retest`).
+pub struct RetestSettings<'a> {
+    pub timeout: u64,
+    pub verbose: bool,
+    pub delay: u64,
+    pub baseline_count: usize,
+    pub warmup: usize,
+    pub storm_cooldown: u64,
+    pub detect: &'a [DetectionMethod],
+    pub oob_host: Option<&'a str>,
+    /// `-H` headers, which fill in the values `--redact` masked in the saved
+    /// payload
+    pub headers: &'a [String],
+}
+
+/// Vulnerable checks of `scans`, in order.
+pub fn saved_findings(scans: Vec<ScanResults>) -> Vec<SavedFinding> {
+    scans
+        .into_iter()
+        .flat_map(|scan| {
+            let (target, address) = (scan.target, scan.address);
+            scan.checks
+                .into_iter()
+                .filter(|check| check.vulnerable)
+                .map(move |check| SavedFinding {
+                    target: target.clone(),
+                    address: address.clone(),
+                    check,
+                })
+        })
+        .collect()
+}
+
+/// Load the findings of a results file written with `-o` (see
+/// [`load_scan_results`]).
+pub fn load_saved_findings(path: &Path) -> Result<Vec<SavedFinding>> {
+    Ok(saved_findings(load_scan_results(path)?))
+}
+
+/// Replay the winning payload of `finding` as a one-payload check, with a
+/// fresh baseline and the usual confirmation retries. `h2-downgrade` findings
+/// keep no replayable payload and `early-data` findings need a fresh session
+/// ticket, so those checks are run again in full.
+async fn replay(finding: &SavedFinding, settings: &RetestSettings<'_>) -> Result<CheckResult> {
+    let url = Url::parse(&finding.target)?;
+    let host = url
+        .host_str()
+        .ok_or_else(|| SmugglexError::Config("Invalid host in URL".to_string()))?;
+    let port = url
+        .port_or_known_default()
+        .ok_or_else(|| SmugglexError::Config("Invalid port in URL".to_string()))?;
+    let path = match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    };
+    let use_tls = url.scheme() == "https";
+    let address = finding
+        .address
+        .as_deref()
+        .map(str::parse::<IpAddr>)
+        .transpose()
+        .map_err(|e| SmugglexError::Config(format!("invalid saved address: {}", e)))?;
+
+    let check_name = finding.check.check_type.as_str();
+    let run = async {
+        if check_name == "h2-downgrade" {
+            return Ok(run_h2_downgrade_check(
+                host,
+                port,
+                host,
+                &path,
+                settings.timeout,
+                settings.verbose,
+                None,
+            )
+            .await);
+        }
+        if check_name == early_data::CHECK_NAME {
+            return Ok(run_early_data_check(
+                host,
+                port,
+                host,
+                &path,
+                settings.timeout,
+                settings.verbose,
+            )
+            .await);
+        }
+        let saved = finding
+            .check
+            .payload
+            .as_deref()
+            .ok_or_else(|| SmugglexError::Config("finding has no saved payload".to_string()))?;
+        let (payload, missing) = restore_redacted(saved, settings.headers);
+        if !missing.is_empty() {
+            log(
+                LogLevel::Warning,
+                &format!(
+                    "{} payload on {} replays redacted {} (pass -H to send the real values)",
+                    check_name,
+                    finding.target,
+                    missing.join(", ")
+                ),
+            );
+        }
+        let pb = ProgressBar::hidden();
+        run_checks_for_type(CheckParams {
+            pb: &pb,
+            check_name,
+            host,
+            port,
+            path: &path,
+            attack_requests: PayloadIter::from(vec![payload]),
+            timeout: settings.timeout,
+            verbose: settings.verbose,
+            use_tls,
+            export: None,
+            archive: None,
+            current_check: 1,
+            total_checks: 1,
+            delay: settings.delay,
+            baseline_count: settings.baseline_count,
+            warmup: settings.warmup,
+            storm_cooldown: settings.storm_cooldown,
+            detect: settings.detect,
+            oob_host: settings.oob_host,
+            corpus: None,
+            fuzz_feedback: None,
+        })
+        .await
+    };
+    match address {
+        Some(addr) => with_pinned_address(addr, run).await,
+        None => run.await,
+    }
+}
+
+/// Replay `finding` and report whether it still reproduces.
+pub async fn retest_finding(finding: &SavedFinding, settings: &RetestSettings<'_>) -> RetestResult {
+    let mut result = RetestResult {
+        target: finding.target.clone(),
+        address: finding.address.clone(),
+        check_type: finding.check.check_type.clone(),
+        payload_index: finding.check.payload_index,
+        payload_name: finding.check.payload_name.clone(),
+        original_confidence: finding.check.confidence.clone(),
+        reproduced: false,
+        check: None,
+        error: None,
+    };
+    match replay(finding, settings).await {
+        Ok(mut check) => {
+            redact_check(&mut check);
+            result.reproduced = check.vulnerable;
+            result.check = Some(check);
+        }
+        Err(e) => result.error = Some(e.to_string()),
+    }
+    result
+}
+
+/// Build the retest report and its counts from the replayed findings.
+pub fn build_retest_report(results: Vec<RetestResult>, version: Option<&str>) -> RetestReport {
+    let reproduced = results.iter().filter(|r| r.reproduced).count();
+    let errors = results.iter().filter(|r| r.error.is_some()).count();
+    RetestReport {
+        smugglex_version: version.map(|s| s.to_string()),
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        fixed: results.len() - reproduced - errors,
+        reproduced,
+        errors,
+        results,
+    }
+}
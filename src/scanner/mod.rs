@@ -1,12 +1,33 @@
-use crate::error::{Result, SmugglexError};
-use crate::http::send_request;
-use crate::model::{CheckResult, Confidence};
-use crate::utils::{export_payload, parse_status_code};
+use crate::archive::ArchiveRecorder;
+use crate::cli::ExportFormat;
+use crate::corpus::FuzzCorpus;
+use crate::error::{Result, SmugglexError, TimeoutPhase};
+use crate::http::{
+    HttpResponse, LengthMismatch, RequestTiming, is_head_request, pipeline_requests, send_request,
+    send_request_lingering, send_request_timed,
+};
+use crate::model::{
+    CheckResult, Confidence, CrossCheck, CrossCheckVerdict, ExportedPayload, FuzzAnomaly,
+    PhaseTimings, RequestErrorKind, ResponseSummary,
+};
+use crate::mutator::{FuzzFeedback, payload_diff};
+use crate::output::log_response_diff;
+use crate::payloads::{Payload, PayloadIter, cl_cl_hop_signals};
+use crate::utils::{
+    PayloadExport, export_payload, export_payload_raw, export_technique, parse_status_code,
+    response_head,
+};
 use chrono::Utc;
 use colored::*;
 use indicatif::ProgressBar;
+use std::collections::{BTreeMap, HashSet};
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::time::Duration;
 
+pub mod detection;
+
+use detection::{DetectionMethod, Evidence, ProbeContext, build_strategies};
+
 /// Multiplier applied to baseline timing to determine anomaly threshold
 pub const TIMING_MULTIPLIER: u128 = 3;
 /// Minimum response delay in milliseconds to consider as suspicious
@@ -51,6 +72,22 @@ pub const FOLLOWUP_PROBE_COUNT: usize = 3;
 /// that the responses are not smuggling-induced. Recorded as a `diagnostics`
 /// note on the CheckResult.
 pub const CONSECUTIVE_FP_REJECTIONS_LIMIT: usize = 3;
+/// This many consecutive payloads that fail outright or draw a 5xx the
+/// control also gets trip the circuit breaker: the target is left alone for
+/// the cool-down and re-baselined, and the scan of the target is aborted if
+/// it has not recovered.
+pub const ERROR_STORM_THRESHOLD: usize = 5;
+/// Upper bound on re-sends spent minimizing one anomalous payload before it
+/// is saved to the `--fuzz-corpus` directory.
+pub const CORPUS_MINIMIZE_ATTEMPTS: usize = 16;
+/// Most fuzz anomalies kept per check for the triage report.
+pub const MAX_FUZZ_ANOMALIES: usize = 20;
+/// Body bytes of the baseline and attack responses shown in the verbose
+/// response diff.
+pub const RESPONSE_DIFF_BODY_BYTES: usize = 1024;
+/// How long the attack and control connections are kept reading after their
+/// response is complete, to catch bytes the server sends unasked.
+pub const UNSOLICITED_WINDOW_MS: u64 = 250;
 
 /// Parameters for running vulnerability checks
 pub struct CheckParams<'a> {
@@ -64,16 +101,18 @@ pub struct CheckParams<'a> {
     pub port: u16,
     /// Request path on the target
     pub path: &'a str,
-    /// List of raw HTTP attack payloads to test
-    pub attack_requests: Vec<String>,
+    /// Raw HTTP attack payloads to test, generated lazily
+    pub attack_requests: PayloadIter,
     /// Socket timeout in seconds
     pub timeout: u64,
     /// Whether to print verbose debug output
     pub verbose: bool,
     /// Whether to use TLS for connections
     pub use_tls: bool,
-    /// Directory to export successful payloads to
-    pub export_dir: Option<&'a str>,
+    /// Where to export successful payloads to
+    pub export: Option<PayloadExport<'a>>,
+    /// Where to record every payload sent, with its outcome (`--export-all`)
+    pub archive: Option<ArchiveRecorder<'a>>,
     /// Index of the current check (for progress display)
     pub current_check: usize,
     /// Total number of checks to run (for progress display)
@@ -82,12 +121,28 @@ pub struct CheckParams<'a> {
     pub delay: u64,
     /// Number of baseline requests for timing measurement (values < 1 are clamped to 1)
     pub baseline_count: usize,
+    /// Throwaway requests sent before the baseline is measured
+    pub warmup: usize,
+    /// Seconds to leave the target alone after an error storm before
+    /// re-baselining it
+    pub storm_cooldown: u64,
+    /// Detection strategies contributing to the verdict
+    pub detect: &'a [DetectionMethod],
+    /// Out-of-band interaction host used by the `oob` strategy
+    pub oob_host: Option<&'a str>,
+    /// Corpus that anomalous payloads are minimized into
+    pub corpus: Option<&'a FuzzCorpus>,
+    /// Response feedback for a feedback-driven fuzz payload stream
+    pub fuzz_feedback: Option<&'a FuzzFeedback>,
 }
 
 struct VulnerabilityInfo {
     status: String,
     status_code: Option<u16>,
+    /// Response time, connection setup excluded
     duration: Duration,
+    /// Time spent opening the connection
+    connect: Duration,
     /// Size of the response body in bytes (post-headers).
     /// Used to detect structural divergence between attack and control responses.
     body_length: usize,
@@ -96,6 +151,21 @@ struct VulnerabilityInfo {
     /// and control as an orthogonal divergence signal alongside body length.
     header_fingerprint: ResponseHeaderFingerprint,
     is_connection_timeout: bool,
+    /// The response is the one a follow-up request got after a HEAD payload,
+    /// answered for the HEAD body the back-end queued (see
+    /// `probe_head_body_queue`).
+    head_body_queued: bool,
+    /// Head of the response (see [`response_head`]), for the verbose diff
+    /// against the baseline; empty when no response arrived.
+    response_head: String,
+    /// The body was cut short of, or ran past, its `Content-Length`.
+    length_mismatch: Option<LengthMismatch>,
+    /// Bytes that followed the complete response on its connection: the
+    /// ones read along with it and those arriving within
+    /// [`UNSOLICITED_WINDOW_MS`]. Empty when none did.
+    unsolicited: Vec<u8>,
+    /// Where the time of the request went; `None` when it timed out
+    phases: Option<PhaseTimings>,
 }
 
 /// Compact fingerprint of response headers used for divergence comparison.
@@ -108,28 +178,20 @@ struct ResponseHeaderFingerprint {
 }
 
 impl ResponseHeaderFingerprint {
-    /// Parse the header section of an HTTP response into a comparison fingerprint.
-    fn from_response(response: &str) -> Self {
-        let head = match response.split_once("\r\n\r\n") {
-            Some((h, _)) => h,
-            None => response,
+    /// Fingerprint the headers of `response`; of repeated fields, the last
+    /// one counts.
+    fn from_response(response: &HttpResponse) -> Self {
+        let value = |name| {
+            response
+                .header_values(name)
+                .last()
+                .map(str::to_ascii_lowercase)
         };
-        let mut fp = ResponseHeaderFingerprint::default();
-        for line in head.lines() {
-            if let Some((name, value)) = line.split_once(':') {
-                let name = name.trim();
-                // Only allocate the lowercased value for the three headers we
-                // track, instead of lowercasing every header's name and value.
-                if name.eq_ignore_ascii_case("content-type") {
-                    fp.content_type = Some(value.trim().to_ascii_lowercase());
-                } else if name.eq_ignore_ascii_case("server") {
-                    fp.server = Some(value.trim().to_ascii_lowercase());
-                } else if name.eq_ignore_ascii_case("content-length") {
-                    fp.content_length = Some(value.trim().to_ascii_lowercase());
-                }
-            }
+        ResponseHeaderFingerprint {
+            content_type: value("content-type"),
+            server: value("server"),
+            content_length: value("content-length"),
         }
-        fp
     }
 
     /// Count of fields where attack and control disagree. A bare presence vs
@@ -149,13 +211,10 @@ impl ResponseHeaderFingerprint {
     }
 }
 
-/// Extract the response body length (everything after the headers terminator).
-/// Returns 0 if the response is malformed or has no body section.
-fn response_body_length(response: &str) -> usize {
-    response
-        .split_once("\r\n\r\n")
-        .map(|(_, body)| body.len())
-        .unwrap_or(0)
+/// Response body length: everything after the headers terminator, trailing
+/// bytes included. 0 if the response is malformed or has no body section.
+fn response_body_length(response: &HttpResponse) -> usize {
+    response.len_after_head()
 }
 
 struct BaselineMeasurement {
@@ -167,10 +226,18 @@ struct BaselineMeasurement {
     /// Maximum baseline duration. Used to derive a noise-aware timing threshold
     /// so that natural per-request variance does not trigger false positives.
     max_duration: Duration,
+    /// Median time spent opening the baseline connections, kept out of
+    /// `duration` and `max_duration`.
+    connect: Duration,
     /// Response body length from the last baseline probe. Used by follow-up
     /// probes to detect post-attack body divergence.
     body_length: usize,
+    /// Head of the last baseline response (see [`response_head`]).
+    response_head: String,
     observed_status_codes: Vec<Option<u16>>,
+    /// Some baseline body disagreed with its `Content-Length`, so a mismatch
+    /// in an attack response says nothing about desync.
+    length_mismatch: bool,
 }
 
 /// True when the majority of baseline responses are gateway-timeout codes (408/504).
@@ -187,6 +254,15 @@ fn baseline_majority_timeout(baseline_status_codes: &[Option<u16>]) -> bool {
     timeouts * 2 > baseline_status_codes.len()
 }
 
+/// True when the majority of baseline responses are 5xx.
+fn baseline_majority_server_error(baseline_status_codes: &[Option<u16>]) -> bool {
+    let server_errors = baseline_status_codes
+        .iter()
+        .filter(|c| c.is_some_and(|c| c >= 500))
+        .count();
+    server_errors * 2 > baseline_status_codes.len()
+}
+
 /// Median of a non-empty slice of durations. Mutates input by sorting.
 fn median_duration(durations: &mut [Duration]) -> Duration {
     debug_assert!(!durations.is_empty(), "median of empty slice");
@@ -208,8 +284,9 @@ fn payload_method(payload: &str) -> String {
 /// Send `count` shape-matched baseline probes that mirror the attack method but
 /// carry no smuggling artifacts (Content-Length: 0, empty body). Used to
 /// augment the GET baseline so timing thresholds account for backend's natural
-/// per-method latency overhead. Returns the durations observed; failures are
-/// silently dropped (the augmentation is best-effort).
+/// per-method latency overhead. Returns the response times observed
+/// (connection setup excluded); failures are silently dropped (the
+/// augmentation is best-effort).
 #[allow(clippy::too_many_arguments)]
 async fn method_matched_baseline_durations(
     host: &str,
@@ -227,17 +304,23 @@ async fn method_matched_baseline_durations(
     );
     let mut futures = Vec::with_capacity(count);
     for _ in 0..count {
-        futures.push(send_request(host, port, &probe, timeout, verbose, use_tls));
+        futures.push(send_request_timed(
+            host, port, &probe, timeout, verbose, use_tls,
+        ));
     }
     futures::future::join_all(futures)
         .await
         .into_iter()
-        .filter_map(|r| r.ok().map(|(_, d)| d))
+        .filter_map(|r| r.ok().map(|(_, timing)| timing.response))
         .collect()
 }
 
 /// Measure baseline by sending normal requests and computing median timing.
-/// Requests are sent concurrently for faster baseline establishment.
+/// Requests are sent concurrently for faster baseline establishment. Durations
+/// are response times: TCP/TLS setup is measured apart, so a slow handshake
+/// does not inflate the thresholds attacks are compared against. `warmup`
+/// requests are sent one by one first and their responses discarded.
+#[allow(clippy::too_many_arguments)]
 async fn measure_baseline(
     host: &str,
     port: u16,
@@ -246,6 +329,7 @@ async fn measure_baseline(
     verbose: bool,
     use_tls: bool,
     baseline_count: usize,
+    warmup: usize,
 ) -> Result<BaselineMeasurement> {
     // Clamp to a minimum of 1 to avoid empty-slice panic and meaningless thresholds.
     let count = baseline_count.max(1);
@@ -255,9 +339,26 @@ async fn measure_baseline(
         path, host
     );
 
+    // Cold caches, backends and connection pools answer the first requests
+    // far slower than the rest; their responses are discarded so they do
+    // not set the thresholds.
+    for i in 0..warmup {
+        if let Err(e) = send_request(host, port, &normal_request, timeout, verbose, use_tls).await
+            && verbose
+        {
+            println!(
+                "  {} warmup request {}/{} failed: {}",
+                "[!]".yellow(),
+                i + 1,
+                warmup,
+                e
+            );
+        }
+    }
+
     let mut futures = Vec::with_capacity(count);
     for _ in 0..count {
-        futures.push(send_request(
+        futures.push(send_request_timed(
             host,
             port,
             &normal_request,
@@ -279,21 +380,29 @@ async fn measure_baseline(
 /// viable check into a false negative. Only when *every* probe failed is an
 /// error returned, since there is then nothing to measure against. This mirrors
 /// the best-effort behavior of `method_matched_baseline_durations`.
-fn aggregate_baseline(results: Vec<Result<(String, Duration)>>) -> Result<BaselineMeasurement> {
+fn aggregate_baseline(
+    results: Vec<Result<(String, RequestTiming)>>,
+) -> Result<BaselineMeasurement> {
     let mut durations = Vec::with_capacity(results.len());
+    let mut connects = Vec::with_capacity(results.len());
     let mut observed_status_codes = Vec::with_capacity(results.len());
     let mut last_status = String::new();
     let mut last_body_length = 0usize;
+    let mut last_head = String::new();
+    let mut length_mismatch = false;
     let mut last_error: Option<SmugglexError> = None;
 
     for result in results {
         match result {
-            Ok((response, duration)) => {
-                let status_line = response.lines().next().unwrap_or("");
-                observed_status_codes.push(parse_status_code(status_line));
-                durations.push(duration);
-                last_status = status_line.to_string();
-                last_body_length = response_body_length(&response);
+            Ok((response, timing)) => {
+                let parsed = HttpResponse::parse(response.as_bytes());
+                observed_status_codes.push(parsed.status);
+                durations.push(timing.response);
+                connects.push(timing.connect);
+                last_body_length = response_body_length(&parsed);
+                length_mismatch |= parsed.length_mismatch().is_some();
+                last_status = parsed.status_line;
+                last_head = response_head(&response, RESPONSE_DIFF_BODY_BYTES);
             }
             Err(e) => last_error = Some(e),
         }
@@ -314,11 +423,15 @@ fn aggregate_baseline(results: Vec<Result<(String, Duration)>>) -> Result<Baseli
         status_code,
         duration: median,
         max_duration,
+        connect: median_duration(&mut connects),
         body_length: last_body_length,
+        response_head: last_head,
         observed_status_codes,
+        length_mismatch,
     })
 }
 
+#[derive(Clone, Copy)]
 struct PayloadCheckParams<'a> {
     host: &'a str,
     port: u16,
@@ -328,25 +441,34 @@ struct PayloadCheckParams<'a> {
     use_tls: bool,
     timing_threshold: u128,
     baseline_status_codes: &'a [Option<u16>],
+    /// The baseline bodies disagreed with their `Content-Length` too
+    baseline_length_mismatch: bool,
+    /// Receives the screening response when fuzzing with feedback
+    feedback: Option<&'a FuzzFeedback>,
 }
 
 async fn check_single_payload(
     params: &PayloadCheckParams<'_>,
 ) -> Result<Option<VulnerabilityInfo>> {
-    match send_request(
+    match send_request_lingering(
         params.host,
         params.port,
         params.attack_request,
         params.timeout,
         params.verbose,
         params.use_tls,
+        Duration::from_millis(UNSOLICITED_WINDOW_MS),
     )
     .await
     {
-        Ok((attack_response, attack_duration)) => {
-            let attack_status_line = attack_response.lines().next().unwrap_or("");
+        Ok((attack_response, timing, lingered)) => {
+            let attack_duration = timing.response;
+            if let Some(feedback) = params.feedback {
+                feedback.record(params.attack_request, Some(&attack_response));
+            }
+            let parsed = HttpResponse::parse(attack_response.as_bytes());
             let attack_millis = attack_duration.as_millis();
-            let status_code = parse_status_code(attack_status_line);
+            let status_code = parsed.status;
 
             // Only treat 408/504 as a smuggling signal if the baseline did NOT
             // produce such codes for the majority of probes.
@@ -355,29 +477,82 @@ async fn check_single_payload(
                 matches!(status_code, Some(408) | Some(504)) && !baseline_timeout_majority;
             let is_delayed =
                 attack_millis > params.timing_threshold && attack_millis > MIN_DELAY_MS;
-
-            if is_timeout_error || is_delayed {
+            // A body cut short of (or running past) its Content-Length, on a
+            // target whose baseline bodies are framed correctly. Responses to
+            // HEAD carry no body whatever they declare.
+            let length_mismatch = (!params.baseline_length_mismatch
+                && !is_head_request(params.attack_request.as_bytes()))
+            .then(|| parsed.length_mismatch())
+            .flatten();
+            // Anything past the response was not asked for: a second response
+            // or part of another request.
+            let mut unsolicited = parsed.trailing.clone();
+            unsolicited.extend_from_slice(&lingered);
+
+            if is_timeout_error
+                || is_delayed
+                || length_mismatch.is_some()
+                || !unsolicited.is_empty()
+            {
+                if let Some(feedback) = params.feedback {
+                    let observed = if !unsolicited.is_empty() {
+                        format!("{} unsolicited bytes after the response", unsolicited.len())
+                    } else if is_delayed {
+                        format!(
+                            "delayed {}ms (threshold {}ms)",
+                            attack_millis, params.timing_threshold
+                        )
+                    } else if let Some(mismatch) = length_mismatch.filter(|_| !is_timeout_error) {
+                        mismatch.to_string()
+                    } else {
+                        format!("status {}", parsed.status_line)
+                    };
+                    feedback.flag_anomaly(params.attack_request, observed);
+                }
                 Ok(Some(VulnerabilityInfo {
-                    status: attack_status_line.to_string(),
                     status_code,
                     duration: attack_duration,
-                    body_length: response_body_length(&attack_response),
-                    header_fingerprint: ResponseHeaderFingerprint::from_response(&attack_response),
+                    connect: timing.connect,
+                    body_length: response_body_length(&parsed),
+                    header_fingerprint: ResponseHeaderFingerprint::from_response(&parsed),
+                    status: parsed.status_line,
                     is_connection_timeout: false,
+                    head_body_queued: false,
+                    response_head: response_head(&attack_response, RESPONSE_DIFF_BODY_BYTES),
+                    length_mismatch,
+                    unsolicited,
+                    phases: Some(timing.phases),
                 }))
             } else {
                 Ok(None)
             }
         }
         Err(e) => {
-            if matches!(e, SmugglexError::Timeout(_)) {
+            if let Some(feedback) = params.feedback {
+                feedback.record(params.attack_request, None);
+            }
+            // A connection that never opened says nothing about the payload;
+            // the error is returned so the request can be retried.
+            if matches!(e, SmugglexError::Timeout { phase, .. } if phase != TimeoutPhase::Connect) {
+                if let Some(feedback) = params.feedback {
+                    feedback.flag_anomaly(
+                        params.attack_request,
+                        format!("connection timeout after {}s", params.timeout),
+                    );
+                }
                 Ok(Some(VulnerabilityInfo {
                     status: "Connection Timeout".to_string(),
                     status_code: None,
                     duration: Duration::from_secs(params.timeout),
+                    connect: Duration::ZERO,
                     body_length: 0,
                     header_fingerprint: ResponseHeaderFingerprint::default(),
                     is_connection_timeout: true,
+                    head_body_queued: false,
+                    response_head: String::new(),
+                    length_mismatch: None,
+                    unsolicited: Vec::new(),
+                    phases: None,
                 }))
             } else {
                 Err(e)
@@ -386,6 +561,46 @@ async fn check_single_payload(
     }
 }
 
+/// Greedily drop header lines (never the request line or `Host`) from an
+/// anomalous payload while it still reproduces the anomaly, so corpus entries
+/// keep only what triggers it. Bounded by [`CORPUS_MINIMIZE_ATTEMPTS`].
+async fn minimize_anomaly(params: &PayloadCheckParams<'_>, delay: u64) -> String {
+    let request = params.attack_request;
+    let Some((head, body)) = request.split_once("\r\n\r\n") else {
+        return request.to_string();
+    };
+    let mut lines: Vec<&str> = head.split("\r\n").collect();
+    let mut attempts = 0;
+    let mut i = 1;
+    while i < lines.len() && attempts < CORPUS_MINIMIZE_ATTEMPTS {
+        let is_host = lines[i]
+            .split_once(':')
+            .is_some_and(|(name, _)| name.trim().eq_ignore_ascii_case("host"));
+        if is_host {
+            i += 1;
+            continue;
+        }
+        let mut reduced = lines.clone();
+        reduced.remove(i);
+        let candidate = format!("{}\r\n\r\n{}", reduced.join("\r\n"), body);
+        attempts += 1;
+        if delay > 0 {
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+        }
+        let probe = PayloadCheckParams {
+            attack_request: &candidate,
+            feedback: None,
+            ..*params
+        };
+        if matches!(check_single_payload(&probe).await, Ok(Some(_))) {
+            lines = reduced;
+        } else {
+            i += 1;
+        }
+    }
+    format!("{}\r\n\r\n{}", lines.join("\r\n"), body)
+}
+
 /// Outcome of vulnerability confirmation across retries.
 struct ConfirmationResult {
     confirmed: bool,
@@ -403,12 +618,16 @@ struct ControlObservation {
     body_length: usize,
     header_fingerprint: ResponseHeaderFingerprint,
     is_connection_timeout: bool,
+    /// The body disagreed with its `Content-Length`
+    length_mismatch: bool,
+    /// Bytes followed the complete response
+    unsolicited: bool,
 }
 
 /// True when a payload carries smuggling-specific markers that the control
-/// comparison knows how to strip. Plain HTTP requests (no TE artifact) and
-/// Upgrade/HTTP-2-shaped payloads are excluded because stripping wouldn't
-/// produce a meaningful control.
+/// comparison knows how to strip: a TE artifact or conflicting Content-Length
+/// headers. Plain HTTP requests and Upgrade/HTTP-2-shaped payloads are
+/// excluded because stripping wouldn't produce a meaningful control.
 fn payload_eligible_for_control(payload: &str) -> bool {
     let head_end = payload.find("\r\n\r\n").unwrap_or(payload.len());
     let head_lower = payload[..head_end].to_ascii_lowercase();
@@ -420,11 +639,13 @@ fn payload_eligible_for_control(payload: &str) -> bool {
         return false;
     }
 
-    // Apply control only to payloads that actually carry TE-related artifacts.
+    // Apply control only to payloads that actually carry TE-related artifacts,
+    // or more than one Content-Length (CL.CL).
     head_lower.contains("transfer-encoding")
         || head_lower.contains("transfer_encoding")
         || head_lower.contains("transfer encoding")
         || head_lower.contains("nsfer-encoding")
+        || head_lower.matches("\r\ncontent-length").count() >= 2
 }
 
 /// Maximum bytes of synthetic body padding emitted by the control request.
@@ -450,7 +671,27 @@ fn build_control_request(payload: &str) -> String {
         Some(idx) => (&payload[..idx], &payload[idx + 4..]),
         None => (payload, ""),
     };
+    let kept = strip_framing_headers(head);
 
+    // Match the original body size (capped) with benign ASCII padding so the
+    // backend takes the same shape-conditional code paths it would for the
+    // attack, minus the smuggling tricks.
+    let body_len = original_body.len().min(CONTROL_BODY_MAX_BYTES);
+    let mut result = String::with_capacity(payload.len());
+    for line in kept {
+        result.push_str(line);
+        result.push_str("\r\n");
+    }
+    result.push_str(&format!("Content-Length: {}\r\n\r\n", body_len));
+    if body_len > 0 {
+        result.extend(std::iter::repeat_n('x', body_len));
+    }
+    result
+}
+
+/// The request line and headers of `head` without any Transfer-Encoding or
+/// Content-Length header, obfuscated variants included.
+fn strip_framing_headers(head: &str) -> Vec<&str> {
     let mut kept: Vec<&str> = Vec::new();
     for (i, line) in head.lines().enumerate() {
         if i == 0 {
@@ -467,21 +708,105 @@ fn build_control_request(payload: &str) -> String {
         }
         kept.push(line);
     }
+    kept
+}
 
-    // Match the original body size (capped) with benign ASCII padding so the
-    // backend takes the same shape-conditional code paths it would for the
-    // attack, minus the smuggling tricks.
-    let body_len = original_body.len().min(CONTROL_BODY_MAX_BYTES);
-    let mut result = String::with_capacity(payload.len());
-    for line in kept {
-        result.push_str(line);
-        result.push_str("\r\n");
+/// True when a payload's headers ask for chunked framing, so a well-formed
+/// chunked sibling is a meaningful control for it.
+fn payload_declares_chunked(payload: &str) -> bool {
+    let head_end = payload.find("\r\n\r\n").unwrap_or(payload.len());
+    payload[..head_end].to_ascii_lowercase().contains("chunked")
+}
+
+/// Build a benign chunked control for `payload`: the same request line and
+/// headers, a plain `Transfer-Encoding: chunked`, and a correctly terminated
+/// chunked body of the attack body's size (capped at
+/// `CONTROL_BODY_MAX_BYTES`) whose `Content-Length` counts exactly those
+/// bytes. Front-end and back-end frame it the same way whichever header they
+/// honour, so a target that is merely slow or erroring on chunked POSTs shows
+/// its anomaly on this request too, while a real desync does not.
+fn build_chunked_control_request(payload: &str) -> String {
+    let (head, original_body) = payload.split_once("\r\n\r\n").unwrap_or((payload, ""));
+    let size = original_body.len().min(CONTROL_BODY_MAX_BYTES);
+    let body = if size == 0 {
+        "0\r\n\r\n".to_string()
+    } else {
+        format!("{:x}\r\n{}\r\n0\r\n\r\n", size, "x".repeat(size))
+    };
+    with_chunked_body(head, &body, body.len())
+}
+
+/// `head` stripped of its framing headers, then sent with a plain
+/// `Transfer-Encoding: chunked`, a `Content-Length` of `content_length` and
+/// `body`.
+fn with_chunked_body(head: &str, body: &str, content_length: usize) -> String {
+    format!(
+        "{}\r\nTransfer-Encoding: chunked\r\nContent-Length: {}\r\n\r\n{}",
+        strip_framing_headers(head).join("\r\n"),
+        content_length,
+        body
+    )
+}
+
+/// The timing probe for the technique complementary to `check_name`'s, built
+/// on the winning `payload`'s request line and headers, with the check it
+/// tests for. After a CL.TE finding the TE.CL probe declares six bytes but
+/// chunk-terminates after five, so only a back-end reading Content-Length
+/// waits; after a TE.CL finding the CL.TE probe declares four bytes of an
+/// unterminated chunk, so only a back-end reading the chunks waits. `None`
+/// for other checks.
+fn build_cross_check_probe(check_name: &str, payload: &str) -> Option<(&'static str, String)> {
+    let head = payload
+        .split_once("\r\n\r\n")
+        .map_or(payload, |(head, _)| head);
+    match check_name {
+        "cl-te" => Some(("te-cl", with_chunked_body(head, "0\r\n\r\nX", 6))),
+        "te-cl" => Some(("cl-te", with_chunked_body(head, "1\r\nA\r\nX", 4))),
+        _ => None,
     }
-    result.push_str(&format!("Content-Length: {}\r\n\r\n", body_len));
-    if body_len > 0 {
-        result.extend(std::iter::repeat_n('x', body_len));
+}
+
+/// Send the complementary-technique `probe` and judge it against the claimed
+/// technique: a confirmed anomaly means the target desyncs under both
+/// framings, so the claimed class is doubtful.
+async fn cross_check(params: &PayloadCheckParams<'_>, technique: &str) -> CrossCheck {
+    let label = technique.replace('-', ".").to_ascii_uppercase();
+    let (verdict, observation) = match check_single_payload(params).await {
+        Ok(None) => (
+            CrossCheckVerdict::Consistent,
+            format!("{} probe answered without delay or error", label),
+        ),
+        Ok(Some(info)) => {
+            if confirm_vulnerability(params, &info).await.confirmed {
+                (
+                    CrossCheckVerdict::Inconsistent,
+                    format!(
+                        "{} probe also desynced ({}, {}ms)",
+                        label,
+                        info.status,
+                        info.duration.as_millis()
+                    ),
+                )
+            } else {
+                (
+                    CrossCheckVerdict::Consistent,
+                    format!(
+                        "{} probe anomaly ({}) did not reproduce",
+                        label, info.status
+                    ),
+                )
+            }
+        }
+        Err(e) => (
+            CrossCheckVerdict::Inconclusive,
+            format!("{} probe failed: {}", label, e),
+        ),
+    };
+    CrossCheck {
+        technique: technique.to_string(),
+        verdict,
+        observation,
     }
-    result
 }
 
 /// Send a single control request and observe its timing/status/body. Returns
@@ -490,32 +815,37 @@ async fn observe_control_once(
     params: &PayloadCheckParams<'_>,
     control_request: &str,
 ) -> Option<ControlObservation> {
-    match send_request(
+    match send_request_lingering(
         params.host,
         params.port,
         control_request,
         params.timeout,
         params.verbose,
         params.use_tls,
+        Duration::from_millis(UNSOLICITED_WINDOW_MS),
     )
     .await
     {
-        Ok((response, duration)) => {
-            let status_line = response.lines().next().unwrap_or("");
+        Ok((response, timing, lingered)) => {
+            let parsed = HttpResponse::parse(response.as_bytes());
             Some(ControlObservation {
-                duration,
-                status_code: parse_status_code(status_line),
-                body_length: response_body_length(&response),
-                header_fingerprint: ResponseHeaderFingerprint::from_response(&response),
+                duration: timing.response,
+                status_code: parsed.status,
+                body_length: response_body_length(&parsed),
+                header_fingerprint: ResponseHeaderFingerprint::from_response(&parsed),
                 is_connection_timeout: false,
+                length_mismatch: parsed.length_mismatch().is_some(),
+                unsolicited: !parsed.trailing.is_empty() || !lingered.is_empty(),
             })
         }
-        Err(SmugglexError::Timeout(_)) => Some(ControlObservation {
+        Err(SmugglexError::Timeout { .. }) => Some(ControlObservation {
             duration: Duration::from_secs(params.timeout),
             status_code: None,
             body_length: 0,
             header_fingerprint: ResponseHeaderFingerprint::default(),
             is_connection_timeout: true,
+            length_mismatch: false,
+            unsolicited: false,
         }),
         Err(_) => None,
     }
@@ -524,7 +854,8 @@ async fn observe_control_once(
 /// Send `CONTROL_SAMPLES` control requests and aggregate them conservatively:
 /// duration is the MAX (worst case favors FP rejection of borderline detections),
 /// status_code/body_length is taken from the slowest sample, and connection
-/// timeout is set if ANY sample timed out.
+/// timeout, length mismatch and unsolicited bytes are set if ANY sample showed
+/// them.
 ///
 /// Multiple samples protect against single-control flukes (a transient fast
 /// response that would otherwise let a real FP slip through) at the cost of one
@@ -549,6 +880,8 @@ async fn observe_control(
         .map(|(i, _)| i)
         .unwrap_or(0);
     let any_timeout = samples.iter().any(|s| s.is_connection_timeout);
+    let any_mismatch = samples.iter().any(|s| s.length_mismatch);
+    let any_unsolicited = samples.iter().any(|s| s.unsolicited);
     let worst = &samples[worst_idx];
     Some(ControlObservation {
         duration: worst.duration,
@@ -556,6 +889,8 @@ async fn observe_control(
         body_length: worst.body_length,
         header_fingerprint: worst.header_fingerprint.clone(),
         is_connection_timeout: any_timeout,
+        length_mismatch: any_mismatch,
+        unsolicited: any_unsolicited,
     })
 }
 
@@ -630,9 +965,9 @@ async fn observe_followup_divergence(
         match res {
             Ok((response, _)) => {
                 total += 1;
-                let status_line = response.lines().next().unwrap_or("");
-                let status_code = parse_status_code(status_line);
-                let body_len = response_body_length(&response);
+                let parsed = HttpResponse::parse(response.as_bytes());
+                let status_code = parsed.status;
+                let body_len = response_body_length(&parsed);
                 // Use the structural status check (excludes flake-prone 5xx) so a
                 // transient gateway error does not count as desync divergence,
                 // matching `count_structural_followup_divergence`.
@@ -645,7 +980,7 @@ async fn observe_followup_divergence(
             // Network-level errors on a fresh follow-up connection can themselves
             // be a desync signal (backend tearing down poisoned connections),
             // but they're also noisy. Count them but do not over-weight.
-            Err(SmugglexError::Timeout(_)) => {
+            Err(SmugglexError::Timeout { .. }) => {
                 total += 1;
                 diverging += 1;
             }
@@ -700,8 +1035,8 @@ async fn count_structural_followup_divergence(
         )
         .await
         {
-            let status_line = response.lines().next().unwrap_or("");
-            let status_code = parse_status_code(status_line);
+            let parsed = HttpResponse::parse(response.as_bytes());
+            let status_code = parsed.status;
             // Skip 5xx responses entirely — gateway/server errors are
             // flake-prone (overload, transient upstream failures) and are
             // handled by the timing/status confirmation path, not the
@@ -710,7 +1045,7 @@ async fn count_structural_followup_divergence(
             if matches!(status_code, Some(c) if c >= 500) {
                 continue;
             }
-            let body_len = response_body_length(&response);
+            let body_len = response_body_length(&parsed);
             if followup_status_diverged(status_code, baseline.status_code)
                 || bodies_diverge(body_len, baseline.body_length)
             {
@@ -766,6 +1101,106 @@ async fn probe_second_request_desync(
     })
 }
 
+/// Whether `payload` is a HEAD request declaring a body.
+fn is_bodied_head(payload: &str) -> bool {
+    let Some((head, body)) = payload.split_once("\r\n\r\n") else {
+        return false;
+    };
+    payload.starts_with("HEAD ") && !head.is_empty() && !body.is_empty()
+}
+
+/// Send `first` and a `Connection: close` GET for `path` pipelined on one
+/// connection, returning the follow-up's response and the exchange duration.
+async fn pipelined_followup(
+    params: &PayloadCheckParams<'_>,
+    first: &str,
+    path: &str,
+) -> Option<(String, Duration)> {
+    let followup = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+        path, params.host
+    );
+    let start = std::time::Instant::now();
+    let mut responses = pipeline_requests(
+        params.host,
+        params.port,
+        &[first.to_string(), followup],
+        params.timeout,
+        params.verbose,
+        params.use_tls,
+    )
+    .await
+    .ok()?;
+    (responses.len() == 2).then(|| (responses.swap_remove(1), start.elapsed()))
+}
+
+/// HEAD body-queue probe: pipeline the HEAD payload and a follow-up GET on one
+/// connection. A back-end that skipped the HEAD body reads it as the start of
+/// the follow-up, which is then answered for the queued request's path — a
+/// non-5xx status that differs from the baseline.
+///
+/// The same exchange with the body stripped must leave the follow-up alone,
+/// and the divergence must reproduce in a strict majority of
+/// `CONFIRMATION_RETRIES` retries. Returns the follow-up's response as the
+/// finding.
+async fn probe_head_body_queue(
+    params: &PayloadCheckParams<'_>,
+    path: &str,
+    baseline: &BaselineMeasurement,
+) -> Option<VulnerabilityInfo> {
+    if !is_bodied_head(params.attack_request) {
+        return None;
+    }
+    let queued = |response: &str| {
+        let status_code = HttpResponse::parse(response.as_bytes()).status;
+        followup_status_diverged(status_code, baseline.status_code)
+    };
+
+    let (response, duration) = pipelined_followup(params, params.attack_request, path).await?;
+    if !queued(&response) {
+        return None;
+    }
+    let head = params
+        .attack_request
+        .split_once("\r\n\r\n")
+        .map_or(params.attack_request, |(head, _)| head);
+    let control = build_control_request(head);
+    if pipelined_followup(params, &control, path)
+        .await
+        .is_some_and(|(r, _)| queued(&r))
+    {
+        return None;
+    }
+    let mut reproduced = 0usize;
+    for _ in 0..CONFIRMATION_RETRIES {
+        if pipelined_followup(params, params.attack_request, path)
+            .await
+            .is_some_and(|(r, _)| queued(&r))
+        {
+            reproduced += 1;
+        }
+    }
+    if reproduced * 2 <= CONFIRMATION_RETRIES {
+        return None;
+    }
+
+    let parsed = HttpResponse::parse(response.as_bytes());
+    Some(VulnerabilityInfo {
+        status_code: parsed.status,
+        duration,
+        connect: Duration::ZERO,
+        body_length: response_body_length(&parsed),
+        header_fingerprint: ResponseHeaderFingerprint::from_response(&parsed),
+        status: parsed.status_line,
+        is_connection_timeout: false,
+        head_body_queued: true,
+        response_head: response_head(&response, RESPONSE_DIFF_BODY_BYTES),
+        length_mismatch: None,
+        unsolicited: Vec::new(),
+        phases: None,
+    })
+}
+
 /// True if attack and control responses have structurally different bodies
 /// (the smaller body is less than `CONTROL_BODY_DIVERGENCE_PCT`% of the larger).
 ///
@@ -791,6 +1226,12 @@ fn control_indicates_false_positive(
     control: &ControlObservation,
     followup: Option<&FollowupObservation>,
 ) -> bool {
+    // ESCAPE: a queued HEAD body was already checked against the bodiless
+    // HEAD, the control that matters for it.
+    if attack.head_body_queued {
+        return false;
+    }
+
     // ESCAPE: post-attack follow-up GETs diverged from baseline → backend state
     // was perturbed by the attack and persisted into a subsequent request.
     // This is the canonical "second-request" smuggling signature and overrides
@@ -822,6 +1263,20 @@ fn control_indicates_false_positive(
         return false;
     }
 
+    // ESCAPE: bytes followed the attack response unasked while none followed
+    // the control's → a hop answered more than the one request it was sent.
+    if !attack.unsolicited.is_empty() && !control.unsolicited {
+        return false;
+    }
+
+    // ESCAPE: the attack's body disagreed with its Content-Length while the
+    // control's was framed correctly → the back-end stopped mid-response or
+    // answered more than one request, which only the smuggling artifacts
+    // explain.
+    if attack.length_mismatch.is_some() && !control.length_mismatch {
+        return false;
+    }
+
     // Connection-level timeout on the control too → backend cannot handle the
     // request shape at all, irrespective of smuggling tricks.
     if attack.is_connection_timeout && control.is_connection_timeout {
@@ -854,11 +1309,18 @@ fn control_indicates_false_positive(
 /// - Status-only (408/504 without timing anomaly): ALL retries must reproduce —
 ///   intermittent gateway-timeout responses are a common non-smuggling cause
 ///   and would otherwise pass strict-majority on a single fluke.
+/// - Length mismatch without timing anomaly: ALL retries must reproduce, as
+///   a connection dropped mid-body is just as often the network's doing.
 /// - Status+timing or timing-only signals: strict majority (>N/2) must reproduce.
 async fn confirm_vulnerability(
     params: &PayloadCheckParams<'_>,
     initial: &VulnerabilityInfo,
 ) -> ConfirmationResult {
+    // Retries re-send the same bytes; only the first response is feedback.
+    let params = &PayloadCheckParams {
+        feedback: None,
+        ..*params
+    };
     let mut durations = Vec::with_capacity(CONFIRMATION_RETRIES);
     for _ in 0..CONFIRMATION_RETRIES {
         if let Ok(Some(info)) = check_single_payload(params).await {
@@ -879,12 +1341,14 @@ async fn confirm_vulnerability(
     let initial_is_status_only = !initial.is_connection_timeout
         && matches!(initial.status_code, Some(408) | Some(504))
         && !initial_has_timing_anomaly;
+    let initial_is_mismatch_only = initial.length_mismatch.is_some() && !initial_has_timing_anomaly;
 
-    let confirmed = if initial.is_connection_timeout || initial_is_status_only {
-        durations.len() == CONFIRMATION_RETRIES
-    } else {
-        durations.len() * 2 > CONFIRMATION_RETRIES
-    };
+    let confirmed =
+        if initial.is_connection_timeout || initial_is_status_only || initial_is_mismatch_only {
+            durations.len() == CONFIRMATION_RETRIES
+        } else {
+            durations.len() * 2 > CONFIRMATION_RETRIES
+        };
 
     ConfirmationResult {
         confirmed,
@@ -917,6 +1381,11 @@ fn compute_confidence(
     if info.is_connection_timeout {
         return Confidence::Low;
     }
+    // The follow-up was answered for our queued request, or a response
+    // arrived that no request asked for: direct evidence.
+    if info.head_body_queued || !info.unsolicited.is_empty() {
+        return Confidence::High;
+    }
 
     let is_timeout_status = matches!(info.status_code, Some(408) | Some(504));
     let attack_millis = info.duration.as_millis();
@@ -927,7 +1396,8 @@ fn compute_confidence(
 
     // Demote timing-only detections from a noisy baseline to Low — the timing
     // delta isn't reliable evidence on its own.
-    if baseline_noisy && !is_timeout_status && !is_extreme_timing {
+    if baseline_noisy && !is_timeout_status && !is_extreme_timing && info.length_mismatch.is_none()
+    {
         return Confidence::Low;
     }
 
@@ -952,11 +1422,20 @@ fn collect_detection_signals(
     if info.is_connection_timeout {
         signals.push("connection_timeout".to_string());
     }
+    if info.head_body_queued {
+        signals.push("head_body_queued".to_string());
+    }
     match info.status_code {
         Some(408) => signals.push("status_408".to_string()),
         Some(504) => signals.push("status_504".to_string()),
         _ => {}
     }
+    if let Some(mismatch) = info.length_mismatch {
+        signals.push(mismatch.signal());
+    }
+    if !info.unsolicited.is_empty() {
+        signals.push(format!("unsolicited_bytes:{}", info.unsolicited.len()));
+    }
     let attack_ms = info.duration.as_millis();
     let normal_ms = normal_duration.as_millis();
     if attack_ms > timing_threshold && attack_ms > MIN_DELAY_MS {
@@ -996,47 +1475,64 @@ fn collect_detection_signals(
 }
 
 /// Build the final `CheckResult` from collected scan state.
-#[allow(clippy::type_complexity)]
 fn build_check_result(
     check_name: &str,
     normal_status: String,
     normal_duration: Duration,
-    vulnerability: Option<(
-        usize,
-        String,
-        VulnerabilityInfo,
-        Option<ControlObservation>,
-        Option<FollowupObservation>,
-    )>,
+    vulnerability: Option<(Payload, VulnerabilityInfo, Evidence)>,
     timing_threshold: u128,
     baseline_noisy: bool,
-    diagnostics: Vec<String>,
+    mut diagnostics: Vec<String>,
 ) -> (CheckResult, Option<(usize, String)>) {
-    if let Some((idx, payload, info, control, followup)) = vulnerability {
+    if let Some((winner, info, evidence)) = vulnerability {
+        let (idx, payload) = (winner.index, winner.request);
         let confidence = compute_confidence(&info, timing_threshold, baseline_noisy);
-        let detection_signals = collect_detection_signals(
+        let mut detection_signals = collect_detection_signals(
             &info,
             normal_duration,
             timing_threshold,
             baseline_noisy,
-            control.as_ref(),
-            followup.as_ref(),
+            evidence.control.as_ref(),
+            evidence.followup.as_ref(),
         );
+        if check_name == "cl-cl" {
+            detection_signals.extend(cl_cl_hop_signals(&payload));
+        }
+        // The finding survived it, so the benign chunked control was clean.
+        if evidence.chunked_control.is_some() {
+            detection_signals.push("chunked_control_clean".to_string());
+        }
+        diagnostics.extend(evidence.diagnostics);
         let attack_status = info.status;
         let attack_duration_ms = info.duration.as_millis() as u64;
         let result = CheckResult {
             check_type: check_name.to_string(),
             vulnerable: true,
             payload_index: Some(idx),
+            payload_name: winner.name,
+            technique: winner.technique,
             normal_status,
             attack_status: Some(attack_status),
             normal_duration_ms: normal_duration.as_millis() as u64,
             attack_duration_ms: Some(attack_duration_ms),
+            normal_connect_ms: None,
+            attack_connect_ms: (!info.is_connection_timeout && !info.connect.is_zero())
+                .then_some(info.connect.as_millis() as u64),
             timestamp: Utc::now().to_rfc3339(),
             payload: Some(payload.clone()),
             confidence: Some(confidence),
             detection_signals,
             diagnostics,
+            payloads_sent: 0,
+            request_errors: Default::default(),
+            h2_observations: Vec::new(),
+            fuzz_anomalies: Vec::new(),
+            unsolicited_bytes: (!info.unsolicited.is_empty())
+                .then(|| String::from_utf8_lossy(&info.unsolicited).into_owned()),
+            attack_phases: info.phases,
+            cross_check: None,
+            evidence: None,
+            consistency: None,
         };
         (result, Some((idx, payload)))
     } else {
@@ -1044,20 +1540,115 @@ fn build_check_result(
             check_type: check_name.to_string(),
             vulnerable: false,
             payload_index: None,
+            payload_name: None,
+            technique: None,
             normal_status,
             attack_status: None,
             normal_duration_ms: normal_duration.as_millis() as u64,
             attack_duration_ms: None,
+            normal_connect_ms: None,
+            attack_connect_ms: None,
             timestamp: Utc::now().to_rfc3339(),
             payload: None,
             confidence: None,
             detection_signals: Vec::new(),
             diagnostics,
+            payloads_sent: 0,
+            request_errors: Default::default(),
+            h2_observations: Vec::new(),
+            fuzz_anomalies: Vec::new(),
+            unsolicited_bytes: None,
+            attack_phases: None,
+            cross_check: None,
+            evidence: None,
+            consistency: None,
         };
         (result, None)
     }
 }
 
+/// Where fuzz anomalies short of a finding are reported from.
+struct FuzzTriage<'a> {
+    feedback: &'a FuzzFeedback,
+    export: Option<PayloadExport<'a>>,
+    host: &'a str,
+    check_name: &'a str,
+    use_tls: bool,
+}
+
+impl FuzzTriage<'_> {
+    /// Build the triage entry for a payload whose screening response was
+    /// anomalous but produced no finding (`outcome` says why); `None` when no
+    /// anomaly was flagged for it. With `--export-payloads`, the request is
+    /// also written out for reproduction.
+    fn entry(&self, payload: &Payload, request: &str, outcome: &str) -> Option<FuzzAnomaly> {
+        let observed = self.feedback.take_anomaly(request)?;
+        let check_type = format!("{}-anomaly", self.check_name);
+        let reproduction_file = self.export.and_then(|export| {
+            match export.format {
+                ExportFormat::Txt => export_payload(
+                    export.dir,
+                    self.host,
+                    &check_type,
+                    payload.index,
+                    request,
+                    self.use_tls,
+                ),
+                ExportFormat::Raw => export_payload_raw(
+                    export.dir,
+                    self.host,
+                    request,
+                    self.use_tls,
+                    ExportedPayload {
+                        target: export.target.to_string(),
+                        check_type: check_type.clone(),
+                        payload_index: payload.index,
+                        payload_name: payload.name.clone(),
+                        technique: payload.technique.clone(),
+                        raw_file: String::new(),
+                        timestamp: Utc::now().to_rfc3339(),
+                        exported_at: String::new(),
+                        outcome: None,
+                        response: ResponseSummary {
+                            signals: vec![observed.clone()],
+                            ..Default::default()
+                        },
+                    },
+                ),
+            }
+            .ok()
+        });
+        Some(FuzzAnomaly {
+            payload_index: payload.index,
+            payload_name: payload.name.clone(),
+            anomaly: format!("{}; {}", observed, outcome),
+            payload: request.to_string(),
+            seed_diff: self
+                .feedback
+                .parent_of(request)
+                .map(|seed| payload_diff(&seed, request))
+                .unwrap_or_default(),
+            reproduction_file,
+        })
+    }
+}
+
+/// The payload's name followed by `(#<index>)`, or `#<index>` alone when it
+/// has no name: names stay put when generators change, indices do not.
+fn payload_label(payload: &Payload) -> String {
+    match payload.name {
+        Some(ref name) => format!("{} (#{})", name, payload.index),
+        None => format!("#{}", payload.index),
+    }
+}
+
+/// Hash of a request's exact bytes, used to skip repeats within a check.
+fn request_hash(request: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    request.hash(&mut hasher);
+    hasher.finish()
+}
+
 /// Runs a set of attack requests for a given check type.
 pub async fn run_checks_for_type(params: CheckParams<'_>) -> Result<CheckResult> {
     let total_requests = params.attack_requests.len();
@@ -1077,10 +1668,12 @@ pub async fn run_checks_for_type(params: CheckParams<'_>) -> Result<CheckResult>
         params.verbose,
         params.use_tls,
         params.baseline_count,
+        params.warmup,
     )
     .await?;
     let normal_status = baseline.status.clone();
     let normal_duration = baseline.duration;
+    let baseline_connect = baseline.connect;
     // Noise-aware threshold: a slow attack must beat BOTH the relative
     // multiplier over the median AND the worst observed baseline plus a buffer.
     // This prevents a single slow baseline sample from inflating noise that
@@ -1090,10 +1683,10 @@ pub async fn run_checks_for_type(params: CheckParams<'_>) -> Result<CheckResult>
     // backends where POST handling is naturally slower than GET — a common
     // false-positive source where the GET baseline understates the per-request
     // floor for the actual attack shape.
-    let attack_method = params
-        .attack_requests
-        .first()
-        .map(|p| payload_method(p))
+    let mut attack_requests = params.attack_requests.peekable();
+    let attack_method = attack_requests
+        .peek()
+        .map(|p| payload_method(&p.request))
         .unwrap_or_else(|| "GET".to_string());
     let mut max_baseline = baseline.max_duration;
     let mut median_baseline = normal_duration;
@@ -1127,22 +1720,152 @@ pub async fn run_checks_for_type(params: CheckParams<'_>) -> Result<CheckResult>
     );
     let baseline_noisy = baseline_is_noisy(median_baseline, max_baseline);
 
-    #[allow(clippy::type_complexity)]
-    let mut vulnerability_info: Option<(
-        usize,
-        String,
-        VulnerabilityInfo,
-        Option<ControlObservation>,
-        Option<FollowupObservation>,
-    )> = None;
+    let strategies = build_strategies(params.detect);
+    let screening = strategies.iter().any(|s| s.screens(params.check_name));
+    if params.verbose {
+        let names: Vec<&str> = strategies.iter().map(|s| s.method().name()).collect();
+        println!(
+            "  {} {} detection: {}",
+            "[*]".cyan(),
+            params.check_name,
+            names.join(", ")
+        );
+    }
+    let mut vulnerability_info: Option<(Payload, VulnerabilityInfo, Evidence)> = None;
+    // Diagnostics from sweeps that did not produce a finding (e.g. OOB probe
+    // hosts) are still worth reporting.
+    let mut sweep_diagnostics: Vec<String> = Vec::new();
 
     // Track consecutive control-FP rejections so we can abandon the check if
     // the backend produces the same shape-dependent anomaly for every
     // variant — that's a strong signal the responses are not smuggling.
     let mut consecutive_fp_rejections: usize = 0;
     let mut early_termination: Option<String> = None;
+    // Consecutive payloads that failed or drew a 5xx; see
+    // ERROR_STORM_THRESHOLD.
+    let mut error_streak: usize = 0;
+    let mut storms_recovered: usize = 0;
+    // First payload eligible for the second-request desync probe, remembered
+    // while streaming so the payloads never need to be regenerated.
+    let mut plant_candidate: Option<Payload> = None;
+    // Hashes of requests already sent: identical bytes on the wire cannot
+    // behave differently, so repeats (duplicate variants, fuzz mutants that
+    // regenerate a seed) are skipped.
+    let mut sent: HashSet<u64> = HashSet::new();
+    let mut duplicates_skipped = 0usize;
+    // Failed attack requests by kind, so "TLS rejected" and "back-end hung"
+    // stay distinguishable in the result.
+    let mut request_errors: BTreeMap<RequestErrorKind, usize> = BTreeMap::new();
+    // Fuzz anomalies that fell short of a finding, for the triage report.
+    let mut fuzz_anomalies: Vec<FuzzAnomaly> = Vec::new();
+    let triage = params.fuzz_feedback.map(|feedback| FuzzTriage {
+        feedback,
+        export: params.export,
+        host: params.host,
+        check_name: params.check_name,
+        use_tls: params.use_tls,
+    });
+
+    // Every payload sent, with its outcome and the response that decided
+    // it, for `--export-all`.
+    let archive = |payload: &Payload,
+                   outcome: &str,
+                   info: Option<&VulnerabilityInfo>,
+                   signals: Vec<String>| {
+        let Some(recorder) = params.archive else {
+            return;
+        };
+        let response = ResponseSummary {
+            normal_status: Some(normal_status.clone()),
+            attack_status: info.map(|info| info.status.clone()),
+            normal_duration_ms: Some(normal_duration.as_millis() as u64),
+            attack_duration_ms: info.map(|info| info.duration.as_millis() as u64),
+            confidence: None,
+            signals,
+        };
+        if let Err(e) = recorder.record(params.check_name, payload, outcome, response)
+            && params.verbose
+        {
+            println!("  {} Failed to archive payload: {}", "[!]".yellow(), e);
+        }
+    };
 
-    for (i, attack_request) in params.attack_requests.iter().enumerate() {
+    for payload in attack_requests {
+        let i = payload.index;
+        let attack_request = &payload.request;
+        if !screening {
+            // Nothing examines individual payloads; only the sweep candidate
+            // is needed.
+            if payload_eligible_for_control(attack_request) {
+                plant_candidate = Some(payload);
+                break;
+            }
+            continue;
+        }
+        if !sent.insert(request_hash(attack_request)) {
+            duplicates_skipped += 1;
+            continue;
+        }
+        if plant_candidate.is_none() && payload_eligible_for_control(attack_request) {
+            plant_candidate = Some(payload.clone());
+        }
+        // Circuit breaker: leave a target that keeps failing alone for the
+        // cool-down, then abort unless the baseline requests succeed again.
+        if error_streak >= ERROR_STORM_THRESHOLD {
+            if params.verbose {
+                println!(
+                    "  {} {} error storm: {} consecutive failed or 5xx payloads, pausing {}s before re-baselining",
+                    "[!]".yellow(),
+                    params.check_name,
+                    error_streak,
+                    params.storm_cooldown
+                );
+            } else {
+                params.pb.set_message(format!(
+                    "[{}/{}] {} error storm, cooling down for {}s",
+                    params.current_check,
+                    params.total_checks,
+                    params.check_name,
+                    params.storm_cooldown
+                ));
+            }
+            tokio::time::sleep(Duration::from_secs(params.storm_cooldown)).await;
+            let failure = match measure_baseline(
+                params.host,
+                params.port,
+                params.path,
+                params.timeout,
+                params.verbose,
+                params.use_tls,
+                params.baseline_count,
+                0,
+            )
+            .await
+            {
+                Ok(rebaseline)
+                    if !baseline_majority_server_error(&rebaseline.observed_status_codes) =>
+                {
+                    None
+                }
+                Ok(rebaseline) => Some(rebaseline.status),
+                Err(e) => Some(e.to_string()),
+            };
+            if let Some(failure) = failure {
+                return Err(SmugglexError::TargetDestabilized(format!(
+                    "{} consecutive failed or 5xx payloads during {}, still failing after a {}s cool-down ({})",
+                    error_streak, params.check_name, params.storm_cooldown, failure
+                )));
+            }
+            if params.verbose {
+                println!(
+                    "  {} {} target recovered, resuming",
+                    "[*]".cyan(),
+                    params.check_name
+                );
+            }
+            error_streak = 0;
+            storms_recovered += 1;
+        }
         if params.delay > 0 && i > 0 {
             tokio::time::sleep(Duration::from_millis(params.delay)).await;
         }
@@ -1150,11 +1873,15 @@ pub async fn run_checks_for_type(params: CheckParams<'_>) -> Result<CheckResult>
         if !params.verbose {
             let current = i + 1;
             let percentage = (current as u32 * 100) / total_requests as u32;
+            let check = match payload.name {
+                Some(ref name) => format!("{} / {}", params.check_name, name),
+                None => params.check_name.to_string(),
+            };
             params.pb.set_message(format!(
                 "[{}/{}] checking {} ({}/{} - {}%)",
                 params.current_check,
                 params.total_checks,
-                params.check_name,
+                check,
                 current,
                 total_requests,
                 percentage
@@ -1170,163 +1897,268 @@ pub async fn run_checks_for_type(params: CheckParams<'_>) -> Result<CheckResult>
             use_tls: params.use_tls,
             timing_threshold,
             baseline_status_codes: &baseline.observed_status_codes,
+            baseline_length_mismatch: baseline.length_mismatch,
+            feedback: params.fuzz_feedback,
+        };
+        let ctx = ProbeContext {
+            payload: &payload_params,
+            check_name: params.check_name,
+            path: params.path,
+            baseline: &baseline,
+            oob_host: params.oob_host,
         };
 
-        match check_single_payload(&payload_params).await {
-            Ok(Some(mut info)) => {
-                let confirmation = confirm_vulnerability(&payload_params, &info).await;
-                if confirmation.confirmed {
-                    // Use the median of (initial + retry) durations to dampen
-                    // the influence of a single transient spike on confidence.
-                    let mut all_durations = Vec::with_capacity(confirmation.durations.len() + 1);
-                    all_durations.push(info.duration);
-                    all_durations.extend(confirmation.durations);
-                    info.duration = median_duration(&mut all_durations);
-
-                    // Differential control check: send a smuggling-stripped
-                    // sibling of the payload. Skipped for payloads where
-                    // stripping isn't meaningful (H2C/H2 or non-TE payloads).
-                    let mut control_observation: Option<ControlObservation> = None;
-                    if payload_eligible_for_control(attack_request) {
-                        let control_request = build_control_request(attack_request);
-                        if let Some(control) =
-                            observe_control(&payload_params, &control_request).await
-                        {
-                            control_observation = Some(control);
-                        }
+        let mut candidate: Option<VulnerabilityInfo> = None;
+        let mut errors: Vec<String> = Vec::new();
+        for strategy in strategies.iter().filter(|s| s.screens(params.check_name)) {
+            let mut examined = strategy.examine(&ctx).await;
+            // A connection that failed to open says nothing about the
+            // payload: send it once more before counting the error. Once the
+            // payload went out it is never re-sent (see `is_retryable`).
+            if let Err(ref e) = examined
+                && e.is_retryable()
+            {
+                if params.verbose {
+                    println!(
+                        "\n{} Retrying {} payload {} after: {}",
+                        "[!]".yellow(),
+                        params.check_name,
+                        i,
+                        e
+                    );
+                }
+                examined = strategy.examine(&ctx).await;
+            }
+            match examined {
+                Ok(Some(info)) => {
+                    candidate = Some(info);
+                    break;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    errors.push(e.to_string());
+                    if let Some(kind) = e.request_error_kind() {
+                        *request_errors.entry(kind).or_default() += 1;
                     }
+                    if params.verbose {
+                        println!(
+                            "\n{} Error during {} attack request (payload {}): {}",
+                            "[!]".yellow(),
+                            params.check_name,
+                            i,
+                            e
+                        );
+                    }
+                }
+            }
+        }
+        let Some(info) = candidate else {
+            // No reproduced anomaly → this payload was not a shape-dependent
+            // FP and does not contribute to the consecutive-rejection streak.
+            if errors.is_empty() {
+                consecutive_fp_rejections = 0;
+                error_streak = 0;
+                archive(&payload, "clean", None, Vec::new());
+            } else {
+                error_streak += 1;
+                archive(&payload, "error", None, errors);
+            }
+            if let Some(ref triage) = triage {
+                fuzz_anomalies.extend(triage.entry(
+                    &payload,
+                    attack_request,
+                    "not reproduced on retry",
+                ));
+            }
+            continue;
+        };
 
-                    // Post-attack follow-up probes: detect proxy↔backend
-                    // desync that persists past the attack. Strong escape
-                    // signal — overrides the control FP rule.
-                    let followup_observation = Some(
-                        observe_followup_divergence(&payload_params, params.path, &baseline).await,
-                    );
+        if info.is_connection_timeout {
+            *request_errors.entry(RequestErrorKind::Timeout).or_default() += 1;
+        }
+        if params.verbose && !info.response_head.is_empty() {
+            log_response_diff(
+                &format!(
+                    "{} payload {} response diff (- baseline, + attack):",
+                    params.check_name,
+                    payload_label(&payload)
+                ),
+                &baseline.response_head,
+                &info.response_head,
+            );
+        }
 
-                    // FP rejection considers both control similarity AND the
-                    // absence of follow-up divergence. If the follow-up
-                    // diverged, the finding survives FP rejection.
-                    if let Some(control) = control_observation.as_ref()
-                        && control_indicates_false_positive(
-                            &info,
-                            control,
-                            followup_observation.as_ref(),
-                        )
-                    {
-                        if params.verbose {
-                            println!(
-                                "  {} {} payload #{} rejected as false positive (control matched attack: status={:?}, attack={}ms, control={}ms)",
-                                "[*]".cyan(),
-                                params.check_name,
-                                i,
-                                control.status_code,
-                                info.duration.as_millis(),
-                                control.duration.as_millis(),
-                            );
-                        }
-                        consecutive_fp_rejections += 1;
-                        if consecutive_fp_rejections >= CONSECUTIVE_FP_REJECTIONS_LIMIT {
-                            early_termination = Some(format!(
-                                "early_termination:consecutive_fp_rejections={}",
-                                consecutive_fp_rejections
-                            ));
-                            if params.verbose {
-                                println!(
-                                    "  {} {} abandoning check after {} consecutive control-FP rejections",
-                                    "[*]".cyan(),
-                                    params.check_name,
-                                    consecutive_fp_rejections,
-                                );
-                            }
-                            break;
-                        }
-                        continue;
-                    }
+        let mut evidence = Evidence::default();
+        for strategy in &strategies {
+            strategy.corroborate(&ctx, &info, &mut evidence).await;
+        }
 
-                    vulnerability_info = Some((
-                        i,
-                        attack_request.clone(),
-                        info,
-                        control_observation,
-                        followup_observation,
-                    ));
-                    break;
-                } else {
-                    // Initial detection didn't confirm — payload was not a
-                    // shape-dependent FP, so this run does not contribute to
-                    // the consecutive-rejection streak.
-                    consecutive_fp_rejections = 0;
+        // Keep the anomaly for later runs whatever the verdict; minimizing
+        // after corroboration so the extra requests cannot disturb it.
+        if let Some(corpus) = params.corpus {
+            let minimized = if info.is_connection_timeout {
+                // Each reduction attempt would wait out the full timeout.
+                attack_request.clone()
+            } else {
+                minimize_anomaly(&payload_params, params.delay).await
+            };
+            match corpus.save(params.check_name, &minimized) {
+                Ok(Some(path)) if params.verbose => println!(
+                    "  {} {} payload {} anomaly ({}) saved to {} ({} -> {} bytes)",
+                    "[*]".cyan(),
+                    params.check_name,
+                    payload_label(&payload),
+                    info.status,
+                    path.display(),
+                    attack_request.len(),
+                    minimized.len(),
+                ),
+                Ok(_) => {}
+                Err(e) => {
+                    if params.verbose {
+                        println!(
+                            "  {} failed to save {} anomaly to corpus: {}",
+                            "[!]".yellow(),
+                            params.check_name,
+                            e
+                        );
+                    }
                 }
             }
-            Ok(None) => {
-                // No detection signal at all → reset the streak.
-                consecutive_fp_rejections = 0;
+        }
+
+        if evidence.refuted {
+            archive(&payload, "refuted", Some(&info), Vec::new());
+            if info.is_connection_timeout || info.status_code.is_some_and(|c| c >= 500) {
+                error_streak += 1;
+            } else {
+                error_streak = 0;
+            }
+            if let Some(ref triage) = triage {
+                fuzz_anomalies.extend(triage.entry(
+                    &payload,
+                    attack_request,
+                    "rejected by the control request",
+                ));
+            }
+            if params.verbose {
+                let (control_status, control_ms) = evidence
+                    .control
+                    .as_ref()
+                    .map(|c| (c.status_code, c.duration.as_millis()))
+                    .unwrap_or_default();
+                println!(
+                    "  {} {} payload {} rejected as false positive (control matched attack: status={:?}, attack={}ms, control={}ms)",
+                    "[*]".cyan(),
+                    params.check_name,
+                    payload_label(&payload),
+                    control_status,
+                    info.duration.as_millis(),
+                    control_ms,
+                );
             }
-            Err(e) => {
+            consecutive_fp_rejections += 1;
+            if consecutive_fp_rejections >= CONSECUTIVE_FP_REJECTIONS_LIMIT {
+                early_termination = Some(format!(
+                    "early_termination:consecutive_fp_rejections={}",
+                    consecutive_fp_rejections
+                ));
                 if params.verbose {
                     println!(
-                        "\n{} Error during {} attack request (payload {}): {}",
-                        "[!]".yellow(),
+                        "  {} {} abandoning check after {} consecutive control-FP rejections",
+                        "[*]".cyan(),
                         params.check_name,
-                        i,
-                        e
+                        consecutive_fp_rejections,
                     );
                 }
+                break;
             }
+            continue;
         }
+
+        archive(&payload, "detected", Some(&info), Vec::new());
+        vulnerability_info = Some((payload, info, evidence));
+        break;
     }
 
-    // Second-request desync probe: only when the main loop found no direct
-    // anomaly and did not early-terminate. Catches CL.TE desyncs whose attack
-    // response is itself benign and only the FOLLOWING request on the shared
-    // upstream connection is corrupted.
+    // Sweeps: only when screening found no direct anomaly and did not
+    // early-terminate. Catches CL.TE desyncs whose attack response is itself
+    // benign and only the FOLLOWING request on the shared upstream connection
+    // is corrupted.
     if vulnerability_info.is_none()
         && early_termination.is_none()
-        && let Some((idx, plant_payload)) = params
-            .attack_requests
-            .iter()
-            .enumerate()
-            .find(|(_, p)| payload_eligible_for_control(p))
+        && let Some(plant) = plant_candidate
     {
+        if !screening {
+            sent.insert(request_hash(&plant.request));
+        }
         let payload_params = PayloadCheckParams {
             host: params.host,
             port: params.port,
-            attack_request: plant_payload,
+            attack_request: &plant.request,
             timeout: params.timeout,
             verbose: params.verbose,
             use_tls: params.use_tls,
             timing_threshold,
             baseline_status_codes: &baseline.observed_status_codes,
+            baseline_length_mismatch: baseline.length_mismatch,
+            feedback: None,
         };
-        if let Some(followup) =
-            probe_second_request_desync(&payload_params, params.path, &baseline).await
-        {
-            if params.verbose {
-                println!(
-                    "  {} {} second-request desync detected: {}/{} follow-up probes diverged from baseline",
-                    "[+]".green(),
-                    params.check_name,
-                    followup.diverging,
-                    followup.total,
-                );
+        let ctx = ProbeContext {
+            payload: &payload_params,
+            check_name: params.check_name,
+            path: params.path,
+            baseline: &baseline,
+            oob_host: params.oob_host,
+        };
+        let mut evidence = Evidence::default();
+        let mut found: Option<VulnerabilityInfo> = None;
+        for strategy in &strategies {
+            if let Some(info) = strategy.sweep(&ctx, &mut evidence).await {
+                found = Some(info);
+                break;
+            }
+        }
+        match found {
+            Some(info) => {
+                archive(&plant, "sweep-detected", Some(&info), Vec::new());
+                vulnerability_info = Some((plant, info, evidence));
+            }
+            None => {
+                archive(&plant, "sweep-clean", None, Vec::new());
+                sweep_diagnostics = evidence.diagnostics;
             }
-            // The attack request itself carried no anomaly — synthesize a
-            // benign VulnerabilityInfo so the finding is reported as a
-            // medium-confidence second-request desync.
-            let info = VulnerabilityInfo {
-                status: normal_status.clone(),
-                status_code: baseline.status_code,
-                duration: normal_duration,
-                body_length: baseline.body_length,
-                header_fingerprint: ResponseHeaderFingerprint::default(),
-                is_connection_timeout: false,
-            };
-            vulnerability_info = Some((idx, plant_payload.clone(), info, None, Some(followup)));
         }
     }
 
-    let diagnostics: Vec<String> = early_termination.into_iter().collect();
-    let (result, exported) = build_check_result(
+    if params.verbose
+        && let Some(feedback) = params.fuzz_feedback
+    {
+        println!(
+            "  {} {} fuzz feedback: {} response cluster(s), {} mutant(s) promoted",
+            "[*]".cyan(),
+            params.check_name,
+            feedback.cluster_count(),
+            feedback.promoted_count(),
+        );
+    }
+
+    if params.verbose && duplicates_skipped > 0 {
+        println!(
+            "  {} {} skipped {} duplicate payload(s); {} unique sent",
+            "[*]".cyan(),
+            params.check_name,
+            duplicates_skipped,
+            sent.len(),
+        );
+    }
+
+    let mut diagnostics: Vec<String> = early_termination.into_iter().collect();
+    if storms_recovered > 0 {
+        diagnostics.push(format!("error_storm_recovered={}", storms_recovered));
+    }
+    diagnostics.extend(sweep_diagnostics);
+    let (mut result, exported) = build_check_result(
         params.check_name,
         normal_status,
         normal_duration,
@@ -1335,19 +2167,92 @@ pub async fn run_checks_for_type(params: CheckParams<'_>) -> Result<CheckResult>
         baseline_noisy,
         diagnostics,
     );
-
-    if let (Some((payload_index, payload)), Some(export_dir)) = (exported, params.export_dir)
-        && let Err(e) = export_payload(
-            export_dir,
-            params.host,
-            params.check_name,
-            payload_index,
-            &payload,
-            params.use_tls,
-        )
-        && params.verbose
+    result.payloads_sent = sent.len();
+    result.normal_connect_ms = Some(baseline_connect.as_millis() as u64);
+
+    // A CL.TE finding should not also desync under TE.CL framing, nor the
+    // reverse; a target that does may have been misclassified.
+    if let Some((technique, probe)) = result
+        .payload
+        .as_deref()
+        .and_then(|payload| build_cross_check_probe(params.check_name, payload))
     {
-        println!("  {} Failed to export payload: {}", "[!]".yellow(), e);
+        if params.delay > 0 {
+            tokio::time::sleep(Duration::from_millis(params.delay)).await;
+        }
+        let probe_params = PayloadCheckParams {
+            host: params.host,
+            port: params.port,
+            attack_request: &probe,
+            timeout: params.timeout,
+            verbose: params.verbose,
+            use_tls: params.use_tls,
+            timing_threshold,
+            baseline_status_codes: &baseline.observed_status_codes,
+            baseline_length_mismatch: baseline.length_mismatch,
+            feedback: None,
+        };
+        let checked = cross_check(&probe_params, technique).await;
+        if params.verbose {
+            println!(
+                "  {} {} cross-check: {} ({})",
+                "[*]".cyan(),
+                params.check_name,
+                checked.verdict,
+                checked.observation
+            );
+        }
+        result.cross_check = Some(checked);
+    }
+    result.request_errors = request_errors;
+    fuzz_anomalies.truncate(MAX_FUZZ_ANOMALIES);
+    result.fuzz_anomalies = fuzz_anomalies;
+
+    if let (Some((payload_index, payload)), Some(export)) = (exported, params.export) {
+        let exported = match export.format {
+            ExportFormat::Txt => export_payload(
+                export.dir,
+                params.host,
+                params.check_name,
+                payload_index,
+                &payload,
+                params.use_tls,
+            )
+            .and_then(|file| match result.technique {
+                Some(ref technique) => export_technique(&file, technique),
+                None => Ok(file),
+            }),
+            ExportFormat::Raw => export_payload_raw(
+                export.dir,
+                params.host,
+                &payload,
+                params.use_tls,
+                ExportedPayload {
+                    target: export.target.to_string(),
+                    check_type: params.check_name.to_string(),
+                    payload_index,
+                    payload_name: result.payload_name.clone(),
+                    technique: result.technique.clone(),
+                    raw_file: String::new(),
+                    timestamp: result.timestamp.clone(),
+                    exported_at: String::new(),
+                    outcome: None,
+                    response: ResponseSummary {
+                        normal_status: Some(result.normal_status.clone()),
+                        attack_status: result.attack_status.clone(),
+                        normal_duration_ms: Some(result.normal_duration_ms),
+                        attack_duration_ms: result.attack_duration_ms,
+                        confidence: result.confidence.clone(),
+                        signals: result.detection_signals.clone(),
+                    },
+                },
+            ),
+        };
+        if let Err(e) = exported
+            && params.verbose
+        {
+            println!("  {} Failed to export payload: {}", "[!]".yellow(), e);
+        }
     }
 
     Ok(result)
@@ -1357,6 +2262,10 @@ pub async fn run_checks_for_type(params: CheckParams<'_>) -> Result<CheckResult>
 mod tests {
     use super::*;
 
+    fn fingerprint(response: &str) -> ResponseHeaderFingerprint {
+        ResponseHeaderFingerprint::from_response(&HttpResponse::parse(response.as_bytes()))
+    }
+
     #[test]
     fn baseline_majority_timeout_empty_is_false() {
         assert!(!baseline_majority_timeout(&[]));
@@ -1398,6 +2307,26 @@ mod tests {
         assert!(payload_eligible_for_control(p));
     }
 
+    #[test]
+    fn bodied_head_needs_head_method_and_body() {
+        assert!(is_bodied_head(
+            "HEAD / HTTP/1.1\r\nHost: t\r\nContent-Length: 3\r\n\r\nabc"
+        ));
+        assert!(!is_bodied_head("HEAD / HTTP/1.1\r\nHost: t\r\n\r\n"));
+        assert!(!is_bodied_head(
+            "POST / HTTP/1.1\r\nHost: t\r\nContent-Length: 3\r\n\r\nabc"
+        ));
+    }
+
+    #[test]
+    fn payload_eligible_for_dual_content_length() {
+        let p =
+            "POST / HTTP/1.1\r\nHost: x\r\nContent-Length: 6\r\ncontent-length: 50\r\n\r\nSMUGGL";
+        assert!(payload_eligible_for_control(p));
+        let single = "POST / HTTP/1.1\r\nHost: x\r\nContent-Length: 6\r\n\r\nSMUGGL";
+        assert!(!payload_eligible_for_control(single));
+    }
+
     #[test]
     fn payload_eligible_skips_h2c_upgrade() {
         // H2C payload has TE-related body but the Upgrade header makes control
@@ -1447,6 +2376,45 @@ mod tests {
         assert_eq!(body.len(), CONTROL_BODY_MAX_BYTES);
     }
 
+    #[test]
+    fn build_chunked_control_is_well_framed() {
+        let p = "POST /a HTTP/1.1\r\nHost: x\r\nCookie: s=1\r\nTransfer-Encoding: chunked\r\nContent-Length: 6\r\n\r\n0\r\n\r\nG";
+        assert!(payload_declares_chunked(p));
+        let control = build_chunked_control_request(p);
+        assert_eq!(
+            control,
+            "POST /a HTTP/1.1\r\nHost: x\r\nCookie: s=1\r\nTransfer-Encoding: chunked\r\nContent-Length: 16\r\n\r\n6\r\nxxxxxx\r\n0\r\n\r\n"
+        );
+
+        let empty = build_chunked_control_request(
+            "POST / HTTP/1.1\r\nHost: x\r\nTransfer-Encoding:\tchunked\r\n\r\n",
+        );
+        assert!(empty.ends_with("Content-Length: 5\r\n\r\n0\r\n\r\n"));
+        assert_eq!(empty.matches("Transfer-Encoding").count(), 1);
+
+        // Duplicate Content-Length payloads have no chunked framing to mirror
+        let cl_cl =
+            "POST / HTTP/1.1\r\nHost: x\r\nContent-Length: 6\r\nContent-Length: 0\r\n\r\nabcdef";
+        assert!(!payload_declares_chunked(cl_cl));
+    }
+
+    #[test]
+    fn build_cross_check_probe_mirrors_the_technique() {
+        let p = "POST /a HTTP/1.1\r\nHost: x\r\nCookie: s=1\r\nTransfer-Encoding: chunked\r\nContent-Length: 4\r\n\r\n1\r\nA\r\nX";
+        let (technique, probe) = build_cross_check_probe("cl-te", p).unwrap();
+        assert_eq!(technique, "te-cl");
+        assert_eq!(
+            probe,
+            "POST /a HTTP/1.1\r\nHost: x\r\nCookie: s=1\r\nTransfer-Encoding: chunked\r\nContent-Length: 6\r\n\r\n0\r\n\r\nX"
+        );
+        let (technique, probe) = build_cross_check_probe("te-cl", p).unwrap();
+        assert_eq!(technique, "cl-te");
+        assert!(probe.ends_with("Content-Length: 4\r\n\r\n1\r\nA\r\nX"));
+        assert_eq!(probe.matches("Content-Length").count(), 1);
+        assert!(build_cross_check_probe("te-te", p).is_none());
+        assert!(build_cross_check_probe("CL.TE", p).is_none());
+    }
+
     #[test]
     fn build_control_preserves_custom_headers() {
         let p = "POST / HTTP/1.1\r\nHost: x\r\nCookie: s=1\r\nX-Custom: v\r\nTransfer-Encoding: chunked\r\nContent-Length: 6\r\n\r\n0\r\n\r\nG";
@@ -1461,9 +2429,15 @@ mod tests {
             status: "HTTP/1.1 504".into(),
             status_code: Some(504),
             duration: Duration::from_millis(500),
+            connect: Duration::ZERO,
             body_length: 20,
             header_fingerprint: ResponseHeaderFingerprint::default(),
             is_connection_timeout: false,
+            head_body_queued: false,
+            response_head: String::new(),
+            length_mismatch: None,
+            unsolicited: Vec::new(),
+            phases: None,
         };
         let control = ControlObservation {
             duration: Duration::from_millis(50),
@@ -1471,6 +2445,8 @@ mod tests {
             body_length: 20,
             header_fingerprint: ResponseHeaderFingerprint::default(),
             is_connection_timeout: false,
+            length_mismatch: false,
+            unsolicited: false,
         };
         assert!(control_indicates_false_positive(&attack, &control, None));
     }
@@ -1481,9 +2457,15 @@ mod tests {
             status: "HTTP/1.1 200".into(),
             status_code: Some(200),
             duration: Duration::from_millis(2000),
+            connect: Duration::ZERO,
             body_length: 13,
             header_fingerprint: ResponseHeaderFingerprint::default(),
             is_connection_timeout: false,
+            head_body_queued: false,
+            response_head: String::new(),
+            length_mismatch: None,
+            unsolicited: Vec::new(),
+            phases: None,
         };
         let control = ControlObservation {
             duration: Duration::from_millis(1500), // 75% of attack
@@ -1491,19 +2473,67 @@ mod tests {
             body_length: 13,
             header_fingerprint: ResponseHeaderFingerprint::default(),
             is_connection_timeout: false,
+            length_mismatch: false,
+            unsolicited: false,
         };
         assert!(control_indicates_false_positive(&attack, &control, None));
     }
 
+    #[test]
+    fn control_kept_when_only_attack_body_mismatches_length() {
+        let mut attack = VulnerabilityInfo {
+            status: "HTTP/1.1 200".into(),
+            status_code: Some(200),
+            duration: Duration::from_millis(40),
+            connect: Duration::ZERO,
+            body_length: 5,
+            header_fingerprint: ResponseHeaderFingerprint::default(),
+            is_connection_timeout: false,
+            head_body_queued: false,
+            response_head: String::new(),
+            length_mismatch: Some(LengthMismatch::Truncated {
+                declared: 100,
+                received: 5,
+            }),
+            unsolicited: Vec::new(),
+            phases: None,
+        };
+        let mut control = ControlObservation {
+            duration: Duration::from_millis(40),
+            status_code: Some(200),
+            body_length: 5,
+            header_fingerprint: ResponseHeaderFingerprint::default(),
+            is_connection_timeout: false,
+            length_mismatch: false,
+            unsolicited: false,
+        };
+        assert!(!control_indicates_false_positive(&attack, &control, None));
+        // A control cut short the same way falls back to the timing rule.
+        control.length_mismatch = true;
+        assert!(control_indicates_false_positive(&attack, &control, None));
+
+        let signals =
+            collect_detection_signals(&attack, Duration::from_millis(40), 1000, false, None, None);
+        assert_eq!(signals, vec!["body_truncated:5/100".to_string()]);
+        attack.length_mismatch = None;
+        assert!(control_indicates_false_positive(&attack, &control, None));
+    }
+
     #[test]
     fn control_kept_when_fast_and_different_status() {
         let attack = VulnerabilityInfo {
             status: "HTTP/1.1 504".into(),
             status_code: Some(504),
             duration: Duration::from_millis(2000),
+            connect: Duration::ZERO,
             body_length: 0,
             header_fingerprint: ResponseHeaderFingerprint::default(),
             is_connection_timeout: false,
+            head_body_queued: false,
+            response_head: String::new(),
+            length_mismatch: None,
+            unsolicited: Vec::new(),
+            phases: None,
         };
         let control = ControlObservation {
             duration: Duration::from_millis(50), // 2.5% of attack — different shape
@@ -1511,6 +2541,8 @@ mod tests {
             body_length: 13,
             header_fingerprint: ResponseHeaderFingerprint::default(),
             is_connection_timeout: false,
+            length_mismatch: false,
+            unsolicited: false,
         };
         assert!(!control_indicates_false_positive(&attack, &control, None));
     }
@@ -1552,9 +2584,15 @@ mod tests {
             status: "HTTP/1.1 200".into(),
             status_code: Some(200),
             duration: Duration::from_millis(2000),
+            connect: Duration::ZERO,
             body_length: 50, // small response (e.g., error page)
             header_fingerprint: ResponseHeaderFingerprint::default(),
             is_connection_timeout: false,
+            head_body_queued: false,
+            response_head: String::new(),
+            length_mismatch: None,
+            unsolicited: Vec::new(),
+            phases: None,
         };
         let control = ControlObservation {
             duration: Duration::from_millis(1900), // 95% of attack — very similar timing
@@ -1562,6 +2600,8 @@ mod tests {
             body_length: 5000, // large response (normal content)
             header_fingerprint: ResponseHeaderFingerprint::default(),
             is_connection_timeout: false,
+            length_mismatch: false,
+            unsolicited: false,
         };
         assert!(!control_indicates_false_positive(&attack, &control, None));
     }
@@ -1620,9 +2660,15 @@ mod tests {
             status: "HTTP/1.1 200".into(),
             status_code: Some(200),
             duration: Duration::from_millis(2000),
+            connect: Duration::ZERO,
             body_length: 13,
             header_fingerprint: ResponseHeaderFingerprint::default(),
             is_connection_timeout: false,
+            head_body_queued: false,
+            response_head: String::new(),
+            length_mismatch: None,
+            unsolicited: Vec::new(),
+            phases: None,
         };
         // Noisy baseline, timing-only signal → Low.
         assert_eq!(compute_confidence(&info, 1200, true), Confidence::Low);
@@ -1634,9 +2680,15 @@ mod tests {
             status: "HTTP/1.1 504".into(),
             status_code: Some(504),
             duration: Duration::from_millis(2000),
+            connect: Duration::ZERO,
             body_length: 0,
             header_fingerprint: ResponseHeaderFingerprint::default(),
             is_connection_timeout: false,
+            head_body_queued: false,
+            response_head: String::new(),
+            length_mismatch: None,
+            unsolicited: Vec::new(),
+            phases: None,
         };
         // 504 + timing anomaly is High regardless of baseline noise.
         assert_eq!(compute_confidence(&info, 1200, true), Confidence::High);
@@ -1648,9 +2700,15 @@ mod tests {
             status: "HTTP/1.1 200".into(),
             status_code: Some(200),
             duration: Duration::from_millis(5000),
+            connect: Duration::ZERO,
             body_length: 13,
             header_fingerprint: ResponseHeaderFingerprint::default(),
             is_connection_timeout: false,
+            head_body_queued: false,
+            response_head: String::new(),
+            length_mismatch: None,
+            unsolicited: Vec::new(),
+            phases: None,
         };
         // 5000ms > 1200*2=2400 AND > MIN_DELAY_MS*2=2000 → extreme → High even
         // on noisy baseline.
@@ -1660,7 +2718,7 @@ mod tests {
     #[test]
     fn header_fingerprint_extracts_known_fields() {
         let r = "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nServer: nginx\r\nContent-Length: 123\r\nX-Other: ignore\r\n\r\nbody";
-        let fp = ResponseHeaderFingerprint::from_response(r);
+        let fp = fingerprint(r);
         assert_eq!(fp.content_type.as_deref(), Some("text/html"));
         assert_eq!(fp.server.as_deref(), Some("nginx"));
         assert_eq!(fp.content_length.as_deref(), Some("123"));
@@ -1668,10 +2726,10 @@ mod tests {
 
     #[test]
     fn header_fingerprint_divergence_count() {
-        let a = ResponseHeaderFingerprint::from_response(
+        let a = fingerprint(
             "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nServer: nginx\r\nContent-Length: 100\r\n\r\nb",
         );
-        let b = ResponseHeaderFingerprint::from_response(
+        let b = fingerprint(
             "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nServer: nginx\r\nContent-Length: 99\r\n\r\nb",
         );
         // content-type differs, content-length differs, server same → 2.
@@ -1686,20 +2744,28 @@ mod tests {
             status: "HTTP/1.1 200".into(),
             status_code: Some(200),
             duration: Duration::from_millis(2000),
+            connect: Duration::ZERO,
             body_length: 200,
-            header_fingerprint: ResponseHeaderFingerprint::from_response(
+            header_fingerprint: fingerprint(
                 "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nServer: backend-v2\r\nContent-Length: 200\r\n\r\n",
             ),
             is_connection_timeout: false,
+            head_body_queued: false,
+            response_head: String::new(),
+            length_mismatch: None,
+            unsolicited: Vec::new(),
+            phases: None,
         };
         let control = ControlObservation {
             duration: Duration::from_millis(1900),
             status_code: Some(200),
             body_length: 210,
-            header_fingerprint: ResponseHeaderFingerprint::from_response(
+            header_fingerprint: fingerprint(
                 "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nServer: proxy-edge\r\nContent-Length: 210\r\n\r\n",
             ),
             is_connection_timeout: false,
+            length_mismatch: false,
+            unsolicited: false,
         };
         // bodies are 200 vs 210 (95% similar — not divergent). Headers differ
         // in content-type, server, content-length (3 fields) → ≥2 → escape.
@@ -1714,20 +2780,28 @@ mod tests {
             status: "HTTP/1.1 200".into(),
             status_code: Some(200),
             duration: Duration::from_millis(2000),
+            connect: Duration::ZERO,
             body_length: 13,
-            header_fingerprint: ResponseHeaderFingerprint::from_response(
+            header_fingerprint: fingerprint(
                 "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nServer: nginx\r\nContent-Length: 13\r\n\r\n",
             ),
             is_connection_timeout: false,
+            head_body_queued: false,
+            response_head: String::new(),
+            length_mismatch: None,
+            unsolicited: Vec::new(),
+            phases: None,
         };
         let control = ControlObservation {
             duration: Duration::from_millis(1800),
             status_code: Some(200),
             body_length: 13,
-            header_fingerprint: ResponseHeaderFingerprint::from_response(
+            header_fingerprint: fingerprint(
                 "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nServer: nginx-alt\r\nContent-Length: 13\r\n\r\n",
             ),
             is_connection_timeout: false,
+            length_mismatch: false,
+            unsolicited: false,
         };
         assert!(control_indicates_false_positive(&attack, &control, None));
     }
@@ -1738,9 +2812,15 @@ mod tests {
             status: "HTTP/1.1 200".into(),
             status_code: Some(200),
             duration: Duration::from_millis(2000),
+            connect: Duration::ZERO,
             body_length: 13,
             header_fingerprint: ResponseHeaderFingerprint::default(),
             is_connection_timeout: false,
+            head_body_queued: false,
+            response_head: String::new(),
+            length_mismatch: None,
+            unsolicited: Vec::new(),
+            phases: None,
         };
         let control = ControlObservation {
             duration: Duration::from_millis(1900), // very similar timing
@@ -1748,6 +2828,8 @@ mod tests {
             body_length: 13,
             header_fingerprint: ResponseHeaderFingerprint::default(),
             is_connection_timeout: false,
+            length_mismatch: false,
+            unsolicited: false,
         };
         let followup = FollowupObservation {
             diverging: 2,
@@ -1769,9 +2851,15 @@ mod tests {
             status: "HTTP/1.1 200".into(),
             status_code: Some(200),
             duration: Duration::from_millis(2000),
+            connect: Duration::ZERO,
             body_length: 13,
             header_fingerprint: ResponseHeaderFingerprint::default(),
             is_connection_timeout: false,
+            head_body_queued: false,
+            response_head: String::new(),
+            length_mismatch: None,
+            unsolicited: Vec::new(),
+            phases: None,
         };
         let control = ControlObservation {
             duration: Duration::from_millis(1900),
@@ -1779,6 +2867,8 @@ mod tests {
             body_length: 13,
             header_fingerprint: ResponseHeaderFingerprint::default(),
             is_connection_timeout: false,
+            length_mismatch: false,
+            unsolicited: false,
         };
         let followup = FollowupObservation {
             diverging: 0,
@@ -1823,9 +2913,15 @@ mod tests {
             status: "HTTP/1.1 200 OK".into(),
             status_code: Some(200),
             duration: Duration::from_millis(5),
+            connect: Duration::ZERO,
             body_length: 500,
             header_fingerprint: ResponseHeaderFingerprint::default(),
             is_connection_timeout: false,
+            head_body_queued: false,
+            response_head: String::new(),
+            length_mismatch: None,
+            unsolicited: Vec::new(),
+            phases: None,
         };
         let followup = FollowupObservation {
             diverging: 1,
@@ -1870,9 +2966,15 @@ mod tests {
             status: "HTTP/1.1 504".into(),
             status_code: Some(504),
             duration: Duration::from_millis(2000),
+            connect: Duration::ZERO,
             body_length: 100,
             header_fingerprint: ResponseHeaderFingerprint::default(),
             is_connection_timeout: false,
+            head_body_queued: false,
+            response_head: String::new(),
+            length_mismatch: None,
+            unsolicited: Vec::new(),
+            phases: None,
         };
         let control = ControlObservation {
             duration: Duration::from_millis(50),
@@ -1880,6 +2982,8 @@ mod tests {
             body_length: 1000,
             header_fingerprint: ResponseHeaderFingerprint::default(),
             is_connection_timeout: false,
+            length_mismatch: false,
+            unsolicited: false,
         };
         // Both 504 would normally be FP, but body divergence overrides.
         assert!(!control_indicates_false_positive(&attack, &control, None));
@@ -1894,9 +2998,15 @@ mod tests {
             status: "HTTP/1.1 200".into(),
             status_code: Some(200),
             duration: Duration::from_millis(2000),
+            connect: Duration::ZERO,
             body_length: 13,
             header_fingerprint: ResponseHeaderFingerprint::default(),
             is_connection_timeout: false,
+            head_body_queued: false,
+            response_head: String::new(),
+            length_mismatch: None,
+            unsolicited: Vec::new(),
+            phases: None,
         };
         let control = ControlObservation {
             duration: Duration::from_millis(1900), // very similar timing
@@ -1904,6 +3014,8 @@ mod tests {
             body_length: 13,
             header_fingerprint: ResponseHeaderFingerprint::default(),
             is_connection_timeout: false,
+            length_mismatch: false,
+            unsolicited: false,
         };
         let single_flake = FollowupObservation {
             diverging: 1,
@@ -1943,15 +3055,20 @@ mod tests {
     #[test]
     fn aggregate_baseline_tolerates_partial_probe_failure() {
         // A single failed probe among successes must not discard the baseline.
-        let results: Vec<Result<(String, Duration)>> = vec![
+        let timing = |ms| RequestTiming {
+            connect: Duration::from_millis(10),
+            response: Duration::from_millis(ms),
+            ..Default::default()
+        };
+        let results: Vec<Result<(String, RequestTiming)>> = vec![
             Ok((
                 "HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n".to_string(),
-                Duration::from_millis(100),
+                timing(100),
             )),
             Err(SmugglexError::Io("connection reset".into())),
             Ok((
                 "HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n".to_string(),
-                Duration::from_millis(120),
+                timing(120),
             )),
         ];
         let baseline = aggregate_baseline(results).expect("survivors should yield a baseline");
@@ -1965,13 +3082,36 @@ mod tests {
 
     #[test]
     fn aggregate_baseline_errors_only_when_all_probes_fail() {
-        let results: Vec<Result<(String, Duration)>> = vec![
+        let results: Vec<Result<(String, RequestTiming)>> = vec![
             Err(SmugglexError::Io("reset".into())),
-            Err(SmugglexError::Timeout("timed out".into())),
+            Err(SmugglexError::timeout(TimeoutPhase::FirstByte, "timed out")),
         ];
         assert!(
             aggregate_baseline(results).is_err(),
             "no surviving samples → error"
         );
     }
+
+    #[test]
+    fn aggregate_baseline_keeps_handshake_out_of_thresholds() {
+        // A 900ms TLS handshake in front of a 50ms response must not raise the
+        // timing baseline that attacks are compared against.
+        let results: Vec<Result<(String, RequestTiming)>> = [(900, 50), (1200, 60), (800, 40)]
+            .into_iter()
+            .map(|(connect, response)| {
+                Ok((
+                    "HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n".to_string(),
+                    RequestTiming {
+                        connect: Duration::from_millis(connect),
+                        response: Duration::from_millis(response),
+                        ..Default::default()
+                    },
+                ))
+            })
+            .collect();
+        let baseline = aggregate_baseline(results).unwrap();
+        assert_eq!(baseline.duration, Duration::from_millis(50));
+        assert_eq!(baseline.max_duration, Duration::from_millis(60));
+        assert_eq!(baseline.connect, Duration::from_millis(900));
+    }
 }
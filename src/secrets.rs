@@ -0,0 +1,99 @@
+//! `@env:NAME` and `@file:PATH` indirection in `-H` header values.
+//!
+//! `-H "Authorization: Bearer @env:API_TOKEN"` keeps the token out of shell
+//! history and process listings: the header is stored as typed and the
+//! reference is only resolved when a request is built.
+
+use crate::error::{Result, SmugglexError};
+
+/// Prefix of an environment variable reference.
+pub const ENV_PREFIX: &str = "@env:";
+/// Prefix of a file reference.
+pub const FILE_PREFIX: &str = "@file:";
+
+/// Whether `header`'s value holds an `@env:` or `@file:` reference.
+pub fn has_secret_reference(header: &str) -> bool {
+    header
+        .split_once(':')
+        .is_some_and(|(_, value)| value.contains(ENV_PREFIX) || value.contains(FILE_PREFIX))
+}
+
+/// `header` with every `@env:NAME` in its value replaced by the variable and
+/// every `@file:PATH` by the file's contents (trailing line break removed).
+/// A reference runs to the next whitespace. Headers without one are returned
+/// unchanged.
+pub fn expand_header(header: &str) -> Result<String> {
+    let Some((name, value)) = header.split_once(':') else {
+        return Ok(header.to_string());
+    };
+    if !has_secret_reference(header) {
+        return Ok(header.to_string());
+    }
+    let label = format!("{} header", name.trim());
+    Ok(format!("{}:{}", name, expand_value(value, &label)?))
+}
+
+/// `value` with its `@env:`/`@file:` references resolved; `label` names the
+/// option or header in error messages.
+pub fn expand_value(value: &str, label: &str) -> Result<String> {
+    let mut expanded = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = [ENV_PREFIX, FILE_PREFIX]
+        .iter()
+        .filter_map(|prefix| rest.find(prefix))
+        .min()
+    {
+        expanded.push_str(&rest[..start]);
+        let reference = &rest[start..];
+        let end = reference
+            .find(char::is_whitespace)
+            .unwrap_or(reference.len());
+        expanded.push_str(&resolve(&reference[..end], label)?);
+        rest = &reference[end..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// [`expand_header`] over every header; fails on the first reference that
+/// does not resolve.
+pub fn expand_headers(headers: &[String]) -> Result<Vec<String>> {
+    headers.iter().map(|h| expand_header(h)).collect()
+}
+
+/// Names of the headers whose values come from a reference, so their
+/// resolved values can be redacted.
+pub fn secret_header_names(headers: &[String]) -> Vec<String> {
+    headers
+        .iter()
+        .filter(|h| has_secret_reference(h))
+        .filter_map(|h| h.split_once(':'))
+        .map(|(name, _)| name.trim().to_string())
+        .collect()
+}
+
+fn resolve(reference: &str, label: &str) -> Result<String> {
+    let value = if let Some(var) = reference.strip_prefix(ENV_PREFIX) {
+        std::env::var(var).map_err(|_| {
+            SmugglexError::Config(format!(
+                "{}: environment variable '{}' is not set",
+                label, var
+            ))
+        })?
+    } else {
+        let path = &reference[FILE_PREFIX.len()..];
+        std::fs::read_to_string(path)
+            .map(|s| s.trim_end_matches(['\r', '\n']).to_string())
+            .map_err(|e| {
+                SmugglexError::Config(format!("{}: cannot read '{}': {}", label, path, e))
+            })?
+    };
+    // A line break would end the header and inject whatever follows.
+    if value.contains(['\r', '\n']) {
+        return Err(SmugglexError::Config(format!(
+            "{}: {} contains a line break",
+            label, reference
+        )));
+    }
+    Ok(value)
+}
@@ -0,0 +1,195 @@
+//! `--syslog udp://host:514`: one RFC 5424 event per finding and per scanned
+//! target, so a SIEM ingests scan results next to the rest of its security
+//! telemetry.
+//!
+//! ```text
+//! <11>1 2024-01-01T00:00:00.000Z scanner smugglex 4242 finding - {"event":"finding","target":"https://target.com/","check":"cl-te",...}
+//! <14>1 2024-01-01T00:00:00.000Z scanner smugglex 4242 scan - {"event":"scan","target":"https://target.com/","vulnerable":true,...}
+//! ```
+//!
+//! The message is a JSON object; structured data is left empty (`-`). Events
+//! use the `user` facility. Over `tcp://` each event is framed with its
+//! length (RFC 6587 octet counting).
+
+use std::time::Duration;
+
+use chrono::{SecondsFormat, Utc};
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpStream, UdpSocket};
+use url::Url;
+
+use crate::error::{Result, SmugglexError, TimeoutPhase};
+use crate::model::{Confidence, ScanResults};
+
+/// Facility of every event: `user` (1).
+const FACILITY: u8 = 1;
+
+/// RFC 5424 severity of a high-confidence finding: error.
+const SEVERITY_ERROR: u8 = 3;
+/// Severity of a medium- or low-confidence finding and of a failed scan:
+/// warning.
+const SEVERITY_WARNING: u8 = 4;
+/// Severity of a scan that completed: informational.
+const SEVERITY_INFO: u8 = 6;
+
+/// How events reach the collector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyslogTransport {
+    /// One datagram per event
+    Udp,
+    /// One stream, events framed by octet counting
+    Tcp,
+}
+
+/// The `--syslog` collector.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyslogSink {
+    pub transport: SyslogTransport,
+    pub host: String,
+    pub port: u16,
+}
+
+impl SyslogSink {
+    /// Parse `udp://host[:port]` or `tcp://host[:port]`; the port defaults
+    /// to 514.
+    pub fn parse(value: &str) -> Result<Self> {
+        let url = Url::parse(value)
+            .map_err(|e| SmugglexError::Config(format!("invalid --syslog URL: {}", e)))?;
+        let transport = match url.scheme() {
+            "udp" => SyslogTransport::Udp,
+            "tcp" => SyslogTransport::Tcp,
+            other => {
+                return Err(SmugglexError::Config(format!(
+                    "--syslog must be a udp:// or tcp:// URL, not {}://",
+                    other
+                )));
+            }
+        };
+        let host = url
+            .host_str()
+            .filter(|host| !host.is_empty())
+            .ok_or_else(|| SmugglexError::Config("--syslog URL has no host".to_string()))?;
+        Ok(SyslogSink {
+            transport,
+            host: host
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .to_string(),
+            port: url.port().unwrap_or(514),
+        })
+    }
+}
+
+/// Host name for the event header: `$HOSTNAME`, else `/etc/hostname`, else
+/// the nil value `-`.
+pub fn local_hostname() -> String {
+    std::env::var("HOSTNAME")
+        .ok()
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty() && name.chars().all(|c| c.is_ascii_graphic()))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// One RFC 5424 line: header fields, no structured data, `message` as the
+/// MSG part.
+fn rfc5424(severity: u8, msgid: &str, hostname: &str, message: &serde_json::Value) -> String {
+    format!(
+        "<{}>1 {} {} smugglex {} {} - {}",
+        FACILITY * 8 + severity,
+        Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+        hostname,
+        std::process::id(),
+        msgid,
+        message
+    )
+}
+
+/// The events for `results`: each target's findings (`finding`), then its
+/// completion (`scan`), which also carries the error of a failed scan.
+pub fn syslog_events(results: &[ScanResults], hostname: &str) -> Vec<String> {
+    let mut events = Vec::new();
+    for scan in results {
+        let findings: Vec<_> = scan.checks.iter().filter(|c| c.vulnerable).collect();
+        for check in &findings {
+            let severity = match check.confidence {
+                Some(Confidence::Medium) | Some(Confidence::Low) => SEVERITY_WARNING,
+                _ => SEVERITY_ERROR,
+            };
+            let message = serde_json::json!({
+                "event": "finding",
+                "target": scan.target,
+                "method": scan.method,
+                "check": check.check_type,
+                "payload_name": check.payload_name,
+                "technique": check.technique.as_ref().map(|t| &t.id),
+                "confidence": check.confidence,
+                "evidence_score": check.evidence.as_ref().map(|e| e.score),
+                "signals": check.detection_signals,
+                "attack_status": check.attack_status,
+                "timestamp": check.timestamp,
+            });
+            events.push(rfc5424(severity, "finding", hostname, &message));
+        }
+        let severity = if scan.error.is_some() {
+            SEVERITY_WARNING
+        } else {
+            SEVERITY_INFO
+        };
+        let message = serde_json::json!({
+            "event": "scan",
+            "target": scan.target,
+            "vulnerable": !findings.is_empty(),
+            "findings": findings.len(),
+            "checks": scan.checks.len(),
+            "duration_ms": scan.duration_ms,
+            "requests_sent": scan.requests_sent,
+            "error": scan.error,
+            "timestamp": scan.timestamp,
+        });
+        events.push(rfc5424(severity, "scan", hostname, &message));
+    }
+    events
+}
+
+async fn send(sink: &SyslogSink, events: &[String]) -> Result<()> {
+    let connect_error =
+        |e: std::io::Error| SmugglexError::Connect(format!("{}:{}: {}", sink.host, sink.port, e));
+    match sink.transport {
+        SyslogTransport::Udp => {
+            let address = tokio::net::lookup_host((sink.host.as_str(), sink.port))
+                .await
+                .map_err(|e| SmugglexError::Dns(format!("{}: {}", sink.host, e)))?
+                .next()
+                .ok_or_else(|| SmugglexError::Dns(format!("{}: no address", sink.host)))?;
+            let local = if address.is_ipv4() {
+                "0.0.0.0:0"
+            } else {
+                "[::]:0"
+            };
+            let socket = UdpSocket::bind(local).await.map_err(connect_error)?;
+            for event in events {
+                socket.send_to(event.as_bytes(), address).await?;
+            }
+        }
+        SyslogTransport::Tcp => {
+            let mut stream = TcpStream::connect((sink.host.as_str(), sink.port))
+                .await
+                .map_err(connect_error)?;
+            let mut framed = String::new();
+            for event in events {
+                framed.push_str(&format!("{} {}", event.len(), event));
+            }
+            stream.write_all(framed.as_bytes()).await?;
+            stream.shutdown().await?;
+        }
+    }
+    Ok(())
+}
+
+/// Send `events` to `sink`, giving up after `timeout` seconds.
+pub async fn send_syslog(sink: &SyslogSink, events: &[String], timeout: u64) -> Result<()> {
+    tokio::time::timeout(Duration::from_secs(timeout), send(sink, events))
+        .await
+        .map_err(|_| SmugglexError::timeout(TimeoutPhase::Request, "syslog delivery timed out"))?
+}
@@ -0,0 +1,261 @@
+//! Server products, versions and TLS certificate details gathered from a
+//! scan's own traffic into a [`TargetInfo`].
+//!
+//! Every response the scan receives is folded in: its `Server` and
+//! `X-Powered-By` values, and product/version strings from error pages. The
+//! first TLS handshake contributes the leaf certificate, decoded by a small
+//! DER reader that only walks the fields reported.
+
+use std::cell::RefCell;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::model::{CertificateInfo, TargetInfo};
+use crate::utils::parse_status_code;
+
+/// Values kept per list, so a target rotating its `Server` header cannot
+/// grow the results without bound.
+const MAX_VALUES: usize = 10;
+
+/// Error-page bytes searched for version strings.
+const MAX_ERROR_PAGE_BYTES: usize = 64 * 1024;
+
+/// `Product/1.2.3` for products whose default error pages name themselves.
+static ERROR_PAGE_VERSION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(Apache Tomcat|Apache|nginx|openresty|Microsoft-IIS|Microsoft-HTTPAPI|LiteSpeed|lighttpd|Jetty|Caddy|Envoy|HAProxy|Varnish|Squid|Werkzeug|gunicorn|Kestrel|PHP|OpenSSL)/(\d+(?:\.\d+)+)",
+    )
+    .expect("valid regex")
+});
+
+fn push_distinct(values: &mut Vec<String>, value: &str) {
+    let value = value.trim();
+    if !value.is_empty() && values.len() < MAX_VALUES && !values.iter().any(|v| v == value) {
+        values.push(value.to_string());
+    }
+}
+
+/// Fold the `Server` / `X-Powered-By` values of `response` and, for a 4xx/5xx
+/// response, the version strings in its body into `info`.
+pub fn merge_response(info: &mut TargetInfo, response: &str) {
+    let (head, body) = response.split_once("\r\n\r\n").unwrap_or((response, ""));
+    for line in head.lines().skip(1) {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        if name.trim().eq_ignore_ascii_case("server") {
+            push_distinct(&mut info.server, value);
+        } else if name.trim().eq_ignore_ascii_case("x-powered-by") {
+            push_distinct(&mut info.powered_by, value);
+        }
+    }
+    let status = head.lines().next().and_then(parse_status_code);
+    if status.is_some_and(|s| s >= 400) {
+        let end = body.floor_char_boundary(MAX_ERROR_PAGE_BYTES);
+        for m in ERROR_PAGE_VERSION.find_iter(&body[..end]) {
+            push_distinct(&mut info.error_page_versions, m.as_str());
+        }
+    }
+}
+
+tokio::task_local! {
+    static OBSERVED_INFO: RefCell<TargetInfo>;
+}
+
+/// Fold `response` into the enclosing [`observe_target_info`] scope; a no-op
+/// outside one.
+pub fn record_response(response: &str) {
+    let _ = OBSERVED_INFO.try_with(|info| merge_response(&mut info.borrow_mut(), response));
+}
+
+/// Record the leaf certificate (`der`) of a TLS handshake, unless one was
+/// already recorded in the enclosing [`observe_target_info`] scope.
+pub fn record_certificate(der: &[u8]) {
+    let _ = OBSERVED_INFO.try_with(|info| {
+        let mut info = info.borrow_mut();
+        if info.certificate.is_none() {
+            info.certificate = parse_certificate(der);
+        }
+    });
+}
+
+/// What the enclosing [`observe_target_info`] scope saw; `None` when nothing
+/// was recorded.
+pub fn observed_target_info() -> Option<TargetInfo> {
+    OBSERVED_INFO
+        .try_with(|info| info.borrow().clone())
+        .ok()
+        .filter(|info| !info.is_empty())
+}
+
+/// Run `scan` with every response and certificate it receives folded into one
+/// [`TargetInfo`], read back with [`observed_target_info`].
+pub fn observe_target_info<F: Future>(scan: F) -> impl Future<Output = F::Output> {
+    OBSERVED_INFO.scope(RefCell::new(TargetInfo::default()), scan)
+}
+
+/// One DER element.
+struct Der<'a> {
+    tag: u8,
+    body: &'a [u8],
+}
+
+/// Split the first element off `input`.
+fn der_next(input: &[u8]) -> Option<(Der<'_>, &[u8])> {
+    let (&tag, rest) = input.split_first()?;
+    let (&first, rest) = rest.split_first()?;
+    let (len, rest) = if first < 0x80 {
+        (first as usize, rest)
+    } else {
+        let n = (first & 0x7f) as usize;
+        if n == 0 || n > 4 || rest.len() < n {
+            return None;
+        }
+        let len = rest[..n]
+            .iter()
+            .fold(0usize, |acc, b| (acc << 8) | *b as usize);
+        (len, &rest[n..])
+    };
+    (rest.len() >= len).then(|| {
+        (
+            Der {
+                tag,
+                body: &rest[..len],
+            },
+            &rest[len..],
+        )
+    })
+}
+
+/// The elements inside a constructed value; stops at the first malformed one.
+fn der_children(mut body: &[u8]) -> Vec<Der<'_>> {
+    let mut children = Vec::new();
+    while let Some((child, rest)) = der_next(body) {
+        children.push(child);
+        body = rest;
+    }
+    children
+}
+
+/// `CN=example.com, O=Example` from a Name; attributes other than the common
+/// ones are left out.
+fn format_name(name: &[u8]) -> String {
+    der_children(name)
+        .iter()
+        .flat_map(|rdn| der_children(rdn.body))
+        .filter_map(|attribute| {
+            let parts = der_children(attribute.body);
+            let label = match parts.first()?.body {
+                [0x55, 0x04, 0x03] => "CN",
+                [0x55, 0x04, 0x06] => "C",
+                [0x55, 0x04, 0x07] => "L",
+                [0x55, 0x04, 0x08] => "ST",
+                [0x55, 0x04, 0x0a] => "O",
+                [0x55, 0x04, 0x0b] => "OU",
+                _ => return None,
+            };
+            Some(format!(
+                "{}={}",
+                label,
+                String::from_utf8_lossy(parts.get(1)?.body)
+            ))
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// RFC 3339 form of a UTCTime or GeneralizedTime.
+fn format_time(time: &Der) -> Option<String> {
+    let text = std::str::from_utf8(time.body).ok()?.strip_suffix('Z')?;
+    let full = match time.tag {
+        0x17 => {
+            let year: u32 = text.get(..2)?.parse().ok()?;
+            format!("{}{}", if year >= 50 { "19" } else { "20" }, text)
+        }
+        0x18 => text.to_string(),
+        _ => return None,
+    };
+    let digits = full.get(..14)?;
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some(format!(
+        "{}-{}-{}T{}:{}:{}Z",
+        &digits[0..4],
+        &digits[4..6],
+        &digits[6..8],
+        &digits[8..10],
+        &digits[10..12],
+        &digits[12..14]
+    ))
+}
+
+/// DNS names and IP addresses of the subjectAltName extension, given the
+/// `[3]` extensions field.
+fn subject_alt_names(extensions: &[u8]) -> Vec<String> {
+    let Some(list) = der_children(extensions).into_iter().next() else {
+        return Vec::new();
+    };
+    for extension in der_children(list.body) {
+        let parts = der_children(extension.body);
+        if parts
+            .first()
+            .is_none_or(|oid| oid.body != [0x55, 0x1d, 0x11])
+        {
+            continue;
+        }
+        let Some(value) = parts.iter().rev().find(|part| part.tag == 0x04) else {
+            return Vec::new();
+        };
+        let Some(names) = der_children(value.body).into_iter().next() else {
+            return Vec::new();
+        };
+        return der_children(names.body)
+            .iter()
+            .filter_map(|name| match (name.tag, name.body.len()) {
+                (0x82, _) => Some(String::from_utf8_lossy(name.body).into_owned()),
+                (0x87, 4) => Some(Ipv4Addr::from(<[u8; 4]>::try_from(name.body).ok()?).to_string()),
+                (0x87, 16) => {
+                    Some(Ipv6Addr::from(<[u8; 16]>::try_from(name.body).ok()?).to_string())
+                }
+                _ => None,
+            })
+            .collect();
+    }
+    Vec::new()
+}
+
+/// Subject, issuer, serial, validity and subjectAltName of a DER-encoded
+/// X.509 certificate; `None` when it does not parse.
+pub fn parse_certificate(der: &[u8]) -> Option<CertificateInfo> {
+    let (certificate, _) = der_next(der)?;
+    let tbs = der_children(certificate.body).into_iter().next()?;
+    let mut fields = der_children(tbs.body).into_iter().peekable();
+    // Explicit `[0]` version, absent for v1 certificates.
+    if fields.peek()?.tag == 0xa0 {
+        fields.next();
+    }
+    let serial = fields.next()?;
+    let _signature = fields.next()?;
+    let issuer = fields.next()?;
+    let validity = der_children(fields.next()?.body);
+    let subject = fields.next()?;
+    let san = fields
+        .find(|field| field.tag == 0xa3)
+        .map(|extensions| subject_alt_names(extensions.body))
+        .unwrap_or_default();
+    let serial = match serial.body {
+        [0, rest @ ..] if !rest.is_empty() => rest,
+        bytes => bytes,
+    };
+    Some(CertificateInfo {
+        subject: format_name(subject.body),
+        issuer: format_name(issuer.body),
+        serial: serial.iter().map(|b| format!("{:02x}", b)).collect(),
+        not_before: format_time(validity.first()?)?,
+        not_after: format_time(validity.get(1)?)?,
+        san,
+    })
+}
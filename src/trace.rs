@@ -0,0 +1,257 @@
+use crate::http::send_request;
+use crate::payloads::get_te_header_variations;
+use crate::utils::parse_status_code;
+
+/// Content-Length spellings probed alongside the Transfer-Encoding
+/// variations. Values are always `0` so the TRACE carries no body; payloads
+/// are matched on their digit-free shape (see [`TraceReport::rank`]).
+const TRACE_CL_VARIANTS: [&str; 9] = [
+    "Content-Length: +0",
+    "Content-Length:  0",
+    "Content-Length:\t0",
+    "Content-Length: 0 ",
+    "Content-Length : 0",
+    "content-length: 0",
+    "Content-Length: 0, 0",
+    "Content-Length: 0,0",
+    " Content-Length: 0",
+];
+
+/// What the echoing hop received in place of a probed header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceFate {
+    /// Echoed byte-for-byte: the obfuscation survives to the echoing hop.
+    Verbatim,
+    /// Echoed as a rewritten header line (e.g. whitespace trimmed).
+    Normalized(String),
+    /// Echoed without the header.
+    Stripped,
+    /// The TRACE was not echoed, with the response status when there was one.
+    Rejected(Option<u16>),
+}
+
+/// One probed header and its fate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceVariant {
+    /// Header line as sent
+    pub header: String,
+    pub fate: TraceFate,
+}
+
+/// Result of [`trace_probe`]: how the chain rewrote every probed header.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TraceReport {
+    pub variants: Vec<TraceVariant>,
+}
+
+impl TraceReport {
+    /// Number of probed headers whose fate matches `pred`.
+    pub fn count(&self, pred: impl Fn(&TraceFate) -> bool) -> usize {
+        self.variants.iter().filter(|v| pred(&v.fate)).count()
+    }
+
+    /// Scheduling rank of a payload, lowest first: `0` when it carries a
+    /// header that survived verbatim, `1` when it carries no probed header,
+    /// `2` when its header was normalized, `3` when it was stripped or
+    /// rejected. The longest probed header found in the request decides, so
+    /// a plain `Transfer-Encoding: chunked` does not shadow an obfuscated
+    /// variant containing it; digits are ignored so `Content-Length: +06`
+    /// matches the probed `Content-Length: +0`.
+    pub fn rank(&self, request: &str) -> u8 {
+        let request = digit_shape(request);
+        let matched = self
+            .variants
+            .iter()
+            .filter(|v| request.contains(&digit_shape(&v.header)))
+            .max_by_key(|v| v.header.len());
+        match matched.map(|v| &v.fate) {
+            Some(TraceFate::Verbatim) => 0,
+            None => 1,
+            Some(TraceFate::Normalized(_)) => 2,
+            Some(TraceFate::Stripped | TraceFate::Rejected(_)) => 3,
+        }
+    }
+}
+
+/// Every header probed by [`trace_probe`]: the Transfer-Encoding variations
+/// used by the CL.TE/TE.CL checks, then the Content-Length spellings.
+pub fn trace_variants() -> Vec<String> {
+    let mut variants = get_te_header_variations();
+    variants.extend(TRACE_CL_VARIANTS.iter().map(|s| s.to_string()));
+    variants
+}
+
+/// Collapse every run of ASCII digits to a single `0`.
+fn digit_shape(s: &str) -> String {
+    let mut shape = String::with_capacity(s.len());
+    let mut in_digits = false;
+    for c in s.chars() {
+        if c.is_ascii_digit() {
+            if !in_digits {
+                shape.push('0');
+            }
+            in_digits = true;
+        } else {
+            shape.push(c);
+            in_digits = false;
+        }
+    }
+    shape
+}
+
+/// TRACE request carrying `header`. A Transfer-Encoding header gets an empty
+/// chunked body so a hop that honors it does not wait for one.
+fn trace_request(host: &str, path: &str, header: Option<&str>) -> String {
+    let (header, body) = match header {
+        Some(h) if h.to_ascii_lowercase().contains("transfer-encoding") => {
+            (format!("{}\r\n", h), "0\r\n\r\n")
+        }
+        Some(h) => (format!("{}\r\n", h), ""),
+        None => (String::new(), ""),
+    };
+    format!(
+        "TRACE {} HTTP/1.1\r\nHost: {}\r\n{}Connection: close\r\n\r\n{}",
+        path, host, header, body
+    )
+}
+
+/// Header section of the request echoed in a `200` TRACE response, `None`
+/// when the response is not an echo.
+fn echoed_head(response: &str) -> Option<&str> {
+    if parse_status_code(response.lines().next().unwrap_or("")) != Some(200) {
+        return None;
+    }
+    let (_, body) = response.split_once("\r\n\r\n")?;
+    let start = body.find("TRACE ")?;
+    let echo = &body[start..];
+    Some(echo.split_once("\r\n\r\n").map_or(echo, |(head, _)| head))
+}
+
+/// Classify the TRACE `response` to a request carrying `header`, against the
+/// echo of the plain TRACE (`baseline`). A rewritten header is an echoed
+/// line missing from the baseline that names the same framing header, so
+/// hop-added per-request lines (request IDs) are not mistaken for it.
+pub fn classify_echo(header: &str, baseline: &str, response: &str) -> TraceFate {
+    let Some(echo) = echoed_head(response) else {
+        return TraceFate::Rejected(parse_status_code(response.lines().next().unwrap_or("")));
+    };
+    if echo.contains(header) {
+        return TraceFate::Verbatim;
+    }
+    let keywords: &[&str] = if header.to_ascii_lowercase().contains("content-length") {
+        &["length"]
+    } else {
+        &["transfer", "chunked"]
+    };
+    let baseline_lines: Vec<&str> = baseline.split("\r\n").collect();
+    echo.split("\r\n")
+        .skip(1)
+        .filter(|line| !baseline_lines.contains(line))
+        .find(|line| {
+            let lower = line.to_ascii_lowercase();
+            keywords.iter().any(|k| lower.contains(k))
+        })
+        .map_or(TraceFate::Stripped, |line| {
+            TraceFate::Normalized(line.to_string())
+        })
+}
+
+/// Probe whether TRACE is echoed anywhere in the chain and, if so, send one
+/// TRACE per [`trace_variants`] header to see how it arrives at the echoing
+/// hop. `None` when the plain TRACE is not echoed (TRACE not permitted);
+/// a variant whose request fails is recorded as rejected without a status.
+pub async fn trace_probe(
+    host: &str,
+    port: u16,
+    path: &str,
+    timeout: u64,
+    verbose: bool,
+    use_tls: bool,
+) -> Option<TraceReport> {
+    let request = trace_request(host, path, None);
+    let (response, _) = send_request(host, port, &request, timeout, verbose, use_tls)
+        .await
+        .ok()?;
+    let baseline = echoed_head(&response)?.to_string();
+
+    let mut report = TraceReport::default();
+    for header in trace_variants() {
+        let request = trace_request(host, path, Some(&header));
+        let fate = match send_request(host, port, &request, timeout, verbose, use_tls).await {
+            Ok((response, _)) => classify_echo(&header, &baseline, &response),
+            Err(_) => TraceFate::Rejected(None),
+        };
+        report.variants.push(TraceVariant { header, fate });
+    }
+    Some(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASELINE: &str = "TRACE / HTTP/1.1\r\nHost: example.com\r\nConnection: close";
+
+    fn echo(lines: &str) -> String {
+        format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: message/http\r\n\r\n\
+             TRACE / HTTP/1.1\r\nHost: example.com\r\n{}Connection: close\r\n\r\n",
+            lines
+        )
+    }
+
+    #[test]
+    fn test_classify_echo() {
+        let header = "Transfer-Encoding : chunked";
+        assert_eq!(
+            classify_echo(header, BASELINE, &echo("Transfer-Encoding : chunked\r\n")),
+            TraceFate::Verbatim
+        );
+        assert_eq!(
+            classify_echo(
+                header,
+                BASELINE,
+                &echo("X-Request-Id: 42\r\nTransfer-Encoding: chunked\r\n")
+            ),
+            TraceFate::Normalized("Transfer-Encoding: chunked".to_string())
+        );
+        assert_eq!(
+            classify_echo(header, BASELINE, &echo("X-Request-Id: 42\r\n")),
+            TraceFate::Stripped
+        );
+        assert_eq!(
+            classify_echo(header, BASELINE, "HTTP/1.1 400 Bad Request\r\n\r\n"),
+            TraceFate::Rejected(Some(400))
+        );
+    }
+
+    #[test]
+    fn test_rank_prefers_verbatim_survivors() {
+        let report = TraceReport {
+            variants: vec![
+                TraceVariant {
+                    header: "Transfer-Encoding: chunked".to_string(),
+                    fate: TraceFate::Verbatim,
+                },
+                TraceVariant {
+                    header: " Transfer-Encoding: chunked".to_string(),
+                    fate: TraceFate::Stripped,
+                },
+                TraceVariant {
+                    header: "Content-Length: +0".to_string(),
+                    fate: TraceFate::Normalized("Content-Length: 0".to_string()),
+                },
+            ],
+        };
+        assert_eq!(
+            report.rank("GET / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n"),
+            0
+        );
+        assert_eq!(report.rank("GET / HTTP/1.1\r\nX: y\r\n"), 1);
+        assert_eq!(report.rank("GET / HTTP/1.1\r\nContent-Length: +06\r\n"), 2);
+        assert_eq!(
+            report.rank("GET / HTTP/1.1\r\n Transfer-Encoding: chunked\r\n"),
+            3
+        );
+    }
+}
@@ -0,0 +1,259 @@
+//! Wire transports.
+//!
+//! Every probe smugglex sends is a raw byte string written to a connection and
+//! answered by one response. How that connection is opened and where a
+//! response ends depends on the protocol, so each protocol implements
+//! [`Transport`]:
+//!
+//! | Kind | Connection | Response boundary |
+//! |------|------------|-------------------|
+//! | [`TransportKind::Http1Plain`] | TCP (optionally via `--proxy`) | HTTP/1.x framing |
+//! | [`TransportKind::Http1Tls`] | TLS over TCP (optionally via `--proxy`) | HTTP/1.x framing |
+//! | [`TransportKind::Http2`] | TLS with ALPN `h2`, direct | first HEADERS / RST_STREAM / GOAWAY on stream 1 |
+//! | [`TransportKind::Http3`] | QUIC — not available yet | — |
+//!
+//! Checks declare the transport they need through [`check_transport`], and
+//! callers obtain the implementation with [`TransportKind::transport`], so a new
+//! protocol is added here rather than at every call site.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use rustls::pki_types::ServerName;
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+use tokio_rustls::TlsConnector;
+
+use crate::error::{Result, SmugglexError};
+
+/// A stream that can be read and written.
+pub trait ReadWrite: AsyncRead + AsyncWrite {}
+impl<T: AsyncRead + AsyncWrite> ReadWrite for T {}
+
+/// An open connection, type-erased over TCP and TLS.
+pub type Connection = Box<dyn ReadWrite + Unpin + Send>;
+
+/// Future returned by transport operations.
+pub type TransportFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// The protocols smugglex can send probes over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TransportKind {
+    /// HTTP/1.x over plain TCP
+    Http1Plain,
+    /// HTTP/1.x over TLS
+    Http1Tls,
+    /// HTTP/2 over TLS (ALPN `h2`)
+    Http2,
+    /// HTTP/3 over QUIC
+    Http3,
+}
+
+impl TransportKind {
+    /// The HTTP/1.x transport for a target scheme.
+    pub fn http1(use_tls: bool) -> Self {
+        if use_tls {
+            TransportKind::Http1Tls
+        } else {
+            TransportKind::Http1Plain
+        }
+    }
+
+    /// Short protocol name used in logs.
+    pub fn name(self) -> &'static str {
+        match self {
+            TransportKind::Http1Plain => "http/1.1",
+            TransportKind::Http1Tls => "http/1.1+tls",
+            TransportKind::Http2 => "h2",
+            TransportKind::Http3 => "h3",
+        }
+    }
+
+    /// Whether the transport only works against https targets.
+    pub fn requires_tls(self) -> bool {
+        !matches!(self, TransportKind::Http1Plain)
+    }
+
+    /// Whether the transport honours `--proxy`.
+    pub fn supports_proxy(self) -> bool {
+        matches!(self, TransportKind::Http1Plain | TransportKind::Http1Tls)
+    }
+
+    /// Whether this build can open connections for the transport.
+    pub fn available(self) -> bool {
+        !matches!(self, TransportKind::Http3)
+    }
+
+    /// The implementation for this kind.
+    pub fn transport(self) -> &'static dyn Transport {
+        match self {
+            TransportKind::Http1Plain => &Http1Plain,
+            TransportKind::Http1Tls => &Http1Tls,
+            TransportKind::Http2 => &Http2,
+            TransportKind::Http3 => &Http3,
+        }
+    }
+}
+
+/// The transport a check needs. Payload-string checks (including `h2c` and the
+/// `h2` pseudo-header payloads, which are HTTP/1.1 requests) ride HTTP/1.x for
+/// the target's scheme; `h2-downgrade` speaks real HTTP/2.
+pub fn check_transport(check_name: &str, use_tls: bool) -> TransportKind {
+    match check_name {
+        "h2-downgrade" => TransportKind::Http2,
+        _ => TransportKind::http1(use_tls),
+    }
+}
+
+/// A way of delivering raw probe bytes and reading back one response.
+pub trait Transport: Send + Sync {
+    /// Which protocol this is.
+    fn kind(&self) -> TransportKind;
+
+    /// Open a connection ready for this protocol's framing.
+    fn connect<'a>(&'a self, host: &'a str, port: u16) -> TransportFuture<'a, Result<Connection>>;
+
+    /// Read exactly one response from `conn`, stopping at the protocol's
+    /// message boundary rather than waiting for EOF.
+    fn read_response<'a>(
+        &'a self,
+        conn: &'a mut Connection,
+    ) -> TransportFuture<'a, Result<Vec<u8>>>;
+
+    /// Open a connection, write `request` verbatim, and read one response.
+    fn exchange<'a>(
+        &'a self,
+        host: &'a str,
+        port: u16,
+        request: &'a [u8],
+    ) -> TransportFuture<'a, Result<Vec<u8>>> {
+        Box::pin(async move {
+            self.exchange_timed(host, port, request)
+                .await
+                .map(|(response, _)| response)
+        })
+    }
+
+    /// [`Transport::exchange`], also returning how long opening the
+    /// connection (TCP, proxy CONNECT and TLS) took.
+    fn exchange_timed<'a>(
+        &'a self,
+        host: &'a str,
+        port: u16,
+        request: &'a [u8],
+    ) -> TransportFuture<'a, Result<(Vec<u8>, Duration)>> {
+        Box::pin(async move {
+            let start = Instant::now();
+            let mut conn = self.connect(host, port).await?;
+            let connect = start.elapsed();
+            conn.write_all(request).await?;
+            // A response to HEAD carries no body, whatever its framing headers say.
+            let response = if crate::http::is_head_request(request) {
+                crate::http::read_http_response_head(&mut *conn).await?
+            } else {
+                self.read_response(&mut conn).await?
+            };
+            Ok((response, connect))
+        })
+    }
+}
+
+/// HTTP/1.x over plain TCP.
+pub struct Http1Plain;
+
+/// HTTP/1.x over TLS.
+pub struct Http1Tls;
+
+/// HTTP/2 over TLS with ALPN `h2`. Callers write the connection preface and
+/// frames themselves, so deliberately malformed messages can be sent.
+pub struct Http2;
+
+/// HTTP/3 over QUIC. Reserved: connecting fails until a QUIC stack is added.
+pub struct Http3;
+
+impl Transport for Http1Plain {
+    fn kind(&self) -> TransportKind {
+        TransportKind::Http1Plain
+    }
+
+    fn connect<'a>(&'a self, host: &'a str, port: u16) -> TransportFuture<'a, Result<Connection>> {
+        Box::pin(crate::http::open_stream(host, port, false))
+    }
+
+    fn read_response<'a>(
+        &'a self,
+        conn: &'a mut Connection,
+    ) -> TransportFuture<'a, Result<Vec<u8>>> {
+        Box::pin(crate::http::read_one_http_response(&mut **conn))
+    }
+}
+
+impl Transport for Http1Tls {
+    fn kind(&self) -> TransportKind {
+        TransportKind::Http1Tls
+    }
+
+    fn connect<'a>(&'a self, host: &'a str, port: u16) -> TransportFuture<'a, Result<Connection>> {
+        Box::pin(crate::http::open_stream(host, port, true))
+    }
+
+    fn read_response<'a>(
+        &'a self,
+        conn: &'a mut Connection,
+    ) -> TransportFuture<'a, Result<Vec<u8>>> {
+        Box::pin(crate::http::read_one_http_response(&mut **conn))
+    }
+}
+
+impl Transport for Http2 {
+    fn kind(&self) -> TransportKind {
+        TransportKind::Http2
+    }
+
+    fn connect<'a>(&'a self, host: &'a str, port: u16) -> TransportFuture<'a, Result<Connection>> {
+        Box::pin(async move {
+            let connector = TlsConnector::from(Arc::clone(crate::http::get_h2_tls_config()));
+            let tcp = crate::http::connect_tcp(host, port).await?;
+            let domain = ServerName::try_from(host.to_string())?;
+            let tls = crate::http::tls_handshake(&connector, domain, tcp).await?;
+            Ok(Box::new(tls) as Connection)
+        })
+    }
+
+    fn read_response<'a>(
+        &'a self,
+        conn: &'a mut Connection,
+    ) -> TransportFuture<'a, Result<Vec<u8>>> {
+        Box::pin(crate::http2::read_raw_response(conn))
+    }
+}
+
+impl Transport for Http3 {
+    fn kind(&self) -> TransportKind {
+        TransportKind::Http3
+    }
+
+    fn connect<'a>(
+        &'a self,
+        _host: &'a str,
+        _port: u16,
+    ) -> TransportFuture<'a, Result<Connection>> {
+        Box::pin(async {
+            Err(SmugglexError::Config(
+                "HTTP/3 (QUIC) transport is not available in this build".to_string(),
+            ))
+        })
+    }
+
+    fn read_response<'a>(
+        &'a self,
+        _conn: &'a mut Connection,
+    ) -> TransportFuture<'a, Result<Vec<u8>>> {
+        Box::pin(async {
+            Err(SmugglexError::Config(
+                "HTTP/3 (QUIC) transport is not available in this build".to_string(),
+            ))
+        })
+    }
+}
@@ -0,0 +1,239 @@
+//! `--triage`: a few cheap probes per target that rank a large target list by
+//! how much a full scan looks worth it.
+//!
+//! Each target gets a baseline GET, two requests on one keep-alive connection,
+//! the first CL.TE and TE.CL payload, and the fingerprint probe. Every signal
+//! adds its weight to the target's score; targets are ranked by score so the
+//! full scan can start from the top of the list. A triage signal is a reason
+//! to scan, not a finding.
+
+use std::time::Duration;
+
+use url::Url;
+
+use crate::error::{Result, SmugglexError};
+use crate::fingerprint::{ProxyType, behind_cache, fingerprint_target, h2_downgrade_likely};
+use crate::http::{pipeline_requests, send_request};
+use crate::model::{TriageReport, TriageResult};
+use crate::payloads::{format_custom_headers, payload_family};
+use crate::utils::parse_status_code;
+
+/// Settings the triage probes run with (taken from the scan flags).
+#[derive(Debug, Clone)]
+pub struct TriageSettings {
+    pub timeout: u64,
+    pub verbose: bool,
+    /// Method of the CL.TE and TE.CL probes
+    pub method: String,
+    /// `-H` headers, sent with every probe
+    pub headers: Vec<String>,
+}
+
+/// Checks whose first payload is sent as a probe.
+const PROBE_CHECKS: [&str; 2] = ["cl-te", "te-cl"];
+
+/// A probe that hit `--timeout`: the back-end waited for a body the
+/// front-end never sent.
+const WEIGHT_TIMEOUT: u32 = 40;
+/// A probe answered [`DELAY_FACTOR`] times slower than the baseline.
+const WEIGHT_DELAY: u32 = 30;
+/// A probe answered with a different status class than the baseline.
+const WEIGHT_STATUS: u32 = 10;
+const WEIGHT_H2_DOWNGRADE: u32 = 20;
+const WEIGHT_PROXY: u32 = 15;
+const WEIGHT_PIPELINING: u32 = 10;
+const WEIGHT_CACHE: u32 = 10;
+const WEIGHT_ASTERISK_REWRITE: u32 = 5;
+
+/// A probe counts as delayed when it took this many times the baseline and
+/// at least [`DELAY_MIN`] longer.
+const DELAY_FACTOR: u32 = 4;
+const DELAY_MIN: Duration = Duration::from_secs(2);
+
+/// Signal and weight of a CL.TE / TE.CL probe's answer, compared with the
+/// baseline GET; `None` when it looks like the baseline (or failed in a way
+/// that says nothing, like a refused connection).
+fn classify_probe(
+    baseline_status: &str,
+    baseline: Duration,
+    probe: &Result<(String, Duration)>,
+) -> Option<(&'static str, u32)> {
+    match probe {
+        Err(SmugglexError::Timeout { .. }) => Some(("timeout", WEIGHT_TIMEOUT)),
+        Err(_) => None,
+        Ok((_, duration)) if *duration >= (baseline * DELAY_FACTOR).max(baseline + DELAY_MIN) => {
+            Some(("delay", WEIGHT_DELAY))
+        }
+        Ok((response, _)) => {
+            let class = |line: &str| parse_status_code(line).map(|code| code / 100);
+            let probe_class = class(response.lines().next().unwrap_or_default());
+            (probe_class != class(baseline_status)).then_some(("status", WEIGHT_STATUS))
+        }
+    }
+}
+
+/// Run the triage probes against `target` and score what they find. A target
+/// whose baseline GET fails scores 0 with the error recorded.
+pub async fn triage_target(target: &str, settings: &TriageSettings) -> TriageResult {
+    let mut result = TriageResult {
+        target: target.to_string(),
+        score: 0,
+        signals: Vec::new(),
+        status: None,
+        error: None,
+    };
+    if let Err(e) = probe(target, settings, &mut result).await {
+        result.error = Some(e.to_string());
+    }
+    result
+}
+
+fn add_signal(result: &mut TriageResult, signal: String, weight: u32) {
+    result.score += weight;
+    result.signals.push(signal);
+}
+
+async fn probe(target: &str, settings: &TriageSettings, result: &mut TriageResult) -> Result<()> {
+    let url = Url::parse(target)?;
+    let host = url
+        .host_str()
+        .ok_or_else(|| SmugglexError::Config("Invalid host in URL".to_string()))?;
+    let port = url
+        .port_or_known_default()
+        .ok_or_else(|| SmugglexError::Config("Invalid port in URL".to_string()))?;
+    let path = match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    };
+    let use_tls = url.scheme() == "https";
+    let (timeout, verbose) = (settings.timeout, settings.verbose);
+    let get = |connection: &str| {
+        format!(
+            "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: {}\r\n{}\r\n",
+            path,
+            host,
+            connection,
+            format_custom_headers(&settings.headers)
+        )
+    };
+
+    let (response, baseline) =
+        send_request(host, port, &get("close"), timeout, verbose, use_tls).await?;
+    let status = response.lines().next().unwrap_or_default().to_string();
+    result.status = Some(status.clone());
+
+    // Desync needs a front-end that keeps reading requests off the client's
+    // connection after the first.
+    let keep_alive = get("keep-alive");
+    if let Ok(responses) = pipeline_requests(
+        host,
+        port,
+        &[keep_alive.clone(), keep_alive],
+        timeout,
+        verbose,
+        use_tls,
+    )
+    .await
+        && responses.len() == 2
+    {
+        add_signal(result, "pipelining".to_string(), WEIGHT_PIPELINING);
+    }
+
+    for check in PROBE_CHECKS {
+        let Some(payload) = payload_family(check).and_then(|family| {
+            (family.generate)(&path, host, &settings.method, &settings.headers, &[]).next()
+        }) else {
+            continue;
+        };
+        let answer = send_request(host, port, &payload.request, timeout, verbose, use_tls).await;
+        if let Some((kind, weight)) = classify_probe(&status, baseline, &answer) {
+            add_signal(result, format!("{}:{}", check, kind), weight);
+        }
+    }
+
+    if let Ok(fingerprint) = fingerprint_target(host, port, &path, timeout, verbose, use_tls).await
+    {
+        if !matches!(fingerprint.detected_proxy, ProxyType::Unknown(_)) {
+            let proxy = fingerprint.detected_proxy.to_string().to_lowercase();
+            add_signal(result, format!("proxy:{}", proxy), WEIGHT_PROXY);
+        } else if let Some(hops) = fingerprint.hop_count.filter(|&hops| hops > 0) {
+            add_signal(result, format!("hops:{}", hops), WEIGHT_PROXY);
+        }
+        if behind_cache(&fingerprint) {
+            add_signal(result, "cache".to_string(), WEIGHT_CACHE);
+        }
+        if h2_downgrade_likely(&fingerprint) {
+            add_signal(
+                result,
+                "h2-downgrade-likely".to_string(),
+                WEIGHT_H2_DOWNGRADE,
+            );
+        }
+        if fingerprint.asterisk_rewrite {
+            add_signal(
+                result,
+                "asterisk-rewrite".to_string(),
+                WEIGHT_ASTERISK_REWRITE,
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Rank `results` by score, highest first (ties keep their input order),
+/// with the totals.
+pub fn build_triage_report(mut results: Vec<TriageResult>, version: Option<&str>) -> TriageReport {
+    results.sort_by_key(|r| std::cmp::Reverse(r.score));
+    TriageReport {
+        smugglex_version: version.map(|s| s.to_string()),
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        promising: results.iter().filter(|r| r.score > 0).count(),
+        errors: results.iter().filter(|r| r.error.is_some()).count(),
+        results,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::TimeoutPhase;
+
+    const OK: &str = "HTTP/1.1 200 OK";
+
+    #[test]
+    fn classify_probe_timeout_and_delay() {
+        let baseline = Duration::from_millis(100);
+        assert_eq!(
+            classify_probe(
+                OK,
+                baseline,
+                &Err(SmugglexError::timeout(TimeoutPhase::FirstByte, "t"))
+            ),
+            Some(("timeout", WEIGHT_TIMEOUT))
+        );
+        let slow = Ok((OK.to_string(), Duration::from_secs(3)));
+        assert_eq!(
+            classify_probe(OK, baseline, &slow),
+            Some(("delay", WEIGHT_DELAY))
+        );
+        // 4x a slow baseline but less than DELAY_MIN later is noise.
+        let noisy = Ok((OK.to_string(), Duration::from_millis(1600)));
+        assert_eq!(classify_probe(OK, Duration::from_millis(400), &noisy), None);
+    }
+
+    #[test]
+    fn classify_probe_status_class() {
+        let baseline = Duration::from_millis(100);
+        let fast = |status: &str| Ok((format!("{}\r\n\r\n", status), baseline));
+        assert_eq!(
+            classify_probe(OK, baseline, &fast("HTTP/1.1 204 No Content")),
+            None
+        );
+        assert_eq!(
+            classify_probe(OK, baseline, &fast("HTTP/1.1 400 Bad Request")),
+            Some(("status", WEIGHT_STATUS))
+        );
+        let refused = Err(SmugglexError::Io("connection refused".into()));
+        assert_eq!(classify_probe(OK, baseline, &refused), None);
+    }
+}
@@ -1,8 +1,12 @@
-use crate::error::Result;
-use crate::http::send_request;
-use chrono::Local;
+use crate::cli::ExportFormat;
+use crate::error::{Result, SmugglexError};
+use crate::http::{HttpResponse, send_request};
+use crate::model::{ExportedPayload, Technique};
+use crate::redact::redact;
+use chrono::{DateTime, Local, Utc};
 use colored::{ColoredString, Colorize};
 use std::fs;
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
 
 static QUIET: AtomicBool = AtomicBool::new(false);
@@ -45,41 +49,80 @@ pub async fn fetch_cookies(
 
     let (response, _) = send_request(host, port, &request, timeout, verbose, use_tls).await?;
 
-    let mut cookies = Vec::new();
-    for line in response.lines() {
-        if line.len() >= 11
-            && line.as_bytes()[..11].eq_ignore_ascii_case(b"set-cookie:")
-            && let Some((_, cookie_value)) = line.split_once(':')
-        {
-            // Extract just the cookie name=value, stop at semicolon
-            let cookie_part = cookie_value
-                .trim()
-                .split(';')
-                .next()
-                .unwrap_or("")
-                .to_string();
-            if !cookie_part.is_empty() {
-                cookies.push(cookie_part);
-            }
-        }
-    }
+    // Keep just each cookie's name=value, stopping at the first attribute.
+    let cookies = HttpResponse::parse(response.as_bytes())
+        .field_values("set-cookie")
+        .filter_map(|value| value.split(';').next())
+        .map(str::trim)
+        .filter(|cookie| !cookie.is_empty())
+        .map(str::to_string)
+        .collect();
 
     Ok(cookies)
 }
 
+/// Reachability preflight: one `GET` for `path` with `Host: host_header`.
+/// Any HTTP response counts as reachable; otherwise returns the error, whose
+/// [`unreachable_kind`](SmugglexError::unreachable_kind) categorizes it, so a
+/// dead target is skipped before its payloads. A retryable failure (a
+/// connection that could not be opened or was reset) is tried once more;
+/// unlike a payload, this plain `GET` is also re-sent after a reset that
+/// followed its write.
+pub async fn preflight(
+    host: &str,
+    port: u16,
+    path: &str,
+    host_header: &str,
+    use_tls: bool,
+    timeout: u64,
+    verbose: bool,
+) -> Result<()> {
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+        path, host_header
+    );
+    let mut sent = send_request(host, port, &request, timeout, verbose, use_tls).await;
+    if let Err(ref e) = sent
+        && (e.is_retryable()
+            || e.request_error_kind() == Some(crate::model::RequestErrorKind::Reset))
+    {
+        sent = send_request(host, port, &request, timeout, verbose, use_tls).await;
+    }
+    match sent? {
+        (response, _) if parse_status_code(&response).is_some() => Ok(()),
+        (response, _) if response.is_empty() => Err(SmugglexError::Io(
+            "connection closed without a response".to_string(),
+        )),
+        _ => Err(SmugglexError::Parse(
+            "response has no HTTP status line".to_string(),
+        )),
+    }
+}
+
 /// Sanitize hostname for use in filenames
 pub fn sanitize_hostname(host: &str) -> String {
     host.replace([':', '/', '.'], "_")
 }
 
-/// Export payload to a file
-pub fn export_payload(
+/// Where and how `--export-payloads` writes payloads.
+#[derive(Debug, Clone, Copy)]
+pub struct PayloadExport<'a> {
+    pub dir: &'a str,
+    pub format: ExportFormat,
+    /// Target URL, recorded in `raw` sidecars
+    pub target: &'a str,
+}
+
+/// Write `payload` to `export_dir` in a file named after the protocol, host,
+/// check and payload index, ending in `extension`.
+fn write_payload_file(
     export_dir: &str,
     host: &str,
     check_type: &str,
     payload_index: usize,
     payload: &str,
     use_tls: bool,
+    extension: &str,
 ) -> Result<String> {
     // Create export directory if it doesn't exist
     fs::create_dir_all(export_dir)?;
@@ -89,8 +132,8 @@ pub fn export_payload(
     let protocol = if use_tls { "https" } else { "http" };
 
     let filename = format!(
-        "{}/{}_{}_{}_{}.txt",
-        export_dir, protocol, sanitized_host, check_type, payload_index
+        "{}/{}_{}_{}_{}.{}",
+        export_dir, protocol, sanitized_host, check_type, payload_index, extension
     );
 
     if fs::metadata(&filename).is_ok() {
@@ -99,8 +142,68 @@ pub fn export_payload(
             &format!("overwriting existing payload file: {}", filename),
         );
     }
-    fs::write(&filename, payload)?;
+    fs::write(&filename, redact(payload))?;
+
+    Ok(filename)
+}
+
+/// Export payload to a file
+pub fn export_payload(
+    export_dir: &str,
+    host: &str,
+    check_type: &str,
+    payload_index: usize,
+    payload: &str,
+    use_tls: bool,
+) -> Result<String> {
+    write_payload_file(
+        export_dir,
+        host,
+        check_type,
+        payload_index,
+        payload,
+        use_tls,
+        "txt",
+    )
+}
 
+/// Write `technique` as JSON next to the payload file `payload_file`
+/// exported by [`export_payload`] (`.txt` becomes `.json`), returning its path.
+pub fn export_technique(payload_file: &str, technique: &Technique) -> Result<String> {
+    let filename = match payload_file.strip_suffix(".txt") {
+        Some(stem) => format!("{}.json", stem),
+        None => format!("{}.json", payload_file),
+    };
+    fs::write(&filename, serde_json::to_string_pretty(technique)?)?;
+    Ok(filename)
+}
+
+/// Export `payload` for `--export-format raw`: the request byte for byte in a
+/// `.raw` file, and `metadata` in a `.json` file of the same name. Its
+/// `raw_file` and `exported_at` are filled in here. Returns the `.raw` path.
+pub fn export_payload_raw(
+    export_dir: &str,
+    host: &str,
+    payload: &str,
+    use_tls: bool,
+    mut metadata: ExportedPayload,
+) -> Result<String> {
+    let filename = write_payload_file(
+        export_dir,
+        host,
+        &metadata.check_type,
+        metadata.payload_index,
+        payload,
+        use_tls,
+        "raw",
+    )?;
+    metadata.raw_file = Path::new(&filename)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    metadata.exported_at = Utc::now().to_rfc3339();
+    let sidecar = format!("{}.json", filename.trim_end_matches(".raw"));
+    fs::write(&sidecar, serde_json::to_string_pretty(&metadata)?)?;
     Ok(filename)
 }
 
@@ -115,6 +218,85 @@ pub fn parse_status_code(status_line: &str) -> Option<u16> {
     }
 }
 
+/// Delay the `Retry-After` header of `response` asks for: delta-seconds, or
+/// an HTTP-date counted from `now` (zero once it has passed).
+pub fn retry_after(response: &str, now: DateTime<Utc>) -> Option<std::time::Duration> {
+    let response = HttpResponse::parse(response.as_bytes());
+    let value = response.header("retry-after")?;
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(std::time::Duration::from_secs(secs));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (date.with_timezone(&Utc) - now)
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
+
+/// Response head compared by the verbose response diff: the status line, the
+/// headers and at most `body_bytes` bytes of the body, one line each with
+/// line endings stripped.
+pub fn response_head(response: &str, body_bytes: usize) -> String {
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .or_else(|| response.split_once("\n\n"))
+        .unwrap_or((response, ""));
+    let mut end = body.len().min(body_bytes);
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    let mut lines: Vec<&str> = head.lines().collect();
+    if end > 0 {
+        lines.push("");
+        lines.extend(body[..end].lines());
+    }
+    lines.join("\n")
+}
+
+/// One line of a [`line_diff`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLine<'a> {
+    /// Present on both sides
+    Same(&'a str),
+    /// Only in the old side
+    Removed(&'a str),
+    /// Only in the new side
+    Added(&'a str),
+}
+
+/// Line diff from `old` to `new` over the longest common subsequence, removed
+/// lines before the added lines that replace them.
+pub fn line_diff<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<DiffLine<'a>> {
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::with_capacity(old.len().max(new.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            diff.push(DiffLine::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            diff.push(DiffLine::Removed(old[i]));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new[j]));
+            j += 1;
+        }
+    }
+    diff
+}
+
 /// Log levels for consistent output formatting
 pub enum LogLevel {
     /// Informational messages (suppressed in quiet mode)
@@ -0,0 +1,256 @@
+//! Linting of `--raw-request` templates (`smugglex validate`).
+//!
+//! The raw request parser is deliberately lenient — unknown lines are skipped,
+//! line endings are normalized and misplaced markers are sent literally — so a
+//! broken template still "works" and quietly scans with the wrong request.
+//! The validator reports those cases up front and shows the request a
+//! template expands to.
+
+use std::path::{Path, PathBuf};
+
+use crate::error::{Result, SmugglexError};
+use crate::model::TemplateValidation;
+use crate::payloads::cl_te_payloads;
+use crate::raw_request::{BODY_MARKER, CL_MARKER, TE_MARKER, parse_raw_request};
+
+/// Template files to validate: `path` itself, or the regular files directly
+/// inside it (hidden files skipped), sorted by name.
+pub fn template_files(path: &Path) -> Result<Vec<PathBuf>> {
+    if !path.is_dir() {
+        if !path.exists() {
+            return Err(SmugglexError::Io(format!(
+                "{} does not exist",
+                path.display()
+            )));
+        }
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if !hidden && entry.file_type()?.is_file() {
+            files.push(entry.path());
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Read and validate every template under `path` (see [`template_files`]).
+/// `host` overrides the Host the sample expansion is built for.
+pub fn validate_path(path: &Path, host: Option<&str>) -> Result<Vec<TemplateValidation>> {
+    let files = template_files(path)?;
+    Ok(files
+        .iter()
+        .map(|file| {
+            let name = file.display().to_string();
+            match std::fs::read_to_string(file) {
+                Ok(content) => validate_template(&name, &content, host),
+                Err(e) => TemplateValidation {
+                    path: name,
+                    errors: vec![format!("cannot read template: {}", e)],
+                    warnings: Vec::new(),
+                    expansion: None,
+                },
+            }
+        })
+        .collect())
+}
+
+/// `{{NAME}}` placeholders in `line`, in order.
+fn placeholders(line: &str) -> Vec<&str> {
+    let mut found = Vec::new();
+    let mut rest = line;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start..].find("}}") else {
+            break;
+        };
+        found.push(&rest[start..start + len + 2]);
+        rest = &rest[start + len + 2..];
+    }
+    found
+}
+
+/// Lint one template and build its sample expansion: the first CL.TE payload
+/// generated from it, with its placement markers applied.
+pub fn validate_template(path: &str, content: &str, host: Option<&str>) -> TemplateValidation {
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+
+    // Line endings: every line is re-sent with CRLF, so a mix means the body
+    // (or a header value) is not what the file shows.
+    let crlf = content.matches("\r\n").count();
+    let bare_lf = content.matches('\n').count() - crlf;
+    let bare_cr = content.matches('\r').count() - crlf;
+    if bare_cr > 0 {
+        errors.push(format!(
+            "{} bare CR (\\r without \\n); it is sent inside the line it appears on",
+            bare_cr
+        ));
+    }
+    if crlf > 0 && bare_lf > 0 {
+        errors.push(format!(
+            "mixed line endings ({} CRLF, {} bare LF); every line is sent with CRLF",
+            crlf, bare_lf
+        ));
+    } else if bare_lf > 0 {
+        warnings.push("bare LF line endings; every line is sent with CRLF".to_string());
+    }
+
+    // Walk the header block line by line, keeping byte offsets so the body
+    // can be measured exactly as it appears in the file.
+    let mut offset = 0;
+    let mut seen_request_line = false;
+    let mut body_start = None;
+    let mut content_length: Option<(usize, String)> = None;
+    let (mut te_markers, mut cl_markers, mut body_markers) = (0, 0, 0);
+    for (idx, raw_line) in content.split_inclusive('\n').enumerate() {
+        let line_no = idx + 1;
+        let line = raw_line.trim_end_matches(['\r', '\n']);
+        offset += raw_line.len();
+        if body_start.is_some() {
+            for placeholder in placeholders(line) {
+                match placeholder {
+                    BODY_MARKER => body_markers += 1,
+                    TE_MARKER | CL_MARKER => {
+                        errors.push(misplaced_header_marker(line_no, placeholder))
+                    }
+                    _ => errors.push(unknown_placeholder(line_no, placeholder)),
+                }
+            }
+            continue;
+        }
+        if line.trim().is_empty() {
+            if seen_request_line {
+                body_start = Some(offset);
+            }
+            continue;
+        }
+        let header_line = seen_request_line;
+        seen_request_line = true;
+        match line.trim() {
+            TE_MARKER if header_line => {
+                te_markers += 1;
+                continue;
+            }
+            CL_MARKER if header_line => {
+                cl_markers += 1;
+                continue;
+            }
+            _ => {}
+        }
+        for placeholder in placeholders(line) {
+            match placeholder {
+                TE_MARKER | CL_MARKER => errors.push(misplaced_header_marker(line_no, placeholder)),
+                BODY_MARKER => errors.push(format!(
+                    "line {}: {} only works in the body; it is sent literally",
+                    line_no, placeholder
+                )),
+                _ => errors.push(unknown_placeholder(line_no, placeholder)),
+            }
+        }
+        if !header_line {
+            continue;
+        }
+        match line.split_once(':') {
+            Some((name, value)) => {
+                if name.trim().eq_ignore_ascii_case("content-length") {
+                    content_length = Some((line_no, value.trim().to_string()));
+                }
+            }
+            None => errors.push(format!(
+                "line {}: not a 'Name: value' header line; it is dropped",
+                line_no
+            )),
+        }
+    }
+
+    if seen_request_line && body_start.is_none() {
+        warnings.push("no blank line ends the header block".to_string());
+    }
+    for (marker, count) in [
+        (TE_MARKER, te_markers),
+        (CL_MARKER, cl_markers),
+        (BODY_MARKER, body_markers),
+    ] {
+        if count > 1 {
+            errors.push(format!(
+                "{} appears {} times; only the first is replaced",
+                marker, count
+            ));
+        }
+    }
+
+    // A captured Content-Length that disagrees with the captured body points
+    // at a truncated or re-encoded capture. Templates with `{{BODY}}` are
+    // skipped: their body is rebuilt around the generated one.
+    if let Some((line_no, declared)) = content_length
+        && body_markers == 0
+    {
+        let body = body_start.map_or("", |start| &content[start..]);
+        let trimmed = body.trim_end_matches(['\r', '\n']);
+        match declared.parse::<usize>() {
+            Ok(n) if n == body.len() || n == trimmed.len() => {}
+            Ok(n) => warnings.push(format!(
+                "line {}: Content-Length: {} does not match the {}-byte body",
+                line_no,
+                n,
+                trimmed.len()
+            )),
+            Err(_) => warnings.push(format!(
+                "line {}: Content-Length '{}' is not a number",
+                line_no, declared
+            )),
+        }
+    }
+
+    let expansion = match parse_raw_request(content) {
+        Ok(raw) => {
+            if let Some((before, after)) = raw.markers.as_ref().and_then(|m| m.body.as_ref())
+                && before.len() + after.len() > 0
+            {
+                warnings.push(format!(
+                    "text around {} adds {} byte(s) the crafted Content-Length does not count",
+                    BODY_MARKER,
+                    before.len() + after.len()
+                ));
+            }
+            let host_header = host.unwrap_or(&raw.host_header);
+            cl_te_payloads(&raw.target, host_header, &raw.method, &raw.headers, &[])
+                .next()
+                .map(|p| match raw.markers {
+                    Some(ref markers) => markers.apply(&p.request, &raw.headers, &[]),
+                    None => p.request,
+                })
+        }
+        Err(e) => {
+            errors.push(match e {
+                SmugglexError::Config(msg) => msg,
+                other => other.to_string(),
+            });
+            None
+        }
+    };
+
+    TemplateValidation {
+        path: path.to_string(),
+        errors,
+        warnings,
+        expansion,
+    }
+}
+
+fn unknown_placeholder(line_no: usize, placeholder: &str) -> String {
+    format!(
+        "line {}: unknown placeholder {} (expected {}, {} or {}); it is sent literally",
+        line_no, placeholder, TE_MARKER, CL_MARKER, BODY_MARKER
+    )
+}
+
+fn misplaced_header_marker(line_no: usize, marker: &str) -> String {
+    format!(
+        "line {}: {} only works on a line of its own in the header block; it is sent literally",
+        line_no, marker
+    )
+}
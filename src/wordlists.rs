@@ -0,0 +1,261 @@
+//! `smugglex wordlists`: curated path and subdomain wordlists from SecLists,
+//! downloaded once into the user data directory and then referenced by name
+//! from `--exploit-wordlist` (path-fuzz) and `--vhost`.
+//!
+//! ```text
+//! smugglex wordlists update
+//! smugglex -e path-fuzz --exploit-wordlist raft-small-directories https://target.com
+//! smugglex --vhost subdomains-top5000 https://target.com
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde::Serialize;
+use url::Url;
+
+use crate::error::{Result, SmugglexError};
+use crate::http::{HttpResponse, send_request};
+
+/// What a wordlist's entries are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WordlistKind {
+    /// URL paths, for `--exploit-wordlist`
+    Paths,
+    /// Subdomain labels, for `--vhost` (each becomes `label.<target host>`)
+    Subdomains,
+}
+
+impl std::fmt::Display for WordlistKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WordlistKind::Paths => write!(f, "paths"),
+            WordlistKind::Subdomains => write!(f, "subdomains"),
+        }
+    }
+}
+
+/// A wordlist `smugglex wordlists update` can fetch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Wordlist {
+    /// Name it is referenced by
+    pub name: &'static str,
+    pub kind: WordlistKind,
+    pub description: &'static str,
+    /// Where it is downloaded from
+    pub url: &'static str,
+}
+
+macro_rules! seclists {
+    ($path:literal) => {
+        concat!(
+            "https://raw.githubusercontent.com/danielmiessler/SecLists/master/",
+            $path
+        )
+    };
+}
+
+/// The curated wordlists, all from SecLists.
+pub const WORDLISTS: &[Wordlist] = &[
+    Wordlist {
+        name: "web-common",
+        kind: WordlistKind::Paths,
+        description: "Common files and directories (~4,700)",
+        url: seclists!("Discovery/Web-Content/common.txt"),
+    },
+    Wordlist {
+        name: "quickhits",
+        kind: WordlistKind::Paths,
+        description: "Sensitive files and admin endpoints worth a first look (~2,500)",
+        url: seclists!("Discovery/Web-Content/quickhits.txt"),
+    },
+    Wordlist {
+        name: "raft-small-directories",
+        kind: WordlistKind::Paths,
+        description: "Directories seen most often on real sites (~20,000)",
+        url: seclists!("Discovery/Web-Content/raft-small-directories.txt"),
+    },
+    Wordlist {
+        name: "api-endpoints",
+        kind: WordlistKind::Paths,
+        description: "REST and GraphQL API endpoints",
+        url: seclists!("Discovery/Web-Content/api/api-endpoints.txt"),
+    },
+    Wordlist {
+        name: "subdomains-top5000",
+        kind: WordlistKind::Subdomains,
+        description: "Most common subdomain labels (5,000)",
+        url: seclists!("Discovery/DNS/subdomains-top1million-5000.txt"),
+    },
+    Wordlist {
+        name: "subdomains-top20000",
+        kind: WordlistKind::Subdomains,
+        description: "Most common subdomain labels (20,000)",
+        url: seclists!("Discovery/DNS/subdomains-top1million-20000.txt"),
+    },
+];
+
+/// The curated wordlist called `name`.
+pub fn find_wordlist(name: &str) -> Option<&'static Wordlist> {
+    WORDLISTS.iter().find(|w| w.name == name)
+}
+
+static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Use `dir` as the data directory (`--data-dir`).
+pub fn set_data_dir(dir: PathBuf) {
+    let _ = DATA_DIR.set(dir);
+}
+
+/// Where smugglex keeps downloaded data: `--data-dir`, else
+/// `$XDG_DATA_HOME/smugglex`, else `~/.local/share/smugglex`.
+pub fn data_dir() -> Option<PathBuf> {
+    if let Some(dir) = DATA_DIR.get() {
+        return Some(dir.clone());
+    }
+    std::env::var_os("XDG_DATA_HOME")
+        .filter(|d| !d.is_empty())
+        .map(|d| PathBuf::from(d).join("smugglex"))
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .or_else(|| std::env::var_os("USERPROFILE"))
+                .map(|home| PathBuf::from(home).join(".local/share/smugglex"))
+        })
+}
+
+/// Directory the wordlists are cached in.
+pub fn wordlist_dir() -> Result<PathBuf> {
+    data_dir().map(|dir| dir.join("wordlists")).ok_or_else(|| {
+        SmugglexError::Config("no data directory found; pass --data-dir".to_string())
+    })
+}
+
+/// Where `wordlist` is cached under `dir`.
+pub fn cached_path(dir: &Path, wordlist: &Wordlist) -> PathBuf {
+    dir.join(format!("{}.txt", wordlist.name))
+}
+
+/// The cached wordlist `value` names, with its file. `None` when `value` is
+/// an existing file or no curated wordlist's name, so it is read as a path;
+/// an error when it names a wordlist that was never downloaded.
+pub fn resolve_wordlist(value: &str) -> Result<Option<(&'static Wordlist, PathBuf)>> {
+    let Some(wordlist) = find_wordlist(value).filter(|_| !Path::new(value).exists()) else {
+        return Ok(None);
+    };
+    let path = cached_path(&wordlist_dir()?, wordlist);
+    if !path.is_file() {
+        return Err(SmugglexError::Config(format!(
+            "wordlist '{}' is not downloaded; run `smugglex wordlists update {}`",
+            wordlist.name, wordlist.name
+        )));
+    }
+    Ok(Some((wordlist, path)))
+}
+
+/// Entries of a wordlist file's `content`: trimmed, without blank lines and
+/// `#` comments.
+pub fn wordlist_entries(content: &str) -> Vec<&str> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+}
+
+/// Download `wordlist`, returning its text. Goes through the configured
+/// proxy like every other request.
+pub async fn download_wordlist(wordlist: &Wordlist, timeout: u64) -> Result<String> {
+    let url = Url::parse(wordlist.url)?;
+    let host = url
+        .host_str()
+        .ok_or_else(|| SmugglexError::Config("Invalid host in URL".to_string()))?;
+    let port = url
+        .port_or_known_default()
+        .ok_or_else(|| SmugglexError::Config("Invalid port in URL".to_string()))?;
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: smugglex/{}\r\nAccept: text/plain\r\nConnection: close\r\n\r\n",
+        url.path(),
+        host,
+        env!("CARGO_PKG_VERSION")
+    );
+    let (raw, _) = send_request(
+        host,
+        port,
+        &request,
+        timeout,
+        false,
+        url.scheme() == "https",
+    )
+    .await?;
+    let response = HttpResponse::parse(raw.as_bytes());
+    match response.status {
+        Some(200) => {}
+        Some(status) => {
+            return Err(SmugglexError::Io(format!(
+                "{}: HTTP {}",
+                wordlist.url, status
+            )));
+        }
+        None => {
+            return Err(SmugglexError::Parse(format!(
+                "{}: no HTTP response",
+                wordlist.url
+            )));
+        }
+    }
+    if response.capped {
+        return Err(SmugglexError::Config(format!(
+            "{} is larger than --max-response-size; raise it to download this wordlist",
+            wordlist.name
+        )));
+    }
+    Ok(String::from_utf8_lossy(&response.body).into_owned())
+}
+
+/// Write `content` as the cached copy of `wordlist` in `dir`, replacing the
+/// previous one only once the new one is complete.
+pub fn save_wordlist(dir: &Path, wordlist: &Wordlist, content: &str) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let path = cached_path(dir, wordlist);
+    let partial = path.with_extension("txt.partial");
+    std::fs::write(&partial, content)?;
+    std::fs::rename(&partial, &path)?;
+    Ok(path)
+}
+
+/// A curated wordlist as `smugglex wordlists list` shows it.
+#[derive(Debug, Clone, Serialize)]
+pub struct WordlistStatus {
+    pub name: &'static str,
+    pub kind: WordlistKind,
+    pub description: &'static str,
+    pub url: &'static str,
+    /// Cached file, when downloaded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    /// Entries in the cached file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entries: Option<usize>,
+}
+
+/// Every curated wordlist, with its cached copy under `dir` when there is one.
+pub fn wordlist_status(dir: &Path) -> Vec<WordlistStatus> {
+    WORDLISTS
+        .iter()
+        .map(|wordlist| {
+            let path = cached_path(dir, wordlist);
+            let entries = std::fs::read_to_string(&path)
+                .ok()
+                .map(|content| wordlist_entries(&content).len());
+            WordlistStatus {
+                name: wordlist.name,
+                kind: wordlist.kind,
+                description: wordlist.description,
+                url: wordlist.url,
+                path: entries.map(|_| path),
+                entries,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,123 @@
+//! Tests for the archive module
+//!
+//! This module contains tests for:
+//! - Appending payloads and their metadata to a `.tar.gz` archive
+//! - Recording payloads with their outcome through an `ArchiveRecorder`
+
+use flate2::read::GzDecoder;
+use smugglex::archive::{ArchiveRecorder, PayloadArchive};
+use smugglex::model::{ExportedPayload, ResponseSummary};
+use smugglex::payloads::Payload;
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+
+fn archive_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "smugglex_archive_{}_{}.tar.gz",
+        name,
+        std::process::id()
+    ))
+}
+
+/// Every entry of the archive at `path`, as (path, contents).
+fn entries(path: &PathBuf) -> Vec<(String, Vec<u8>)> {
+    let mut archive = tar::Archive::new(GzDecoder::new(File::open(path).unwrap()));
+    archive
+        .entries()
+        .unwrap()
+        .map(|entry| {
+            let mut entry = entry.unwrap();
+            let name = entry.path().unwrap().to_string_lossy().into_owned();
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data).unwrap();
+            (name, data)
+        })
+        .collect()
+}
+
+fn payload(index: usize, request: &str) -> Payload {
+    Payload {
+        index,
+        name: Some(format!("variant-{}", index)),
+        request: request.to_string(),
+        technique: None,
+    }
+}
+
+#[test]
+fn test_archive_records_payloads_and_outcomes() {
+    let path = archive_path("record");
+    let archive = PayloadArchive::create(&path).unwrap();
+    let recorder = ArchiveRecorder {
+        archive: &archive,
+        target: "https://example.com/",
+    };
+    let request = "POST / HTTP/1.1\r\nTransfer-Encoding:\x0bchunked\r\n\r\n0\r\n\r\n";
+    recorder
+        .record(
+            "cl-te",
+            &payload(4, request),
+            "clean",
+            ResponseSummary::default(),
+        )
+        .unwrap();
+    recorder
+        .record(
+            "te-cl",
+            &payload(0, "GET / HTTP/1.1\r\n\r\n"),
+            "error",
+            ResponseSummary {
+                signals: vec!["connection refused".to_string()],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    assert_eq!(archive.len(), 2);
+    assert_eq!(archive.finish().unwrap(), 2);
+    assert!(
+        recorder
+            .record(
+                "cl-te",
+                &payload(5, request),
+                "clean",
+                ResponseSummary::default()
+            )
+            .is_err()
+    );
+
+    let entries = entries(&path);
+    let names: Vec<&str> = entries.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "payloads/000001_cl-te_4.raw",
+            "payloads/000001_cl-te_4.json",
+            "payloads/000002_te-cl_0.raw",
+            "payloads/000002_te-cl_0.json",
+        ]
+    );
+    assert_eq!(entries[0].1, request.as_bytes());
+    let metadata: ExportedPayload = serde_json::from_slice(&entries[1].1).unwrap();
+    assert_eq!(metadata.target, "https://example.com/");
+    assert_eq!(metadata.check_type, "cl-te");
+    assert_eq!(metadata.payload_index, 4);
+    assert_eq!(metadata.payload_name.as_deref(), Some("variant-4"));
+    assert_eq!(metadata.raw_file, "000001_cl-te_4.raw");
+    assert_eq!(metadata.outcome.as_deref(), Some("clean"));
+    let metadata: ExportedPayload = serde_json::from_slice(&entries[3].1).unwrap();
+    assert_eq!(metadata.outcome.as_deref(), Some("error"));
+    assert_eq!(metadata.response.signals, ["connection refused"]);
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn test_empty_archive_is_valid() {
+    let path = archive_path("empty");
+    let archive = PayloadArchive::create(&path).unwrap();
+    assert!(archive.is_empty());
+    assert_eq!(archive.finish().unwrap(), 0);
+    assert!(entries(&path).is_empty());
+    let _ = std::fs::remove_file(&path);
+}
@@ -8,9 +8,17 @@
 //! - Check type selection
 //! - Virtual host and export options
 //! - HTTP method variations
+//! - Daemon job args: flags that touch the daemon host are rejected
+//! - Distributed worker profiles: built from the coordinator's settings and
+//!   checked by the worker
 
 use clap::Parser;
-use smugglex::cli::{Cli, OutputFormat};
+use smugglex::cli::{
+    Cli, Command, DnsChangePolicy, ExportFormat, OutputFormat, PayloadsCommand, ReplayTool,
+    ReportFormat, StdinFormat, WordlistsCommand, completion_script,
+};
+use smugglex::exploit::{FuzzCategory, SizeRange};
+use smugglex::mutator::FuzzMode;
 
 #[test]
 fn test_single_url_parsing() {
@@ -127,13 +135,76 @@ fn test_various_timeout_values() {
 #[test]
 fn test_output_file_option() {
     let cli = Cli::parse_from(["smugglex", "http://example.com", "-o", "results.json"]);
-    assert_eq!(cli.output, Some("results.json".to_string()));
+    assert_eq!(cli.output, vec!["results.json".to_string()]);
+}
+
+#[test]
+fn test_repeated_output_files_infer_format() {
+    let cli = Cli::parse_from([
+        "smugglex",
+        "http://example.com",
+        "-o",
+        "out.json",
+        "-o",
+        "out.sarif",
+        "--output",
+        "report.HTML",
+        "-o",
+        "findings.md",
+        "-o",
+        "rows.csv",
+        "-o",
+        "results",
+        "-o",
+        "import.DefectDojo.json",
+        "-o",
+        "alerts.zap.json",
+        "-o",
+        "alerts.zap.xml",
+    ]);
+    assert_eq!(cli.output.len(), 9);
+    assert_eq!(
+        cli.json_outputs().collect::<Vec<_>>(),
+        vec!["out.json", "results"]
+    );
+    assert_eq!(
+        cli.report_outputs().collect::<Vec<_>>(),
+        vec![
+            ("out.sarif", ReportFormat::Sarif),
+            ("report.HTML", ReportFormat::Html),
+            ("findings.md", ReportFormat::Md),
+            ("rows.csv", ReportFormat::Csv),
+            ("import.DefectDojo.json", ReportFormat::Defectdojo),
+            ("alerts.zap.json", ReportFormat::ZapJson),
+            ("alerts.zap.xml", ReportFormat::ZapXml),
+        ]
+    );
+}
+
+#[test]
+fn test_stdin_format() {
+    let cli = Cli::parse_from(["smugglex"]);
+    assert_eq!(cli.stdin_format, StdinFormat::Urls);
+    let cli = Cli::parse_from(["smugglex", "--stdin-format", "jsonl"]);
+    assert_eq!(cli.stdin_format, StdinFormat::Jsonl);
+    assert!(Cli::try_parse_from(["smugglex", "--stdin-format", "csv"]).is_err());
+}
+
+#[test]
+fn test_targets_list() {
+    let cli = Cli::parse_from(["smugglex"]);
+    assert!(cli.list.is_none());
+    let cli = Cli::parse_from(["smugglex", "-l", "targets.txt"]);
+    assert_eq!(cli.list, Some(std::path::PathBuf::from("targets.txt")));
+    assert!(
+        Cli::try_parse_from(["smugglex", "-l", "targets.txt", "--raw-request", "req.txt"]).is_err()
+    );
 }
 
 #[test]
 fn test_no_output_file() {
     let cli = Cli::parse_from(["smugglex", "http://example.com"]);
-    assert_eq!(cli.output, None, "Output should be None by default");
+    assert!(cli.output.is_empty(), "Output should be empty by default");
 }
 
 // Test checks option
@@ -171,6 +242,59 @@ fn test_vhost_option() {
 fn test_no_vhost_option() {
     let cli = Cli::parse_from(["smugglex", "http://example.com"]);
     assert_eq!(cli.vhost, None, "Vhost should be None by default");
+    assert!(cli.vhosts().unwrap().is_empty());
+}
+
+#[test]
+fn test_vhost_list_and_file() {
+    let cli = Cli::parse_from([
+        "smugglex",
+        "http://192.168.1.1",
+        "--vhost",
+        "a.example.com, b.example.com,",
+    ]);
+    assert_eq!(
+        cli.vhosts().unwrap(),
+        vec!["a.example.com", "b.example.com"]
+    );
+
+    let dir = std::env::temp_dir().join("smugglex_test_vhosts");
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("vhosts.txt");
+    std::fs::write(
+        &file,
+        "# shared proxy
+admin.internal
+
+api.internal
+",
+    )
+    .unwrap();
+    let cli = Cli::parse_from([
+        "smugglex",
+        "http://192.168.1.1",
+        "--vhost",
+        file.to_str().unwrap(),
+    ]);
+    assert_eq!(
+        cli.vhosts().unwrap(),
+        vec!["admin.internal", "api.internal"]
+    );
+
+    let empty = dir.join("empty.txt");
+    std::fs::write(
+        &empty,
+        "# nothing
+",
+    )
+    .unwrap();
+    let cli = Cli::parse_from([
+        "smugglex",
+        "http://192.168.1.1",
+        "--vhost",
+        empty.to_str().unwrap(),
+    ]);
+    assert!(cli.vhosts().is_err());
 }
 
 // Test cookies option
@@ -198,6 +322,83 @@ fn test_no_export_payloads_option() {
     assert_eq!(cli.export_dir, None, "export_dir should be None by default");
 }
 
+#[test]
+fn test_export_all_option() {
+    let cli = Cli::parse_from(["smugglex", "http://example.com"]);
+    assert!(cli.export_all.is_none());
+    let cli = Cli::parse_from([
+        "smugglex",
+        "http://example.com",
+        "--export-all",
+        "payloads.tar.gz",
+    ]);
+    assert_eq!(
+        cli.export_all,
+        Some(std::path::PathBuf::from("payloads.tar.gz"))
+    );
+}
+
+#[test]
+fn test_export_format_option() {
+    let cli = Cli::parse_from(["smugglex", "http://example.com", "--export-payloads", "out"]);
+    assert_eq!(cli.export_format, ExportFormat::Txt);
+    let cli = Cli::parse_from([
+        "smugglex",
+        "http://example.com",
+        "--export-payloads",
+        "out",
+        "--export-format",
+        "raw",
+    ]);
+    assert_eq!(cli.export_format, ExportFormat::Raw);
+    assert!(
+        Cli::try_parse_from(["smugglex", "http://example.com", "--export-format", "raw"]).is_err()
+    );
+}
+
+// Test redact options
+#[test]
+fn test_redact_option() {
+    let cli = Cli::parse_from(["smugglex", "http://example.com"]);
+    assert!(cli.redact.contains(&"Authorization".to_string()));
+    assert!(cli.redact.contains(&"Cookie".to_string()));
+    assert!(!cli.no_redact);
+
+    let cli = Cli::parse_from([
+        "smugglex",
+        "http://example.com",
+        "--redact",
+        "X-Session,X-Api-Key",
+        "--no-redact",
+    ]);
+    assert_eq!(cli.redact, vec!["X-Session", "X-Api-Key"]);
+    assert!(cli.no_redact);
+}
+
+#[test]
+fn test_proxy_auth_option() {
+    let cli = Cli::parse_from(["smugglex", "http://example.com"]);
+    assert!(cli.proxy_auth.is_none());
+
+    let cli = Cli::parse_from([
+        "smugglex",
+        "http://example.com",
+        "-x",
+        "http://127.0.0.1:3128",
+        "--proxy-auth",
+        "user:@env:PROXY_PASS",
+    ]);
+    assert_eq!(cli.proxy_auth.as_deref(), Some("user:@env:PROXY_PASS"));
+}
+
+#[test]
+fn test_no_env_proxy_flag() {
+    let cli = Cli::parse_from(["smugglex", "http://example.com"]);
+    assert!(!cli.no_env_proxy);
+    let cli = Cli::parse_from(["smugglex", "http://example.com", "--no-env-proxy"]);
+    assert!(cli.no_env_proxy);
+}
+
 // Test format option
 #[test]
 fn test_format_default_plain() {
@@ -235,6 +436,24 @@ fn test_format_plain_explicit() {
     );
 }
 
+#[test]
+fn test_format_github() {
+    let cli = Cli::parse_from(["smugglex", "http://example.com", "--format", "github"]);
+    assert!(matches!(cli.format, OutputFormat::Github));
+    assert!(cli.effective_format().is_github());
+    assert!(!cli.effective_format().is_json());
+    assert_eq!(cli.format.to_string(), "github");
+}
+
+#[test]
+fn test_format_junit() {
+    let cli = Cli::parse_from(["smugglex", "http://example.com", "--format", "junit"]);
+    assert!(matches!(cli.format, OutputFormat::Junit));
+    assert!(cli.effective_format().is_junit());
+    assert!(!cli.effective_format().is_json());
+    assert_eq!(cli.format.to_string(), "junit");
+}
+
 #[test]
 fn test_format_invalid_value() {
     let result = Cli::try_parse_from(["smugglex", "http://example.com", "--format", "invalid"]);
@@ -323,7 +542,7 @@ fn test_all_options_combined() {
     assert_eq!(cli.method, "GET");
     assert_eq!(cli.timeout, 30);
     assert!(cli.verbose);
-    assert_eq!(cli.output, Some("output.json".to_string()));
+    assert_eq!(cli.output, vec!["output.json".to_string()]);
     assert_eq!(cli.headers.len(), 1);
     assert_eq!(cli.checks, Some("cl-te,te-cl".to_string()));
     assert_eq!(cli.vhost, Some("test.local".to_string()));
@@ -417,7 +636,7 @@ fn test_invalid_output_file_path() {
     // This will succeed, as clap doesn't validate file paths
     assert_eq!(
         cli.output,
-        Some("/invalid/path/that/does/not/exist.json".to_string())
+        vec!["/invalid/path/that/does/not/exist.json".to_string()]
     );
 }
 
@@ -475,6 +694,64 @@ fn test_ports_option_custom() {
     assert_eq!(cli.exploit_ports, "80,443");
 }
 
+#[test]
+fn test_internal_sweep_options() {
+    let cli = Cli::parse_from(["smugglex", "http://example.com"]);
+    assert!(cli.range.is_none());
+    assert_eq!(cli.sweep_budget, 200);
+    let cli = Cli::parse_from([
+        "smugglex",
+        "http://example.com",
+        "-e",
+        "internal-sweep",
+        "--range",
+        "10.0.0.0/24",
+        "--ports",
+        "80,8080",
+        "--sweep-budget",
+        "50",
+    ]);
+    assert_eq!(cli.range.as_deref(), Some("10.0.0.0/24"));
+    assert_eq!(cli.exploit_ports, "80,8080");
+    assert_eq!(cli.sweep_budget, 50);
+    assert!(Cli::try_parse_from(["smugglex", "--sweep-budget", "0"]).is_err());
+}
+
+#[test]
+fn test_exploit_runtime_caps() {
+    let cli = Cli::parse_from(["smugglex", "http://example.com"]);
+    assert!(cli.exploit_max_requests.is_none());
+    assert!(cli.exploit_max_time.is_none());
+    let cli = Cli::parse_from([
+        "smugglex",
+        "http://example.com",
+        "--exploit-max-requests",
+        "50",
+        "--exploit-max-time",
+        "300",
+    ]);
+    assert_eq!(cli.exploit_max_requests, Some(50));
+    assert_eq!(cli.exploit_max_time, Some(300));
+    assert!(Cli::try_parse_from(["smugglex", "--exploit-max-requests", "0"]).is_err());
+    assert!(Cli::try_parse_from(["smugglex", "--exploit-max-time", "0"]).is_err());
+}
+
+#[test]
+fn test_exploit_hosts_option() {
+    let cli = Cli::parse_from(["smugglex", "http://example.com"]);
+    assert!(cli.exploit_hosts.is_none());
+    let cli = Cli::parse_from([
+        "smugglex",
+        "http://example.com",
+        "--exploit-hosts",
+        "intranet.corp,admin.internal",
+    ]);
+    assert_eq!(
+        cli.exploit_hosts.as_deref(),
+        Some("intranet.corp,admin.internal")
+    );
+}
+
 #[test]
 fn test_exploit_with_custom_ports() {
     let cli = Cli::parse_from([
@@ -569,6 +846,138 @@ fn test_exploit_path_fuzz_with_wordlist() {
     );
 }
 
+#[test]
+fn test_fuzz_mode_option() {
+    let cli = Cli::parse_from(["smugglex", "http://example.com"]);
+    assert_eq!(cli.fuzz_mode, FuzzMode::Mixed);
+
+    let cli = Cli::parse_from(["smugglex", "http://example.com", "--fuzz-mode", "grammar"]);
+    assert_eq!(cli.fuzz_mode, FuzzMode::Grammar);
+    assert!(Cli::try_parse_from(["smugglex", "http://x", "--fuzz-mode", "nope"]).is_err());
+}
+
+#[test]
+fn test_fuzz_corpus_requires_fuzz() {
+    let cli = Cli::parse_from([
+        "smugglex",
+        "http://example.com",
+        "--fuzz",
+        "--fuzz-corpus",
+        "corpus/",
+    ]);
+    assert_eq!(cli.fuzz_corpus.as_deref(), Some("corpus/"));
+    assert!(Cli::try_parse_from(["smugglex", "http://x", "--fuzz-corpus", "corpus/"]).is_err());
+}
+
+#[test]
+fn test_fuzz_checks_and_budget_options() {
+    let cli = Cli::parse_from([
+        "smugglex",
+        "http://example.com",
+        "--fuzz",
+        "--fuzz-checks",
+        "te-cl",
+        "--fuzz-budget",
+        "500",
+    ]);
+    assert_eq!(cli.fuzz_checks.as_deref(), Some("te-cl"));
+    assert_eq!(cli.fuzz_budget, Some(500));
+
+    let cli = Cli::parse_from(["smugglex", "http://example.com", "--fuzz"]);
+    assert_eq!(cli.fuzz_checks, None);
+    assert_eq!(cli.fuzz_budget, None);
+
+    assert!(Cli::try_parse_from(["smugglex", "http://x", "--fuzz-budget", "10"]).is_err());
+    assert!(Cli::try_parse_from(["smugglex", "http://x", "--fuzz", "--fuzz-budget", "0"]).is_err());
+}
+
+#[test]
+fn test_fuzz_categories_option() {
+    let cli = Cli::parse_from([
+        "smugglex",
+        "http://example.com",
+        "--fuzz-categories",
+        "admin,cloud-metadata,actuator",
+    ]);
+    assert_eq!(
+        cli.fuzz_categories,
+        vec![
+            FuzzCategory::Admin,
+            FuzzCategory::CloudMetadata,
+            FuzzCategory::Actuator
+        ]
+    );
+
+    let cli = Cli::parse_from(["smugglex", "http://example.com"]);
+    assert!(cli.fuzz_categories.is_empty());
+    assert!(Cli::try_parse_from(["smugglex", "http://x", "--fuzz-categories", "nope"]).is_err());
+}
+
+#[test]
+fn test_path_fuzz_matcher_options() {
+    let cli = Cli::parse_from([
+        "smugglex",
+        "http://example.com",
+        "--match-status",
+        "200,302",
+        "--filter-size",
+        "0,1000-2000",
+        "--match-regex",
+        "admin",
+    ]);
+    assert_eq!(cli.match_status, vec![200, 302]);
+    assert_eq!(
+        cli.filter_size,
+        vec![
+            SizeRange { min: 0, max: 0 },
+            SizeRange {
+                min: 1000,
+                max: 2000
+            }
+        ]
+    );
+    let matcher = cli.response_matcher();
+    assert_eq!(matcher.match_regex.unwrap().as_str(), "admin");
+    assert!(matcher.filter_regex.is_none());
+
+    // Invalid regexes and sizes are rejected at parse time
+    assert!(Cli::try_parse_from(["smugglex", "http://x", "--match-regex", "("]).is_err());
+    assert!(Cli::try_parse_from(["smugglex", "http://x", "--filter-size", "9-1"]).is_err());
+}
+
+#[test]
+fn test_auto_exploit_option() {
+    let cli = Cli::parse_from(["smugglex", "http://example.com"]);
+    assert!(!cli.auto_exploit);
+    assert_eq!(cli.auto_exploit_budget, 40);
+
+    let cli = Cli::parse_from([
+        "smugglex",
+        "http://example.com",
+        "--auto-exploit",
+        "--auto-exploit-budget",
+        "12",
+    ]);
+    assert!(cli.auto_exploit);
+    assert_eq!(cli.auto_exploit_budget, 12);
+
+    // Choosing an exploit explicitly and automatically are exclusive.
+    assert!(
+        Cli::try_parse_from(["smugglex", "http://x", "--auto-exploit", "-e", "path-fuzz"]).is_err()
+    );
+    assert!(Cli::try_parse_from(["smugglex", "http://x", "--auto-exploit-budget", "0"]).is_err());
+}
+
+#[test]
+fn test_yes_option() {
+    let cli = Cli::parse_from(["smugglex", "http://example.com", "-e", "smuggle"]);
+    assert!(!cli.yes);
+    let cli = Cli::parse_from(["smugglex", "http://example.com", "-e", "smuggle", "-y"]);
+    assert!(cli.yes);
+    let cli = Cli::parse_from(["smugglex", "http://example.com", "--auto-exploit", "--yes"]);
+    assert!(cli.yes);
+}
+
 #[test]
 fn test_raw_request_default_none() {
     let cli = Cli::parse_from(["smugglex", "http://example.com"]);
@@ -608,3 +1017,680 @@ fn test_raw_request_proto_override() {
     ]);
     assert_eq!(cli.raw_request_proto, "http");
 }
+
+#[test]
+fn test_rps_flag() {
+    let cli = Cli::parse_from(["smugglex", "http://example.com"]);
+    assert_eq!(cli.rps, None);
+    let cli = Cli::parse_from(["smugglex", "--rps", "25", "http://example.com"]);
+    assert_eq!(cli.rps, Some(25));
+    assert!(Cli::try_parse_from(["smugglex", "--rps", "0", "http://example.com"]).is_err());
+}
+
+#[test]
+fn test_scope_include_exclude() {
+    let cli = Cli::parse_from(["smugglex", "https://example.com"]);
+    assert_eq!(cli.out_of_scope("https://anything.test"), None);
+
+    let cli = Cli::parse_from([
+        "smugglex",
+        "--scope-include",
+        r"^https?://([a-z0-9-]+\.)*example\.com(:\d+)?/",
+        "--scope-exclude",
+        r"//admin\.example\.com",
+        "https://example.com/",
+    ]);
+    assert_eq!(cli.out_of_scope("https://api.example.com/login"), None);
+    assert_eq!(
+        cli.out_of_scope("https://example.com.evil.test/"),
+        Some("matches no --scope-include".to_string())
+    );
+    assert!(
+        cli.out_of_scope("https://admin.example.com/")
+            .unwrap()
+            .contains("--scope-exclude")
+    );
+    assert!(
+        Cli::try_parse_from(["smugglex", "--scope-include", "(", "https://example.com"]).is_err()
+    );
+}
+
+#[test]
+fn test_target_ip() {
+    let cli = Cli::parse_from([
+        "smugglex",
+        "--target-ip",
+        "203.0.113.7",
+        "https://example.com",
+    ]);
+    assert_eq!(cli.target_ip, Some("203.0.113.7".parse().unwrap()));
+    let cli = Cli::parse_from([
+        "smugglex",
+        "--target-ip",
+        "2001:db8::7",
+        "https://example.com",
+    ]);
+    assert_eq!(cli.target_ip, Some("2001:db8::7".parse().unwrap()));
+    assert!(
+        Cli::try_parse_from([
+            "smugglex",
+            "--target-ip",
+            "origin.local",
+            "https://example.com"
+        ])
+        .is_err()
+    );
+    assert!(
+        Cli::try_parse_from([
+            "smugglex",
+            "--target-ip",
+            "203.0.113.7",
+            "--scan-all-ips",
+            "https://example.com",
+        ])
+        .is_err()
+    );
+}
+
+#[test]
+fn test_dns_flags() {
+    let cli = Cli::parse_from(["smugglex", "http://example.com"]);
+    assert!(!cli.scan_all_ips);
+    assert_eq!(cli.on_dns_change, DnsChangePolicy::Warn);
+    let cli = Cli::parse_from([
+        "smugglex",
+        "--scan-all-ips",
+        "--on-dns-change",
+        "abort",
+        "http://example.com",
+    ]);
+    assert!(cli.scan_all_ips);
+    assert_eq!(cli.on_dns_change, DnsChangePolicy::Abort);
+    assert!(cli.target_ip.is_none());
+    assert!(
+        Cli::try_parse_from(["smugglex", "--on-dns-change", "retry", "http://example.com"])
+            .is_err()
+    );
+}
+
+#[test]
+fn test_retest_subcommand() {
+    let cli = Cli::parse_from(["smugglex", "-t", "5", "retest", "results.json"]);
+    assert_eq!(cli.timeout, 5);
+    match cli.command {
+        Some(Command::Retest(args)) => {
+            assert_eq!(args.results, std::path::PathBuf::from("results.json"))
+        }
+        other => panic!("expected retest, got {:?}", other),
+    }
+    assert!(Cli::try_parse_from(["smugglex", "retest"]).is_err());
+}
+
+#[test]
+fn test_report_subcommand() {
+    let cli = Cli::parse_from([
+        "smugglex",
+        "report",
+        "results.json",
+        "--format",
+        "md",
+        "-o",
+        "report.md",
+    ]);
+    match cli.command {
+        Some(Command::Report(args)) => {
+            assert_eq!(args.results, std::path::PathBuf::from("results.json"));
+            assert_eq!(args.format, ReportFormat::Md);
+            assert_eq!(args.output.as_deref(), Some("report.md"));
+        }
+        other => panic!("expected report, got {:?}", other),
+    }
+    assert!(Cli::try_parse_from(["smugglex", "report", "results.json"]).is_err());
+    assert!(
+        Cli::try_parse_from(["smugglex", "report", "results.json", "--format", "pdf"]).is_err()
+    );
+}
+
+#[test]
+fn test_completions_subcommand() {
+    let cli = Cli::parse_from(["smugglex", "completions", "zsh"]);
+    match cli.command {
+        Some(Command::Completions(args)) => assert_eq!(args.shell, clap_complete::Shell::Zsh),
+        other => panic!("expected completions, got {:?}", other),
+    }
+    assert!(Cli::try_parse_from(["smugglex", "completions", "tcsh"]).is_err());
+
+    for shell in [
+        clap_complete::Shell::Bash,
+        clap_complete::Shell::Zsh,
+        clap_complete::Shell::Fish,
+        clap_complete::Shell::PowerShell,
+    ] {
+        let script = completion_script(shell);
+        assert!(script.contains("h2-downgrade"), "{:?}", shell);
+        assert!(script.contains("cloud-metadata"), "{:?}", shell);
+        assert!(script.contains("retest"), "{:?}", shell);
+    }
+    // Names only complete; the comma-separated lists still parse as before.
+    let cli = Cli::parse_from(["smugglex", "-c", "cl-te,te-cl", "-e", "smuggle,reveal"]);
+    assert_eq!(cli.checks.as_deref(), Some("cl-te,te-cl"));
+    assert_eq!(cli.exploit.as_deref(), Some("smuggle,reveal"));
+}
+
+#[test]
+fn test_payloads_list_subcommand() {
+    let cli = Cli::parse_from(["smugglex", "payloads", "list", "--check", "te-cl"]);
+    match cli.command {
+        Some(Command::Payloads(args)) => {
+            let PayloadsCommand::List(list) = args.command;
+            assert_eq!(list.check.as_deref(), Some("te-cl"));
+        }
+        other => panic!("expected payloads, got {:?}", other),
+    }
+    let cli = Cli::parse_from(["smugglex", "--json", "payloads", "list"]);
+    assert!(cli.json);
+    assert!(Cli::try_parse_from(["smugglex", "payloads"]).is_err());
+}
+
+#[test]
+fn test_wordlists_subcommand() {
+    let cli = Cli::parse_from(["smugglex", "wordlists", "update", "quickhits", "web-common"]);
+    match cli.command {
+        Some(Command::Wordlists(args)) => match args.command {
+            WordlistsCommand::Update(update) => {
+                assert_eq!(update.names, vec!["quickhits", "web-common"]);
+            }
+            other => panic!("expected update, got {:?}", other),
+        },
+        other => panic!("expected wordlists, got {:?}", other),
+    }
+    let cli = Cli::parse_from(["smugglex", "--data-dir", "/tmp/sx", "wordlists", "list"]);
+    assert_eq!(cli.data_dir, Some(std::path::PathBuf::from("/tmp/sx")));
+    assert!(matches!(
+        cli.command,
+        Some(Command::Wordlists(ref args)) if matches!(args.command, WordlistsCommand::List)
+    ));
+    assert!(Cli::try_parse_from(["smugglex", "wordlists"]).is_err());
+}
+
+#[test]
+fn test_validate_subcommand() {
+    let cli = Cli::parse_from([
+        "smugglex",
+        "validate",
+        "templates/",
+        "--host",
+        "example.com",
+    ]);
+    match cli.command {
+        Some(Command::Validate(args)) => {
+            assert_eq!(args.path, std::path::PathBuf::from("templates/"));
+            assert_eq!(args.host.as_deref(), Some("example.com"));
+        }
+        other => panic!("expected validate, got {:?}", other),
+    }
+    assert!(Cli::try_parse_from(["smugglex", "validate"]).is_err());
+}
+
+#[test]
+fn test_detect_flag() {
+    use smugglex::scanner::detection::{DEFAULT_DETECTION, DetectionMethod};
+
+    let cli = Cli::parse_from(["smugglex", "http://example.com"]);
+    assert_eq!(cli.detect, DEFAULT_DETECTION);
+    assert_eq!(cli.oob_host, None);
+
+    let cli = Cli::parse_from([
+        "smugglex",
+        "--detect",
+        "canary,oob",
+        "--oob-host",
+        "oob.example",
+        "http://example.com",
+    ]);
+    assert_eq!(
+        cli.detect,
+        vec![DetectionMethod::Canary, DetectionMethod::Oob]
+    );
+    assert_eq!(cli.oob_host.as_deref(), Some("oob.example"));
+    assert!(Cli::try_parse_from(["smugglex", "--detect", "magic", "http://example.com"]).is_err());
+}
+
+#[test]
+fn test_warmup() {
+    let cli = Cli::parse_from(["smugglex", "https://example.com"]);
+    assert_eq!(cli.warmup, 0);
+    let cli = Cli::parse_from(["smugglex", "--warmup", "5", "https://example.com"]);
+    assert_eq!(cli.warmup, 5);
+    assert!(Cli::try_parse_from(["smugglex", "--warmup", "-1", "https://example.com"]).is_err());
+}
+
+#[test]
+fn test_storm_cooldown() {
+    let cli = Cli::parse_from(["smugglex", "https://example.com"]);
+    assert_eq!(cli.storm_cooldown, 10);
+    let cli = Cli::parse_from(["smugglex", "--storm-cooldown", "30", "https://example.com"]);
+    assert_eq!(cli.storm_cooldown, 30);
+}
+
+#[test]
+fn test_per_host_concurrency() {
+    let cli = Cli::parse_from(["smugglex", "https://example.com"]);
+    assert!(cli.per_host_concurrency.is_none());
+    let cli = Cli::parse_from([
+        "smugglex",
+        "-j",
+        "50",
+        "--per-host-concurrency",
+        "2",
+        "https://example.com",
+    ]);
+    assert_eq!(cli.per_host_concurrency, Some(2));
+    assert!(
+        Cli::try_parse_from([
+            "smugglex",
+            "--per-host-concurrency",
+            "0",
+            "https://example.com"
+        ])
+        .is_err()
+    );
+}
+
+#[test]
+fn test_auto_concurrency() {
+    let cli = Cli::parse_from(["smugglex", "https://example.com"]);
+    assert!(!cli.auto_concurrency);
+    let cli = Cli::parse_from([
+        "smugglex",
+        "-j",
+        "8",
+        "--auto-concurrency",
+        "https://example.com",
+    ]);
+    assert!(cli.auto_concurrency);
+    assert_eq!(cli.concurrency, 8);
+}
+
+#[test]
+fn test_repeat() {
+    let cli = Cli::parse_from(["smugglex", "https://example.com"]);
+    assert_eq!(cli.repeat, 1);
+    let cli = Cli::parse_from(["smugglex", "--repeat", "3", "https://example.com"]);
+    assert_eq!(cli.repeat, 3);
+    assert!(Cli::try_parse_from(["smugglex", "--repeat", "0", "https://example.com"]).is_err());
+}
+
+#[test]
+fn test_retry_after_max() {
+    let cli = Cli::parse_from(["smugglex", "https://example.com"]);
+    assert_eq!(cli.retry_after_max, 30);
+    let cli = Cli::parse_from(["smugglex", "--retry-after-max", "0", "https://example.com"]);
+    assert_eq!(cli.retry_after_max, 0);
+}
+
+#[test]
+fn test_max_response_size() {
+    let cli = Cli::parse_from(["smugglex", "https://example.com"]);
+    assert_eq!(cli.max_response_size, 512);
+    let cli = Cli::parse_from([
+        "smugglex",
+        "--max-response-size",
+        "64",
+        "https://example.com",
+    ]);
+    assert_eq!(cli.max_response_size, 64);
+    assert!(
+        Cli::try_parse_from([
+            "smugglex",
+            "--max-response-size",
+            "0",
+            "https://example.com"
+        ])
+        .is_err()
+    );
+}
+
+#[test]
+fn test_phase_timing() {
+    let cli = Cli::parse_from(["smugglex", "https://example.com"]);
+    assert!(!cli.phase_timing);
+    let cli = Cli::parse_from(["smugglex", "--phase-timing", "https://example.com"]);
+    assert!(cli.phase_timing);
+}
+
+#[test]
+fn test_no_decompress() {
+    let cli = Cli::parse_from(["smugglex", "https://example.com"]);
+    assert!(!cli.no_decompress);
+    let cli = Cli::parse_from(["smugglex", "--no-decompress", "https://example.com"]);
+    assert!(cli.no_decompress);
+}
+
+#[test]
+fn test_no_tls_resume() {
+    let cli = Cli::parse_from(["smugglex", "https://example.com"]);
+    assert!(!cli.no_tls_resume);
+    let cli = Cli::parse_from(["smugglex", "--no-tls-resume", "https://example.com"]);
+    assert!(cli.no_tls_resume);
+}
+
+#[test]
+fn test_triage() {
+    let cli = Cli::parse_from(["smugglex", "https://example.com"]);
+    assert!(!cli.triage);
+    let cli = Cli::parse_from(["smugglex", "--triage", "-j", "50", "https://example.com"]);
+    assert!(cli.triage);
+    assert_eq!(cli.concurrency, 50);
+}
+
+#[test]
+fn test_quick() {
+    let cli = Cli::parse_from(["smugglex", "https://example.com"]);
+    assert!(!cli.quick);
+    let cli = Cli::parse_from([
+        "smugglex",
+        "--quick",
+        "--max-payloads",
+        "5",
+        "https://example.com",
+    ]);
+    assert!(cli.quick);
+    assert_eq!(cli.max_payloads, Some(5));
+}
+
+#[test]
+fn test_extended() {
+    let cli = Cli::parse_from(["smugglex", "--extended", "https://example.com"]);
+    assert!(cli.extended);
+    assert!(
+        Cli::try_parse_from(["smugglex", "--extended", "--quick", "https://example.com"]).is_err()
+    );
+}
+
+#[test]
+fn test_reproduce_subcommand() {
+    let cli = Cli::parse_from([
+        "smugglex",
+        "reproduce",
+        "results.json",
+        "--id",
+        "te-cl/vanilla",
+    ]);
+    match cli.command {
+        Some(Command::Reproduce(args)) => {
+            assert_eq!(args.results, std::path::PathBuf::from("results.json"));
+            assert_eq!(args.id.as_deref(), Some("te-cl/vanilla"));
+            assert_eq!(args.tool, ReplayTool::Ncat);
+        }
+        other => panic!("expected reproduce, got {:?}", other),
+    }
+    let cli = Cli::parse_from(["smugglex", "reproduce", "results.json", "--tool", "openssl"]);
+    match cli.command {
+        Some(Command::Reproduce(args)) => {
+            assert!(args.id.is_none());
+            assert_eq!(args.tool, ReplayTool::Openssl);
+        }
+        other => panic!("expected reproduce, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_every_option_has_an_env_var() {
+    use clap::CommandFactory;
+    let command = Cli::command();
+    for arg in command.get_arguments() {
+        let Some(long) = arg.get_long() else {
+            continue;
+        };
+        if long == "help" || long == "version" {
+            continue;
+        }
+        let expected = format!("SMUGGLEX_{}", long.to_uppercase().replace('-', "_"));
+        assert_eq!(
+            arg.get_env().and_then(|name| name.to_str()),
+            Some(expected.as_str()),
+            "--{}",
+            long
+        );
+    }
+}
+
+fn job_args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|a| a.to_string()).collect()
+}
+
+fn assert_job_rejects(args: &[&str], flag: &str) {
+    let err = Cli::parse_job_args(&job_args(args))
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.contains(&format!("--{} is not allowed in job args", flag)),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_job_args_allowed() {
+    let cli = Cli::parse_job_args(&job_args(&[
+        "-m",
+        "GET",
+        "-c",
+        "cl-te",
+        "--vhost",
+        "internal.example",
+    ]))
+    .unwrap();
+    assert_eq!(cli.method, "GET");
+    assert_eq!(cli.vhost.as_deref(), Some("internal.example"));
+    assert!(cli.output.is_empty() && cli.export_dir.is_none() && !cli.auto_exploit);
+    assert!(Cli::parse_job_args(&job_args(&["payloads", "list"])).is_err());
+}
+
+#[test]
+fn test_job_args_reject_secret_references() {
+    assert!(Cli::parse_job_args(&job_args(&["-H", "Authorization: @env:TOKEN"])).is_err());
+    assert!(Cli::parse_job_args(&job_args(&["--proxy-auth", "user:@file:/etc/shadow"])).is_err());
+}
+
+#[test]
+fn test_job_args_reject_vhost_lists_and_files() {
+    assert!(Cli::parse_job_args(&job_args(&["--vhost", "a.example,b.example"])).is_err());
+    assert!(Cli::parse_job_args(&job_args(&["--vhost", "/etc/hosts"])).is_err());
+}
+
+#[test]
+fn test_job_args_reject_list() {
+    assert_job_rejects(&["-l", "targets.txt"], "list");
+}
+
+#[test]
+fn test_job_args_reject_raw_request() {
+    assert_job_rejects(&["--raw-request", "req.txt"], "raw-request");
+}
+
+#[test]
+fn test_job_args_reject_output() {
+    assert_job_rejects(&["-o", "/tmp/out.json"], "output");
+}
+
+#[test]
+fn test_job_args_reject_export_payloads() {
+    assert_job_rejects(&["--export-payloads", "/tmp/payloads"], "export-payloads");
+}
+
+#[test]
+fn test_job_args_reject_export_all() {
+    assert_job_rejects(&["--export-all", "/tmp/all.tar"], "export-all");
+}
+
+#[test]
+fn test_job_args_reject_history_dir() {
+    assert_job_rejects(&["--history-dir", "/tmp/history"], "history-dir");
+}
+
+#[test]
+fn test_job_args_reject_email_to() {
+    assert_job_rejects(
+        &[
+            "--email-to",
+            "ops@corp.example",
+            "--smtp",
+            "smtp://mail.example",
+        ],
+        "email-to",
+    );
+}
+
+#[test]
+fn test_job_args_reject_smtp() {
+    assert_job_rejects(&["--smtp", "smtp://mail.example"], "smtp");
+}
+
+#[test]
+fn test_job_args_reject_email_from() {
+    assert_job_rejects(&["--email-from", "a@corp.example"], "email-from");
+}
+
+#[test]
+fn test_job_args_reject_syslog() {
+    assert_job_rejects(&["--syslog", "udp://siem.example"], "syslog");
+}
+
+#[test]
+fn test_job_args_reject_fuzz_corpus() {
+    assert_job_rejects(&["--fuzz", "--fuzz-corpus", "/tmp/corpus"], "fuzz-corpus");
+}
+
+#[test]
+fn test_job_args_reject_exploit() {
+    assert_job_rejects(&["--exploit", "localhost-access"], "exploit");
+}
+
+#[test]
+fn test_job_args_reject_auto_exploit() {
+    assert_job_rejects(&["--auto-exploit"], "auto-exploit");
+}
+
+#[test]
+fn test_job_args_reject_yes() {
+    assert_job_rejects(&["--yes"], "yes");
+}
+
+#[test]
+fn test_job_args_reject_exploit_wordlist() {
+    assert_job_rejects(&["--exploit-wordlist", "/etc/passwd"], "exploit-wordlist");
+}
+
+#[test]
+fn test_job_args_reject_data_dir() {
+    assert_job_rejects(&["--data-dir", "/tmp/data"], "data-dir");
+}
+
+#[test]
+fn test_job_args_reject_cacert() {
+    assert_job_rejects(&["--cacert", "/etc/ssl/ca.pem"], "cacert");
+}
+
+#[test]
+fn test_job_args_reject_daemon_wide_settings() {
+    for args in [
+        &["-x", "http://proxy.example:8080"][..],
+        &["--proxy-auth", "user:pass"],
+        &["--no-env-proxy"],
+        &["--rps", "5"],
+        &["--per-host-concurrency", "2"],
+        &["--max-response-size", "64"],
+        &["--retry-after-max", "5"],
+        &["--no-decompress"],
+        &["--redact", "X-Api-Key"],
+        &["--no-redact"],
+        &["-k"],
+        &["--no-tls-resume"],
+    ] {
+        let err = Cli::parse_job_args(&job_args(args))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("is daemon-wide"), "{:?}: {}", args, err);
+        assert!(err.contains("--rps, --per-host-concurrency"), "{}", err);
+    }
+}
+
+#[test]
+fn test_worker_profile_from_coordinator_args() {
+    use clap::CommandFactory;
+    let matches = Cli::command()
+        .try_get_matches_from([
+            "smugglex",
+            "-c",
+            "cl-te",
+            "-H",
+            "X-A: 1",
+            "-H",
+            "X-B: 2",
+            "-k",
+            "--rps",
+            "5",
+            "-o",
+            "results.json",
+            "serve",
+            "--role",
+            "coordinator",
+            "--token",
+            "t",
+        ])
+        .unwrap();
+    let profile = Cli::worker_profile(&matches);
+    for arg in [
+        "--checks=cl-te",
+        "--header=X-A: 1",
+        "--header=X-B: 2",
+        "--insecure",
+        "--rps=5",
+    ] {
+        assert!(profile.contains(&arg.to_string()), "{arg} in {profile:?}");
+    }
+    // Output files stay with the coordinator; defaults are not forwarded.
+    assert!(!profile.iter().any(|a| a.starts_with("--output")));
+    assert!(!profile.iter().any(|a| a.starts_with("--timeout")));
+
+    let cli = Cli::parse_worker_profile(&profile).unwrap();
+    assert_eq!(cli.checks.as_deref(), Some("cl-te"));
+    assert_eq!(cli.headers, vec!["X-A: 1", "X-B: 2"]);
+    assert!(cli.insecure);
+    assert_eq!(cli.rps, Some(5));
+}
+
+#[test]
+fn test_worker_profile_rejects_local_effects() {
+    for args in [
+        &["--auto-exploit", "--yes"][..],
+        &["--export-payloads", "/tmp/x"],
+        &["--history-dir", "/tmp/h"],
+        &[
+            "--email-to",
+            "a@example.com",
+            "--smtp",
+            "smtp://mail.example",
+        ],
+        &["--syslog", "udp://127.0.0.1:514"],
+        &["--raw-request", "/etc/passwd"],
+        &["--cacert", "/etc/ssl/ca.pem"],
+        &["--data-dir", "/tmp/d"],
+        &["-o", "out.json"],
+    ] {
+        let err = Cli::parse_worker_profile(&job_args(args))
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("is not allowed in a worker profile"),
+            "{:?}: {}",
+            args,
+            err
+        );
+    }
+    assert!(
+        Cli::parse_worker_profile(&job_args(&["-H", "Authorization: @file:/etc/shadow"])).is_err()
+    );
+}
@@ -0,0 +1,175 @@
+//! Tests for the concurrency module
+//!
+//! - Load samples from scan results: transport errors without timeouts,
+//!   baseline latencies, failed scans
+//! - Baseline latency stability
+//! - Stepping up to the ceiling and halving on a degraded round
+
+use smugglex::concurrency::{Adjustment, AutoConcurrency, ScanSample};
+use smugglex::model::{CheckResult, RequestErrorKind, ScanResults};
+
+fn check(normal_duration_ms: u64, errors: &[(RequestErrorKind, usize)]) -> CheckResult {
+    CheckResult {
+        check_type: "cl-te".to_string(),
+        vulnerable: false,
+        payload_index: None,
+        payload_name: None,
+        technique: None,
+        normal_status: "HTTP/1.1 200 OK".to_string(),
+        attack_status: None,
+        normal_duration_ms,
+        attack_duration_ms: None,
+        normal_connect_ms: None,
+        attack_connect_ms: None,
+        timestamp: "2024-01-01T00:00:00Z".to_string(),
+        payload: None,
+        confidence: None,
+        detection_signals: Vec::new(),
+        diagnostics: Vec::new(),
+        payloads_sent: 9,
+        request_errors: errors.iter().copied().collect(),
+        h2_observations: Vec::new(),
+        fuzz_anomalies: Vec::new(),
+        unsolicited_bytes: None,
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
+    }
+}
+
+fn scan(checks: Vec<CheckResult>, error: Option<&str>) -> ScanResults {
+    ScanResults {
+        target: "https://target.com/".to_string(),
+        address: None,
+        vhost: None,
+        ip: None,
+        port: None,
+        scheme: None,
+        method: "POST".to_string(),
+        timestamp: "2024-01-01T00:00:00Z".to_string(),
+        fingerprint: None,
+        checks,
+        exploits: None,
+        proxy_chain: Vec::new(),
+        unreachable: None,
+        throttle: None,
+        target_info: None,
+        error: error.map(str::to_string),
+        error_info: None,
+        duration_ms: None,
+        requests_sent: None,
+        summary: None,
+    }
+}
+
+fn healthy() -> ScanSample {
+    ScanSample {
+        requests: 100,
+        errors: 0,
+        latencies_ms: vec![120, 130, 125],
+    }
+}
+
+#[test]
+fn test_sample_from_results() {
+    let results = scan(
+        vec![
+            check(
+                120,
+                &[(RequestErrorKind::Reset, 2), (RequestErrorKind::Timeout, 5)],
+            ),
+            check(130, &[(RequestErrorKind::ConnectRefused, 1)]),
+            check(0, &[]),
+        ],
+        None,
+    );
+    let sample = ScanSample::from_results(&results);
+    assert_eq!(sample.requests, 30);
+    assert_eq!(sample.errors, 3, "timeouts are not load errors");
+    assert_eq!(sample.latencies_ms, vec![120, 130]);
+
+    let mut counted = results.clone();
+    counted.requests_sent = Some(42);
+    assert_eq!(ScanSample::from_results(&counted).requests, 42);
+
+    let failed = ScanSample::from_results(&scan(Vec::new(), Some("connection refused")));
+    assert_eq!(failed, ScanSample::failed());
+    assert_eq!((failed.requests, failed.errors), (1, 1));
+}
+
+#[test]
+fn test_latency_unstable() {
+    assert!(!healthy().latency_unstable());
+    let spiky = ScanSample {
+        latencies_ms: vec![200, 210, 4000],
+        ..healthy()
+    };
+    assert!(spiky.latency_unstable());
+    // A large ratio on a fast target is jitter, not degradation.
+    let jitter = ScanSample {
+        latencies_ms: vec![5, 6, 40],
+        ..healthy()
+    };
+    assert!(!jitter.latency_unstable());
+    let too_few = ScanSample {
+        latencies_ms: vec![200, 4000],
+        ..healthy()
+    };
+    assert!(!too_few.latency_unstable());
+}
+
+#[test]
+fn test_auto_concurrency_steps_up_to_ceiling() {
+    let mut tuner = AutoConcurrency::new(3);
+    assert_eq!(tuner.current(), 1);
+    assert_eq!(
+        tuner.observe(&[healthy()]),
+        Some(Adjustment {
+            from: 1,
+            to: 2,
+            reason: None
+        })
+    );
+    tuner.observe(&[healthy(), healthy()]);
+    assert_eq!(tuner.current(), 3);
+    assert_eq!(tuner.observe(&[healthy(), healthy(), healthy()]), None);
+    assert_eq!(tuner.current(), 3);
+}
+
+#[test]
+fn test_auto_concurrency_backs_off() {
+    let mut tuner = AutoConcurrency::new(16);
+    for _ in 0..7 {
+        tuner.observe(&[healthy()]);
+    }
+    assert_eq!(tuner.current(), 8);
+
+    let erroring = ScanSample {
+        errors: 20,
+        ..healthy()
+    };
+    let adjustment = tuner.observe(&[healthy(), erroring]).unwrap();
+    assert_eq!((adjustment.from, adjustment.to), (8, 4));
+    assert_eq!(
+        adjustment.reason.as_deref(),
+        Some("20 of 200 request(s) failed")
+    );
+
+    let spiky = ScanSample {
+        latencies_ms: vec![200, 210, 4000],
+        ..healthy()
+    };
+    let adjustment = tuner.observe(&[spiky]).unwrap();
+    assert_eq!(adjustment.to, 2);
+    assert_eq!(
+        adjustment.reason.as_deref(),
+        Some("baseline latency unstable on 1 target(s)")
+    );
+
+    // Every target failing halves down to one, never below.
+    tuner.observe(&[ScanSample::failed()]);
+    assert_eq!(tuner.current(), 1);
+    assert_eq!(tuner.observe(&[ScanSample::failed()]), None);
+    assert_eq!(tuner.current(), 1);
+}
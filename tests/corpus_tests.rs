@@ -0,0 +1,63 @@
+//! Tests for the on-disk fuzzing corpus
+//!
+//! This module contains tests for:
+//! - Saving entries and deduplicating them across Host values
+//! - Loading entries per check with the Host header rewritten
+
+use smugglex::corpus::FuzzCorpus;
+use std::fs;
+
+fn temp_corpus_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("smugglex-corpus-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn test_corpus_save_deduplicates_across_hosts() {
+    let dir = temp_corpus_dir("dedup");
+    let corpus = FuzzCorpus::open(&dir).unwrap();
+
+    let request =
+        "POST / HTTP/1.1\r\nHost: a.example\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\n";
+    let first = corpus.save("cl-te", request).unwrap();
+    assert!(first.is_some());
+    assert!(corpus.save("cl-te", request).unwrap().is_none());
+    assert!(
+        corpus
+            .save("cl-te", &request.replace("a.example", "b.example"))
+            .unwrap()
+            .is_none()
+    );
+    // The same bytes under another check are a separate entry.
+    assert!(corpus.save("te-cl", request).unwrap().is_some());
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_corpus_load_filters_by_check_and_rewrites_host() {
+    let dir = temp_corpus_dir("load");
+    let corpus = FuzzCorpus::open(&dir).unwrap();
+
+    corpus
+        .save("cl-te", "POST / HTTP/1.1\r\nHost: old\r\n\r\nA")
+        .unwrap();
+    corpus
+        .save("cl-te-edge", "POST / HTTP/1.1\r\nHost: old\r\n\r\nB")
+        .unwrap();
+    fs::write(dir.join("notes.md"), "ignored").unwrap();
+
+    let entries = corpus.load("cl-te", "new.example").unwrap();
+    assert_eq!(
+        entries,
+        vec!["POST / HTTP/1.1\r\nHost: new.example\r\n\r\nA"]
+    );
+
+    // A fresh handle on the same directory sees the saved entries.
+    let reopened = FuzzCorpus::open(&dir).unwrap();
+    assert_eq!(reopened.load("cl-te-edge", "h").unwrap().len(), 1);
+    assert!(reopened.load("h2c", "h").unwrap().is_empty());
+
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,431 @@
+//! Tests for the daemon module
+//!
+//! Drives the REST API over loopback with a stubbed scan runner.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use smugglex::daemon::{
+    DEFAULT_MAX_FINISHED_JOBS, DEFAULT_READ_TIMEOUT, DaemonConfig, ScanFuture, ScanRunner,
+    parse_interval, parse_notify_url, serve_daemon,
+};
+use smugglex::error::{Result, SmugglexError};
+use smugglex::model::{CheckResult, ScanResults};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+struct StubRunner;
+
+impl ScanRunner for StubRunner {
+    fn validate(&self, args: &[String]) -> Result<()> {
+        if args.iter().any(|a| a == "--bogus") {
+            return Err(SmugglexError::Config("unknown flag --bogus".to_string()));
+        }
+        Ok(())
+    }
+
+    fn scan(&self, _args: Vec<String>, target: String) -> ScanFuture {
+        Box::pin(async move {
+            let vulnerable = target.contains("vuln");
+            ScanResults {
+                target,
+                address: None,
+                vhost: None,
+                ip: None,
+                port: None,
+                scheme: None,
+                method: "POST".to_string(),
+                timestamp: "2024-01-01T00:00:00Z".to_string(),
+                fingerprint: None,
+                checks: vec![CheckResult {
+                    check_type: "cl-te".to_string(),
+                    vulnerable,
+                    payload_index: vulnerable.then_some(0),
+                    payload_name: None,
+                    technique: None,
+                    normal_status: "HTTP/1.1 200 OK".to_string(),
+                    attack_status: None,
+                    normal_duration_ms: 10,
+                    attack_duration_ms: None,
+                    normal_connect_ms: None,
+                    attack_connect_ms: None,
+                    timestamp: "2024-01-01T00:00:00Z".to_string(),
+                    payload: None,
+                    confidence: None,
+                    detection_signals: Vec::new(),
+                    diagnostics: Vec::new(),
+                    payloads_sent: 0,
+                    request_errors: Default::default(),
+                    h2_observations: Vec::new(),
+                    fuzz_anomalies: Vec::new(),
+                    unsolicited_bytes: None,
+                    attack_phases: None,
+                    cross_check: None,
+                    evidence: None,
+                    consistency: None,
+                }],
+                exploits: None,
+                proxy_chain: Vec::new(),
+                unreachable: None,
+                throttle: None,
+                target_info: None,
+                error: None,
+                error_info: None,
+                duration_ms: None,
+                requests_sent: None,
+                summary: None,
+            }
+        })
+    }
+}
+
+async fn start(token: Option<&str>) -> String {
+    start_with_state(token, None).await
+}
+
+async fn start_with_state(token: Option<&str>, state_dir: Option<std::path::PathBuf>) -> String {
+    start_with_config(DaemonConfig {
+        listen: String::new(),
+        token: token.map(|t| t.to_string()),
+        state_dir,
+        read_timeout: DEFAULT_READ_TIMEOUT,
+        max_finished_jobs: DEFAULT_MAX_FINISHED_JOBS,
+    })
+    .await
+}
+
+async fn start_with_config(mut config: DaemonConfig) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    config.listen = addr.clone();
+    tokio::spawn(serve_daemon(listener, config, Arc::new(StubRunner)));
+    addr
+}
+
+/// Send one request and return (status code, parsed JSON body).
+async fn call(
+    addr: &str,
+    method: &str,
+    path: &str,
+    body: &str,
+    auth: Option<&str>,
+) -> (u16, serde_json::Value) {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let auth_header = auth
+        .map(|t| format!("Authorization: Bearer {}\r\n", t))
+        .unwrap_or_default();
+    let request = format!(
+        "{} {} HTTP/1.1\r\nHost: x\r\n{}Content-Length: {}\r\n\r\n{}",
+        method,
+        path,
+        auth_header,
+        body.len(),
+        body
+    );
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    let status = response[9..12].parse().unwrap();
+    let json = response.split_once("\r\n\r\n").unwrap().1;
+    (status, serde_json::from_str(json).unwrap())
+}
+
+#[tokio::test]
+async fn test_submit_poll_and_fetch_results() {
+    let addr = start(None).await;
+
+    let (status, job) = call(
+        &addr,
+        "POST",
+        "/scans",
+        r#"{"targets":["http://a","http://vuln"],"args":["-c","cl-te"]}"#,
+        None,
+    )
+    .await;
+    assert_eq!(status, 202);
+    let id = job["id"].as_str().unwrap().to_string();
+
+    let mut state = String::new();
+    for _ in 0..50 {
+        let (_, s) = call(&addr, "GET", &format!("/scans/{}", id), "", None).await;
+        state = s["state"].as_str().unwrap().to_string();
+        if state == "done" {
+            assert_eq!(s["completed"], 2);
+            assert_eq!(s["vulnerable_targets"], 1);
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(state, "done");
+
+    let (status, results) = call(&addr, "GET", &format!("/scans/{}/results", id), "", None).await;
+    assert_eq!(status, 200);
+    assert_eq!(results["summary"]["vulnerable_targets"], 1);
+    assert_eq!(results["results"].as_array().unwrap().len(), 2);
+
+    let (_, sarif) = call(
+        &addr,
+        "GET",
+        &format!("/scans/{}/results?format=sarif", id),
+        "",
+        None,
+    )
+    .await;
+    assert_eq!(sarif["version"], "2.1.0");
+    assert_eq!(sarif["runs"][0]["results"].as_array().unwrap().len(), 1);
+
+    let (_, list) = call(&addr, "GET", "/scans", "", None).await;
+    assert_eq!(list["jobs"].as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_invalid_submissions_are_rejected() {
+    let addr = start(None).await;
+    let (status, _) = call(&addr, "POST", "/scans", "not json", None).await;
+    assert_eq!(status, 400);
+    let (status, _) = call(&addr, "POST", "/scans", r#"{"targets":[]}"#, None).await;
+    assert_eq!(status, 400);
+    let (status, body) = call(
+        &addr,
+        "POST",
+        "/scans",
+        r#"{"targets":["http://a"],"args":["--bogus"]}"#,
+        None,
+    )
+    .await;
+    assert_eq!(status, 400);
+    assert!(body["error"].as_str().unwrap().contains("--bogus"));
+    let (status, _) = call(&addr, "GET", "/scans/job-99", "", None).await;
+    assert_eq!(status, 404);
+    let (status, _) = call(&addr, "DELETE", "/scans", "", None).await;
+    assert_eq!(status, 405);
+}
+
+#[tokio::test]
+async fn test_token_is_required_when_configured() {
+    let addr = start(Some("t0k")).await;
+    let (status, _) = call(&addr, "GET", "/health", "", None).await;
+    assert_eq!(status, 401);
+    let (status, _) = call(&addr, "GET", "/health", "", Some("wrong")).await;
+    assert_eq!(status, 401);
+    // Same length or a prefix of the token is no closer to passing.
+    for wrong in ["t0x", "t0", "t0kk"] {
+        let (status, _) = call(&addr, "GET", "/health", "", Some(wrong)).await;
+        assert_eq!(status, 401);
+    }
+    let (status, body) = call(&addr, "GET", "/health", "", Some("t0k")).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["status"], "ok");
+}
+
+#[tokio::test]
+async fn test_silent_client_gets_request_timeout() {
+    let addr = start_with_config(DaemonConfig {
+        listen: String::new(),
+        token: None,
+        state_dir: None,
+        read_timeout: Duration::from_millis(200),
+        max_finished_jobs: DEFAULT_MAX_FINISHED_JOBS,
+    })
+    .await;
+    // A client that connects and never sends a request is answered with 408
+    // instead of holding its connection open.
+    let mut stream = TcpStream::connect(&addr).await.unwrap();
+    let mut response = String::new();
+    tokio::time::timeout(Duration::from_secs(5), stream.read_to_string(&mut response))
+        .await
+        .expect("daemon closed the connection")
+        .unwrap();
+    assert!(response.starts_with("HTTP/1.1 408 Request Timeout"));
+}
+
+#[tokio::test]
+async fn test_oldest_finished_jobs_are_dropped() {
+    let addr = start_with_config(DaemonConfig {
+        listen: String::new(),
+        token: None,
+        state_dir: None,
+        read_timeout: DEFAULT_READ_TIMEOUT,
+        max_finished_jobs: 2,
+    })
+    .await;
+    let mut ids = Vec::new();
+    for _ in 0..3 {
+        let (_, job) = call(&addr, "POST", "/scans", r#"{"targets":["http://a"]}"#, None).await;
+        ids.push(job["id"].as_str().unwrap().to_string());
+    }
+    // Pruning follows the last job's completion, so poll until it shows.
+    let mut listed = Vec::new();
+    for _ in 0..100 {
+        let (_, list) = call(&addr, "GET", "/scans", "", None).await;
+        listed = list["jobs"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|j| j["id"].as_str().unwrap().to_string())
+            .collect();
+        if listed.len() == 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(listed, ids[1..].to_vec());
+    let (status, _) = call(&addr, "GET", &format!("/scans/{}", ids[0]), "", None).await;
+    assert_eq!(status, 404);
+}
+
+#[test]
+fn test_parse_interval() {
+    assert_eq!(parse_interval("90").unwrap(), Duration::from_secs(90));
+    assert_eq!(parse_interval("30m").unwrap(), Duration::from_secs(1800));
+    assert_eq!(parse_interval("24h").unwrap(), Duration::from_secs(86400));
+    assert_eq!(parse_interval("7d").unwrap(), Duration::from_secs(604800));
+    assert!(parse_interval("0s").is_err());
+    assert!(parse_interval("5w").is_err());
+    assert!(parse_interval("h").is_err());
+    assert_eq!(
+        parse_interval("365d").unwrap(),
+        smugglex::daemon::MAX_INTERVAL
+    );
+    assert!(parse_interval("366d").is_err());
+    assert!(parse_interval("9999999999d").is_err());
+    assert!(parse_interval("99999999999999999999").is_err());
+}
+
+#[test]
+fn test_parse_notify_url() {
+    assert!(parse_notify_url("https://hooks.example/smugglex?x=1").is_ok());
+    assert!(parse_notify_url("http://10.0.0.5:8080/hook").is_ok());
+    for bad in [
+        "hooks.example/x",
+        "ftp://hooks.example/x",
+        "file:///tmp/x",
+        "http://",
+        "",
+    ] {
+        assert!(parse_notify_url(bad).is_err(), "{bad} should be rejected");
+    }
+}
+
+#[tokio::test]
+async fn test_schedule_runs_record_history_and_persist() {
+    let dir = std::env::temp_dir().join(format!("smugglex-daemon-state-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let addr = start_with_state(None, Some(dir.clone())).await;
+
+    let (status, schedule) = call(
+        &addr,
+        "POST",
+        "/schedules",
+        r#"{"targets":["http://a","http://vuln"],"args":["-c","cl-te"],"every":"1s"}"#,
+        None,
+    )
+    .await;
+    assert_eq!(status, 201);
+    let id = schedule["id"].as_str().unwrap().to_string();
+
+    let mut history = Vec::new();
+    for _ in 0..100 {
+        let (_, s) = call(&addr, "GET", &format!("/schedules/{}", id), "", None).await;
+        history = s["history"].as_array().unwrap().clone();
+        if history.len() >= 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert!(history.len() >= 2, "expected two recorded runs");
+    // The first run reports the finding as new; the next run does not repeat it.
+    assert_eq!(history[0]["new_findings"][0], "http://vuln cl-te");
+    assert_eq!(history[0]["vulnerable_targets"], 1);
+    assert!(history[1]["new_findings"].as_array().unwrap().is_empty());
+
+    let (_, jobs) = call(&addr, "GET", "/scans", "", None).await;
+    assert_eq!(jobs["jobs"][0]["schedule_id"], id.as_str());
+
+    // A daemon restarted on the same state directory picks the schedule up.
+    let restarted = start_with_state(None, Some(dir.clone())).await;
+    let (status, list) = call(&restarted, "GET", "/schedules", "", None).await;
+    assert_eq!(status, 200);
+    assert_eq!(list["schedules"][0]["id"], id.as_str());
+
+    let (status, _) = call(
+        &restarted,
+        "DELETE",
+        &format!("/schedules/{}", id),
+        "",
+        None,
+    )
+    .await;
+    assert_eq!(status, 200);
+    let (status, _) = call(&restarted, "GET", &format!("/schedules/{}", id), "", None).await;
+    assert_eq!(status, 404);
+    let (status, _) = call(
+        &restarted,
+        "POST",
+        "/schedules",
+        r#"{"targets":["http://a"],"every":"soon"}"#,
+        None,
+    )
+    .await;
+    assert_eq!(status, 400);
+    let (status, body) = call(
+        &restarted,
+        "POST",
+        "/schedules",
+        r#"{"targets":["http://a"],"every":"9999999999d"}"#,
+        None,
+    )
+    .await;
+    assert_eq!(status, 400);
+    assert!(
+        body["error"]
+            .as_str()
+            .unwrap()
+            .contains("longer than 365 days")
+    );
+    let (status, body) = call(
+        &restarted,
+        "POST",
+        "/schedules",
+        r#"{"targets":["http://a"],"every":"1h","notify_url":"ftp://hooks.example/x"}"#,
+        None,
+    )
+    .await;
+    assert_eq!(status, 400);
+    assert!(body["error"].as_str().unwrap().contains("notify_url"));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn test_persisted_oversized_interval_does_not_stop_scheduler() {
+    let dir = std::env::temp_dir().join(format!("smugglex-daemon-huge-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let schedule = serde_json::json!([{
+        "id": "schedule-1",
+        "targets": ["http://a"],
+        "args": [],
+        "every": "9999999999d",
+        "interval_secs": u64::MAX,
+        "created_at": "2024-01-01T00:00:00Z",
+        "next_run_at": "2024-01-01T00:00:00Z",
+    }]);
+    std::fs::write(dir.join("schedules.json"), schedule.to_string()).unwrap();
+    let addr = start_with_state(None, Some(dir.clone())).await;
+
+    let mut history = Vec::new();
+    for _ in 0..100 {
+        let (_, s) = call(&addr, "GET", "/schedules/schedule-1", "", None).await;
+        history = s["history"].as_array().unwrap().clone();
+        if !history.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert_eq!(history.len(), 1, "the due run completes");
+    let (_, s) = call(&addr, "GET", "/schedules/schedule-1", "", None).await;
+    assert!(s["next_run_at"].as_str().unwrap() > "2025");
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
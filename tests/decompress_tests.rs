@@ -0,0 +1,148 @@
+//! Tests for the decompress module
+//!
+//! - Decoding gzip, deflate (zlib-wrapped and bare), br and stacked codings
+//! - Unknown codings and corrupt bodies left undecoded
+//! - Rewriting a compressed response as an uncompressed one
+//! - `--no-decompress` leaving responses untouched
+
+use std::io::Write;
+use std::sync::Mutex;
+
+use flate2::Compression;
+use flate2::write::{DeflateEncoder, GzEncoder, ZlibEncoder};
+use smugglex::decompress::{decode_body, decompress_response, set_decompression};
+use smugglex::http::HttpResponse;
+
+/// Held by tests of `decompress_response`, which depend on the process-wide
+/// `set_decompression` switch.
+static DECOMPRESSION: Mutex<()> = Mutex::new(());
+
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+fn zlib(data: &[u8]) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+fn raw_deflate(data: &[u8]) -> Vec<u8> {
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+/// `data` as a brotli stream of one uncompressed meta-block (at most 64 KiB).
+fn brotli_stored(data: &[u8]) -> Vec<u8> {
+    // WBITS=16, ISLAST=0, MNIBBLES=4, MLEN-1 in 16 bits, ISUNCOMPRESSED=1.
+    let bits = ((data.len() as u32 - 1) << 4) | (1 << 20);
+    let mut out = bits.to_le_bytes()[..3].to_vec();
+    out.extend_from_slice(data);
+    // Empty last meta-block: ISLAST=1, ISLASTEMPTY=1.
+    out.push(0x03);
+    out
+}
+
+#[test]
+fn test_decode_body_gzip_and_deflate() {
+    let text = b"<html>decoded page</html>";
+    assert_eq!(decode_body("gzip", &gzip(text)).unwrap(), text);
+    assert_eq!(decode_body("X-Gzip", &gzip(text)).unwrap(), text);
+    assert_eq!(decode_body("deflate", &zlib(text)).unwrap(), text);
+    assert_eq!(decode_body("deflate", &raw_deflate(text)).unwrap(), text);
+    assert_eq!(decode_body("identity", text).unwrap(), text);
+}
+
+#[test]
+fn test_decode_body_brotli() {
+    let text = b"hello, brotli";
+    assert_eq!(decode_body("br", &brotli_stored(text)).unwrap(), text);
+}
+
+#[test]
+fn test_decode_body_stacked_codings() {
+    let text = b"twice compressed";
+    // Applied deflate first, then gzip: decoded in reverse.
+    let body = gzip(&zlib(text));
+    assert_eq!(decode_body("deflate, gzip", &body).unwrap(), text);
+    assert!(decode_body("gzip, deflate", &body).is_none());
+}
+
+#[test]
+fn test_decode_body_rejects_unknown_and_corrupt() {
+    let body = gzip(b"some response body that is long enough");
+    assert!(decode_body("zstd", &body).is_none());
+    assert!(decode_body("gzip", &body[..body.len() / 2]).is_none());
+    assert!(decode_body("gzip", b"not gzip at all").is_none());
+}
+
+#[test]
+fn test_decompress_response_rewrites_content_length_response() {
+    let _guard = DECOMPRESSION.lock().unwrap_or_else(|e| e.into_inner());
+    let body = gzip(b"hello world");
+    let mut raw = format!(
+        "HTTP/1.1 200 OK\r\nContent-Encoding: gzip\r\nContent-Length: {}\r\nX-Test: 1\r\n\r\n",
+        body.len()
+    )
+    .into_bytes();
+    raw.extend_from_slice(&body);
+    raw.extend_from_slice(b"HTTP/1.1 404 Not Found\r\n\r\n");
+
+    let decoded = decompress_response(raw);
+    let response = HttpResponse::parse(&decoded);
+    assert_eq!(response.status, Some(200));
+    assert_eq!(response.body, b"hello world");
+    assert_eq!(response.header("content-length"), Some("11"));
+    assert_eq!(response.header("x-test"), Some("1"));
+    assert!(response.header("content-encoding").is_none());
+    assert_eq!(response.trailing, b"HTTP/1.1 404 Not Found\r\n\r\n");
+}
+
+#[test]
+fn test_decompress_response_rewrites_chunked_response() {
+    let _guard = DECOMPRESSION.lock().unwrap_or_else(|e| e.into_inner());
+    let body = gzip(b"chunked and compressed");
+    let (first, second) = body.split_at(body.len() / 2);
+    let mut raw =
+        b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nContent-Encoding: gzip\r\n\r\n".to_vec();
+    for chunk in [first, second] {
+        raw.extend_from_slice(format!("{:x}\r\n", chunk.len()).as_bytes());
+        raw.extend_from_slice(chunk);
+        raw.extend_from_slice(b"\r\n");
+    }
+    raw.extend_from_slice(b"0\r\nX-Checksum: abc\r\n\r\n");
+
+    let decoded = decompress_response(raw);
+    let response = HttpResponse::parse(&decoded);
+    assert!(!response.chunked);
+    assert_eq!(response.body, b"chunked and compressed");
+    assert_eq!(response.header("x-checksum"), Some("abc"));
+    assert!(response.header("transfer-encoding").is_none());
+    assert!(response.trailing.is_empty());
+}
+
+#[test]
+fn test_decompress_response_keeps_undecodable_and_disabled() {
+    let _guard = DECOMPRESSION.lock().unwrap_or_else(|e| e.into_inner());
+    let raw =
+        b"HTTP/1.1 200 OK\r\nContent-Encoding: gzip\r\nContent-Length: 4\r\n\r\nabcd".to_vec();
+    assert_eq!(decompress_response(raw.clone()), raw);
+
+    let plain = b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok".to_vec();
+    assert_eq!(decompress_response(plain.clone()), plain);
+
+    let body = gzip(b"hello");
+    let mut compressed = format!(
+        "HTTP/1.1 200 OK\r\nContent-Encoding: gzip\r\nContent-Length: {}\r\n\r\n",
+        body.len()
+    )
+    .into_bytes();
+    compressed.extend_from_slice(&body);
+    set_decompression(false);
+    let kept = decompress_response(compressed.clone());
+    set_decompression(true);
+    assert_eq!(kept, compressed);
+}
@@ -0,0 +1,212 @@
+//! Tests for the distributed module
+//!
+//! Runs a coordinator and workers over loopback TCP with a stubbed scan
+//! function, exercising sharding, authentication and result aggregation.
+
+use std::time::Duration;
+
+use smugglex::distributed::{
+    CoordinatorConfig, MAX_HELLO_LINE, connect_worker, run_worker_shards, serve_coordinator,
+    shard_targets,
+};
+use smugglex::model::ScanResults;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+fn stub_result(target: String) -> ScanResults {
+    ScanResults {
+        target,
+        address: None,
+        vhost: None,
+        ip: None,
+        port: None,
+        scheme: None,
+        method: "POST".to_string(),
+        timestamp: "2024-01-01T00:00:00Z".to_string(),
+        fingerprint: None,
+        checks: Vec::new(),
+        exploits: None,
+        proxy_chain: Vec::new(),
+        unreachable: None,
+        throttle: None,
+        target_info: None,
+        error: None,
+        error_info: None,
+        duration_ms: None,
+        requests_sent: None,
+        summary: None,
+    }
+}
+
+fn config(token: &str, shard_size: usize) -> CoordinatorConfig {
+    CoordinatorConfig {
+        listen: "127.0.0.1:0".to_string(),
+        token: token.to_string(),
+        shard_size,
+        profile: vec!["-c".to_string(), "cl-te".to_string()],
+    }
+}
+
+#[test]
+fn test_shard_targets_splits_in_order() {
+    let targets: Vec<String> = (0..5).map(|i| format!("http://t{}", i)).collect();
+    let shards = shard_targets(&targets, 2);
+    assert_eq!(shards.len(), 3);
+    assert_eq!(shards[0], vec!["http://t0", "http://t1"]);
+    assert_eq!(shards[2], vec!["http://t4"]);
+    // A zero shard size is clamped rather than looping forever.
+    assert_eq!(shard_targets(&targets, 0).len(), 5);
+    assert!(shard_targets(&[], 3).is_empty());
+}
+
+#[tokio::test]
+async fn test_coordinator_aggregates_worker_results_in_order() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let targets: Vec<String> = (0..5).map(|i| format!("http://t{}", i)).collect();
+
+    let coordinator = tokio::spawn(serve_coordinator(
+        listener,
+        config("s3cret", 2),
+        targets.clone(),
+    ));
+
+    let mut workers = Vec::new();
+    for name in ["w1", "w2"] {
+        let addr = addr.clone();
+        workers.push(tokio::spawn(async move {
+            let (profile, conn) = connect_worker(&addr, "s3cret", name).await.unwrap();
+            assert_eq!(profile, vec!["-c".to_string(), "cl-te".to_string()]);
+            run_worker_shards(conn, |t| async move { stub_result(t) })
+                .await
+                .unwrap()
+        }));
+    }
+
+    let results = coordinator.await.unwrap().unwrap();
+    let scanned: Vec<String> = results.into_iter().map(|r| r.target).collect();
+    assert_eq!(scanned, targets);
+
+    let total: usize = futures::future::join_all(workers)
+        .await
+        .into_iter()
+        .map(|r| r.unwrap())
+        .sum();
+    assert_eq!(total, 5);
+}
+
+#[tokio::test]
+async fn test_worker_with_wrong_token_is_rejected() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let coordinator = tokio::spawn(serve_coordinator(
+        listener,
+        config("right", 10),
+        vec!["http://t0".to_string()],
+    ));
+
+    let err = connect_worker(&addr, "wrong", "intruder").await;
+    assert!(err.is_err());
+
+    // The shard is still available to an authenticated worker afterwards.
+    let (_, conn) = connect_worker(&addr, "right", "ok").await.unwrap();
+    let scanned = run_worker_shards(conn, |t| async move { stub_result(t) })
+        .await
+        .unwrap();
+    assert_eq!(scanned, 1);
+    assert_eq!(coordinator.await.unwrap().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_repeated_next_requeues_held_shard() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let targets: Vec<String> = (0..2).map(|i| format!("http://t{}", i)).collect();
+    let coordinator = tokio::spawn(serve_coordinator(
+        listener,
+        config("s3cret", 1),
+        targets.clone(),
+    ));
+
+    // A worker that asks for a second shard without returning the first, then
+    // leaves: neither shard may stay stuck in flight.
+    let (_, conn) = connect_worker(&addr, "s3cret", "greedy").await.unwrap();
+    let (reader, mut writer) = conn.into_split();
+    let mut lines = reader.lines();
+    for _ in 0..2 {
+        writer.write_all(b"{\"type\":\"next\"}\n").await.unwrap();
+        assert!(lines.next_line().await.unwrap().unwrap().contains("shard"));
+    }
+    drop((lines, writer));
+
+    let (_, conn) = connect_worker(&addr, "s3cret", "ok").await.unwrap();
+    let scanned = run_worker_shards(conn, |t| async move { stub_result(t) })
+        .await
+        .unwrap();
+    assert_eq!(scanned, 2);
+    let results = tokio::time::timeout(Duration::from_secs(5), coordinator)
+        .await
+        .expect("coordinator finished")
+        .unwrap()
+        .unwrap();
+    let scanned: Vec<String> = results.into_iter().map(|r| r.target).collect();
+    assert_eq!(scanned, targets);
+}
+
+#[tokio::test]
+async fn test_oversized_hello_is_dropped() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let coordinator = tokio::spawn(serve_coordinator(
+        listener,
+        config("s3cret", 10),
+        vec!["http://t0".to_string()],
+    ));
+
+    // The coordinator closes the connection instead of buffering an endless line.
+    let mut stream = TcpStream::connect(&addr).await.unwrap();
+    let _ = stream
+        .write_all(&vec![b'x'; MAX_HELLO_LINE as usize * 2])
+        .await;
+    let mut buf = Vec::new();
+    let closed = tokio::time::timeout(Duration::from_secs(5), stream.read_to_end(&mut buf))
+        .await
+        .expect("connection closed");
+    assert!(closed.is_err() || buf.is_empty());
+
+    let (_, conn) = connect_worker(&addr, "s3cret", "ok").await.unwrap();
+    let scanned = run_worker_shards(conn, |t| async move { stub_result(t) })
+        .await
+        .unwrap();
+    assert_eq!(scanned, 1);
+    assert_eq!(coordinator.await.unwrap().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_worker_rejects_coordinator_without_token() {
+    // A fake coordinator that cannot prove it holds the token gets no answer
+    // to its challenge, and the token never crosses the wire.
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let fake = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        let hello = lines.next_line().await.unwrap().unwrap();
+        writer
+            .write_all(b"{\"type\":\"challenge\",\"nonce\":\"AAAA\",\"proof\":\"AAAA\"}\n")
+            .await
+            .unwrap();
+        (hello, lines.next_line().await.ok().flatten())
+    });
+
+    let err = connect_worker(&addr, "s3cret", "w1").await.err().unwrap();
+    assert!(
+        err.to_string().contains("does not hold the token"),
+        "{}",
+        err
+    );
+    let (hello, reply) = fake.await.unwrap();
+    assert!(!hello.contains("s3cret"));
+    assert_eq!(reply, None);
+}
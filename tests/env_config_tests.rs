@@ -0,0 +1,50 @@
+//! Tests for configuring options through `SMUGGLEX_*` environment variables
+//!
+//! Kept apart from cli_tests because the variables are process-wide.
+
+use clap::{CommandFactory, Parser};
+use smugglex::cli::{Cli, Command, OutputFormat};
+
+#[test]
+fn test_options_from_environment() {
+    // SAFETY: the only test in this binary, so nothing reads the environment concurrently
+    unsafe {
+        std::env::set_var("SMUGGLEX_TIMEOUT", "30");
+        std::env::set_var("SMUGGLEX_CHECKS", "cl-te,te-cl");
+        std::env::set_var("SMUGGLEX_FORMAT", "json");
+        std::env::set_var("SMUGGLEX_QUIET", "true");
+        std::env::set_var("SMUGGLEX_EXIT_FIRST", "false");
+        std::env::set_var("SMUGGLEX_TOKEN", "s3cret");
+    }
+
+    let cli = Cli::parse_from(["smugglex", "https://example.com"]);
+    assert_eq!(cli.timeout, 30);
+    assert_eq!(cli.checks.as_deref(), Some("cl-te,te-cl"));
+    assert!(matches!(cli.format, OutputFormat::Json));
+    assert!(cli.quiet);
+    assert!(!cli.exit_first);
+
+    // The command line wins over the environment
+    let cli = Cli::parse_from(["smugglex", "-t", "5", "https://example.com"]);
+    assert_eq!(cli.timeout, 5);
+
+    let cli = Cli::parse_from(["smugglex", "daemon"]);
+    match cli.command {
+        Some(Command::Daemon(args)) => assert_eq!(args.token.as_deref(), Some("s3cret")),
+        other => panic!("expected daemon, got {:?}", other),
+    }
+
+    // A coordinator forwards settings from its environment to its workers
+    let matches = Cli::command()
+        .try_get_matches_from(["smugglex", "serve", "--role", "coordinator"])
+        .unwrap();
+    let profile = Cli::worker_profile(&matches);
+    for arg in ["--timeout=30", "--checks=cl-te,te-cl", "--quiet"] {
+        assert!(profile.contains(&arg.to_string()), "{arg} in {profile:?}");
+    }
+    assert!(!profile.iter().any(|a| a.starts_with("--exit-first")));
+
+    // Invalid values are rejected like their flags
+    unsafe { std::env::set_var("SMUGGLEX_TIMEOUT", "0") };
+    assert!(Cli::try_parse_from(["smugglex", "https://example.com"]).is_err());
+}
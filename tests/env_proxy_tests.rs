@@ -0,0 +1,72 @@
+//! Tests for the env_proxy module
+//!
+//! Picks the proxy for a target from HTTP_PROXY / HTTPS_PROXY / NO_PROXY.
+
+use smugglex::env_proxy::EnvProxy;
+use std::collections::HashMap;
+
+fn env(vars: &[(&str, &str)]) -> EnvProxy {
+    let vars: HashMap<String, String> = vars
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    EnvProxy::from_lookup(|name| vars.get(name).cloned())
+}
+
+#[test]
+fn test_proxy_per_scheme() {
+    let proxy = env(&[
+        ("HTTP_PROXY", "http://plain:3128"),
+        ("https_proxy", "secure:3129"),
+        ("HTTPS_PROXY", "http://ignored:1"),
+    ]);
+    assert_eq!(
+        proxy.proxy_for("example.com", false),
+        Some("http://plain:3128")
+    );
+    assert_eq!(
+        proxy.proxy_for("example.com", true),
+        Some("http://secure:3129")
+    );
+
+    let proxy = env(&[("ALL_PROXY", "http://all:8080"), ("HTTP_PROXY", "")]);
+    assert_eq!(
+        proxy.proxy_for("example.com", false),
+        Some("http://all:8080")
+    );
+    assert_eq!(
+        proxy.proxy_for("example.com", true),
+        Some("http://all:8080")
+    );
+
+    assert!(env(&[]).is_empty());
+    assert_eq!(env(&[]).proxy_for("example.com", true), None);
+}
+
+#[test]
+fn test_no_proxy() {
+    let proxy = env(&[
+        ("HTTPS_PROXY", "http://proxy:3128"),
+        (
+            "NO_PROXY",
+            "localhost, .internal.corp,*.svc,10.0.0.5:8443,[::1]",
+        ),
+    ]);
+    assert_eq!(proxy.proxy_for("localhost", true), None);
+    assert_eq!(proxy.proxy_for("api.internal.corp", true), None);
+    assert_eq!(proxy.proxy_for("internal.corp", true), None);
+    assert_eq!(proxy.proxy_for("db.svc", true), None);
+    assert_eq!(proxy.proxy_for("10.0.0.5", true), None);
+    assert_eq!(proxy.proxy_for("[::1]", true), None);
+    assert_eq!(
+        proxy.proxy_for("notinternal.corp", true),
+        Some("http://proxy:3128")
+    );
+    assert_eq!(
+        proxy.proxy_for("example.com", true),
+        Some("http://proxy:3128")
+    );
+
+    let proxy = env(&[("HTTPS_PROXY", "http://proxy:3128"), ("no_proxy", "*")]);
+    assert!(proxy.bypasses("example.com"));
+}
@@ -4,18 +4,29 @@
 //! - Display trait implementation for SmugglexError
 //! - From trait implementations for converting external errors
 //! - Error trait implementation
+//! - Retryable classification and the JSON error info
 
-use smugglex::error::SmugglexError;
+use smugglex::error::{SmugglexError, TimeoutPhase};
 use std::error::Error;
 use std::io;
 
-/// Test Display implementation for HttpRequest variant
+/// Test Display implementation for Dns variant
 #[test]
-fn test_display_http_request_error() {
-    let err = SmugglexError::HttpRequest("connection failed".to_string());
+fn test_display_dns_error() {
+    let err = SmugglexError::Dns("example.invalid: no such host".to_string());
     assert_eq!(
         format!("{}", err),
-        "HTTP request error: connection failed (check target connectivity and verify the URL is reachable)"
+        "DNS error: example.invalid: no such host (check that the host name resolves)"
+    );
+}
+
+/// Test Display implementation for Connect variant
+#[test]
+fn test_display_connect_error() {
+    let err = SmugglexError::Connect("connection failed".to_string());
+    assert_eq!(
+        format!("{}", err),
+        "Connection error: connection failed (check target connectivity and verify the URL is reachable)"
     );
 }
 
@@ -29,14 +40,11 @@ fn test_display_tls_error() {
     );
 }
 
-/// Test Display implementation for UrlParse variant
+/// Test Display implementation for Parse variant
 #[test]
-fn test_display_url_parse_error() {
-    let err = SmugglexError::UrlParse("invalid URL".to_string());
-    assert_eq!(
-        format!("{}", err),
-        "URL parsing error: invalid URL (ensure the URL includes scheme, e.g. http:// or https://)"
-    );
+fn test_display_parse_error() {
+    let err = SmugglexError::Parse("invalid URL".to_string());
+    assert_eq!(format!("{}", err), "Parse error: invalid URL");
 }
 
 /// Test Display implementation for Io variant
@@ -46,30 +54,34 @@ fn test_display_io_error() {
     assert_eq!(format!("{}", err), "I/O error: file not found");
 }
 
-/// Test Display implementation for Json variant
-#[test]
-fn test_display_json_error() {
-    let err = SmugglexError::Json("invalid JSON".to_string());
-    assert_eq!(format!("{}", err), "JSON error: invalid JSON");
-}
-
 /// Test Display implementation for Timeout variant
 #[test]
 fn test_display_timeout_error() {
-    let err = SmugglexError::Timeout("request timed out".to_string());
+    let err = SmugglexError::timeout(TimeoutPhase::FirstByte, "request timed out");
     assert_eq!(
         format!("{}", err),
-        "Timeout: request timed out (try increasing timeout with -t option)"
+        "Timeout (first-byte): request timed out (try increasing timeout with -t option)"
     );
 }
 
-/// Test Display implementation for InvalidInput variant
+/// Test Display implementation for Config variant
 #[test]
-fn test_display_invalid_input_error() {
-    let err = SmugglexError::InvalidInput("bad argument".to_string());
+fn test_display_config_error() {
+    let err = SmugglexError::Config("bad argument".to_string());
     assert_eq!(format!("{}", err), "Invalid input: bad argument");
 }
 
+/// Test Display implementation for TargetDestabilized variant
+#[test]
+fn test_display_target_destabilized_error() {
+    let err = SmugglexError::TargetDestabilized("5 consecutive failed payloads".to_string());
+    assert_eq!(
+        format!("{}", err),
+        "target destabilized: 5 consecutive failed payloads"
+    );
+    assert!(err.request_error_kind().is_none());
+}
+
 /// Test From<std::io::Error> implementation
 #[test]
 fn test_from_io_error() {
@@ -87,7 +99,13 @@ fn test_from_io_error_timedout_maps_to_timeout() {
     let io_err = io::Error::new(io::ErrorKind::TimedOut, "timed out");
     let smugglex_err: SmugglexError = io_err.into();
     assert!(
-        matches!(smugglex_err, SmugglexError::Timeout(_)),
+        matches!(
+            smugglex_err,
+            SmugglexError::Timeout {
+                phase: TimeoutPhase::Request,
+                ..
+            }
+        ),
         "TimedOut io::Error should classify as Timeout"
     );
     assert!(smugglex_err.to_string().contains("try increasing timeout"));
@@ -110,7 +128,7 @@ fn test_from_elapsed_maps_to_timeout() {
         .unwrap_err()
     });
     let smugglex_err: SmugglexError = elapsed.into();
-    assert!(matches!(smugglex_err, SmugglexError::Timeout(_)));
+    assert!(matches!(smugglex_err, SmugglexError::Timeout { .. }));
 }
 
 /// Test From<serde_json::Error> implementation
@@ -121,14 +139,14 @@ fn test_from_serde_json_error() {
         serde_json::from_str::<serde_json::Value>(json_str).unwrap_err();
     let smugglex_err: SmugglexError = json_err.into();
     match smugglex_err {
-        SmugglexError::Json(msg) => {
+        SmugglexError::Parse(msg) => {
             assert!(
                 msg.contains("key must be a string")
                     || msg.contains("expected")
                     || msg.contains("invalid")
             );
         }
-        _ => panic!("Expected Json error variant"),
+        _ => panic!("Expected Parse error variant"),
     }
 }
 
@@ -138,8 +156,11 @@ fn test_from_url_parse_error() {
     let url_err = url::Url::parse("http://[::1").unwrap_err();
     let smugglex_err: SmugglexError = url_err.into();
     match smugglex_err {
-        SmugglexError::UrlParse(msg) => assert!(msg.contains("invalid") || msg.contains("port")),
-        _ => panic!("Expected UrlParse error variant"),
+        SmugglexError::Parse(msg) => {
+            assert!(msg.contains("invalid URL"));
+            assert!(msg.contains("ensure the URL includes scheme"));
+        }
+        _ => panic!("Expected Parse error variant"),
     }
 }
 
@@ -148,15 +169,15 @@ fn test_from_url_parse_error() {
 fn test_from_str() {
     let smugglex_err: SmugglexError = "test error message".into();
     match smugglex_err {
-        SmugglexError::InvalidInput(msg) => assert_eq!(msg, "test error message"),
-        _ => panic!("Expected InvalidInput error variant"),
+        SmugglexError::Config(msg) => assert_eq!(msg, "test error message"),
+        _ => panic!("Expected Config error variant"),
     }
 }
 
 /// Test Error trait implementation - source method
 #[test]
 fn test_error_trait_source() {
-    let err = SmugglexError::HttpRequest("test".to_string());
+    let err = SmugglexError::Connect("test".to_string());
     // Since SmugglexError doesn't implement source (no underlying cause), it should return None
     assert!(err.source().is_none());
 }
@@ -164,7 +185,7 @@ fn test_error_trait_source() {
 /// Test that SmugglexError implements std::error::Error
 #[test]
 fn test_error_trait_implementation() {
-    let err = SmugglexError::InvalidInput("test".to_string());
+    let err = SmugglexError::Config("test".to_string());
     // Just ensure it implements Error trait by calling methods
     let _description = err.to_string();
     assert!(err.source().is_none());
@@ -183,3 +204,126 @@ fn test_error_conversion_chain() {
         _ => panic!("Expected Io variant"),
     }
 }
+
+/// Network failures map to the error kinds reported in `CheckResult`
+#[test]
+fn test_request_error_kind_classification() {
+    use smugglex::model::RequestErrorKind;
+
+    let refused: SmugglexError = io::Error::new(
+        io::ErrorKind::ConnectionRefused,
+        "Connection refused (os error 111)",
+    )
+    .into();
+    assert_eq!(
+        refused.request_error_kind(),
+        Some(RequestErrorKind::ConnectRefused)
+    );
+    let reset: SmugglexError = io::Error::new(
+        io::ErrorKind::ConnectionReset,
+        "Connection reset by peer (os error 104)",
+    )
+    .into();
+    assert_eq!(reset.request_error_kind(), Some(RequestErrorKind::Reset));
+    let dns: SmugglexError =
+        io::Error::other("failed to lookup address information: Name or service not known").into();
+    assert_eq!(dns.request_error_kind(), Some(RequestErrorKind::Dns));
+    assert_eq!(
+        SmugglexError::Dns("no such host".into()).request_error_kind(),
+        Some(RequestErrorKind::Dns)
+    );
+    let timeout: SmugglexError = io::Error::new(io::ErrorKind::TimedOut, "timed out").into();
+    assert_eq!(
+        timeout.request_error_kind(),
+        Some(RequestErrorKind::Timeout)
+    );
+    assert_eq!(
+        SmugglexError::Tls("handshake failed: bad certificate".into()).request_error_kind(),
+        Some(RequestErrorKind::TlsHandshake)
+    );
+    let other: SmugglexError = io::Error::other("something odd").into();
+    assert_eq!(other.request_error_kind(), Some(RequestErrorKind::Other));
+    assert_eq!(
+        SmugglexError::Config("bad".into()).request_error_kind(),
+        None
+    );
+}
+
+/// Preflight failures map to the categories recorded in `ScanResults`
+#[test]
+fn test_unreachable_kind() {
+    use smugglex::model::Unreachable;
+
+    let refused: SmugglexError =
+        io::Error::new(io::ErrorKind::ConnectionRefused, "Connection refused").into();
+    assert_eq!(refused.unreachable_kind(), Unreachable::Refused);
+    let dns: SmugglexError = io::Error::other("failed to lookup address information").into();
+    assert_eq!(dns.unreachable_kind(), Unreachable::DnsFailure);
+    assert_eq!(
+        SmugglexError::Tls("handshake failed".into()).unreachable_kind(),
+        Unreachable::TlsError
+    );
+    let reset: SmugglexError =
+        io::Error::new(io::ErrorKind::ConnectionReset, "Connection reset by peer").into();
+    assert_eq!(reset.unreachable_kind(), Unreachable::HttpError);
+    assert_eq!(Unreachable::DnsFailure.to_string(), "dns-failure");
+}
+
+/// Connections that failed to open or were reset are worth another try;
+/// stalled responses, DNS, TLS and input errors are not
+#[test]
+fn test_is_retryable() {
+    let reset: SmugglexError =
+        io::Error::new(io::ErrorKind::ConnectionReset, "Connection reset by peer").into();
+    assert!(reset.is_retryable());
+    assert!(SmugglexError::Connect("network is unreachable".into()).is_retryable());
+    assert!(SmugglexError::timeout(TimeoutPhase::Connect, "timed out").is_retryable());
+
+    let refused: SmugglexError =
+        io::Error::new(io::ErrorKind::ConnectionRefused, "Connection refused").into();
+    assert!(matches!(refused, SmugglexError::Connect(_)));
+    assert!(!refused.is_retryable());
+    assert!(!SmugglexError::timeout(TimeoutPhase::FirstByte, "timed out").is_retryable());
+    assert!(!SmugglexError::timeout(TimeoutPhase::Transfer, "timed out").is_retryable());
+    assert!(!SmugglexError::Dns("no such host".into()).is_retryable());
+    assert!(!SmugglexError::Tls("handshake failed".into()).is_retryable());
+    assert!(!SmugglexError::Io("file not found".into()).is_retryable());
+    assert!(!SmugglexError::Parse("bad".into()).is_retryable());
+    assert!(!SmugglexError::Config("bad".into()).is_retryable());
+
+    use smugglex::model::RequestErrorKind;
+
+    // Once request bytes were written, a reset is no longer safe to retry
+    let written = reset.after_write();
+    assert!(written.request_written());
+    assert!(!written.is_retryable());
+    assert_eq!(written.kind(), "io");
+    assert_eq!(written.request_error_kind(), Some(RequestErrorKind::Reset));
+    assert!(
+        !SmugglexError::timeout(TimeoutPhase::Connect, "timed out")
+            .after_write()
+            .request_written()
+    );
+}
+
+/// The kind, timeout phase and retryability reported in `ScanResults`
+#[test]
+fn test_error_info() {
+    let info = SmugglexError::timeout(TimeoutPhase::FirstByte, "timed out").info();
+    assert_eq!(info.kind, "timeout");
+    assert_eq!(info.phase, Some(TimeoutPhase::FirstByte));
+    assert!(!info.retryable);
+    let value = serde_json::to_value(&info).unwrap();
+    assert_eq!(value["phase"], "first-byte");
+
+    let info = SmugglexError::Connect("network is unreachable".into()).info();
+    assert_eq!(info.kind, "connect");
+    assert!(info.retryable);
+    let value = serde_json::to_value(&info).unwrap();
+    assert!(value.get("phase").is_none());
+    assert_eq!(SmugglexError::Dns("x".into()).kind(), "dns");
+    assert_eq!(
+        SmugglexError::TargetDestabilized("x".into()).kind(),
+        "target-destabilized"
+    );
+}
@@ -2,15 +2,21 @@
 //!
 //! This module contains tests for:
 //! - Vulnerability context extraction
-//! - Localhost payload generation
+//! - Localhost payload generation and `--exploit-hosts` parsing
 //! - Response analysis logic
 //! - Path fuzz functionality
+//! - Internal sweep ranges and verdicts
+//! - Exploit plan shown before exploitation
 
 use smugglex::exploit::{
-    LocalhostAccessResult, PathFuzzResult, RevealResult, VulnerabilityContext,
-    extract_vulnerability_context, get_fuzz_paths,
+    CloudProvider, ExploitBudget, ExploitCap, FuzzCategory, LOOPBACK_HOSTS, LocalhostAccessResult,
+    METADATA_ENDPOINTS, PathFuzzResult, PlanInputs, PortVerdict, ResponseMatcher, RevealResult,
+    SizeRange, SweepRange, SweepVerdict, VulnerabilityContext, extract_vulnerability_context,
+    get_fuzz_paths, parse_hosts, plan_exploits, sweep_requests,
 };
 use smugglex::model::CheckResult;
+use std::net::Ipv4Addr;
+use std::time::Duration;
 
 #[test]
 fn test_extract_vulnerability_context_clte() {
@@ -19,29 +25,55 @@ fn test_extract_vulnerability_context_clte() {
             check_type: "cl-te".to_string(),
             vulnerable: true,
             payload_index: Some(0),
+            payload_name: None,
+            technique: None,
             normal_status: "HTTP/1.1 200 OK".to_string(),
             attack_status: Some("HTTP/1.1 504 Gateway Timeout".to_string()),
             normal_duration_ms: 100,
             attack_duration_ms: Some(5000),
+            normal_connect_ms: None,
+            attack_connect_ms: None,
             timestamp: "2024-01-01T00:00:00Z".to_string(),
             payload: Some("POST / HTTP/1.1\r\nHost: example.com\r\n...".to_string()),
             confidence: None,
             detection_signals: Vec::new(),
             diagnostics: Vec::new(),
+            payloads_sent: 0,
+            request_errors: Default::default(),
+            h2_observations: Vec::new(),
+            fuzz_anomalies: Vec::new(),
+            unsolicited_bytes: None,
+            attack_phases: None,
+            cross_check: None,
+            evidence: None,
+            consistency: None,
         },
         CheckResult {
             check_type: "te-cl".to_string(),
             vulnerable: false,
             payload_index: None,
+            payload_name: None,
+            technique: None,
             normal_status: "HTTP/1.1 200 OK".to_string(),
             attack_status: None,
             normal_duration_ms: 100,
             attack_duration_ms: None,
+            normal_connect_ms: None,
+            attack_connect_ms: None,
             timestamp: "2024-01-01T00:00:00Z".to_string(),
             payload: None,
             confidence: None,
             detection_signals: Vec::new(),
             diagnostics: Vec::new(),
+            payloads_sent: 0,
+            request_errors: Default::default(),
+            h2_observations: Vec::new(),
+            fuzz_anomalies: Vec::new(),
+            unsolicited_bytes: None,
+            attack_phases: None,
+            cross_check: None,
+            evidence: None,
+            consistency: None,
         },
     ];
 
@@ -59,15 +91,28 @@ fn test_extract_vulnerability_context_tecl() {
         check_type: "te-cl".to_string(),
         vulnerable: true,
         payload_index: Some(5),
+        payload_name: None,
+        technique: None,
         normal_status: "HTTP/1.1 200 OK".to_string(),
         attack_status: Some("HTTP/1.1 408 Request Timeout".to_string()),
         normal_duration_ms: 100,
         attack_duration_ms: Some(3000),
+        normal_connect_ms: None,
+        attack_connect_ms: None,
         timestamp: "2024-01-01T00:00:00Z".to_string(),
         payload: Some("POST / HTTP/1.1\r\nHost: test.com\r\n...".to_string()),
         confidence: None,
         detection_signals: Vec::new(),
         diagnostics: Vec::new(),
+        payloads_sent: 0,
+        request_errors: Default::default(),
+        h2_observations: Vec::new(),
+        fuzz_anomalies: Vec::new(),
+        unsolicited_bytes: None,
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
     }];
 
     let ctx = extract_vulnerability_context(&results);
@@ -83,15 +128,28 @@ fn test_extract_vulnerability_context_none() {
         check_type: "cl-te".to_string(),
         vulnerable: false,
         payload_index: None,
+        payload_name: None,
+        technique: None,
         normal_status: "HTTP/1.1 200 OK".to_string(),
         attack_status: None,
         normal_duration_ms: 100,
         attack_duration_ms: None,
+        normal_connect_ms: None,
+        attack_connect_ms: None,
         timestamp: "2024-01-01T00:00:00Z".to_string(),
         payload: None,
         confidence: None,
         detection_signals: Vec::new(),
         diagnostics: Vec::new(),
+        payloads_sent: 0,
+        request_errors: Default::default(),
+        h2_observations: Vec::new(),
+        fuzz_anomalies: Vec::new(),
+        unsolicited_bytes: None,
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
     }];
 
     let ctx = extract_vulnerability_context(&results);
@@ -105,29 +163,55 @@ fn test_extract_vulnerability_context_multiple_vulnerabilities() {
             check_type: "cl-te".to_string(),
             vulnerable: true,
             payload_index: Some(0),
+            payload_name: None,
+            technique: None,
             normal_status: "HTTP/1.1 200 OK".to_string(),
             attack_status: Some("HTTP/1.1 504 Gateway Timeout".to_string()),
             normal_duration_ms: 100,
             attack_duration_ms: Some(5000),
+            normal_connect_ms: None,
+            attack_connect_ms: None,
             timestamp: "2024-01-01T00:00:00Z".to_string(),
             payload: Some("CL.TE payload".to_string()),
             confidence: None,
             detection_signals: Vec::new(),
             diagnostics: Vec::new(),
+            payloads_sent: 0,
+            request_errors: Default::default(),
+            h2_observations: Vec::new(),
+            fuzz_anomalies: Vec::new(),
+            unsolicited_bytes: None,
+            attack_phases: None,
+            cross_check: None,
+            evidence: None,
+            consistency: None,
         },
         CheckResult {
             check_type: "te-cl".to_string(),
             vulnerable: true,
             payload_index: Some(3),
+            payload_name: None,
+            technique: None,
             normal_status: "HTTP/1.1 200 OK".to_string(),
             attack_status: Some("HTTP/1.1 408 Request Timeout".to_string()),
             normal_duration_ms: 100,
             attack_duration_ms: Some(3000),
+            normal_connect_ms: None,
+            attack_connect_ms: None,
             timestamp: "2024-01-01T00:00:00Z".to_string(),
             payload: Some("TE.CL payload".to_string()),
             confidence: None,
             detection_signals: Vec::new(),
             diagnostics: Vec::new(),
+            payloads_sent: 0,
+            request_errors: Default::default(),
+            h2_observations: Vec::new(),
+            fuzz_anomalies: Vec::new(),
+            unsolicited_bytes: None,
+            attack_phases: None,
+            cross_check: None,
+            evidence: None,
+            consistency: None,
         },
     ];
 
@@ -200,7 +284,7 @@ fn test_ports_parsing_invalid() {
 
 #[test]
 fn test_get_fuzz_paths_default() {
-    let paths = get_fuzz_paths(None).unwrap();
+    let paths = get_fuzz_paths(None, &[]).unwrap();
     assert!(!paths.is_empty());
     // Check that paths start with /
     for path in &paths {
@@ -214,7 +298,7 @@ fn test_get_fuzz_paths_default() {
 
 #[test]
 fn test_get_fuzz_paths_default_has_common_paths() {
-    let paths = get_fuzz_paths(None).unwrap();
+    let paths = get_fuzz_paths(None, &[]).unwrap();
 
     // Check for various categories of common paths
     let admin_paths: Vec<_> = paths.iter().filter(|p| p.contains("admin")).collect();
@@ -240,7 +324,7 @@ fn test_get_fuzz_paths_from_wordlist() {
     writeln!(file, "  whitespace-path  ").unwrap();
     drop(file);
 
-    let paths = get_fuzz_paths(Some(wordlist_path.to_str().unwrap())).unwrap();
+    let paths = get_fuzz_paths(Some(wordlist_path.to_str().unwrap()), &[]).unwrap();
 
     assert_eq!(paths.len(), 3);
     assert!(paths.contains(&"/custom-path".to_string()));
@@ -253,7 +337,7 @@ fn test_get_fuzz_paths_from_wordlist() {
 
 #[test]
 fn test_get_fuzz_paths_invalid_file() {
-    let result = get_fuzz_paths(Some("/nonexistent/path/wordlist.txt"));
+    let result = get_fuzz_paths(Some("/nonexistent/path/wordlist.txt"), &[]);
     assert!(result.is_err());
 }
 
@@ -269,13 +353,48 @@ fn test_get_fuzz_paths_empty_wordlist() {
     writeln!(file).unwrap();
     drop(file);
 
-    let result = get_fuzz_paths(Some(wordlist_path.to_str().unwrap()));
+    let result = get_fuzz_paths(Some(wordlist_path.to_str().unwrap()), &[]);
     assert!(result.is_err());
 
     // Cleanup
     std::fs::remove_file(&wordlist_path).ok();
 }
 
+#[test]
+fn test_get_fuzz_paths_categories() {
+    let paths =
+        get_fuzz_paths(None, &[FuzzCategory::Actuator, FuzzCategory::CloudMetadata]).unwrap();
+    assert!(paths.contains(&"/actuator/env".to_string()));
+    assert!(paths.contains(&"/latest/meta-data/".to_string()));
+    // Only the selected categories, not the common list
+    assert!(!paths.contains(&"/wp-login.php".to_string()));
+
+    // Overlapping categories are deduplicated
+    let paths = get_fuzz_paths(None, &[FuzzCategory::Admin, FuzzCategory::Debug]).unwrap();
+    let consoles = paths.iter().filter(|p| *p == "/console").count();
+    assert_eq!(consoles, 1);
+}
+
+#[test]
+fn test_get_fuzz_paths_categories_with_wordlist() {
+    use std::io::Write;
+
+    let wordlist_path = std::env::temp_dir().join("category_wordlist.txt");
+    let mut file = std::fs::File::create(&wordlist_path).unwrap();
+    writeln!(file, "custom-only").unwrap();
+    writeln!(file, "graphql").unwrap();
+    drop(file);
+
+    let paths =
+        get_fuzz_paths(Some(wordlist_path.to_str().unwrap()), &[FuzzCategory::Api]).unwrap();
+    assert!(paths.contains(&"/api/v1".to_string()));
+    assert!(paths.contains(&"/custom-only".to_string()));
+    assert_eq!(paths.iter().filter(|p| *p == "/graphql").count(), 1);
+    assert_eq!(paths.last(), Some(&"/custom-only".to_string()));
+
+    std::fs::remove_file(&wordlist_path).ok();
+}
+
 #[test]
 fn test_path_fuzz_result_creation() {
     let result = PathFuzzResult {
@@ -283,6 +402,7 @@ fn test_path_fuzz_result_creation() {
         found: true,
         reason: "Status changed to 200 OK".to_string(),
         response_status: Some("HTTP 200".to_string()),
+        response_size: Some(11),
         response_body_preview: Some("Admin panel".to_string()),
     };
 
@@ -300,6 +420,7 @@ fn test_path_fuzz_result_not_found() {
         found: false,
         reason: "No indicators of hidden path".to_string(),
         response_status: None,
+        response_size: None,
         response_body_preview: None,
     };
 
@@ -309,6 +430,39 @@ fn test_path_fuzz_result_not_found() {
     assert!(result.response_body_preview.is_none());
 }
 
+#[test]
+fn test_size_range_parse() {
+    assert_eq!("0".parse::<SizeRange>(), Ok(SizeRange { min: 0, max: 0 }));
+    assert_eq!(
+        "100-250".parse::<SizeRange>(),
+        Ok(SizeRange { min: 100, max: 250 })
+    );
+    assert!("250-100".parse::<SizeRange>().is_err());
+    assert!("big".parse::<SizeRange>().is_err());
+}
+
+#[test]
+fn test_response_matcher_rules() {
+    let matcher = ResponseMatcher {
+        match_status: vec![200, 302],
+        match_regex: Some(regex::Regex::new("admin").unwrap()),
+        filter_size: vec![SizeRange { min: 0, max: 0 }],
+        ..Default::default()
+    };
+    assert!(matcher.has_match_rules());
+    assert!(matcher.matches(Some(200), "admin panel").is_some());
+    // Every match rule must hold
+    assert!(matcher.matches(Some(404), "admin panel").is_none());
+    assert!(matcher.matches(Some(302), "login").is_none());
+    assert!(matcher.matches(None, "admin").is_none());
+
+    assert!(matcher.filters(Some(200), ""));
+    assert!(!matcher.filters(Some(200), "admin"));
+
+    assert!(ResponseMatcher::default().is_empty());
+    assert!(ResponseMatcher::default().matches(Some(500), "").is_some());
+}
+
 #[test]
 fn test_localhost_access_result_success() {
     let result = LocalhostAccessResult {
@@ -317,6 +471,9 @@ fn test_localhost_access_result_success() {
         reason: "Status code changed from 200 to 502".to_string(),
         response_status: Some("HTTP/1.1 502 Bad Gateway".to_string()),
         response_body: Some("Bad Gateway".to_string()),
+        verdict: PortVerdict::Filtered,
+        loopback_host: "127.0.0.1".to_string(),
+        internal_host: None,
     };
 
     assert_eq!(result.port, 8080);
@@ -333,10 +490,103 @@ fn test_localhost_access_result_failure() {
         reason: "No indicators of localhost access detected".to_string(),
         response_status: Some("HTTP/1.1 200 OK".to_string()),
         response_body: None,
+        verdict: PortVerdict::SameApp,
+        loopback_host: "0177.0.0.1".to_string(),
+        internal_host: None,
     };
 
     assert!(!result.success);
     assert_eq!(result.port, 22);
+    assert_eq!(result.verdict.to_string(), "same-app");
+    assert_eq!(result.target(), "22");
+}
+
+#[test]
+fn test_localhost_access_result_internal_host() {
+    let result = LocalhostAccessResult {
+        port: 8080,
+        success: true,
+        reason: "Status code changed from 200 to 401".to_string(),
+        response_status: Some("HTTP/1.1 401 Unauthorized".to_string()),
+        response_body: None,
+        verdict: PortVerdict::OpenDifferentService,
+        loopback_host: String::new(),
+        internal_host: Some("admin.internal".to_string()),
+    };
+    assert_eq!(result.target(), "admin.internal:8080");
+}
+
+#[test]
+fn test_parse_hosts() {
+    assert_eq!(
+        parse_hosts(" intranet.corp, admin.internal,,ADMIN.internal,10.0.0.5"),
+        ["intranet.corp", "admin.internal", "10.0.0.5"]
+    );
+    // Anything that could break out of the smuggled Host header is dropped
+    assert_eq!(
+        parse_hosts("a b,evil\r\nX: 1,host:81,/path,ok-host_1"),
+        ["ok-host_1"]
+    );
+    assert!(parse_hosts("").is_empty());
+}
+
+// Internal sweep tests
+
+#[test]
+fn test_sweep_range() {
+    let range = SweepRange::parse("10.0.0.77/24").unwrap();
+    assert_eq!(range.to_string(), "10.0.0.0/24");
+    assert_eq!(range.address_count(), 254);
+    let addresses: Vec<Ipv4Addr> = range.addresses().collect();
+    assert_eq!(addresses.len(), 254);
+    assert_eq!(addresses[0], Ipv4Addr::new(10, 0, 0, 1));
+    assert_eq!(addresses[253], Ipv4Addr::new(10, 0, 0, 254));
+
+    let single = SweepRange::parse("192.168.1.5").unwrap();
+    assert_eq!(single.to_string(), "192.168.1.5/32");
+    assert_eq!(
+        single.addresses().collect::<Vec<_>>(),
+        [Ipv4Addr::new(192, 168, 1, 5)]
+    );
+    assert_eq!(SweepRange::parse("10.0.0.0/31").unwrap().address_count(), 2);
+    assert_eq!(
+        SweepRange::parse("172.16.0.0/16").unwrap().address_count(),
+        65534
+    );
+
+    for bad in [
+        "10.0.0.0/8",
+        "10.0.0.0/33",
+        "10.0.0/24",
+        "::1/128",
+        "intranet",
+        "",
+    ] {
+        assert!(
+            SweepRange::parse(bad).is_err(),
+            "{} should be rejected",
+            bad
+        );
+    }
+}
+
+#[test]
+fn test_sweep_verdicts_and_requests() {
+    assert_eq!(
+        SweepVerdict::from(PortVerdict::OpenDifferentService).to_string(),
+        "different-app"
+    );
+    assert_eq!(
+        SweepVerdict::from(PortVerdict::SameApp),
+        SweepVerdict::Reachable
+    );
+    assert_eq!(
+        SweepVerdict::from(PortVerdict::Filtered),
+        SweepVerdict::Refused
+    );
+    // Baseline, control, then two requests per pair, within the budget
+    assert_eq!(sweep_requests(2, 200), 7);
+    assert_eq!(sweep_requests(1000, 200), 200);
 }
 
 // Reveal exploit tests
@@ -384,15 +634,28 @@ fn test_extract_vulnerability_context_no_payload() {
         check_type: "cl-te".to_string(),
         vulnerable: true,
         payload_index: Some(0),
+        payload_name: None,
+        technique: None,
         normal_status: "HTTP/1.1 200 OK".to_string(),
         attack_status: Some("HTTP/1.1 504 Gateway Timeout".to_string()),
         normal_duration_ms: 100,
         attack_duration_ms: Some(5000),
+        normal_connect_ms: None,
+        attack_connect_ms: None,
         timestamp: "2024-01-01T00:00:00Z".to_string(),
         payload: None, // No payload stored
         confidence: None,
         detection_signals: Vec::new(),
         diagnostics: Vec::new(),
+        payloads_sent: 0,
+        request_errors: Default::default(),
+        h2_observations: Vec::new(),
+        fuzz_anomalies: Vec::new(),
+        unsolicited_bytes: None,
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
     }];
 
     let ctx = extract_vulnerability_context(&results);
@@ -412,3 +675,175 @@ fn test_vulnerability_context_clone() {
     assert_eq!(cloned.payload, ctx.payload);
     assert_eq!(cloned.payload_index, ctx.payload_index);
 }
+
+// Cloud metadata tests
+
+#[test]
+fn test_metadata_endpoints_cover_providers() {
+    for provider in [CloudProvider::Aws, CloudProvider::Gcp, CloudProvider::Azure] {
+        assert!(
+            METADATA_ENDPOINTS.iter().any(|e| e.provider == provider),
+            "missing {} endpoint",
+            provider
+        );
+    }
+    // GCP and Azure refuse requests without their metadata header.
+    for endpoint in METADATA_ENDPOINTS {
+        match endpoint.provider {
+            CloudProvider::Gcp => assert_eq!(endpoint.headers, ["Metadata-Flavor: Google"]),
+            CloudProvider::Azure => assert_eq!(endpoint.headers, ["Metadata: true"]),
+            CloudProvider::Aws => assert!(endpoint.headers.is_empty()),
+        }
+        assert!(endpoint.path.starts_with('/'));
+    }
+}
+
+fn plan_inputs<'a>(exploits: &'a [&'a str], fuzz_paths: &'a [String]) -> PlanInputs<'a> {
+    PlanInputs {
+        exploits,
+        host: "example.com",
+        port: 443,
+        path: "/",
+        ports: "22,80,80,x",
+        hosts: "",
+        range: None,
+        sweep_budget: 200,
+        fuzz_paths,
+        smuggle_request: None,
+        reveal_endpoint: Some("/search"),
+        reveal_param: "q",
+        request_budget: None,
+        max_requests: None,
+    }
+}
+
+#[test]
+fn test_plan_exploits() {
+    let fuzz_paths = vec!["/admin".to_string(), "/debug".to_string()];
+    let plan = plan_exploits(&plan_inputs(
+        &[
+            "localhost-access",
+            "path-fuzz",
+            "smuggle",
+            "capture",
+            "reveal",
+            "bogus",
+        ],
+        &fuzz_paths,
+    ));
+    let names: Vec<&str> = plan.iter().map(|e| e.exploit.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "localhost-access",
+            "path-fuzz",
+            "smuggle",
+            "capture",
+            "reveal"
+        ]
+    );
+
+    // Duplicate and invalid ports are dropped, likeliest port first
+    assert_eq!(
+        plan[0].smuggled,
+        [
+            "GET / HTTP/1.1 (Host: 127.0.0.1:80)",
+            "GET / HTTP/1.1 (Host: 127.0.0.1:22)"
+        ]
+    );
+    assert_eq!(plan[0].max_requests, 1 + (1 + 2 * LOOPBACK_HOSTS.len()) * 2);
+
+    assert_eq!(plan[1].max_requests, 5);
+    assert_eq!(
+        plan[1].smuggled,
+        ["GET /admin HTTP/1.1", "GET /debug HTTP/1.1"]
+    );
+    assert_eq!(plan[2].smuggled, ["GPOST / HTTP/1.1"]);
+    assert_eq!(plan[3].smuggled, ["GET /admin HTTP/1.1"]);
+    assert_eq!(plan[4].smuggled, ["POST /search HTTP/1.1"]);
+}
+
+#[test]
+fn test_plan_exploits_budget_and_custom_request() {
+    let mut inputs = plan_inputs(&["localhost-access", "reveal", "capture"], &[]);
+    inputs.request_budget = Some(12);
+    inputs.smuggle_request = Some("GET /internal HTTP/1.1\\r\\nHost: x\\r\\n\\r\\n");
+    let plan = plan_exploits(&inputs);
+    assert_eq!(plan[0].max_requests, 12);
+    assert_eq!(plan[1].max_requests, 12);
+    // The budget only caps the exploits that honour it
+    assert!(plan[2].max_requests > 12);
+    assert_eq!(plan[2].smuggled, ["GET /internal HTTP/1.1"]);
+
+    let mut inputs = plan_inputs(&["localhost-access"], &[]);
+    inputs.hosts = "admin.internal,bad host";
+    let plan = plan_exploits(&inputs);
+    // Loopback attempts, then the unknown-name control and each port of the host
+    assert_eq!(
+        plan[0].max_requests,
+        1 + (1 + 2 * LOOPBACK_HOSTS.len() + 1 + 2) * 2
+    );
+    assert_eq!(
+        &plan[0].smuggled[2..],
+        [
+            "GET / HTTP/1.1 (Host: admin.internal:80)",
+            "GET / HTTP/1.1 (Host: admin.internal:22)"
+        ]
+    );
+
+    let mut inputs = plan_inputs(&["internal-sweep"], &[]);
+    let plan = plan_exploits(&inputs);
+    assert!(plan.is_empty(), "no range, nothing to sweep");
+    inputs.range = Some("10.0.0.0/24");
+    inputs.sweep_budget = 9;
+    let plan = plan_exploits(&inputs);
+    // Baseline, control, then three address and port pairs
+    assert_eq!(plan[0].max_requests, 9);
+    assert_eq!(
+        plan[0].smuggled,
+        [
+            "GET / HTTP/1.1 (Host: 10.0.0.1:80)",
+            "GET / HTTP/1.1 (Host: 10.0.0.1:22)",
+            "GET / HTTP/1.1 (Host: 10.0.0.2:80)"
+        ]
+    );
+
+    let plan = plan_exploits(&plan_inputs(&["cloud-metadata"], &[]));
+    assert_eq!(plan[0].smuggled.len(), METADATA_ENDPOINTS.len());
+    assert!(plan[0].smuggled[0].contains("169.254.169.254"));
+
+    // --exploit-max-requests caps every exploit, whatever its own budget
+    let mut inputs = plan_inputs(&["localhost-access", "smuggle", "capture"], &[]);
+    inputs.max_requests = Some(3);
+    let plan = plan_exploits(&inputs);
+    assert!(plan.iter().all(|e| e.max_requests <= 3));
+}
+
+#[test]
+fn test_exploit_budget() {
+    let budget = ExploitBudget::new(Some(5), None);
+    assert!(budget.take(3));
+    assert!(!budget.take(3), "would pass the cap");
+    assert_eq!(budget.sent(), 3);
+    assert_eq!(budget.hit(), Some(ExploitCap::Requests(5)));
+    assert_eq!(
+        budget.exhausted_reason(),
+        "exploit request cap of 5 reached"
+    );
+    // A smaller request still fits
+    assert!(budget.take(2));
+    assert!(!budget.take(1));
+    assert_eq!(budget.sent(), 5);
+
+    let budget = ExploitBudget::new(None, Some(Duration::ZERO));
+    assert!(!budget.take(1));
+    assert_eq!(budget.sent(), 0);
+    assert_eq!(budget.hit(), Some(ExploitCap::Time(Duration::ZERO)));
+    assert_eq!(budget.exhausted_reason(), "exploit time cap of 0s reached");
+
+    let budget = ExploitBudget::unlimited();
+    assert!(budget.take(1000));
+    assert_eq!(budget.sent(), 1000);
+    assert_eq!(budget.hit(), None);
+    assert_eq!(budget.exhausted_reason(), "request budget exhausted");
+}
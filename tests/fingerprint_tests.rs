@@ -4,9 +4,16 @@
 //! - Proxy type identification from response headers
 //! - Fingerprint result display formatting
 //! - suggest_checks ordering for each proxy type
+//! - Cache detection
+//! - ALPN-based h2 downgrade heuristic
+//! - Adaptive check ordering from fingerprint and TRACE signals
 //! - Integration tests with mock servers
 
-use smugglex::fingerprint::{FingerprintResult, ProxyType, fingerprint_target, suggest_checks};
+use smugglex::fingerprint::{
+    FingerprintResult, ProxyType, adaptive_check_order, behind_cache, fingerprint_target,
+    h2_downgrade_likely, probe_alpn, suggest_checks,
+};
+use smugglex::trace::{TraceFate, TraceReport, TraceVariant};
 use std::collections::HashMap;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpListener;
@@ -43,6 +50,10 @@ async fn test_fingerprint_nginx_server() {
         .unwrap();
     assert_eq!(result.detected_proxy, ProxyType::Nginx);
     assert_eq!(result.server_header.as_deref(), Some("nginx/1.24.0"));
+    // The mock answers only the GET probe, so the asterisk and Max-Forwards
+    // probes are inconclusive.
+    assert!(!result.asterisk_rewrite);
+    assert_eq!(result.hop_count, None);
 }
 
 #[tokio::test]
@@ -118,10 +129,15 @@ fn test_suggest_checks_nginx_prioritizes_cl_te() {
         via_header: None,
         powered_by: None,
         raw_headers: HashMap::new(),
+        asterisk_rewrite: false,
+        hop_count: None,
+        error_hop: None,
+        alpn: None,
     };
     let checks = suggest_checks(&fp);
     assert_eq!(checks[0], "cl-te");
     assert!(checks.contains(&"cl-edge"));
+    assert!(checks.contains(&"cl-cl"));
 }
 
 #[test]
@@ -132,6 +148,10 @@ fn test_suggest_checks_haproxy_prioritizes_te_cl() {
         via_header: None,
         powered_by: None,
         raw_headers: HashMap::new(),
+        asterisk_rewrite: false,
+        hop_count: None,
+        error_hop: None,
+        alpn: None,
     };
     let checks = suggest_checks(&fp);
     assert_eq!(checks[0], "te-cl");
@@ -145,6 +165,10 @@ fn test_suggest_checks_cloudflare_prioritizes_te_te() {
         via_header: None,
         powered_by: None,
         raw_headers: HashMap::new(),
+        asterisk_rewrite: false,
+        hop_count: None,
+        error_hop: None,
+        alpn: None,
     };
     let checks = suggest_checks(&fp);
     assert_eq!(checks[0], "te-te");
@@ -158,13 +182,186 @@ fn test_suggest_checks_returns_all_checks() {
         via_header: None,
         powered_by: None,
         raw_headers: HashMap::new(),
+        asterisk_rewrite: false,
+        hop_count: None,
+        error_hop: None,
+        alpn: None,
     };
     let checks = suggest_checks(&fp);
-    assert_eq!(checks.len(), 6);
+    assert_eq!(checks.len(), 10);
     assert!(checks.contains(&"cl-te"));
     assert!(checks.contains(&"te-cl"));
     assert!(checks.contains(&"te-te"));
     assert!(checks.contains(&"h2c"));
     assert!(checks.contains(&"h2"));
     assert!(checks.contains(&"cl-edge"));
+    assert!(checks.contains(&"cl-cl"));
+    assert!(checks.contains(&"asterisk"));
+    assert!(checks.contains(&"connection"));
+    assert!(checks.contains(&"head"));
+}
+
+#[test]
+fn test_h2_downgrade_likely_needs_h2_and_a_hop() {
+    let fp = |proxy: ProxyType, alpn: Option<&str>, hop_count: Option<usize>| FingerprintResult {
+        detected_proxy: proxy,
+        server_header: None,
+        via_header: None,
+        powered_by: None,
+        raw_headers: HashMap::new(),
+        asterisk_rewrite: false,
+        hop_count,
+        error_hop: None,
+        alpn: alpn.map(str::to_string),
+    };
+    assert!(h2_downgrade_likely(&fp(ProxyType::Nginx, Some("h2"), None)));
+    assert!(h2_downgrade_likely(&fp(
+        ProxyType::Unknown("origin".to_string()),
+        Some("h2"),
+        Some(1)
+    )));
+    // An h2 origin with nothing in front of it has nothing to downgrade to.
+    assert!(!h2_downgrade_likely(&fp(
+        ProxyType::Unknown("origin".to_string()),
+        Some("h2"),
+        Some(0)
+    )));
+    assert!(!h2_downgrade_likely(&fp(
+        ProxyType::Nginx,
+        Some("http/1.1"),
+        None
+    )));
+    assert!(!h2_downgrade_likely(&fp(ProxyType::Nginx, None, None)));
+
+    let mut nginx = fp(ProxyType::Nginx, Some("h2"), None);
+    assert_eq!(suggest_checks(&nginx)[0], "h2");
+    assert_eq!(suggest_checks(&nginx).len(), 10);
+    nginx.alpn = None;
+    assert_eq!(suggest_checks(&nginx)[0], "cl-te");
+}
+
+#[tokio::test]
+async fn test_probe_alpn_without_tls() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let _ = socket.write_all(b"HTTP/1.1 400 Bad Request\r\n\r\n").await;
+    });
+    assert_eq!(probe_alpn("127.0.0.1", port, 2).await, None);
+}
+
+#[test]
+fn test_behind_cache() {
+    let fp = |proxy: ProxyType, headers: &[(&str, &str)]| FingerprintResult {
+        detected_proxy: proxy,
+        server_header: None,
+        via_header: None,
+        powered_by: None,
+        raw_headers: headers
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+        asterisk_rewrite: false,
+        hop_count: None,
+        error_hop: None,
+        alpn: None,
+    };
+    assert!(behind_cache(&fp(ProxyType::Varnish, &[])));
+    assert!(behind_cache(&fp(ProxyType::Fastly, &[])));
+    assert!(behind_cache(&fp(ProxyType::Nginx, &[("x-cache", "HIT")])));
+    assert!(behind_cache(&fp(ProxyType::Apache, &[("age", "12")])));
+    assert!(!behind_cache(&fp(ProxyType::Nginx, &[("server", "nginx")])));
+    assert!(!behind_cache(&fp(
+        ProxyType::Unknown("unidentified".to_string()),
+        &[]
+    )));
+}
+
+fn plain_fingerprint(proxy: ProxyType) -> FingerprintResult {
+    FingerprintResult {
+        detected_proxy: proxy,
+        server_header: None,
+        via_header: None,
+        powered_by: None,
+        raw_headers: HashMap::new(),
+        asterisk_rewrite: false,
+        hop_count: None,
+        error_hop: None,
+        alpn: None,
+    }
+}
+
+fn trace(variants: &[(&str, TraceFate)]) -> TraceReport {
+    TraceReport {
+        variants: variants
+            .iter()
+            .map(|(header, fate)| TraceVariant {
+                header: header.to_string(),
+                fate: fate.clone(),
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn test_adaptive_check_order_without_signals() {
+    let order = adaptive_check_order(None, None);
+    assert_eq!(order.checks[..3], ["cl-te", "te-cl", "te-te"]);
+    assert_eq!(order.checks.len(), 10);
+    assert!(order.reasons.is_empty());
+
+    let fp = plain_fingerprint(ProxyType::HAProxy);
+    let order = adaptive_check_order(Some(&fp), None);
+    assert_eq!(order.checks, suggest_checks(&fp));
+}
+
+#[test]
+fn test_adaptive_check_order_fingerprint_signals() {
+    let mut fp = plain_fingerprint(ProxyType::Nginx);
+    fp.alpn = Some("h2".to_string());
+    fp.asterisk_rewrite = true;
+    let order = adaptive_check_order(Some(&fp), None);
+    assert_eq!(order.checks[..3], ["h2", "asterisk", "cl-te"]);
+    assert_eq!(order.reasons.len(), 2);
+    assert!(order.reasons[0].starts_with("h2 first:"));
+
+    // A front door without h2 pushes the h2 payloads to the back.
+    fp.alpn = Some("http/1.1".to_string());
+    fp.asterisk_rewrite = false;
+    let order = adaptive_check_order(Some(&fp), None);
+    assert_eq!(order.checks.last(), Some(&"h2"));
+    assert_eq!(
+        order.reasons,
+        ["h2 last: the front door does not negotiate h2"]
+    );
+}
+
+#[test]
+fn test_adaptive_check_order_trace_signals() {
+    let report = trace(&[
+        ("Transfer-Encoding: chunked", TraceFate::Verbatim),
+        ("Transfer-Encoding : chunked", TraceFate::Verbatim),
+        ("Content-Length: +0", TraceFate::Stripped),
+        (
+            "Content-Length:  0",
+            TraceFate::Normalized("Content-Length: 0".to_string()),
+        ),
+    ]);
+    let order = adaptive_check_order(None, Some(&report));
+    assert_eq!(order.checks[0], "te-te");
+    assert_eq!(order.checks.last(), Some(&"cl-edge"));
+    assert_eq!(order.reasons.len(), 2);
+
+    // Only the plain spelling survived: every obfuscation was defeated.
+    let report = trace(&[
+        ("Transfer-Encoding: chunked", TraceFate::Verbatim),
+        (
+            "Transfer-Encoding : chunked",
+            TraceFate::Rejected(Some(400)),
+        ),
+    ]);
+    let order = adaptive_check_order(None, Some(&report));
+    assert_eq!(order.checks.last(), Some(&"te-te"));
+    assert_eq!(order.checks[0], "cl-te");
 }
@@ -0,0 +1,214 @@
+//! Tests for the history module
+//!
+//! - Naming a target's history directory
+//! - Storing results and appending to the index, without overwriting
+//! - Reading the index back
+//! - `smugglex stats`: first and last seen, fixed, flapping, per technique
+
+use smugglex::history::{
+    HistoryEntry, INDEX_FILE, expand_home, history_stats, host_dir_name, read_index, record_results,
+};
+use smugglex::model::{CheckResult, ScanResults};
+
+fn check(check_type: &str, vulnerable: bool) -> CheckResult {
+    CheckResult {
+        check_type: check_type.to_string(),
+        vulnerable,
+        payload_index: vulnerable.then_some(3),
+        payload_name: vulnerable.then(|| "vanilla".to_string()),
+        technique: None,
+        normal_status: "HTTP/1.1 200 OK".to_string(),
+        attack_status: None,
+        normal_duration_ms: 0,
+        attack_duration_ms: None,
+        normal_connect_ms: None,
+        attack_connect_ms: None,
+        timestamp: "2024-05-01T09:12:44Z".to_string(),
+        payload: None,
+        confidence: None,
+        detection_signals: Vec::new(),
+        diagnostics: Vec::new(),
+        payloads_sent: 0,
+        request_errors: Default::default(),
+        h2_observations: Vec::new(),
+        fuzz_anomalies: Vec::new(),
+        unsolicited_bytes: None,
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
+    }
+}
+
+fn scan(target: &str, checks: Vec<CheckResult>) -> ScanResults {
+    ScanResults {
+        target: target.to_string(),
+        address: None,
+        vhost: None,
+        ip: None,
+        port: None,
+        scheme: None,
+        method: "POST".to_string(),
+        timestamp: "2024-05-01T09:12:44.120+00:00".to_string(),
+        fingerprint: None,
+        checks,
+        exploits: None,
+        proxy_chain: Vec::new(),
+        unreachable: None,
+        throttle: None,
+        target_info: None,
+        error: None,
+        error_info: None,
+        duration_ms: None,
+        requests_sent: None,
+        summary: None,
+    }
+}
+
+fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("smugglex_history_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn test_host_dir_name() {
+    assert_eq!(host_dir_name("https://target.com/path?q=1"), "target.com");
+    assert_eq!(host_dir_name("https://target.com:443/"), "target.com");
+    assert_eq!(host_dir_name("http://target.com:8080/"), "target.com_8080");
+    assert_eq!(host_dir_name("http://[::1]:8080/"), "__1_8080");
+    assert_eq!(host_dir_name("../../etc"), "_.._etc");
+}
+
+#[test]
+fn test_expand_home() {
+    let home = std::env::var("HOME").unwrap();
+    assert_eq!(
+        expand_home(std::path::Path::new("~/.smugglex/history")),
+        std::path::Path::new(&home).join(".smugglex/history")
+    );
+    assert_eq!(
+        expand_home(std::path::Path::new("/var/lib/smugglex")),
+        std::path::Path::new("/var/lib/smugglex")
+    );
+}
+
+#[test]
+fn test_record_results_and_index() {
+    let dir = temp_dir("record");
+    assert!(read_index(&dir).unwrap().is_empty());
+
+    let vulnerable = scan(
+        "https://target.com/",
+        vec![check("cl-te", true), check("te-cl", false)],
+    );
+    let first = record_results(&dir, &vulnerable).unwrap();
+    assert_eq!(first.file, "target.com/20240501T091244Z.json");
+    assert!(first.vulnerable);
+    assert_eq!(first.findings, vec!["cl-te/vanilla"]);
+
+    // Same target and second: stored alongside, never overwritten.
+    let clean = scan("https://target.com/", vec![check("cl-te", false)]);
+    let second = record_results(&dir, &clean).unwrap();
+    assert_eq!(second.file, "target.com/20240501T091244Z-2.json");
+    assert!(!second.vulnerable);
+
+    let stored: ScanResults =
+        serde_json::from_str(&std::fs::read_to_string(dir.join(&first.file)).unwrap()).unwrap();
+    assert_eq!(stored.checks.len(), 2);
+    assert!(stored.checks[0].vulnerable);
+
+    let index = read_index(&dir).unwrap();
+    assert_eq!(index, vec![first, second]);
+    assert_eq!(
+        std::fs::read_to_string(dir.join(INDEX_FILE))
+            .unwrap()
+            .lines()
+            .count(),
+        2
+    );
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_read_index_reports_bad_line() {
+    let dir = temp_dir("bad_index");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join(INDEX_FILE), "not json\n").unwrap();
+    let err = read_index(&dir).unwrap_err().to_string();
+    assert!(err.contains("index.jsonl line 1"));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+fn entry(target: &str, timestamp: &str, findings: &[&str], error: Option<&str>) -> HistoryEntry {
+    HistoryEntry {
+        target: target.to_string(),
+        timestamp: timestamp.to_string(),
+        file: String::new(),
+        vulnerable: !findings.is_empty(),
+        findings: findings.iter().map(|f| f.to_string()).collect(),
+        error: error.map(str::to_string),
+    }
+}
+
+#[test]
+fn test_history_stats() {
+    let a = "https://a.example/";
+    let entries = vec![
+        entry(a, "2024-05-03T00:00:00Z", &["cl-te/vanilla"], None),
+        entry(
+            a,
+            "2024-05-01T00:00:00Z",
+            &["cl-te/vanilla", "te-cl/linefold-tab"],
+            None,
+        ),
+        entry(a, "2024-05-02T00:00:00Z", &["te-cl/linefold-tab"], None),
+        // A failed scan neither hides nor fixes a finding.
+        entry(a, "2024-05-04T00:00:00Z", &[], Some("connection refused")),
+        entry(a, "2024-05-05T00:00:00Z", &["cl-te/vanilla"], None),
+        entry(
+            "https://b.example/",
+            "2024-05-02T00:00:00Z",
+            &["cl-te/#4"],
+            None,
+        ),
+    ];
+    let stats = history_stats(&entries);
+    assert_eq!(stats.scans, 6);
+    assert_eq!(stats.targets.len(), 2);
+
+    let target = &stats.targets[0];
+    assert_eq!(target.target, a);
+    assert_eq!(target.scans, 4);
+    assert_eq!(target.failed_scans, 1);
+    assert_eq!(target.first_scan, "2024-05-01T00:00:00Z");
+    assert_eq!(target.last_scan, "2024-05-05T00:00:00Z");
+
+    let vanilla = &target.findings[0];
+    assert_eq!(vanilla.finding, "cl-te/vanilla");
+    assert_eq!(vanilla.first_seen, "2024-05-01T00:00:00Z");
+    assert_eq!(vanilla.last_seen, "2024-05-05T00:00:00Z");
+    assert_eq!(vanilla.fixed, None);
+    assert!(vanilla.flapping);
+    assert_eq!(vanilla.detections, 3);
+
+    let linefold = &target.findings[1];
+    assert_eq!(linefold.finding, "te-cl/linefold-tab");
+    assert_eq!(linefold.last_seen, "2024-05-02T00:00:00Z");
+    assert_eq!(linefold.fixed.as_deref(), Some("2024-05-03T00:00:00Z"));
+    assert!(!linefold.flapping);
+    assert_eq!(linefold.detections, 2);
+
+    assert_eq!(stats.techniques.len(), 2);
+    assert_eq!(stats.techniques[0].technique, "cl-te");
+    assert_eq!(stats.techniques[0].findings, 2);
+    assert_eq!(stats.techniques[0].open, 2);
+    assert_eq!(stats.techniques[0].detections, 4);
+    assert_eq!(stats.techniques[1].technique, "te-cl");
+    assert_eq!(stats.techniques[1].fixed, 1);
+
+    let json = serde_json::to_value(&stats).unwrap();
+    assert!(json["targets"][0]["findings"][0].get("fixed").is_none());
+}
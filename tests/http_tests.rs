@@ -5,8 +5,18 @@
 //! - TLS and non-TLS request handling
 //! - Timeout behavior
 //! - Error handling for connection failures
-
-use smugglex::http::send_request;
+//! - Rate and per-host concurrency limits
+//! - Retry-After pauses
+//! - Structured response parsing (`HttpResponse`), chunked decoding and trailers
+//! - Unsolicited bytes after a complete response
+//! - The response size cap (`--max-response-size`)
+//! - Per-request phase timing
+//! - Request counting and the connected address per scan
+
+use smugglex::http::{
+    DEFAULT_MAX_RESPONSE_SIZE, HttpResponse, LengthMismatch, max_response_size, send_request,
+    send_request_lingering, send_request_timed,
+};
 
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpListener;
@@ -111,11 +121,12 @@ async fn test_send_request_connection_refused() {
 
     assert!(result.is_err());
     let err = result.unwrap_err();
-    // Should be an Io error due to connection refused
-    match err {
-        smugglex::error::SmugglexError::Io(_) => {}
-        _ => panic!("Expected Io error"),
+    // A refused connection is a connect error, and not worth retrying
+    match &err {
+        smugglex::error::SmugglexError::Connect(_) => {}
+        _ => panic!("Expected Connect error"),
     }
+    assert!(!err.is_retryable());
 }
 
 #[tokio::test]
@@ -247,3 +258,513 @@ async fn test_pipeline_requests_preserves_responses_on_timeout() {
     assert!(responses[0].contains("HTTP/1.1 200 OK"));
     assert!(responses[0].contains("AB"));
 }
+
+#[test]
+fn test_rate_limiter_allows_burst_then_spaces_permits() {
+    use smugglex::http::RateLimiter;
+
+    let limiter = RateLimiter::new(4);
+    // A full second of burst is available immediately.
+    for _ in 0..4 {
+        assert_eq!(limiter.reserve(), Duration::ZERO);
+    }
+    // Further permits are queued 1/rate apart.
+    let fifth = limiter.reserve();
+    let sixth = limiter.reserve();
+    assert!(fifth > Duration::from_millis(200) && fifth <= Duration::from_millis(250));
+    assert!(sixth > Duration::from_millis(450) && sixth <= Duration::from_millis(500));
+}
+
+#[tokio::test]
+async fn test_host_limiter_caps_each_host_separately() {
+    use smugglex::http::HostLimiter;
+
+    let limiter = HostLimiter::new(2);
+    let first = limiter.acquire("Example.com").await;
+    let _second = limiter.acquire("example.com").await;
+    // The host is full; a third scan waits for a slot.
+    assert!(
+        timeout(Duration::from_millis(50), limiter.acquire("EXAMPLE.COM"))
+            .await
+            .is_err()
+    );
+    // Other hosts are not held up.
+    assert!(
+        timeout(Duration::from_millis(50), limiter.acquire("other.example"))
+            .await
+            .is_ok()
+    );
+    drop(first);
+    assert!(
+        timeout(Duration::from_millis(50), limiter.acquire("example.com"))
+            .await
+            .is_ok()
+    );
+}
+
+#[test]
+fn test_check_transport_declarations() {
+    use smugglex::transport::{TransportKind, check_transport};
+
+    assert_eq!(check_transport("cl-te", false), TransportKind::Http1Plain);
+    assert_eq!(check_transport("h2c", true), TransportKind::Http1Tls);
+    assert_eq!(check_transport("h2-downgrade", true), TransportKind::Http2);
+    assert!(TransportKind::Http2.requires_tls());
+    assert!(!TransportKind::Http2.supports_proxy());
+    assert!(!TransportKind::Http3.available());
+}
+
+#[tokio::test]
+async fn test_send_via_transport() {
+    use smugglex::http::send_via;
+    use smugglex::transport::TransportKind;
+
+    let port = 8086;
+    mock_server(port, "via transport").await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let transport = TransportKind::Http1Plain.transport();
+    assert_eq!(transport.kind(), TransportKind::Http1Plain);
+    let (response, _) = send_via(
+        transport,
+        "127.0.0.1",
+        port,
+        "GET / HTTP/1.1\r\nHost: localhost\r\n\r\n",
+        5,
+        false,
+    )
+    .await
+    .unwrap();
+    assert!(response.ends_with("via transport"));
+
+    let err = send_via(
+        TransportKind::Http3.transport(),
+        "127.0.0.1",
+        port,
+        "GET / HTTP/1.1\r\n\r\n",
+        5,
+        false,
+    )
+    .await;
+    assert!(err.is_err());
+}
+
+#[tokio::test]
+async fn test_pinned_address_replaces_host_resolution() {
+    use smugglex::http::{pinned_address, resolve_addresses, with_pinned_address};
+    use std::net::{IpAddr, Ipv4Addr};
+
+    let port = 8087;
+    mock_server(port, "pinned").await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let loopback = IpAddr::V4(Ipv4Addr::LOCALHOST);
+    assert_eq!(
+        resolve_addresses("127.0.0.1", port).await.unwrap(),
+        vec![loopback]
+    );
+    assert_eq!(pinned_address(), None);
+
+    // The host name never resolves; the pinned address is dialed instead.
+    let (response, _) = with_pinned_address(loopback, async {
+        assert_eq!(pinned_address(), Some(loopback));
+        send_request(
+            "smugglex-pinned.invalid",
+            port,
+            "GET / HTTP/1.1\r\nHost: smugglex-pinned.invalid\r\n\r\n",
+            5,
+            false,
+            false,
+        )
+        .await
+    })
+    .await
+    .unwrap();
+    assert!(response.ends_with("pinned"));
+}
+
+#[tokio::test]
+async fn test_retry_after_pauses_the_next_request() {
+    use smugglex::http::{
+        observe_throttle, observed_throttle, set_retry_after_max, with_pinned_address,
+    };
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::time::Instant;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        for response in [
+            "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 1\r\nContent-Length: 0\r\n\r\n",
+            "HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok",
+        ] {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0; 1024];
+            let _ = socket.read(&mut buf).await;
+            socket.write_all(response.as_bytes()).await.unwrap();
+        }
+    });
+    set_retry_after_max(Duration::from_secs(5));
+
+    // A host name of its own keeps the pause away from the other tests.
+    let host = "smugglex-retry-after.invalid";
+    let request = format!("GET / HTTP/1.1\r\nHost: {}\r\n\r\n", host);
+    let (waited, throttle) = with_pinned_address(
+        IpAddr::V4(Ipv4Addr::LOCALHOST),
+        observe_throttle(async {
+            let (first, _) = send_request(host, port, &request, 5, false, false)
+                .await
+                .unwrap();
+            assert!(first.starts_with("HTTP/1.1 429"));
+            let start = Instant::now();
+            let (second, duration) = send_request(host, port, &request, 5, false, false)
+                .await
+                .unwrap();
+            assert!(second.ends_with("ok"));
+            // The pause is not part of the measured response time.
+            assert!(duration < Duration::from_millis(500));
+            (start.elapsed(), observed_throttle())
+        }),
+    )
+    .await;
+
+    assert!(waited >= Duration::from_millis(900));
+    let throttle = throttle.unwrap();
+    assert_eq!(throttle.events, 1);
+    assert_eq!(throttle.longest_retry_after_secs, 1);
+    assert_eq!(throttle.capped, 0);
+    assert!(throttle.paused_ms >= 900);
+}
+
+#[tokio::test]
+async fn test_observe_requests_counts_requests_and_peer() {
+    use smugglex::http::{observe_requests, observed_peer, observed_requests};
+    use std::net::{IpAddr, Ipv4Addr};
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        for _ in 0..2 {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0; 1024];
+            let _ = socket.read(&mut buf).await;
+            socket
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok")
+                .await
+                .unwrap();
+        }
+    });
+
+    let (requests, peer) = observe_requests(async {
+        for _ in 0..2 {
+            send_request("127.0.0.1", port, "GET / HTTP/1.1\r\n\r\n", 5, false, false)
+                .await
+                .unwrap();
+        }
+        (observed_requests(), observed_peer())
+    })
+    .await;
+    assert_eq!(requests, 2);
+    assert_eq!(peer, Some(IpAddr::V4(Ipv4Addr::LOCALHOST)));
+    assert_eq!(observed_requests(), 0);
+}
+
+#[test]
+fn test_http_response_parses_parts() {
+    let raw = b"HTTP/1.1 404 Not Found Here\r\nServer: nginx\r\nSet-Cookie: a=1\r\nset-cookie : b=2\r\nContent-Length: 4\r\n\r\nbodyHTTP/1.1 200 OK\r\n\r\n";
+    let response = HttpResponse::parse(raw);
+    assert_eq!(response.status_line, "HTTP/1.1 404 Not Found Here");
+    assert_eq!(response.version, "HTTP/1.1");
+    assert_eq!(response.status, Some(404));
+    assert_eq!(response.reason, "Not Found Here");
+    assert_eq!(response.headers.len(), 4);
+    assert_eq!(response.header("SERVER"), Some("nginx"));
+    let cookies: Vec<&str> = response.header_values("set-cookie").collect();
+    assert_eq!(cookies, ["a=1", "b=2"]);
+    assert_eq!(response.body, b"body");
+    assert_eq!(response.trailing, b"HTTP/1.1 200 OK\r\n\r\n");
+    assert_eq!(response.text_after_head(), "bodyHTTP/1.1 200 OK\r\n\r\n");
+    assert_eq!(response.len_after_head(), 23);
+}
+
+#[test]
+fn test_http_response_chunked_and_unframed_bodies() {
+    let chunked = HttpResponse::parse(
+        b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nContent-Length: 1\r\n\r\n3\r\nabc\r\n0\r\n\r\nGPOST",
+    );
+    assert!(chunked.chunked);
+    assert_eq!(chunked.body, b"abc");
+    assert_eq!(chunked.trailing, b"GPOST");
+    assert_eq!(chunked.len_after_head(), 8);
+
+    let unframed = HttpResponse::parse(b"HTTP/1.0 200 OK\r\n\r\nuntil close");
+    assert_eq!(unframed.body, b"until close");
+    assert!(unframed.trailing.is_empty());
+
+    // A Content-Length longer than what arrived keeps what did.
+    let short = HttpResponse::parse(b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\nabc");
+    assert_eq!(short.body, b"abc");
+}
+
+#[test]
+fn test_http_response_malformed_input() {
+    let empty = HttpResponse::parse(b"");
+    assert_eq!(empty, HttpResponse::default());
+
+    let garbage = HttpResponse::parse(b"garbage: not a status line\r\nX-A: 1");
+    assert_eq!(garbage.status, None);
+    assert_eq!(garbage.status_line, "garbage: not a status line");
+    assert!(garbage.reason.is_empty());
+    assert_eq!(garbage.header("x-a"), Some("1"));
+    assert!(garbage.body.is_empty());
+
+    // Without a header terminator there is no body.
+    let head_only = HttpResponse::parse(b"HTTP/1.1 204 No Content\r\nServer: x\r\n");
+    assert_eq!(head_only.status, Some(204));
+    assert_eq!(head_only.len_after_head(), 0);
+}
+
+#[test]
+fn test_http_response_length_mismatch() {
+    let short = HttpResponse::parse(b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\nabc");
+    assert_eq!(short.content_length, Some(10));
+    let mismatch = short.length_mismatch().unwrap();
+    assert_eq!(
+        mismatch,
+        LengthMismatch::Truncated {
+            declared: 10,
+            received: 3
+        }
+    );
+    assert_eq!(mismatch.signal(), "body_truncated:3/10");
+    assert_eq!(mismatch.to_string(), "body truncated at 3 of 10 bytes");
+
+    let long = HttpResponse::parse(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nokHTTP/1.1 404");
+    let mismatch = long.length_mismatch().unwrap();
+    assert_eq!(
+        mismatch,
+        LengthMismatch::Overrun {
+            declared: 2,
+            extra: 12
+        }
+    );
+    assert_eq!(mismatch.signal(), "body_overrun:+12");
+
+    let exact = HttpResponse::parse(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok");
+    assert_eq!(exact.length_mismatch(), None);
+}
+
+#[test]
+fn test_http_response_length_mismatch_skips_bodiless_and_unframed() {
+    for raw in [
+        &b"HTTP/1.1 304 Not Modified\r\nContent-Length: 10\r\n\r\n"[..],
+        b"HTTP/1.1 204 No Content\r\nContent-Length: 10\r\n\r\n",
+        b"HTTP/1.1 100 Continue\r\nContent-Length: 10\r\n\r\n",
+        b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nContent-Length: 99\r\n\r\n0\r\n\r\n",
+        b"HTTP/1.1 200 OK\r\n\r\nto close",
+    ] {
+        assert_eq!(HttpResponse::parse(raw).length_mismatch(), None);
+    }
+}
+
+#[tokio::test]
+async fn test_send_request_lingering_captures_unsolicited_bytes() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        if let Ok((mut socket, _)) = listener.accept().await {
+            let mut buf = [0; 1024];
+            let _ = socket.read(&mut buf).await;
+            let _ = socket
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok")
+                .await;
+            // A second response nobody asked for, a little later.
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            let _ = socket
+                .write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n")
+                .await;
+            tokio::time::sleep(Duration::from_secs(2)).await;
+        }
+    });
+
+    let (response, timing, unsolicited) = send_request_lingering(
+        "127.0.0.1",
+        port,
+        "GET / HTTP/1.1\r\nHost: localhost\r\n\r\n",
+        5,
+        false,
+        false,
+        Duration::from_millis(300),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response, "HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok");
+    assert_eq!(
+        unsolicited,
+        b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n"
+    );
+    // The window is not part of the response time.
+    assert!(timing.response < Duration::from_millis(300));
+}
+
+#[tokio::test]
+async fn test_send_request_lingering_quiet_connection() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        if let Ok((mut socket, _)) = listener.accept().await {
+            let mut buf = [0; 1024];
+            let _ = socket.read(&mut buf).await;
+            let _ = socket
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nquiet")
+                .await;
+            tokio::time::sleep(Duration::from_secs(2)).await;
+        }
+    });
+
+    let (response, _, unsolicited) = send_request_lingering(
+        "127.0.0.1",
+        port,
+        "GET / HTTP/1.1\r\nHost: localhost\r\n\r\n",
+        5,
+        false,
+        false,
+        Duration::from_millis(100),
+    )
+    .await
+    .unwrap();
+    assert!(response.ends_with("quiet"));
+    assert!(unsolicited.is_empty());
+}
+
+#[test]
+fn test_http_response_decodes_chunks_and_trailers() {
+    let response = HttpResponse::parse(
+        b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nSet-Cookie: a=1\r\n\r\n\
+          5;ext=x\r\nhello\r\nA\r\n, chunked!\r\n0\r\nSet-Cookie: b=2\r\nX-Checksum: 9\r\n\r\n",
+    );
+    assert_eq!(response.body, b"hello, chunked!");
+    assert_eq!(
+        response.trailers,
+        [
+            ("Set-Cookie".to_string(), "b=2".to_string()),
+            ("X-Checksum".to_string(), "9".to_string()),
+        ]
+    );
+    assert!(response.trailing.is_empty());
+    assert_eq!(response.text_after_head(), "hello, chunked!");
+    // Trailers are fields, but not headers.
+    assert_eq!(response.header_values("set-cookie").count(), 1);
+    let cookies: Vec<&str> = response.field_values("set-cookie").collect();
+    assert_eq!(cookies, ["a=1", "b=2"]);
+}
+
+#[test]
+fn test_http_response_partial_and_bogus_chunks() {
+    // Cut off mid-chunk: what arrived is kept.
+    let partial = HttpResponse::parse(
+        b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n9\r\nwor",
+    );
+    assert_eq!(partial.body, b"hellowor");
+    assert!(partial.trailing.is_empty());
+
+    // Not chunked after all: the bytes stay as sent.
+    let bogus = HttpResponse::parse(
+        b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n<html>\r\n</html>",
+    );
+    assert_eq!(bogus.body, b"<html>\r\n</html>");
+
+    // A hostile chunk size neither overflows nor panics.
+    let huge = HttpResponse::parse(
+        b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\nffffffffffffffff\r\nabc",
+    );
+    assert_eq!(huge.body, b"abc");
+}
+
+#[tokio::test]
+async fn test_send_request_stops_reading_endless_body_at_cap() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        if let Ok((mut socket, _)) = listener.accept().await {
+            let mut buf = [0; 1024];
+            let _ = socket.read(&mut buf).await;
+            let _ = socket
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 1000000000\r\n\r\n")
+                .await;
+            let chunk = [b'a'; 8192];
+            while socket.write_all(&chunk).await.is_ok() {}
+        }
+    });
+
+    let (response, _) = timeout(
+        Duration::from_secs(5),
+        send_request(
+            "127.0.0.1",
+            port,
+            "GET / HTTP/1.1\r\nHost: localhost\r\n\r\n",
+            10,
+            false,
+            false,
+        ),
+    )
+    .await
+    .expect("reading must stop at the cap")
+    .unwrap();
+    assert_eq!(max_response_size(), DEFAULT_MAX_RESPONSE_SIZE);
+    assert_eq!(response.len(), DEFAULT_MAX_RESPONSE_SIZE);
+
+    // Cut by the cap, not by the target: no truncation is reported.
+    let parsed = HttpResponse::parse(response.as_bytes());
+    assert!(parsed.capped);
+    assert_eq!(parsed.length_mismatch(), None);
+}
+
+#[tokio::test]
+async fn test_send_request_timed_splits_phases() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        if let Ok((mut socket, _)) = listener.accept().await {
+            let mut buf = [0; 1024];
+            let _ = socket.read(&mut buf).await;
+            // The server thinks before the first byte...
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            let _ = socket
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 4\r\n\r\nab")
+                .await;
+            // ...and is slow to finish the body.
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            let _ = socket.write_all(b"cd").await;
+        }
+    });
+
+    let (response, timing) = send_request_timed(
+        "127.0.0.1",
+        port,
+        "GET / HTTP/1.1\r\nHost: localhost\r\n\r\n",
+        5,
+        false,
+        false,
+    )
+    .await
+    .unwrap();
+    assert!(response.ends_with("abcd"));
+    let phases = timing.phases;
+    assert_eq!(phases.tls_ms, 0);
+    assert!(phases.first_byte_ms >= 250, "{}", phases);
+    assert!(phases.transfer_ms >= 150, "{}", phases);
+    let total = phases.dns_ms
+        + phases.tcp_ms
+        + phases.tls_ms
+        + phases.write_ms
+        + phases.first_byte_ms
+        + phases.transfer_ms;
+    let measured = timing.total().as_millis() as u64;
+    assert!(
+        total.abs_diff(measured) <= 6,
+        "{} vs {}ms",
+        phases,
+        measured
+    );
+}
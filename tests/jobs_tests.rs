@@ -0,0 +1,133 @@
+//! Tests for the jobs module
+//!
+//! - Parsing `--stdin-format jsonl` lines, blank lines and bad lines
+//! - Parsing `-l` targets files mixing URLs, jobs and comments
+//! - Building a job's settings from the command line, its profile and fields
+//! - Rejecting jobs with unknown checks, subcommands or bad flags
+
+use smugglex::jobs::{ScanJob, job_cli, parse_jobs, parse_target_list};
+
+fn args(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+fn job(url: &str) -> ScanJob {
+    ScanJob::for_url(url)
+}
+
+#[test]
+fn test_parse_jobs() {
+    let input = r#"{"url":"https://a.example.com"}
+
+{"url":"https://b.example.com","method":"GET","headers":["X-Team: blue"],"vhost":"b.internal","checks":"cl-te","profile":["-t","5"]}
+"#;
+    let jobs = parse_jobs(input).unwrap();
+    assert_eq!(jobs.len(), 2);
+    assert_eq!(jobs[0], job("https://a.example.com"));
+    assert_eq!(jobs[1].method.as_deref(), Some("GET"));
+    assert_eq!(jobs[1].headers, vec!["X-Team: blue"]);
+    assert_eq!(jobs[1].vhost.as_deref(), Some("b.internal"));
+    assert_eq!(jobs[1].checks.as_deref(), Some("cl-te"));
+    assert_eq!(jobs[1].profile, vec!["-t", "5"]);
+}
+
+#[test]
+fn test_parse_jobs_names_bad_line() {
+    let err = parse_jobs("{\"url\":\"https://a.example.com\"}\nhttps://b.example.com\n")
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("stdin line 2"), "{}", err);
+
+    let err = parse_jobs("{\"url\":\"https://a.example.com\",\"method \":\"GET\"}")
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("unknown field"), "{}", err);
+    assert!(parse_jobs("{\"method\":\"GET\"}").is_err());
+}
+
+#[test]
+fn test_parse_target_list() {
+    let input = r#"# web
+https://a.example.com
+
+  {"url":"https://b.example.com/api","method":"GET","headers":["Accept: application/json"],"cookies":true}
+https://c.example.com/login
+"#;
+    let jobs = parse_target_list(input, "targets.txt").unwrap();
+    assert_eq!(jobs.len(), 3);
+    assert_eq!(jobs[0], job("https://a.example.com"));
+    assert_eq!(jobs[1].method.as_deref(), Some("GET"));
+    assert_eq!(jobs[1].headers, vec!["Accept: application/json"]);
+    assert_eq!(jobs[1].cookies, Some(true));
+    assert_eq!(jobs[2], job("https://c.example.com/login"));
+
+    let err = parse_target_list("https://a.example.com\n{\"url\":1}\n", "targets.txt")
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("targets.txt line 2"), "{}", err);
+}
+
+#[test]
+fn test_job_cli_layers_command_line_profile_and_fields() {
+    let base = args(&[
+        "--stdin-format",
+        "jsonl",
+        "-t",
+        "20",
+        "-H",
+        "X-Base: 1",
+        "-c",
+        "te-cl",
+        "-m",
+        "PUT",
+    ]);
+    let mut spec = job("https://a.example.com/app");
+    spec.profile = args(&["-t", "5", "--quick"]);
+    spec.headers = args(&["X-Job: 2"]);
+    spec.method = Some("GET".to_string());
+    spec.vhost = Some("a.internal".to_string());
+
+    let cli = job_cli(&base, &spec).unwrap();
+    assert_eq!(cli.urls, vec!["https://a.example.com/app"]);
+    assert_eq!(cli.timeout, 5);
+    assert!(cli.quick);
+    assert_eq!(cli.headers, vec!["X-Base: 1", "X-Job: 2"]);
+    assert_eq!(cli.method, "GET");
+    assert_eq!(cli.vhost.as_deref(), Some("a.internal"));
+    assert_eq!(cli.checks.as_deref(), Some("te-cl"));
+    assert!(!cli.use_cookies);
+
+    // A job's cookie setting wins over the command line's, either way.
+    let mut spec = job("https://a.example.com/app");
+    spec.cookies = Some(false);
+    let mut with_cookies = base.clone();
+    with_cookies.push("--cookies".to_string());
+    assert!(!job_cli(&with_cookies, &spec).unwrap().use_cookies);
+    spec.cookies = Some(true);
+    assert!(job_cli(&base, &spec).unwrap().use_cookies);
+
+    // Without a profile or fields, the command line applies as is.
+    let cli = job_cli(&base, &job("https://b.example.com")).unwrap();
+    assert_eq!(cli.timeout, 20);
+    assert_eq!(cli.method, "PUT");
+    assert!(!cli.quick);
+}
+
+#[test]
+fn test_job_cli_rejects_bad_jobs() {
+    let mut spec = job("https://a.example.com");
+    spec.checks = Some("cl-tee".to_string());
+    let err = job_cli(&[], &spec).unwrap_err().to_string();
+    assert!(err.contains("no valid checks"), "{}", err);
+
+    let mut spec = job("https://a.example.com");
+    spec.profile = args(&["--no-such-flag"]);
+    let err = job_cli(&[], &spec).unwrap_err().to_string();
+    assert!(err.contains("invalid profile"), "{}", err);
+    assert!(!err.contains('\n'));
+
+    let mut spec = job("https://a.example.com");
+    spec.profile = args(&["payloads", "list"]);
+    let err = job_cli(&[], &spec).unwrap_err().to_string();
+    assert!(err.contains("subcommands are not allowed"), "{}", err);
+}
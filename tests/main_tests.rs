@@ -193,6 +193,11 @@ async fn test_output_file_json_structure() {
 
     let scan_results = ScanResults {
         target: "http://example.com".to_string(),
+        address: None,
+        vhost: None,
+        ip: None,
+        port: None,
+        scheme: None,
         method: "GET".to_string(),
         timestamp: Utc::now().to_rfc3339(),
         fingerprint: None,
@@ -200,17 +205,39 @@ async fn test_output_file_json_structure() {
             check_type: "CL.TE".to_string(),
             vulnerable: false,
             payload_index: None,
+            payload_name: None,
+            technique: None,
             normal_status: "HTTP/1.1 200 OK".to_string(),
             attack_status: None,
             normal_duration_ms: 100,
             attack_duration_ms: None,
+            normal_connect_ms: None,
+            attack_connect_ms: None,
             timestamp: Utc::now().to_rfc3339(),
             payload: None,
             confidence: None,
             detection_signals: Vec::new(),
             diagnostics: Vec::new(),
+            payloads_sent: 0,
+            request_errors: Default::default(),
+            h2_observations: Vec::new(),
+            fuzz_anomalies: Vec::new(),
+            unsolicited_bytes: None,
+            attack_phases: None,
+            cross_check: None,
+            evidence: None,
+            consistency: None,
         }],
+        exploits: None,
+        proxy_chain: Vec::new(),
+        unreachable: None,
+        throttle: None,
+        target_info: None,
         error: None,
+        error_info: None,
+        duration_ms: None,
+        requests_sent: None,
+        summary: None,
     };
 
     let json_output = serde_json::to_string_pretty(&scan_results);
@@ -232,11 +259,25 @@ async fn test_output_file_creation() {
 
     let scan_results = ScanResults {
         target: "http://example.com".to_string(),
+        address: None,
+        vhost: None,
+        ip: None,
+        port: None,
+        scheme: None,
         method: "GET".to_string(),
         timestamp: Utc::now().to_rfc3339(),
         fingerprint: None,
         checks: vec![],
+        exploits: None,
+        proxy_chain: Vec::new(),
+        unreachable: None,
+        throttle: None,
+        target_info: None,
         error: None,
+        error_info: None,
+        duration_ms: None,
+        requests_sent: None,
+        summary: None,
     };
 
     let json_output = serde_json::to_string_pretty(&scan_results).unwrap();
@@ -264,43 +305,82 @@ async fn test_vulnerable_count_calculation() {
             check_type: "CL.TE".to_string(),
             vulnerable: true,
             payload_index: Some(0),
+            payload_name: None,
+            technique: None,
             normal_status: "HTTP/1.1 200 OK".to_string(),
             attack_status: Some("HTTP/1.1 504 Gateway Timeout".to_string()),
             normal_duration_ms: 100,
             attack_duration_ms: Some(5000),
+            normal_connect_ms: None,
+            attack_connect_ms: None,
             timestamp: Utc::now().to_rfc3339(),
             payload: None,
             confidence: None,
             detection_signals: Vec::new(),
             diagnostics: Vec::new(),
+            payloads_sent: 0,
+            request_errors: Default::default(),
+            h2_observations: Vec::new(),
+            fuzz_anomalies: Vec::new(),
+            unsolicited_bytes: None,
+            attack_phases: None,
+            cross_check: None,
+            evidence: None,
+            consistency: None,
         },
         CheckResult {
             check_type: "TE.CL".to_string(),
             vulnerable: false,
             payload_index: None,
+            payload_name: None,
+            technique: None,
             normal_status: "HTTP/1.1 200 OK".to_string(),
             attack_status: None,
             normal_duration_ms: 100,
             attack_duration_ms: None,
+            normal_connect_ms: None,
+            attack_connect_ms: None,
             timestamp: Utc::now().to_rfc3339(),
             payload: None,
             confidence: None,
             detection_signals: Vec::new(),
             diagnostics: Vec::new(),
+            payloads_sent: 0,
+            request_errors: Default::default(),
+            h2_observations: Vec::new(),
+            fuzz_anomalies: Vec::new(),
+            unsolicited_bytes: None,
+            attack_phases: None,
+            cross_check: None,
+            evidence: None,
+            consistency: None,
         },
         CheckResult {
             check_type: "H2C".to_string(),
             vulnerable: true,
             payload_index: Some(1),
+            payload_name: None,
+            technique: None,
             normal_status: "HTTP/1.1 200 OK".to_string(),
             attack_status: Some("HTTP/1.1 408 Request Timeout".to_string()),
             normal_duration_ms: 100,
             attack_duration_ms: Some(3000),
+            normal_connect_ms: None,
+            attack_connect_ms: None,
             timestamp: Utc::now().to_rfc3339(),
             payload: None,
             confidence: None,
             detection_signals: Vec::new(),
             diagnostics: Vec::new(),
+            payloads_sent: 0,
+            request_errors: Default::default(),
+            h2_observations: Vec::new(),
+            fuzz_anomalies: Vec::new(),
+            unsolicited_bytes: None,
+            attack_phases: None,
+            cross_check: None,
+            evidence: None,
+            consistency: None,
         },
     ];
 
@@ -635,29 +715,55 @@ async fn test_results_aggregation() {
             check_type: "CL.TE".to_string(),
             vulnerable: false,
             payload_index: None,
+            payload_name: None,
+            technique: None,
             normal_status: "HTTP/1.1 200 OK".to_string(),
             attack_status: None,
             normal_duration_ms: 100,
             attack_duration_ms: None,
+            normal_connect_ms: None,
+            attack_connect_ms: None,
             timestamp: Utc::now().to_rfc3339(),
             payload: None,
             confidence: None,
             detection_signals: Vec::new(),
             diagnostics: Vec::new(),
+            payloads_sent: 0,
+            request_errors: Default::default(),
+            h2_observations: Vec::new(),
+            fuzz_anomalies: Vec::new(),
+            unsolicited_bytes: None,
+            attack_phases: None,
+            cross_check: None,
+            evidence: None,
+            consistency: None,
         },
         CheckResult {
             check_type: "TE.CL".to_string(),
             vulnerable: true,
             payload_index: Some(0),
+            payload_name: None,
+            technique: None,
             normal_status: "HTTP/1.1 200 OK".to_string(),
             attack_status: Some("HTTP/1.1 504 Gateway Timeout".to_string()),
             normal_duration_ms: 100,
             attack_duration_ms: Some(3000),
+            normal_connect_ms: None,
+            attack_connect_ms: None,
             timestamp: Utc::now().to_rfc3339(),
             payload: None,
             confidence: None,
             detection_signals: Vec::new(),
             diagnostics: Vec::new(),
+            payloads_sent: 0,
+            request_errors: Default::default(),
+            h2_observations: Vec::new(),
+            fuzz_anomalies: Vec::new(),
+            unsolicited_bytes: None,
+            attack_phases: None,
+            cross_check: None,
+            evidence: None,
+            consistency: None,
         },
     ];
 
@@ -8,8 +8,26 @@
 //! - Different check types and status codes
 //! - Clone implementation
 //! - Confidence enum serialization
-
-use smugglex::model::{CheckResult, Confidence, ScanResults};
+//! - Exploit results serialization
+//! - Fuzz triage anomalies serialization
+//! - Payload name serialization
+//! - Connection setup time serialization
+//! - Retry-After throttle summary
+//! - Attack request phase timings
+//! - HTTP/2 termination observations
+//! - Target info (server versions, certificate)
+//! - Per-target summary of check outcomes
+//! - Cross-technique checks of CL.TE / TE.CL findings
+//! - Evidence scores weighing a finding's signals
+//! - Merging `--repeat` runs and their consistency
+
+use smugglex::model::{
+    CertificateInfo, CheckOutcome, CheckResult, Confidence, Consistency, CrossCheck,
+    CrossCheckVerdict, DiscoveredPath, EvidenceScore, ExploitDecision, ExploitPlan, ExploitResults,
+    FuzzAnomaly, H2Observation, H2Termination, InternalSweep, LocalhostPortResult, PhaseTimings,
+    PlannedExploit, RequestErrorKind, ScanResults, ScanSummary, SweepTarget, TargetInfo, Throttle,
+    merge_repeated_runs,
+};
 
 /// Helper function to create a test CheckResult
 fn create_test_check_result(
@@ -23,15 +41,28 @@ fn create_test_check_result(
         check_type: check_type.to_string(),
         vulnerable,
         payload_index,
+        payload_name: None,
+        technique: None,
         normal_status: "HTTP/1.1 200 OK".to_string(),
         attack_status: attack_status.map(|s| s.to_string()),
         normal_duration_ms: 150,
         attack_duration_ms,
+        normal_connect_ms: None,
+        attack_connect_ms: None,
         timestamp: "2024-01-01T12:00:00Z".to_string(),
         payload: None,
         confidence: None,
         detection_signals: Vec::new(),
         diagnostics: Vec::new(),
+        payloads_sent: 0,
+        request_errors: Default::default(),
+        h2_observations: Vec::new(),
+        fuzz_anomalies: Vec::new(),
+        unsolicited_bytes: None,
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
     }
 }
 
@@ -142,15 +173,28 @@ fn test_check_result_zero_duration() {
         check_type: "CL.TE".to_string(),
         vulnerable: false,
         payload_index: None,
+        payload_name: None,
+        technique: None,
         normal_status: "HTTP/1.1 200 OK".to_string(),
         attack_status: None,
         normal_duration_ms: 0,
         attack_duration_ms: Some(0),
+        normal_connect_ms: None,
+        attack_connect_ms: None,
         timestamp: "2024-01-01T12:00:00Z".to_string(),
         payload: None,
         confidence: None,
         detection_signals: Vec::new(),
         diagnostics: Vec::new(),
+        payloads_sent: 0,
+        request_errors: Default::default(),
+        h2_observations: Vec::new(),
+        fuzz_anomalies: Vec::new(),
+        unsolicited_bytes: None,
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
     };
 
     assert_eq!(result.normal_duration_ms, 0);
@@ -164,15 +208,28 @@ fn test_check_result_special_characters() {
         check_type: "CL.TE".to_string(),
         vulnerable: true,
         payload_index: Some(0),
+        payload_name: None,
+        technique: None,
         normal_status: "HTTP/1.1 200 OK".to_string(),
         attack_status: Some("HTTP/1.1 504 \"Gateway\" Timeout".to_string()),
         normal_duration_ms: 100,
         attack_duration_ms: Some(5000),
+        normal_connect_ms: None,
+        attack_connect_ms: None,
         timestamp: "2024-01-01T12:00:00+00:00".to_string(),
         payload: None,
         confidence: Some(Confidence::High),
         detection_signals: Vec::new(),
         diagnostics: Vec::new(),
+        payloads_sent: 0,
+        request_errors: Default::default(),
+        h2_observations: Vec::new(),
+        fuzz_anomalies: Vec::new(),
+        unsolicited_bytes: None,
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
     };
 
     let json = serde_json::to_string(&result).expect("Should serialize");
@@ -188,15 +245,28 @@ fn test_check_result_serialization_vulnerable() {
         check_type: "TE.TE".to_string(),
         vulnerable: true,
         payload_index: Some(1),
+        payload_name: None,
+        technique: None,
         normal_status: "HTTP/1.1 200 OK".to_string(),
         attack_status: Some("HTTP/1.1 408 Request Timeout".to_string()),
         normal_duration_ms: 100,
         attack_duration_ms: Some(10000),
+        normal_connect_ms: None,
+        attack_connect_ms: None,
         timestamp: "2024-01-01T12:00:00Z".to_string(),
         payload: None,
         confidence: Some(Confidence::Medium),
         detection_signals: Vec::new(),
         diagnostics: Vec::new(),
+        payloads_sent: 0,
+        request_errors: Default::default(),
+        h2_observations: Vec::new(),
+        fuzz_anomalies: Vec::new(),
+        unsolicited_bytes: None,
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
     };
 
     let json = serde_json::to_string(&result).expect("Failed to serialize");
@@ -230,21 +300,112 @@ fn test_check_result_deserialization() {
     assert_eq!(result.attack_duration_ms, Some(4500));
 }
 
+#[test]
+fn test_check_result_cross_check_serialization() {
+    let mut result = create_test_check_result("cl-te", true, Some(0), None, Some(5000));
+    let json = serde_json::to_value(&result).unwrap();
+    assert!(json.get("cross_check").is_none());
+
+    result.cross_check = Some(CrossCheck {
+        technique: "te-cl".to_string(),
+        verdict: CrossCheckVerdict::Inconsistent,
+        observation: "TE.CL probe also desynced (Connection Timeout, 5000ms)".to_string(),
+    });
+    let json = serde_json::to_value(&result).unwrap();
+    assert_eq!(json["cross_check"]["technique"], "te-cl");
+    assert_eq!(json["cross_check"]["verdict"], "inconsistent");
+    let parsed: CheckResult = serde_json::from_value(json).unwrap();
+    assert_eq!(parsed.cross_check, result.cross_check);
+}
+
+#[test]
+fn test_evidence_score() {
+    let mut result = create_test_check_result("cl-cl", false, None, None, None);
+    result.detection_signals = vec!["timing_anomaly:4.0x".to_string()];
+    assert!(EvidenceScore::of(&result).is_none(), "no finding, no score");
+
+    result.vulnerable = true;
+    result.detection_signals = vec![
+        "status_504".to_string(),
+        "timing_anomaly:83.4x".to_string(),
+        "baseline_noisy".to_string(),
+        "front_end_honors:content-length#1=5".to_string(),
+    ];
+    result.cross_check = Some(CrossCheck {
+        technique: "te-cl".to_string(),
+        verdict: CrossCheckVerdict::Consistent,
+        observation: String::new(),
+    });
+    let evidence = EvidenceScore::of(&result).unwrap();
+    assert_eq!(evidence.score, 30);
+    let factors: Vec<(&str, i32)> = evidence
+        .factors
+        .iter()
+        .map(|f| (f.factor.as_str(), f.weight))
+        .collect();
+    // Descriptive signals carry no weight and are left out
+    assert_eq!(
+        factors,
+        [
+            ("status_504", 15),
+            ("timing_anomaly:83.4x", 20),
+            ("baseline_noisy", -15),
+            ("cross_check:consistent", 10),
+        ]
+    );
+    assert_eq!(
+        evidence.to_string(),
+        "30/100 (status_504 +15, timing_anomaly:83.4x +20, baseline_noisy -15, cross_check:consistent +10)"
+    );
+
+    // Clamped to 0..=100
+    result.cross_check = None;
+    result.detection_signals = vec![
+        "unsolicited_bytes:45".to_string(),
+        "H2_CL_stall".to_string(),
+        "h2_downgrade_desync".to_string(),
+        "followup_divergence:3/3".to_string(),
+        "second_request_desync".to_string(),
+    ];
+    assert_eq!(EvidenceScore::of(&result).unwrap().score, 100);
+    result.detection_signals = vec!["baseline_noisy".to_string()];
+    assert_eq!(EvidenceScore::of(&result).unwrap().score, 0);
+
+    result.evidence = EvidenceScore::of(&result);
+    let json = serde_json::to_value(&result).unwrap();
+    assert_eq!(json["evidence"]["score"], 0);
+    assert_eq!(json["evidence"]["factors"][0]["factor"], "baseline_noisy");
+    assert_eq!(json["evidence"]["factors"][0]["weight"], -15);
+}
+
 #[test]
 fn test_check_result_clone() {
     let result = CheckResult {
         check_type: "CL.TE".to_string(),
         vulnerable: true,
         payload_index: Some(0),
+        payload_name: None,
+        technique: None,
         normal_status: "HTTP/1.1 200 OK".to_string(),
         attack_status: Some("HTTP/1.1 504 Gateway Timeout".to_string()),
         normal_duration_ms: 200,
         attack_duration_ms: Some(3000),
+        normal_connect_ms: None,
+        attack_connect_ms: None,
         timestamp: "2024-01-01T12:00:00Z".to_string(),
         payload: None,
         confidence: Some(Confidence::High),
         detection_signals: Vec::new(),
         diagnostics: Vec::new(),
+        payloads_sent: 0,
+        request_errors: Default::default(),
+        h2_observations: Vec::new(),
+        fuzz_anomalies: Vec::new(),
+        unsolicited_bytes: None,
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
     };
 
     let cloned = result.clone();
@@ -261,39 +422,79 @@ fn test_scan_results_creation() {
         check_type: "CL.TE".to_string(),
         vulnerable: true,
         payload_index: Some(1),
+        payload_name: None,
+        technique: None,
         normal_status: "HTTP/1.1 200 OK".to_string(),
         attack_status: Some("HTTP/1.1 504 Gateway Timeout".to_string()),
         normal_duration_ms: 200,
         attack_duration_ms: Some(5000),
+        normal_connect_ms: None,
+        attack_connect_ms: None,
         timestamp: "2024-01-01T12:00:00Z".to_string(),
         payload: None,
         confidence: Some(Confidence::High),
         detection_signals: Vec::new(),
         diagnostics: Vec::new(),
+        payloads_sent: 0,
+        request_errors: Default::default(),
+        h2_observations: Vec::new(),
+        fuzz_anomalies: Vec::new(),
+        unsolicited_bytes: None,
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
     };
 
     let check2 = CheckResult {
         check_type: "TE.CL".to_string(),
         vulnerable: false,
         payload_index: None,
+        payload_name: None,
+        technique: None,
         normal_status: "HTTP/1.1 200 OK".to_string(),
         attack_status: None,
         normal_duration_ms: 150,
         attack_duration_ms: None,
+        normal_connect_ms: None,
+        attack_connect_ms: None,
         timestamp: "2024-01-01T12:00:01Z".to_string(),
         payload: None,
         confidence: None,
         detection_signals: Vec::new(),
         diagnostics: Vec::new(),
+        payloads_sent: 0,
+        request_errors: Default::default(),
+        h2_observations: Vec::new(),
+        fuzz_anomalies: Vec::new(),
+        unsolicited_bytes: None,
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
     };
 
     let scan_results = ScanResults {
         target: "https://example.com".to_string(),
+        address: None,
+        vhost: None,
+        ip: None,
+        port: None,
+        scheme: None,
         method: "POST".to_string(),
         timestamp: "2024-01-01T12:00:00Z".to_string(),
         fingerprint: None,
         checks: vec![check1, check2],
+        exploits: None,
+        proxy_chain: Vec::new(),
+        unreachable: None,
+        throttle: None,
+        target_info: None,
         error: None,
+        error_info: None,
+        duration_ms: None,
+        requests_sent: None,
+        summary: None,
     };
 
     assert_eq!(scan_results.target, "https://example.com");
@@ -309,24 +510,51 @@ fn test_scan_results_serialization() {
         check_type: "CL.TE".to_string(),
         vulnerable: true,
         payload_index: Some(0),
+        payload_name: None,
+        technique: None,
         normal_status: "HTTP/1.1 200 OK".to_string(),
         attack_status: Some("HTTP/1.1 504 Gateway Timeout".to_string()),
         normal_duration_ms: 200,
         attack_duration_ms: Some(4000),
+        normal_connect_ms: None,
+        attack_connect_ms: None,
         timestamp: "2024-01-01T12:00:00Z".to_string(),
         payload: None,
         confidence: Some(Confidence::High),
         detection_signals: Vec::new(),
         diagnostics: Vec::new(),
+        payloads_sent: 0,
+        request_errors: Default::default(),
+        h2_observations: Vec::new(),
+        fuzz_anomalies: Vec::new(),
+        unsolicited_bytes: None,
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
     };
 
     let scan_results = ScanResults {
         target: "https://api.example.com/test".to_string(),
+        address: None,
+        vhost: None,
+        ip: Some("93.184.216.34".to_string()),
+        port: Some(443),
+        scheme: Some("https".to_string()),
         method: "GET".to_string(),
         timestamp: "2024-01-01T12:00:00Z".to_string(),
         fingerprint: None,
         checks: vec![check],
+        exploits: None,
+        proxy_chain: Vec::new(),
+        unreachable: None,
+        throttle: None,
+        target_info: None,
         error: None,
+        error_info: None,
+        duration_ms: Some(12500),
+        requests_sent: Some(184),
+        summary: None,
     };
 
     let json = serde_json::to_string_pretty(&scan_results).expect("Failed to serialize");
@@ -336,6 +564,11 @@ fn test_scan_results_serialization() {
     assert!(json.contains("\"timestamp\":"));
     assert!(json.contains("\"checks\":"));
     assert!(json.contains("https://api.example.com/test"));
+    assert!(json.contains("\"ip\": \"93.184.216.34\""));
+    assert!(json.contains("\"port\": 443"));
+    assert!(json.contains("\"scheme\": \"https\""));
+    assert!(json.contains("\"duration_ms\": 12500"));
+    assert!(json.contains("\"requests_sent\": 184"));
 }
 
 #[test]
@@ -370,11 +603,25 @@ fn test_scan_results_deserialization() {
 fn test_scan_results_empty_checks() {
     let scan_results = ScanResults {
         target: "http://test.com".to_string(),
+        address: None,
+        vhost: None,
+        ip: None,
+        port: None,
+        scheme: None,
         method: "GET".to_string(),
         timestamp: "2024-01-01T12:00:00Z".to_string(),
         fingerprint: None,
         checks: vec![],
+        exploits: None,
+        proxy_chain: Vec::new(),
+        unreachable: None,
+        throttle: None,
+        target_info: None,
         error: None,
+        error_info: None,
+        duration_ms: None,
+        requests_sent: None,
+        summary: None,
     };
 
     assert_eq!(scan_results.checks.len(), 0);
@@ -392,53 +639,106 @@ fn test_scan_results_multiple_checks() {
             check_type: "CL.TE".to_string(),
             vulnerable: true,
             payload_index: Some(0),
+            payload_name: None,
+            technique: None,
             normal_status: "HTTP/1.1 200 OK".to_string(),
             attack_status: Some("HTTP/1.1 504 Gateway Timeout".to_string()),
             normal_duration_ms: 150,
             attack_duration_ms: Some(3000),
+            normal_connect_ms: None,
+            attack_connect_ms: None,
             timestamp: "2024-01-01T12:00:00Z".to_string(),
             payload: None,
             confidence: Some(Confidence::High),
             detection_signals: Vec::new(),
             diagnostics: Vec::new(),
+            payloads_sent: 0,
+            request_errors: Default::default(),
+            h2_observations: Vec::new(),
+            fuzz_anomalies: Vec::new(),
+            unsolicited_bytes: None,
+            attack_phases: None,
+            cross_check: None,
+            evidence: None,
+            consistency: None,
         },
         CheckResult {
             check_type: "TE.CL".to_string(),
             vulnerable: false,
             payload_index: None,
+            payload_name: None,
+            technique: None,
             normal_status: "HTTP/1.1 200 OK".to_string(),
             attack_status: None,
             normal_duration_ms: 160,
             attack_duration_ms: None,
+            normal_connect_ms: None,
+            attack_connect_ms: None,
             timestamp: "2024-01-01T12:00:01Z".to_string(),
             payload: None,
             confidence: None,
             detection_signals: Vec::new(),
             diagnostics: Vec::new(),
+            payloads_sent: 0,
+            request_errors: Default::default(),
+            h2_observations: Vec::new(),
+            fuzz_anomalies: Vec::new(),
+            unsolicited_bytes: None,
+            attack_phases: None,
+            cross_check: None,
+            evidence: None,
+            consistency: None,
         },
         CheckResult {
             check_type: "TE.TE".to_string(),
             vulnerable: true,
             payload_index: Some(2),
+            payload_name: None,
+            technique: None,
             normal_status: "HTTP/1.1 200 OK".to_string(),
             attack_status: Some("Connection Timeout".to_string()),
             normal_duration_ms: 140,
             attack_duration_ms: Some(10000),
+            normal_connect_ms: None,
+            attack_connect_ms: None,
             timestamp: "2024-01-01T12:00:02Z".to_string(),
             payload: None,
             confidence: Some(Confidence::Low),
             detection_signals: Vec::new(),
             diagnostics: Vec::new(),
+            payloads_sent: 0,
+            request_errors: Default::default(),
+            h2_observations: Vec::new(),
+            fuzz_anomalies: Vec::new(),
+            unsolicited_bytes: None,
+            attack_phases: None,
+            cross_check: None,
+            evidence: None,
+            consistency: None,
         },
     ];
 
     let scan_results = ScanResults {
         target: "https://vulnerable.example.com".to_string(),
+        address: None,
+        vhost: None,
+        ip: None,
+        port: None,
+        scheme: None,
         method: "POST".to_string(),
         timestamp: "2024-01-01T12:00:00Z".to_string(),
         fingerprint: None,
         checks: checks.clone(),
+        exploits: None,
+        proxy_chain: Vec::new(),
+        unreachable: None,
+        throttle: None,
+        target_info: None,
         error: None,
+        error_info: None,
+        duration_ms: None,
+        requests_sent: None,
+        summary: None,
     };
 
     assert_eq!(scan_results.checks.len(), 3);
@@ -456,15 +756,28 @@ fn test_check_result_different_check_types() {
             check_type: check_type.to_string(),
             vulnerable: false,
             payload_index: None,
+            payload_name: None,
+            technique: None,
             normal_status: "HTTP/1.1 200 OK".to_string(),
             attack_status: None,
             normal_duration_ms: 100,
             attack_duration_ms: None,
+            normal_connect_ms: None,
+            attack_connect_ms: None,
             timestamp: "2024-01-01T12:00:00Z".to_string(),
             payload: None,
             confidence: None,
             detection_signals: Vec::new(),
             diagnostics: Vec::new(),
+            payloads_sent: 0,
+            request_errors: Default::default(),
+            h2_observations: Vec::new(),
+            fuzz_anomalies: Vec::new(),
+            unsolicited_bytes: None,
+            attack_phases: None,
+            cross_check: None,
+            evidence: None,
+            consistency: None,
         };
 
         assert_eq!(result.check_type, check_type);
@@ -478,15 +791,28 @@ fn test_check_result_timeout_scenarios() {
         check_type: "CL.TE".to_string(),
         vulnerable: true,
         payload_index: Some(0),
+        payload_name: None,
+        technique: None,
         normal_status: "HTTP/1.1 200 OK".to_string(),
         attack_status: Some("HTTP/1.1 504 Gateway Timeout".to_string()),
         normal_duration_ms: 100,
         attack_duration_ms: Some(15000),
+        normal_connect_ms: None,
+        attack_connect_ms: None,
         timestamp: "2024-01-01T12:00:00Z".to_string(),
         payload: None,
         confidence: Some(Confidence::High),
         detection_signals: Vec::new(),
         diagnostics: Vec::new(),
+        payloads_sent: 0,
+        request_errors: Default::default(),
+        h2_observations: Vec::new(),
+        fuzz_anomalies: Vec::new(),
+        unsolicited_bytes: None,
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
     };
 
     assert!(result1.attack_status.as_ref().unwrap().contains("504"));
@@ -496,15 +822,28 @@ fn test_check_result_timeout_scenarios() {
         check_type: "TE.CL".to_string(),
         vulnerable: true,
         payload_index: Some(1),
+        payload_name: None,
+        technique: None,
         normal_status: "HTTP/1.1 200 OK".to_string(),
         attack_status: Some("Connection Timeout".to_string()),
         normal_duration_ms: 100,
         attack_duration_ms: Some(10000),
+        normal_connect_ms: None,
+        attack_connect_ms: None,
         timestamp: "2024-01-01T12:00:00Z".to_string(),
         payload: None,
         confidence: Some(Confidence::Low),
         detection_signals: Vec::new(),
         diagnostics: Vec::new(),
+        payloads_sent: 0,
+        request_errors: Default::default(),
+        h2_observations: Vec::new(),
+        fuzz_anomalies: Vec::new(),
+        unsolicited_bytes: None,
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
     };
 
     assert_eq!(
@@ -522,15 +861,28 @@ fn test_payload_stored_in_vulnerable_result() {
         check_type: "CL.TE".to_string(),
         vulnerable: true,
         payload_index: Some(0),
+        payload_name: None,
+        technique: None,
         normal_status: "HTTP/1.1 200 OK".to_string(),
         attack_status: Some("Connection Timeout".to_string()),
         normal_duration_ms: 100,
         attack_duration_ms: Some(5000),
+        normal_connect_ms: None,
+        attack_connect_ms: None,
         timestamp: "2024-01-01T12:00:00Z".to_string(),
         payload: Some(payload_content.to_string()),
         confidence: Some(Confidence::Medium),
         detection_signals: Vec::new(),
         diagnostics: Vec::new(),
+        payloads_sent: 0,
+        request_errors: Default::default(),
+        h2_observations: Vec::new(),
+        fuzz_anomalies: Vec::new(),
+        unsolicited_bytes: None,
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
     };
 
     assert!(result.vulnerable);
@@ -545,15 +897,28 @@ fn test_payload_is_none_for_non_vulnerable() {
         check_type: "TE.CL".to_string(),
         vulnerable: false,
         payload_index: None,
+        payload_name: None,
+        technique: None,
         normal_status: "HTTP/1.1 200 OK".to_string(),
         attack_status: None,
         normal_duration_ms: 100,
         attack_duration_ms: None,
+        normal_connect_ms: None,
+        attack_connect_ms: None,
         timestamp: "2024-01-01T12:00:00Z".to_string(),
         payload: None,
         confidence: None,
         detection_signals: Vec::new(),
         diagnostics: Vec::new(),
+        payloads_sent: 0,
+        request_errors: Default::default(),
+        h2_observations: Vec::new(),
+        fuzz_anomalies: Vec::new(),
+        unsolicited_bytes: None,
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
     };
 
     assert!(!result.vulnerable);
@@ -567,15 +932,28 @@ fn test_payload_serialization_with_payload() {
         check_type: "TE.TE".to_string(),
         vulnerable: true,
         payload_index: Some(1),
+        payload_name: None,
+        technique: None,
         normal_status: "HTTP/1.1 200 OK".to_string(),
         attack_status: Some("Connection Timeout".to_string()),
         normal_duration_ms: 100,
         attack_duration_ms: Some(10000),
+        normal_connect_ms: None,
+        attack_connect_ms: None,
         timestamp: "2024-01-01T12:00:00Z".to_string(),
         payload: Some("GET / HTTP/1.1\r\nHost: example.com\r\n\r\n".to_string()),
         confidence: Some(Confidence::Low),
         detection_signals: Vec::new(),
         diagnostics: Vec::new(),
+        payloads_sent: 0,
+        request_errors: Default::default(),
+        h2_observations: Vec::new(),
+        fuzz_anomalies: Vec::new(),
+        unsolicited_bytes: None,
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
     };
 
     let json = serde_json::to_string(&result).expect("Failed to serialize");
@@ -592,15 +970,28 @@ fn test_payload_serialization_without_payload() {
         check_type: "CL.TE".to_string(),
         vulnerable: false,
         payload_index: None,
+        payload_name: None,
+        technique: None,
         normal_status: "HTTP/1.1 200 OK".to_string(),
         attack_status: None,
         normal_duration_ms: 100,
         attack_duration_ms: None,
+        normal_connect_ms: None,
+        attack_connect_ms: None,
         timestamp: "2024-01-01T12:00:00Z".to_string(),
         payload: None,
         confidence: None,
         detection_signals: Vec::new(),
         diagnostics: Vec::new(),
+        payloads_sent: 0,
+        request_errors: Default::default(),
+        h2_observations: Vec::new(),
+        fuzz_anomalies: Vec::new(),
+        unsolicited_bytes: None,
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
     };
 
     let json = serde_json::to_string(&result).expect("Failed to serialize");
@@ -620,15 +1011,28 @@ fn test_confidence_serialization() {
         check_type: "CL.TE".to_string(),
         vulnerable: true,
         payload_index: Some(0),
+        payload_name: None,
+        technique: None,
         normal_status: "HTTP/1.1 200 OK".to_string(),
         attack_status: Some("HTTP/1.1 504 Gateway Timeout".to_string()),
         normal_duration_ms: 100,
         attack_duration_ms: Some(5000),
+        normal_connect_ms: None,
+        attack_connect_ms: None,
         timestamp: "2024-01-01T12:00:00Z".to_string(),
         payload: None,
         confidence: Some(Confidence::High),
         detection_signals: Vec::new(),
         diagnostics: Vec::new(),
+        payloads_sent: 0,
+        request_errors: Default::default(),
+        h2_observations: Vec::new(),
+        fuzz_anomalies: Vec::new(),
+        unsolicited_bytes: None,
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
     };
     let json = serde_json::to_string(&result).expect("Failed to serialize");
     assert!(json.contains("\"confidence\":\"high\""));
@@ -660,15 +1064,28 @@ fn test_confidence_skip_when_none() {
         check_type: "CL.TE".to_string(),
         vulnerable: false,
         payload_index: None,
+        payload_name: None,
+        technique: None,
         normal_status: "HTTP/1.1 200 OK".to_string(),
         attack_status: None,
         normal_duration_ms: 100,
         attack_duration_ms: None,
+        normal_connect_ms: None,
+        attack_connect_ms: None,
         timestamp: "2024-01-01T12:00:00Z".to_string(),
         payload: None,
         confidence: None,
         detection_signals: Vec::new(),
         diagnostics: Vec::new(),
+        payloads_sent: 0,
+        request_errors: Default::default(),
+        h2_observations: Vec::new(),
+        fuzz_anomalies: Vec::new(),
+        unsolicited_bytes: None,
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
     };
     let json = serde_json::to_string(&result).expect("Failed to serialize");
     assert!(!json.contains("confidence"));
@@ -692,3 +1109,417 @@ fn test_confidence_backward_compat_deserialization() {
     assert!(result.vulnerable);
     assert_eq!(result.confidence, None);
 }
+
+// ========== Exploit Results Tests ==========
+
+#[test]
+fn test_scan_results_exploits_skipped_when_none() {
+    let scan_results = ScanResults {
+        target: "http://example.com".to_string(),
+        address: None,
+        vhost: None,
+        ip: None,
+        port: None,
+        scheme: None,
+        method: "POST".to_string(),
+        timestamp: "2024-01-01T12:00:00Z".to_string(),
+        fingerprint: None,
+        checks: Vec::new(),
+        exploits: None,
+        proxy_chain: Vec::new(),
+        unreachable: None,
+        throttle: None,
+        target_info: None,
+        error: None,
+        error_info: None,
+        duration_ms: None,
+        requests_sent: None,
+        summary: None,
+    };
+    let json = serde_json::to_string(&scan_results).unwrap();
+    assert!(!json.contains("exploits"));
+
+    // Results written before the field existed still deserialize.
+    let parsed: ScanResults = serde_json::from_str(
+        r#"{"target":"http://example.com","method":"POST","timestamp":"t","checks":[]}"#,
+    )
+    .unwrap();
+    assert!(parsed.exploits.is_none());
+}
+
+#[test]
+fn test_scan_results_exploits_round_trip() {
+    let exploits = ExploitResults {
+        plan: Some(ExploitPlan {
+            exploits: vec![PlannedExploit {
+                exploit: "localhost-access".to_string(),
+                max_requests: 17,
+                smuggled: vec!["GET / HTTP/1.1 (Host: 127.0.0.1:8080)".to_string()],
+            }],
+            decision: ExploitDecision::PreApproved,
+        }),
+        capped: Some("exploit request cap of 50 reached".to_string()),
+        localhost_access: vec![
+            LocalhostPortResult {
+                port: 8080,
+                success: true,
+                verdict: "open-different-service".to_string(),
+                loopback_host: "127.0.0.1".to_string(),
+                internal_host: None,
+                reason: "HTTP 200 with body".to_string(),
+                response_status: Some("HTTP/1.1 200 OK".to_string()),
+            },
+            LocalhostPortResult {
+                port: 80,
+                success: true,
+                verdict: "open-different-service".to_string(),
+                loopback_host: String::new(),
+                internal_host: Some("admin.internal".to_string()),
+                reason: "Status code changed from 200 to 401".to_string(),
+                response_status: Some("HTTP/1.1 401 Unauthorized".to_string()),
+            },
+        ],
+        path_fuzz: vec![DiscoveredPath {
+            path: "/admin".to_string(),
+            reason: "status 200".to_string(),
+            response_status: Some("HTTP/1.1 200 OK".to_string()),
+            response_size: Some(512),
+        }],
+        internal_sweep: Some(InternalSweep {
+            range: "10.0.0.0/30".to_string(),
+            requests_sent: 7,
+            untested: 2,
+            stopped: Some("request budget exhausted".to_string()),
+            targets: vec![SweepTarget {
+                address: "10.0.0.1".to_string(),
+                port: 8080,
+                verdict: "different-app".to_string(),
+                reason: "Status code changed from 200 to 401".to_string(),
+                response_status: Some("HTTP/1.1 401 Unauthorized".to_string()),
+            }],
+        }),
+    };
+    let scan_results = ScanResults {
+        target: "http://example.com".to_string(),
+        address: None,
+        vhost: None,
+        ip: None,
+        port: None,
+        scheme: None,
+        method: "POST".to_string(),
+        timestamp: "2024-01-01T12:00:00Z".to_string(),
+        fingerprint: None,
+        checks: Vec::new(),
+        exploits: Some(exploits.clone()),
+        proxy_chain: Vec::new(),
+        unreachable: None,
+        throttle: None,
+        target_info: None,
+        error: None,
+        error_info: None,
+        duration_ms: None,
+        requests_sent: None,
+        summary: None,
+    };
+
+    let value = serde_json::to_value(&scan_results).unwrap();
+    assert_eq!(value["exploits"]["localhost_access"][0]["port"], 8080);
+    assert_eq!(value["exploits"]["path_fuzz"][0]["path"], "/admin");
+    assert_eq!(value["exploits"]["plan"]["decision"], "pre-approved");
+    assert_eq!(value["exploits"]["plan"]["exploits"][0]["max_requests"], 17);
+    assert_eq!(
+        value["exploits"]["capped"],
+        "exploit request cap of 50 reached"
+    );
+
+    let parsed: ScanResults = serde_json::from_value(value).unwrap();
+    assert_eq!(parsed.exploits, Some(exploits));
+}
+
+#[test]
+fn test_exploit_results_empty_sections_skipped() {
+    let exploits = ExploitResults::default();
+    assert!(exploits.is_empty());
+    assert_eq!(serde_json::to_string(&exploits).unwrap(), "{}");
+}
+
+// ========== Fuzz Triage Tests ==========
+
+#[test]
+fn test_fuzz_anomalies_serialization() {
+    let mut result = create_test_check_result("te-cl", false, None, None, None);
+    let json = serde_json::to_string(&result).unwrap();
+    assert!(!json.contains("fuzz_anomalies"));
+
+    result.fuzz_anomalies.push(FuzzAnomaly {
+        payload_index: 7,
+        payload_name: Some("mutant of linefold-tab".to_string()),
+        anomaly: "status HTTP/1.1 504 Gateway Timeout; not reproduced on retry".to_string(),
+        payload: "POST / HTTP/1.1\r\n\r\n".to_string(),
+        seed_diff: vec!["+ X-Junk: garbage\\r\\n".to_string()],
+        reproduction_file: None,
+    });
+    let value = serde_json::to_value(&result).unwrap();
+    assert_eq!(value["fuzz_anomalies"][0]["payload_index"], 7);
+    assert_eq!(
+        value["fuzz_anomalies"][0]["payload_name"],
+        "mutant of linefold-tab"
+    );
+    assert!(
+        value["fuzz_anomalies"][0]
+            .get("reproduction_file")
+            .is_none()
+    );
+
+    let parsed: CheckResult = serde_json::from_value(value).unwrap();
+    assert_eq!(parsed.fuzz_anomalies, result.fuzz_anomalies);
+}
+
+#[test]
+fn test_payload_name_serialization() {
+    let mut result = create_test_check_result("te-cl", true, Some(12), None, None);
+    let json = serde_json::to_string(&result).unwrap();
+    assert!(!json.contains("payload_name"));
+
+    result.payload_name = Some("linefold-tab".to_string());
+    let value = serde_json::to_value(&result).unwrap();
+    assert_eq!(value["payload_name"], "linefold-tab");
+    let parsed: CheckResult = serde_json::from_value(value).unwrap();
+    assert_eq!(parsed.payload_name.as_deref(), Some("linefold-tab"));
+}
+
+#[test]
+fn test_connect_time_serialization() {
+    let mut result = create_test_check_result("cl-te", true, Some(0), None, Some(5000));
+    let json = serde_json::to_string(&result).unwrap();
+    assert!(!json.contains("connect_ms"));
+
+    result.normal_connect_ms = Some(310);
+    result.attack_connect_ms = Some(295);
+    let value = serde_json::to_value(&result).unwrap();
+    assert_eq!(value["normal_connect_ms"], 310);
+    assert_eq!(value["attack_connect_ms"], 295);
+    let parsed: CheckResult = serde_json::from_value(value).unwrap();
+    assert_eq!(parsed.normal_connect_ms, Some(310));
+    assert_eq!(parsed.attack_connect_ms, Some(295));
+}
+
+#[test]
+fn test_throttle_serialization_and_display() {
+    let throttle = Throttle {
+        events: 3,
+        paused_ms: 12_500,
+        longest_retry_after_secs: 120,
+        capped: 1,
+    };
+    assert_eq!(
+        throttle.to_string(),
+        "3 Retry-After response(s), paused 12.5s (longest asked 120s, 1 capped)"
+    );
+    let value = serde_json::to_value(&throttle).unwrap();
+    assert_eq!(value["paused_ms"], 12_500);
+    let parsed: Throttle = serde_json::from_value(value).unwrap();
+    assert_eq!(parsed, throttle);
+
+    let uncapped = Throttle {
+        capped: 0,
+        ..throttle
+    };
+    assert_eq!(
+        uncapped.to_string(),
+        "3 Retry-After response(s), paused 12.5s (longest asked 120s)"
+    );
+}
+
+#[test]
+fn test_attack_phases_serialization_and_display() {
+    let mut result = create_test_check_result("cl-te", true, Some(0), None, None);
+    let json = serde_json::to_string(&result).unwrap();
+    assert!(!json.contains("attack_phases"));
+
+    let phases = PhaseTimings {
+        dns_ms: 2,
+        tcp_ms: 11,
+        tls_ms: 35,
+        write_ms: 0,
+        first_byte_ms: 5012,
+        transfer_ms: 1,
+    };
+    assert_eq!(
+        phases.to_string(),
+        "dns 2ms, tcp 11ms, tls 35ms, write 0ms, first byte 5012ms, transfer 1ms"
+    );
+    result.attack_phases = Some(phases);
+    let value = serde_json::to_value(&result).unwrap();
+    assert_eq!(value["attack_phases"]["first_byte_ms"], 5012);
+    let parsed: CheckResult = serde_json::from_value(value).unwrap();
+    assert_eq!(parsed.attack_phases, Some(phases));
+}
+
+#[test]
+fn test_h2_observation_serialization() {
+    let mut result = create_test_check_result("h2-downgrade", false, None, None, None);
+    let json = serde_json::to_string(&result).unwrap();
+    assert!(!json.contains("h2_observations"));
+
+    result.h2_observations = vec![
+        H2Observation {
+            payload: "h2.cl".to_string(),
+            termination: H2Termination::RstStream,
+            error_code: Some("PROTOCOL_ERROR".to_string()),
+        },
+        H2Observation {
+            payload: "control".to_string(),
+            termination: H2Termination::FlowControlStall,
+            error_code: None,
+        },
+    ];
+    let value = serde_json::to_value(&result).unwrap();
+    assert_eq!(value["h2_observations"][0]["termination"], "rst-stream");
+    assert_eq!(value["h2_observations"][0]["error_code"], "PROTOCOL_ERROR");
+    assert_eq!(
+        value["h2_observations"][1]["termination"],
+        "flow-control-stall"
+    );
+    assert!(value["h2_observations"][1].get("error_code").is_none());
+    let parsed: CheckResult = serde_json::from_value(value).unwrap();
+    assert_eq!(parsed.h2_observations, result.h2_observations);
+}
+
+#[test]
+fn test_target_info_serialization_and_display() {
+    let info = TargetInfo {
+        server: vec!["nginx/1.24.0".to_string()],
+        powered_by: Vec::new(),
+        error_page_versions: vec!["Apache/2.4.57".to_string()],
+        certificate: Some(CertificateInfo {
+            subject: "CN=example.com".to_string(),
+            issuer: "CN=Example CA".to_string(),
+            serial: "0a1b".to_string(),
+            not_before: "2024-01-01T00:00:00Z".to_string(),
+            not_after: "2025-01-01T00:00:00Z".to_string(),
+            san: Vec::new(),
+        }),
+    };
+    assert_eq!(
+        info.to_string(),
+        "Server nginx/1.24.0; error page Apache/2.4.57; certificate CN=example.com (issuer CN=Example CA, expires 2025-01-01T00:00:00Z)"
+    );
+    let value = serde_json::to_value(&info).unwrap();
+    assert!(value.get("powered_by").is_none());
+    assert!(value["certificate"].get("san").is_none());
+    assert_eq!(value["certificate"]["serial"], "0a1b");
+    let parsed: TargetInfo = serde_json::from_value(value).unwrap();
+    assert_eq!(parsed, info);
+
+    assert!(TargetInfo::default().is_empty());
+    assert!(!info.is_empty());
+}
+
+#[test]
+fn test_scan_summary_from_checks() {
+    let mut medium = create_test_check_result("cl-te", true, Some(3), None, Some(5000));
+    medium.confidence = Some(Confidence::Medium);
+    medium.payloads_sent = 4;
+    let mut high = create_test_check_result("te-cl", true, Some(1), None, Some(5000));
+    high.confidence = Some(Confidence::High);
+    high.payloads_sent = 2;
+    let mut clean = create_test_check_result("te-te", false, None, None, None);
+    clean.payloads_sent = 40;
+    let mut errored = create_test_check_result("h2c", false, None, None, None);
+    errored.payloads_sent = 3;
+    errored.request_errors.insert(RequestErrorKind::Reset, 3);
+
+    assert_eq!(medium.outcome(), CheckOutcome::Vulnerable);
+    assert_eq!(clean.outcome(), CheckOutcome::Clean);
+    assert_eq!(errored.outcome(), CheckOutcome::Error);
+
+    let summary = ScanSummary::from_checks(&[medium, clean, high, errored], 12_500);
+    assert_eq!(summary.vulnerable, 2);
+    assert_eq!(summary.clean, 1);
+    assert_eq!(summary.errored, 1);
+    assert_eq!(summary.payloads_sent, 49);
+    assert_eq!(summary.highest_confidence, Some(Confidence::High));
+    assert_eq!(
+        summary.to_string(),
+        "4 check(s): 2 vulnerable, 1 clean, 1 error; 49 payload(s) sent, highest confidence high, 12.5s"
+    );
+
+    let json = serde_json::to_value(&summary).unwrap();
+    assert_eq!(json["highest_confidence"], "high");
+    assert_eq!(json["duration_ms"], 12_500);
+
+    let empty = ScanSummary::from_checks(&[], 0);
+    assert_eq!(empty.highest_confidence, None);
+    assert!(
+        !serde_json::to_string(&empty)
+            .unwrap()
+            .contains("highest_confidence")
+    );
+}
+
+#[test]
+fn test_merge_repeated_runs() {
+    let mut missed = create_test_check_result("cl-te", false, None, None, None);
+    missed.payloads_sent = 10;
+    missed.request_errors.insert(RequestErrorKind::Reset, 1);
+    let mut found =
+        create_test_check_result("cl-te", true, Some(4), Some("HTTP/1.1 504"), Some(9000));
+    found.payloads_sent = 5;
+    found.request_errors.insert(RequestErrorKind::Reset, 2);
+
+    let merged = merge_repeated_runs(vec![missed.clone(), found.clone(), missed.clone()]).unwrap();
+    assert!(merged.vulnerable);
+    assert_eq!(merged.payload_index, Some(4));
+    assert_eq!(merged.payloads_sent, 25);
+    assert_eq!(merged.request_errors[&RequestErrorKind::Reset], 4);
+    let consistency = merged.consistency.unwrap();
+    assert_eq!(consistency, Consistency::new(3, 1));
+    assert!(consistency.flaky);
+    assert_eq!(consistency.to_string(), "found 1/3 (flaky)");
+
+    let stable = merge_repeated_runs(vec![found.clone(), found.clone()]).unwrap();
+    assert!(!stable.consistency.unwrap().flaky);
+    assert_eq!(stable.consistency.unwrap().to_string(), "found 2/2");
+
+    let clean = merge_repeated_runs(vec![missed.clone(), missed.clone()]).unwrap();
+    assert!(!clean.vulnerable);
+    assert_eq!(clean.consistency, Some(Consistency::new(2, 0)));
+    assert!(!clean.consistency.unwrap().flaky);
+
+    // One run (no --repeat) is left as it was.
+    let single = merge_repeated_runs(vec![found]).unwrap();
+    assert!(single.vulnerable);
+    assert_eq!(single.payloads_sent, 5);
+    assert!(single.consistency.is_none());
+    assert!(merge_repeated_runs(Vec::new()).is_none());
+}
+
+#[test]
+fn test_repeat_consistency_in_evidence_and_summary() {
+    let mut flaky = create_test_check_result("cl-te", true, Some(0), None, Some(9000));
+    flaky.detection_signals = vec!["status_504".to_string()];
+    flaky.consistency = Some(Consistency::new(3, 1));
+    let evidence = EvidenceScore::of(&flaky).unwrap();
+    assert_eq!(evidence.score, 0);
+    assert_eq!(evidence.factors[1].factor, "consistency:1/3");
+    assert_eq!(evidence.factors[1].weight, -15);
+
+    let mut stable = flaky.clone();
+    stable.consistency = Some(Consistency::new(3, 3));
+    assert_eq!(EvidenceScore::of(&stable).unwrap().score, 25);
+
+    let summary = ScanSummary::from_checks(&[flaky, stable], 1000);
+    assert_eq!(summary.flaky, 1);
+    assert!(
+        summary
+            .to_string()
+            .contains("2 vulnerable, 0 clean, 0 error; 0 payload(s) sent, 1 flaky")
+    );
+    assert_eq!(serde_json::to_value(&summary).unwrap()["flaky"], 1);
+    assert!(
+        !serde_json::to_string(&ScanSummary::from_checks(&[], 0))
+            .unwrap()
+            .contains("flaky")
+    );
+}
@@ -6,8 +6,11 @@
 //! - Mutant validity (still contains HTTP/1.1)
 //! - Different seeds produce different results
 //! - Edge cases (empty input, single payload)
+//! - Grammar-aware mode (request line and Host preserved, structural mutants)
+//! - Feedback-driven scheduling toward seeds reaching rare response clusters
+//! - Mutant budgets (`max_mutants`)
 
-use smugglex::mutator::{Mutator, MutatorConfig};
+use smugglex::mutator::{FuzzFeedback, FuzzMode, Mutator, MutatorConfig};
 use std::collections::HashSet;
 
 fn sample_payload() -> String {
@@ -25,10 +28,12 @@ fn test_deterministic_same_seed() {
     let mut m1 = Mutator::new(MutatorConfig {
         seed: 42,
         mutations_per_payload: 10,
+        ..MutatorConfig::default()
     });
     let mut m2 = Mutator::new(MutatorConfig {
         seed: 42,
         mutations_per_payload: 10,
+        ..MutatorConfig::default()
     });
 
     let r1 = m1.mutate_payloads(&seeds);
@@ -43,10 +48,12 @@ fn test_different_seeds_differ() {
     let mut m1 = Mutator::new(MutatorConfig {
         seed: 1,
         mutations_per_payload: 10,
+        ..MutatorConfig::default()
     });
     let mut m2 = Mutator::new(MutatorConfig {
         seed: 9999,
         mutations_per_payload: 10,
+        ..MutatorConfig::default()
     });
 
     let r1 = m1.mutate_payloads(&seeds);
@@ -61,6 +68,7 @@ fn test_deduplication() {
     let mut m = Mutator::new(MutatorConfig {
         seed: 42,
         mutations_per_payload: 5,
+        ..MutatorConfig::default()
     });
     let result = m.mutate_payloads(&seeds);
 
@@ -79,6 +87,7 @@ fn test_originals_preserved_first() {
     let mut m = Mutator::new(MutatorConfig {
         seed: 42,
         mutations_per_payload: 5,
+        ..MutatorConfig::default()
     });
     let result = m.mutate_payloads(&seeds);
 
@@ -95,6 +104,7 @@ fn test_mutants_contain_http_version() {
     let mut m = Mutator::new(MutatorConfig {
         seed: 42,
         mutations_per_payload: 10,
+        ..MutatorConfig::default()
     });
     let result = m.mutate_payloads(&seeds);
 
@@ -114,6 +124,7 @@ fn test_generates_more_than_originals() {
     let mut m = Mutator::new(MutatorConfig {
         seed: 42,
         mutations_per_payload: 5,
+        ..MutatorConfig::default()
     });
     let result = m.mutate_payloads(&seeds);
 
@@ -131,6 +142,7 @@ fn test_empty_input() {
     let mut m = Mutator::new(MutatorConfig {
         seed: 42,
         mutations_per_payload: 5,
+        ..MutatorConfig::default()
     });
     let result = m.mutate_payloads(&seeds);
 
@@ -144,6 +156,7 @@ fn test_multiple_seeds() {
     let mut m = Mutator::new(MutatorConfig {
         seed: 42,
         mutations_per_payload: 3,
+        ..MutatorConfig::default()
     });
     let result = m.mutate_payloads(&seeds);
 
@@ -160,6 +173,7 @@ fn test_high_mutation_count() {
     let mut m = Mutator::new(MutatorConfig {
         seed: 42,
         mutations_per_payload: 50,
+        ..MutatorConfig::default()
     });
     let result = m.mutate_payloads(&seeds);
 
@@ -170,3 +184,158 @@ fn test_high_mutation_count() {
         result.len()
     );
 }
+
+#[test]
+fn test_grammar_mode_preserves_request_line_and_host() {
+    let seeds = vec![te_cl_payload()];
+
+    let mut m = Mutator::new(MutatorConfig {
+        seed: 42,
+        mutations_per_payload: 50,
+        mode: FuzzMode::Grammar,
+        ..MutatorConfig::default()
+    });
+    let result = m.mutate_payloads(&seeds);
+
+    assert!(result.len() > 10, "got {} results", result.len());
+    for payload in &result {
+        assert!(payload.starts_with("POST / HTTP/1.1\r\n"));
+        assert!(payload.contains("Host: example.com"));
+    }
+}
+
+#[test]
+fn test_grammar_mode_explores_structure() {
+    let seeds = vec![te_cl_payload()];
+
+    let mut m = Mutator::new(MutatorConfig {
+        seed: 1337,
+        mutations_per_payload: 100,
+        mode: FuzzMode::Grammar,
+        ..MutatorConfig::default()
+    });
+    let result = m.mutate_payloads(&seeds);
+
+    // Header order changed: Transfer-Encoding now precedes Content-Length.
+    assert!(result.iter().any(|p| {
+        let head = p.split("\r\n\r\n").next().unwrap();
+        matches!(
+            (head.find("Transfer-Encoding: chunked"), head.find("Content-Length: 4")),
+            (Some(te), Some(cl)) if te < cl
+        )
+    }));
+    // A chunk size was re-encoded.
+    assert!(
+        result
+            .iter()
+            .any(|p| p.contains("\r\n\r\n00000001\r\nA") || p.contains("\r\n\r\n0x1\r\nA"))
+    );
+    // A smuggling primitive was spliced in.
+    assert!(
+        result
+            .iter()
+            .any(|p| p.contains("Transfer-Encoding: identity")
+                || p.contains("xchunked")
+                || p.contains("Content-Length: 0\r\n"))
+    );
+}
+
+#[test]
+fn test_grammar_mode_deterministic() {
+    let seeds = vec![sample_payload(), te_cl_payload()];
+    let config = MutatorConfig {
+        seed: 7,
+        mutations_per_payload: 10,
+        mode: FuzzMode::Grammar,
+        ..MutatorConfig::default()
+    };
+    let r1 = Mutator::new(config.clone()).mutate_payloads(&seeds);
+    let r2 = Mutator::new(config).mutate_payloads(&seeds);
+    assert_eq!(r1, r2);
+}
+
+#[test]
+fn test_feedback_payloads_originals_first_with_fixed_budget() {
+    let seeds = vec![sample_payload(), te_cl_payload(), sample_payload()];
+    let m = Mutator::new(MutatorConfig {
+        seed: 42,
+        mutations_per_payload: 4,
+        ..MutatorConfig::default()
+    });
+    let payloads: Vec<String> = m
+        .feedback_payloads(&seeds, FuzzFeedback::default())
+        .map(|p| p.request)
+        .collect();
+
+    // Duplicate seeds collapse; the budget matches mutate_payloads'.
+    assert_eq!(payloads.len(), 2 * 5);
+    assert_eq!(payloads[0], sample_payload());
+    assert_eq!(payloads[1], te_cl_payload());
+}
+
+#[test]
+fn test_feedback_payloads_bias_toward_rare_clusters() {
+    let rejected = "POST /rejected HTTP/1.1\r\nHost: example.com\r\nContent-Length: 4\r\nTransfer-Encoding: chunked\r\n\r\n1\r\nA\r\n0\r\n\r\n".to_string();
+    let varied = rejected.replace("/rejected", "/varied");
+    let feedback = FuzzFeedback::default();
+    let m = Mutator::new(MutatorConfig {
+        seed: 42,
+        mutations_per_payload: 50,
+        mode: FuzzMode::Grammar,
+        ..MutatorConfig::default()
+    });
+
+    let mut from_varied = 0;
+    let mut from_rejected = 0;
+    for (i, payload) in m
+        .feedback_payloads(&[rejected, varied], feedback.clone())
+        .enumerate()
+    {
+        // The front-end rejects every `/rejected` request identically, while
+        // `/varied` requests each land in a distinct cluster.
+        if payload.request.starts_with("POST /rejected ") {
+            feedback.record(&payload.request, Some("HTTP/1.1 400 Bad Request\r\n\r\n"));
+            from_rejected += usize::from(i >= 2);
+        } else {
+            let body: String = (0..=i).map(|n| (b'a' + (n % 26) as u8) as char).collect();
+            let response = format!("HTTP/1.1 200 OK\r\n\r\n{}", body);
+            feedback.record(&payload.request, Some(&response));
+            from_varied += usize::from(i >= 2);
+        }
+    }
+
+    assert!(
+        from_varied > from_rejected * 2,
+        "varied={} rejected={}",
+        from_varied,
+        from_rejected
+    );
+    assert!(feedback.promoted_count() > 0);
+}
+
+#[test]
+fn test_max_mutants_caps_both_generators() {
+    let seeds = vec![sample_payload(), te_cl_payload()];
+    let config = MutatorConfig {
+        seed: 42,
+        mutations_per_payload: 20,
+        max_mutants: Some(7),
+        ..MutatorConfig::default()
+    };
+
+    let blind = Mutator::new(config.clone()).mutate_payloads(&seeds);
+    assert!(blind.len() <= seeds.len() + 7);
+    assert_eq!(&blind[..2], &seeds[..]);
+
+    let lazy = Mutator::new(config).feedback_payloads(&seeds, FuzzFeedback::default());
+    assert_eq!(lazy.len(), seeds.len() + 7);
+
+    // A budget above the per-seed allowance does not add mutants.
+    let generous = Mutator::new(MutatorConfig {
+        mutations_per_payload: 2,
+        max_mutants: Some(500),
+        ..MutatorConfig::default()
+    })
+    .feedback_payloads(&seeds, FuzzFeedback::default());
+    assert_eq!(generous.len(), seeds.len() * 3);
+}
@@ -2,8 +2,14 @@
 //!
 //! This module tests result formatting and file saving logic.
 
-use smugglex::model::{BatchScanResults, CheckResult, FingerprintInfo, ScanResults};
-use smugglex::output::{build_batch_results, save_batch_to_file, save_results_to_file};
+use smugglex::model::{
+    BatchScanResults, CheckResult, Confidence, DiscoveredPath, ExploitResults, FingerprintInfo,
+    H2Observation, H2Termination, ProxyHop, ScanResults, Technique,
+};
+use smugglex::output::{
+    build_batch_results, format_h2_observations, format_response_diff, save_batch_to_file,
+    save_results_to_file,
+};
 use std::fs;
 
 fn sample_check_result(check_type: &str, vulnerable: bool) -> CheckResult {
@@ -11,15 +17,28 @@ fn sample_check_result(check_type: &str, vulnerable: bool) -> CheckResult {
         check_type: check_type.to_string(),
         vulnerable,
         payload_index: Some(0),
+        payload_name: None,
+        technique: None,
         normal_status: "HTTP/1.1 200 OK".to_string(),
         attack_status: vulnerable.then_some("HTTP/1.1 504 Gateway Timeout".to_string()),
         normal_duration_ms: 100,
         attack_duration_ms: vulnerable.then_some(5000),
+        normal_connect_ms: None,
+        attack_connect_ms: None,
         timestamp: "2024-01-01T00:00:00Z".to_string(),
         payload: Some("test payload".to_string()),
         confidence: None,
         detection_signals: Vec::new(),
         diagnostics: Vec::new(),
+        payloads_sent: 0,
+        request_errors: Default::default(),
+        h2_observations: Vec::new(),
+        fuzz_anomalies: Vec::new(),
+        unsolicited_bytes: None,
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
     }
 }
 
@@ -31,7 +50,15 @@ fn test_save_results_to_file_creates_json() {
 
     let results = vec![sample_check_result("cl-te", true)];
 
-    let result = save_results_to_file(output_path, "http://example.com", "GET", results, &None);
+    let result = save_results_to_file(
+        output_path,
+        "http://example.com",
+        "GET",
+        results,
+        &None,
+        None,
+        &[],
+    );
     assert!(result.is_ok());
 
     // Verify the file was created and contains valid JSON
@@ -57,6 +84,11 @@ fn test_save_results_to_file_with_fingerprint() {
         server_header: Some("nginx/1.24.0".to_string()),
         via_header: None,
         powered_by: None,
+        asterisk_rewrite: false,
+        hop_count: Some(2),
+        error_hop: None,
+        alpn: None,
+        h2_downgrade_likely: false,
     });
 
     let mut result = sample_check_result("te-cl", false);
@@ -71,6 +103,8 @@ fn test_save_results_to_file_with_fingerprint() {
         "POST",
         results,
         &fingerprint,
+        None,
+        &[],
     );
     assert!(result.is_ok());
 
@@ -85,13 +119,58 @@ fn test_save_results_to_file_with_fingerprint() {
     fs::remove_file(output_path).ok();
 }
 
+#[test]
+fn test_save_results_to_file_with_proxy_chain() {
+    let temp_dir = std::env::temp_dir();
+    let output_file = temp_dir.join("smugglex_test_output_chain.json");
+    let output_path = output_file.to_str().unwrap();
+
+    let chain = vec![
+        ProxyHop {
+            product: "cloudflare".to_string(),
+            source: "cf-ray".to_string(),
+            detail: "8a1b2c3d4e5f-LAX".to_string(),
+        },
+        ProxyHop {
+            product: "nginx".to_string(),
+            source: "server".to_string(),
+            detail: "nginx/1.24.0".to_string(),
+        },
+    ];
+    let result = save_results_to_file(
+        output_path,
+        "https://test.com",
+        "POST",
+        Vec::new(),
+        &None,
+        None,
+        &chain,
+    );
+    assert!(result.is_ok());
+
+    let content = fs::read_to_string(output_path).unwrap();
+    let parsed: ScanResults = serde_json::from_str(&content).unwrap();
+    assert_eq!(parsed.proxy_chain, chain);
+
+    // Cleanup
+    fs::remove_file(output_path).ok();
+}
+
 #[test]
 fn test_save_results_to_file_empty_results() {
     let temp_dir = std::env::temp_dir();
     let output_file = temp_dir.join("smugglex_test_output_empty.json");
     let output_path = output_file.to_str().unwrap();
 
-    let result = save_results_to_file(output_path, "http://example.com", "GET", Vec::new(), &None);
+    let result = save_results_to_file(
+        output_path,
+        "http://example.com",
+        "GET",
+        Vec::new(),
+        &None,
+        None,
+        &[],
+    );
     assert!(result.is_ok());
 
     let content = fs::read_to_string(output_path).unwrap();
@@ -110,6 +189,8 @@ fn test_save_results_to_file_invalid_path() {
         "GET",
         Vec::new(),
         &None,
+        None,
+        &[],
     );
     assert!(result.is_err());
 }
@@ -119,6 +200,11 @@ fn test_build_batch_results_summary_counts_failures_and_vulnerabilities() {
     let results = vec![
         ScanResults {
             target: "http://one.example".to_string(),
+            address: None,
+            vhost: None,
+            ip: None,
+            port: None,
+            scheme: None,
             method: "GET".to_string(),
             timestamp: "2024-01-01T00:00:00Z".to_string(),
             fingerprint: None,
@@ -126,15 +212,38 @@ fn test_build_batch_results_summary_counts_failures_and_vulnerabilities() {
                 sample_check_result("cl-te", true),
                 sample_check_result("te-cl", false),
             ],
+            exploits: None,
+            proxy_chain: Vec::new(),
+            unreachable: None,
+            throttle: None,
+            target_info: None,
             error: None,
+            error_info: None,
+            duration_ms: None,
+            requests_sent: None,
+            summary: None,
         },
         ScanResults {
             target: "http://two.example".to_string(),
+            address: None,
+            vhost: None,
+            ip: None,
+            port: None,
+            scheme: None,
             method: "GET".to_string(),
             timestamp: "2024-01-01T00:00:00Z".to_string(),
             fingerprint: None,
             checks: vec![],
+            exploits: None,
+            proxy_chain: Vec::new(),
+            unreachable: None,
+            throttle: None,
+            target_info: None,
             error: Some("URL parse error".to_string()),
+            error_info: None,
+            duration_ms: None,
+            requests_sent: None,
+            summary: None,
         },
     ];
 
@@ -155,11 +264,25 @@ fn test_save_batch_to_file_creates_parseable_json() {
     let batch = build_batch_results(
         vec![ScanResults {
             target: "http://example.com".to_string(),
+            address: None,
+            vhost: None,
+            ip: None,
+            port: None,
+            scheme: None,
             method: "GET".to_string(),
             timestamp: "2024-01-01T00:00:00Z".to_string(),
             fingerprint: None,
             checks: vec![sample_check_result("cl-te", false)],
+            exploits: None,
+            proxy_chain: Vec::new(),
+            unreachable: None,
+            throttle: None,
+            target_info: None,
             error: None,
+            error_info: None,
+            duration_ms: None,
+            requests_sent: None,
+            summary: None,
         }],
         Some("0.2.0"),
     );
@@ -175,3 +298,187 @@ fn test_save_batch_to_file_creates_parseable_json() {
 
     fs::remove_file(output_path).ok();
 }
+
+#[test]
+fn test_build_sarif_reports_only_vulnerable_checks() {
+    let scan = ScanResults {
+        target: "http://example.com".to_string(),
+        address: None,
+        vhost: None,
+        ip: None,
+        port: None,
+        scheme: None,
+        method: "POST".to_string(),
+        timestamp: "2024-01-01T00:00:00Z".to_string(),
+        fingerprint: None,
+        checks: vec![
+            sample_check_result("cl-te", true),
+            sample_check_result("te-cl", false),
+        ],
+        exploits: None,
+        proxy_chain: Vec::new(),
+        unreachable: None,
+        throttle: None,
+        target_info: None,
+        error: None,
+        error_info: None,
+        duration_ms: None,
+        requests_sent: None,
+        summary: None,
+    };
+    let sarif = smugglex::output::build_sarif(&[scan], "0.0.0");
+    assert_eq!(sarif["version"], "2.1.0");
+    let run = &sarif["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], "smugglex");
+    assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 1);
+    let results = run["results"].as_array().unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["ruleId"], "cl-te");
+    assert_eq!(
+        results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+        "http://example.com"
+    );
+}
+
+#[test]
+fn test_github_annotations() {
+    let mut high = sample_check_result("cl-te", true);
+    high.confidence = Some(Confidence::High);
+    high.technique = Some(Technique {
+        family: "cl-te".to_string(),
+        id: "cl-te/linefold-tab".to_string(),
+        description: "TE header, folded".to_string(),
+        reference: String::new(),
+    });
+    let mut medium = sample_check_result("te-cl", true);
+    medium.confidence = Some(Confidence::Medium);
+    medium.payload_name = Some("space before colon".to_string());
+    let scan = |target: &str, checks: Vec<CheckResult>, error: Option<&str>| ScanResults {
+        target: target.to_string(),
+        address: None,
+        vhost: None,
+        ip: None,
+        port: None,
+        scheme: None,
+        method: "POST".to_string(),
+        timestamp: "2024-01-01T00:00:00Z".to_string(),
+        fingerprint: None,
+        checks,
+        exploits: None,
+        proxy_chain: Vec::new(),
+        unreachable: None,
+        throttle: None,
+        target_info: None,
+        error: error.map(str::to_string),
+        error_info: None,
+        duration_ms: None,
+        requests_sent: None,
+        summary: None,
+    };
+    let results = [
+        scan(
+            "https://a.example.com",
+            vec![high, medium, sample_check_result("h2c", false)],
+            None,
+        ),
+        scan(
+            "https://b.example.com",
+            Vec::new(),
+            Some("connect failed\nreset"),
+        ),
+    ];
+
+    let lines = smugglex::output::github_annotations(&results);
+    assert_eq!(
+        lines,
+        [
+            "::error title=HTTP request smuggling (cl-te)::HTTP request smuggling (cl-te) on https://a.example.com via cl-te/linefold-tab: TE header, folded, confidence high",
+            "::warning title=HTTP request smuggling (te-cl)::HTTP request smuggling (te-cl) on https://a.example.com via space before colon, confidence medium",
+            "::warning title=smugglex scan failed::https://b.example.com: connect failed%0Areset",
+        ]
+    );
+}
+
+#[test]
+fn test_build_sarif_includes_exploit_results() {
+    let scan = ScanResults {
+        target: "http://example.com".to_string(),
+        address: None,
+        vhost: None,
+        ip: None,
+        port: None,
+        scheme: None,
+        method: "POST".to_string(),
+        timestamp: "2024-01-01T00:00:00Z".to_string(),
+        fingerprint: None,
+        checks: vec![sample_check_result("cl-te", true)],
+        exploits: Some(ExploitResults {
+            plan: None,
+            capped: None,
+            localhost_access: Vec::new(),
+            path_fuzz: vec![DiscoveredPath {
+                path: "/admin".to_string(),
+                reason: "status 200".to_string(),
+                response_status: Some("HTTP/1.1 200 OK".to_string()),
+                response_size: Some(42),
+            }],
+            internal_sweep: None,
+        }),
+        proxy_chain: Vec::new(),
+        unreachable: None,
+        throttle: None,
+        target_info: None,
+        error: None,
+        error_info: None,
+        duration_ms: None,
+        requests_sent: None,
+        summary: None,
+    };
+    let sarif = smugglex::output::build_sarif(&[scan], "0.0.0");
+    let properties = &sarif["runs"][0]["results"][0]["properties"];
+    assert_eq!(properties["exploits"]["path_fuzz"][0]["path"], "/admin");
+    assert!(properties["exploits"].get("localhost_access").is_none());
+}
+
+#[test]
+fn test_format_response_diff_collapses_unchanged_lines() {
+    let baseline = "HTTP/1.1 200 OK\nA: 1\nB: 2\nC: 3\nD: 4\nE: 5\nF: 6\nG: 7\nServer: nginx";
+    let attack =
+        "HTTP/1.1 404 Not Found\nA: 1\nB: 2\nC: 3\nD: 4\nE: 5\nF: 6\nG: 7\nServer: varnish\r";
+    assert_eq!(
+        format_response_diff(baseline, attack),
+        vec![
+            "- HTTP/1.1 200 OK",
+            "+ HTTP/1.1 404 Not Found",
+            "  A: 1",
+            "  B: 2",
+            "  ... 3 unchanged line(s)",
+            "  F: 6",
+            "  G: 7",
+            "- Server: nginx",
+            "+ Server: varnish\\r",
+        ]
+    );
+}
+
+#[test]
+fn test_format_h2_observations() {
+    let mut result = sample_check_result("h2-downgrade", false);
+    assert_eq!(format_h2_observations(&result), "");
+    result.h2_observations = vec![
+        H2Observation {
+            payload: "h2.cl".to_string(),
+            termination: H2Termination::RstStream,
+            error_code: Some("PROTOCOL_ERROR".to_string()),
+        },
+        H2Observation {
+            payload: "control".to_string(),
+            termination: H2Termination::Goaway,
+            error_code: None,
+        },
+    ];
+    assert_eq!(
+        format_h2_observations(&result),
+        "h2.cl: rst-stream PROTOCOL_ERROR, control: goaway"
+    );
+}
@@ -8,6 +8,9 @@
 //! - PortSwigger http-request-smuggler pattern compatibility
 //! - Custom headers and cookies formatting
 //! - Payload structure and HTTP compliance
+//! - Curated `--quick` payload selections
+//! - `--extended` long-tail variations
+//! - Technique metadata of family payloads
 
 use smugglex::model::CheckResult;
 use smugglex::payloads::*;
@@ -109,15 +112,28 @@ fn test_check_result_serialization() {
         check_type: "CL.TE".to_string(),
         vulnerable: false,
         payload_index: None,
+        payload_name: None,
+        technique: None,
         normal_status: "HTTP/1.1 200 OK".to_string(),
         attack_status: None,
         normal_duration_ms: 150,
         attack_duration_ms: None,
+        normal_connect_ms: None,
+        attack_connect_ms: None,
         timestamp: "2024-01-01T12:00:00Z".to_string(),
         payload: None,
         confidence: None,
         detection_signals: Vec::new(),
         diagnostics: Vec::new(),
+        payloads_sent: 0,
+        request_errors: Default::default(),
+        h2_observations: Vec::new(),
+        fuzz_anomalies: Vec::new(),
+        unsolicited_bytes: None,
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
     };
 
     let json = serde_json::to_string(&result);
@@ -1489,6 +1505,40 @@ fn test_cl_edge_case_cl_value_variations() {
     );
 }
 
+#[test]
+fn test_cl_edge_case_cl_value_obfuscation() {
+    let payloads = get_cl_edge_case_payloads("/", "example.com", "POST", &[], &[]);
+
+    for value in [
+        "+06",
+        "   6",
+        "\t6",
+        "000000000000000000006",
+        "6, 6",
+        "6,6",
+        "6a",
+        "6;",
+        "6,",
+    ] {
+        assert!(
+            payloads.iter().any(|p| p.contains(&format!(
+                "Content-Length: {}\r\nTransfer-Encoding: chunked",
+                value
+            ))),
+            "Should contain obfuscated CL value {:?}",
+            value
+        );
+    }
+    // Conflicting comma/space-separated values, without TE
+    for value in ["0, 6", "6, 0", "0 6"] {
+        let payload = payloads
+            .iter()
+            .find(|p| p.contains(&format!("Content-Length: {}\r\n", value)))
+            .unwrap_or_else(|| panic!("Should contain CL value {:?}", value));
+        assert!(!payload.contains("Transfer-Encoding"));
+    }
+}
+
 #[test]
 fn test_cl_edge_case_header_name_variations() {
     let payloads = get_cl_edge_case_payloads("/", "example.com", "POST", &[], &[]);
@@ -1560,3 +1610,436 @@ fn test_cl_edge_case_chunked_trailers() {
     let has_trailer = payloads.iter().any(|p| p.contains("Trailer: value"));
     assert!(has_trailer, "Should contain trailer after final chunk");
 }
+
+// ========== CL.CL Payload Tests ==========
+
+#[test]
+fn test_cl_cl_payloads_carry_two_conflicting_lengths() {
+    let payloads = get_cl_cl_payloads("/", "example.com", "POST", &[], &[]);
+    assert_eq!(payloads.len(), 28);
+    for payload in &payloads {
+        let lengths: Vec<&str> = payload
+            .split("\r\n\r\n")
+            .next()
+            .unwrap()
+            .split("\r\n")
+            .filter_map(|line| line.split_once(':'))
+            .filter(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+            .map(|(_, value)| value.trim())
+            .collect();
+        assert_eq!(lengths.len(), 2, "payload: {:?}", payload);
+        assert_ne!(lengths[0], lengths[1]);
+        assert!(payload.ends_with("\r\n\r\nSMUGGL"));
+        assert!(!payload.contains("Transfer-Encoding"));
+    }
+    // Both orders, and casing/whitespace variants of the second header
+    assert!(
+        payloads
+            .iter()
+            .any(|p| p.contains("Content-Length: 6\r\nContent-Length: 50\r\n"))
+    );
+    assert!(
+        payloads
+            .iter()
+            .any(|p| p.contains("Content-Length: 50\r\nContent-Length: 6\r\n"))
+    );
+    assert!(payloads.iter().any(|p| p.contains("content-length: 0\r\n")));
+    assert!(payloads.iter().any(|p| p.contains("CONTENT-LENGTH: 6\r\n")));
+    assert!(
+        payloads
+            .iter()
+            .any(|p| p.contains("Content-Length:\t50\r\n"))
+    );
+}
+
+#[test]
+fn test_cl_cl_hop_signals() {
+    let stall =
+        "POST / HTTP/1.1\r\nHost: h\r\nContent-Length: 6\r\ncontent-length: 50\r\n\r\nSMUGGL";
+    assert_eq!(
+        cl_cl_hop_signals(stall),
+        vec![
+            "front_end_honors:content-length#1=6",
+            "back_end_honors:content-length#2=50"
+        ]
+    );
+    let prefix =
+        "POST / HTTP/1.1\r\nHost: h\r\nContent-Length: 0\r\nContent-Length:\t6\r\n\r\nSMUGGL";
+    assert_eq!(
+        cl_cl_hop_signals(prefix),
+        vec![
+            "front_end_honors:content-length#2=6",
+            "back_end_honors:content-length#1=0"
+        ]
+    );
+    // Not a CL.CL shape
+    assert!(cl_cl_hop_signals("POST / HTTP/1.1\r\nContent-Length: 6\r\n\r\nSMUGGL").is_empty());
+}
+
+// ========== Asterisk-form Payload Tests ==========
+
+#[test]
+fn test_asterisk_payloads_use_asterisk_form_with_framing_conflicts() {
+    let payloads = get_asterisk_payloads("/api", "example.com", "POST", &[], &[]);
+    assert_eq!(payloads.len(), 12);
+    for payload in &payloads {
+        assert!(
+            payload.starts_with("OPTIONS * HTTP/1.1\r\n")
+                || payload.starts_with("POST * HTTP/1.1\r\n")
+        );
+        assert!(!payload.contains("/api"));
+        assert!(payload.contains("Host: example.com\r\n"));
+        assert!(
+            payload.matches("Content-Length").count()
+                + payload.matches("Transfer-Encoding").count()
+                >= 2
+        );
+    }
+    assert_eq!(
+        payloads
+            .iter()
+            .filter(|p| p.starts_with("OPTIONS *"))
+            .count(),
+        6
+    );
+    assert!(
+        payloads
+            .iter()
+            .any(|p| p.contains("Transfer-Encoding : chunked"))
+    );
+    assert!(
+        payloads
+            .iter()
+            .any(|p| p.contains("Content-Length: 0\r\nContent-Length: 6\r\n"))
+    );
+}
+
+// ========== Connection Header Payload Tests ==========
+
+#[test]
+fn test_connection_payloads_vary_the_connection_header() {
+    let headers = vec!["X-Test: 1".to_string()];
+    let payloads = get_connection_payloads("/api", "example.com", "POST", &headers, &[]);
+    assert_eq!(payloads.len(), 28);
+    for payload in &payloads {
+        assert!(payload.starts_with("POST /api HTTP/1.1\r\nHost: example.com\r\n"));
+        assert!(payload.to_lowercase().contains("\r\nconnection"));
+        assert!(payload.contains("X-Test: 1\r\n"));
+        assert!(payload.contains("Transfer-Encoding: chunked\r\n"));
+    }
+    for variant in [
+        "Host: example.com\r\nConnection: Transfer-Encoding\r\n",
+        "Connection: keep-alive, Content-Length\r\n",
+        "Connection: keep-alive\r\nConnection: Transfer-Encoding\r\n",
+        "CONNECTION: TRANSFER-ENCODING\r\n",
+        "Connection: close\r\n",
+    ] {
+        assert_eq!(
+            payloads.iter().filter(|p| p.contains(variant)).count(),
+            2,
+            "{:?} should be sent with CL.TE and TE.CL framing",
+            variant
+        );
+    }
+}
+
+// ========== HEAD Payload Tests ==========
+
+#[test]
+fn test_head_payloads_declare_a_queued_request_body() {
+    let payloads = get_head_payloads("/api", "example.com", "POST", &[], &[]);
+    assert_eq!(payloads.len(), 5);
+    for payload in &payloads {
+        assert!(payload.starts_with("HEAD /api HTTP/1.1\r\nHost: example.com\r\n"));
+        let (head, body) = payload.split_once("\r\n\r\n").unwrap();
+        assert!(body.contains("GET /smugglex-head-queued HTTP/1.1\r\n"));
+        // Every Content-Length matches what the framing it belongs to expects.
+        if let Some(line) = head.lines().find(|l| l.starts_with("Content-Length: ")) {
+            let declared: usize = line["Content-Length: ".len()..].parse().unwrap();
+            let te_cl =
+                body.starts_with(|c: char| c.is_ascii_hexdigit()) && !body.starts_with("0\r\n");
+            if te_cl {
+                assert_eq!(declared, body.find("\r\n").unwrap() + 2);
+            } else {
+                assert_eq!(declared, body.len());
+            }
+        }
+    }
+    assert!(
+        payloads
+            .iter()
+            .any(|p| !p.contains("Transfer-Encoding") && p.contains("Content-Length"))
+    );
+    assert!(
+        payloads
+            .iter()
+            .any(|p| p.contains("Transfer-Encoding : chunked"))
+    );
+}
+
+#[test]
+fn test_payload_iter_matches_eager_generators() {
+    let headers = vec!["X-Test: 1".to_string()];
+    let cookies = vec!["a=b".to_string()];
+    let lazy = cl_edge_case_payloads("/", "example.com", "POST", &headers, &cookies);
+    assert_eq!(lazy.len(), 45);
+    let eager = get_cl_edge_case_payloads("/", "example.com", "POST", &headers, &cookies);
+    let requests: Vec<String> = lazy.map(|p| p.request).collect();
+    assert_eq!(requests, eager);
+
+    // Every generator's advertised count matches what it yields.
+    for iter in [
+        cl_te_payloads("/", "h", "POST", &[], &[]),
+        te_cl_payloads("/", "h", "POST", &[], &[]),
+        te_te_payloads("/", "h", "POST", &[], &[]),
+        h2c_payloads("/", "h", "POST", &[], &[]),
+        h2_payloads("/", "h", "POST", &[], &[]),
+        cl_cl_payloads("/", "h", "POST", &[], &[]),
+        asterisk_payloads("/", "h", "POST", &[], &[]),
+        connection_payloads("/", "h", "POST", &[], &[]),
+        head_payloads("/", "h", "POST", &[], &[]),
+    ] {
+        let len = iter.len();
+        let indices: Vec<usize> = iter.map(|p| p.index).collect();
+        assert_eq!(indices, (0..len).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn test_payload_iter_truncate_and_map_requests() {
+    let mut iter = cl_te_payloads("/", "example.com", "POST", &[], &[])
+        .truncate(3)
+        .map_requests(|r| r.replacen("POST", "PUT", 1));
+    assert_eq!(iter.len(), 3);
+    let first = iter.next().unwrap();
+    assert_eq!(first.index, 0);
+    assert!(first.request.starts_with("PUT / HTTP/1.1"));
+    assert_eq!(iter.len(), 2);
+    assert_eq!(iter.count(), 2);
+
+    let from_vec = PayloadIter::from(vec!["A".to_string(), "B".to_string()]);
+    let collected: Vec<Payload> = from_vec.collect();
+    assert_eq!(
+        collected[1],
+        Payload {
+            index: 1,
+            request: "B".to_string(),
+            name: None,
+            technique: None,
+        }
+    );
+}
+
+#[test]
+fn test_payload_iter_prioritize_keeps_indices() {
+    let eager = get_cl_te_payloads("/", "example.com", "POST", &[], &[]);
+    let iter = cl_te_payloads("/", "example.com", "POST", &[], &[])
+        .prioritize(|r| {
+            if r.contains("Transfer-Encoding : chunked") {
+                0
+            } else {
+                1
+            }
+        })
+        .truncate(eager.len());
+    assert_eq!(iter.len(), eager.len());
+
+    let reordered: Vec<Payload> = iter.collect();
+    assert!(reordered[0].request.contains("Transfer-Encoding : chunked"));
+    assert_ne!(reordered[0].index, 0);
+    for payload in &reordered {
+        assert_eq!(payload.request, eager[payload.index]);
+    }
+    // Ties keep their original order.
+    let rest: Vec<usize> = reordered
+        .iter()
+        .filter(|p| !p.request.contains("Transfer-Encoding : chunked"))
+        .map(|p| p.index)
+        .collect();
+    assert!(rest.windows(2).all(|w| w[0] < w[1]));
+}
+
+#[test]
+fn test_generated_variations_are_unique() {
+    let te_headers = get_te_header_variations();
+    let unique: std::collections::HashSet<_> = te_headers.iter().collect();
+    assert_eq!(unique.len(), te_headers.len());
+
+    let te_te = get_te_te_payloads("/", "example.com", "POST", &[], &[]);
+    let unique: std::collections::HashSet<_> = te_te.iter().collect();
+    assert_eq!(unique.len(), te_te.len());
+}
+
+#[test]
+fn test_payload_families_cover_payload_checks() {
+    let names: Vec<&str> = PAYLOAD_FAMILIES.iter().map(|f| f.check).collect();
+    let expected: Vec<&str> = smugglex::cli::KNOWN_CHECK_NAMES
+        .into_iter()
+        .filter(|n| !matches!(*n, "h2-downgrade" | "early-data"))
+        .collect();
+    assert_eq!(names, expected);
+    assert!(payload_family("te-cl").is_some());
+    assert!(payload_family("h2-downgrade").is_none());
+    assert!(payload_family("early-data").is_none());
+}
+
+#[test]
+fn test_payload_catalog_entry() {
+    let entry = payload_family("te-cl").unwrap().catalog_entry();
+    let payloads = get_te_cl_payloads("/", "example.com", "POST", &[], &[]);
+    assert_eq!(entry.check, "te-cl");
+    assert_eq!(entry.count, payloads.len());
+    assert_eq!(entry.example, payloads[0]);
+    assert!(!entry.description.is_empty());
+
+    let head = payload_family("head").unwrap().catalog_entry();
+    assert!(
+        head.example
+            .starts_with("HEAD / HTTP/1.1\r\nHost: example.com\r\n")
+    );
+}
+
+#[test]
+fn test_payload_families_name_every_payload() {
+    for family in PAYLOAD_FAMILIES.iter() {
+        let payloads: Vec<Payload> =
+            (family.generate)("/", "example.com", "POST", &[], &[]).collect();
+        let names: Vec<&str> = payloads
+            .iter()
+            .map(|p| p.name.as_deref().expect("payload is named"))
+            .collect();
+        let unique: std::collections::HashSet<_> = names.iter().collect();
+        assert_eq!(unique.len(), names.len(), "{} names repeat", family.check);
+    }
+
+    let te_cl: Vec<Payload> = te_cl_payloads("/", "example.com", "POST", &[], &[])
+        .map_requests(|r| r.replace("example.com", "example.org"))
+        .collect();
+    assert_eq!(te_cl[0].name.as_deref(), Some("vanilla"));
+    assert!(
+        te_cl
+            .iter()
+            .any(|p| p.name.as_deref() == Some("linefold-tab"))
+    );
+}
+
+#[test]
+fn test_quick_payloads_exist_in_their_family() {
+    for family in PAYLOAD_FAMILIES.iter() {
+        assert!(
+            !family.quick.is_empty(),
+            "{} has no quick payloads",
+            family.check
+        );
+        assert!(
+            family.quick.len() <= 10,
+            "{} quick set too large",
+            family.check
+        );
+        let selected: Vec<Payload> = (family.generate)("/", "example.com", "POST", &[], &[])
+            .select_named(family.quick)
+            .collect();
+        let names: Vec<&str> = selected.iter().filter_map(|p| p.name.as_deref()).collect();
+        assert_eq!(names, family.quick, "{} quick names drifted", family.check);
+    }
+}
+
+#[test]
+fn test_payload_iter_select_named_keeps_indices() {
+    let eager = get_te_cl_payloads("/", "example.com", "POST", &[], &[]);
+    let selected: Vec<Payload> = te_cl_payloads("/", "example.com", "POST", &[], &[])
+        .select_named(&["linefold-tab", "no-such-payload", "vanilla"])
+        .collect();
+    assert_eq!(selected.len(), 2);
+    assert_eq!(selected[0].name.as_deref(), Some("linefold-tab"));
+    assert_eq!(selected[1].index, 0);
+    for payload in &selected {
+        assert_eq!(payload.request, eager[payload.index]);
+    }
+}
+
+#[test]
+fn test_te_header_extended_variations() {
+    let default = get_te_header_variations();
+    let extended = te_header_extended_variations();
+    assert!(extended.len() >= 200, "got {}", extended.len());
+    assert!(extended.iter().all(|(_, header)| !default.contains(header)));
+    let names: std::collections::HashSet<_> = extended.iter().map(|(name, _)| name).collect();
+    assert_eq!(names.len(), extended.len());
+    // Control-character matrix, prefix sweep and exotic encodings
+    assert!(extended.contains(&(
+        "ctl-01-mid-value".to_string(),
+        "Transfer-Encoding: chun\x01ked".to_string()
+    )));
+    assert!(extended.contains(&(
+        "byte-2c-prefix".to_string(),
+        ",Transfer-Encoding: chunked".to_string()
+    )));
+    assert!(
+        extended
+            .iter()
+            .any(|(name, _)| name == "mime-quoted-printable")
+    );
+    // The U+FFFD stand-ins for extended ASCII left the default set
+    assert!(default.iter().all(|header| !header.contains('\u{FFFD}')));
+    assert!(extended.iter().any(|(name, _)| name == "high-byte-hyphen"));
+}
+
+#[test]
+fn test_payload_iter_append_numbers_after_len() {
+    let default = get_te_te_payloads("/", "example.com", "POST", &[], &[]);
+    let default_len = default.len();
+    let appended: Vec<Payload> = te_te_payloads("/", "example.com", "POST", &[], &[])
+        .append(te_te_extended_payloads(
+            "/",
+            "example.com",
+            "POST",
+            &[],
+            &[],
+        ))
+        .collect();
+    assert!(appended.len() > default_len);
+    for (position, payload) in appended.iter().enumerate() {
+        assert_eq!(payload.index, position);
+    }
+    assert_eq!(appended[0].request, default[0]);
+    let tail = &appended[default_len];
+    assert!(tail.request.contains("Transfer-Encoding: chunked\r\n"));
+    let names: std::collections::HashSet<_> = appended.iter().map(|p| p.name.clone()).collect();
+    assert_eq!(names.len(), appended.len(), "te-te names repeat");
+
+    let families_with_tail: Vec<&str> = PAYLOAD_FAMILIES
+        .iter()
+        .filter(|f| f.extended.is_some())
+        .map(|f| f.check)
+        .collect();
+    assert_eq!(families_with_tail, ["cl-te", "te-cl", "te-te"]);
+}
+
+#[test]
+fn test_family_payloads_carry_technique() {
+    for family in PAYLOAD_FAMILIES.iter() {
+        let payloads = (family.generate)("/", "example.com", "POST", &[], &[]).in_family(family);
+        for payload in payloads {
+            let technique = payload.technique.expect("family payload has a technique");
+            assert_eq!(technique.family, family.check);
+            assert_eq!(
+                technique.id,
+                format!("{}/{}", family.check, payload.name.unwrap())
+            );
+            assert_eq!(technique.description, family.description);
+            assert!(technique.reference.starts_with("https://"));
+        }
+    }
+
+    let family = payload_family("cl-te").unwrap();
+    let unnamed = PayloadIter::from(vec!["A".to_string()])
+        .in_family(family)
+        .next()
+        .unwrap();
+    assert_eq!(unnamed.technique.unwrap().id, "cl-te/0");
+    let plain = cl_te_payloads("/", "example.com", "POST", &[], &[])
+        .next()
+        .unwrap();
+    assert!(plain.technique.is_none());
+}
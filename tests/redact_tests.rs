@@ -0,0 +1,116 @@
+//! Tests for the redact module
+//!
+//! Masks sensitive header values in requests, diffs and check results, and
+//! restores them for a replay.
+
+use smugglex::model::{CheckResult, FuzzAnomaly};
+use smugglex::redact::{REDACTED, redact_with, restore_redacted};
+
+fn names(list: &[&str]) -> Vec<String> {
+    list.iter().map(|n| n.to_ascii_lowercase()).collect()
+}
+
+#[test]
+fn test_redact_masks_configured_headers() {
+    let request = "POST / HTTP/1.1\r\nHost: example.com\r\nauthorization: Bearer abc\r\n\
+                   Cookie:session=1; theme=dark\r\nX-Other: keep\r\n\r\n0\r\n\r\n\
+                   GET /admin HTTP/1.1\r\nCookie: smuggled=1\r\n";
+    let redacted = redact_with(request, &names(&["Authorization", "Cookie"]));
+    assert_eq!(
+        redacted,
+        format!(
+            "POST / HTTP/1.1\r\nHost: example.com\r\nauthorization: {r}\r\n\
+             Cookie:{r}\r\nX-Other: keep\r\n\r\n0\r\n\r\n\
+             GET /admin HTTP/1.1\r\nCookie: {r}\r\n",
+            r = REDACTED
+        )
+    );
+    // Names are matched whole, not as prefixes.
+    assert_eq!(
+        redact_with("Cookie-Policy: strict\r\n", &names(&["Cookie"])),
+        "Cookie-Policy: strict\r\n"
+    );
+    assert_eq!(redact_with(request, &[]), request);
+}
+
+#[test]
+fn test_redact_diff_lines_keep_escaped_line_ending() {
+    let cookie = names(&["Cookie"]);
+    assert_eq!(
+        redact_with("+ Cookie: a=1\\r\\n", &cookie),
+        format!("+ Cookie: {}\\r\\n", REDACTED)
+    );
+    assert_eq!(
+        redact_with("- X-Junk: 1\\r\\n", &cookie),
+        "- X-Junk: 1\\r\\n"
+    );
+}
+
+#[test]
+fn test_restore_redacted_from_headers() {
+    let saved = format!(
+        "POST / HTTP/1.1\r\nAuthorization: {r}\r\nCookie: {r}\r\n\r\n0",
+        r = REDACTED
+    );
+    let (restored, missing) = restore_redacted(&saved, &["authorization: Bearer abc".to_string()]);
+    assert_eq!(
+        restored,
+        format!(
+            "POST / HTTP/1.1\r\nAuthorization: Bearer abc\r\nCookie: {}\r\n\r\n0",
+            REDACTED
+        )
+    );
+    assert_eq!(missing, vec!["Cookie".to_string()]);
+}
+
+#[test]
+fn test_redact_check_masks_payloads_and_anomalies() {
+    smugglex::redact::set_redacted_headers(&["Cookie".to_string()]);
+    let mut check = CheckResult {
+        check_type: "cl-te".to_string(),
+        vulnerable: true,
+        payload_index: Some(0),
+        payload_name: None,
+        technique: None,
+        normal_status: "HTTP/1.1 200 OK".to_string(),
+        attack_status: None,
+        normal_duration_ms: 10,
+        attack_duration_ms: None,
+        normal_connect_ms: None,
+        attack_connect_ms: None,
+        timestamp: "2024-01-01T00:00:00Z".to_string(),
+        payload: Some("POST / HTTP/1.1\r\nCookie: s=1\r\n\r\n".to_string()),
+        confidence: None,
+        detection_signals: Vec::new(),
+        diagnostics: Vec::new(),
+        payloads_sent: 0,
+        request_errors: Default::default(),
+        h2_observations: Vec::new(),
+        fuzz_anomalies: vec![FuzzAnomaly {
+            payload_index: 1,
+            payload_name: None,
+            anomaly: "status 504".to_string(),
+            payload: "POST / HTTP/1.1\r\nCookie: s=1\r\n\r\n".to_string(),
+            seed_diff: vec!["+ Cookie: s=1\\r\\n".to_string()],
+            reproduction_file: None,
+        }],
+        unsolicited_bytes: Some("GET / HTTP/1.1\r\nCookie: s=1\r\n\r\n".to_string()),
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
+    };
+    smugglex::redact::redact_check(&mut check);
+    smugglex::redact::set_redacted_headers(&[]);
+    let masked = format!("POST / HTTP/1.1\r\nCookie: {}\r\n\r\n", REDACTED);
+    assert_eq!(check.payload.as_deref(), Some(masked.as_str()));
+    assert_eq!(check.fuzz_anomalies[0].payload, masked);
+    assert_eq!(
+        check.fuzz_anomalies[0].seed_diff,
+        vec![format!("+ Cookie: {}\\r\\n", REDACTED)]
+    );
+    assert_eq!(
+        check.unsolicited_bytes,
+        Some(format!("GET / HTTP/1.1\r\nCookie: {}\r\n\r\n", REDACTED))
+    );
+}
@@ -0,0 +1,330 @@
+//! Tests for the report module
+//!
+//! Renders saved results in every offline report format and as JUnit XML.
+
+use smugglex::cli::ReportFormat;
+use smugglex::model::{
+    CertificateInfo, CheckResult, Confidence, ScanResults, TargetInfo, Technique,
+};
+use smugglex::report::{render_csv, render_html, render_junit, render_markdown, render_report};
+
+fn check(check_type: &str, vulnerable: bool) -> CheckResult {
+    CheckResult {
+        check_type: check_type.to_string(),
+        vulnerable,
+        payload_index: vulnerable.then_some(2),
+        payload_name: vulnerable.then(|| "vanilla".to_string()),
+        technique: vulnerable.then(|| Technique {
+            family: check_type.to_string(),
+            id: format!("{}/vanilla", check_type),
+            description: "Body framed by <Content-Length>".to_string(),
+            reference: "https://example.org/cl-te".to_string(),
+        }),
+        normal_status: "HTTP/1.1 200 OK".to_string(),
+        attack_status: vulnerable.then(|| "HTTP/1.1 504 Gateway Timeout".to_string()),
+        normal_duration_ms: 40,
+        attack_duration_ms: vulnerable.then_some(5020),
+        normal_connect_ms: None,
+        attack_connect_ms: None,
+        timestamp: "2024-01-01T00:00:00Z".to_string(),
+        payload: vulnerable
+            .then(|| "POST /<x> HTTP/1.1\r\nHost: example.com\r\n\r\n0\r\n\r\nG".to_string()),
+        confidence: vulnerable.then_some(Confidence::High),
+        detection_signals: if vulnerable {
+            vec!["status_504".to_string(), "timing_anomaly:3.5x".to_string()]
+        } else {
+            Vec::new()
+        },
+        diagnostics: Vec::new(),
+        payloads_sent: 0,
+        request_errors: Default::default(),
+        h2_observations: Vec::new(),
+        fuzz_anomalies: Vec::new(),
+        unsolicited_bytes: None,
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
+    }
+}
+
+fn sample_results() -> Vec<ScanResults> {
+    vec![
+        ScanResults {
+            target: "https://example.com/".to_string(),
+            address: Some("203.0.113.10".to_string()),
+            vhost: Some("internal.example.com".to_string()),
+            ip: None,
+            port: None,
+            scheme: None,
+            method: "POST".to_string(),
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            fingerprint: None,
+            checks: vec![check("cl-te", true), check("te-cl", false)],
+            exploits: None,
+            proxy_chain: Vec::new(),
+            unreachable: None,
+            throttle: None,
+            target_info: Some(TargetInfo {
+                server: vec!["nginx/1.24.0".to_string()],
+                powered_by: vec!["PHP/8.2.12".to_string()],
+                error_page_versions: vec!["nginx/1.24.0".to_string()],
+                certificate: Some(CertificateInfo {
+                    subject: "CN=example.com".to_string(),
+                    issuer: "CN=Example CA, O=Example".to_string(),
+                    serial: "0a1b".to_string(),
+                    not_before: "2024-01-01T00:00:00Z".to_string(),
+                    not_after: "2025-01-01T00:00:00Z".to_string(),
+                    san: vec!["example.com".to_string(), "www.example.com".to_string()],
+                }),
+            }),
+            error: None,
+            error_info: None,
+            duration_ms: None,
+            requests_sent: None,
+            summary: None,
+        },
+        ScanResults {
+            target: "https://broken.example/".to_string(),
+            address: None,
+            vhost: None,
+            ip: None,
+            port: None,
+            scheme: None,
+            method: "POST".to_string(),
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            fingerprint: None,
+            checks: Vec::new(),
+            exploits: None,
+            proxy_chain: Vec::new(),
+            unreachable: None,
+            throttle: None,
+            target_info: None,
+            error: Some("URL parse error, \"bad\"".to_string()),
+            error_info: None,
+            duration_ms: None,
+            requests_sent: None,
+            summary: None,
+        },
+    ]
+}
+
+#[test]
+fn test_render_markdown() {
+    let md = render_markdown(&sample_results(), "0.0.0");
+    assert!(md.starts_with("# smugglex report"));
+    assert!(md.contains("2 target(s), 1 vulnerable check(s)"));
+    assert!(md.contains("## https://example.com/"));
+    assert!(md.contains("- **Address:** 203.0.113.10"));
+    assert!(md.contains("- **Virtual host:** internal.example.com"));
+    assert!(md.contains("- **Server software:** nginx/1.24.0, PHP/8.2.12\n"));
+    assert!(md.contains(
+        "- **Certificate:** CN=example.com (issuer CN=Example CA, O=Example, serial 0a1b, valid 2024-01-01T00:00:00Z to 2025-01-01T00:00:00Z), SAN example.com, www.example.com"
+    ));
+    assert!(md.contains(
+        "| cl-te | VULNERABLE | high | HTTP/1.1 200 OK | HTTP/1.1 504 Gateway Timeout | status_504, timing_anomaly:3.5x |"
+    ));
+    assert!(md.contains("| te-cl | ok |"));
+    assert!(md.contains("### cl-te payload vanilla (#2)"));
+    assert!(md.contains(
+        "Technique `cl-te/vanilla`: Body framed by <Content-Length> ([reference](https://example.org/cl-te))"
+    ));
+    assert!(md.contains("```http\nPOST /<x> HTTP/1.1\nHost: example.com\n"));
+    assert!(md.contains("- **Error:** URL parse error"));
+}
+
+#[test]
+fn test_render_html_escapes_content() {
+    let html = render_html(&sample_results(), "0.0.0");
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<td class=\"vulnerable\">VULNERABLE</td>"));
+    assert!(html.contains("<pre>POST /&lt;x&gt; HTTP/1.1\nHost: example.com"));
+    assert!(!html.contains("/<x>"));
+    assert!(html.contains(
+        "<p>Technique <code>cl-te/vanilla</code>: Body framed by &lt;Content-Length&gt; (<a href=\"https://example.org/cl-te\">reference</a>)</p>"
+    ));
+    assert!(html.contains("URL parse error, &quot;bad&quot;"));
+    assert!(html.trim_end().ends_with("</html>"));
+}
+
+#[test]
+fn test_render_csv_rows_and_quoting() {
+    let csv = render_csv(&sample_results());
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines[0].starts_with("target,address,vhost,method,check_type,vulnerable"));
+    assert_eq!(
+        lines[1],
+        "https://example.com/,203.0.113.10,internal.example.com,POST,cl-te,true,high,2,vanilla,HTTP/1.1 200 OK,\
+         HTTP/1.1 504 Gateway Timeout,40,5020,status_504;timing_anomaly:3.5x,"
+    );
+    assert_eq!(
+        lines[2],
+        "https://example.com/,203.0.113.10,internal.example.com,POST,te-cl,false,,,,HTTP/1.1 200 OK,,40,,,"
+    );
+    assert_eq!(
+        lines[3],
+        "https://broken.example/,,,POST,,,,,,,,,,,\"URL parse error, \"\"bad\"\"\""
+    );
+}
+
+#[test]
+fn test_render_junit() {
+    let mut results = sample_results();
+    results[0].duration_ms = Some(12480);
+    results[0].checks[0].payload = Some("GET / HTTP/1.1\r\n\u{0}X: <y>\r\n\r\n".to_string());
+    let xml = render_junit(&results, "0.0.0");
+    assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"));
+    assert!(
+        xml.contains(
+            "<testsuites name=\"smugglex 0.0.0\" tests=\"3\" failures=\"1\" errors=\"1\">"
+        )
+    );
+    assert!(xml.contains(
+        "<testsuite name=\"https://example.com/\" tests=\"2\" failures=\"1\" errors=\"0\" timestamp=\"2024-01-01T00:00:00Z\" time=\"12.480\">"
+    ));
+    assert!(xml.contains(
+        "<failure message=\"HTTP request smuggling via vanilla (#2), confidence high\" type=\"cl-te\">"
+    ));
+    assert!(xml.contains("Technique: cl-te/vanilla (Body framed by &lt;Content-Length&gt;)"));
+    assert!(xml.contains("Signals: status_504, timing_anomaly:3.5x"));
+    assert!(xml.contains("X: &lt;y&gt;"));
+    assert!(!xml.contains('\u{0}'));
+    assert!(xml.contains("<testcase classname=\"https://example.com/\" name=\"te-cl\"/>"));
+    assert!(xml.contains(
+        "<testcase classname=\"https://broken.example/\" name=\"scan\">\n      <error message=\"URL parse error, &quot;bad&quot;\"/>"
+    ));
+    assert!(xml.trim_end().ends_with("</testsuites>"));
+}
+
+#[test]
+fn test_render_report_defectdojo() {
+    let import: serde_json::Value = serde_json::from_str(&render_report(
+        &sample_results(),
+        ReportFormat::Defectdojo,
+        "0.0.0",
+    ))
+    .unwrap();
+    let findings = import["findings"].as_array().unwrap();
+    assert_eq!(findings.len(), 1);
+    let finding = &findings[0];
+    assert_eq!(
+        finding["title"],
+        "HTTP request smuggling (cl-te, vanilla) on https://example.com/"
+    );
+    assert_eq!(finding["severity"], "High");
+    assert_eq!(finding["date"], "2024-01-01");
+    assert_eq!(finding["cwe"], 444);
+    assert_eq!(finding["vuln_id_from_tool"], "cl-te");
+    assert_eq!(
+        finding["unique_id_from_tool"],
+        "https://example.com/|cl-te|vanilla"
+    );
+    assert_eq!(
+        finding["endpoints"],
+        serde_json::json!([{ "protocol": "https", "host": "example.com", "port": 443, "path": "" }])
+    );
+    let description = finding["description"].as_str().unwrap();
+    assert!(description.contains("**Technique:** cl-te/vanilla: Body framed by <Content-Length>"));
+    assert!(description.contains("**Signals:** status_504, timing_anomaly:3.5x"));
+    assert!(description.contains("```http\nPOST /<x> HTTP/1.1\r\nHost: example.com"));
+    assert!(
+        finding["references"]
+            .as_str()
+            .unwrap()
+            .starts_with("https://example.org/cl-te\n")
+    );
+}
+
+#[test]
+fn test_render_report_zap() {
+    let mut results = sample_results();
+    results[1] = results[0].clone();
+    results[1].target = "https://example.com/other".to_string();
+    results[1].checks[0].confidence = Some(Confidence::Low);
+    let zap: serde_json::Value =
+        serde_json::from_str(&render_report(&results, ReportFormat::ZapJson, "0.0.0")).unwrap();
+    assert_eq!(zap["@programName"], "smugglex");
+    let sites = zap["site"].as_array().unwrap();
+    assert_eq!(sites.len(), 1, "targets on one origin share a site");
+    assert_eq!(sites[0]["@name"], "https://example.com");
+    assert_eq!(sites[0]["@port"], "443");
+    assert_eq!(sites[0]["@ssl"], "true");
+    let alerts = sites[0]["alerts"].as_array().unwrap();
+    assert_eq!(alerts.len(), 1, "findings of one check type share an alert");
+    assert_eq!(alerts[0]["pluginid"], "smugglex-cl-te");
+    assert_eq!(alerts[0]["count"], "2");
+    assert_eq!(alerts[0]["instances"].as_array().unwrap().len(), 2);
+    assert_eq!(
+        alerts[0]["instances"][1]["uri"],
+        "https://example.com/other"
+    );
+    assert_eq!(alerts[0]["riskcode"], "3");
+    assert_eq!(alerts[0]["confidence"], "3");
+    assert_eq!(alerts[0]["riskdesc"], "High (High)");
+    assert!(
+        alerts[0]["desc"]
+            .as_str()
+            .unwrap()
+            .contains("Body framed by &lt;Content-Length&gt;")
+    );
+    assert_eq!(alerts[0]["cweid"], "444");
+    assert_eq!(alerts[0]["wascid"], "26");
+    assert_eq!(alerts[0]["instances"][0]["uri"], "https://example.com/");
+    assert_eq!(alerts[0]["instances"][0]["attack"], "vanilla");
+    assert!(
+        alerts[0]["instances"][0]["otherinfo"]
+            .as_str()
+            .unwrap()
+            .contains("Signals: status_504, timing_anomaly:3.5x")
+    );
+    // `otherinfo` is HTML in ZAP reports, so the raw request is escaped.
+    assert!(
+        alerts[0]["instances"][0]["otherinfo"]
+            .as_str()
+            .unwrap()
+            .contains("POST /&lt;x&gt; HTTP/1.1")
+    );
+
+    let xml = render_report(&results, ReportFormat::ZapXml, "0.0.0");
+    assert!(xml.contains("<OWASPZAPReport programName=\"smugglex\" version=\"0.0.0\""));
+    assert!(xml.contains(
+        "<site name=\"https://example.com\" host=\"example.com\" port=\"443\" ssl=\"true\">"
+    ));
+    assert!(xml.contains(
+        "<alertitem>\n<pluginid>smugglex-cl-te</pluginid>\n<alertRef>smugglex-cl-te</alertRef>"
+    ));
+    assert!(xml.contains(
+        "<instances>\n<instance>\n<uri>https://example.com/</uri>\n<method>POST</method>"
+    ));
+    assert!(xml.contains("POST /&amp;lt;x&amp;gt; HTTP/1.1"));
+    assert!(xml.trim_end().ends_with("</OWASPZAPReport>"));
+}
+
+#[test]
+fn test_render_report_sarif() {
+    let sarif: serde_json::Value = serde_json::from_str(&render_report(
+        &sample_results(),
+        ReportFormat::Sarif,
+        "0.0.0",
+    ))
+    .unwrap();
+    assert_eq!(sarif["version"], "2.1.0");
+    assert_eq!(sarif["runs"][0]["results"].as_array().unwrap().len(), 1);
+    assert_eq!(
+        sarif["runs"][0]["results"][0]["properties"]["payload_name"],
+        "vanilla"
+    );
+    assert_eq!(
+        sarif["runs"][0]["results"][0]["properties"]["technique"]["id"],
+        "cl-te/vanilla"
+    );
+    assert_eq!(
+        sarif["runs"][0]["results"][0]["message"]["text"],
+        "HTTP request smuggling (cl-te, vanilla) detected on https://example.com/"
+    );
+    assert_eq!(
+        sarif["runs"][0]["results"][0]["partialFingerprints"]["smugglexFinding/v1"],
+        "https://example.com/|cl-te|vanilla"
+    );
+}
@@ -0,0 +1,174 @@
+//! Tests for the reproduce module
+//!
+//! This module contains tests for:
+//! - Finding ids and `--id` selection
+//! - printf escaping of the payload bytes
+//! - ncat / openssl one-liners, pinned addresses and redacted headers
+
+use smugglex::cli::ReplayTool;
+use smugglex::model::{CheckResult, Confidence};
+use smugglex::redact::REDACTED;
+use smugglex::reproduce::{build_reproduction, finding_id, printf_literal, select_finding};
+use smugglex::retest::SavedFinding;
+
+fn finding(
+    target: &str,
+    check_type: &str,
+    name: Option<&str>,
+    payload: Option<&str>,
+) -> SavedFinding {
+    SavedFinding {
+        target: target.to_string(),
+        address: None,
+        check: CheckResult {
+            check_type: check_type.to_string(),
+            vulnerable: true,
+            payload_index: Some(12),
+            payload_name: name.map(str::to_string),
+            technique: None,
+            normal_status: "HTTP/1.1 200 OK".to_string(),
+            attack_status: None,
+            normal_duration_ms: 10,
+            attack_duration_ms: None,
+            normal_connect_ms: None,
+            attack_connect_ms: None,
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            payload: payload.map(str::to_string),
+            confidence: Some(Confidence::High),
+            detection_signals: Vec::new(),
+            diagnostics: Vec::new(),
+            payloads_sent: 0,
+            request_errors: Default::default(),
+            h2_observations: Vec::new(),
+            fuzz_anomalies: Vec::new(),
+            unsolicited_bytes: None,
+            attack_phases: None,
+            cross_check: None,
+            evidence: None,
+            consistency: None,
+        },
+    }
+}
+
+const PAYLOAD: &str =
+    "POST / HTTP/1.1\r\nHost: example.com\r\nTransfer-Encoding:\tchunked\r\n\r\n0\r\n\r\nG";
+
+#[test]
+fn test_finding_ids_and_selection() {
+    let findings = vec![
+        finding(
+            "https://a.example/",
+            "te-cl",
+            Some("linefold-tab"),
+            Some(PAYLOAD),
+        ),
+        finding(
+            "https://b.example/",
+            "te-cl",
+            Some("linefold-tab"),
+            Some(PAYLOAD),
+        ),
+        finding("https://a.example/", "cl-te", None, Some(PAYLOAD)),
+        finding(
+            "https://a.example/",
+            "h2-downgrade",
+            Some("te-header"),
+            None,
+        ),
+    ];
+    assert_eq!(finding_id(&findings[0]), "te-cl/linefold-tab");
+    assert_eq!(finding_id(&findings[2]), "cl-te/#12");
+
+    assert_eq!(select_finding(&findings, "2").unwrap(), 1);
+    assert_eq!(select_finding(&findings, " cl-te/#12 ").unwrap(), 2);
+    assert!(select_finding(&findings, "0").is_err());
+    assert!(select_finding(&findings, "5").is_err());
+    assert!(select_finding(&findings, "te-te/vanilla").is_err());
+    let ambiguous = select_finding(&findings, "te-cl/linefold-tab").unwrap_err();
+    assert!(ambiguous.to_string().contains("#1, #2"), "{}", ambiguous);
+}
+
+#[test]
+fn test_printf_literal_escapes() {
+    assert_eq!(
+        printf_literal(b"a%b\\c'd\r\n\te\x00\x0b"),
+        "'a%%b\\\\c'\\''d\\r\\n\\te\\000\\013'"
+    );
+    assert_eq!(printf_literal("\u{FFFD}".as_bytes()), "'\\357\\277\\275'");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_printf_literal_round_trips_through_sh() {
+    let bytes = b"GET /%41 HTTP/1.1\r\nX: 'q' \\n \x01\x7f\xc3\xa9\r\n\r\n";
+    let output = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(format!("printf {}", printf_literal(bytes)))
+        .output()
+        .unwrap();
+    assert_eq!(output.stdout, bytes);
+}
+
+#[test]
+fn test_build_reproduction_commands() {
+    let https = finding(
+        "https://example.com/",
+        "te-cl",
+        Some("linefold-tab"),
+        Some(PAYLOAD),
+    );
+    let ncat = build_reproduction(&https, ReplayTool::Ncat, &[]).unwrap();
+    assert_eq!(ncat.id, "te-cl/linefold-tab");
+    assert_eq!(ncat.check_type, "te-cl");
+    assert!(
+        ncat.command
+            .starts_with("printf 'POST / HTTP/1.1\\r\\nHost: example.com\\r\\n")
+    );
+    assert!(ncat.command.ends_with("' | ncat --ssl example.com 443"));
+    assert!(ncat.redacted.is_empty());
+
+    let openssl = build_reproduction(&https, ReplayTool::Openssl, &[]).unwrap();
+    assert!(
+        openssl.command.ends_with(
+            " | openssl s_client -quiet -connect example.com:443 -servername example.com"
+        )
+    );
+
+    let mut pinned = https.clone();
+    pinned.address = Some("203.0.113.10".to_string());
+    let command = build_reproduction(&pinned, ReplayTool::Ncat, &[])
+        .unwrap()
+        .command;
+    assert!(command.ends_with(" | ncat --ssl --ssl-servername example.com 203.0.113.10 443"));
+
+    let plain = finding("http://example.com:8080/", "cl-te", None, Some(PAYLOAD));
+    let command = build_reproduction(&plain, ReplayTool::Openssl, &[])
+        .unwrap()
+        .command;
+    assert!(command.ends_with(" | ncat example.com 8080"));
+
+    let h2 = finding("https://example.com/", "h2-downgrade", None, None);
+    assert!(build_reproduction(&h2, ReplayTool::Ncat, &[]).is_err());
+}
+
+#[test]
+fn test_build_reproduction_restores_redacted_headers() {
+    let payload = format!(
+        "POST / HTTP/1.1\r\nHost: example.com\r\nCookie: {}\r\nAuthorization: {}\r\n\r\n",
+        REDACTED, REDACTED
+    );
+    let saved = finding(
+        "https://example.com/",
+        "cl-te",
+        Some("vanilla"),
+        Some(&payload),
+    );
+    let reproduction = build_reproduction(
+        &saved,
+        ReplayTool::Ncat,
+        &["Cookie: session=abc".to_string()],
+    )
+    .unwrap();
+    assert!(reproduction.command.contains("Cookie: session=abc\\r\\n"));
+    assert_eq!(reproduction.redacted, ["Authorization"]);
+}
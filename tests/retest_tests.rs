@@ -0,0 +1,186 @@
+//! Tests for the retest module
+//!
+//! Loads saved findings from both results file layouts and checks the report
+//! counts; replays that would touch the network are not exercised.
+
+use smugglex::model::{CheckResult, Confidence, RetestResult, ScanResults};
+use smugglex::output::build_batch_results;
+use smugglex::retest::{
+    RetestSettings, SavedFinding, build_retest_report, load_saved_findings, retest_finding,
+    saved_findings,
+};
+use std::fs;
+
+fn check(check_type: &str, vulnerable: bool) -> CheckResult {
+    CheckResult {
+        check_type: check_type.to_string(),
+        vulnerable,
+        payload_index: vulnerable.then_some(3),
+        payload_name: None,
+        technique: None,
+        normal_status: "HTTP/1.1 200 OK".to_string(),
+        attack_status: None,
+        normal_duration_ms: 10,
+        attack_duration_ms: None,
+        normal_connect_ms: None,
+        attack_connect_ms: None,
+        timestamp: "2024-01-01T00:00:00Z".to_string(),
+        payload: vulnerable.then(|| "POST / HTTP/1.1\r\nHost: example.com\r\n\r\n".to_string()),
+        confidence: vulnerable.then_some(Confidence::High),
+        detection_signals: Vec::new(),
+        diagnostics: Vec::new(),
+        payloads_sent: 0,
+        request_errors: Default::default(),
+        h2_observations: Vec::new(),
+        fuzz_anomalies: Vec::new(),
+        unsolicited_bytes: None,
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
+    }
+}
+
+fn scan(target: &str, address: Option<&str>, checks: Vec<CheckResult>) -> ScanResults {
+    ScanResults {
+        target: target.to_string(),
+        address: address.map(|a| a.to_string()),
+        vhost: None,
+        ip: None,
+        port: None,
+        scheme: None,
+        method: "POST".to_string(),
+        timestamp: "2024-01-01T00:00:00Z".to_string(),
+        fingerprint: None,
+        checks,
+        exploits: None,
+        proxy_chain: Vec::new(),
+        unreachable: None,
+        throttle: None,
+        target_info: None,
+        error: None,
+        error_info: None,
+        duration_ms: None,
+        requests_sent: None,
+        summary: None,
+    }
+}
+
+fn result(reproduced: bool, error: Option<&str>) -> RetestResult {
+    RetestResult {
+        target: "https://example.com/".to_string(),
+        address: None,
+        check_type: "cl-te".to_string(),
+        payload_index: Some(3),
+        payload_name: None,
+        original_confidence: Some(Confidence::High),
+        reproduced,
+        check: None,
+        error: error.map(|e| e.to_string()),
+    }
+}
+
+#[test]
+fn test_saved_findings_keeps_only_vulnerable_checks() {
+    let findings = saved_findings(vec![
+        scan(
+            "https://a.example/",
+            Some("203.0.113.10"),
+            vec![check("cl-te", true), check("te-cl", false)],
+        ),
+        scan("https://b.example/", None, vec![check("te-te", false)]),
+        scan("https://c.example/", None, vec![check("h2c", true)]),
+    ]);
+    let summary: Vec<(&str, Option<&str>, &str)> = findings
+        .iter()
+        .map(|f| {
+            (
+                f.target.as_str(),
+                f.address.as_deref(),
+                f.check.check_type.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("https://a.example/", Some("203.0.113.10"), "cl-te"),
+            ("https://c.example/", None, "h2c"),
+        ]
+    );
+}
+
+#[test]
+fn test_load_saved_findings_reads_batch_and_single_results() {
+    let temp_dir = std::env::temp_dir();
+
+    let batch_file = temp_dir.join("smugglex_test_retest_batch.json");
+    let batch = build_batch_results(
+        vec![
+            scan("https://a.example/", None, vec![check("cl-te", true)]),
+            scan("https://b.example/", None, vec![check("te-cl", true)]),
+        ],
+        Some("0.0.0"),
+    );
+    fs::write(&batch_file, serde_json::to_string(&batch).unwrap()).unwrap();
+    assert_eq!(load_saved_findings(&batch_file).unwrap().len(), 2);
+
+    let single_file = temp_dir.join("smugglex_test_retest_single.json");
+    let single = scan("https://a.example/", None, vec![check("cl-te", true)]);
+    fs::write(&single_file, serde_json::to_string(&single).unwrap()).unwrap();
+    let findings = load_saved_findings(&single_file).unwrap();
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].check.payload_index, Some(3));
+
+    fs::write(&single_file, "not json").unwrap();
+    assert!(load_saved_findings(&single_file).is_err());
+
+    // Cleanup
+    fs::remove_file(batch_file).ok();
+    fs::remove_file(single_file).ok();
+}
+
+#[tokio::test]
+async fn test_retest_finding_without_payload_is_an_error() {
+    let mut saved = check("cl-te", true);
+    saved.payload = None;
+    let finding = SavedFinding {
+        target: "https://example.com/".to_string(),
+        address: None,
+        check: saved,
+    };
+    let settings = RetestSettings {
+        timeout: 1,
+        verbose: false,
+        delay: 0,
+        baseline_count: 1,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: &[],
+        oob_host: None,
+        headers: &[],
+    };
+    let result = retest_finding(&finding, &settings).await;
+    assert!(!result.reproduced);
+    assert!(result.check.is_none());
+    assert_eq!(result.payload_index, Some(3));
+    assert_eq!(result.original_confidence, Some(Confidence::High));
+    assert!(result.error.unwrap().contains("no saved payload"));
+}
+
+#[test]
+fn test_build_retest_report_counts() {
+    let report = build_retest_report(
+        vec![
+            result(true, None),
+            result(false, None),
+            result(false, None),
+            result(false, Some("connection refused")),
+        ],
+        Some("0.0.0"),
+    );
+    assert_eq!(report.reproduced, 1);
+    assert_eq!(report.fixed, 2);
+    assert_eq!(report.errors, 1);
+    assert_eq!(report.smugglex_version.as_deref(), Some("0.0.0"));
+}
@@ -10,14 +10,28 @@
 //! - Progress message formatting showing current check number vs total checks (e.g., [1/4])
 //! - Integration tests for run_checks_for_type function
 //! - False positive reduction: multi-baseline, confirmation retries, baseline status code context
+//! - Fuzz corpus: anomalous payloads minimized and saved
+//! - Fuzz triage: unconfirmed anomalies reported with a reproduction file
+//! - Warmup requests ahead of the baseline
+//! - Circuit breaker on error storms: recovery and abort
+//! - Bodies cut short of their Content-Length as desync evidence
+//! - Unsolicited bytes after the attack response as high-confidence evidence
+//! - Cross-technique confirmation of CL.TE findings with a TE.CL probe
 
 use chrono::Utc;
 use indicatif::ProgressBar;
-use smugglex::model::CheckResult;
+use smugglex::archive::{ArchiveRecorder, PayloadArchive};
+use smugglex::cli::ExportFormat;
+use smugglex::corpus::FuzzCorpus;
+use smugglex::error::SmugglexError;
+use smugglex::model::{CheckResult, CrossCheckVerdict, ExportedPayload, RequestErrorKind};
+use smugglex::mutator::FuzzFeedback;
+use smugglex::scanner::detection::{DEFAULT_DETECTION, DetectionMethod};
 use smugglex::scanner::{
-    CONFIRMATION_RETRIES, CheckParams, DEFAULT_BASELINE_COUNT, MIN_DELAY_MS, TIMING_MULTIPLIER,
-    run_checks_for_type,
+    CONFIRMATION_RETRIES, CheckParams, DEFAULT_BASELINE_COUNT, ERROR_STORM_THRESHOLD, MIN_DELAY_MS,
+    TIMING_MULTIPLIER, run_checks_for_type,
 };
+use smugglex::utils::{PayloadExport, sanitize_hostname};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
@@ -329,15 +343,28 @@ fn test_check_result_vulnerable_state() {
         check_type: "CL.TE".to_string(),
         vulnerable: true,
         payload_index: Some(2),
+        payload_name: None,
+        technique: None,
         normal_status: "HTTP/1.1 200 OK".to_string(),
         attack_status: Some("HTTP/1.1 504 Gateway Timeout".to_string()),
         normal_duration_ms: 150,
         attack_duration_ms: Some(5000),
+        normal_connect_ms: None,
+        attack_connect_ms: None,
         timestamp: Utc::now().to_rfc3339(),
         payload: None,
         confidence: None,
         detection_signals: Vec::new(),
         diagnostics: Vec::new(),
+        payloads_sent: 0,
+        request_errors: Default::default(),
+        h2_observations: Vec::new(),
+        fuzz_anomalies: Vec::new(),
+        unsolicited_bytes: None,
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
     };
 
     assert!(result.vulnerable);
@@ -352,15 +379,28 @@ fn test_check_result_not_vulnerable_state() {
         check_type: "TE.CL".to_string(),
         vulnerable: false,
         payload_index: None,
+        payload_name: None,
+        technique: None,
         normal_status: "HTTP/1.1 200 OK".to_string(),
         attack_status: None,
         normal_duration_ms: 150,
         attack_duration_ms: None,
+        normal_connect_ms: None,
+        attack_connect_ms: None,
         timestamp: Utc::now().to_rfc3339(),
         payload: None,
         confidence: None,
         detection_signals: Vec::new(),
         diagnostics: Vec::new(),
+        payloads_sent: 0,
+        request_errors: Default::default(),
+        h2_observations: Vec::new(),
+        fuzz_anomalies: Vec::new(),
+        unsolicited_bytes: None,
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
     };
 
     assert!(!result.vulnerable);
@@ -424,6 +464,55 @@ async fn start_normal_server() -> (String, u16, tokio::task::JoinHandle<()>) {
 
 /// Helper function to start a mock HTTP server that responds with timeout status
 /// after DEFAULT_BASELINE_COUNT normal requests. Also responds with 504 on confirmation retries.
+/// Answers GET baselines normally but resets (RST) the connection for any
+/// request carrying a body, simulating a front-end that aborts attacks.
+async fn start_resetting_server() -> (String, u16, tokio::task::JoinHandle<()>) {
+    let (host, port, handle, _) = start_counting_resetting_server("").await;
+    (host, port, handle)
+}
+
+/// [`start_resetting_server`], also counting the reset requests that contain
+/// `marker`.
+async fn start_counting_resetting_server(
+    marker: &'static str,
+) -> (
+    String,
+    u16,
+    tokio::task::JoinHandle<()>,
+    std::sync::Arc<std::sync::atomic::AtomicUsize>,
+) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let host = addr.ip().to_string();
+    let port = addr.port();
+    let resets = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let counter = std::sync::Arc::clone(&resets);
+
+    let handle = tokio::spawn(async move {
+        loop {
+            if let Ok((mut socket, _)) = listener.accept().await {
+                let counter = std::sync::Arc::clone(&counter);
+                tokio::spawn(async move {
+                    let mut buf = vec![0u8; 4096];
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    let request = String::from_utf8_lossy(&buf[..n]);
+                    if request.contains("Content-Length") {
+                        if request.contains(marker) {
+                            counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                        }
+                        let _ = socket.set_zero_linger();
+                        return;
+                    }
+                    let response = "HTTP/1.1 200 OK\r\nContent-Length: 13\r\n\r\nHello, World!";
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        }
+    });
+
+    (host, port, handle, resets)
+}
+
 async fn start_timeout_server() -> (String, u16, tokio::task::JoinHandle<()>) {
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
@@ -510,15 +599,22 @@ async fn test_run_checks_for_type_not_vulnerable() {
         host: &host,
         port,
         path: "/",
-        attack_requests,
+        attack_requests: attack_requests.into(),
         timeout: 5,
         verbose: false,
         use_tls: false,
-        export_dir: None,
+        export: None,
+        archive: None,
         current_check: 1,
         total_checks: 1,
         delay: 0,
         baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
     })
     .await;
 
@@ -532,6 +628,295 @@ async fn test_run_checks_for_type_not_vulnerable() {
     assert_eq!(check_result.confidence, None);
 }
 
+#[tokio::test]
+async fn test_run_checks_for_type_skips_duplicate_payloads() {
+    let (host, port, handle) = start_normal_server().await;
+
+    let pb = ProgressBar::new_spinner();
+    pb.finish_and_clear();
+
+    let request = format!(
+        "GET / HTTP/1.1\r\nHost: {}\r\nContent-Length: 5\r\n\r\ntest1",
+        host
+    );
+    let other = request.replace("test1", "test2");
+    let attack_requests = vec![request.clone(), other, request];
+
+    let result = run_checks_for_type(CheckParams {
+        pb: &pb,
+        check_name: "TEST",
+        host: &host,
+        port,
+        path: "/",
+        attack_requests: attack_requests.into(),
+        timeout: 5,
+        verbose: false,
+        use_tls: false,
+        export: None,
+        archive: None,
+        current_check: 1,
+        total_checks: 1,
+        delay: 0,
+        baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
+    })
+    .await;
+
+    handle.abort();
+
+    let check_result = result.unwrap();
+    assert!(!check_result.vulnerable);
+    assert_eq!(check_result.payloads_sent, 2);
+}
+
+#[tokio::test]
+async fn test_run_checks_for_type_records_request_error_kinds() {
+    let (host, port, handle) = start_resetting_server().await;
+
+    let pb = ProgressBar::new_spinner();
+    pb.finish_and_clear();
+
+    let request = format!(
+        "POST / HTTP/1.1\r\nHost: {}\r\nContent-Length: 5\r\n\r\ntest1",
+        host
+    );
+    let attack_requests = vec![request.clone(), request.replace("test1", "test2")];
+
+    let result = run_checks_for_type(CheckParams {
+        pb: &pb,
+        check_name: "TEST",
+        host: &host,
+        port,
+        path: "/",
+        attack_requests: attack_requests.into(),
+        timeout: 5,
+        verbose: false,
+        use_tls: false,
+        export: None,
+        archive: None,
+        current_check: 1,
+        total_checks: 1,
+        delay: 0,
+        baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
+    })
+    .await;
+
+    handle.abort();
+
+    let check_result = result.unwrap();
+    assert!(!check_result.vulnerable);
+    assert_eq!(
+        check_result.request_errors.get(&RequestErrorKind::Reset),
+        Some(&2)
+    );
+    let json = serde_json::to_value(&check_result).unwrap();
+    assert_eq!(json["request_errors"]["reset"], 2);
+}
+
+#[tokio::test]
+async fn test_run_checks_for_type_never_resends_payload_after_reset() {
+    // The server reads each attack request in full, then resets: the payload
+    // reached the target, so it must not be sent a second time.
+    let (host, port, handle, resets) = start_counting_resetting_server("test1").await;
+
+    let pb = ProgressBar::new_spinner();
+    pb.finish_and_clear();
+
+    let request = format!(
+        "POST / HTTP/1.1\r\nHost: {}\r\nContent-Length: 5\r\n\r\ntest1",
+        host
+    );
+
+    let result = run_checks_for_type(CheckParams {
+        pb: &pb,
+        check_name: "TEST",
+        host: &host,
+        port,
+        path: "/",
+        attack_requests: vec![request].into(),
+        timeout: 5,
+        verbose: false,
+        use_tls: false,
+        export: None,
+        archive: None,
+        current_check: 1,
+        total_checks: 1,
+        delay: 0,
+        baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
+    })
+    .await;
+
+    handle.abort();
+
+    let check_result = result.unwrap();
+    assert_eq!(resets.load(std::sync::atomic::Ordering::SeqCst), 1);
+    assert_eq!(
+        check_result.request_errors.get(&RequestErrorKind::Reset),
+        Some(&1)
+    );
+}
+
+fn storm_params<'a>(
+    pb: &'a ProgressBar,
+    port: u16,
+    attack_requests: Vec<String>,
+) -> CheckParams<'a> {
+    CheckParams {
+        pb,
+        check_name: "TEST",
+        host: "127.0.0.1",
+        port,
+        path: "/",
+        attack_requests: attack_requests.into(),
+        timeout: 5,
+        verbose: false,
+        use_tls: false,
+        export: None,
+        archive: None,
+        current_check: 1,
+        total_checks: 1,
+        delay: 0,
+        baseline_count: 1,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
+    }
+}
+
+/// An error storm that clears up after the cool-down resumes the check.
+#[tokio::test]
+async fn test_error_storm_recovers_after_cooldown() {
+    let (_, port, handle) = start_resetting_server().await;
+    let pb = ProgressBar::new_spinner();
+    pb.finish_and_clear();
+
+    let attack_requests = (0..ERROR_STORM_THRESHOLD + 1)
+        .map(|n| {
+            format!(
+                "POST / HTTP/1.1\r\nHost: 127.0.0.1\r\nContent-Length: 1\r\n\r\n{}",
+                n
+            )
+        })
+        .collect();
+    let result = run_checks_for_type(storm_params(&pb, port, attack_requests)).await;
+    handle.abort();
+
+    let check_result = result.unwrap();
+    assert!(!check_result.vulnerable);
+    assert_eq!(check_result.payloads_sent, ERROR_STORM_THRESHOLD + 1);
+    assert!(
+        check_result
+            .diagnostics
+            .contains(&"error_storm_recovered=1".to_string())
+    );
+}
+
+/// A target that still fails after the cool-down aborts the check.
+#[tokio::test]
+async fn test_error_storm_aborts_destabilized_target() {
+    // Answers the first baseline request, then resets every connection.
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let handle = tokio::spawn(async move {
+        let mut answered = false;
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let mut buf = vec![0u8; 4096];
+            let _ = socket.read(&mut buf).await;
+            if answered {
+                let _ = socket.set_zero_linger();
+                continue;
+            }
+            answered = true;
+            let response = "HTTP/1.1 200 OK\r\nContent-Length: 13\r\n\r\nHello, World!";
+            let _ = socket.write_all(response.as_bytes()).await;
+        }
+    });
+    let pb = ProgressBar::new_spinner();
+    pb.finish_and_clear();
+
+    let attack_requests = (0..ERROR_STORM_THRESHOLD + 1)
+        .map(|n| format!("GET /{} HTTP/1.1\r\nHost: 127.0.0.1\r\n\r\n", n))
+        .collect();
+    let result = run_checks_for_type(storm_params(&pb, port, attack_requests)).await;
+    handle.abort();
+
+    match result {
+        Err(SmugglexError::TargetDestabilized(msg)) => {
+            assert!(msg.contains("5 consecutive failed or 5xx payloads during TEST"));
+        }
+        other => panic!(
+            "expected TargetDestabilized, got {:?}",
+            other.map(|r| r.diagnostics)
+        ),
+    }
+}
+
+/// Without a screening strategy the attack payloads are never examined, so a
+/// timing anomaly alone cannot produce a finding.
+#[tokio::test]
+async fn test_run_checks_for_type_without_timing_detection_ignores_timeouts() {
+    let (host, port, handle) = start_timeout_server().await;
+
+    let pb = ProgressBar::new_spinner();
+    pb.finish_and_clear();
+
+    let attack_requests = vec![format!(
+        "GET / HTTP/1.1\r\nHost: {}\r\nContent-Length: 5\r\n\r\ntest1",
+        host
+    )];
+
+    let result = run_checks_for_type(CheckParams {
+        pb: &pb,
+        check_name: "CL.TE",
+        host: &host,
+        port,
+        path: "/",
+        attack_requests: attack_requests.into(),
+        timeout: 5,
+        verbose: false,
+        use_tls: false,
+        export: None,
+        archive: None,
+        current_check: 1,
+        total_checks: 1,
+        delay: 0,
+        baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: &[DetectionMethod::Differential, DetectionMethod::Canary],
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
+    })
+    .await;
+
+    handle.abort();
+
+    let check_result = result.unwrap();
+    assert!(!check_result.vulnerable);
+    assert_eq!(check_result.payloads_sent, 0);
+}
+
 #[tokio::test]
 async fn test_run_checks_for_type_vulnerable_timeout_status() {
     let (host, port, handle) = start_timeout_server().await;
@@ -550,15 +935,22 @@ async fn test_run_checks_for_type_vulnerable_timeout_status() {
         host: &host,
         port,
         path: "/",
-        attack_requests,
+        attack_requests: attack_requests.into(),
         timeout: 5,
         verbose: false,
         use_tls: false,
-        export_dir: None,
+        export: None,
+        archive: None,
         current_check: 1,
         total_checks: 1,
         delay: 0,
         baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
     })
     .await;
 
@@ -573,6 +965,150 @@ async fn test_run_checks_for_type_vulnerable_timeout_status() {
     assert!(check_result.confidence.is_some());
 }
 
+/// Mock server that cuts the body short of its Content-Length, either for
+/// requests carrying Transfer-Encoding only or for every request.
+async fn start_truncating_server(always: bool) -> (String, u16, tokio::task::JoinHandle<()>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let host = addr.ip().to_string();
+    let port = addr.port();
+
+    let handle = tokio::spawn(async move {
+        loop {
+            if let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = vec![0u8; 4096];
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    let request = String::from_utf8_lossy(&buf[..n]);
+                    let response = if always || request.contains("Transfer-Encoding") {
+                        "HTTP/1.1 200 OK\r\nContent-Length: 100\r\n\r\nHello"
+                    } else {
+                        "HTTP/1.1 200 OK\r\nContent-Length: 13\r\n\r\nHello, World!"
+                    };
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        }
+    });
+
+    (host, port, handle)
+}
+
+/// Mock server that answers requests carrying Transfer-Encoding twice, the
+/// second response a moment after the first on the same connection.
+async fn start_double_response_server() -> (String, u16, tokio::task::JoinHandle<()>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let host = addr.ip().to_string();
+    let port = addr.port();
+
+    let handle = tokio::spawn(async move {
+        loop {
+            if let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = vec![0u8; 4096];
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    let request = String::from_utf8_lossy(&buf[..n]);
+                    let _ = socket
+                        .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 13\r\n\r\nHello, World!")
+                        .await;
+                    if request.contains("Transfer-Encoding") {
+                        tokio::time::sleep(Duration::from_millis(20)).await;
+                        let _ = socket
+                            .write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n")
+                            .await;
+                    }
+                });
+            }
+        }
+    });
+
+    (host, port, handle)
+}
+
+async fn run_truncation_check(host: &str, port: u16) -> CheckResult {
+    let pb = ProgressBar::new_spinner();
+    pb.finish_and_clear();
+    let attack_requests = vec![format!(
+        "POST / HTTP/1.1\r\nHost: {}\r\nContent-Length: 6\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nG",
+        host
+    )];
+    run_checks_for_type(CheckParams {
+        pb: &pb,
+        check_name: "cl-te",
+        host,
+        port,
+        path: "/",
+        attack_requests: attack_requests.into(),
+        timeout: 5,
+        verbose: false,
+        use_tls: false,
+        export: None,
+        archive: None,
+        current_check: 1,
+        total_checks: 1,
+        delay: 0,
+        baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_run_checks_for_type_truncated_body_is_evidence() {
+    let (host, port, handle) = start_truncating_server(false).await;
+    let result = run_truncation_check(&host, port).await;
+    handle.abort();
+
+    // The smuggling-stripped control is framed correctly, so the short body
+    // is put down to the Transfer-Encoding header.
+    assert!(result.vulnerable);
+    assert_eq!(result.payload_index, Some(0));
+    assert!(
+        result
+            .detection_signals
+            .contains(&"body_truncated:5/100".to_string()),
+        "{:?}",
+        result.detection_signals
+    );
+}
+
+#[tokio::test]
+async fn test_run_checks_for_type_unsolicited_response_is_high_confidence() {
+    let (host, port, handle) = start_double_response_server().await;
+    let result = run_truncation_check(&host, port).await;
+    handle.abort();
+
+    assert!(result.vulnerable);
+    assert_eq!(result.confidence, Some(smugglex::model::Confidence::High));
+    assert!(
+        result
+            .detection_signals
+            .contains(&"unsolicited_bytes:45".to_string()),
+        "{:?}",
+        result.detection_signals
+    );
+    assert_eq!(
+        result.unsolicited_bytes.as_deref(),
+        Some("HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n")
+    );
+}
+
+#[tokio::test]
+async fn test_run_checks_for_type_ignores_truncation_seen_in_baseline() {
+    let (host, port, handle) = start_truncating_server(true).await;
+    let result = run_truncation_check(&host, port).await;
+    handle.abort();
+
+    assert!(!result.vulnerable);
+}
+
 #[tokio::test]
 async fn test_run_checks_for_type_vulnerable_timing() {
     let (host, port, handle) = start_slow_server().await;
@@ -591,15 +1127,22 @@ async fn test_run_checks_for_type_vulnerable_timing() {
         host: &host,
         port,
         path: "/",
-        attack_requests,
+        attack_requests: attack_requests.into(),
         timeout: 5,
         verbose: false,
         use_tls: false,
-        export_dir: None,
+        export: None,
+        archive: None,
         current_check: 1,
         total_checks: 1,
         delay: 0,
         baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
     })
     .await;
 
@@ -638,15 +1181,22 @@ async fn test_run_checks_for_type_multiple_payloads() {
         host: &host,
         port,
         path: "/test",
-        attack_requests,
+        attack_requests: attack_requests.into(),
         timeout: 5,
         verbose: false,
         use_tls: false,
-        export_dir: None,
+        export: None,
+        archive: None,
         current_check: 2,
         total_checks: 5,
         delay: 0,
         baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
     })
     .await;
 
@@ -679,15 +1229,26 @@ async fn test_run_checks_for_type_with_export_dir() {
         host: &host,
         port,
         path: "/",
-        attack_requests,
+        attack_requests: attack_requests.into(),
         timeout: 5,
         verbose: false,
         use_tls: false,
-        export_dir: Some(temp_dir.to_str().unwrap()),
+        export: Some(PayloadExport {
+            dir: temp_dir.to_str().unwrap(),
+            format: ExportFormat::Raw,
+            target: "http://127.0.0.1/",
+        }),
+        archive: None,
         current_check: 1,
         total_checks: 1,
         delay: 0,
         baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
     })
     .await;
 
@@ -697,10 +1258,104 @@ async fn test_run_checks_for_type_with_export_dir() {
     let check_result = result.unwrap();
     assert!(check_result.vulnerable);
 
+    let stem = format!("http_{}_TE.TE_0", sanitize_hostname(&host));
+    let raw = std::fs::read(temp_dir.join(format!("{}.raw", stem))).unwrap();
+    assert_eq!(raw, check_result.payload.unwrap().as_bytes());
+    let sidecar: ExportedPayload = serde_json::from_str(
+        &std::fs::read_to_string(temp_dir.join(format!("{}.json", stem))).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(sidecar.target, "http://127.0.0.1/");
+    assert_eq!(sidecar.check_type, "TE.TE");
+    assert_eq!(sidecar.payload_index, 0);
+    assert_eq!(sidecar.raw_file, format!("{}.raw", stem));
+    assert_eq!(sidecar.timestamp, check_result.timestamp);
+    assert_eq!(sidecar.response.attack_status, check_result.attack_status);
+    assert_eq!(sidecar.response.confidence, check_result.confidence);
+
     // Clean up
     let _ = std::fs::remove_dir_all(&temp_dir);
 }
 
+#[tokio::test]
+async fn test_run_checks_for_type_archives_every_payload() {
+    let (host, port, handle) = start_normal_server().await;
+
+    let pb = ProgressBar::new_spinner();
+    pb.finish_and_clear();
+
+    let path = std::env::temp_dir().join(format!(
+        "smugglex_test_archive_{}.tar.gz",
+        std::process::id()
+    ));
+    let archive = PayloadArchive::create(&path).unwrap();
+    let attack_requests = vec![
+        format!("GET / HTTP/1.1\r\nHost: {}\r\n\r\n", host),
+        format!("GET / HTTP/1.1\r\nHost: {}\r\nX-Variant: 1\r\n\r\n", host),
+    ];
+
+    let result = run_checks_for_type(CheckParams {
+        pb: &pb,
+        check_name: "cl-te",
+        host: &host,
+        port,
+        path: "/",
+        attack_requests: attack_requests.into(),
+        timeout: 5,
+        verbose: false,
+        use_tls: false,
+        export: None,
+        archive: Some(ArchiveRecorder {
+            archive: &archive,
+            target: "http://127.0.0.1/",
+        }),
+        current_check: 1,
+        total_checks: 1,
+        delay: 0,
+        baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: &[DetectionMethod::Timing],
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
+    })
+    .await
+    .unwrap();
+
+    handle.abort();
+
+    assert!(!result.vulnerable);
+    assert_eq!(archive.finish().unwrap(), 2);
+    let mut entries = tar::Archive::new(flate2::read::GzDecoder::new(
+        std::fs::File::open(&path).unwrap(),
+    ));
+    let outcomes: Vec<(String, Option<String>)> = entries
+        .entries()
+        .unwrap()
+        .map(|entry| entry.unwrap())
+        .filter(|entry| {
+            entry
+                .path()
+                .unwrap()
+                .extension()
+                .is_some_and(|e| e == "json")
+        })
+        .map(|entry| {
+            let metadata: ExportedPayload = serde_json::from_reader(entry).unwrap();
+            (metadata.raw_file, metadata.outcome)
+        })
+        .collect();
+    assert_eq!(
+        outcomes,
+        [
+            ("000001_cl-te_0.raw".to_string(), Some("clean".to_string())),
+            ("000002_cl-te_1.raw".to_string(), Some("clean".to_string())),
+        ]
+    );
+    let _ = std::fs::remove_file(&path);
+}
+
 #[tokio::test]
 async fn test_run_checks_for_type_verbose_mode() {
     let (host, port, handle) = start_normal_server().await;
@@ -719,15 +1374,22 @@ async fn test_run_checks_for_type_verbose_mode() {
         host: &host,
         port,
         path: "/",
-        attack_requests,
+        attack_requests: attack_requests.into(),
         timeout: 5,
         verbose: true, // Test verbose mode
         use_tls: false,
-        export_dir: None,
+        export: None,
+        archive: None,
         current_check: 5,
         total_checks: 5,
         delay: 0,
         baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
     })
     .await;
 
@@ -756,15 +1418,22 @@ async fn test_run_checks_for_type_with_custom_path() {
         host: &host,
         port,
         path: "/api/v1/test",
-        attack_requests,
+        attack_requests: attack_requests.into(),
         timeout: 5,
         verbose: false,
         use_tls: false,
-        export_dir: None,
+        export: None,
+        archive: None,
         current_check: 1,
         total_checks: 1,
         delay: 0,
         baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
     })
     .await;
 
@@ -788,15 +1457,22 @@ async fn test_run_checks_for_type_empty_payloads() {
         host: &host,
         port,
         path: "/",
-        attack_requests,
+        attack_requests: attack_requests.into(),
         timeout: 5,
         verbose: false,
         use_tls: false,
-        export_dir: None,
+        export: None,
+        archive: None,
         current_check: 1,
         total_checks: 1,
         delay: 0,
         baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
     })
     .await;
 
@@ -825,15 +1501,22 @@ async fn test_run_checks_for_type_different_check_names() {
             host: &host,
             port,
             path: "/",
-            attack_requests,
+            attack_requests: attack_requests.into(),
             timeout: 5,
             verbose: false,
             use_tls: false,
-            export_dir: None,
+            export: None,
+            archive: None,
             current_check: 1,
             total_checks: 1,
             delay: 0,
             baseline_count: DEFAULT_BASELINE_COUNT,
+            warmup: 0,
+            storm_cooldown: 0,
+            detect: DEFAULT_DETECTION,
+            oob_host: None,
+            corpus: None,
+            fuzz_feedback: None,
         })
         .await;
 
@@ -886,15 +1569,22 @@ async fn test_run_checks_for_type_408_status_code() {
         host: &host,
         port,
         path: "/",
-        attack_requests,
+        attack_requests: attack_requests.into(),
         timeout: 5,
         verbose: false,
         use_tls: false,
-        export_dir: None,
+        export: None,
+        archive: None,
         current_check: 1,
         total_checks: 1,
         delay: 0,
         baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
     })
     .await;
 
@@ -974,15 +1664,22 @@ async fn test_flaky_504_not_confirmed() {
         host: &host,
         port,
         path: "/",
-        attack_requests,
+        attack_requests: attack_requests.into(),
         timeout: 5,
         verbose: false,
         use_tls: false,
-        export_dir: None,
+        export: None,
+        archive: None,
         current_check: 1,
         total_checks: 1,
         delay: 0,
         baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
     })
     .await;
 
@@ -1034,15 +1731,22 @@ async fn test_baseline_504_not_flagged() {
         host: &host,
         port,
         path: "/",
-        attack_requests,
+        attack_requests: attack_requests.into(),
         timeout: 5,
         verbose: false,
         use_tls: false,
-        export_dir: None,
+        export: None,
+        archive: None,
         current_check: 1,
         total_checks: 1,
         delay: 0,
         baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
     })
     .await;
 
@@ -1104,15 +1808,22 @@ async fn test_connection_timeout_strict_confirmation() {
         host: &host,
         port,
         path: "/",
-        attack_requests,
+        attack_requests: attack_requests.into(),
         timeout: 5,
         verbose: false,
         use_tls: false,
-        export_dir: None,
+        export: None,
+        archive: None,
         current_check: 1,
         total_checks: 1,
         delay: 0,
         baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
     })
     .await;
 
@@ -1175,15 +1886,22 @@ async fn test_confirmed_vulnerability_high_confidence() {
         host: &host,
         port,
         path: "/",
-        attack_requests,
+        attack_requests: attack_requests.into(),
         timeout: 5,
         verbose: false,
         use_tls: false,
-        export_dir: None,
+        export: None,
+        archive: None,
         current_check: 1,
         total_checks: 1,
         delay: 0,
         baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
     })
     .await;
 
@@ -1248,15 +1966,22 @@ async fn test_confirmed_vulnerability_medium_confidence() {
         host: &host,
         port,
         path: "/",
-        attack_requests,
+        attack_requests: attack_requests.into(),
         timeout: 5,
         verbose: false,
         use_tls: false,
-        export_dir: None,
+        export: None,
+        archive: None,
         current_check: 1,
         total_checks: 1,
         delay: 0,
         baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
     })
     .await;
 
@@ -1321,15 +2046,22 @@ async fn test_single_baseline_504_does_not_disable_signal() {
         host: &host,
         port,
         path: "/",
-        attack_requests,
+        attack_requests: attack_requests.into(),
         timeout: 5,
         verbose: false,
         use_tls: false,
-        export_dir: None,
+        export: None,
+        archive: None,
         current_check: 1,
         total_checks: 1,
         delay: 0,
         baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
     })
     .await;
 
@@ -1359,15 +2091,22 @@ async fn test_baseline_count_zero_does_not_panic() {
         host: &host,
         port,
         path: "/",
-        attack_requests,
+        attack_requests: attack_requests.into(),
         timeout: 5,
         verbose: false,
         use_tls: false,
-        export_dir: None,
+        export: None,
+        archive: None,
         current_check: 1,
         total_checks: 1,
         delay: 0,
         baseline_count: 0,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
     })
     .await;
 
@@ -1378,6 +2117,64 @@ async fn test_baseline_count_zero_does_not_panic() {
     );
 }
 
+/// `warmup: N` sends exactly N extra requests ahead of the baseline.
+#[tokio::test]
+async fn test_warmup_requests_sent_before_baseline() {
+    async fn requests_with_warmup(warmup: usize) -> usize {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let request_count = Arc::new(AtomicUsize::new(0));
+        let counter = request_count.clone();
+        let handle = tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                counter.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    let mut buf = vec![0u8; 4096];
+                    let _ = socket.read(&mut buf).await;
+                    let response = "HTTP/1.1 200 OK\r\nContent-Length: 13\r\n\r\nHello, World!";
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+
+        let pb = ProgressBar::new_spinner();
+        pb.finish_and_clear();
+        let attack_requests = vec!["GET / HTTP/1.1\r\nHost: 127.0.0.1\r\n\r\n".to_string()];
+        let result = run_checks_for_type(CheckParams {
+            pb: &pb,
+            check_name: "CL.TE",
+            host: "127.0.0.1",
+            port,
+            path: "/",
+            attack_requests: attack_requests.into(),
+            timeout: 5,
+            verbose: false,
+            use_tls: false,
+            export: None,
+            archive: None,
+            current_check: 1,
+            total_checks: 1,
+            delay: 0,
+            baseline_count: 1,
+            warmup,
+            storm_cooldown: 0,
+            detect: DEFAULT_DETECTION,
+            oob_host: None,
+            corpus: None,
+            fuzz_feedback: None,
+        })
+        .await
+        .unwrap();
+        handle.abort();
+        assert!(!result.vulnerable);
+        request_count.load(Ordering::SeqCst)
+    }
+
+    let cold = requests_with_warmup(0).await;
+    let warm = requests_with_warmup(3).await;
+    assert_eq!(warm, cold + 3);
+}
+
 /// Confirmation requires strict majority (>N/2) for status/timing signals.
 /// With 3 retries: only 1 of 3 reproducing must NOT confirm.
 #[tokio::test]
@@ -1429,15 +2226,22 @@ async fn test_minority_confirmation_not_flagged() {
         host: &host,
         port,
         path: "/",
-        attack_requests,
+        attack_requests: attack_requests.into(),
         timeout: 5,
         verbose: false,
         use_tls: false,
-        export_dir: None,
+        export: None,
+        archive: None,
         current_check: 1,
         total_checks: 1,
         delay: 0,
         baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
     })
     .await;
 
@@ -1490,8 +2294,12 @@ async fn start_uniformly_slow_post_server() -> (String, u16, tokio::task::JoinHa
 /// Mock server that is slow ONLY when the request carries a Transfer-Encoding
 /// header. This simulates a real smuggling-vulnerable backend whose desync is
 /// triggered by the TE header, while shape-matched non-TE requests respond
-/// promptly.
-async fn start_te_specific_slow_server() -> (String, u16, tokio::task::JoinHandle<()>) {
+/// promptly. A well-framed chunked request (ending in its terminating chunk)
+/// is answered promptly too, as a real desync needs misframed bytes, unless
+/// `stall_any_chunked` makes the backend slow on every chunked request.
+async fn start_te_specific_slow_server(
+    stall_any_chunked: bool,
+) -> (String, u16, tokio::task::JoinHandle<()>) {
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
     let host = addr.ip().to_string();
@@ -1508,7 +2316,8 @@ async fn start_te_specific_slow_server() -> (String, u16, tokio::task::JoinHandl
                     let body_str = String::from_utf8_lossy(&buf[..n_read]).to_ascii_lowercase();
                     let n = count.fetch_add(1, Ordering::SeqCst) + 1;
 
-                    let has_te = body_str.contains("transfer-encoding");
+                    let has_te = body_str.contains("transfer-encoding")
+                        && (stall_any_chunked || !body_str.ends_with("0\r\n\r\n"));
                     if n > DEFAULT_BASELINE_COUNT && has_te {
                         tokio::time::sleep(Duration::from_millis(2000)).await;
                     }
@@ -1546,15 +2355,22 @@ async fn test_uniform_slow_backend_rejected_via_control() {
         host: &host,
         port,
         path: "/",
-        attack_requests,
+        attack_requests: attack_requests.into(),
         timeout: 5,
         verbose: false,
         use_tls: false,
-        export_dir: None,
+        export: None,
+        archive: None,
         current_check: 1,
         total_checks: 1,
         delay: 0,
         baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
     })
     .await;
 
@@ -1571,7 +2387,7 @@ async fn test_uniform_slow_backend_rejected_via_control() {
 /// confirms smuggling rather than rejecting it.
 #[tokio::test]
 async fn test_te_specific_slow_backend_still_detected() {
-    let (host, port, handle) = start_te_specific_slow_server().await;
+    let (host, port, handle) = start_te_specific_slow_server(false).await;
 
     let pb = ProgressBar::new_spinner();
     pb.finish_and_clear();
@@ -1587,15 +2403,22 @@ async fn test_te_specific_slow_backend_still_detected() {
         host: &host,
         port,
         path: "/",
-        attack_requests,
+        attack_requests: attack_requests.into(),
         timeout: 5,
         verbose: false,
         use_tls: false,
-        export_dir: None,
+        export: None,
+        archive: None,
         current_check: 1,
         total_checks: 1,
         delay: 0,
         baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
     })
     .await;
 
@@ -1605,6 +2428,164 @@ async fn test_te_specific_slow_backend_still_detected() {
         check_result.vulnerable,
         "TE-specific slow backend must remain detected after control comparison"
     );
+    assert!(
+        check_result
+            .detection_signals
+            .iter()
+            .any(|s| s == "chunked_control_clean"),
+        "Expected chunked_control_clean signal, got: {:?}",
+        check_result.detection_signals
+    );
+}
+
+/// Mock CL.TE chain: the front-end forwards `Content-Length` bytes of the body
+/// and the back-end, reading chunks, stalls when those bytes hold no
+/// terminating chunk.
+async fn start_cl_te_server() -> (String, u16, tokio::task::JoinHandle<()>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let host = addr.ip().to_string();
+    let port = addr.port();
+
+    let handle = tokio::spawn(async move {
+        loop {
+            if let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = vec![0u8; 8192];
+                    let n_read = socket.read(&mut buf).await.unwrap_or(0);
+                    let req = String::from_utf8_lossy(&buf[..n_read]).to_ascii_lowercase();
+                    let (head, body) = req.split_once("\r\n\r\n").unwrap_or((&req, ""));
+                    let declared = head
+                        .lines()
+                        .find_map(|l| l.strip_prefix("content-length:"))
+                        .and_then(|v| v.trim().parse::<usize>().ok())
+                        .unwrap_or(0);
+                    let forwarded = &body[..declared.min(body.len())];
+                    if head.contains("transfer-encoding") && !forwarded.contains("0\r\n\r\n") {
+                        tokio::time::sleep(Duration::from_millis(2000)).await;
+                    }
+                    let response = "HTTP/1.1 200 OK\r\nContent-Length: 13\r\n\r\nHello, World!";
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        }
+    });
+
+    (host, port, handle)
+}
+
+async fn run_cl_te_check(host: &str, port: u16) -> CheckResult {
+    let pb = ProgressBar::new_spinner();
+    pb.finish_and_clear();
+    let attack_requests = vec![format!(
+        "POST / HTTP/1.1\r\nHost: {}\r\nTransfer-Encoding: chunked\r\nContent-Length: 4\r\n\r\n1\r\nA\r\nX",
+        host
+    )];
+    run_checks_for_type(CheckParams {
+        pb: &pb,
+        check_name: "cl-te",
+        host,
+        port,
+        path: "/",
+        attack_requests: attack_requests.into(),
+        timeout: 5,
+        verbose: false,
+        use_tls: false,
+        export: None,
+        archive: None,
+        current_check: 1,
+        total_checks: 1,
+        delay: 0,
+        baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
+    })
+    .await
+    .unwrap()
+}
+
+/// A CL.TE finding is followed by a TE.CL probe, which a real CL.TE chain
+/// answers promptly.
+#[tokio::test]
+async fn test_cl_te_cross_check_consistent() {
+    let (host, port, handle) = start_cl_te_server().await;
+    let result = run_cl_te_check(&host, port).await;
+    handle.abort();
+
+    assert!(result.vulnerable);
+    let cross_check = result.cross_check.expect("cross-check should run");
+    assert_eq!(cross_check.technique, "te-cl");
+    assert_eq!(cross_check.verdict, CrossCheckVerdict::Consistent);
+}
+
+/// A back-end that stalls on any misframed chunked request stalls on the
+/// TE.CL probe too, so the CL.TE classification is flagged as doubtful.
+#[tokio::test]
+async fn test_cl_te_cross_check_inconsistent() {
+    let (host, port, handle) = start_te_specific_slow_server(false).await;
+    let result = run_cl_te_check(&host, port).await;
+    handle.abort();
+
+    assert!(result.vulnerable);
+    let cross_check = result.cross_check.expect("cross-check should run");
+    assert_eq!(cross_check.verdict, CrossCheckVerdict::Inconsistent);
+    assert!(
+        cross_check
+            .observation
+            .starts_with("TE.CL probe also desynced")
+    );
+}
+
+/// FP rejection: a backend that is slow on every chunked request, well-framed
+/// or not, must be rejected via the benign chunked control even though the
+/// smuggling-stripped (Content-Length only) control is fast.
+#[tokio::test]
+async fn test_slow_on_any_chunked_backend_rejected_via_chunked_control() {
+    let (host, port, handle) = start_te_specific_slow_server(true).await;
+
+    let pb = ProgressBar::new_spinner();
+    pb.finish_and_clear();
+
+    let attack_requests = vec![format!(
+        "POST / HTTP/1.1\r\nHost: {}\r\nTransfer-Encoding: chunked\r\nContent-Length: 6\r\n\r\n0\r\n\r\nG",
+        host
+    )];
+
+    let result = run_checks_for_type(CheckParams {
+        pb: &pb,
+        check_name: "CL.TE",
+        host: &host,
+        port,
+        path: "/",
+        attack_requests: attack_requests.into(),
+        timeout: 5,
+        verbose: false,
+        use_tls: false,
+        export: None,
+        archive: None,
+        current_check: 1,
+        total_checks: 1,
+        delay: 0,
+        baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
+    })
+    .await;
+
+    handle.abort();
+    let check_result = result.unwrap();
+    assert!(
+        !check_result.vulnerable,
+        "Backend slow on any chunked POST should be rejected by the benign chunked control"
+    );
 }
 
 /// Noise-aware threshold: a noisy baseline whose max approaches the attack
@@ -1656,15 +2637,22 @@ async fn test_noisy_baseline_suppresses_borderline_attack() {
         host: &host,
         port,
         path: "/",
-        attack_requests,
+        attack_requests: attack_requests.into(),
         timeout: 5,
         verbose: false,
         use_tls: false,
-        export_dir: None,
+        export: None,
+        archive: None,
         current_check: 1,
         total_checks: 1,
         delay: 0,
         baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
     })
     .await;
 
@@ -1702,7 +2690,7 @@ async fn test_body_divergence_overrides_control_timing_similarity() {
                     // fast, attack (TE) and control (CL=body_len padding) both
                     // run slow — this is the scenario the body-divergence
                     // escape clause exists for.
-                    let has_te = req.contains("transfer-encoding");
+                    let has_te = req.contains("transfer-encoding") && !req.ends_with("0\r\n\r\n");
                     let has_cl_nonzero = req.lines().any(|l| {
                         let lt = l.trim_start();
                         lt.starts_with("content-length:")
@@ -1748,15 +2736,22 @@ async fn test_body_divergence_overrides_control_timing_similarity() {
         host: &host,
         port,
         path: "/",
-        attack_requests,
+        attack_requests: attack_requests.into(),
         timeout: 5,
         verbose: false,
         use_tls: false,
-        export_dir: None,
+        export: None,
+        archive: None,
         current_check: 1,
         total_checks: 1,
         delay: 0,
         baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
     })
     .await;
 
@@ -1822,15 +2817,22 @@ async fn test_method_matched_baseline_suppresses_post_only_slowness() {
         host: &host,
         port,
         path: "/",
-        attack_requests,
+        attack_requests: attack_requests.into(),
         timeout: 5,
         verbose: false,
         use_tls: false,
-        export_dir: None,
+        export: None,
+        archive: None,
         current_check: 1,
         total_checks: 1,
         delay: 0,
         baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
     })
     .await;
 
@@ -1906,15 +2908,22 @@ async fn test_consecutive_fp_rejections_short_circuit_check() {
         host: &host,
         port,
         path: "/",
-        attack_requests,
+        attack_requests: attack_requests.into(),
         timeout: 6,
         verbose: false,
         use_tls: false,
-        export_dir: None,
+        export: None,
+        archive: None,
         current_check: 1,
         total_checks: 1,
         delay: 0,
         baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
     })
     .await;
     let elapsed = start.elapsed();
@@ -2028,15 +3037,22 @@ async fn test_followup_divergence_signal_recorded() {
         host: &host,
         port,
         path: "/",
-        attack_requests,
+        attack_requests: attack_requests.into(),
         timeout: 5,
         verbose: false,
         use_tls: false,
-        export_dir: None,
+        export: None,
+        archive: None,
         current_check: 1,
         total_checks: 1,
         delay: 0,
         baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
     })
     .await;
 
@@ -2075,15 +3091,22 @@ async fn test_detection_signals_populated_for_timing_only() {
         host: &host,
         port,
         path: "/",
-        attack_requests,
+        attack_requests: attack_requests.into(),
         timeout: 5,
         verbose: false,
         use_tls: false,
-        export_dir: None,
+        export: None,
+        archive: None,
         current_check: 1,
         total_checks: 1,
         delay: 0,
         baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: DEFAULT_DETECTION,
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: None,
     })
     .await;
 
@@ -2103,3 +3126,166 @@ async fn test_detection_signals_populated_for_timing_only() {
         check_result.detection_signals
     );
 }
+
+/// An anomalous payload is minimized to the header lines that still trigger
+/// the anomaly and saved to the corpus.
+#[tokio::test]
+async fn test_anomaly_minimized_into_corpus() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let host = addr.ip().to_string();
+    let port = addr.port();
+
+    // Only requests carrying `X-Trigger` are slow.
+    let handle = tokio::spawn(async move {
+        loop {
+            if let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = vec![0u8; 4096];
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    if String::from_utf8_lossy(&buf[..n]).contains("X-Trigger") {
+                        tokio::time::sleep(Duration::from_millis(1500)).await;
+                    }
+                    let response = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nOK";
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        }
+    });
+
+    let dir = std::env::temp_dir().join(format!("smugglex-corpus-scan-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let corpus = FuzzCorpus::open(&dir).unwrap();
+
+    let pb = ProgressBar::new_spinner();
+    pb.finish_and_clear();
+
+    let attack_requests = vec![format!(
+        "GET / HTTP/1.1\r\nHost: {}\r\nX-Noise: a\r\nX-Trigger: 1\r\nX-Other: b\r\nContent-Length: 5\r\n\r\ntest1",
+        host
+    )];
+
+    let _ = run_checks_for_type(CheckParams {
+        pb: &pb,
+        check_name: "cl-te",
+        host: &host,
+        port,
+        path: "/",
+        attack_requests: attack_requests.into(),
+        timeout: 5,
+        verbose: false,
+        use_tls: false,
+        export: None,
+        archive: None,
+        current_check: 1,
+        total_checks: 1,
+        delay: 0,
+        baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: &[DetectionMethod::Timing],
+        oob_host: None,
+        corpus: Some(&corpus),
+        fuzz_feedback: None,
+    })
+    .await;
+
+    handle.abort();
+
+    let entries = corpus.load("cl-te", "example.com").unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+    assert_eq!(
+        entries,
+        vec!["GET / HTTP/1.1\r\nHost: example.com\r\nX-Trigger: 1\r\n\r\ntest1".to_string()]
+    );
+}
+
+/// A fuzzed payload whose anomaly does not reproduce is reported for triage
+/// instead of disappearing.
+#[tokio::test]
+async fn test_unconfirmed_fuzz_anomaly_reported_for_triage() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let host = addr.ip().to_string();
+    let port = addr.port();
+
+    // Only the first `X-Spike` request is slow.
+    let handle = tokio::spawn(async move {
+        let spiked = Arc::new(AtomicUsize::new(0));
+        loop {
+            if let Ok((mut socket, _)) = listener.accept().await {
+                let spiked = spiked.clone();
+                tokio::spawn(async move {
+                    let mut buf = vec![0u8; 4096];
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    if String::from_utf8_lossy(&buf[..n]).contains("X-Spike")
+                        && spiked.fetch_add(1, Ordering::SeqCst) == 0
+                    {
+                        tokio::time::sleep(Duration::from_millis(1500)).await;
+                    }
+                    let response = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nOK";
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        }
+    });
+
+    let dir = std::env::temp_dir().join(format!("smugglex-triage-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let feedback = FuzzFeedback::default();
+
+    let pb = ProgressBar::new_spinner();
+    pb.finish_and_clear();
+
+    let attack_requests = vec![
+        format!("GET / HTTP/1.1\r\nHost: {}\r\n\r\n", host),
+        format!("GET / HTTP/1.1\r\nHost: {}\r\nX-Spike: 1\r\n\r\n", host),
+    ];
+
+    let result = run_checks_for_type(CheckParams {
+        pb: &pb,
+        check_name: "te-cl",
+        host: &host,
+        port,
+        path: "/",
+        attack_requests: attack_requests.into(),
+        timeout: 5,
+        verbose: false,
+        use_tls: false,
+        export: dir.to_str().map(|dir| PayloadExport {
+            dir,
+            format: ExportFormat::Txt,
+            target: "http://127.0.0.1/",
+        }),
+        archive: None,
+        current_check: 1,
+        total_checks: 1,
+        delay: 0,
+        baseline_count: DEFAULT_BASELINE_COUNT,
+        warmup: 0,
+        storm_cooldown: 0,
+        detect: &[DetectionMethod::Timing],
+        oob_host: None,
+        corpus: None,
+        fuzz_feedback: Some(&feedback),
+    })
+    .await
+    .unwrap();
+
+    handle.abort();
+
+    assert!(!result.vulnerable);
+    assert_eq!(result.fuzz_anomalies.len(), 1);
+    let anomaly = &result.fuzz_anomalies[0];
+    assert_eq!(anomaly.payload_index, 1);
+    assert!(
+        anomaly.anomaly.starts_with("delayed "),
+        "{}",
+        anomaly.anomaly
+    );
+    assert!(anomaly.anomaly.ends_with("not reproduced on retry"));
+    assert!(anomaly.payload.contains("X-Spike"));
+    let file = anomaly.reproduction_file.as_ref().unwrap();
+    assert_eq!(std::fs::read_to_string(file).unwrap(), anomaly.payload);
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,61 @@
+//! Tests for the secrets module
+//!
+//! Resolves `@env:` and `@file:` references in header values.
+
+use smugglex::payloads::format_custom_headers;
+use smugglex::secrets::{expand_header, expand_headers, has_secret_reference, secret_header_names};
+use std::fs;
+
+#[test]
+fn test_expand_env_reference() {
+    let path = std::env::var("PATH").expect("PATH is set");
+    assert_eq!(
+        expand_header("X-Path: prefix @env:PATH suffix").unwrap(),
+        format!("X-Path: prefix {} suffix", path)
+    );
+    let err = expand_header("Authorization: Bearer @env:SMUGGLEX_TEST_UNSET_VAR")
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("SMUGGLEX_TEST_UNSET_VAR"));
+}
+
+#[test]
+fn test_expand_file_reference() {
+    let dir = std::env::temp_dir().join("smugglex_test_secrets");
+    fs::create_dir_all(&dir).unwrap();
+    let token = dir.join("token.txt");
+    fs::write(&token, "s3cr3t\n").unwrap();
+    let multiline = dir.join("multiline.txt");
+    fs::write(&multiline, "a\r\nInjected: 1\n").unwrap();
+
+    let header = format!("Authorization: Bearer @file:{}", token.display());
+    assert_eq!(
+        expand_header(&header).unwrap(),
+        "Authorization: Bearer s3cr3t"
+    );
+    assert_eq!(
+        format_custom_headers(std::slice::from_ref(&header)),
+        "Authorization: Bearer s3cr3t\r\n"
+    );
+    let err = expand_header(&format!("X-Token: @file:{}", multiline.display()))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("line break"));
+    assert!(expand_headers(&[header, "X-Token: @file:/nonexistent/smugglex".to_string()]).is_err());
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_headers_without_references_are_unchanged() {
+    let header = "X-Email: user@example.com";
+    assert!(!has_secret_reference(header));
+    assert_eq!(expand_header(header).unwrap(), header);
+    assert_eq!(
+        secret_header_names(&[
+            header.to_string(),
+            "Authorization: Bearer @env:API_TOKEN".to_string(),
+        ]),
+        vec!["Authorization".to_string()]
+    );
+}
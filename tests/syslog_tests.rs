@@ -0,0 +1,183 @@
+//! Tests for the syslog module
+//!
+//! - Parsing `--syslog` URLs
+//! - RFC 5424 events for findings and scanned targets
+//! - Delivery over UDP and over TCP with octet-counting framing
+
+use smugglex::model::{CheckResult, Confidence, ScanResults};
+use smugglex::syslog::{SyslogSink, SyslogTransport, send_syslog, syslog_events};
+use tokio::io::AsyncReadExt;
+use tokio::net::{TcpListener, UdpSocket};
+
+fn check(check_type: &str, vulnerable: bool, confidence: Option<Confidence>) -> CheckResult {
+    CheckResult {
+        check_type: check_type.to_string(),
+        vulnerable,
+        payload_index: vulnerable.then_some(0),
+        payload_name: vulnerable.then(|| "vanilla".to_string()),
+        technique: None,
+        normal_status: "HTTP/1.1 200 OK".to_string(),
+        attack_status: vulnerable.then(|| "HTTP/1.1 504 Gateway Timeout".to_string()),
+        normal_duration_ms: 0,
+        attack_duration_ms: None,
+        normal_connect_ms: None,
+        attack_connect_ms: None,
+        timestamp: "2024-01-01T00:00:00Z".to_string(),
+        payload: None,
+        confidence,
+        detection_signals: if vulnerable {
+            vec!["status_504".to_string()]
+        } else {
+            Vec::new()
+        },
+        diagnostics: Vec::new(),
+        payloads_sent: 0,
+        request_errors: Default::default(),
+        h2_observations: Vec::new(),
+        fuzz_anomalies: Vec::new(),
+        unsolicited_bytes: None,
+        attack_phases: None,
+        cross_check: None,
+        evidence: None,
+        consistency: None,
+    }
+}
+
+fn scan(target: &str, checks: Vec<CheckResult>, error: Option<&str>) -> ScanResults {
+    ScanResults {
+        target: target.to_string(),
+        address: None,
+        vhost: None,
+        ip: None,
+        port: None,
+        scheme: None,
+        method: "POST".to_string(),
+        timestamp: "2024-01-01T00:00:00Z".to_string(),
+        fingerprint: None,
+        checks,
+        exploits: None,
+        proxy_chain: Vec::new(),
+        unreachable: None,
+        throttle: None,
+        target_info: None,
+        error: error.map(str::to_string),
+        error_info: None,
+        duration_ms: Some(1200),
+        requests_sent: Some(42),
+        summary: None,
+    }
+}
+
+fn sample_results() -> Vec<ScanResults> {
+    vec![
+        scan(
+            "https://a.example/",
+            vec![
+                check("cl-te", true, Some(Confidence::High)),
+                check("te-cl", true, Some(Confidence::Low)),
+                check("te-te", false, None),
+            ],
+            None,
+        ),
+        scan("https://b.example/", Vec::new(), Some("connection refused")),
+    ]
+}
+
+/// The JSON message of an event, after the header's seven fields.
+fn message(event: &str) -> serde_json::Value {
+    serde_json::from_str(event.splitn(8, ' ').nth(7).unwrap()).unwrap()
+}
+
+#[test]
+fn test_parse_syslog_url() {
+    let sink = SyslogSink::parse("udp://siem.example").unwrap();
+    assert_eq!(sink.transport, SyslogTransport::Udp);
+    assert_eq!(sink.host, "siem.example");
+    assert_eq!(sink.port, 514);
+
+    let sink = SyslogSink::parse("tcp://[::1]:6514").unwrap();
+    assert_eq!(sink.transport, SyslogTransport::Tcp);
+    assert_eq!(sink.host, "::1");
+    assert_eq!(sink.port, 6514);
+
+    assert!(SyslogSink::parse("http://siem.example").is_err());
+    assert!(SyslogSink::parse("siem.example:514").is_err());
+}
+
+#[test]
+fn test_syslog_events() {
+    let events = syslog_events(&sample_results(), "scanner");
+    assert_eq!(events.len(), 4);
+
+    // user facility (1): error 3 -> 11, warning 4 -> 12, info 6 -> 14
+    assert!(events[0].starts_with("<11>1 "));
+    let fields: Vec<&str> = events[0].splitn(8, ' ').collect();
+    assert_eq!(fields[2], "scanner");
+    assert_eq!(fields[3], "smugglex");
+    assert_eq!(fields[4], std::process::id().to_string());
+    assert_eq!(fields[5], "finding");
+    assert_eq!(fields[6], "-");
+    let finding = message(&events[0]);
+    assert_eq!(finding["event"], "finding");
+    assert_eq!(finding["target"], "https://a.example/");
+    assert_eq!(finding["check"], "cl-te");
+    assert_eq!(finding["payload_name"], "vanilla");
+    assert_eq!(finding["confidence"], "high");
+    assert_eq!(finding["signals"], serde_json::json!(["status_504"]));
+
+    assert!(events[1].starts_with("<12>1 "));
+    assert_eq!(message(&events[1])["check"], "te-cl");
+
+    assert!(events[2].starts_with("<14>1 "));
+    let completed = message(&events[2]);
+    assert_eq!(completed["event"], "scan");
+    assert_eq!(completed["vulnerable"], true);
+    assert_eq!(completed["findings"], 2);
+    assert_eq!(completed["checks"], 3);
+    assert_eq!(completed["requests_sent"], 42);
+
+    assert!(events[3].starts_with("<12>1 "));
+    let failed = message(&events[3]);
+    assert_eq!(failed["vulnerable"], false);
+    assert_eq!(failed["error"], "connection refused");
+}
+
+#[tokio::test]
+async fn test_send_syslog_udp() {
+    let collector = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let port = collector.local_addr().unwrap().port();
+    let sink = SyslogSink::parse(&format!("udp://127.0.0.1:{}", port)).unwrap();
+    let events = syslog_events(&sample_results(), "scanner");
+    send_syslog(&sink, &events, 5).await.unwrap();
+
+    let mut buf = vec![0u8; 65536];
+    for event in &events {
+        let (n, _) = collector.recv_from(&mut buf).await.unwrap();
+        assert_eq!(&buf[..n], event.as_bytes());
+    }
+}
+
+#[tokio::test]
+async fn test_send_syslog_tcp_octet_counting() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let received = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut data = String::new();
+        stream.read_to_string(&mut data).await.unwrap();
+        data
+    });
+    let sink = SyslogSink::parse(&format!("tcp://127.0.0.1:{}", port)).unwrap();
+    let events = syslog_events(&sample_results(), "scanner");
+    send_syslog(&sink, &events, 5).await.unwrap();
+
+    let data = received.await.unwrap();
+    let mut rest = data.as_str();
+    for event in &events {
+        let (len, tail) = rest.split_once(' ').unwrap();
+        let len: usize = len.parse().unwrap();
+        assert_eq!(&tail[..len], event);
+        rest = &tail[len..];
+    }
+    assert!(rest.is_empty());
+}
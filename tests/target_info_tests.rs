@@ -0,0 +1,190 @@
+//! Tests for the target_info module
+//!
+//! This module contains tests for:
+//! - Server / X-Powered-By collection and error-page version strings
+//! - The task-local observation scope
+//! - X.509 certificate parsing (subject, issuer, serial, validity, SAN)
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use smugglex::model::TargetInfo;
+use smugglex::target_info::{
+    merge_response, observe_target_info, observed_target_info, parse_certificate,
+    record_certificate, record_response,
+};
+
+/// Self-signed P-256 CA, `CN=smugglex-test-ca`, no subjectAltName.
+const TEST_CA_PEM: &str = "-----BEGIN CERTIFICATE-----
+MIIBizCCATGgAwIBAgIUI+T8ON5AaiTHXj9NCrnmcjbX5w0wCgYIKoZIzj0EAwIw
+GzEZMBcGA1UEAwwQc211Z2dsZXgtdGVzdC1jYTAeFw0yNjA3MDIwMjE3MTRaFw0z
+NjA2MjkwMjE3MTRaMBsxGTAXBgNVBAMMEHNtdWdnbGV4LXRlc3QtY2EwWTATBgcq
+hkjOPQIBBggqhkjOPQMBBwNCAAS3odwa9jb2EDMyxaSJK0x3K8ClDOaqVOhl/WSD
+49cSDOAY/6YtsAfemTspMIlIF72/WKXC0OOaBA91F40D5lGko1MwUTAdBgNVHQ4E
+FgQUkHsScQHXJRom6fCCYxOHzDg6nnEwHwYDVR0jBBgwFoAUkHsScQHXJRom6fCC
+YxOHzDg6nnEwDwYDVR0TAQH/BAUwAwEB/zAKBggqhkjOPQQDAgNIADBFAiBxLwD7
+b7gsdI1uCJNQ7DVc6fBAO6R+RC2GY54m3FBDTgIhAN4e51Gx9T9E3Z6zytX8bLCr
+kJ8CRz+khnaPy0Io4PLR
+-----END CERTIFICATE-----";
+
+fn test_ca_der() -> Vec<u8> {
+    let body: String = TEST_CA_PEM
+        .lines()
+        .filter(|line| !line.starts_with("-----"))
+        .collect();
+    STANDARD.decode(body).unwrap()
+}
+
+/// A DER element; bodies of 128 bytes or more get a one-byte long-form length.
+fn tlv(tag: u8, body: &[u8]) -> Vec<u8> {
+    let mut out = if body.len() < 0x80 {
+        vec![tag, body.len() as u8]
+    } else {
+        vec![tag, 0x81, body.len() as u8]
+    };
+    out.extend_from_slice(body);
+    out
+}
+
+fn name(attributes: &[(u8, &str)]) -> Vec<u8> {
+    let rdns: Vec<u8> = attributes
+        .iter()
+        .flat_map(|(oid, value)| {
+            let atv = [tlv(0x06, &[0x55, 0x04, *oid]), tlv(0x0c, value.as_bytes())].concat();
+            tlv(0x31, &tlv(0x30, &atv))
+        })
+        .collect();
+    tlv(0x30, &rdns)
+}
+
+/// v1-shaped certificate (no version field) with GeneralizedTime validity and
+/// a subjectAltName extension.
+fn synthetic_certificate() -> Vec<u8> {
+    let san_names = [
+        tlv(0x82, b"example.com"),
+        tlv(0x82, b"www.example.com"),
+        tlv(0x87, &[192, 0, 2, 1]),
+    ]
+    .concat();
+    let san = [
+        tlv(0x06, &[0x55, 0x1d, 0x11]),
+        tlv(0x04, &tlv(0x30, &san_names)),
+    ]
+    .concat();
+    let extensions = tlv(0xa3, &tlv(0x30, &tlv(0x30, &san)));
+    let tbs = [
+        tlv(0x02, &[0x00, 0x80, 0x01]),
+        tlv(0x30, &tlv(0x06, &[0x2a, 0x86, 0x48])),
+        name(&[(0x06, "US"), (0x0a, "Example"), (0x03, "Example CA")]),
+        tlv(
+            0x30,
+            &[tlv(0x18, b"20240101000000Z"), tlv(0x18, b"20250101120000Z")].concat(),
+        ),
+        name(&[(0x03, "example.com")]),
+        tlv(0x30, &[]),
+        extensions,
+    ]
+    .concat();
+    tlv(0x30, &tlv(0x30, &tbs))
+}
+
+#[test]
+fn test_parse_certificate() {
+    let cert = parse_certificate(&test_ca_der()).unwrap();
+    assert_eq!(cert.subject, "CN=smugglex-test-ca");
+    assert_eq!(cert.issuer, "CN=smugglex-test-ca");
+    assert_eq!(cert.serial, "23e4fc38de406a24c75e3f4d0ab9e67236d7e70d");
+    assert_eq!(cert.not_before, "2026-07-02T02:17:14Z");
+    assert_eq!(cert.not_after, "2036-06-29T02:17:14Z");
+    assert!(cert.san.is_empty());
+}
+
+#[test]
+fn test_parse_certificate_san_and_generalized_time() {
+    let cert = parse_certificate(&synthetic_certificate()).unwrap();
+    assert_eq!(cert.subject, "CN=example.com");
+    assert_eq!(cert.issuer, "C=US, O=Example, CN=Example CA");
+    assert_eq!(cert.serial, "8001");
+    assert_eq!(cert.not_before, "2024-01-01T00:00:00Z");
+    assert_eq!(cert.not_after, "2025-01-01T12:00:00Z");
+    assert_eq!(cert.san, ["example.com", "www.example.com", "192.0.2.1"]);
+}
+
+#[test]
+fn test_parse_certificate_rejects_garbage() {
+    assert!(parse_certificate(b"").is_none());
+    assert!(parse_certificate(b"not a certificate").is_none());
+    let der = test_ca_der();
+    assert!(parse_certificate(&der[..40]).is_none());
+}
+
+#[test]
+fn test_merge_response_headers() {
+    let mut info = TargetInfo::default();
+    merge_response(
+        &mut info,
+        "HTTP/1.1 200 OK\r\nServer: nginx/1.24.0\r\nx-powered-by: PHP/8.2.12\r\n\r\nApache/2.4.57",
+    );
+    merge_response(
+        &mut info,
+        "HTTP/1.1 200 OK\r\nserver:  nginx/1.24.0 \r\nServer: cloudflare\r\n\r\n",
+    );
+    assert_eq!(info.server, ["nginx/1.24.0", "cloudflare"]);
+    assert_eq!(info.powered_by, ["PHP/8.2.12"]);
+    // Only error pages are searched for versions.
+    assert!(info.error_page_versions.is_empty());
+    assert!(info.certificate.is_none());
+}
+
+#[test]
+fn test_merge_response_error_page_versions() {
+    let mut info = TargetInfo::default();
+    merge_response(
+        &mut info,
+        "HTTP/1.1 404 Not Found\r\nContent-Type: text/html\r\n\r\n<hr><address>Apache/2.4.57 (Debian) Server at example.com Port 80</address>",
+    );
+    merge_response(
+        &mut info,
+        "HTTP/1.1 502 Bad Gateway\r\n\r\n<center>nginx/1.18.0</center><h3>Apache Tomcat/9.0.85</h3>",
+    );
+    merge_response(
+        &mut info,
+        "HTTP/1.1 500 Internal Server Error\r\n\r\n<center>nginx/1.18.0 and version 2.0 of nothing</center>",
+    );
+    assert_eq!(
+        info.error_page_versions,
+        ["Apache/2.4.57", "nginx/1.18.0", "Apache Tomcat/9.0.85"]
+    );
+}
+
+#[test]
+fn test_merge_response_caps_values() {
+    let mut info = TargetInfo::default();
+    for i in 0..50 {
+        merge_response(
+            &mut info,
+            &format!("HTTP/1.1 200 OK\r\nServer: node-{}\r\n\r\n", i),
+        );
+    }
+    assert_eq!(info.server.len(), 10);
+    assert_eq!(info.server[0], "node-0");
+}
+
+#[tokio::test]
+async fn test_observe_target_info_scope() {
+    // Outside a scope nothing is recorded.
+    record_response("HTTP/1.1 200 OK\r\nServer: outside\r\n\r\n");
+    assert!(observed_target_info().is_none());
+
+    let info = observe_target_info(async {
+        assert!(observed_target_info().is_none());
+        record_response("HTTP/1.1 200 OK\r\nServer: nginx\r\n\r\n");
+        record_certificate(&test_ca_der());
+        // The first certificate seen is kept.
+        record_certificate(&synthetic_certificate());
+        observed_target_info()
+    })
+    .await
+    .unwrap();
+    assert_eq!(info.server, ["nginx"]);
+    assert_eq!(info.certificate.unwrap().subject, "CN=smugglex-test-ca");
+}
@@ -0,0 +1,139 @@
+//! Tests for the triage module
+//!
+//! This module contains tests for:
+//! - Probing mock targets (keep-alive reuse, hanging CL.TE / TE.CL probes,
+//!   fingerprint signals, unreachable targets)
+//! - Ranking and totals of the triage report
+
+use smugglex::model::TriageResult;
+use smugglex::triage::{TriageSettings, build_triage_report, triage_target};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+fn settings() -> TriageSettings {
+    TriageSettings {
+        timeout: 1,
+        verbose: false,
+        method: "POST".to_string(),
+        headers: Vec::new(),
+    }
+}
+
+/// Keep-alive server answering every request with `Server: nginx/1.24.0`;
+/// with `hang_on_te`, requests carrying `Transfer-Encoding` get no answer.
+async fn start_mock_server(hang_on_te: bool) -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut buf = vec![0u8; 8192];
+                while let Ok(n) = stream.read(&mut buf).await {
+                    if n == 0 {
+                        break;
+                    }
+                    let request = String::from_utf8_lossy(&buf[..n]).to_ascii_lowercase();
+                    if hang_on_te && request.contains("transfer-encoding") {
+                        continue;
+                    }
+                    let response =
+                        "HTTP/1.1 200 OK\r\nServer: nginx/1.24.0\r\nContent-Length: 2\r\n\r\nOK";
+                    if stream.write_all(response.as_bytes()).await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+    port
+}
+
+fn result(target: &str, score: u32, error: Option<&str>) -> TriageResult {
+    TriageResult {
+        target: target.to_string(),
+        score,
+        signals: Vec::new(),
+        status: None,
+        error: error.map(str::to_string),
+    }
+}
+
+#[tokio::test]
+async fn test_triage_keep_alive_target() {
+    let port = start_mock_server(false).await;
+    let target = format!("http://127.0.0.1:{}/", port);
+    let result = triage_target(&target, &settings()).await;
+    assert!(result.error.is_none(), "{:?}", result.error);
+    assert_eq!(result.status.as_deref(), Some("HTTP/1.1 200 OK"));
+    assert!(result.signals.contains(&"pipelining".to_string()));
+    assert!(result.signals.contains(&"proxy:nginx".to_string()));
+    assert!(!result.signals.iter().any(|s| s.starts_with("cl-te")));
+    assert!(result.score > 0);
+}
+
+#[tokio::test]
+async fn test_triage_hanging_probes_rank_first() {
+    let hanging = start_mock_server(true).await;
+    let plain = start_mock_server(false).await;
+    let hanging_target = format!("http://127.0.0.1:{}/", hanging);
+    let plain_target = format!("http://127.0.0.1:{}/", plain);
+
+    let hanging_result = triage_target(&hanging_target, &settings()).await;
+    assert!(
+        hanging_result
+            .signals
+            .contains(&"cl-te:timeout".to_string())
+    );
+    assert!(
+        hanging_result
+            .signals
+            .contains(&"te-cl:timeout".to_string())
+    );
+
+    let plain_result = triage_target(&plain_target, &settings()).await;
+    let report = build_triage_report(vec![plain_result, hanging_result], None);
+    assert_eq!(report.results[0].target, hanging_target);
+    assert_eq!(report.promising, 2);
+    assert_eq!(report.errors, 0);
+}
+
+#[tokio::test]
+async fn test_triage_unreachable_target() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    drop(listener);
+    let result = triage_target(&format!("http://127.0.0.1:{}/", port), &settings()).await;
+    assert_eq!(result.score, 0);
+    assert!(result.signals.is_empty());
+    assert!(result.error.is_some());
+}
+
+#[test]
+fn test_build_triage_report_ranks_by_score() {
+    let report = build_triage_report(
+        vec![
+            result("https://a.example/", 10, None),
+            result("https://b.example/", 0, Some("timeout")),
+            result("https://c.example/", 55, None),
+            result("https://d.example/", 10, None),
+        ],
+        Some("1.2.3"),
+    );
+    let order: Vec<&str> = report.results.iter().map(|r| r.target.as_str()).collect();
+    assert_eq!(
+        order,
+        [
+            "https://c.example/",
+            "https://a.example/",
+            "https://d.example/",
+            "https://b.example/"
+        ]
+    );
+    assert_eq!(report.promising, 3);
+    assert_eq!(report.errors, 1);
+    assert_eq!(report.smugglex_version.as_deref(), Some("1.2.3"));
+
+    let value = serde_json::to_value(&report).unwrap();
+    assert!(value["results"][0].get("signals").is_none());
+    assert_eq!(value["results"][3]["error"], "timeout");
+}
@@ -1,16 +1,27 @@
 //! Tests for utility functions
 //!
 //! This module contains tests for:
-//! - Payload export functionality
+//! - Payload export functionality, with the technique sidecar and the
+//!   raw layout's metadata sidecar
 //! - Hostname sanitization for file names
 //! - Directory creation and file management
 //! - Multiple file exports
 //! - Protocol handling (HTTP/HTTPS)
-
-use smugglex::utils::{export_payload, parse_status_code, sanitize_hostname};
+//! - Response heads and line diffs
+//! - Reachability preflight
+//! - Retry-After parsing
+
+use smugglex::model::{ExportedPayload, ResponseSummary, Technique, Unreachable};
+use smugglex::utils::{
+    DiffLine, export_payload, export_payload_raw, export_technique, line_diff, parse_status_code,
+    preflight, response_head, retry_after, sanitize_hostname,
+};
 use std::env;
 use std::fs;
 use std::path::Path;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
 
 /// Helper function to create a temporary test directory
 fn create_test_dir(name: &str) -> String {
@@ -48,6 +59,63 @@ fn test_export_payload_creates_file() {
     cleanup_test_dir(&temp_dir);
 }
 
+#[test]
+fn test_export_technique_writes_sidecar() {
+    let temp_dir = create_test_dir("export_technique");
+    let payload_file =
+        export_payload(&temp_dir, "example.com", "cl-te", 3, "payload", false).unwrap();
+    let technique = Technique {
+        family: "cl-te".to_string(),
+        id: "cl-te/linefold-tab".to_string(),
+        description: "Obfuscated Transfer-Encoding".to_string(),
+        reference: "https://example.org/".to_string(),
+    };
+
+    let sidecar = export_technique(&payload_file, &technique).unwrap();
+    assert_eq!(sidecar, payload_file.replace(".txt", ".json"));
+    let saved: Technique = serde_json::from_str(&fs::read_to_string(&sidecar).unwrap()).unwrap();
+    assert_eq!(saved, technique);
+
+    cleanup_test_dir(&temp_dir);
+}
+
+#[test]
+fn test_export_payload_raw_writes_bytes_and_metadata() {
+    let temp_dir = create_test_dir("export_raw");
+    let payload = "POST / HTTP/1.1\r\nTransfer-Encoding:\x0bchunked\r\n\r\n0\r\n\r\n";
+    let metadata = ExportedPayload {
+        target: "https://example.com/".to_string(),
+        check_type: "cl-te".to_string(),
+        payload_index: 3,
+        payload_name: Some("vtab-after-colon".to_string()),
+        technique: None,
+        raw_file: String::new(),
+        timestamp: "2024-01-01T00:00:00Z".to_string(),
+        exported_at: String::new(),
+        outcome: None,
+        response: ResponseSummary {
+            attack_status: Some("HTTP/1.1 504 Gateway Timeout".to_string()),
+            ..Default::default()
+        },
+    };
+
+    let raw_file =
+        export_payload_raw(&temp_dir, "example.com", payload, true, metadata.clone()).unwrap();
+    assert!(raw_file.ends_with("https_example_com_cl-te_3.raw"));
+    assert_eq!(fs::read(&raw_file).unwrap(), payload.as_bytes());
+
+    let sidecar = raw_file.replace(".raw", ".json");
+    let saved: ExportedPayload =
+        serde_json::from_str(&fs::read_to_string(&sidecar).unwrap()).unwrap();
+    assert_eq!(saved.raw_file, "https_example_com_cl-te_3.raw");
+    assert!(!saved.exported_at.is_empty());
+    assert_eq!(saved.payload_name, metadata.payload_name);
+    assert_eq!(saved.response, metadata.response);
+    assert!(!Path::new(&raw_file.replace(".raw", ".txt")).exists());
+
+    cleanup_test_dir(&temp_dir);
+}
+
 #[test]
 fn test_export_payload_sanitizes_hostname() {
     let temp_dir = create_test_dir("sanitize");
@@ -231,3 +299,116 @@ fn test_parse_status_code_non_numeric_token_returns_none() {
     assert_eq!(parse_status_code("HTTP/1.1 OK"), None);
     assert_eq!(parse_status_code("HTTP/2 abc"), None);
 }
+
+#[test]
+fn test_response_head_truncates_body() {
+    let response = "HTTP/1.1 200 OK\r\nServer: nginx\r\n\r\nline one\r\nline two";
+    assert_eq!(
+        response_head(response, 1024),
+        "HTTP/1.1 200 OK\nServer: nginx\n\nline one\nline two"
+    );
+    assert_eq!(
+        response_head(response, 4),
+        "HTTP/1.1 200 OK\nServer: nginx\n\nline"
+    );
+    assert_eq!(response_head(response, 0), "HTTP/1.1 200 OK\nServer: nginx");
+    // Never splits a multi-byte character.
+    assert_eq!(
+        response_head("HTTP/1.1 200 OK\r\n\r\né", 1),
+        "HTTP/1.1 200 OK"
+    );
+}
+
+#[test]
+fn test_line_diff() {
+    let old = ["HTTP/1.1 200 OK", "Server: nginx", "Content-Length: 5"];
+    let new = ["HTTP/1.1 504 Gateway Timeout", "Server: nginx"];
+    assert_eq!(
+        line_diff(&old, &new),
+        vec![
+            DiffLine::Removed("HTTP/1.1 200 OK"),
+            DiffLine::Added("HTTP/1.1 504 Gateway Timeout"),
+            DiffLine::Same("Server: nginx"),
+            DiffLine::Removed("Content-Length: 5"),
+        ]
+    );
+}
+
+/// Serve one connection with `response`, returning the port.
+async fn one_shot_server(response: &'static [u8]) -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        if let Ok((mut socket, _)) = listener.accept().await {
+            let mut buf = [0; 1024];
+            let _ = socket.read(&mut buf).await;
+            let _ = socket.write_all(response).await;
+        }
+    });
+    port
+}
+
+#[tokio::test]
+async fn test_preflight_categories() {
+    let port =
+        one_shot_server(b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\n\r\n").await;
+    assert!(
+        preflight("127.0.0.1", port, "/", "example.com", false, 5, false)
+            .await
+            .is_ok(),
+        "any HTTP status counts as reachable"
+    );
+
+    let port = one_shot_server(b"SSH-2.0-OpenSSH_9.6\r\n").await;
+    let err = preflight("127.0.0.1", port, "/", "example.com", false, 2, false)
+        .await
+        .unwrap_err();
+    assert_eq!(err.unreachable_kind(), Unreachable::HttpError);
+    assert!(err.to_string().contains("no HTTP status line"));
+
+    // Bind then drop to get a port nothing listens on.
+    let closed = TcpListener::bind("127.0.0.1:0")
+        .await
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let err = preflight("127.0.0.1", closed, "/", "example.com", false, 2, false)
+        .await
+        .unwrap_err();
+    assert_eq!(err.unreachable_kind(), Unreachable::Refused);
+    assert!(!err.info().retryable);
+}
+
+#[test]
+fn test_retry_after_seconds_and_dates() {
+    let now = chrono::DateTime::parse_from_rfc3339("2015-10-21T07:28:00Z")
+        .unwrap()
+        .with_timezone(&chrono::Utc);
+    let response = |value: &str| {
+        format!(
+            "HTTP/1.1 503 Service Unavailable\r\nretry-after: {}\r\n\r\nbody",
+            value
+        )
+    };
+    assert_eq!(
+        retry_after(&response("120"), now),
+        Some(Duration::from_secs(120))
+    );
+    assert_eq!(
+        retry_after(&response("Wed, 21 Oct 2015 07:28:30 GMT"), now),
+        Some(Duration::from_secs(30))
+    );
+    // A date in the past asks for no pause.
+    assert_eq!(
+        retry_after(&response("Wed, 21 Oct 2015 07:00:00 GMT"), now),
+        Some(Duration::ZERO)
+    );
+    assert_eq!(retry_after(&response("soon"), now), None);
+    assert_eq!(retry_after("HTTP/1.1 200 OK\r\n\r\n", now), None);
+    // Only the headers count, not a body that happens to contain the name.
+    assert_eq!(
+        retry_after("HTTP/1.1 200 OK\r\n\r\nRetry-After: 5", now),
+        None
+    );
+}
@@ -0,0 +1,121 @@
+//! Tests for the validate module
+//!
+//! Lints raw request templates and checks their sample expansions.
+
+use smugglex::validate::{template_files, validate_path, validate_template};
+
+#[test]
+fn test_clean_template_expands_with_markers() {
+    let content = "POST /api HTTP/1.1\r\nHost: example.com\r\nAuthorization: Bearer t\r\n{{TE}}\r\n{{CL}}\r\n\r\n{{BODY}}";
+    let validation = validate_template("marked.txt", content, None);
+    assert!(validation.errors.is_empty(), "{:?}", validation.errors);
+    assert!(validation.warnings.is_empty(), "{:?}", validation.warnings);
+    let expansion = validation.expansion.expect("template should expand");
+    assert!(expansion.starts_with("POST /api HTTP/1.1\r\nHost: example.com\r\n"));
+    assert!(expansion.contains("Authorization: Bearer t\r\nTransfer-Encoding: chunked\r\nContent-Length: 6\r\n\r\n0\r\n\r\nG"));
+    assert!(!expansion.contains("{{"));
+
+    let other = validate_template("marked.txt", content, Some("other.test"));
+    assert!(other.expansion.unwrap().contains("Host: other.test\r\n"));
+}
+
+#[test]
+fn test_line_endings_and_separator() {
+    let mixed = validate_template("mixed.txt", "GET / HTTP/1.1\nHost: x\r\nbody\n", None);
+    assert!(
+        mixed
+            .errors
+            .iter()
+            .any(|e| e.starts_with("mixed line endings"))
+    );
+    assert!(
+        mixed
+            .errors
+            .iter()
+            .any(|e| e.starts_with("line 3: not a 'Name: value'"))
+    );
+    assert!(
+        mixed
+            .warnings
+            .contains(&"no blank line ends the header block".to_string())
+    );
+
+    let bare_lf = validate_template("lf.txt", "GET / HTTP/1.1\nHost: x\n\n", None);
+    assert!(bare_lf.errors.is_empty(), "{:?}", bare_lf.errors);
+    assert!(bare_lf.warnings[0].starts_with("bare LF line endings"));
+
+    let bare_cr = validate_template("cr.txt", "GET / HTTP/1.1\r\nHost: x\rX-A: 1\r\n\r\n", None);
+    assert!(bare_cr.errors[0].starts_with("1 bare CR"));
+}
+
+#[test]
+fn test_placeholder_resolution() {
+    let content = "POST /{{TE}} HTTP/1.1\r\nHost: x\r\nX-Id: {{ID}}\r\n{{CL}}\r\n{{CL}}\r\n{{BODY}}\r\n\r\n{{TE}}";
+    let validation = validate_template("placeholders.txt", content, None);
+    let errors = validation.errors.join("\n");
+    assert!(errors.contains("line 1: {{TE}} only works on a line of its own"));
+    assert!(errors.contains("line 3: unknown placeholder {{ID}}"));
+    assert!(errors.contains("{{CL}} appears 2 times"));
+    assert!(errors.contains("line 6: {{BODY}} only works in the body"));
+    assert!(errors.contains("line 8: {{TE}} only works on a line of its own"));
+}
+
+#[test]
+fn test_content_length_consistency() {
+    let truncated = validate_template(
+        "cl.txt",
+        "POST / HTTP/1.1\r\nHost: x\r\nContent-Length: 10\r\n\r\nabc",
+        None,
+    );
+    assert_eq!(
+        truncated.warnings,
+        vec!["line 3: Content-Length: 10 does not match the 3-byte body".to_string()]
+    );
+
+    // A trailing newline added by an editor is not counted against the capture.
+    let matching = validate_template(
+        "cl.txt",
+        "POST / HTTP/1.1\r\nHost: x\r\nContent-Length: 3\r\n\r\nabc\r\n",
+        None,
+    );
+    assert!(matching.warnings.is_empty(), "{:?}", matching.warnings);
+
+    let wrapped = validate_template(
+        "wrapped.txt",
+        "POST / HTTP/1.1\r\nHost: x\r\n\r\ndata={{BODY}}",
+        None,
+    );
+    assert!(
+        wrapped.warnings[0].contains("adds 5 byte(s) the crafted Content-Length does not count")
+    );
+}
+
+#[test]
+fn test_unparseable_template_has_no_expansion() {
+    let validation = validate_template("bad.txt", "GET / HTTP/1.1\r\n\r\n", None);
+    assert!(validation.expansion.is_none());
+    assert!(
+        validation
+            .errors
+            .iter()
+            .any(|e| e.contains("missing a Host header"))
+    );
+}
+
+#[test]
+fn test_validate_directory() {
+    let dir = std::env::temp_dir().join(format!("smugglex-validate-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("b.txt"), "GET / HTTP/1.1\r\nHost: x\r\n\r\n").unwrap();
+    std::fs::write(dir.join("a.txt"), "GET / HTTP/1.1\r\n\r\n").unwrap();
+    std::fs::write(dir.join(".hidden"), "junk").unwrap();
+
+    let files = template_files(&dir).unwrap();
+    assert_eq!(files, vec![dir.join("a.txt"), dir.join("b.txt")]);
+    let validations = validate_path(&dir, None).unwrap();
+    assert!(!validations[0].errors.is_empty());
+    assert!(validations[1].errors.is_empty());
+    assert!(template_files(&dir.join("missing")).is_err());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
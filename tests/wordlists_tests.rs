@@ -0,0 +1,138 @@
+//! Tests for the wordlists module
+//!
+//! - Looking up curated wordlists by name
+//! - Parsing wordlist entries
+//! - Saving a downloaded wordlist and reporting what is cached
+//! - Resolving `--exploit-wordlist` / `--vhost` values to cached wordlists
+//! - Expanding a subdomain wordlist into per-target virtual hosts
+
+use clap::Parser;
+use smugglex::cli::{Cli, vhost_for_target};
+use smugglex::exploit::get_fuzz_paths;
+use smugglex::wordlists::{
+    WORDLISTS, WordlistKind, cached_path, find_wordlist, resolve_wordlist, save_wordlist,
+    set_data_dir, wordlist_dir, wordlist_entries, wordlist_status,
+};
+
+/// Every test shares one data directory, since it is set once per process.
+fn data_dir() -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join("smugglex_test_wordlists");
+    set_data_dir(dir.clone());
+    dir
+}
+
+#[test]
+fn test_catalog() {
+    let names: Vec<&str> = WORDLISTS.iter().map(|w| w.name).collect();
+    let mut unique = names.clone();
+    unique.sort();
+    unique.dedup();
+    assert_eq!(names.len(), unique.len(), "duplicate wordlist names");
+    assert!(WORDLISTS.iter().all(|w| w.url.starts_with("https://")));
+
+    let quickhits = find_wordlist("quickhits").unwrap();
+    assert_eq!(quickhits.kind, WordlistKind::Paths);
+    assert_eq!(
+        find_wordlist("subdomains-top5000").unwrap().kind,
+        WordlistKind::Subdomains
+    );
+    assert!(find_wordlist("nope").is_none());
+}
+
+#[test]
+fn test_wordlist_entries() {
+    assert_eq!(
+        wordlist_entries("# comment\n  admin \n\n.git/HEAD\r\n"),
+        vec!["admin", ".git/HEAD"]
+    );
+    assert!(wordlist_entries("").is_empty());
+}
+
+#[test]
+fn test_save_and_status() {
+    let dir = std::env::temp_dir().join("smugglex_test_wordlist_status");
+    let _ = std::fs::remove_dir_all(&dir);
+    let api = find_wordlist("api-endpoints").unwrap();
+
+    let status = wordlist_status(&dir);
+    assert_eq!(status.len(), WORDLISTS.len());
+    assert!(
+        status
+            .iter()
+            .all(|s| s.path.is_none() && s.entries.is_none())
+    );
+
+    let path = save_wordlist(&dir, api, "/api\n/graphql\n").unwrap();
+    assert_eq!(path, cached_path(&dir, api));
+    assert!(!path.with_extension("txt.partial").exists());
+    let path = save_wordlist(&dir, api, "/api\n/graphql\n/v1\n").unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "/api\n/graphql\n/v1\n"
+    );
+
+    let status = wordlist_status(&dir);
+    let saved = status.iter().find(|s| s.name == "api-endpoints").unwrap();
+    assert_eq!(saved.entries, Some(3));
+    assert_eq!(saved.path.as_deref(), Some(path.as_path()));
+    let json = serde_json::to_value(saved).unwrap();
+    assert_eq!(json["kind"], "paths");
+    assert_eq!(json["entries"], 3);
+    let missing = serde_json::to_value(&status[0]).unwrap();
+    assert!(missing.get("path").is_none());
+}
+
+#[test]
+fn test_resolve_wordlist() {
+    let dir = data_dir();
+    let wordlists = wordlist_dir().unwrap();
+    assert_eq!(wordlists, dir.join("wordlists"));
+
+    // Not a wordlist name: read as a path.
+    assert!(resolve_wordlist("paths.txt").unwrap().is_none());
+
+    let web = find_wordlist("web-common").unwrap();
+    let _ = std::fs::remove_file(cached_path(&wordlists, web));
+    let err = resolve_wordlist("web-common").unwrap_err().to_string();
+    assert!(
+        err.contains("smugglex wordlists update web-common"),
+        "{}",
+        err
+    );
+
+    save_wordlist(&wordlists, web, "admin\n# skip\nlogin\n").unwrap();
+    let (found, path) = resolve_wordlist("web-common").unwrap().unwrap();
+    assert_eq!(found.name, "web-common");
+    assert_eq!(path, cached_path(&wordlists, web));
+
+    let paths = get_fuzz_paths(Some("web-common"), &[]).unwrap();
+    assert_eq!(paths, vec!["/admin", "/login"]);
+}
+
+#[test]
+fn test_subdomain_wordlists() {
+    let wordlists = data_dir().join("wordlists");
+    let top = find_wordlist("subdomains-top5000").unwrap();
+    save_wordlist(&wordlists, top, "www\nmail\n").unwrap();
+
+    let cli = Cli::parse_from([
+        "smugglex",
+        "--vhost",
+        "subdomains-top5000",
+        "https://x.test",
+    ]);
+    let vhosts = cli.vhosts().unwrap();
+    assert_eq!(vhosts, vec!["www.{host}", "mail.{host}"]);
+    let hosts: Vec<String> = vhosts
+        .iter()
+        .map(|v| vhost_for_target(v, "example.com"))
+        .collect();
+    assert_eq!(hosts, vec!["www.example.com", "mail.example.com"]);
+
+    assert!(get_fuzz_paths(Some("subdomains-top5000"), &[]).is_err());
+
+    let api = find_wordlist("api-endpoints").unwrap();
+    save_wordlist(&wordlists, api, "/api\n").unwrap();
+    let cli = Cli::parse_from(["smugglex", "--vhost", "api-endpoints", "https://x.test"]);
+    assert!(cli.vhosts().is_err());
+}